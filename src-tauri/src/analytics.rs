@@ -1,4 +1,6 @@
 use crate::device_id::get_or_create_device_id;
+use crate::http_client;
+use crate::offline_mode;
 use reqwest::Client;
 use serde::Serialize;
 use std::sync::{Arc, Mutex};
@@ -24,7 +26,10 @@ impl AnalyticsState {
     pub fn new() -> Self {
         Self {
             session: Arc::new(Mutex::new(None)),
-            client: Client::new(),
+            client: http_client::client().unwrap_or_else(|e| {
+                log::warn!("Analytics falls back to a default HTTP client: {}", e);
+                Client::new()
+            }),
         }
     }
 }
@@ -77,6 +82,10 @@ pub async fn start_session(
     app_data_dir: &std::path::Path,
     app_version: &str,
 ) {
+    if offline_mode::is_offline() {
+        log::info!("Offline mode: skipping analytics session");
+        return;
+    }
     let device_id = get_or_create_device_id(app_data_dir);
     let session_id = uuid::Uuid::new_v4().to_string();
 
@@ -161,7 +170,13 @@ pub fn send_session_end_sync(state: &AnalyticsState) {
         };
 
         // Use blocking request since we're in a sync context during window close
-        let client = reqwest::blocking::Client::new();
+        let client = match http_client::blocking_client() {
+            Ok(client) => client,
+            Err(e) => {
+                log::error!("Failed to send session_end: {}", e);
+                return;
+            }
+        };
         match client
             .post(API_URL)
             .json(&payload)
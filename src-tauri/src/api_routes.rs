@@ -0,0 +1,533 @@
+// API route extraction
+//
+// Builds a route table (method, path, handler) for common web frameworks:
+// Express/Fastify routers and Next.js file routes in JavaScript/TypeScript, axum and
+// actix-web in Rust, Flask and Django in Python, and Spring mappings in Java.
+// Extraction is pattern based and line oriented; decorator/attribute style routes are
+// attached to the next function definition.
+
+use crate::code_navigation::CodeNavigationService;
+use crate::path_utils;
+use crate::walker::{WalkerConfig, WorkspaceWalker};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+const HTTP_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiRoute {
+    pub method: String,
+    pub path: String,
+    pub handler: Option<String>,
+    pub framework: String,
+    pub file_path: String,
+    pub line: u32,
+}
+
+macro_rules! cached_regex {
+    ($name:ident, $pattern:expr) => {
+        fn $name() -> &'static Regex {
+            static RE: OnceLock<Regex> = OnceLock::new();
+            RE.get_or_init(|| Regex::new($pattern).unwrap())
+        }
+    };
+}
+
+cached_regex!(
+    express_regex,
+    r#"\b(app|server|fastify|\w*[Rr]outer)\.(get|post|put|patch|delete|head|options|all)\(\s*['"`]([^'"`]+)['"`]([^)]*)"#
+);
+cached_regex!(identifier_regex, r"[A-Za-z_$][\w$.]*");
+cached_regex!(
+    next_handler_regex,
+    r"^\s*export\s+(?:async\s+)?(?:function\s+|const\s+)(GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS)\b"
+);
+cached_regex!(
+    next_default_regex,
+    r"^\s*export\s+default\s+(?:async\s+)?(?:function\s+)?(\w+)"
+);
+cached_regex!(rust_route_regex, r#"\.route\(\s*"([^"]+)"\s*,(.*)"#);
+cached_regex!(
+    rust_method_regex,
+    r"\b(get|post|put|patch|delete|head|options|any)\((?:\)\.to\()?\s*([A-Za-z_][\w:]*)"
+);
+cached_regex!(
+    actix_attr_regex,
+    r#"^\s*#\[(get|post|put|patch|delete|head|options)\(\s*"([^"]+)""#
+);
+cached_regex!(rust_fn_regex, r"^\s*(?:pub\s+)?(?:async\s+)?fn\s+(\w+)");
+cached_regex!(
+    flask_regex,
+    r#"^\s*@\w+\.(route|get|post|put|patch|delete)\(\s*['"]([^'"]+)['"](.*)"#
+);
+cached_regex!(flask_methods_regex, r#"['"](\w+)['"]"#);
+cached_regex!(python_def_regex, r"^\s*(?:async\s+)?def\s+(\w+)");
+cached_regex!(
+    django_regex,
+    r#"\b(?:re_)?path\(\s*r?['"]([^'"]*)['"]\s*,\s*([\w.]+)"#
+);
+cached_regex!(
+    spring_regex,
+    r#"^\s*@(Get|Post|Put|Patch|Delete|Request)Mapping\b(?:\((?:\s*(?:value|path)\s*=\s*)?\{?\s*"([^"]*)")?(.*)"#
+);
+cached_regex!(spring_method_regex, r"RequestMethod\.(\w+)");
+cached_regex!(java_class_regex, r"\bclass\s+(\w+)");
+cached_regex!(java_method_regex, r"(\w+)\s*\(");
+
+/// A decorator/attribute route waiting for the function it annotates
+struct PendingRoute {
+    methods: Vec<String>,
+    path: String,
+    line: u32,
+}
+
+fn join_paths(prefix: &str, path: &str) -> String {
+    if prefix.is_empty() {
+        return path.to_string();
+    }
+    format!(
+        "{}/{}",
+        prefix.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+    .trim_end_matches('/')
+    .to_string()
+}
+
+/// URL path of a Next.js route file, for both the app and pages routers
+pub fn next_route_path(rel_path: &str) -> Option<String> {
+    let rel_path = path_utils::to_slash(rel_path);
+    let segments: Vec<&str> = rel_path.split('/').collect();
+    let file = *segments.last()?;
+    let stem = file.split('.').next()?;
+
+    let (base, parts): (&str, Vec<&str>) =
+        if let Some(pos) = segments.iter().rposition(|s| *s == "app") {
+            if stem != "route" {
+                return None;
+            }
+            ("", segments[pos + 1..segments.len() - 1].to_vec())
+        } else if let Some(pos) = segments.windows(2).rposition(|w| w == ["pages", "api"]) {
+            let mut parts = segments[pos + 2..segments.len() - 1].to_vec();
+            if stem != "index" {
+                parts.push(stem);
+            }
+            ("/api", parts)
+        } else {
+            return None;
+        };
+
+    let parts: Vec<&str> = parts
+        .into_iter()
+        // Route groups "(marketing)" and private folders "_lib" don't appear in URLs
+        .filter(|p| !(p.starts_with('_') || (p.starts_with('(') && p.ends_with(')'))))
+        .collect();
+    let path = format!("{}/{}", base, parts.join("/"));
+    Some(if path.len() > 1 {
+        path.trim_end_matches('/').to_string()
+    } else {
+        path
+    })
+}
+
+/// Extract the routes declared in one file
+pub fn extract_routes(
+    file_path: &str,
+    rel_path: &str,
+    content: &str,
+    lang_id: &str,
+) -> Vec<ApiRoute> {
+    let mut routes = Vec::new();
+    let route =
+        |method: &str, path: &str, handler: Option<String>, framework: &str, line: u32| ApiRoute {
+            method: method.to_uppercase(),
+            path: path.to_string(),
+            handler,
+            framework: framework.to_string(),
+            file_path: file_path.to_string(),
+            line,
+        };
+
+    let mut pending: Vec<PendingRoute> = Vec::new();
+    let mut class_prefix = String::new();
+    let next_path = next_route_path(rel_path);
+    let is_django_urls = rel_path.ends_with("urls.py");
+
+    for (idx, line) in content.lines().enumerate() {
+        let line_no = idx as u32 + 1;
+        match lang_id {
+            "typescript" | "javascript" => {
+                for caps in express_regex().captures_iter(line) {
+                    let framework = if &caps[1] == "fastify" {
+                        "fastify"
+                    } else {
+                        "express"
+                    };
+                    // The handler is the last plain identifier argument; inline
+                    // functions have no name to report
+                    let args = &caps[4];
+                    let handler = if args.contains('(') {
+                        None
+                    } else {
+                        args.split(',')
+                            .map(str::trim)
+                            .rfind(|arg| !arg.is_empty())
+                            .filter(|arg| {
+                                identifier_regex()
+                                    .find(arg)
+                                    .is_some_and(|m| m.as_str() == *arg)
+                            })
+                            .map(|arg| arg.to_string())
+                    };
+                    routes.push(route(&caps[2], &caps[3], handler, framework, line_no));
+                }
+                if let (Some(path), Some(caps)) = (&next_path, next_handler_regex().captures(line))
+                {
+                    routes.push(route(
+                        &caps[1],
+                        path,
+                        Some(caps[1].to_string()),
+                        "nextjs",
+                        line_no,
+                    ));
+                }
+                if next_path.is_some()
+                    && rel_path.contains("pages/api/")
+                    && next_default_regex().is_match(line)
+                {
+                    let handler = next_default_regex()
+                        .captures(line)
+                        .map(|caps| caps[1].to_string())
+                        .filter(|w| w != "function" && w != "async");
+                    routes.push(route(
+                        "ALL",
+                        next_path.as_deref().unwrap_or("/"),
+                        handler,
+                        "nextjs",
+                        line_no,
+                    ));
+                }
+            }
+            "rust" => {
+                if let Some(caps) = rust_route_regex().captures(line) {
+                    let framework = if caps[2].contains("web::") {
+                        "actix"
+                    } else {
+                        "axum"
+                    };
+                    for method in rust_method_regex().captures_iter(&caps[2]) {
+                        let handler = method[2].rsplit("::").next().map(|s| s.to_string());
+                        routes.push(route(&method[1], &caps[1], handler, framework, line_no));
+                    }
+                } else if let Some(caps) = actix_attr_regex().captures(line) {
+                    pending.push(PendingRoute {
+                        methods: vec![caps[1].to_string()],
+                        path: caps[2].to_string(),
+                        line: line_no,
+                    });
+                } else if let Some(caps) = rust_fn_regex().captures(line) {
+                    for p in pending.drain(..) {
+                        for method in &p.methods {
+                            routes.push(route(
+                                method,
+                                &p.path,
+                                Some(caps[1].to_string()),
+                                "actix",
+                                p.line,
+                            ));
+                        }
+                    }
+                }
+            }
+            "python" => {
+                if let Some(caps) = flask_regex().captures(line) {
+                    let methods = if &caps[1] == "route" {
+                        let listed: Vec<String> = caps[3]
+                            .split_once("methods")
+                            .map(|(_, rest)| {
+                                flask_methods_regex()
+                                    .captures_iter(rest)
+                                    .map(|m| m[1].to_string())
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        if listed.is_empty() {
+                            vec!["GET".to_string()]
+                        } else {
+                            listed
+                        }
+                    } else {
+                        vec![caps[1].to_string()]
+                    };
+                    pending.push(PendingRoute {
+                        methods,
+                        path: caps[2].to_string(),
+                        line: line_no,
+                    });
+                } else if let Some(caps) = python_def_regex().captures(line) {
+                    for p in pending.drain(..) {
+                        for method in &p.methods {
+                            routes.push(route(
+                                method,
+                                &p.path,
+                                Some(caps[1].to_string()),
+                                "flask",
+                                p.line,
+                            ));
+                        }
+                    }
+                } else if is_django_urls {
+                    if let Some(caps) = django_regex().captures(line) {
+                        let path =
+                            format!("/{}", caps[1].trim_start_matches('^').trim_end_matches('$'));
+                        routes.push(route(
+                            "ANY",
+                            &path,
+                            Some(caps[2].to_string()),
+                            "django",
+                            line_no,
+                        ));
+                    }
+                }
+            }
+            "java" => {
+                if let Some(caps) = spring_regex().captures(line) {
+                    let methods = if &caps[1] == "Request" {
+                        let listed: Vec<String> = spring_method_regex()
+                            .captures_iter(&caps[3])
+                            .map(|m| m[1].to_string())
+                            .collect();
+                        if listed.is_empty() {
+                            vec!["ANY".to_string()]
+                        } else {
+                            listed
+                        }
+                    } else {
+                        vec![caps[1].to_string()]
+                    };
+                    pending.push(PendingRoute {
+                        methods,
+                        path: caps.get(2).map(|m| m.as_str()).unwrap_or("").to_string(),
+                        line: line_no,
+                    });
+                } else if !pending.is_empty() && !line.trim_start().starts_with('@') {
+                    if java_class_regex().is_match(line) {
+                        // A mapping on the controller class prefixes its methods
+                        class_prefix = pending.drain(..).next().map(|p| p.path).unwrap_or_default();
+                    } else if let Some(caps) = java_method_regex().captures(line) {
+                        for p in pending.drain(..) {
+                            let path = join_paths(&class_prefix, &p.path);
+                            let path = if path.is_empty() {
+                                "/".to_string()
+                            } else {
+                                path
+                            };
+                            for method in &p.methods {
+                                routes.push(route(
+                                    method,
+                                    &path,
+                                    Some(caps[1].to_string()),
+                                    "spring",
+                                    p.line,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    routes
+        .into_iter()
+        .filter(|r| {
+            r.method == "ALL" || r.method == "ANY" || HTTP_METHODS.contains(&r.method.as_str())
+        })
+        .collect()
+}
+
+fn scan_workspace(root_path: &str) -> Vec<ApiRoute> {
+    let root = Path::new(root_path);
+    let mut routes = Vec::new();
+    for entry in WorkspaceWalker::new(root_path, WalkerConfig::for_list_files())
+        .build()
+        .flatten()
+    {
+        let path = entry.path();
+        if !path.is_file() || path.metadata().map(|m| m.len()).unwrap_or(0) > MAX_FILE_BYTES {
+            continue;
+        }
+        let path_str = path.to_string_lossy().to_string();
+        let Some(lang_id) = CodeNavigationService::get_lang_id_from_path(&path_str) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let rel_path = path_utils::relative_display(path, root);
+        routes.extend(extract_routes(&path_str, &rel_path, &content, &lang_id));
+    }
+    routes.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.method.cmp(&b.method)));
+    routes
+}
+
+#[tauri::command]
+pub async fn get_api_routes(root_path: String) -> Result<Vec<ApiRoute>, String> {
+    let routes = tokio::task::spawn_blocking(move || scan_workspace(&root_path))
+        .await
+        .map_err(|e| format!("Route extraction failed: {}", e))?;
+    log::info!("Extracted {} API routes", routes.len());
+    Ok(routes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(routes: &[ApiRoute]) -> Vec<(String, String, Option<String>)> {
+        routes
+            .iter()
+            .map(|r| (r.method.clone(), r.path.clone(), r.handler.clone()))
+            .collect()
+    }
+
+    fn entry(method: &str, path: &str, handler: Option<&str>) -> (String, String, Option<String>) {
+        (
+            method.to_string(),
+            path.to_string(),
+            handler.map(|h| h.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_express_routes() {
+        let content = "router.get('/users', auth, listUsers);\napp.post(\"/users\", (req, res) => {});\nconst r = await axios.get('/remote');\n";
+        let routes = extract_routes("/app/src/users.ts", "src/users.ts", content, "typescript");
+        assert_eq!(
+            summary(&routes),
+            vec![
+                entry("GET", "/users", Some("listUsers")),
+                entry("POST", "/users", None),
+            ]
+        );
+        assert_eq!(routes[0].framework, "express");
+        assert_eq!(routes[1].line, 2);
+    }
+
+    #[test]
+    fn test_next_route_path() {
+        assert_eq!(
+            next_route_path("app/api/users/[id]/route.ts").as_deref(),
+            Some("/api/users/[id]")
+        );
+        assert_eq!(
+            next_route_path("src/app/(admin)/stats/route.js").as_deref(),
+            Some("/stats")
+        );
+        assert_eq!(
+            next_route_path("pages/api/index.ts").as_deref(),
+            Some("/api")
+        );
+        assert_eq!(
+            next_route_path("pages/api/users/list.ts").as_deref(),
+            Some("/api/users/list")
+        );
+        assert_eq!(next_route_path("app/page.tsx"), None);
+        assert_eq!(next_route_path("src/lib/util.ts"), None);
+    }
+
+    #[test]
+    fn test_next_routes() {
+        let content = "export async function GET(req) {}\nexport function POST(req) {}\n";
+        let routes = extract_routes(
+            "/p/app/users/route.ts",
+            "app/users/route.ts",
+            content,
+            "typescript",
+        );
+        assert_eq!(
+            summary(&routes),
+            vec![
+                entry("GET", "/users", Some("GET")),
+                entry("POST", "/users", Some("POST"))
+            ]
+        );
+
+        let content = "export default async function handler(req, res) {}\n";
+        let routes = extract_routes(
+            "/p/pages/api/ping.ts",
+            "pages/api/ping.ts",
+            content,
+            "typescript",
+        );
+        assert_eq!(
+            summary(&routes),
+            vec![entry("ALL", "/api/ping", Some("handler"))]
+        );
+    }
+
+    #[test]
+    fn test_axum_and_actix_routes() {
+        let content = "let app = Router::new()\n    .route(\"/users\", get(list_users).post(handlers::create_user))\n    .route(\"/health\", web::get().to(health));\n\n#[get(\"/items/{id}\")]\nasync fn get_item() {}\n";
+        let routes = extract_routes("/p/src/main.rs", "src/main.rs", content, "rust");
+        assert_eq!(
+            summary(&routes),
+            vec![
+                entry("GET", "/users", Some("list_users")),
+                entry("POST", "/users", Some("create_user")),
+                entry("GET", "/health", Some("health")),
+                entry("GET", "/items/{id}", Some("get_item")),
+            ]
+        );
+        assert_eq!(routes[0].framework, "axum");
+        assert_eq!(routes[2].framework, "actix");
+        assert_eq!(routes[3].line, 5);
+    }
+
+    #[test]
+    fn test_flask_and_django_routes() {
+        let content = "@app.route('/login', methods=['GET', 'POST'])\ndef login():\n    pass\n\n@bp.delete('/items/<id>')\nasync def remove(id):\n    pass\n";
+        let routes = extract_routes("/p/app.py", "app.py", content, "python");
+        assert_eq!(
+            summary(&routes),
+            vec![
+                entry("GET", "/login", Some("login")),
+                entry("POST", "/login", Some("login")),
+                entry("DELETE", "/items/<id>", Some("remove")),
+            ]
+        );
+
+        let content =
+            "urlpatterns = [\n    path('users/<int:pk>/', views.user_detail, name='user'),\n]\n";
+        let routes = extract_routes("/p/shop/urls.py", "shop/urls.py", content, "python");
+        assert_eq!(
+            summary(&routes),
+            vec![entry("ANY", "/users/<int:pk>/", Some("views.user_detail"))]
+        );
+        assert_eq!(routes[0].framework, "django");
+    }
+
+    #[test]
+    fn test_spring_routes() {
+        let content = "@RestController\n@RequestMapping(\"/api/users\")\npublic class UserController {\n    @GetMapping(\"/{id}\")\n    public User get(@PathVariable Long id) {}\n\n    @PostMapping\n    public User create(@RequestBody User user) {}\n\n    @RequestMapping(value = \"/search\", method = RequestMethod.PUT)\n    public List<User> search() {}\n}\n";
+        let routes = extract_routes(
+            "/p/UserController.java",
+            "UserController.java",
+            content,
+            "java",
+        );
+        assert_eq!(
+            summary(&routes),
+            vec![
+                entry("GET", "/api/users/{id}", Some("get")),
+                entry("POST", "/api/users", Some("create")),
+                entry("PUT", "/api/users/search", Some("search")),
+            ]
+        );
+    }
+}
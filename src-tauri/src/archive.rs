@@ -1,14 +1,23 @@
 // Archive operations for skill package management
 // Provides tar.gz creation and extraction functionality
-
+//
+// `inspect_archive` and `extract_archive` open arbitrary zip and tar.gz files
+// (downloaded dependencies, bundles a user hands the agent), so they treat every
+// archive as hostile: entries with absolute paths or `..` fail the whole extraction,
+// links and special files are skipped, nothing is written through a symlink that
+// leads out of the destination, and entry count and unpacked size are capped by the
+// bytes actually written rather than what the archive headers claim. An extraction
+// that fails part way removes the files and directories it created.
+
+use crate::path_policy;
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
-use std::io::Write;
-use std::path::Path;
-use tar::{Archive, Builder};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, Builder, EntryType};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateTarballRequest {
@@ -203,6 +212,443 @@ pub fn extract_tarball(request: ExtractTarballRequest) -> Result<ExtractTarballR
     })
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveEntryKind {
+    File,
+    Directory,
+    Symlink,
+    /// Hard links, devices, FIFOs
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    /// Entry name as stored in the archive
+    pub path: String,
+    pub kind: ArchiveEntryKind,
+    /// Uncompressed size claimed by the archive
+    pub size: u64,
+    /// Zip only
+    pub compressed_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedEntry {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveInfo {
+    pub path: String,
+    pub format: ArchiveFormat,
+    pub entry_count: usize,
+    pub file_count: usize,
+    /// Sum of the claimed sizes of all files
+    pub total_size: u64,
+    /// The first `MAX_LISTED_ENTRIES` entries
+    pub entries: Vec<ArchiveEntry>,
+    pub truncated: bool,
+    /// Entries that escape the destination; an archive with any is not extracted
+    pub unsafe_entries: Vec<RejectedEntry>,
+    /// Links and special files, which extraction skips
+    pub skipped_entries: Vec<RejectedEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractArchiveResult {
+    pub dest_dir: String,
+    pub files_extracted: usize,
+    pub dirs_created: usize,
+    pub bytes_written: u64,
+    pub skipped_entries: Vec<RejectedEntry>,
+}
+
+/// Caps on what one archive may unpack to
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveLimits {
+    pub max_entries: usize,
+    pub max_entry_bytes: u64,
+    pub max_total_bytes: u64,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 50_000,
+            max_entry_bytes: 512 * 1024 * 1024,
+            max_total_bytes: 2 * 1024 * 1024 * 1024,
+        }
+    }
+}
+
+const MAX_LISTED_ENTRIES: usize = 1_000;
+
+/// An entry as both archive formats describe it
+struct RawEntry {
+    name: String,
+    kind: ArchiveEntryKind,
+    size: u64,
+    compressed_size: Option<u64>,
+    /// Unix permission bits, when the archive records them
+    mode: Option<u32>,
+}
+
+/// Format from the file's magic bytes, falling back to its extension
+fn detect_format(path: &Path) -> Result<ArchiveFormat, String> {
+    let mut magic = [0u8; 4];
+    let read = File::open(path)
+        .and_then(|mut f| f.read(&mut magic))
+        .map_err(|e| format!("Failed to open archive {}: {}", path.display(), e))?;
+    if read >= 4 && (magic == *b"PK\x03\x04" || magic == *b"PK\x05\x06") {
+        return Ok(ArchiveFormat::Zip);
+    }
+    if read >= 2 && magic[..2] == [0x1f, 0x8b] {
+        return Ok(ArchiveFormat::TarGz);
+    }
+    let name = path.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        Ok(ArchiveFormat::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Ok(ArchiveFormat::TarGz)
+    } else {
+        Err(format!(
+            "Unsupported archive format (expected zip or tar.gz): {}",
+            path.display()
+        ))
+    }
+}
+
+/// Call `visit` with each entry and a reader over its contents
+fn for_each_entry<F>(path: &Path, format: ArchiveFormat, mut visit: F) -> Result<(), String>
+where
+    F: FnMut(&RawEntry, &mut dyn Read) -> Result<(), String>,
+{
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    match format {
+        ArchiveFormat::TarGz => {
+            let mut archive = Archive::new(GzDecoder::new(file));
+            for entry in archive
+                .entries()
+                .map_err(|e| format!("Failed to read archive entries: {}", e))?
+            {
+                let mut entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+                let kind = match entry.header().entry_type() {
+                    EntryType::Regular | EntryType::Continuous => ArchiveEntryKind::File,
+                    EntryType::Directory => ArchiveEntryKind::Directory,
+                    EntryType::Symlink => ArchiveEntryKind::Symlink,
+                    // PAX and GNU long-name headers are consumed by the tar crate
+                    _ => ArchiveEntryKind::Other,
+                };
+                let raw = RawEntry {
+                    name: String::from_utf8_lossy(&entry.path_bytes()).to_string(),
+                    kind,
+                    size: entry.size(),
+                    compressed_size: None,
+                    mode: entry.header().mode().ok(),
+                };
+                visit(&raw, &mut entry)?;
+            }
+        }
+        ArchiveFormat::Zip => {
+            let mut archive =
+                zip::ZipArchive::new(file).map_err(|e| format!("Failed to open zip: {}", e))?;
+            for i in 0..archive.len() {
+                let mut entry = archive
+                    .by_index(i)
+                    .map_err(|e| format!("Failed to read zip entry: {}", e))?;
+                let mode = entry.unix_mode();
+                let kind = if mode.is_some_and(|m| m & 0o170000 == 0o120000) {
+                    ArchiveEntryKind::Symlink
+                } else if entry.is_dir() {
+                    ArchiveEntryKind::Directory
+                } else {
+                    ArchiveEntryKind::File
+                };
+                let raw = RawEntry {
+                    name: entry.name().to_string(),
+                    kind,
+                    size: entry.size(),
+                    compressed_size: Some(entry.compressed_size()),
+                    mode,
+                };
+                visit(&raw, &mut entry)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The relative path an entry unpacks to, or why it may not be unpacked
+fn safe_entry_path(name: &str) -> Result<PathBuf, String> {
+    let name = name.replace('\\', "/");
+    if name.starts_with('/') || name.as_bytes().get(1) == Some(&b':') {
+        return Err("absolute path".to_string());
+    }
+    let mut relative = PathBuf::new();
+    for component in Path::new(&name).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => return Err("path traversal (..)".to_string()),
+            Component::RootDir | Component::Prefix(_) => return Err("absolute path".to_string()),
+        }
+    }
+    // Empty for the archive root ("./"), which callers skip
+    Ok(relative)
+}
+
+fn inspect_with_limits(path: &Path, limits: &ArchiveLimits) -> Result<ArchiveInfo, String> {
+    let format = detect_format(path)?;
+    let mut info = ArchiveInfo {
+        path: path.to_string_lossy().to_string(),
+        format,
+        entry_count: 0,
+        file_count: 0,
+        total_size: 0,
+        entries: Vec::new(),
+        truncated: false,
+        unsafe_entries: Vec::new(),
+        skipped_entries: Vec::new(),
+    };
+
+    for_each_entry(path, format, |raw, _| {
+        info.entry_count += 1;
+        if info.entry_count > limits.max_entries {
+            return Err(format!(
+                "Archive has more than {} entries",
+                limits.max_entries
+            ));
+        }
+        let checked = safe_entry_path(&raw.name).and_then(|relative| {
+            if relative.as_os_str().is_empty() && raw.kind != ArchiveEntryKind::Directory {
+                Err("empty path".to_string())
+            } else {
+                Ok(relative)
+            }
+        });
+        if let Err(reason) = checked {
+            info.unsafe_entries.push(RejectedEntry {
+                path: raw.name.clone(),
+                reason,
+            });
+        } else {
+            match raw.kind {
+                ArchiveEntryKind::File => {
+                    info.file_count += 1;
+                    info.total_size = info.total_size.saturating_add(raw.size);
+                }
+                ArchiveEntryKind::Directory => {}
+                ArchiveEntryKind::Symlink => info.skipped_entries.push(RejectedEntry {
+                    path: raw.name.clone(),
+                    reason: "symlinks are not extracted".to_string(),
+                }),
+                ArchiveEntryKind::Other => info.skipped_entries.push(RejectedEntry {
+                    path: raw.name.clone(),
+                    reason: "hard links and special files are not extracted".to_string(),
+                }),
+            }
+        }
+        if info.entries.len() < MAX_LISTED_ENTRIES {
+            info.entries.push(ArchiveEntry {
+                path: raw.name.clone(),
+                kind: raw.kind,
+                size: raw.size,
+                compressed_size: raw.compressed_size,
+            });
+        } else {
+            info.truncated = true;
+        }
+        Ok(())
+    })?;
+    Ok(info)
+}
+
+#[cfg(unix)]
+fn apply_mode(path: &Path, mode: Option<u32>) {
+    use std::os::unix::fs::PermissionsExt;
+    // Permission bits only: no setuid, setgid or sticky bits from an archive
+    if let Some(mode) = mode.map(|m| m & 0o777).filter(|m| *m != 0) {
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(mode | 0o600));
+    }
+}
+
+#[cfg(not(unix))]
+fn apply_mode(_path: &Path, _mode: Option<u32>) {}
+
+/// Files and directories an extraction created, removed again if it fails
+#[derive(Default)]
+struct CreatedPaths {
+    files: Vec<PathBuf>,
+    /// Parents before children
+    dirs: Vec<PathBuf>,
+}
+
+impl CreatedPaths {
+    /// `fs::create_dir_all`, remembering the directories that did not exist yet
+    fn create_dir_all(&mut self, dir: &Path) -> std::io::Result<()> {
+        let missing: Vec<PathBuf> = dir
+            .ancestors()
+            .take_while(|d| !d.as_os_str().is_empty() && fs::symlink_metadata(d).is_err())
+            .map(Path::to_path_buf)
+            .collect();
+        self.dirs.extend(missing.into_iter().rev());
+        fs::create_dir_all(dir)
+    }
+
+    /// Files that existed before and were replaced under `overwrite` are left alone
+    fn remove(&self) {
+        for file in &self.files {
+            let _ = fs::remove_file(file);
+        }
+        // Children first; `remove_dir` keeps directories something else wrote into
+        for dir in self.dirs.iter().rev() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+fn extract_with_limits(
+    path: &Path,
+    dest_dir: &Path,
+    overwrite: bool,
+    limits: &ArchiveLimits,
+) -> Result<ExtractArchiveResult, String> {
+    // Validate everything before writing anything
+    let info = inspect_with_limits(path, limits)?;
+    if let Some(entry) = info.unsafe_entries.first() {
+        return Err(format!(
+            "Refusing to extract {}: entry '{}' has an {} ({} unsafe entries)",
+            path.display(),
+            entry.path,
+            entry.reason,
+            info.unsafe_entries.len()
+        ));
+    }
+    if info.total_size > limits.max_total_bytes {
+        return Err(format!(
+            "Archive unpacks to {} bytes, over the {} byte limit",
+            info.total_size, limits.max_total_bytes
+        ));
+    }
+
+    let mut created = CreatedPaths::default();
+    created
+        .create_dir_all(dest_dir)
+        .map_err(|e| format!("Failed to create destination directory: {}", e))?;
+    let mut result = ExtractArchiveResult {
+        dest_dir: dest_dir.to_string_lossy().to_string(),
+        files_extracted: 0,
+        dirs_created: 0,
+        bytes_written: 0,
+        skipped_entries: info.skipped_entries,
+    };
+
+    let extracted = for_each_entry(path, info.format, |raw, reader| {
+        if !matches!(
+            raw.kind,
+            ArchiveEntryKind::File | ArchiveEntryKind::Directory
+        ) {
+            return Ok(());
+        }
+        let relative = safe_entry_path(&raw.name)?;
+        if relative.as_os_str().is_empty() {
+            return Ok(());
+        }
+        // Catches symlinks already present in the destination
+        let target = path_policy::resolve_within(&relative, dest_dir).map_err(String::from)?;
+
+        if raw.kind == ArchiveEntryKind::Directory {
+            created
+                .create_dir_all(&target)
+                .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+            result.dirs_created += 1;
+            return Ok(());
+        }
+
+        let exists = fs::symlink_metadata(&target).is_ok();
+        if !overwrite && exists {
+            return Err(format!(
+                "{} already exists; pass overwrite to replace it",
+                target.display()
+            ));
+        }
+        if let Some(parent) = target.parent() {
+            created
+                .create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        // Headers can understate sizes, so the limits apply to the bytes actually read
+        let remaining = limits.max_total_bytes - result.bytes_written;
+        let allowed = limits.max_entry_bytes.min(remaining);
+        let mut output = File::create(&target)
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        if !exists {
+            created.files.push(target.clone());
+        }
+        let written = std::io::copy(&mut reader.take(allowed + 1), &mut output)
+            .map_err(|e| format!("Failed to extract {}: {}", raw.name, e))?;
+        if written > allowed {
+            drop(output);
+            let _ = fs::remove_file(&target);
+            return Err(format!(
+                "Entry '{}' exceeds the size limit ({} bytes)",
+                raw.name, allowed
+            ));
+        }
+        apply_mode(&target, raw.mode);
+        result.bytes_written += written;
+        result.files_extracted += 1;
+        Ok(())
+    });
+    if let Err(e) = extracted {
+        created.remove();
+        return Err(e);
+    }
+
+    log::info!(
+        "Extracted {} files ({} bytes) from {} to {}",
+        result.files_extracted,
+        result.bytes_written,
+        path.display(),
+        dest_dir.display()
+    );
+    Ok(result)
+}
+
+/// List a zip or tar.gz archive without extracting it
+#[tauri::command]
+pub fn inspect_archive(path: String) -> Result<ArchiveInfo, String> {
+    inspect_with_limits(Path::new(&path), &ArchiveLimits::default())
+}
+
+/// Extract a zip or tar.gz archive into `dest`. With `root_path`, `dest` must be inside
+/// the workspace. Existing files are only replaced when `overwrite` is set.
+#[tauri::command]
+pub fn extract_archive(
+    path: String,
+    dest: String,
+    root_path: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<ExtractArchiveResult, String> {
+    path_policy::check_optional(&dest, root_path.as_deref())?;
+    extract_with_limits(
+        Path::new(&path),
+        Path::new(&dest),
+        overwrite.unwrap_or(false),
+        &ArchiveLimits::default(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -326,4 +772,158 @@ mod tests {
         // This demonstrates that our starts_with check correctly identifies
         // paths that escape the destination directory
     }
+
+    fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_inspect_and_extract_zip() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("bundle.zip");
+        write_zip(
+            &archive_path,
+            &[("README.md", b"# hi"), ("src/lib.rs", b"pub fn a() {}")],
+        );
+
+        let info = inspect_archive(archive_path.to_string_lossy().to_string()).unwrap();
+        assert_eq!(info.format, ArchiveFormat::Zip);
+        assert_eq!(info.file_count, 2);
+        assert_eq!(info.total_size, 17);
+        assert!(info.unsafe_entries.is_empty());
+
+        let dest = temp_dir.path().join("out");
+        let result =
+            extract_with_limits(&archive_path, &dest, false, &ArchiveLimits::default()).unwrap();
+        assert_eq!(result.files_extracted, 2);
+        assert_eq!(
+            fs::read_to_string(dest.join("src/lib.rs")).unwrap(),
+            "pub fn a() {}"
+        );
+
+        // Existing files are kept unless overwrite is set
+        assert!(
+            extract_with_limits(&archive_path, &dest, false, &ArchiveLimits::default()).is_err()
+        );
+        assert!(extract_with_limits(&archive_path, &dest, true, &ArchiveLimits::default()).is_ok());
+    }
+
+    #[test]
+    fn test_extract_archive_rejects_traversal() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("evil.zip");
+        write_zip(&archive_path, &[("ok.txt", b"ok"), ("../evil.txt", b"x")]);
+
+        let info = inspect_with_limits(&archive_path, &ArchiveLimits::default()).unwrap();
+        assert_eq!(info.unsafe_entries.len(), 1);
+
+        let dest = temp_dir.path().join("out");
+        let error = extract_with_limits(&archive_path, &dest, false, &ArchiveLimits::default())
+            .unwrap_err();
+        assert!(error.contains("path traversal"), "{}", error);
+        assert!(!temp_dir.path().join("evil.txt").exists());
+        // Nothing is written when any entry is unsafe
+        assert!(!dest.join("ok.txt").exists());
+    }
+
+    #[test]
+    fn test_safe_entry_path() {
+        assert_eq!(
+            safe_entry_path("./a/b.txt").unwrap(),
+            PathBuf::from("a/b.txt")
+        );
+        assert!(safe_entry_path("/etc/passwd").is_err());
+        assert!(safe_entry_path("C:\\Windows\\x").is_err());
+        assert!(safe_entry_path("a\\..\\..\\b").is_err());
+        assert!(safe_entry_path("./").unwrap().as_os_str().is_empty());
+    }
+
+    #[test]
+    fn test_extract_archive_size_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("big.zip");
+        write_zip(
+            &archive_path,
+            &[("a.bin", &[0u8; 4096]), ("b.bin", &[0u8; 10])],
+        );
+        let dest = temp_dir.path().join("out");
+
+        let limits = ArchiveLimits {
+            max_entries: 10,
+            max_entry_bytes: 1024,
+            max_total_bytes: 1 << 20,
+        };
+        let error = extract_with_limits(&archive_path, &dest, false, &limits).unwrap_err();
+        assert!(error.contains("size limit"), "{}", error);
+        assert!(!dest.join("a.bin").exists());
+
+        let limits = ArchiveLimits {
+            max_entries: 1,
+            ..ArchiveLimits::default()
+        };
+        assert!(inspect_with_limits(&archive_path, &limits).is_err());
+    }
+
+    #[test]
+    fn test_failed_extraction_removes_written_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive_path = temp_dir.path().join("big.zip");
+        write_zip(
+            &archive_path,
+            &[("a.txt", b"small"), ("src/b.bin", &[0u8; 4096])],
+        );
+        let dest = temp_dir.path().join("out");
+        fs::create_dir_all(&dest).unwrap();
+
+        // The second entry is over the limit after the first was written
+        let limits = ArchiveLimits {
+            max_entries: 10,
+            max_entry_bytes: 1024,
+            max_total_bytes: 1 << 20,
+        };
+        let error = extract_with_limits(&archive_path, &dest, false, &limits).unwrap_err();
+        assert!(error.contains("size limit"), "{}", error);
+        assert_eq!(fs::read_dir(&dest).unwrap().count(), 0);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_extract_tar_gz_skips_symlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        // No extension: the format comes from the gzip magic bytes
+        let archive_path = temp_dir.path().join("download");
+        let encoder = GzEncoder::new(File::create(&archive_path).unwrap(), Compression::default());
+        let mut builder = Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(10);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "run.sh", &b"#!/bin/sh\n"[..])
+            .unwrap();
+        let mut link = tar::Header::new_gnu();
+        link.set_entry_type(EntryType::Symlink);
+        link.set_size(0);
+        builder
+            .append_link(&mut link, "passwd", "/etc/passwd")
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let info = inspect_with_limits(&archive_path, &ArchiveLimits::default()).unwrap();
+        assert_eq!(info.format, ArchiveFormat::TarGz);
+        assert_eq!(info.skipped_entries.len(), 1);
+
+        let dest = temp_dir.path().join("out");
+        let result =
+            extract_with_limits(&archive_path, &dest, false, &ArchiveLimits::default()).unwrap();
+        assert_eq!(result.files_extracted, 1);
+        assert!(fs::metadata(dest.join("run.sh")).unwrap().is_file());
+        assert!(fs::symlink_metadata(dest.join("passwd")).is_err());
+    }
 }
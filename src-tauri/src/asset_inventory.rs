@@ -0,0 +1,272 @@
+// Binary artifact and asset inventory
+//
+// Lists images, fonts, media, archives and binaries in the workspace with their sizes
+// and the source files that mention them by file name, so bundle-size investigations
+// start from structured data rather than directory dumps.
+
+use crate::path_utils;
+use crate::walker::{WalkerConfig, WorkspaceWalker};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Text files larger than this are not scanned for references
+const MAX_TEXT_FILE_BYTES: u64 = 1024 * 1024;
+
+const TEXT_EXTENSIONS: &[&str] = &[
+    "ts", "tsx", "js", "jsx", "mjs", "cjs", "vue", "svelte", "astro", "css", "scss", "sass",
+    "less", "html", "htm", "json", "md", "mdx", "rs", "py", "go", "java", "kt", "swift", "xml",
+    "yml", "yaml", "toml",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetEntry {
+    /// Path relative to the workspace root
+    pub path: String,
+    pub kind: String,
+    pub size_bytes: u64,
+    pub referenced_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KindTotals {
+    pub count: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetInventory {
+    /// Largest first
+    pub assets: Vec<AssetEntry>,
+    pub total_bytes: u64,
+    pub by_kind: BTreeMap<String, KindTotals>,
+    pub unreferenced_count: usize,
+    pub unreferenced_bytes: u64,
+}
+
+/// Asset category for a file extension, None for non-asset files
+pub fn asset_kind(extension: &str) -> Option<&'static str> {
+    let kind = match extension.to_lowercase().as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "avif" | "svg" | "ico" | "bmp" | "tiff"
+        | "icns" => "image",
+        "woff" | "woff2" | "ttf" | "otf" | "eot" => "font",
+        "mp4" | "webm" | "mov" | "avi" | "mp3" | "wav" | "ogg" | "flac" | "m4a" => "media",
+        "zip" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "7z" | "rar" => "archive",
+        "exe" | "dll" | "so" | "dylib" | "wasm" | "bin" | "node" | "a" | "lib" | "o" | "jar"
+        | "class" => "binary",
+        "pdf" => "document",
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Attach referencing files to assets by file name occurrence
+pub fn find_references(
+    assets: &mut [AssetEntry],
+    text_files: &[(String, String)], // (relative path, content)
+) {
+    let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, asset) in assets.iter().enumerate() {
+        if let Some(name) = Path::new(&asset.path).file_name() {
+            by_name
+                .entry(name.to_string_lossy().to_string())
+                .or_default()
+                .push(idx);
+        }
+    }
+    if by_name.is_empty() {
+        return;
+    }
+
+    let mut names: Vec<&String> = by_name.keys().collect();
+    // Longest first so "logo-dark.png" wins over "dark.png"
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+    let pattern = names
+        .iter()
+        .map(|name| regex::escape(name))
+        .collect::<Vec<_>>()
+        .join("|");
+    let Ok(matcher) = Regex::new(&format!(r"(?:^|[^\w.-])({})\b", pattern)) else {
+        return;
+    };
+
+    let mut references: Vec<BTreeSet<String>> = vec![BTreeSet::new(); assets.len()];
+    for (path, content) in text_files {
+        for caps in matcher.captures_iter(content) {
+            for &idx in &by_name[&caps[1]] {
+                references[idx].insert(path.clone());
+            }
+        }
+    }
+    for (asset, refs) in assets.iter_mut().zip(references) {
+        asset.referenced_by = refs.into_iter().collect();
+    }
+}
+
+pub fn summarize(mut assets: Vec<AssetEntry>) -> AssetInventory {
+    assets.sort_by(|a, b| {
+        b.size_bytes
+            .cmp(&a.size_bytes)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let mut by_kind: BTreeMap<String, KindTotals> = BTreeMap::new();
+    for asset in &assets {
+        let totals = by_kind.entry(asset.kind.clone()).or_default();
+        totals.count += 1;
+        totals.bytes += asset.size_bytes;
+    }
+    let unreferenced: Vec<&AssetEntry> = assets
+        .iter()
+        .filter(|a| a.referenced_by.is_empty())
+        .collect();
+
+    AssetInventory {
+        total_bytes: assets.iter().map(|a| a.size_bytes).sum(),
+        unreferenced_count: unreferenced.len(),
+        unreferenced_bytes: unreferenced.iter().map(|a| a.size_bytes).sum(),
+        by_kind,
+        assets,
+    }
+}
+
+fn scan_workspace(root_path: &str) -> AssetInventory {
+    let root = Path::new(root_path);
+    let mut assets = Vec::new();
+    let mut text_files = Vec::new();
+
+    for entry in WorkspaceWalker::new(root_path, WalkerConfig::for_list_files())
+        .build()
+        .flatten()
+    {
+        let path = entry.path();
+        let Ok(metadata) = path.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let rel_path = path_utils::relative_display(path, root);
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+        if let Some(kind) = asset_kind(ext) {
+            assets.push(AssetEntry {
+                path: rel_path.clone(),
+                kind: kind.to_string(),
+                size_bytes: metadata.len(),
+                referenced_by: Vec::new(),
+            });
+        }
+        // SVGs are both assets and text that can reference other assets
+        if (TEXT_EXTENSIONS.contains(&ext) || ext == "svg") && metadata.len() <= MAX_TEXT_FILE_BYTES
+        {
+            if let Ok(content) = fs::read_to_string(path) {
+                text_files.push((rel_path, content));
+            }
+        }
+    }
+
+    find_references(&mut assets, &text_files);
+    summarize(assets)
+}
+
+#[tauri::command]
+pub async fn inventory_assets(
+    root_path: String,
+    limit: Option<usize>,
+) -> Result<AssetInventory, String> {
+    let mut inventory = tokio::task::spawn_blocking(move || scan_workspace(&root_path))
+        .await
+        .map_err(|e| format!("Asset inventory failed: {}", e))?;
+    log::info!(
+        "Inventoried {} assets ({} bytes, {} unreferenced)",
+        inventory.assets.len(),
+        inventory.total_bytes,
+        inventory.unreferenced_count
+    );
+    // Totals cover everything; only the entry list is truncated
+    if let Some(limit) = limit {
+        inventory.assets.truncate(limit);
+    }
+    Ok(inventory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn asset(path: &str, kind: &str, size: u64) -> AssetEntry {
+        AssetEntry {
+            path: path.to_string(),
+            kind: kind.to_string(),
+            size_bytes: size,
+            referenced_by: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_asset_kind() {
+        assert_eq!(asset_kind("PNG"), Some("image"));
+        assert_eq!(asset_kind("woff2"), Some("font"));
+        assert_eq!(asset_kind("wasm"), Some("binary"));
+        assert_eq!(asset_kind("ts"), None);
+    }
+
+    #[test]
+    fn test_find_references() {
+        let mut assets = vec![
+            asset("public/logo.png", "image", 10),
+            asset("public/logo-dark.png", "image", 10),
+            asset("fonts/Inter.woff2", "font", 10),
+        ];
+        let text_files = vec![
+            (
+                "src/App.tsx".to_string(),
+                "import logo from './logo-dark.png';\n".to_string(),
+            ),
+            (
+                "src/index.css".to_string(),
+                "body { background: url(/logo.png); }\n".to_string(),
+            ),
+        ];
+        find_references(&mut assets, &text_files);
+        assert_eq!(assets[0].referenced_by, vec!["src/index.css"]);
+        assert_eq!(assets[1].referenced_by, vec!["src/App.tsx"]);
+        assert!(assets[2].referenced_by.is_empty());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let mut referenced = asset("a.png", "image", 100);
+        referenced.referenced_by = vec!["index.html".to_string()];
+        let inventory = summarize(vec![
+            asset("small.woff", "font", 5),
+            referenced,
+            asset("big.mp4", "media", 1000),
+        ]);
+        assert_eq!(inventory.assets[0].path, "big.mp4");
+        assert_eq!(inventory.total_bytes, 1105);
+        assert_eq!(inventory.by_kind["image"].count, 1);
+        assert_eq!(inventory.unreferenced_count, 2);
+        assert_eq!(inventory.unreferenced_bytes, 1005);
+    }
+
+    #[test]
+    fn test_scan_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("assets")).unwrap();
+        fs::write(root.join("assets/hero.jpg"), vec![0u8; 2048]).unwrap();
+        fs::write(root.join("assets/unused.gif"), vec![0u8; 16]).unwrap();
+        fs::write(root.join("index.html"), "<img src=\"assets/hero.jpg\">\n").unwrap();
+
+        let inventory = scan_workspace(&root.to_string_lossy());
+        assert_eq!(inventory.assets.len(), 2);
+        assert_eq!(inventory.assets[0].path, "assets/hero.jpg");
+        assert_eq!(inventory.assets[0].referenced_by, vec!["index.html"]);
+        assert_eq!(inventory.unreferenced_count, 1);
+    }
+}
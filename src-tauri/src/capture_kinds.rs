@@ -0,0 +1,285 @@
+// Capture naming convention for tree-sitter queries
+//
+// Every capture in the definition and summarization queries names one CaptureKind.
+// Summarization queries capture whole definitions with the bare kind (`@function`);
+// definition queries capture the name node with a `.definition` suffix
+// (`@function.definition`). A few older spellings are accepted as aliases so existing
+// queries keep their output. Captures starting with `_` only feed predicates
+// (`(#any-of? @_keyword "def" "defp")`) and are never reported. `introspect_queries`
+// lists what each language captures so the frontend and query overrides can rely on
+// these kinds instead of raw names.
+
+use crate::code_navigation::{get_language, get_summarization_query, CodeNavigationService};
+use serde::{Deserialize, Serialize};
+use tree_sitter::Query;
+
+/// Suffix of captures that mark a definition's name node
+pub const DEFINITION_SUFFIX: &str = ".definition";
+
+/// Captures that only exist for predicates, e.g. the keyword of an Elixir `def`
+pub fn is_helper_capture(name: &str) -> bool {
+    name.starts_with('_')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureKind {
+    Function,
+    Method,
+    /// A variable bound to an arrow function (JS/TS)
+    ArrowFunction,
+    Class,
+    Interface,
+    TypeAlias,
+    Enum,
+    Struct,
+    Trait,
+    /// Rust `impl` block
+    Impl,
+    Const,
+    Static,
+    /// `var`, `let` and module-level assignments
+    Variable,
+    Field,
+    /// C/C++ `typedef`
+    Typedef,
+}
+
+impl CaptureKind {
+    pub const ALL: &'static [CaptureKind] = &[
+        CaptureKind::Function,
+        CaptureKind::Method,
+        CaptureKind::ArrowFunction,
+        CaptureKind::Class,
+        CaptureKind::Interface,
+        CaptureKind::TypeAlias,
+        CaptureKind::Enum,
+        CaptureKind::Struct,
+        CaptureKind::Trait,
+        CaptureKind::Impl,
+        CaptureKind::Const,
+        CaptureKind::Static,
+        CaptureKind::Variable,
+        CaptureKind::Field,
+        CaptureKind::Typedef,
+    ];
+
+    /// Canonical capture name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaptureKind::Function => "function",
+            CaptureKind::Method => "method",
+            CaptureKind::ArrowFunction => "arrow_function",
+            CaptureKind::Class => "class",
+            CaptureKind::Interface => "interface",
+            CaptureKind::TypeAlias => "type_alias",
+            CaptureKind::Enum => "enum",
+            CaptureKind::Struct => "struct",
+            CaptureKind::Trait => "trait",
+            CaptureKind::Impl => "impl",
+            CaptureKind::Const => "const",
+            CaptureKind::Static => "static",
+            CaptureKind::Variable => "variable",
+            CaptureKind::Field => "field",
+            CaptureKind::Typedef => "typedef",
+        }
+    }
+
+    /// Kind of a capture name, with or without the `.definition` suffix
+    pub fn from_capture_name(name: &str) -> Option<Self> {
+        let base = name.strip_suffix(DEFINITION_SUFFIX).unwrap_or(name);
+        let kind = match base {
+            // Aliases used by the original queries
+            "type" | "type_decl" => CaptureKind::TypeAlias,
+            "const_decl" => CaptureKind::Const,
+            "var" | "assignment" => CaptureKind::Variable,
+            _ => {
+                return CaptureKind::ALL
+                    .iter()
+                    .copied()
+                    .find(|k| k.as_str() == base)
+            }
+        };
+        Some(kind)
+    }
+
+    /// `SymbolInfo::kind` reported for definitions of this kind
+    pub fn symbol_kind(self) -> &'static str {
+        match self {
+            CaptureKind::ArrowFunction => "function",
+            CaptureKind::TypeAlias | CaptureKind::Typedef => "type",
+            other => other.as_str(),
+        }
+    }
+
+    pub fn is_callable(self) -> bool {
+        matches!(
+            self,
+            CaptureKind::Function | CaptureKind::Method | CaptureKind::ArrowFunction
+        )
+    }
+
+    /// Value bindings rather than types or callables
+    pub fn is_value(self) -> bool {
+        matches!(
+            self,
+            CaptureKind::Const | CaptureKind::Static | CaptureKind::Variable | CaptureKind::Field
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureInfo {
+    pub capture_name: String,
+    /// None when the capture does not follow the naming convention
+    pub kind: Option<CaptureKind>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryInfo {
+    /// "definitions" or "summarization"
+    pub name: String,
+    pub pattern_count: usize,
+    pub captures: Vec<CaptureInfo>,
+    /// Set when the query does not compile for the language
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryIntrospection {
+    pub lang_id: String,
+    pub queries: Vec<QueryInfo>,
+}
+
+fn describe_query(name: &str, lang_id: &str, source: &str) -> Option<QueryInfo> {
+    if source.is_empty() {
+        return None;
+    }
+    let language = get_language(lang_id)?;
+    Some(match Query::new(&language, source) {
+        Ok(query) => QueryInfo {
+            name: name.to_string(),
+            pattern_count: query.pattern_count(),
+            captures: query
+                .capture_names()
+                .iter()
+                .filter(|capture| !is_helper_capture(capture))
+                .map(|capture| CaptureInfo {
+                    capture_name: capture.to_string(),
+                    kind: CaptureKind::from_capture_name(capture),
+                })
+                .collect(),
+            error: None,
+        },
+        Err(e) => QueryInfo {
+            name: name.to_string(),
+            pattern_count: 0,
+            captures: Vec::new(),
+            error: Some(format!("{:?}", e)),
+        },
+    })
+}
+
+pub fn introspect(lang_id: &str) -> Result<QueryIntrospection, String> {
+    if get_language(lang_id).is_none() {
+        return Err(format!("Unsupported language: {}", lang_id));
+    }
+    let queries = [
+        describe_query(
+            "definitions",
+            lang_id,
+            CodeNavigationService::get_definition_query(lang_id),
+        ),
+        describe_query("summarization", lang_id, get_summarization_query(lang_id)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    Ok(QueryIntrospection {
+        lang_id: lang_id.to_string(),
+        queries,
+    })
+}
+
+/// List the captures of each query the backend runs for `lang_id`
+#[tauri::command]
+pub fn introspect_queries(lang_id: String) -> Result<QueryIntrospection, String> {
+    introspect(&lang_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_name_parsing() {
+        assert_eq!(
+            CaptureKind::from_capture_name("function.definition"),
+            Some(CaptureKind::Function)
+        );
+        assert_eq!(
+            CaptureKind::from_capture_name("const_decl"),
+            Some(CaptureKind::Const)
+        );
+        assert_eq!(
+            CaptureKind::from_capture_name("type.definition"),
+            Some(CaptureKind::TypeAlias)
+        );
+        assert_eq!(CaptureKind::from_capture_name("unknown"), None);
+        for kind in CaptureKind::ALL {
+            assert_eq!(CaptureKind::from_capture_name(kind.as_str()), Some(*kind));
+        }
+    }
+
+    #[test]
+    fn test_all_queries_follow_convention() {
+        for lang_id in [
+            "python",
+            "rust",
+            "go",
+            "c",
+            "cpp",
+            "java",
+            "csharp",
+            "swift",
+            "ruby",
+            "scala",
+            "elixir",
+            "haskell",
+            "ocaml",
+            "ocaml_interface",
+            "bash",
+            "powershell",
+            "objc",
+            "julia",
+            "typescript",
+            "javascript",
+        ] {
+            let introspection = introspect(lang_id).unwrap();
+            assert_eq!(introspection.queries.len(), 2, "{}", lang_id);
+            for query in &introspection.queries {
+                assert!(
+                    query.error.is_none(),
+                    "{} {} query: {:?}",
+                    lang_id,
+                    query.name,
+                    query.error
+                );
+                assert!(query.pattern_count > 0);
+                for capture in &query.captures {
+                    assert!(
+                        capture.kind.is_some(),
+                        "{} capture @{} has no kind",
+                        lang_id,
+                        capture.capture_name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        assert!(introspect_queries("cobol".to_string()).is_err());
+    }
+}
@@ -1,15 +1,39 @@
+use crate::capture_kinds::{is_helper_capture, CaptureKind};
+use crate::css_outline;
+use crate::declaration_files;
+use crate::gradle_outline;
+use crate::grammar_cache::{self, QuerySet};
+use crate::graphql_outline;
+use crate::html_outline;
+use crate::index_maintenance::{self, IndexFile};
+use crate::language_config::{self, LanguageConfig};
+use crate::large_file;
+use crate::line_endings;
+use crate::markdown_outline;
+use crate::position_encoding::{self, PositionEncoding};
+use crate::precise_index::PreciseIndex;
+use crate::protobuf_outline;
+use crate::qualified_names;
 use crate::search::RipgrepSearch;
+use crate::summary_policy::{self, KindPolicy};
+use crate::symbol_match::{self, MatchMode};
+use crate::symbol_priority::{select_within_budget, RetentionCandidate};
+use crate::text_slice::{before_in_code, find_in_code, slice_to, through_in_code};
+use crate::toml_outline;
+use crate::vue_sfc;
+use crate::workspace_state::{Scoped, WorkspaceState};
+use crate::yaml_outline;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 use streaming_iterator::StreamingIterator;
 use tauri::{AppHandle, Manager, State};
-use tree_sitter::{Language, Parser, Point, Query, QueryCursor, Tree};
+use tree_sitter::{Language, Node, Parser, Point, Query, QueryCursor, Tree};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolInfo {
@@ -21,6 +45,9 @@ pub struct SymbolInfo {
     pub start_column: u32,
     pub end_line: u32,
     pub end_column: u32,
+    /// `Class.method`, `module::fn`, `pkg.Class#method`; None for top-level definitions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qualified_name: Option<String>,
 }
 
 #[derive(Default)]
@@ -33,60 +60,56 @@ struct SymbolIndex {
 pub struct CodeNavigationService {
     parsers: HashMap<String, Parser>,
     languages: HashMap<String, Language>,
-    queries: HashMap<String, Query>,
+    queries: HashMap<String, Arc<Query>>,
     index: SymbolIndex,
+    /// Occurrences imported from SCIP/LSIF dumps; kept across re-indexing
+    pub(crate) precise: PreciseIndex,
+    /// Workspace extension overrides and disabled languages
+    pub(crate) language_config: LanguageConfig,
 }
 
 impl CodeNavigationService {
+    /// Languages are loaded on first use; see `grammar_cache` for warming them up early
     pub fn new() -> Self {
-        let mut service = Self {
+        Self {
             parsers: HashMap::new(),
             languages: HashMap::new(),
             queries: HashMap::new(),
             index: SymbolIndex::default(),
-        };
-        service.init_languages();
-        service
+            precise: PreciseIndex::default(),
+            language_config: LanguageConfig::default(),
+        }
     }
 
-    fn init_languages(&mut self) {
-        self.register_language("python", tree_sitter_python::LANGUAGE.into());
-        self.register_language("rust", tree_sitter_rust::LANGUAGE.into());
-        self.register_language("go", tree_sitter_go::LANGUAGE.into());
-        self.register_language("c", tree_sitter_c::LANGUAGE.into());
-        self.register_language("cpp", tree_sitter_cpp::LANGUAGE.into());
-        self.register_language("java", tree_sitter_java::LANGUAGE.into());
-
-        // TypeScript and JavaScript (using TSX parser which handles both TS and TSX/JSX syntax)
-        self.register_language("typescript", tree_sitter_typescript::LANGUAGE_TSX.into());
-        self.register_language("javascript", tree_sitter_typescript::LANGUAGE_TSX.into());
-    }
+    /// Set up the parser and definition query for a language the first time it is seen.
+    /// Returns false for unsupported languages.
+    fn ensure_language(&mut self, lang_id: &str) -> bool {
+        if self.parsers.contains_key(lang_id) {
+            return true;
+        }
+        let Some(language) = get_language(lang_id) else {
+            return false;
+        };
 
-    fn register_language(&mut self, lang_id: &str, language: Language) {
         let mut parser = Parser::new();
         if parser.set_language(&language).is_err() {
             log::error!("Failed to set language for {}", lang_id);
-            return;
+            return false;
         }
 
-        // Create definition query for this language
-        let query_str = Self::get_definition_query(lang_id);
-        if !query_str.is_empty() {
-            match Query::new(&language, query_str) {
-                Ok(query) => {
-                    self.queries.insert(lang_id.to_string(), query);
-                }
-                Err(e) => {
-                    log::error!("Failed to create definition query for {}: {:?}", lang_id, e);
-                }
+        match grammar_cache::query(QuerySet::Definitions, lang_id) {
+            Ok(query) => {
+                self.queries.insert(lang_id.to_string(), query);
             }
+            Err(e) => log::error!("Failed to create definition query for {}: {}", lang_id, e),
         }
 
         self.parsers.insert(lang_id.to_string(), parser);
         self.languages.insert(lang_id.to_string(), language);
+        true
     }
 
-    fn get_definition_query(lang_id: &str) -> &'static str {
+    pub(crate) fn get_definition_query(lang_id: &str) -> &'static str {
         match lang_id {
             "python" => {
                 r#"
@@ -133,6 +156,131 @@ impl CodeNavigationService {
                 (interface_declaration name: (identifier) @interface.definition)
                 "#
             }
+            "csharp" => {
+                r#"
+                (class_declaration name: (identifier) @class.definition)
+                (record_declaration name: (identifier) @class.definition)
+                (interface_declaration name: (identifier) @interface.definition)
+                (struct_declaration name: (identifier) @struct.definition)
+                (enum_declaration name: (identifier) @enum.definition)
+                (method_declaration name: (identifier) @method.definition)
+                (property_declaration name: (identifier) @field.definition)
+                "#
+            }
+            "swift" => {
+                r#"
+                (class_declaration declaration_kind: "class" name: (type_identifier) @class.definition)
+                (class_declaration declaration_kind: "actor" name: (type_identifier) @class.definition)
+                (class_declaration declaration_kind: "struct" name: (type_identifier) @struct.definition)
+                (class_declaration declaration_kind: "enum" name: (type_identifier) @enum.definition)
+                (protocol_declaration name: (type_identifier) @interface.definition)
+                (function_declaration name: (simple_identifier) @function.definition)
+                "#
+            }
+            "ruby" => {
+                r#"
+                (class name: (constant) @class.definition)
+                (module name: (constant) @class.definition)
+                (method name: (_) @method.definition)
+                (singleton_method name: (_) @method.definition)
+                (assignment left: (constant) @const.definition)
+                "#
+            }
+            "scala" => {
+                r#"
+                (class_definition name: (_) @class.definition)
+                (object_definition name: (_) @class.definition)
+                (trait_definition name: (_) @trait.definition)
+                (function_definition name: (_) @function.definition)
+                "#
+            }
+            "elixir" => {
+                r#"
+                (call
+                  target: (identifier) @_keyword
+                  (arguments . (alias) @class.definition)
+                  (#any-of? @_keyword "defmodule" "defprotocol"))
+                (call
+                  target: (identifier) @_keyword
+                  (arguments . [
+                    (identifier) @function.definition
+                    (call target: (identifier) @function.definition)
+                    (binary_operator left: (call target: (identifier) @function.definition))
+                  ])
+                  (#any-of? @_keyword "def" "defp" "defmacro" "defmacrop" "defguard" "defguardp" "defdelegate"))
+                (unary_operator
+                  operator: "@"
+                  operand: (call target: (identifier) @const.definition)
+                  (#not-any-of? @const.definition "moduledoc" "doc" "typedoc" "spec" "impl" "type" "typep" "opaque" "callback" "macrocallback" "behaviour" "derive" "enforce_keys" "deprecated" "since" "compile"))
+                "#
+            }
+            "haskell" => {
+                r#"
+                (signature name: (_) @function.definition)
+                (data_type name: (_) @struct.definition)
+                (newtype name: (_) @struct.definition)
+                (type_synomym name: (_) @type.definition)
+                (class name: (_) @trait.definition)
+                "#
+            }
+            "ocaml" => {
+                r#"
+                (value_definition (let_binding pattern: (value_name) @function.definition))
+                (module_binding (module_name) @class.definition)
+                (module_type_definition (module_type_name) @interface.definition)
+                (type_binding name: (_) @type.definition)
+                "#
+            }
+            "bash" => {
+                r#"
+                (function_definition name: (word) @function.definition)
+                (program (variable_assignment name: (variable_name) @variable.definition))
+                (program (declaration_command (variable_assignment name: (variable_name) @variable.definition)))
+                "#
+            }
+            "powershell" => {
+                r#"
+                (function_statement (function_name) @function.definition)
+                "#
+            }
+            "objc" => {
+                r#"
+                (class_interface . (identifier) @class.definition)
+                (class_implementation . (identifier) @class.definition)
+                (protocol_declaration . (identifier) @interface.definition)
+                (method_definition (identifier) @method.definition)
+                (function_definition declarator: (function_declarator declarator: (identifier) @function.definition))
+                "#
+            }
+            "julia" => {
+                r#"
+                (module_definition . (identifier) @class.definition)
+                (struct_definition (type_head [
+                  (identifier) @struct.definition
+                  (parametrized_type_expression . (identifier) @struct.definition)
+                  (binary_expression . [
+                    (identifier) @struct.definition
+                    (parametrized_type_expression . (identifier) @struct.definition)
+                  ])
+                ]))
+                (abstract_definition (type_head [
+                  (identifier) @type.definition
+                  (binary_expression . (identifier) @type.definition)
+                ]))
+                (function_definition (signature (call_expression . (identifier) @function.definition)))
+                (function_definition (signature (where_expression . (call_expression . (identifier) @function.definition))))
+                (macro_definition (signature (call_expression . (identifier) @function.definition)))
+                (assignment . (call_expression . (identifier) @function.definition))
+                "#
+            }
+            "ocaml_interface" => {
+                r#"
+                (value_specification (value_name) @function.definition)
+                (module_binding (module_name) @class.definition)
+                (module_type_definition (module_type_name) @interface.definition)
+                (type_binding name: (_) @type.definition)
+                "#
+            }
             "typescript" | "javascript" => {
                 r#"
                 (function_declaration name: (identifier) @function.definition)
@@ -155,29 +303,10 @@ impl CodeNavigationService {
     }
 
     fn get_symbol_kind(capture_name: &str) -> String {
-        if capture_name.contains("function") {
-            "function".to_string()
-        } else if capture_name.contains("class") {
-            "class".to_string()
-        } else if capture_name.contains("struct") {
-            "struct".to_string()
-        } else if capture_name.contains("enum") {
-            "enum".to_string()
-        } else if capture_name.contains("trait") {
-            "trait".to_string()
-        } else if capture_name.contains("interface") {
-            "interface".to_string()
-        } else if capture_name.contains("method") {
-            "method".to_string()
-        } else if capture_name.contains("type") {
-            "type".to_string()
-        } else if capture_name.contains("const") {
-            "const".to_string()
-        } else if capture_name.contains("static") {
-            "static".to_string()
-        } else {
-            "symbol".to_string()
-        }
+        CaptureKind::from_capture_name(capture_name)
+            .map(CaptureKind::symbol_kind)
+            .unwrap_or("symbol")
+            .to_string()
     }
 
     /// Get language family for language isolation
@@ -185,12 +314,22 @@ impl CodeNavigationService {
     /// Other languages are isolated
     pub fn get_lang_family(lang_id: &str) -> &'static str {
         match lang_id {
-            "c" | "cpp" => "c_family",
+            "c" | "cpp" | "objc" => "c_family",
             "typescript" | "javascript" => "js_family",
             "python" => "python",
             "rust" => "rust",
             "go" => "go",
             "java" => "java",
+            "csharp" => "csharp",
+            "swift" => "swift",
+            "ruby" => "ruby",
+            "scala" => "scala",
+            "elixir" => "elixir",
+            "haskell" => "haskell",
+            "ocaml" | "ocaml_interface" => "ocaml",
+            "bash" => "bash",
+            "powershell" => "powershell",
+            "julia" => "julia",
             _ => "unknown",
         }
     }
@@ -201,6 +340,15 @@ impl CodeNavigationService {
         // First clear existing symbols for this file
         self.clear_file(file_path);
 
+        let Some(lang_id) = self.language_config.resolve(file_path, lang_id) else {
+            log::debug!("Language disabled for {}", file_path);
+            return;
+        };
+        let lang_id = lang_id.as_str();
+        if !self.ensure_language(lang_id) {
+            log::debug!("No parser for language: {}", lang_id);
+            return;
+        }
         let parser = match self.parsers.get_mut(lang_id) {
             Some(p) => p,
             None => {
@@ -226,18 +374,31 @@ impl CodeNavigationService {
 
         if let Some(query) = self.queries.get(lang_id) {
             let mut cursor = QueryCursor::new();
-            let mut matches = cursor.matches(query, tree.root_node(), source_bytes);
+            let mut matches = cursor.matches(query.as_ref(), tree.root_node(), source_bytes);
 
             while let Some(m) = matches.next() {
                 for capture in m.captures {
                     let node = capture.node;
-                    let name = match node.utf8_text(source_bytes) {
-                        Ok(text) => text.to_string(),
+                    let raw_name = match node.utf8_text(source_bytes) {
+                        Ok(text) => qualified_names::strip_generics(text),
                         Err(_) => continue,
                     };
 
                     let capture_name = query.capture_names()[capture.index as usize];
+                    if is_helper_capture(capture_name) {
+                        continue;
+                    }
                     let kind = Self::get_symbol_kind(capture_name);
+                    let callable = CaptureKind::from_capture_name(capture_name)
+                        .is_some_and(CaptureKind::is_callable);
+                    let qualified_name = qualified_names::qualified_name(
+                        node,
+                        source_bytes,
+                        lang_id,
+                        &raw_name,
+                        callable,
+                    );
+                    let name = qualified_names::index_key(&raw_name);
 
                     definitions.push(SymbolInfo {
                         name: name.clone(),
@@ -248,6 +409,7 @@ impl CodeNavigationService {
                         start_column: node.start_position().column as u32 + 1,
                         end_line: node.end_position().row as u32 + 1,
                         end_column: node.end_position().column as u32 + 1,
+                        qualified_name,
                     });
                     defined_names.insert(name);
                 }
@@ -277,18 +439,81 @@ impl CodeNavigationService {
         );
     }
 
+    /// Snapshot of the index in its persisted format
+    pub fn to_persisted(
+        &self,
+        root_path: &str,
+        file_timestamps: HashMap<String, i64>,
+    ) -> PersistedIndex {
+        PersistedIndex {
+            version: INDEX_VERSION,
+            root_path: root_path.to_string(),
+            last_updated: chrono::Utc::now().timestamp(),
+            file_timestamps,
+            definitions: self.index.definitions.clone(),
+            file_definitions: self.index.file_definitions.clone(),
+        }
+    }
+
+    /// Definitions of `symbol_name`, which may be qualified with any separator
+    /// (`Foo.bar`, `Foo::bar`, `Foo#bar`) and carry generic parameters
     pub fn find_definition(&self, symbol_name: &str, lang_family: &str) -> Vec<SymbolInfo> {
-        self.index
+        let in_family = |key: &str| -> Vec<&SymbolInfo> {
+            self.index
+                .definitions
+                .get(key)
+                .map(|symbols| {
+                    symbols
+                        .iter()
+                        .filter(|s| s.lang_family == lang_family)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        // Names that contain dots themselves (Elixir modules) are keys as written
+        let mut found = in_family(&qualified_names::strip_generics(symbol_name));
+        let query = qualified_names::segments(symbol_name);
+        if let [.., last] = query.as_slice() {
+            if query.len() > 1 {
+                found.extend(in_family(last).into_iter().filter(|s| {
+                    s.qualified_name
+                        .as_deref()
+                        .is_some_and(|q| qualified_names::matches_query(q, &query))
+                }));
+            }
+        }
+        found.into_iter().cloned().collect()
+    }
+
+    /// Definitions whose name matches `query` under `mode`, strictest matches first,
+    /// then shorter names
+    pub fn search_symbols(
+        &self,
+        query: &str,
+        lang_family: Option<&str>,
+        mode: MatchMode,
+        limit: usize,
+    ) -> Vec<SymbolInfo> {
+        let mut matched: Vec<(MatchMode, &String)> = self
+            .index
             .definitions
-            .get(symbol_name)
-            .map(|symbols| {
-                symbols
-                    .iter()
-                    .filter(|s| s.lang_family == lang_family)
-                    .cloned()
-                    .collect()
-            })
-            .unwrap_or_default()
+            .keys()
+            .filter_map(|name| symbol_match::match_mode(name, query, mode).map(|m| (m, name)))
+            .collect();
+        matched.sort_by(|(a_mode, a), (b_mode, b)| {
+            a_mode
+                .cmp(b_mode)
+                .then(a.len().cmp(&b.len()))
+                .then(a.cmp(b))
+        });
+        matched
+            .into_iter()
+            .flat_map(|(_, name)| &self.index.definitions[name])
+            .filter(|s| lang_family.is_none_or(|family| s.lang_family == family))
+            .take(limit)
+            .cloned()
+            .collect()
     }
 
     /// Hybrid reference search: text search + tree-sitter filtering
@@ -345,15 +570,8 @@ impl CodeNavigationService {
             };
 
             // Get language and create parser
-            let language: Language = match lang_id.as_str() {
-                "python" => tree_sitter_python::LANGUAGE.into(),
-                "rust" => tree_sitter_rust::LANGUAGE.into(),
-                "go" => tree_sitter_go::LANGUAGE.into(),
-                "c" => tree_sitter_c::LANGUAGE.into(),
-                "cpp" => tree_sitter_cpp::LANGUAGE.into(),
-                "java" => tree_sitter_java::LANGUAGE.into(),
-                "typescript" | "javascript" => tree_sitter_typescript::LANGUAGE_TSX.into(),
-                _ => continue,
+            let Some(language) = get_language(&lang_id) else {
+                continue;
             };
 
             let mut parser = Parser::new();
@@ -395,7 +613,7 @@ impl CodeNavigationService {
     }
 
     /// Get language ID from file path based on extension
-    fn get_lang_id_from_path(file_path: &str) -> Option<String> {
+    pub(crate) fn get_lang_id_from_path(file_path: &str) -> Option<String> {
         let ext = file_path.rsplit('.').next()?;
         match ext.to_lowercase().as_str() {
             "py" => Some("python".to_string()),
@@ -404,6 +622,29 @@ impl CodeNavigationService {
             "c" | "h" => Some("c".to_string()),
             "cpp" | "cc" | "cxx" | "hpp" | "hxx" => Some("cpp".to_string()),
             "java" => Some("java".to_string()),
+            "cs" => Some("csharp".to_string()),
+            "swift" => Some("swift".to_string()),
+            "rb" | "rake" | "gemspec" => Some("ruby".to_string()),
+            "scala" | "sc" => Some("scala".to_string()),
+            "ex" | "exs" => Some("elixir".to_string()),
+            "hs" => Some("haskell".to_string()),
+            "ml" => Some("ocaml".to_string()),
+            "mli" => Some("ocaml_interface".to_string()),
+            "sh" | "bash" => Some("bash".to_string()),
+            "ps1" | "psm1" => Some("powershell".to_string()),
+            "graphql" | "graphqls" | "gql" => Some("graphql".to_string()),
+            "proto" => Some("protobuf".to_string()),
+            // `.h` stays C: headers are shared and most are plain C
+            "m" | "mm" => Some("objc".to_string()),
+            "jl" => Some("julia".to_string()),
+            "vue" => Some("vue".to_string()),
+            "html" | "htm" => Some("html".to_string()),
+            "css" => Some("css".to_string()),
+            "scss" => Some("scss".to_string()),
+            "yaml" | "yml" => Some("yaml".to_string()),
+            "toml" => Some("toml".to_string()),
+            "gradle" => Some("gradle".to_string()),
+            "md" | "markdown" | "mdx" => Some("markdown".to_string()),
             "ts" | "tsx" => Some("typescript".to_string()),
             "js" | "jsx" | "mjs" | "cjs" => Some("javascript".to_string()),
             _ => None,
@@ -480,6 +721,7 @@ impl CodeNavigationService {
                         start_column: (col + 1) as u32,
                         end_line: line_number as u32,
                         end_column: (col + 1 + symbol_name.len()) as u32,
+                        qualified_name: None,
                     });
                 }
             }
@@ -505,18 +747,39 @@ impl CodeNavigationService {
             return false;
         }
 
-        // 2. Must be an identifier or type_identifier
+        // 2. Must be one of the language's identifier kinds
         let node_kind = node.kind();
         let valid_kinds = match lang_id {
             "typescript" | "javascript" => {
                 &["identifier", "type_identifier", "property_identifier"][..]
             }
             "go" => &["identifier", "type_identifier", "field_identifier"][..],
+            "swift" => &["simple_identifier", "type_identifier"][..],
+            "ruby" => &["identifier", "constant"][..],
+            "elixir" => &["identifier", "alias"][..],
+            "haskell" => &["variable", "name", "constructor"][..],
+            "ocaml" | "ocaml_interface" => &[
+                "value_name",
+                "type_constructor",
+                "module_name",
+                "constructor_name",
+            ][..],
+            "bash" => &["word", "variable_name"][..],
+            "powershell" => &["function_name", "command_name"][..],
             _ => &["identifier", "type_identifier"][..],
         };
         if !valid_kinds.contains(&node_kind) {
             return false;
         }
+        // Shell arguments are words too; only command and function names refer to a function
+        if lang_id == "bash"
+            && node_kind == "word"
+            && !node
+                .parent()
+                .is_some_and(|p| matches!(p.kind(), "command_name" | "function_definition"))
+        {
+            return false;
+        }
 
         // 3. Exclude if inside string or comment (check ancestors)
         let mut parent = node.parent();
@@ -529,11 +792,21 @@ impl CodeNavigationService {
                 || kind == "string_content"
                 || kind == "interpreted_string_literal"
                 || kind == "raw_string_literal"
+                || kind == "verbatim_string_literal"
+                || kind == "line_string_literal"
+                || kind == "multi_line_string_literal"
+                || kind == "raw_string"
+                || kind == "heredoc_body"
             {
                 return false;
             }
             // Comments
-            if kind == "comment" || kind == "line_comment" || kind == "block_comment" {
+            if kind == "comment"
+                || kind == "line_comment"
+                || kind == "block_comment"
+                || kind == "multiline_comment"
+                || kind == "haddock"
+            {
                 return false;
             }
             parent = p.parent();
@@ -666,25 +939,83 @@ impl CodeNavigationService {
         }
     }
 
+    /// Snapshot of all indexed definitions whose file lives under `root_path`
+    pub fn definitions_under(&self, root_path: &str) -> Vec<SymbolInfo> {
+        self.index
+            .definitions
+            .values()
+            .flatten()
+            .filter(|s| s.file_path.starts_with(root_path))
+            .cloned()
+            .collect()
+    }
+
+    pub fn indexed_file_count(&self) -> usize {
+        self.index.file_definitions.len()
+    }
+
     pub fn clear_all(&mut self) {
         self.index.definitions.clear();
         self.index.file_definitions.clear();
     }
 }
 
+/// Get the tree-sitter language for a language id, if supported
+pub(crate) fn get_language(lang_id: &str) -> Option<Language> {
+    match lang_id {
+        "python" => Some(tree_sitter_python::LANGUAGE.into()),
+        "rust" => Some(tree_sitter_rust::LANGUAGE.into()),
+        "go" => Some(tree_sitter_go::LANGUAGE.into()),
+        "c" => Some(tree_sitter_c::LANGUAGE.into()),
+        "cpp" => Some(tree_sitter_cpp::LANGUAGE.into()),
+        "java" => Some(tree_sitter_java::LANGUAGE.into()),
+        "csharp" => Some(tree_sitter_c_sharp::LANGUAGE.into()),
+        "swift" => Some(tree_sitter_swift::LANGUAGE.into()),
+        "ruby" => Some(tree_sitter_ruby::LANGUAGE.into()),
+        "scala" => Some(tree_sitter_scala::LANGUAGE.into()),
+        "elixir" => Some(tree_sitter_elixir::LANGUAGE.into()),
+        "haskell" => Some(tree_sitter_haskell::LANGUAGE.into()),
+        "ocaml" => Some(tree_sitter_ocaml::LANGUAGE_OCAML.into()),
+        "ocaml_interface" => Some(tree_sitter_ocaml::LANGUAGE_OCAML_INTERFACE.into()),
+        "bash" => Some(tree_sitter_bash::LANGUAGE.into()),
+        "powershell" => Some(tree_sitter_powershell::LANGUAGE.into()),
+        "objc" => Some(tree_sitter_objc::LANGUAGE.into()),
+        "julia" => Some(tree_sitter_julia::LANGUAGE.into()),
+        "typescript" | "javascript" | "tsx" | "jsx" => {
+            Some(tree_sitter_typescript::LANGUAGE_TSX.into())
+        }
+        _ => None,
+    }
+}
+
 // Tauri state wrapper using RwLock for better read concurrency
 pub struct CodeNavState(pub RwLock<CodeNavigationService>);
 
+/// Code navigation service for a command: the workspace's own service when a
+/// workspace handle is given, otherwise the shared one
+pub fn resolve_nav<'a>(
+    state: &'a CodeNavState,
+    workspaces: &WorkspaceState,
+    workspace_id: Option<&str>,
+) -> Result<Scoped<'a, CodeNavigationService>, String> {
+    match workspace_id {
+        Some(id) => Ok(Scoped::Workspace(workspaces.code_nav(id)?)),
+        None => Ok(Scoped::Shared(&state.0)),
+    }
+}
+
 // Tauri commands
 #[tauri::command]
 pub async fn code_nav_index_file(
     state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
     file_path: String,
     content: String,
     lang_id: String,
 ) -> Result<(), String> {
-    let mut service = state
-        .0
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let mut service = nav
         .write()
         .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
     service.index_file(&file_path, &content, &lang_id);
@@ -694,37 +1025,139 @@ pub async fn code_nav_index_file(
 #[tauri::command]
 pub async fn code_nav_find_definition(
     state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
     symbol_name: String,
     lang_family: String,
+    encoding: Option<PositionEncoding>,
+) -> Result<Vec<SymbolInfo>, String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let mut symbols = {
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        service.find_definition(&symbol_name, &lang_family)
+    };
+    if encoding == Some(PositionEncoding::Utf16) {
+        position_encoding::symbols_to_utf16(&mut symbols);
+    }
+    Ok(symbols)
+}
+
+/// Default number of results from `code_nav_search_symbols`
+const SEARCH_SYMBOLS_LIMIT: usize = 50;
+
+/// Filters for `code_nav_search_symbols`; omitted fields take their defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SymbolSearchOptions {
+    /// Only symbols of this language family
+    pub lang_family: Option<String>,
+    pub mode: MatchMode,
+    /// At most this many results; `SEARCH_SYMBOLS_LIMIT` when None
+    pub limit: Option<usize>,
+}
+
+#[tauri::command]
+pub async fn code_nav_search_symbols(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    query: String,
+    options: Option<SymbolSearchOptions>,
+    encoding: Option<PositionEncoding>,
 ) -> Result<Vec<SymbolInfo>, String> {
-    let service = state
-        .0
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let options = options.unwrap_or_default();
+    let mut symbols = {
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        service.search_symbols(
+            &query,
+            options.lang_family.as_deref(),
+            options.mode,
+            options.limit.unwrap_or(SEARCH_SYMBOLS_LIMIT),
+        )
+    };
+    if encoding == Some(PositionEncoding::Utf16) {
+        position_encoding::symbols_to_utf16(&mut symbols);
+    }
+    Ok(symbols)
+}
+
+/// Language of `file_path` with the workspace's overrides applied; None when the
+/// extension is unknown or its language is disabled
+#[tauri::command]
+pub async fn code_nav_get_lang_from_path(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    file_path: String,
+) -> Result<Option<String>, String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let service = nav
         .read()
         .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
-    Ok(service.find_definition(&symbol_name, &lang_family))
+    Ok(service.language_config.lang_id_for_path(&file_path))
+}
+
+/// Re-read languages.json after the user edits it. Files already indexed under a
+/// now-disabled language stay in the index until they are re-indexed or cleared.
+#[tauri::command]
+pub async fn code_nav_load_language_config(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    root_path: String,
+) -> Result<LanguageConfig, String> {
+    let config = language_config::load_for_workspace(&root_path);
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let mut service = nav
+        .write()
+        .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+    service.language_config = config.clone();
+    log::info!(
+        "Loaded language config for {} ({} extension overrides, {} disabled)",
+        root_path,
+        config.extensions.len(),
+        config.disabled.len()
+    );
+    Ok(config)
 }
 
 #[tauri::command]
 pub async fn code_nav_find_references_hybrid(
     state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
     symbol_name: String,
     lang_family: String,
     root_path: String,
+    encoding: Option<PositionEncoding>,
 ) -> Result<Vec<SymbolInfo>, String> {
-    let service = state
-        .0
-        .read()
-        .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
-    Ok(service.find_references_hybrid(&symbol_name, &lang_family, &root_path))
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let mut symbols = {
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        service.find_references_hybrid(&symbol_name, &lang_family, &root_path)
+    };
+    if encoding == Some(PositionEncoding::Utf16) {
+        position_encoding::symbols_to_utf16(&mut symbols);
+    }
+    Ok(symbols)
 }
 
 #[tauri::command]
 pub async fn code_nav_clear_file(
     state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
     file_path: String,
 ) -> Result<(), String> {
-    let mut service = state
-        .0
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let mut service = nav
         .write()
         .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
     service.clear_file(&file_path);
@@ -732,130 +1165,118 @@ pub async fn code_nav_clear_file(
 }
 
 #[tauri::command]
-pub async fn code_nav_clear_all(state: State<'_, CodeNavState>) -> Result<(), String> {
-    let mut service = state
-        .0
+pub async fn code_nav_clear_all(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+) -> Result<(), String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let mut service = nav
         .write()
         .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
     service.clear_all();
     Ok(())
 }
 
-/// Batch index multiple files in parallel (definitions only)
-/// References are searched on-demand via hybrid search
-#[tauri::command]
-pub async fn code_nav_index_files_batch(
-    state: State<'_, CodeNavState>,
-    files: Vec<(String, String, String)>, // (file_path, content, lang_id)
-) -> Result<(), String> {
-    let start = Instant::now();
+/// Parse `source` and extract its definitions; None for unsupported languages or parse failures
+fn extract_definitions(
+    file_path: &str,
+    source: &[u8],
+    lang_id: &str,
+) -> Option<(Vec<SymbolInfo>, HashSet<String>)> {
+    let Some(language) = get_language(lang_id) else {
+        log::warn!(
+            "Unsupported language for indexing: {} (file: {})",
+            lang_id,
+            file_path
+        );
+        return None;
+    };
 
-    // Log files being indexed for debugging
-    for (file_path, _, lang_id) in &files {
-        log::debug!("Batch indexing file: {} (lang: {})", file_path, lang_id);
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        log::error!(
+            "Failed to set language for parser: {} (file: {})",
+            lang_id,
+            file_path
+        );
+        return None;
     }
 
-    // Parallel extraction of definitions
-    let def_results: Vec<(Vec<SymbolInfo>, HashSet<String>, String)> = files
-        .par_iter()
-        .filter_map(|(file_path, content, lang_id)| {
-            let language: Language = match lang_id.as_str() {
-                "python" => tree_sitter_python::LANGUAGE.into(),
-                "rust" => tree_sitter_rust::LANGUAGE.into(),
-                "go" => tree_sitter_go::LANGUAGE.into(),
-                "c" => tree_sitter_c::LANGUAGE.into(),
-                "cpp" => tree_sitter_cpp::LANGUAGE.into(),
-                "java" => tree_sitter_java::LANGUAGE.into(),
-                "typescript" | "javascript" => tree_sitter_typescript::LANGUAGE_TSX.into(),
-                _ => {
-                    log::warn!(
-                        "Unsupported language for indexing: {} (file: {})",
-                        lang_id,
-                        file_path
-                    );
-                    return None;
-                }
-            };
-
-            let mut parser = Parser::new();
-            if parser.set_language(&language).is_err() {
-                log::error!(
-                    "Failed to set language for parser: {} (file: {})",
-                    lang_id,
-                    file_path
-                );
-                return None;
-            }
-
-            let tree = match parser.parse(content, None) {
-                Some(t) => t,
-                None => {
-                    log::error!("Failed to parse file: {}", file_path);
-                    return None;
-                }
-            };
-            let source_bytes = content.as_bytes();
-            let lang_family = CodeNavigationService::get_lang_family(lang_id).to_string();
+    let tree = match large_file::parse_bytes(&mut parser, source) {
+        Some(t) => t,
+        None => {
+            log::error!("Failed to parse file: {}", file_path);
+            return None;
+        }
+    };
+    let lang_family = CodeNavigationService::get_lang_family(lang_id).to_string();
 
-            let def_query_str = CodeNavigationService::get_definition_query(lang_id);
-            let def_query = match Query::new(&language, def_query_str) {
-                Ok(q) => q,
-                Err(e) => {
-                    log::error!("Failed to create query for {}: {:?}", file_path, e);
-                    return None;
-                }
-            };
+    let def_query = match grammar_cache::query(QuerySet::Definitions, lang_id) {
+        Ok(q) => q,
+        Err(e) => {
+            log::error!("Failed to create query for {}: {}", file_path, e);
+            return None;
+        }
+    };
 
-            let mut definitions = Vec::new();
-            let mut defined_names = HashSet::new();
-            {
-                let mut cursor = QueryCursor::new();
-                let mut matches = cursor.matches(&def_query, tree.root_node(), source_bytes);
-                while let Some(m) = matches.next() {
-                    for capture in m.captures {
-                        let node = capture.node;
-                        // Use continue instead of ? to avoid skipping the entire file on one bad capture
-                        let name = match node.utf8_text(source_bytes) {
-                            Ok(text) => text.to_string(),
-                            Err(_) => continue,
-                        };
-                        let capture_name = def_query.capture_names()[capture.index as usize];
-                        let kind = CodeNavigationService::get_symbol_kind(capture_name);
-
-                        definitions.push(SymbolInfo {
-                            name: name.clone(),
-                            kind,
-                            file_path: file_path.clone(),
-                            lang_family: lang_family.clone(),
-                            start_line: node.start_position().row as u32 + 1,
-                            start_column: node.start_position().column as u32 + 1,
-                            end_line: node.end_position().row as u32 + 1,
-                            end_column: node.end_position().column as u32 + 1,
-                        });
-                        defined_names.insert(name);
-                    }
+    let mut definitions = Vec::new();
+    let mut defined_names = HashSet::new();
+    {
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&def_query, tree.root_node(), source);
+        while let Some(m) = matches.next() {
+            for capture in m.captures {
+                let node = capture.node;
+                // Use continue instead of ? to avoid skipping the entire file on one bad capture
+                let raw_name = match node.utf8_text(source) {
+                    Ok(text) => qualified_names::strip_generics(text),
+                    Err(_) => continue,
+                };
+                let capture_name = def_query.capture_names()[capture.index as usize];
+                if is_helper_capture(capture_name) {
+                    continue;
                 }
+                let kind = CodeNavigationService::get_symbol_kind(capture_name);
+                let callable = CaptureKind::from_capture_name(capture_name)
+                    .is_some_and(CaptureKind::is_callable);
+                let qualified_name =
+                    qualified_names::qualified_name(node, source, lang_id, &raw_name, callable);
+                let name = qualified_names::index_key(&raw_name);
+
+                definitions.push(SymbolInfo {
+                    name: name.clone(),
+                    kind,
+                    file_path: file_path.to_string(),
+                    lang_family: lang_family.clone(),
+                    start_line: node.start_position().row as u32 + 1,
+                    start_column: node.start_position().column as u32 + 1,
+                    end_line: node.end_position().row as u32 + 1,
+                    end_column: node.end_position().column as u32 + 1,
+                    qualified_name,
+                });
+                defined_names.insert(name);
             }
+        }
+    }
 
-            log::debug!(
-                "File {} parsed with {} definitions",
-                file_path,
-                definitions.len()
-            );
-            Some((definitions, defined_names, file_path.clone()))
-        })
-        .collect();
-
-    // Merge definitions into the index
-    let mut service = state
-        .0
-        .write()
-        .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+    log::debug!(
+        "File {} parsed with {} definitions",
+        file_path,
+        definitions.len()
+    );
+    Some((definitions, defined_names))
+}
 
+/// Replace the indexed definitions of each parsed file; returns the definition count
+fn merge_definitions(
+    service: &mut CodeNavigationService,
+    def_results: &[(Vec<SymbolInfo>, HashSet<String>, String)],
+) -> usize {
     let mut total_defs = 0;
 
     // Clear files and add definitions
-    for (definitions, defined_names, file_path) in &def_results {
+    for (definitions, defined_names, file_path) in def_results {
         service.clear_file(file_path);
         total_defs += definitions.len();
 
@@ -875,6 +1296,47 @@ pub async fn code_nav_index_files_batch(
                 .push(symbol.clone());
         }
     }
+    total_defs
+}
+
+/// Batch index multiple files in parallel (definitions only)
+/// References are searched on-demand via hybrid search
+#[tauri::command]
+pub async fn code_nav_index_files_batch(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    files: Vec<(String, String, String)>, // (file_path, content, lang_id)
+) -> Result<(), String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let start = Instant::now();
+    let language_config = nav
+        .read()
+        .map_err(|e| format!("Failed to acquire read lock: {}", e))?
+        .language_config
+        .clone();
+
+    // Log files being indexed for debugging
+    for (file_path, _, lang_id) in &files {
+        log::debug!("Batch indexing file: {} (lang: {})", file_path, lang_id);
+    }
+
+    // Parallel extraction of definitions
+    let def_results: Vec<(Vec<SymbolInfo>, HashSet<String>, String)> = files
+        .par_iter()
+        .filter_map(|(file_path, content, lang_id)| {
+            let lang_id = language_config.resolve(file_path, lang_id)?;
+            let (definitions, defined_names) =
+                extract_definitions(file_path, content.as_bytes(), &lang_id)?;
+            Some((definitions, defined_names, file_path.clone()))
+        })
+        .collect();
+
+    // Merge definitions into the index
+    let mut service = nav
+        .write()
+        .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+    let total_defs = merge_definitions(&mut service, &def_results);
 
     let duration = start.elapsed();
     log::info!(
@@ -888,18 +1350,70 @@ pub async fn code_nav_index_files_batch(
     Ok(())
 }
 
-// ============================================================================
-// Index Persistence
-// ============================================================================
+/// Batch index files read from disk. Files at or above `mmap_threshold` bytes are
+/// memory-mapped and parsed in chunks instead of being loaded into a String, which
+/// keeps peak memory flat on repos with generated megafiles.
+#[tauri::command]
+pub async fn code_nav_index_paths(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    files: Vec<(String, String)>, // (file_path, lang_id)
+    mmap_threshold: Option<u64>,
+) -> Result<(), String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let start = Instant::now();
+    let threshold = mmap_threshold.unwrap_or(large_file::DEFAULT_MMAP_THRESHOLD);
+    let language_config = nav
+        .read()
+        .map_err(|e| format!("Failed to acquire read lock: {}", e))?
+        .language_config
+        .clone();
 
-/// Current version of the persisted index format
-/// Version 2: Removed reference indexing (references are now searched on-demand via hybrid search)
-const INDEX_VERSION: u32 = 2;
+    let def_results: Vec<(Vec<SymbolInfo>, HashSet<String>, String)> = files
+        .par_iter()
+        .filter_map(|(file_path, lang_id)| {
+            let lang_id = language_config.resolve(file_path, lang_id)?;
+            let source = match large_file::read_source(file_path, threshold) {
+                Ok(source) => source,
+                Err(e) => {
+                    log::warn!("Failed to read {} for indexing: {}", file_path, e);
+                    return None;
+                }
+            };
+            let (definitions, defined_names) = extract_definitions(file_path, &source, &lang_id)?;
+            Some((definitions, defined_names, file_path.clone()))
+        })
+        .collect();
 
-/// Persisted index data structure (definitions only, references are searched on-demand)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PersistedIndex {
-    pub version: u32,
+    let mut service = nav
+        .write()
+        .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+    let total_defs = merge_definitions(&mut service, &def_results);
+
+    log::info!(
+        "Indexed {} files from disk ({} successfully parsed, {} definitions) in {:.2}ms",
+        files.len(),
+        def_results.len(),
+        total_defs,
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+    Ok(())
+}
+
+// ============================================================================
+// Index Persistence
+// ============================================================================
+
+/// Current version of the persisted index format
+/// Version 2: Removed reference indexing (references are now searched on-demand via hybrid search)
+/// Version 3: Keys drop generic parameters and scope; symbols carry their qualified name
+pub(crate) const INDEX_VERSION: u32 = 3;
+
+/// Persisted index data structure (definitions only, references are searched on-demand)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedIndex {
+    pub version: u32,
     pub root_path: String,
     pub last_updated: i64,
     pub file_timestamps: HashMap<String, i64>,
@@ -920,7 +1434,7 @@ pub struct IndexMetadata {
 }
 
 /// Generate a hash for the project path to use as filename
-fn get_project_hash(root_path: &str) -> String {
+pub(crate) fn get_project_hash(root_path: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(root_path.as_bytes());
     let result = hasher.finalize();
@@ -929,7 +1443,7 @@ fn get_project_hash(root_path: &str) -> String {
 }
 
 /// Get the index directory path
-fn get_index_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+pub(crate) fn get_index_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app_handle
         .path()
         .app_data_dir()
@@ -949,24 +1463,19 @@ fn get_index_path(app_handle: &AppHandle, root_path: &str) -> Result<PathBuf, St
 pub async fn code_nav_save_index(
     app_handle: AppHandle,
     state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
     root_path: String,
     file_timestamps: HashMap<String, i64>,
 ) -> Result<(), String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
     let start = Instant::now();
 
-    let service = state
-        .0
+    let service = nav
         .read()
         .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
 
-    let persisted = PersistedIndex {
-        version: INDEX_VERSION,
-        root_path: root_path.clone(),
-        last_updated: chrono::Utc::now().timestamp(),
-        file_timestamps,
-        definitions: service.index.definitions.clone(),
-        file_definitions: service.index.file_definitions.clone(),
-    };
+    let persisted = service.to_persisted(&root_path, file_timestamps);
 
     // Release the lock before doing I/O
     drop(service);
@@ -978,9 +1487,7 @@ pub async fn code_nav_save_index(
 
     // Serialize and write to file
     let index_path = get_index_path(&app_handle, &root_path)?;
-    let json = serde_json::to_string(&persisted)
-        .map_err(|e| format!("Failed to serialize index: {}", e))?;
-    fs::write(&index_path, json).map_err(|e| format!("Failed to write index file: {}", e))?;
+    index_maintenance::write_index_file(&index_path, &persisted)?;
 
     let duration = start.elapsed();
     log::info!(
@@ -1002,8 +1509,11 @@ pub async fn code_nav_save_index(
 pub async fn code_nav_load_index(
     app_handle: AppHandle,
     state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
     root_path: String,
 ) -> Result<bool, String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
     let start = Instant::now();
 
     let index_path = get_index_path(&app_handle, &root_path)?;
@@ -1013,23 +1523,30 @@ pub async fn code_nav_load_index(
         return Ok(false);
     }
 
-    // Read and deserialize
-    let json =
-        fs::read_to_string(&index_path).map_err(|e| format!("Failed to read index file: {}", e))?;
-    let persisted: PersistedIndex =
-        serde_json::from_str(&json).map_err(|e| format!("Failed to deserialize index: {}", e))?;
-
-    // Check version compatibility
-    if persisted.version != INDEX_VERSION {
-        log::warn!(
-            "Index version mismatch: expected {}, got {}. Rebuilding index.",
-            INDEX_VERSION,
-            persisted.version
-        );
-        // Delete outdated index file
-        let _ = fs::remove_file(&index_path);
-        return Ok(false);
-    }
+    // Read, migrating older formats; corrupt files are deleted so the index is rebuilt
+    let persisted = match index_maintenance::read_index_file(&index_path)? {
+        IndexFile::Valid {
+            index,
+            migrated_from,
+        } => {
+            if let Some(version) = migrated_from {
+                log::info!("Migrated index for {} from version {}", root_path, version);
+                if let Err(e) = index_maintenance::write_index_file(&index_path, &index) {
+                    log::warn!("Failed to save migrated index: {}", e);
+                }
+            }
+            index
+        }
+        IndexFile::Corrupt(reason) => {
+            log::warn!(
+                "Corrupt index for {} ({}). Rebuilding index.",
+                root_path,
+                reason
+            );
+            let _ = fs::remove_file(&index_path);
+            return Ok(false);
+        }
+    };
 
     // Verify root path matches
     if persisted.root_path != root_path {
@@ -1038,8 +1555,7 @@ pub async fn code_nav_load_index(
     }
 
     // Load into service
-    let mut service = state
-        .0
+    let mut service = nav
         .write()
         .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
 
@@ -1113,9 +1629,11 @@ pub async fn code_nav_delete_index(app_handle: AppHandle, root_path: String) ->
 #[tauri::command]
 pub async fn code_nav_get_indexed_files(
     state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
 ) -> Result<Vec<String>, String> {
-    let service = state
-        .0
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let service = nav
         .read()
         .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
 
@@ -1133,6 +1651,32 @@ pub struct CodeSummary {
     pub summary: String,
     pub original_lines: usize,
     pub lang_id: String,
+    /// Why the summary fell back to an outline, if it did
+    pub truncated: Option<String>,
+    /// Why the content was returned unsummarized, if it was
+    pub skipped_reason: Option<String>,
+    /// Definitions left out to stay within `SummaryOptions::symbol_budget`
+    pub omitted_symbols: Vec<String>,
+}
+
+impl CodeSummary {
+    /// The original content, returned with the reason it was not summarized
+    pub fn unchanged(
+        content: String,
+        original_lines: usize,
+        lang_id: String,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            success: false,
+            summary: content,
+            original_lines,
+            lang_id,
+            truncated: None,
+            skipped_reason: Some(reason.into()),
+            omitted_symbols: Vec::new(),
+        }
+    }
 }
 
 /// Summarize code content using tree-sitter to extract only signatures and key definitions.
@@ -1143,31 +1687,136 @@ pub struct CodeSummary {
 /// - Class/struct/interface/enum definitions (fields only, not method bodies)
 /// - Type aliases
 /// - Top-level constants
+///
+/// The output is deterministic: the same content and language always produce the same
+/// summary, independent of platform or locale. Entries appear in source order (outer
+/// definitions before nested ones that start at the same position), and lines are joined
+/// with `\n` regardless of the input's line endings. Caches and snapshot tests rely on this.
 #[tauri::command]
 pub async fn summarize_code_content(
     content: String,
     lang_id: String,
     file_path: String,
+    options: Option<SummaryOptions>,
+) -> Result<CodeSummary, String> {
+    let options = options
+        .unwrap_or_default()
+        .with_configured_policies(&lang_id);
+    summarize_source(content, lang_id, file_path, &options)
+}
+
+/// One summary entry and the original lines it stands in for. Lines are 1-based and
+/// inclusive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummarySegment {
+    pub kind: String,
+    /// Declared name, empty when the definition has none
+    pub name: String,
+    pub original_start_line: usize,
+    pub original_end_line: usize,
+    pub summary_start_line: usize,
+    pub summary_end_line: usize,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryPreview {
+    pub path: String,
+    pub summary: CodeSummary,
+    /// In summary order
+    pub segments: Vec<SummarySegment>,
+}
+
+/// Dry run of the summarizer on a file: the summary plus, for each entry, the original
+/// range it replaces, so the result can be reviewed side by side before it is trusted
+#[tauri::command]
+pub async fn preview_summary(
+    path: String,
+    options: Option<SummaryOptions>,
+) -> Result<SummaryPreview, String> {
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&path)
+        .ok_or_else(|| format!("Unsupported file type: {}", path))?;
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let options = options
+        .unwrap_or_default()
+        .with_configured_policies(&lang_id);
+    let (summary, segments) = summarize_with_segments(content, lang_id, path.clone(), &options)?;
+    Ok(SummaryPreview {
+        path,
+        summary,
+        segments,
+    })
+}
+
+/// Synchronous core of `summarize_code_content`, for callers that batch or run it off
+/// the async runtime
+pub fn summarize_source(
+    content: String,
+    lang_id: String,
+    file_path: String,
+    options: &SummaryOptions,
 ) -> Result<CodeSummary, String> {
+    summarize_with_segments(content, lang_id, file_path, options).map(|(summary, _)| summary)
+}
+
+/// Summarize and report which original line range each summary entry replaced.
+/// Content returned unchanged (or trimmed as a declaration file) has no segments.
+pub fn summarize_with_segments(
+    content: String,
+    lang_id: String,
+    file_path: String,
+    options: &SummaryOptions,
+) -> Result<(CodeSummary, Vec<SummarySegment>), String> {
+    // CRLF would leave `\r` in captured text and signatures
+    let content = line_endings::normalize(&content);
     let original_lines = content.lines().count();
 
+    // Markup is reduced to its element tree
+    if lang_id == "html" {
+        return Ok((html_outline::summarize_html(&content)?, Vec::new()));
+    }
+    // Stylesheets are reduced to their selectors
+    if lang_id == "css" || lang_id == "scss" {
+        return Ok((css_outline::summarize_css(&content, &lang_id)?, Vec::new()));
+    }
+    // Configuration files are reduced to their key hierarchy
+    if lang_id == "yaml" {
+        return Ok((yaml_outline::summarize_yaml(&content)?, Vec::new()));
+    }
+    if lang_id == "toml" {
+        return Ok((toml_outline::summarize_toml(&content)?, Vec::new()));
+    }
+    // Build scripts are reduced to their plugins, dependencies and tasks
+    if lang_id == "gradle" {
+        return Ok((gradle_outline::summarize_gradle(&content), Vec::new()));
+    }
+    // Schemas keep their types and fields, operations their signatures
+    if lang_id == "graphql" {
+        return Ok((graphql_outline::summarize_graphql(&content), Vec::new()));
+    }
+    // Protocol definitions keep their messages, enums and services
+    if lang_id == "protobuf" {
+        return Ok((protobuf_outline::summarize_protobuf(&content), Vec::new()));
+    }
+    // Documents keep their headings and the sentence that opens each section
+    if lang_id == "markdown" {
+        return Ok((markdown_outline::summarize_markdown(&content), Vec::new()));
+    }
+    // Single-file components mix languages; their blocks are summarized separately
+    if lang_id == "vue" {
+        return Ok((
+            vue_sfc::summarize_vue(&content, &file_path, options)?,
+            Vec::new(),
+        ));
+    }
+
     // Get language, return unsupported error if language is not recognized
-    let language: Language = match lang_id.as_str() {
-        "python" => tree_sitter_python::LANGUAGE.into(),
-        "rust" => tree_sitter_rust::LANGUAGE.into(),
-        "go" => tree_sitter_go::LANGUAGE.into(),
-        "c" => tree_sitter_c::LANGUAGE.into(),
-        "cpp" => tree_sitter_cpp::LANGUAGE.into(),
-        "java" => tree_sitter_java::LANGUAGE.into(),
-        "typescript" | "javascript" | "tsx" | "jsx" => tree_sitter_typescript::LANGUAGE_TSX.into(),
-        _ => {
-            return Ok(CodeSummary {
-                success: false,
-                summary: content, // Return original for unsupported languages
-                original_lines,
-                lang_id,
-            });
-        }
+    let Some(language) = get_language(&lang_id) else {
+        return Ok((
+            CodeSummary::unchanged(content, original_lines, lang_id, "unsupported language"),
+            Vec::new(),
+        ));
     };
 
     let mut parser = Parser::new();
@@ -1185,28 +1834,99 @@ pub async fn summarize_code_content(
     // Get the summarization query for this language
     let query_str = get_summarization_query(&lang_id);
     if query_str.is_empty() {
-        return Ok(CodeSummary {
-            success: false,
-            summary: content,
-            original_lines,
-            lang_id,
-        });
+        return Ok((
+            CodeSummary::unchanged(
+                content,
+                original_lines,
+                lang_id,
+                "no summarization query for language",
+            ),
+            Vec::new(),
+        ));
     }
 
-    let query = match Query::new(&language, query_str) {
-        Ok(q) => q,
-        Err(e) => return Err(format!("Failed to create summarization query: {:?}", e)),
-    };
+    // Declaration files are the types themselves; keep them except for the parts that
+    // are noise
+    if declaration_files::is_declaration_file(&file_path) {
+        let max_doc_lines = options
+            .max_doc_lines
+            .unwrap_or(declaration_files::DTS_DEFAULT_MAX_DOC_LINES);
+        let trimmed = declaration_files::summarize_declaration_file(&tree, &content, max_doc_lines);
+        let summary = match trimmed {
+            Some(trimmed) => CodeSummary {
+                success: true,
+                summary: format!(
+                    "[COMPRESSED: Original {} lines → Declaration file, long docs and nested namespaces trimmed]\n\n{}",
+                    original_lines, trimmed
+                ),
+                original_lines,
+                lang_id,
+                truncated: None,
+                skipped_reason: None,
+                omitted_symbols: Vec::new(),
+            },
+            None => CodeSummary::unchanged(
+                content,
+                original_lines,
+                lang_id,
+                "declaration file has nothing to trim",
+            ),
+        };
+        return Ok((summary, Vec::new()));
+    }
+
+    // Headers and interface-only modules are already close to what a summary would
+    // keep; compressing them loses type details for little gain
+    if options.min_body_ratio > 0.0 {
+        let ratio = body_line_ratio(&tree, &content);
+        if ratio < options.min_body_ratio {
+            return Ok((
+                CodeSummary::unchanged(
+                    content,
+                    original_lines,
+                    lang_id,
+                    format!(
+                        "already compact: {:.0}% of code lines are function bodies",
+                        ratio * 100.0
+                    ),
+                ),
+                Vec::new(),
+            ));
+        }
+    }
+
+    let query = grammar_cache::query(QuerySet::Summarization, &lang_id)
+        .map_err(|e| format!("Failed to create summarization query: {}", e))?;
+
+    // Generated code can nest thousands of levels deep or define tens of thousands of
+    // symbols; past the limits only an outline is produced
+    let mut truncated = tree_depth_exceeds(&tree, options.max_depth).then(|| {
+        format!(
+            "nesting deeper than {} levels was not summarized",
+            options.max_depth
+        )
+    });
 
     // Collect all captured ranges with their types
     let mut captures: Vec<CapturedSymbol> = Vec::new();
     let mut cursor = QueryCursor::new();
+    cursor.set_max_start_depth(Some(options.max_depth));
     let mut matches = cursor.matches(&query, tree.root_node(), source_bytes);
 
-    while let Some(m) = matches.next() {
+    'matches: while let Some(m) = matches.next() {
         for capture in m.captures {
+            if captures.len() >= options.max_captures {
+                truncated = Some(format!(
+                    "stopped after {} definitions",
+                    options.max_captures
+                ));
+                break 'matches;
+            }
             let node = capture.node;
             let capture_name = query.capture_names()[capture.index as usize];
+            if is_helper_capture(capture_name) {
+                continue;
+            }
 
             // Get the full node text (for definitions, this includes the whole signature)
             let text = match node.utf8_text(source_bytes) {
@@ -1216,37 +1936,352 @@ pub async fn summarize_code_content(
 
             captures.push(CapturedSymbol {
                 kind: capture_name.to_string(),
+                name: definition_name(node, source_bytes),
                 text,
                 start_line: node.start_position().row,
+                end_line: node.end_position().row,
                 start_byte: node.start_byte(),
+                end_byte: node.end_byte(),
+                exported: node.kind() == "export_statement"
+                    || node
+                        .parent()
+                        .is_some_and(|parent| parent.kind() == "export_statement"),
             });
         }
     }
 
-    // Sort by start position
-    captures.sort_by_key(|c| c.start_byte);
+    sort_captures(&mut captures);
+    captures.retain(|c| options.policy(&c.kind) != Some(KindPolicy::Drop));
 
-    // Build summary from captures
-    let summary = build_summary(&content, &captures, &lang_id, original_lines);
+    // Over budget, drop the definitions least likely to be needed first
+    let mut omitted_symbols = Vec::new();
+    if let Some(budget) = options.symbol_budget {
+        let candidates: Vec<RetentionCandidate> = captures
+            .iter()
+            .map(|c| RetentionCandidate {
+                kind: &c.kind,
+                name: &c.name,
+                text: &c.text,
+                exported: c.exported,
+            })
+            .collect();
+        let keep = select_within_budget(&lang_id, &candidates, budget, &options.context_symbols);
+        if keep.len() < captures.len() {
+            let mut keep = keep.into_iter().peekable();
+            let mut kept = Vec::with_capacity(budget);
+            for (index, capture) in captures.into_iter().enumerate() {
+                if keep.next_if_eq(&index).is_some() {
+                    kept.push(capture);
+                } else if capture.name.is_empty() {
+                    omitted_symbols.push(capture.text.lines().next().unwrap_or("").to_string());
+                } else {
+                    omitted_symbols.push(capture.name);
+                }
+            }
+            captures = kept;
+        }
+    }
 
-    Ok(CodeSummary {
-        success: true,
-        summary,
+    // Build summary from captures
+    let (summary, segments) = build_summary(
+        &content,
+        &captures,
+        &lang_id,
         original_lines,
-        lang_id,
-    })
+        truncated.as_deref(),
+        omitted_symbols.len(),
+        options,
+    );
+    if let Some(reason) = &truncated {
+        log::warn!("Summary of {} truncated: {}", file_path, reason);
+    }
+
+    Ok((
+        CodeSummary {
+            success: true,
+            summary,
+            original_lines,
+            lang_id,
+            truncated,
+            skipped_reason: None,
+            omitted_symbols,
+        },
+        segments,
+    ))
+}
+
+/// Per-call summarization options; omitted fields take their defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummaryOptions {
+    /// Stop collecting definitions after this many captures
+    pub max_captures: usize,
+    /// Definitions nested deeper than this many syntax levels are skipped
+    pub max_depth: u32,
+    /// Keep the doc comment above each definition
+    pub include_doc_comments: bool,
+    /// Cut doc comments longer than this many lines; license headers repeated on every
+    /// function would otherwise outweigh the signatures
+    pub max_doc_lines: Option<usize>,
+    /// Files where function bodies make up less than this share of the code lines are
+    /// returned as-is; 0 (the default) disables the check. Context compaction opts in.
+    pub min_body_ratio: f64,
+    /// Keep at most this many definitions, dropping private helpers first
+    pub symbol_budget: Option<usize>,
+    /// Names the current task refers to; kept over everything else under a budget
+    pub context_symbols: Vec<String>,
+    /// Per-kind overrides of the built-in summarization; when None, commands load the
+    /// configured policies for `root_path`
+    pub policies: Option<HashMap<CaptureKind, KindPolicy>>,
+    /// Project whose `.talkcody/summary-policies.json` applies
+    pub root_path: Option<String>,
+}
+
+impl SummaryOptions {
+    /// Fill in `policies` from the policy files unless the caller gave them
+    pub fn with_configured_policies(mut self, lang_id: &str) -> Self {
+        if self.policies.is_none() {
+            self.policies = Some(summary_policy::configured_policies(
+                self.root_path.as_deref(),
+                lang_id,
+            ));
+        }
+        self
+    }
+
+    fn policy(&self, capture_name: &str) -> Option<KindPolicy> {
+        let kind = CaptureKind::from_capture_name(capture_name)?;
+        self.policies.as_ref()?.get(&kind).copied()
+    }
+}
+
+impl Default for SummaryOptions {
+    fn default() -> Self {
+        Self {
+            max_captures: 5000,
+            max_depth: 64,
+            include_doc_comments: true,
+            max_doc_lines: None,
+            min_body_ratio: 0.0,
+            symbol_budget: None,
+            context_symbols: Vec::new(),
+            policies: None,
+            root_path: None,
+        }
+    }
+}
+
+/// Share of code lines (non-blank, non-comment) inside function bodies, i.e. the lines
+/// a summary would drop
+fn body_line_ratio(tree: &Tree, content: &str) -> f64 {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut comment = vec![false; lines.len()];
+    let mut body = vec![false; lines.len()];
+    let mark = |rows: &mut [bool], from: usize, to: usize| {
+        for row in rows.iter_mut().take(to + 1).skip(from) {
+            *row = true;
+        }
+    };
+
+    let mut cursor = tree.walk();
+    'walk: loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        let mut descend = true;
+        if kind.contains("comment") {
+            mark(
+                &mut comment,
+                node.start_position().row,
+                node.end_position().row,
+            );
+        } else if kind.contains("function")
+            || kind.contains("method")
+            || kind.contains("constructor")
+            || kind == "let_binding"
+        {
+            // PowerShell function and Objective-C method bodies have no field name
+            let body_block = node.child_by_field_name("body").or_else(|| {
+                let mut cursor = node.walk();
+                let block = node
+                    .named_children(&mut cursor)
+                    .find(|child| matches!(child.kind(), "script_block" | "compound_statement"));
+                block
+            });
+            if let Some(block) = body_block {
+                // The signature row stays in the summary
+                let from = block
+                    .start_position()
+                    .row
+                    .max(node.start_position().row + 1);
+                mark(&mut body, from, block.end_position().row);
+                // Nested definitions are already covered
+                descend = false;
+            } else if kind == "function" {
+                // A Haskell equation has no body field; its type signature is a
+                // separate node, so the whole equation is elided
+                mark(
+                    &mut body,
+                    node.start_position().row,
+                    node.end_position().row,
+                );
+                descend = false;
+            } else if node
+                .child(0)
+                .is_some_and(|keyword| keyword.kind() == "function")
+            {
+                // Julia bodies are the statements between the signature and `end`
+                mark(
+                    &mut body,
+                    node.start_position().row + 1,
+                    node.end_position().row,
+                );
+                descend = false;
+            }
+        } else if let Some(block) = elixir_def_body(node, content.as_bytes()) {
+            let from = block
+                .start_position()
+                .row
+                .max(node.start_position().row + 1);
+            mark(&mut body, from, block.end_position().row);
+            descend = false;
+        } else if kind == "case_item" {
+            // Shell case items are summarized to their pattern line
+            mark(
+                &mut body,
+                node.start_position().row + 1,
+                node.end_position().row,
+            );
+            descend = false;
+        }
+
+        if descend && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
+        }
+    }
+
+    let mut code_lines = 0;
+    let mut body_lines = 0;
+    for (row, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() || comment[row] {
+            continue;
+        }
+        code_lines += 1;
+        if body[row] {
+            body_lines += 1;
+        }
+    }
+    if code_lines == 0 {
+        0.0
+    } else {
+        body_lines as f64 / code_lines as f64
+    }
+}
+
+/// Elixir keywords that define a function, macro or guard
+const ELIXIR_DEF_KEYWORDS: &[&str] = &[
+    "def",
+    "defp",
+    "defmacro",
+    "defmacrop",
+    "defguard",
+    "defguardp",
+    "defdelegate",
+];
+
+/// The `do ... end` block of an Elixir def, which the grammar parses as a plain call
+fn elixir_def_body<'a>(node: Node<'a>, source: &[u8]) -> Option<Node<'a>> {
+    if node.kind() != "call" {
+        return None;
+    }
+    let keyword = node.child_by_field_name("target")?.utf8_text(source).ok()?;
+    if !ELIXIR_DEF_KEYWORDS.contains(&keyword) {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let block = node
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "do_block");
+    block
+}
+
+/// Whether any node in `tree` is nested deeper than `limit`; stops at the first one
+fn tree_depth_exceeds(tree: &Tree, limit: u32) -> bool {
+    let mut cursor = tree.walk();
+    let mut depth = 0u32;
+    loop {
+        if depth > limit {
+            return true;
+        }
+        if cursor.goto_first_child() {
+            depth += 1;
+            continue;
+        }
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            if !cursor.goto_parent() {
+                return false;
+            }
+            depth -= 1;
+        }
+    }
 }
 
 #[derive(Debug)]
 struct CapturedSymbol {
     kind: String,
+    /// Declared name, empty when the node has no recognizable name field
+    name: String,
     text: String,
     start_line: usize,
+    end_line: usize,
     start_byte: usize,
+    end_byte: usize,
+    /// Wrapped in a JS/TS `export` statement
+    exported: bool,
+}
+
+/// Name of a definition node: its `name` field, the target of an assignment, or the
+/// name of its first declarator/spec (`const x = ...`, Go `type T struct`)
+fn definition_name(node: Node, source: &[u8]) -> String {
+    if let Some(declaration) = node.child_by_field_name("declaration") {
+        // `export <declaration>`
+        return definition_name(declaration, source);
+    }
+    node.child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("left"))
+        .or_else(|| {
+            node.named_child(0)
+                .and_then(|child| child.child_by_field_name("name"))
+        })
+        .and_then(|name| name.utf8_text(source).ok())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Order captures by position with tie-breakers so the summary never depends on
+/// query match order: outer nodes first, then by capture name. Exact duplicates
+/// (same range and kind from overlapping patterns) are dropped.
+fn sort_captures(captures: &mut Vec<CapturedSymbol>) {
+    captures.sort_by(|a, b| {
+        a.start_byte
+            .cmp(&b.start_byte)
+            .then_with(|| b.end_byte.cmp(&a.end_byte))
+            .then_with(|| a.kind.cmp(&b.kind))
+    });
+    captures.dedup_by(|a, b| {
+        a.start_byte == b.start_byte && a.end_byte == b.end_byte && a.kind == b.kind
+    });
 }
 
 /// Get tree-sitter query for extracting code signatures and definitions
-fn get_summarization_query(lang_id: &str) -> &'static str {
+pub(crate) fn get_summarization_query(lang_id: &str) -> &'static str {
     match lang_id {
         "typescript" | "javascript" | "tsx" | "jsx" => {
             r#"
@@ -1360,1230 +2395,4635 @@ fn get_summarization_query(lang_id: &str) -> &'static str {
             (field_declaration) @field
             "#
         }
-        "c" | "cpp" => {
+        "csharp" => {
             r#"
-            ; Function definitions
-            (function_definition) @function
+            ; Class and record declarations (records may have no body)
+            (class_declaration) @class
+            (record_declaration) @class
 
-            ; Struct specifiers
-            (struct_specifier) @struct
+            ; Interface declarations
+            (interface_declaration) @interface
 
-            ; Class specifiers (C++)
-            (class_specifier) @class
+            ; Struct declarations
+            (struct_declaration) @struct
 
-            ; Enum specifiers
-            (enum_specifier) @enum
+            ; Enum declarations
+            (enum_declaration) @enum
 
-            ; Type definitions
-            (type_definition) @typedef
+            ; Methods and constructors
+            (method_declaration) @method
+            (constructor_declaration) @method
+
+            ; Properties are summarized like fields
+            (property_declaration) @field
             "#
         }
-        _ => "",
-    }
-}
-
-/// Build a human-readable summary from captured symbols
-fn build_summary(
-    content: &str,
-    captures: &[CapturedSymbol],
-    lang_id: &str,
-    original_lines: usize,
-) -> String {
-    let mut result = format!(
-        "[COMPRESSED: Original {} lines → Summarized using tree-sitter]\n\n",
-        original_lines
-    );
+        "swift" => {
+            r#"
+            ; Classes, structs, enums and extensions share one node kind
+            (class_declaration declaration_kind: "class") @class
+            (class_declaration declaration_kind: "actor") @class
+            (class_declaration declaration_kind: "struct") @struct
+            (class_declaration declaration_kind: "enum") @enum
+            (class_declaration declaration_kind: "extension") @impl
 
-    let lines: Vec<&str> = content.lines().collect();
+            ; Protocols
+            (protocol_declaration) @interface
 
-    for capture in captures {
-        // Get the captured text
-        let text = &capture.text;
+            ; Functions and methods
+            (function_declaration) @function
+            (init_declaration) @method
+            "#
+        }
+        "ruby" => {
+            r#"
+            ; Classes and modules
+            (class) @class
+            (module) @class
 
-        // For function/method bodies, we want to show only the signature
-        let summarized = match capture.kind.as_str() {
-            "function" | "method" | "arrow_function" => extract_function_signature(text, lang_id),
-            "class" => extract_class_summary(text, lang_id),
-            "impl" => extract_impl_summary(text),
-            "interface" | "type_alias" | "enum" | "struct" | "trait" | "type_decl" => {
-                // For types, keep as-is (they're usually not too long)
-                // But limit to reasonable size
-                limit_text(text, 30)
-            }
-            "const" | "static" | "const_decl" | "var" | "field" | "assignment" | "typedef" => {
-                // For constants, keep the first line
-                text.lines().next().unwrap_or(text).to_string()
-            }
-            _ => text.clone(),
-        };
+            ; Instance and singleton (`def self.x`) methods
+            (method) @method
+            (singleton_method) @method
 
-        // Add doc comment if available (look at lines before start_line)
-        let doc_comment = extract_doc_comment(&lines, capture.start_line, lang_id);
-        if !doc_comment.is_empty() {
-            result.push_str(&doc_comment);
-            result.push('\n');
+            ; Constants
+            (assignment left: (constant)) @const
+            "#
+        }
+        "scala" => {
+            r#"
+            ; Case classes are the data model and are kept whole; other classes are
+            ; summarized like any class
+            ((class_definition) @struct
+             (#match? @struct "^(@[^\\s(]+(\\([^)]*\\))?\\s+|[a-z]+\\s+)*case\\s"))
+            ((class_definition) @class
+             (#not-match? @class "^(@[^\\s(]+(\\([^)]*\\))?\\s+|[a-z]+\\s+)*case\\s"))
+
+            ; Objects and traits
+            (object_definition) @class
+            (trait_definition) @trait
+
+            ; Defs, at the top level or inside a template
+            (function_definition) @function
+            "#
         }
+        "elixir" => {
+            r#"
+            ; Modules, protocols and implementations; everything in Elixir is a call
+            ((call target: (identifier) @_keyword) @class
+             (#any-of? @_keyword "defmodule" "defprotocol" "defimpl"))
 
-        result.push_str(&summarized);
-        result.push_str("\n\n");
-    }
+            ; Functions, macros and guards, public and private
+            ((call target: (identifier) @_keyword) @function
+             (#any-of? @_keyword "def" "defp" "defmacro" "defmacrop" "defguard" "defguardp" "defdelegate"))
 
-    result.trim_end().to_string()
-}
+            ; Module attributes; docs are shown with the definition they document
+            ((unary_operator operator: "@" operand: (call target: (identifier) @_attribute)) @const
+             (#not-any-of? @_attribute "moduledoc" "doc" "typedoc"))
+            "#
+        }
+        "haskell" => {
+            r#"
+            ; Top-level type signatures; equations are not captured, so they are elided
+            (declarations (signature) @function)
 
-/// Extract function signature without body
-fn extract_function_signature(text: &str, lang_id: &str) -> String {
-    match lang_id {
-        "typescript" | "javascript" | "tsx" | "jsx" => {
-            // Find the opening brace and truncate
-            if let Some(pos) = text.find('{') {
-                let sig = text[..pos].trim();
-                format!("{} {{ ... }}", sig)
-            } else if let Some(pos) = text.find("=>") {
-                // Arrow function
-                let sig = text[..pos + 2].trim();
-                format!("{} {{ ... }}", sig)
-            } else {
-                text.lines().next().unwrap_or(text).to_string()
-            }
+            ; Data types, newtypes and synonyms
+            (data_type) @struct
+            (newtype) @struct
+            (type_synomym) @type_alias
+
+            ; Type classes and instances
+            (class) @trait
+            (instance) @impl
+            "#
         }
-        "python" => {
-            // Find the colon and truncate
-            if let Some(pos) = text.find(':') {
-                let sig = text[..pos + 1].trim();
-                format!("{}\n    ...", sig)
-            } else {
-                text.lines().next().unwrap_or(text).to_string()
-            }
-        }
-        "rust" => {
-            // Find the opening brace and truncate
-            if let Some(pos) = text.find('{') {
-                let sig = text[..pos].trim();
-                format!("{} {{ ... }}", sig)
-            } else {
-                text.lines().next().unwrap_or(text).to_string()
-            }
-        }
-        "go" => {
-            // Find the opening brace
-            if let Some(pos) = text.find('{') {
-                let sig = text[..pos].trim();
-                format!("{} {{ ... }}", sig)
-            } else {
-                text.lines().next().unwrap_or(text).to_string()
-            }
+        "ocaml" => {
+            r#"
+            ; Top-level and module-level let bindings
+            (compilation_unit (value_definition) @function)
+            (structure (value_definition) @function)
+
+            ; Modules and functors; their items follow as separate captures
+            (compilation_unit (module_definition) @class)
+            (structure (module_definition) @class)
+
+            ; Module types are signatures, kept whole
+            (compilation_unit (module_type_definition) @interface)
+            (structure (module_type_definition) @interface)
+
+            ; Types and exceptions
+            (compilation_unit (type_definition) @type_alias)
+            (structure (type_definition) @type_alias)
+            (compilation_unit (exception_definition) @type_alias)
+            (structure (exception_definition) @type_alias)
+            "#
         }
-        "java" | "c" | "cpp" => {
-            // Find the opening brace
-            if let Some(pos) = text.find('{') {
-                let sig = text[..pos].trim();
-                format!("{} {{ ... }}", sig)
-            } else {
-                text.lines().next().unwrap_or(text).to_string()
-            }
+        "ocaml_interface" => {
+            r#"
+            ; An interface file is all signatures; every item is kept verbatim
+            (compilation_unit (value_specification) @function)
+            (compilation_unit (external) @function)
+            (compilation_unit (type_definition) @type_alias)
+            (compilation_unit (exception_definition) @type_alias)
+            (compilation_unit (module_definition) @class)
+            (compilation_unit (module_type_definition) @interface)
+            "#
         }
-        _ => text.lines().next().unwrap_or(text).to_string(),
-    }
-}
-
-/// Extract class summary - signature + field names + method signatures
-fn extract_class_summary(text: &str, lang_id: &str) -> String {
-    let lines: Vec<&str> = text.lines().collect();
-    if lines.is_empty() {
-        return text.to_string();
-    }
-
-    let mut result = Vec::new();
+        "bash" => {
+            r#"
+            (function_definition) @function
 
-    match lang_id {
-        "typescript" | "javascript" | "tsx" | "jsx" => {
-            result.push(lines[0].to_string());
+            ; Top-level settings: assignments, `export`, `readonly`, `declare`
+            (program (variable_assignment) @variable)
+            (program (declaration_command) @variable)
 
-            // Detect the indentation level of class members (first non-empty line after class declaration)
-            let member_indent = lines
-                .iter()
-                .skip(1)
-                .find(|l| {
-                    !l.trim().is_empty()
-                        && !l.trim().starts_with("//")
-                        && !l.trim().starts_with("*")
-                })
-                .map(|l| l.len() - l.trim_start().len())
-                .unwrap_or(2);
+            ; Argument parsing: `case` blocks and `getopts` loops
+            (while_statement
+              condition: (command name: (command_name) @_command (#eq? @_command "getopts"))) @enum
+            (case_statement) @enum
+            "#
+        }
+        "powershell" => {
+            r#"
+            ; Functions, filters and workflows
+            (function_statement) @function
 
-            for line in lines.iter().skip(1) {
-                let trimmed = line.trim();
-                let current_indent = line.len() - line.trim_start().len();
+            ; Script parameters, with their [CmdletBinding()] attribute
+            (program (param_block) @variable)
+            "#
+        }
+        "objc" => {
+            r#"
+            ; Interfaces, categories and protocols: properties and method declarations
+            (class_interface) @interface
+            (category_interface) @interface
+            (protocol_declaration) @interface
+
+            ; Implementations keep their method signatures
+            (class_implementation) @class
+            (category_implementation) @class
+
+            ; C functions and types
+            (translation_unit (function_definition) @function)
+            (translation_unit (struct_specifier) @struct)
+            (translation_unit (enum_specifier) @enum)
+            (translation_unit (type_definition) @typedef)
+            "#
+        }
+        "julia" => {
+            r#"
+            ; Modules keep their imports and exports
+            (module_definition) @class
 
-                // Only consider lines at the class member indentation level
-                if current_indent != member_indent || trimmed.is_empty() {
-                    continue;
-                }
+            ; Functions and macros; an assignment to a call is a short-form definition
+            (function_definition) @function
+            (macro_definition) @function
+            (assignment . (call_expression)) @function
 
-                // Check if it's a class member (field, method, constructor, decorator)
-                let is_member = trimmed.starts_with("private ")
-                    || trimmed.starts_with("public ")
-                    || trimmed.starts_with("protected ")
-                    || trimmed.starts_with("readonly ")
-                    || trimmed.starts_with("static ")
-                    || trimmed.starts_with("constructor")
-                    || trimmed.starts_with("async ")
-                    || trimmed.starts_with("@") // decorators
-                    || trimmed.starts_with("get ")
-                    || trimmed.starts_with("set ")
-                    // Method without access modifier (must have parentheses and be followed by { or :)
-                    || (trimmed.contains('(')
-                        && (trimmed.contains(") {") || trimmed.contains("): ")));
+            ; Types; struct fields are the data model
+            (struct_definition) @struct
+            (abstract_definition) @type_alias
+            (primitive_definition) @type_alias
 
-                if is_member {
-                    if let Some(brace_pos) = line.find('{') {
-                        result.push(format!("{}{{ ... }}", &line[..brace_pos]));
-                    } else {
-                        result.push(line.to_string());
-                    }
-                }
-            }
-            result.push("}".to_string());
+            ; Constants, which Julia only allows at global scope
+            (const_statement) @const
+            "#
         }
-        "python" => {
-            // Class definition line
-            result.push(lines[0].to_string());
+        "c" => {
+            r#"
+            ; Function definitions
+            (function_definition) @function
 
-            for line in lines.iter().skip(1) {
-                let trimmed = line.trim();
-                // Include def lines (methods)
-                if trimmed.starts_with("def ") || trimmed.starts_with("async def ") {
-                    if let Some(colon_pos) = line.find(':') {
-                        result.push(format!("{}:\n        ...", &line[..colon_pos]));
-                    } else {
-                        result.push(line.to_string());
-                    }
-                } else if trimmed.starts_with("self.") && trimmed.contains('=') {
-                    // Field assignment in __init__
-                    result.push(format!("    {}", trimmed));
-                }
-            }
-        }
-        "java" => {
-            result.push(lines[0].to_string());
+            ; Struct specifiers
+            (struct_specifier) @struct
 
-            for line in lines.iter().skip(1) {
-                let trimmed = line.trim();
-                // Include field and method declarations
-                if trimmed.starts_with("private ")
-                    || trimmed.starts_with("public ")
-                    || trimmed.starts_with("protected ")
-                    || trimmed.starts_with("static ")
-                    || trimmed.starts_with("final ")
-                {
-                    if let Some(brace_pos) = line.find('{') {
-                        result.push(format!("{}{{ ... }}", &line[..brace_pos]));
-                    } else {
-                        result.push(line.to_string());
-                    }
-                }
-            }
-            result.push("}".to_string());
-        }
-        _ => {
-            // Default: just show first few lines
-            return limit_text(text, 20);
+            ; Enum specifiers
+            (enum_specifier) @enum
+
+            ; Type definitions
+            (type_definition) @typedef
+            "#
         }
-    }
+        "cpp" => {
+            r#"
+            ; Function definitions
+            (function_definition) @function
 
-    result.join("\n")
-}
+            ; Struct specifiers
+            (struct_specifier) @struct
 
-/// Extract impl block summary for Rust
-fn extract_impl_summary(text: &str) -> String {
-    let lines: Vec<&str> = text.lines().collect();
-    if lines.is_empty() {
-        return text.to_string();
-    }
+            ; Class specifiers
+            (class_specifier) @class
 
-    let mut result = Vec::new();
-    result.push(lines[0].to_string()); // impl line
+            ; Enum specifiers
+            (enum_specifier) @enum
 
-    for line in lines.iter().skip(1) {
-        let trimmed = line.trim();
-        // Include fn signatures
-        if trimmed.starts_with("pub fn ")
-            || trimmed.starts_with("fn ")
-            || trimmed.starts_with("pub async fn ")
-            || trimmed.starts_with("async fn ")
-        {
-            if let Some(brace_pos) = line.find('{') {
-                result.push(format!("{}{{ ... }}", &line[..brace_pos]));
-            } else {
-                result.push(line.to_string());
-            }
+            ; Type definitions
+            (type_definition) @typedef
+            "#
         }
+        _ => "",
     }
-    result.push("}".to_string());
-
-    result.join("\n")
 }
 
-/// Extract doc comments before a definition
-fn extract_doc_comment(lines: &[&str], start_line: usize, lang_id: &str) -> String {
-    if start_line == 0 {
-        return String::new();
+/// Build a human-readable summary from captured symbols
+fn build_summary(
+    content: &str,
+    captures: &[CapturedSymbol],
+    lang_id: &str,
+    original_lines: usize,
+    truncated: Option<&str>,
+    omitted: usize,
+    options: &SummaryOptions,
+) -> (String, Vec<SummarySegment>) {
+    let mut result = format!(
+        "[COMPRESSED: Original {} lines → Summarized using tree-sitter]\n\n",
+        original_lines
+    );
+    if let Some(reason) = truncated {
+        result.push_str(&format!("[TRUNCATED: {}; outline only]\n\n", reason));
+    }
+    if omitted > 0 {
+        result.push_str(&format!(
+            "[OMITTED: {} lower-priority definitions over budget]\n\n",
+            omitted
+        ));
     }
 
-    let mut doc_lines = Vec::new();
-    let mut line_idx = start_line - 1;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut segments = Vec::with_capacity(captures.len());
+    // 1-based line the next write to `result` starts on, counted incrementally
+    let mut line = 1;
+    let mut counted = 0;
+    let mut record = |result: &str, capture: &CapturedSymbol, replacement: String| {
+        line += result[counted..].matches('\n').count();
+        counted = result.len();
+        segments.push(SummarySegment {
+            kind: capture.kind.clone(),
+            name: capture.name.clone(),
+            original_start_line: capture.start_line + 1,
+            original_end_line: capture.end_line + 1,
+            summary_start_line: line,
+            summary_end_line: line + replacement.lines().count().max(1) - 1,
+            replacement,
+        });
+    };
 
-    // Look backwards for doc comments
-    loop {
-        let line = lines.get(line_idx).unwrap_or(&"").trim();
+    for capture in captures {
+        // Get the captured text
+        let text = &capture.text;
 
-        let is_doc_comment = match lang_id {
-            "typescript" | "javascript" | "tsx" | "jsx" | "java" | "c" | "cpp" => {
-                line.starts_with("/**")
-                    || line.starts_with("*")
-                    || line.starts_with("//")
-                    || line.ends_with("*/")
-            }
-            "python" => {
-                line.starts_with("\"\"\"") || line.starts_with("'''") || line.starts_with("#")
+        if truncated.is_some() {
+            let outline = outline_line(text);
+            record(&result, capture, outline.clone());
+            result.push_str(&outline);
+            result.push('\n');
+            continue;
+        }
+
+        let kind = CaptureKind::from_capture_name(&capture.kind);
+        let summarized = match options.policy(&capture.kind) {
+            Some(KindPolicy::KeepFull) => text.clone(),
+            Some(KindPolicy::FirstLine) => text.lines().next().unwrap_or(text).to_string(),
+            Some(KindPolicy::SignatureOnly) if kind.is_some_and(CaptureKind::is_callable) => {
+                extract_function_signature(text, lang_id)
             }
-            "rust" => line.starts_with("///") || line.starts_with("//!"),
-            "go" => line.starts_with("//"),
-            _ => false,
+            Some(KindPolicy::SignatureOnly) => outline_line(text),
+            _ => default_summary(text, kind, lang_id),
         };
 
-        if is_doc_comment {
-            doc_lines.push(line.to_string());
-            if line.starts_with("/**") || line.starts_with("\"\"\"") || line.starts_with("'''") {
-                break; // Start of block comment
+        // Add doc comment if available (look at lines before start_line)
+        if options.include_doc_comments {
+            let mut doc_comment = extract_doc_comment(&lines, capture.start_line, lang_id);
+            if let Some(max_lines) = options.max_doc_lines {
+                doc_comment = cap_doc_comment(&doc_comment, max_lines);
             }
-        } else if line.is_empty() {
-            // Allow one empty line
-            if !doc_lines.is_empty() {
-                break;
+            if !doc_comment.is_empty() {
+                result.push_str(&doc_comment);
+                result.push('\n');
             }
-        } else {
-            break;
         }
 
-        if line_idx == 0 {
-            break;
-        }
-        line_idx -= 1;
+        record(&result, capture, summarized.clone());
+        result.push_str(&summarized);
+        result.push_str("\n\n");
     }
 
-    doc_lines.reverse();
-    doc_lines.join("\n")
+    (result.trim_end().to_string(), segments)
 }
 
-/// Limit text to a certain number of lines
-fn limit_text(text: &str, max_lines: usize) -> String {
-    let lines: Vec<&str> = text.lines().collect();
-    if lines.len() <= max_lines {
-        text.to_string()
-    } else {
-        let mut result: Vec<&str> = lines[..max_lines].to_vec();
-        result.push("    // ... (truncated)");
-        result.push("}");
-        result.join("\n")
+/// Built-in summary of one capture: signatures for callables, bounded text for types,
+/// the first line for values
+fn default_summary(text: &str, kind: Option<CaptureKind>, lang_id: &str) -> String {
+    // For function/method bodies, we want to show only the signature
+    match kind {
+        // `.mli` files are signatures already
+        Some(_) if lang_id == "ocaml_interface" => text.trim_end().to_string(),
+        Some(kind) if kind.is_callable() => extract_function_signature(text, lang_id),
+        // Objective-C containers end at `@end`, not a closing brace
+        Some(CaptureKind::Class | CaptureKind::Interface) if lang_id == "objc" => {
+            objc_container_summary(text)
+        }
+        // Julia modules wrap whole files; their definitions are captured on their own
+        Some(CaptureKind::Class) if lang_id == "julia" => julia_module_summary(text),
+        Some(CaptureKind::Class) => extract_class_summary(text, lang_id),
+        // Swift types and extensions are mostly method bodies
+        Some(
+            CaptureKind::Struct | CaptureKind::Enum | CaptureKind::Interface | CaptureKind::Impl,
+        ) if lang_id == "swift" => extract_class_summary(text, lang_id),
+        // Haskell types are kept whole; classes and instances drop their equations
+        Some(CaptureKind::Struct | CaptureKind::TypeAlias) if lang_id == "haskell" => {
+            text.to_string()
+        }
+        Some(CaptureKind::Trait | CaptureKind::Impl) if lang_id == "haskell" => {
+            extract_haskell_class_summary(text)
+        }
+        // OCaml module types and type declarations are signatures; keep them intact
+        Some(CaptureKind::Interface | CaptureKind::TypeAlias) if lang_id == "ocaml" => {
+            text.trim_end().to_string()
+        }
+        // Shell `case` blocks keep their patterns; a `getopts` loop keeps its option string
+        Some(CaptureKind::Enum) if lang_id == "bash" => bash_case_summary(text),
+        // A script's param block is its command-line interface
+        Some(CaptureKind::Variable) if lang_id == "powershell" => text.trim_end().to_string(),
+        // Scala case classes keep their full parameter list; traits hold def bodies
+        Some(CaptureKind::Struct | CaptureKind::Trait) if lang_id == "scala" => {
+            extract_scala_type_summary(text)
+        }
+        Some(CaptureKind::Impl) => extract_impl_summary(text),
+        Some(
+            CaptureKind::Interface
+            | CaptureKind::TypeAlias
+            | CaptureKind::Enum
+            | CaptureKind::Struct
+            | CaptureKind::Trait,
+        ) => {
+            // For types, keep as-is (they're usually not too long)
+            // But limit to reasonable size
+            limit_text(text, 30)
+        }
+        // Elixir `@spec` and `@type` attributes often wrap
+        Some(CaptureKind::Const) if lang_id == "elixir" => limit_text(text, 10),
+        // C# properties: the first line may be an attribute
+        Some(_) if lang_id == "csharp" => csharp_signature(text, true),
+        Some(_) => {
+            // For constants, fields and typedefs, keep the first line
+            text.lines().next().unwrap_or(text).to_string()
+        }
+        None => text.to_string(),
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_new_service_has_languages() {
-        let service = CodeNavigationService::new();
-
-        // Check that languages are registered
-        assert!(service.languages.contains_key("python"));
-        assert!(service.languages.contains_key("rust"));
-        assert!(service.languages.contains_key("go"));
-        assert!(service.languages.contains_key("c"));
-        assert!(service.languages.contains_key("cpp"));
-        assert!(service.languages.contains_key("java"));
-        assert!(service.languages.contains_key("typescript"));
-        assert!(service.languages.contains_key("javascript"));
+/// First line of a definition, with an opened body marked as elided
+fn outline_line(text: &str) -> String {
+    let first = text.lines().next().unwrap_or("").trim_end();
+    if first.ends_with('{') {
+        format!("{} ... }}", first)
+    } else {
+        first.to_string()
     }
+}
 
-    #[test]
-    fn test_new_service_has_parsers() {
-        let service = CodeNavigationService::new();
-
-        // Check that parsers are registered
-        assert!(service.parsers.contains_key("python"));
-        assert!(service.parsers.contains_key("rust"));
-        assert!(service.parsers.contains_key("typescript"));
+/// Summary of a single definition, chosen by its tree-sitter node kind
+pub(crate) fn summarize_definition(text: &str, node_kind: &str, lang_id: &str) -> String {
+    match node_kind {
+        "impl_item" => extract_impl_summary(text),
+        k if k.contains("function") || k.contains("method") || k.contains("constructor") => {
+            extract_function_signature(text, lang_id)
+        }
+        "func_literal" => extract_function_signature(text, lang_id),
+        k if k.starts_with("class") => extract_class_summary(text, lang_id),
+        _ => limit_text(text, 30),
     }
+}
 
-    #[test]
-    fn test_new_service_has_queries() {
-        let service = CodeNavigationService::new();
+/// Extract function signature without body
+fn extract_function_signature(text: &str, lang_id: &str) -> String {
+    let first_line = || text.lines().next().unwrap_or(text).to_string();
+    match lang_id {
+        "typescript" | "javascript" | "tsx" | "jsx" => {
+            // Cut at the body's opening brace, or after the arrow of an expression body
+            if let Some(sig) = before_in_code(text, "{", lang_id) {
+                format!("{} {{ ... }}", sig.trim())
+            } else if let Some(sig) = through_in_code(text, "=>", lang_id) {
+                format!("{} {{ ... }}", sig.trim())
+            } else {
+                first_line()
+            }
+        }
+        "python" => {
+            // Cut after the colon that ends the def line
+            match through_in_code(text, ":", lang_id) {
+                Some(sig) => format!("{}\n    ...", sig.trim()),
+                None => first_line(),
+            }
+        }
+        "rust" | "go" | "java" | "c" | "cpp" | "objc" | "swift" => {
+            // Cut at the body's opening brace
+            match before_in_code(text, "{", lang_id) {
+                Some(sig) => format!("{} {{ ... }}", sig.trim()),
+                None => first_line(),
+            }
+        }
+        "csharp" => csharp_signature(text, false),
+        "ruby" => ruby_signature(text),
+        "julia" => julia_signature(text),
+        "scala" => scala_signature(text),
+        "elixir" => elixir_signature(text),
+        "haskell" => haskell_signature(text),
+        "ocaml" => ocaml_let_signature(text),
+        "bash" => bash_function_signature(text),
+        "powershell" => powershell_function_signature(text),
+        _ => first_line(),
+    }
+}
+
+/// Modifiers that start a C# member declaration
+const CSHARP_MODIFIERS: &[&str] = &[
+    "public",
+    "private",
+    "protected",
+    "internal",
+    "static",
+    "abstract",
+    "virtual",
+    "override",
+    "sealed",
+    "async",
+    "readonly",
+    "const",
+    "event",
+    "partial",
+    "required",
+];
+
+/// Split the attribute lists (`[HttpGet("{id}")]`) off the start of a C# declaration
+fn split_csharp_attributes(text: &str) -> (Vec<&str>, &str) {
+    let mut attributes = Vec::new();
+    let mut rest = text.trim_start();
+    while rest.starts_with('[') {
+        // The closing bracket is the first `]` outside strings and nested brackets
+        let Some(close) = find_in_code(&rest[1..], "]", "csharp") else {
+            break;
+        };
+        attributes.push(&rest[..close + 2]);
+        rest = rest[close + 2..].trim_start();
+    }
+    (attributes, rest)
+}
+
+/// A C# member's attributes, one per line, followed by its declaration cut at the body
+/// (`{ ... }`) or expression body (`=> ...;`). With `keep_single_line`, one-line
+/// declarations such as auto-properties are kept whole.
+fn csharp_signature(text: &str, keep_single_line: bool) -> String {
+    let (attributes, declaration) = split_csharp_attributes(text);
+    let signature = if keep_single_line && !declaration.trim_end().contains('\n') {
+        declaration.trim_end().to_string()
+    } else {
+        let body = find_in_code(declaration, "{", "csharp");
+        let arrow = find_in_code(declaration, "=>", "csharp");
+        match (body, arrow) {
+            (Some(b), Some(a)) if a < b => format!("{} => ...;", slice_to(declaration, a).trim()),
+            (None, Some(a)) => format!("{} => ...;", slice_to(declaration, a).trim()),
+            (Some(b), _) => format!("{} {{ ... }}", slice_to(declaration, b).trim()),
+            (None, None) => declaration.lines().next().unwrap_or("").to_string(),
+        }
+    };
+    let mut lines: Vec<&str> = attributes;
+    lines.push(&signature);
+    lines.join("\n")
+}
+
+/// C# type summary: attributes and declaration, then the member signatures at the
+/// body's top level, each with the attributes directly above it
+fn extract_csharp_type_summary(text: &str) -> String {
+    let (attributes, declaration) = split_csharp_attributes(text);
+    let Some(open) = find_in_code(declaration, "{", "csharp") else {
+        // Positional records without a body
+        return limit_text(text, 20);
+    };
+    let mut result: Vec<String> = attributes.iter().map(|a| a.to_string()).collect();
+    result.push(format!("{} {{", slice_to(declaration, open).trim()));
+
+    let body = &declaration[open + 1..];
+    let is_code = |l: &&str| {
+        let t = l.trim();
+        !t.is_empty() && !t.starts_with("//") && !t.starts_with('*') && !t.starts_with("/*")
+    };
+    let member_indent = body
+        .lines()
+        .find(is_code)
+        .map(|l| l.len() - l.trim_start().len())
+        .unwrap_or(4);
+
+    let mut pending_attributes: Vec<&str> = Vec::new();
+    for line in body.lines().filter(is_code) {
+        let trimmed = line.trim();
+        if line.len() - line.trim_start().len() != member_indent {
+            continue;
+        }
+        if trimmed.starts_with('[') {
+            pending_attributes.push(line.trim_end());
+            continue;
+        }
+        let is_member = CSHARP_MODIFIERS
+            .iter()
+            .any(|m| trimmed.strip_prefix(m).is_some_and(|r| r.starts_with(' ')));
+        if is_member {
+            result.extend(pending_attributes.drain(..).map(str::to_string));
+            let indent = &line[..member_indent];
+            if trimmed.contains("get;") || trimmed.contains("set;") || trimmed.contains("init;") {
+                result.push(line.trim_end().to_string());
+            } else {
+                result.push(format!("{}{}", indent, csharp_signature(trimmed, false)));
+            }
+        } else {
+            pending_attributes.clear();
+        }
+    }
+    result.push("}".to_string());
+    result.join("\n")
+}
+
+/// Swift declaration modifiers that can precede a member's keyword
+const SWIFT_MODIFIERS: &[&str] = &[
+    "public",
+    "private",
+    "fileprivate",
+    "internal",
+    "open",
+    "package",
+    "static",
+    "class",
+    "final",
+    "override",
+    "mutating",
+    "nonmutating",
+    "lazy",
+    "weak",
+    "unowned",
+    "convenience",
+    "required",
+    "dynamic",
+    "nonisolated",
+    "indirect",
+];
+
+/// Keywords that start a Swift member declaration
+const SWIFT_MEMBER_KEYWORDS: &[&str] = &[
+    "func",
+    "init",
+    "deinit",
+    "var",
+    "let",
+    "case",
+    "subscript",
+    "typealias",
+    "associatedtype",
+    "struct",
+    "enum",
+    "class",
+    "actor",
+    "protocol",
+];
+
+/// Whether a line declares a member, after any attributes (`@MainActor`) and modifiers
+/// (`private(set)`, `static`)
+fn is_swift_member(line: &str) -> bool {
+    for word in line.split_whitespace() {
+        if word.starts_with('@') {
+            continue;
+        }
+        let base = word
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .next()
+            .unwrap_or(word);
+        if SWIFT_MEMBER_KEYWORDS.contains(&base) {
+            return true;
+        }
+        if !SWIFT_MODIFIERS.contains(&base) {
+            return false;
+        }
+    }
+    false
+}
+
+/// Swift type or extension summary: the declaration, then the member declarations at
+/// the body's top level with bodies elided. Attribute-only lines are kept with the
+/// member that follows them.
+fn extract_swift_type_summary(text: &str) -> String {
+    let Some(open) = find_in_code(text, "{", "swift") else {
+        return limit_text(text, 20);
+    };
+    let mut result = vec![format!("{} {{", slice_to(text, open).trim_end())];
+
+    let body = &text[open + 1..];
+    let is_code = |l: &&str| {
+        let t = l.trim();
+        !t.is_empty() && !t.starts_with("//") && !t.starts_with('*') && !t.starts_with("/*")
+    };
+    let member_indent = body
+        .lines()
+        .find(is_code)
+        .map(|l| l.len() - l.trim_start().len())
+        .unwrap_or(4);
+
+    let mut pending_attributes: Vec<&str> = Vec::new();
+    for line in body.lines().filter(is_code) {
+        let trimmed = line.trim();
+        if line.len() - line.trim_start().len() != member_indent {
+            continue;
+        }
+        if trimmed.starts_with('@') && !trimmed.contains(' ') {
+            pending_attributes.push(line.trim_end());
+            continue;
+        }
+        if is_swift_member(trimmed) {
+            result.extend(pending_attributes.drain(..).map(str::to_string));
+            match before_in_code(line, "{", "swift") {
+                Some(sig) => result.push(format!("{}{{ ... }}", sig)),
+                None => result.push(line.trim_end().to_string()),
+            }
+        } else {
+            pending_attributes.clear();
+        }
+    }
+    result.push("}".to_string());
+    result.join("\n")
+}
+
+/// A Ruby `def` up to the end of its parameter list, which may span lines; the body of a
+/// one-line `def x; ...; end` is dropped
+fn ruby_signature(text: &str) -> String {
+    let first_line = text.lines().next().unwrap_or(text);
+    let head = if first_line.matches('(').count() > first_line.matches(')').count() {
+        before_in_code(text, "\n", "ruby").unwrap_or(first_line)
+    } else {
+        first_line
+    };
+    let head = before_in_code(head, "#", "ruby").unwrap_or(head);
+    before_in_code(head, ";", "ruby")
+        .unwrap_or(head)
+        .trim_end()
+        .to_string()
+}
+
+/// Julia function or macro signature: the first line, or through the closing paren of
+/// an argument list that wraps. Short-form definitions (`f(x) = ...`) keep their line.
+fn julia_signature(text: &str) -> String {
+    let first_line = text.lines().next().unwrap_or(text);
+    let head = if first_line.matches('(').count() > first_line.matches(')').count() {
+        before_in_code(text, "\n", "julia").unwrap_or(first_line)
+    } else {
+        first_line
+    };
+    before_in_code(head, "#", "julia")
+        .unwrap_or(head)
+        .trim_end()
+        .to_string()
+}
+
+/// Julia module: its declaration, `using`, `import`, `export` and `include` lines, and
+/// `end`
+fn julia_module_summary(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() < 2 {
+        return text.to_string();
+    }
+    let mut kept = vec![lines[0].trim_end()];
+    kept.extend(lines[1..lines.len() - 1].iter().copied().filter(|line| {
+        let trimmed = line.trim_start();
+        ["using ", "import ", "export ", "include("]
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+    }));
+    kept.push(lines[lines.len() - 1].trim_end());
+    kept.join("\n")
+}
+
+/// Whether a line at a class body's top level opens a block closed by a later `end`
+fn opens_ruby_block(trimmed: &str) -> bool {
+    let first_word = trimmed.split_whitespace().next().unwrap_or("");
+    if matches!(first_word, "class" | "module" | "begin") {
+        return !trimmed.ends_with("end");
+    }
+    if first_word == "def" {
+        // Endless (`def x = 1`) and one-line methods have no body to elide
+        return !trimmed.ends_with("end") && find_in_code(trimmed, " = ", "ruby").is_none();
+    }
+    let code = before_in_code(trimmed, "#", "ruby")
+        .unwrap_or(trimmed)
+        .trim_end();
+    code.ends_with(" do") || (code.contains(" do |") && code.ends_with('|'))
+}
+
+/// Ruby class or module summary: the declaration, then the body's top-level lines
+/// (macros like `has_many :posts`, `include`, constants, visibility keywords) with
+/// method and block bodies elided
+fn extract_ruby_class_summary(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() < 2 {
+        return text.to_string();
+    }
+    let mut result = vec![lines[0].to_string()];
+
+    let body = &lines[1..lines.len() - 1];
+    let is_code = |l: &&&str| {
+        let t = l.trim();
+        !t.is_empty() && !t.starts_with('#')
+    };
+    let member_indent = body
+        .iter()
+        .find(is_code)
+        .map(|l| l.len() - l.trim_start().len())
+        .unwrap_or(2);
+
+    for line in body.iter().filter(is_code) {
+        let trimmed = line.trim();
+        if line.len() - line.trim_start().len() != member_indent || trimmed == "end" {
+            continue;
+        }
+        if trimmed.starts_with("def ") {
+            let signature = ruby_signature(line);
+            if opens_ruby_block(trimmed) {
+                result.push(format!("{} ... end", signature));
+            } else {
+                result.push(signature);
+            }
+        } else if opens_ruby_block(trimmed) {
+            result.push(format!("{} ... end", line.trim_end()));
+        } else {
+            result.push(line.trim_end().to_string());
+        }
+    }
+    result.push(lines[lines.len() - 1].to_string());
+    result.join("\n")
+}
+
+/// Scala modifiers and soft keywords that can precede `def`, `val` or `class`
+const SCALA_MODIFIERS: &[&str] = &[
+    "private",
+    "protected",
+    "override",
+    "final",
+    "implicit",
+    "lazy",
+    "abstract",
+    "sealed",
+    "inline",
+    "transparent",
+    "opaque",
+    "open",
+    "infix",
+    "given",
+];
+
+/// Where a Scala definition's body starts: the `=` of a def or val, or the `{` of a
+/// template, whichever comes first at the top level
+fn scala_body_start(text: &str) -> Option<usize> {
+    let trailing_equals = text.trim_end().strip_suffix(" =").map(|head| head.len());
+    [" = ", " =\n", "{"]
+        .iter()
+        .filter_map(|needle| find_in_code(text, needle, "scala"))
+        .chain(trailing_equals)
+        .min()
+}
+
+/// A Scala def with its body elided; abstract defs are returned whole
+fn scala_signature(text: &str) -> String {
+    match scala_body_start(text) {
+        Some(start) => format!("{} = ...", slice_to(text, start).trim_end()),
+        None => text.trim_end().to_string(),
+    }
+}
+
+/// The keyword a Scala member line starts with, after annotations and modifiers
+fn scala_member_keyword(line: &str) -> &str {
+    line.split_whitespace()
+        .find(|word| {
+            let base = word.split('[').next().unwrap_or(word);
+            !word.starts_with('@') && !SCALA_MODIFIERS.contains(&base)
+        })
+        .unwrap_or("")
+}
+
+/// Scala class, case class, object or trait summary: the declaration with its full
+/// parameter list, then the body's top-level members with def bodies elided. A case
+/// class without a body is returned whole. Scala 3 `:` bodies are handled like braces.
+fn extract_scala_type_summary(text: &str) -> String {
+    let open = [
+        find_in_code(text, "{", "scala"),
+        find_in_code(text, ":\n", "scala"),
+    ]
+    .into_iter()
+    .flatten()
+    .min();
+    let Some(open) = open else {
+        return text.trim_end().to_string();
+    };
+    let braced = text[open..].starts_with('{');
+    let mut result = if braced {
+        vec![format!("{} {{", slice_to(text, open).trim_end())]
+    } else {
+        vec![slice_to(text, open + 1).to_string()]
+    };
+
+    let body = &text[open + 1..];
+    let is_code = |l: &&str| {
+        let t = l.trim();
+        !t.is_empty()
+            && !t.starts_with("//")
+            && !t.starts_with('*')
+            && !t.starts_with("/*")
+            && !t.starts_with('}')
+    };
+    let member_indent = body
+        .lines()
+        .find(is_code)
+        .map(|l| l.len() - l.trim_start().len())
+        .unwrap_or(2);
+
+    for line in body.lines().filter(is_code) {
+        if line.len() - line.trim_start().len() != member_indent {
+            continue;
+        }
+        let line = line.trim_end();
+        if scala_member_keyword(line) == "def" {
+            result.push(scala_signature(line));
+        } else if line.matches('{').count() > line.matches('}').count() {
+            // A nested template or a block-valued member
+            match before_in_code(line, "{", "scala") {
+                Some(head) => result.push(format!("{}{{ ... }}", head)),
+                None => result.push(line.to_string()),
+            }
+        } else {
+            result.push(line.to_string());
+        }
+    }
+    if braced {
+        result.push("}".to_string());
+    }
+    result.join("\n")
+}
+
+/// Extract class summary - signature + field names + method signatures
+/// An Elixir def up to its `do`, with the body elided; bodyless heads (`def f(x \\ 1)`)
+/// and `defdelegate` are kept whole
+fn elixir_signature(text: &str) -> String {
+    let block = find_in_code(text, " do\n", "elixir");
+    let keyword = find_in_code(text, ", do:", "elixir");
+    match (block, keyword) {
+        (Some(block), Some(keyword)) if keyword < block => {
+            format!("{}, do: ...", slice_to(text, keyword).trim_end())
+        }
+        (Some(block), _) => format!("{} do ... end", slice_to(text, block).trim_end()),
+        (None, Some(keyword)) => format!("{}, do: ...", slice_to(text, keyword).trim_end()),
+        (None, None) => text.trim_end().to_string(),
+    }
+}
+
+/// Opening minus closing brackets on a line
+fn elixir_bracket_balance(line: &str) -> i32 {
+    line.chars()
+        .map(|c| match c {
+            '(' | '[' | '{' => 1,
+            ')' | ']' | '}' => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Whether a line opens a heredoc (`@moduledoc """`, `@doc ~S"""`) closed by a later line
+fn opens_elixir_heredoc(trimmed: &str) -> bool {
+    trimmed != "\"\"\""
+        && trimmed != "'''"
+        && (trimmed.ends_with("\"\"\"") || trimmed.ends_with("'''"))
+}
+
+/// Elixir module summary: the `defmodule` line, its `@moduledoc`, and the `use`, `alias`,
+/// `import` and `require` directives and struct at the module's top level. Functions and
+/// attributes are captured on their own, so they are not repeated here.
+fn extract_elixir_module_summary(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() < 2 {
+        return text.to_string();
+    }
+    let mut result = vec![lines[0].to_string()];
+
+    let body = &lines[1..lines.len() - 1];
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let member_indent = body
+        .iter()
+        .find(|l| {
+            let t = l.trim();
+            !t.is_empty() && !t.starts_with('#')
+        })
+        .map(|l| indent(l))
+        .unwrap_or(2);
+
+    // Inside a heredoc, and whether its lines are kept
+    let mut heredoc: Option<bool> = None;
+    // Unclosed brackets of a kept directive spanning lines
+    let mut open_brackets = 0;
+    for line in body {
+        let trimmed = line.trim();
+        if let Some(keep) = heredoc {
+            if keep {
+                result.push(line.trim_end().to_string());
+            }
+            if trimmed == "\"\"\"" || trimmed == "'''" {
+                heredoc = None;
+            }
+            continue;
+        }
+        if open_brackets > 0 {
+            result.push(line.trim_end().to_string());
+            open_brackets += elixir_bracket_balance(trimmed);
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') || indent(line) != member_indent {
+            continue;
+        }
+        let first_word = trimmed
+            .split(|c: char| c.is_whitespace() || c == '(')
+            .next()
+            .unwrap_or("");
+        let keep = matches!(
+            first_word,
+            "@moduledoc" | "use" | "alias" | "import" | "require" | "defstruct" | "defexception"
+        );
+        if opens_elixir_heredoc(trimmed) {
+            heredoc = Some(keep);
+        } else if keep {
+            open_brackets = elixir_bracket_balance(trimmed);
+        }
+        if keep {
+            result.push(line.trim_end().to_string());
+        }
+    }
+    result.join("\n")
+}
+
+/// The `@doc` or `@typedoc` above an Elixir definition: a one-line attribute
+/// (`@doc "Adds two numbers"`, `@doc false`) or a heredoc
+fn extract_elixir_doc(lines: &[&str], start_line: usize) -> String {
+    let mut end = start_line;
+    while end > 0 && lines.get(end - 1).is_some_and(|l| l.trim().is_empty()) {
+        end -= 1;
+    }
+    let Some(last) = end.checked_sub(1).and_then(|i| lines.get(i)) else {
+        return String::new();
+    };
+    let is_doc = |trimmed: &str| trimmed.starts_with("@doc ") || trimmed.starts_with("@typedoc ");
+    let last = last.trim();
+    if is_doc(last) {
+        return last.to_string();
+    }
+    if last != "\"\"\"" && last != "'''" {
+        return String::new();
+    }
+
+    // Walk back to the heredoc's opening line, keeping the indentation inside it
+    let Some(open) = (0..end - 1)
+        .rev()
+        .find(|&i| opens_elixir_heredoc(lines[i].trim()))
+    else {
+        return String::new();
+    };
+    if !is_doc(lines[open].trim()) {
+        return String::new();
+    }
+    let base = lines[open].len() - lines[open].trim_start().len();
+    lines[open..end]
+        .iter()
+        .map(|line| {
+            let strip = base.min(line.len() - line.trim_start().len());
+            line[strip..].trim_end()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The docstring directly above a Julia definition: a `"""` block or a one-line string
+fn extract_julia_doc(lines: &[&str], start_line: usize) -> String {
+    let Some(last) = lines.get(start_line - 1).map(|l| l.trim()) else {
+        return String::new();
+    };
+    if last.len() > 6 && last.starts_with("\"\"\"") && last.ends_with("\"\"\"") {
+        return last.to_string();
+    }
+    if last != "\"\"\"" {
+        let one_line = last.len() >= 2 && last.starts_with('"') && last.ends_with('"');
+        return if one_line {
+            last.to_string()
+        } else {
+            String::new()
+        };
+    }
+
+    // Walk back to the opening quotes, keeping the indentation inside the block
+    let Some(open) = (0..start_line - 1)
+        .rev()
+        .find(|&i| lines[i].trim_start().starts_with("\"\"\""))
+    else {
+        return String::new();
+    };
+    let base = lines[open].len() - lines[open].trim_start().len();
+    lines[open..start_line]
+        .iter()
+        .map(|line| {
+            let strip = base.min(line.len() - line.trim_start().len());
+            line[strip..].trim_end()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The `(* ... *)` comment directly above an OCaml item, usually a `(** ... *)` doc
+/// comment
+fn extract_ocaml_doc(lines: &[&str], start_line: usize) -> String {
+    let mut end = start_line;
+    while end > 0 && lines.get(end - 1).is_some_and(|l| l.trim().is_empty()) {
+        end -= 1;
+    }
+    if end == 0
+        || !lines
+            .get(end - 1)
+            .is_some_and(|l| l.trim_end().ends_with("*)"))
+    {
+        return String::new();
+    }
+    let Some(open) = (0..end)
+        .rev()
+        .find(|&i| lines[i].trim_start().starts_with("(*"))
+    else {
+        return String::new();
+    };
+    lines[open..end]
+        .iter()
+        .map(|l| l.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn extract_class_summary(text: &str, lang_id: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.is_empty() {
+        return text.to_string();
+    }
+
+    let mut result = Vec::new();
+
+    match lang_id {
+        "typescript" | "javascript" | "tsx" | "jsx" => {
+            result.push(lines[0].to_string());
+
+            // Detect the indentation level of class members (first non-empty line after class declaration)
+            let member_indent = lines
+                .iter()
+                .skip(1)
+                .find(|l| {
+                    !l.trim().is_empty()
+                        && !l.trim().starts_with("//")
+                        && !l.trim().starts_with("*")
+                })
+                .map(|l| l.len() - l.trim_start().len())
+                .unwrap_or(2);
+
+            for line in lines.iter().skip(1) {
+                let trimmed = line.trim();
+                let current_indent = line.len() - line.trim_start().len();
+
+                // Only consider lines at the class member indentation level
+                if current_indent != member_indent || trimmed.is_empty() {
+                    continue;
+                }
+
+                // Check if it's a class member (field, method, constructor, decorator)
+                let is_member = trimmed.starts_with("private ")
+                    || trimmed.starts_with("public ")
+                    || trimmed.starts_with("protected ")
+                    || trimmed.starts_with("readonly ")
+                    || trimmed.starts_with("static ")
+                    || trimmed.starts_with("constructor")
+                    || trimmed.starts_with("async ")
+                    || trimmed.starts_with("@") // decorators
+                    || trimmed.starts_with("get ")
+                    || trimmed.starts_with("set ")
+                    // Method without access modifier (must have parentheses and be followed by { or :)
+                    || (trimmed.contains('(')
+                        && (trimmed.contains(") {") || trimmed.contains("): ")));
+
+                if is_member {
+                    if let Some(sig) = before_in_code(line, "{", lang_id) {
+                        result.push(format!("{}{{ ... }}", sig));
+                    } else {
+                        result.push(line.to_string());
+                    }
+                }
+            }
+            result.push("}".to_string());
+        }
+        "python" => {
+            // Class definition line
+            result.push(lines[0].to_string());
+
+            for line in lines.iter().skip(1) {
+                let trimmed = line.trim();
+                // Include def lines (methods)
+                if trimmed.starts_with("def ") || trimmed.starts_with("async def ") {
+                    if let Some(sig) = through_in_code(line, ":", lang_id) {
+                        result.push(format!("{}\n        ...", sig));
+                    } else {
+                        result.push(line.to_string());
+                    }
+                } else if trimmed.starts_with("self.") && trimmed.contains('=') {
+                    // Field assignment in __init__
+                    result.push(format!("    {}", trimmed));
+                }
+            }
+        }
+        "java" => {
+            result.push(lines[0].to_string());
+
+            for line in lines.iter().skip(1) {
+                let trimmed = line.trim();
+                // Include field and method declarations
+                if trimmed.starts_with("private ")
+                    || trimmed.starts_with("public ")
+                    || trimmed.starts_with("protected ")
+                    || trimmed.starts_with("static ")
+                    || trimmed.starts_with("final ")
+                {
+                    if let Some(sig) = before_in_code(line, "{", lang_id) {
+                        result.push(format!("{}{{ ... }}", sig));
+                    } else {
+                        result.push(line.to_string());
+                    }
+                }
+            }
+            result.push("}".to_string());
+        }
+        "csharp" => return extract_csharp_type_summary(text),
+        "swift" => return extract_swift_type_summary(text),
+        "ruby" => return extract_ruby_class_summary(text),
+        "scala" => return extract_scala_type_summary(text),
+        "elixir" => return extract_elixir_module_summary(text),
+        "haskell" => return extract_haskell_class_summary(text),
+        "ocaml" => return extract_ocaml_module_summary(text),
+        _ => {
+            // Default: just show first few lines
+            return limit_text(text, 20);
+        }
+    }
+
+    result.join("\n")
+}
+
+/// Extract impl block summary for Rust
+fn extract_impl_summary(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.is_empty() {
+        return text.to_string();
+    }
+
+    let mut result = Vec::new();
+    result.push(lines[0].to_string()); // impl line
+
+    for line in lines.iter().skip(1) {
+        let trimmed = line.trim();
+        // Include fn signatures
+        if trimmed.starts_with("pub fn ")
+            || trimmed.starts_with("fn ")
+            || trimmed.starts_with("pub async fn ")
+            || trimmed.starts_with("async fn ")
+        {
+            if let Some(sig) = before_in_code(line, "{", "rust") {
+                result.push(format!("{}{{ ... }}", sig));
+            } else {
+                result.push(line.to_string());
+            }
+        }
+    }
+    result.push("}".to_string());
+
+    result.join("\n")
+}
+
+/// A Haskell definition's type: signatures are kept verbatim, an equation is cut at its
+/// `=`
+fn haskell_signature(text: &str) -> String {
+    let annotation = find_in_code(text, "::", "haskell");
+    // Spaced, so `==`, `/=` and `=>` are not taken for the equation's `=`
+    let equals = [" = ", " =\n"]
+        .iter()
+        .filter_map(|needle| find_in_code(text, needle, "haskell"))
+        .min();
+    match equals {
+        Some(equals) if annotation.is_none_or(|a| equals < a) => {
+            format!("{} = ...", slice_to(text, equals).trim_end())
+        }
+        _ => text.trim_end().to_string(),
+    }
+}
+
+/// An OCaml `let` binding: a one-line binding is kept whole, a longer one is cut at the
+/// `=` of each of its `let` and `and` heads
+fn ocaml_let_signature(text: &str) -> String {
+    let text = text.trim_end();
+    if !text.contains('\n') {
+        return text.to_string();
+    }
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let base = text.lines().next().map(indent).unwrap_or(0);
+
+    // Split into the `let` binding and the `and` bindings at its indentation
+    let mut bindings: Vec<Vec<&str>> = Vec::new();
+    for line in text.lines() {
+        let starts_and = indent(line) == base && line.trim_start().starts_with("and ");
+        match bindings.last_mut() {
+            Some(binding) if !starts_and => binding.push(line),
+            _ => bindings.push(vec![line]),
+        }
+    }
+
+    bindings
+        .iter()
+        .map(|binding| {
+            let binding = format!("{}\n", binding.join("\n"));
+            // Spaced, so `==`, `<=` and `>=` are not taken for the binding's `=`
+            let equals = [" = ", " =\n"]
+                .iter()
+                .filter_map(|needle| find_in_code(&binding, needle, "ocaml"))
+                .min();
+            match equals {
+                Some(equals) => format!("{} = ...", slice_to(&binding, equals).trim_end()),
+                None => binding.trim_end().to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// OCaml module or functor summary: the header through `struct`, including any
+/// signature constraint; the module's items are summarized as their own captures
+fn extract_ocaml_module_summary(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let Some(head_end) = lines.iter().position(|l| {
+        let code = l.trim_end();
+        code == "struct" || code.trim_start() == "struct" || code.ends_with(" struct")
+    }) else {
+        // Aliases and functor applications (`module M = Make (Int)`)
+        return limit_text(text.trim_end(), 20);
+    };
+    lines[..=head_end]
+        .iter()
+        .map(|l| l.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Shell function header: `name() { ... }`, `function name { ... }`. A body that is not a
+/// `{ ... }` group (`name() ( subshell )`) leaves the first line.
+fn bash_function_signature(text: &str) -> String {
+    match before_in_code(text, "{", "bash") {
+        Some(header) if header.trim().lines().count() <= 2 && !header.contains('$') => {
+            format!("{} {{ ... }}", header.trim().replace('\n', " "))
+        }
+        _ => text.lines().next().unwrap_or(text).to_string(),
+    }
+}
+
+/// Shell `case` block reduced to its patterns, each with its commands elided; a
+/// `while getopts ...; do` loop is reduced to its header
+fn bash_case_summary(text: &str) -> String {
+    let mut lines = text.lines();
+    let header = lines.next().unwrap_or(text).trim_end();
+    if !header.trim_start().starts_with("case") {
+        return header.to_string();
+    }
+
+    let mut summary = vec![header.to_string()];
+    let mut depth = 1;
+    let mut expecting_pattern = true;
+    for line in lines {
+        let code = line.trim();
+        if code.is_empty() || code.starts_with('#') {
+            continue;
+        }
+        let first_word = code.split_whitespace().next().unwrap_or_default();
+        if first_word == "esac" || code.starts_with("esac;") {
+            depth -= 1;
+            if depth == 0 {
+                summary.push(line.trim_end().to_string());
+                break;
+            }
+            continue;
+        }
+        if depth == 1 && expecting_pattern {
+            // The pattern runs through the first unquoted `)`
+            if let Some(close) = find_in_code(line, ")", "bash") {
+                summary.push(format!("{} ...", slice_to(line, close + 1).trim_end()));
+                expecting_pattern = false;
+            }
+        } else if first_word == "case" {
+            depth += 1;
+        }
+        // `;;`, `;&` and `;;&` end an item
+        if depth == 1 && (code.ends_with(";;") || code.ends_with(";&") || code.ends_with(";;&")) {
+            expecting_pattern = true;
+        }
+    }
+    summary.join("\n")
+}
+
+/// PowerShell function header with the parts of the body that describe how it is
+/// called: comment-based help placed at the top of the body, attributes such as
+/// `[CmdletBinding()]` and `[OutputType(...)]`, and the `param(...)` block
+fn powershell_function_signature(text: &str) -> String {
+    let Some(open) = find_in_code(text, "{", "powershell") else {
+        return text.lines().next().unwrap_or(text).to_string();
+    };
+    let header = text[..open].trim();
+    let body = &text[open + 1..];
+
+    let mut end = 0;
+    loop {
+        let rest = body[end..].trim_start();
+        let start = body.len() - rest.len();
+        let consumed = if rest.starts_with("<#") {
+            rest.find("#>").map(|close| close + 2)
+        } else if let Some(inner) = rest.strip_prefix('[') {
+            find_in_code(inner, "]", "powershell").map(|close| close + 2)
+        } else if rest
+            .get(..5)
+            .is_some_and(|kw| kw.eq_ignore_ascii_case("param"))
+            && rest[5..].trim_start().starts_with('(')
+        {
+            let paren = rest.find('(').unwrap_or(5);
+            find_in_code(&rest[paren + 1..], ")", "powershell").map(|close| paren + close + 2)
+        } else {
+            None
+        };
+        match consumed {
+            Some(len) => end = start + len,
+            None => break,
+        }
+    }
+
+    if end == 0 {
+        return format!("{} {{ ... }}", header);
+    }
+    let kept = body[..end].trim_end();
+    let indent = kept
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .unwrap_or("    ");
+    format!("{} {{{}\n{}...\n}}", header, kept, indent)
+}
+
+/// Objective-C `@interface`, `@implementation` or `@protocol` through `@end`. Properties,
+/// method declarations and instance variables are kept; method bodies become `{ ... }`,
+/// whether their brace opens on the signature's line or the next one.
+fn objc_container_summary(text: &str) -> String {
+    let mut kept: Vec<String> = Vec::new();
+    let mut depth = 0i32;
+    let mut in_body = false;
+    // A method signature was seen and its `;` or body has not
+    let mut in_signature = false;
+    for line in text.lines() {
+        let balance = line.matches('{').count() as i32 - line.matches('}').count() as i32;
+        if in_body {
+            depth += balance;
+            if depth <= 0 {
+                in_body = false;
+                depth = 0;
+            }
+            continue;
+        }
+        let trimmed = line.trim();
+        let is_method = depth == 0 && (trimmed.starts_with('-') || trimmed.starts_with('+'));
+        if !(is_method || in_signature) {
+            depth += balance;
+            kept.push(line.trim_end().to_string());
+            continue;
+        }
+        match line.find('{') {
+            Some(brace) => {
+                let signature = line[..brace].trim_end();
+                if signature.trim().is_empty() {
+                    if let Some(last) = kept.last_mut() {
+                        last.push_str(" { ... }");
+                    }
+                } else {
+                    kept.push(format!("{} {{ ... }}", signature));
+                }
+                depth = balance;
+                in_body = depth > 0;
+                in_signature = false;
+            }
+            None => {
+                kept.push(line.trim_end().to_string());
+                in_signature = !trimmed.ends_with(';');
+            }
+        }
+    }
+    kept.join("\n")
+}
+
+/// A `<# ... #>` comment-based help block directly above a PowerShell function
+fn extract_powershell_help(lines: &[&str], start_line: usize) -> Option<String> {
+    let mut end = start_line;
+    while end > 0 && lines.get(end - 1).is_some_and(|l| l.trim().is_empty()) {
+        end -= 1;
+    }
+    if end == 0 || !lines.get(end - 1)?.trim_end().ends_with("#>") {
+        return None;
+    }
+    let open = (0..end)
+        .rev()
+        .find(|&i| lines[i].trim_start().starts_with("<#"))?;
+    Some(
+        lines[open..end]
+            .iter()
+            .map(|l| l.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// Haskell class or instance summary: the head through `where`, then the body's
+/// signatures, fixity declarations, pragmas and associated types, with equations
+/// (default methods, instance methods) replaced by `...`
+fn extract_haskell_class_summary(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let Some(head_end) = lines.iter().position(|l| {
+        let code = before_in_code(l, "--", "haskell").unwrap_or(l).trim_end();
+        code == "where" || code.ends_with(" where")
+    }) else {
+        return text.trim_end().to_string();
+    };
+    let mut result: Vec<String> = lines[..=head_end].iter().map(|l| l.to_string()).collect();
+
+    let body = &lines[head_end + 1..];
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let member_indent = body
+        .iter()
+        .find(|l| !l.trim().is_empty())
+        .map(|l| indent(l))
+        .unwrap_or(2);
+
+    // Whether the current member is kept; continuation lines follow it
+    let mut keep = false;
+    let mut elided = false;
+    for line in body {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if indent(line) <= member_indent {
+            keep = trimmed.starts_with("--")
+                || trimmed.starts_with("{-")
+                || trimmed.starts_with("infix")
+                || trimmed.starts_with("type ")
+                || trimmed.starts_with("data ")
+                || (trimmed.contains("::") && haskell_signature(trimmed) == trimmed);
+            elided |= !keep;
+        }
+        if keep {
+            result.push(line.trim_end().to_string());
+        }
+    }
+    if elided {
+        result.push(format!("{}...", " ".repeat(member_indent)));
+    }
+    result.join("\n")
+}
+
+/// Extract doc comments before a definition
+pub(crate) fn extract_doc_comment(lines: &[&str], start_line: usize, lang_id: &str) -> String {
+    if start_line == 0 {
+        return String::new();
+    }
+    // Elixir documents definitions with attributes rather than comments
+    if lang_id == "elixir" {
+        return extract_elixir_doc(lines, start_line);
+    }
+    if lang_id == "ocaml" || lang_id == "ocaml_interface" {
+        return extract_ocaml_doc(lines, start_line);
+    }
+    // Julia documents definitions with the string literal right above them
+    if lang_id == "julia" {
+        return extract_julia_doc(lines, start_line);
+    }
+    if lang_id == "powershell" {
+        if let Some(help) = extract_powershell_help(lines, start_line) {
+            return help;
+        }
+    }
+
+    let mut doc_lines = Vec::new();
+    let mut line_idx = start_line - 1;
+
+    // Look backwards for doc comments
+    loop {
+        let line = lines.get(line_idx).unwrap_or(&"").trim();
+
+        let is_doc_comment = match lang_id {
+            "typescript" | "javascript" | "tsx" | "jsx" | "java" | "c" | "cpp" | "objc"
+            | "csharp" | "swift" | "scala" => {
+                line.starts_with("/**")
+                    || line.starts_with("*")
+                    || line.starts_with("//")
+                    || line.ends_with("*/")
+            }
+            "python" => {
+                line.starts_with("\"\"\"") || line.starts_with("'''") || line.starts_with("#")
+            }
+            "rust" => line.starts_with("///") || line.starts_with("//!"),
+            // Magic comments at the top of the file are not documentation
+            "ruby" => {
+                line.starts_with('#')
+                    && !line.starts_with("#!")
+                    && !line.starts_with("# frozen_string_literal:")
+            }
+            "go" => line.starts_with("//"),
+            // Shebangs are not documentation
+            "bash" => line.starts_with('#') && !line.starts_with("#!"),
+            // `#Requires` statements are not documentation
+            "powershell" => {
+                line.starts_with('#')
+                    && !line.starts_with("#!")
+                    && !line.to_ascii_lowercase().starts_with("#requires")
+            }
+            // Haddock comments; `{-# LANGUAGE ... #-}` pragmas are not documentation
+            "haskell" => {
+                line.starts_with("--")
+                    || (line.starts_with("{-") && !line.starts_with("{-#"))
+                    || (line.ends_with("-}") && !line.ends_with("#-}"))
+            }
+            _ => false,
+        };
+
+        if is_doc_comment {
+            doc_lines.push(line.to_string());
+            if line.starts_with("/**") || line.starts_with("\"\"\"") || line.starts_with("'''") {
+                break; // Start of block comment
+            }
+        } else if line.is_empty() {
+            // Allow one empty line
+            if !doc_lines.is_empty() {
+                break;
+            }
+        } else {
+            break;
+        }
+
+        if line_idx == 0 {
+            break;
+        }
+        line_idx -= 1;
+    }
+
+    doc_lines.reverse();
+    doc_lines.join("\n")
+}
+
+/// Keep the first `max_lines` lines of a doc comment, marking the cut with the comment's
+/// own prefix and indentation and closing a block comment that was cut open
+pub(crate) fn cap_doc_comment(doc: &str, max_lines: usize) -> String {
+    let lines: Vec<&str> = doc.lines().collect();
+    if lines.len() <= max_lines {
+        return doc.to_string();
+    }
+    let indent = |line: &str| line[..line.len() - line.trim_start().len()].to_string();
+
+    let kept = &lines[..max_lines];
+    let last_kept = kept.last().copied().unwrap_or("");
+    let prefix = match last_kept.trim_start() {
+        l if l.starts_with("///") => "/// ",
+        l if l.starts_with("//") => "// ",
+        l if l.starts_with('#') => "# ",
+        l if l.starts_with("/*") || l.starts_with('*') => "* ",
+        _ => "",
+    };
+    let mut result = kept.join("\n");
+    result.push('\n');
+    result.push_str(&indent(last_kept));
+    result.push_str(prefix);
+    result.push_str("… (doc truncated)");
+    if let Some(last) = lines.last().filter(|l| l.ends_with("*/")) {
+        result.push('\n');
+        result.push_str(&indent(last));
+        result.push_str("*/");
+    } else if let Some(last) = lines.last().filter(|l| l.ends_with("#>")) {
+        result.push('\n');
+        result.push_str(&indent(last));
+        result.push_str("#>");
+    }
+    result
+}
+
+/// Limit text to a certain number of lines
+fn limit_text(text: &str, max_lines: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= max_lines {
+        text.to_string()
+    } else {
+        let mut result: Vec<&str> = lines[..max_lines].to_vec();
+        result.push("    // ... (truncated)");
+        result.push("}");
+        result.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_service_loads_languages_lazily() {
+        let mut service = CodeNavigationService::new();
+        assert!(service.languages.is_empty());
+        assert!(service.parsers.is_empty());
+
+        service.index_file("/test/lib.rs", "fn main() {}", "rust");
+        assert!(service.languages.contains_key("rust"));
+        assert!(service.parsers.contains_key("rust"));
+        assert!(service.queries.contains_key("rust"));
+        assert!(!service.parsers.contains_key("python"));
+    }
+
+    #[test]
+    fn test_every_language_can_be_loaded() {
+        let mut service = CodeNavigationService::new();
+        for lang_id in [
+            "python",
+            "rust",
+            "go",
+            "c",
+            "cpp",
+            "java",
+            "csharp",
+            "swift",
+            "ruby",
+            "scala",
+            "elixir",
+            "haskell",
+            "ocaml",
+            "ocaml_interface",
+            "bash",
+            "powershell",
+            "objc",
+            "julia",
+            "typescript",
+            "javascript",
+        ] {
+            assert!(service.ensure_language(lang_id), "{}", lang_id);
+            assert!(service.queries.contains_key(lang_id), "{}", lang_id);
+        }
+        assert!(!service.ensure_language("cobol"));
+    }
+
+    /// Lines of `source` holding a valid reference to `symbol`
+    fn reference_lines(lang_id: &str, source: &str, symbol: &str) -> Vec<u64> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&get_language(lang_id).unwrap())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        (1..=source.lines().count() as u64)
+            .filter(|&line| {
+                !CodeNavigationService::validate_reference_at_line(
+                    &tree,
+                    source.as_bytes(),
+                    line,
+                    symbol,
+                    lang_id,
+                    "test",
+                    lang_id,
+                )
+                .is_empty()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_references_csharp() {
+        let source = r#"class Cart {
+    int Total() { return Sum(items); }
+    // Sum of the items
+    string label = "Sum";
+}
+"#;
+        assert_eq!(reference_lines("csharp", source, "Sum"), vec![2]);
+    }
+
+    #[test]
+    fn test_references_swift() {
+        let source = r#"func total() -> Int { 0 }
+let value = total()
+// total
+let label = "total"
+"#;
+        assert_eq!(reference_lines("swift", source, "total"), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_references_ruby() {
+        let source = r#"class Cart
+end
+cart = Cart.new
+# Cart
+label = "Cart"
+"#;
+        assert_eq!(reference_lines("ruby", source, "Cart"), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_references_elixir() {
+        let source = r#"defmodule Cart do
+  def total(items), do: items
+end
+Cart.total([])
+# Cart
+label = "Cart"
+"#;
+        assert_eq!(reference_lines("elixir", source, "Cart"), vec![1, 4]);
+    }
+
+    #[test]
+    fn test_references_haskell() {
+        let source = r#"total :: [Int] -> Int
+total = sum
+value = total []
+-- total
+label = "total"
+"#;
+        assert_eq!(reference_lines("haskell", source, "total"), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_references_ocaml() {
+        let source = r#"let total items = List.length items
+let value = total []
+(* total *)
+let label = "total"
+"#;
+        assert_eq!(reference_lines("ocaml", source, "total"), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_references_bash() {
+        let source = r#"deploy() {
+  echo deploy
+}
+deploy
+# deploy
+echo "deploy"
+"#;
+        assert_eq!(reference_lines("bash", source, "deploy"), vec![1, 4]);
+    }
+
+    #[test]
+    fn test_references_powershell() {
+        let source = r#"function Get-Total { 1 }
+Get-Total
+# Get-Total
+$label = "Get-Total"
+"#;
+        assert_eq!(
+            reference_lines("powershell", source, "Get-Total"),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_get_lang_family() {
+        assert_eq!(CodeNavigationService::get_lang_family("c"), "c_family");
+        assert_eq!(CodeNavigationService::get_lang_family("cpp"), "c_family");
+        assert_eq!(CodeNavigationService::get_lang_family("objc"), "c_family");
+        assert_eq!(CodeNavigationService::get_lang_family("julia"), "julia");
+        assert_eq!(
+            CodeNavigationService::get_lang_family("typescript"),
+            "js_family"
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_family("javascript"),
+            "js_family"
+        );
+        assert_eq!(CodeNavigationService::get_lang_family("python"), "python");
+        assert_eq!(CodeNavigationService::get_lang_family("rust"), "rust");
+        assert_eq!(CodeNavigationService::get_lang_family("go"), "go");
+        assert_eq!(CodeNavigationService::get_lang_family("java"), "java");
+        assert_eq!(CodeNavigationService::get_lang_family("unknown"), "unknown");
+    }
+
+    #[test]
+    fn test_get_lang_id_from_path() {
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.py"),
+            Some("python".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.rs"),
+            Some("rust".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.go"),
+            Some("go".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.c"),
+            Some("c".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.h"),
+            Some("c".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.cpp"),
+            Some("cpp".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.cc"),
+            Some("cpp".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.hpp"),
+            Some("cpp".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.java"),
+            Some("java".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("Program.cs"),
+            Some("csharp".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("View.swift"),
+            Some("swift".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("app/models/user.rb"),
+            Some("ruby".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("Main.scala"),
+            Some("scala".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("cart_test.exs"),
+            Some("elixir".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("Shapes.hs"),
+            Some("haskell".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("tree.ml"),
+            Some("ocaml".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("tree.mli"),
+            Some("ocaml_interface".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("install.sh"),
+            Some("bash".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("Deploy.PS1"),
+            Some("powershell".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("schema.graphql"),
+            Some("graphql".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("api/v1/orders.proto"),
+            Some("protobuf".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("AppDelegate.m"),
+            Some("objc".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("src/Shapes.jl"),
+            Some("julia".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("TodoList.vue"),
+            Some("vue".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("email.html"),
+            Some("html".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("theme.scss"),
+            Some("scss".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("deploy.yml"),
+            Some("yaml".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("Cargo.toml"),
+            Some("toml".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("app/build.gradle"),
+            Some("gradle".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("docs/DESIGN.md"),
+            Some("markdown".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.ts"),
+            Some("typescript".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.tsx"),
+            Some("typescript".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.js"),
+            Some("javascript".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.jsx"),
+            Some("javascript".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.mjs"),
+            Some("javascript".to_string())
+        );
+        assert_eq!(
+            CodeNavigationService::get_lang_id_from_path("test.txt"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_symbol_kind() {
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("function.definition"),
+            "function"
+        );
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("class.definition"),
+            "class"
+        );
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("struct.definition"),
+            "struct"
+        );
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("enum.definition"),
+            "enum"
+        );
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("trait.definition"),
+            "trait"
+        );
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("interface.definition"),
+            "interface"
+        );
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("method.definition"),
+            "method"
+        );
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("type.definition"),
+            "type"
+        );
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("const.definition"),
+            "const"
+        );
+        assert_eq!(
+            CodeNavigationService::get_symbol_kind("static.definition"),
+            "static"
+        );
+        assert_eq!(CodeNavigationService::get_symbol_kind("unknown"), "symbol");
+    }
+
+    #[test]
+    fn test_index_python_file() {
+        let mut service = CodeNavigationService::new();
+
+        let python_code = r#"
+def my_function():
+    pass
+
+class MyClass:
+    def method(self):
+        pass
+"#;
+
+        service.index_file("test.py", python_code, "python");
+
+        // Check that function definition was indexed
+        let func_defs = service.find_definition("my_function", "python");
+        assert!(!func_defs.is_empty(), "Should find my_function definition");
+        assert_eq!(func_defs[0].name, "my_function");
+        assert_eq!(func_defs[0].kind, "function");
+        assert_eq!(func_defs[0].lang_family, "python");
+
+        // Check that class definition was indexed
+        let class_defs = service.find_definition("MyClass", "python");
+        assert!(!class_defs.is_empty(), "Should find MyClass definition");
+        assert_eq!(class_defs[0].name, "MyClass");
+        assert_eq!(class_defs[0].kind, "class");
+    }
+
+    #[test]
+    fn test_index_rust_file() {
+        let mut service = CodeNavigationService::new();
+
+        let rust_code = r#"
+fn my_function() {
+    println!("Hello");
+}
+
+struct MyStruct {
+    field: i32,
+}
+
+enum MyEnum {
+    Variant1,
+    Variant2,
+}
+
+trait MyTrait {
+    fn method(&self);
+}
+
+const MY_CONST: i32 = 42;
+
+type MyType = Vec<i32>;
+"#;
+
+        service.index_file("test.rs", rust_code, "rust");
+
+        // Check function
+        let func_defs = service.find_definition("my_function", "rust");
+        assert!(!func_defs.is_empty(), "Should find my_function");
+        assert_eq!(func_defs[0].kind, "function");
+
+        // Check struct
+        let struct_defs = service.find_definition("MyStruct", "rust");
+        assert!(!struct_defs.is_empty(), "Should find MyStruct");
+        assert_eq!(struct_defs[0].kind, "struct");
+
+        // Check enum
+        let enum_defs = service.find_definition("MyEnum", "rust");
+        assert!(!enum_defs.is_empty(), "Should find MyEnum");
+        assert_eq!(enum_defs[0].kind, "enum");
+
+        // Check trait
+        let trait_defs = service.find_definition("MyTrait", "rust");
+        assert!(!trait_defs.is_empty(), "Should find MyTrait");
+        assert_eq!(trait_defs[0].kind, "trait");
+
+        // Check const
+        let const_defs = service.find_definition("MY_CONST", "rust");
+        assert!(!const_defs.is_empty(), "Should find MY_CONST");
+        assert_eq!(const_defs[0].kind, "const");
+
+        // Check type alias
+        let type_defs = service.find_definition("MyType", "rust");
+        assert!(!type_defs.is_empty(), "Should find MyType");
+        assert_eq!(type_defs[0].kind, "type");
+    }
+
+    #[test]
+    fn test_index_typescript_file() {
+        let mut service = CodeNavigationService::new();
+
+        let ts_code = r#"
+function myFunction() {
+    console.log("Hello");
+}
+
+class MyClass {
+    method() {}
+}
+
+interface MyInterface {
+    prop: string;
+}
+
+type MyType = {
+    field: number;
+};
+
+enum MyEnum {
+    A,
+    B,
+}
+
+const MY_CONST = 42;
+"#;
+
+        service.index_file("test.ts", ts_code, "typescript");
+
+        // Check function
+        let func_defs = service.find_definition("myFunction", "js_family");
+        assert!(!func_defs.is_empty(), "Should find myFunction");
+        assert_eq!(func_defs[0].kind, "function");
+
+        // Check class
+        let class_defs = service.find_definition("MyClass", "js_family");
+        assert!(!class_defs.is_empty(), "Should find MyClass");
+        assert_eq!(class_defs[0].kind, "class");
+
+        // Check interface
+        let interface_defs = service.find_definition("MyInterface", "js_family");
+        assert!(!interface_defs.is_empty(), "Should find MyInterface");
+        assert_eq!(interface_defs[0].kind, "interface");
+
+        // Check type
+        let type_defs = service.find_definition("MyType", "js_family");
+        assert!(!type_defs.is_empty(), "Should find MyType");
+        assert_eq!(type_defs[0].kind, "type");
+
+        // Check enum
+        let enum_defs = service.find_definition("MyEnum", "js_family");
+        assert!(!enum_defs.is_empty(), "Should find MyEnum");
+        assert_eq!(enum_defs[0].kind, "enum");
+    }
+
+    #[test]
+    fn test_index_go_file() {
+        let mut service = CodeNavigationService::new();
+
+        let go_code = r#"
+package main
+
+func myFunction() {
+    fmt.Println("Hello")
+}
+
+type MyStruct struct {
+    Field int
+}
+
+func (m *MyStruct) Method() {}
+"#;
+
+        service.index_file("test.go", go_code, "go");
+
+        // Check function
+        let func_defs = service.find_definition("myFunction", "go");
+        assert!(!func_defs.is_empty(), "Should find myFunction");
+        assert_eq!(func_defs[0].kind, "function");
+
+        // Check struct type
+        let type_defs = service.find_definition("MyStruct", "go");
+        assert!(!type_defs.is_empty(), "Should find MyStruct");
+        assert_eq!(type_defs[0].kind, "type");
+
+        // Check method
+        let method_defs = service.find_definition("Method", "go");
+        assert!(!method_defs.is_empty(), "Should find Method");
+        assert_eq!(method_defs[0].kind, "method");
+    }
+
+    #[test]
+    fn test_find_definition_filters_by_lang_family() {
+        let mut service = CodeNavigationService::new();
+
+        // Index same symbol name in different languages
+        let python_code = "def test_func(): pass";
+        let rust_code = "fn test_func() {}";
+
+        service.index_file("test.py", python_code, "python");
+        service.index_file("test.rs", rust_code, "rust");
+
+        // Should only find Python definition
+        let python_defs = service.find_definition("test_func", "python");
+        assert_eq!(python_defs.len(), 1);
+        assert_eq!(python_defs[0].file_path, "test.py");
+
+        // Should only find Rust definition
+        let rust_defs = service.find_definition("test_func", "rust");
+        assert_eq!(rust_defs.len(), 1);
+        assert_eq!(rust_defs[0].file_path, "test.rs");
+
+        // Should find nothing for wrong lang family
+        let js_defs = service.find_definition("test_func", "js_family");
+        assert!(js_defs.is_empty());
+    }
+
+    #[test]
+    fn test_search_symbols_by_mode() {
+        let mut service = CodeNavigationService::new();
+        service.index_file(
+            "rewrite.ts",
+            "class MessageRewriter {}\nfunction message_rewriter() {}\nfunction mapRows() {}\n",
+            "typescript",
+        );
+        service.index_file("rewrite.py", "class MessageRewriter: pass\n", "python");
+
+        let names = |mode: MatchMode, family: Option<&str>| -> Vec<String> {
+            service
+                .search_symbols("MR", family, mode, 10)
+                .into_iter()
+                .map(|s| s.name)
+                .collect()
+        };
+        assert!(names(MatchMode::Words, None).is_empty());
+        assert_eq!(
+            names(MatchMode::Initials, Some("js_family")),
+            vec!["mapRows", "MessageRewriter", "message_rewriter"]
+        );
+
+        let words = service.search_symbols("messageRewriter", None, MatchMode::Words, 10);
+        assert_eq!(words.len(), 3);
+        assert_eq!(words[0].name, "MessageRewriter");
+        assert_eq!(
+            service
+                .search_symbols("messageRewriter", None, MatchMode::Words, 1)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_find_definition_by_qualified_name() {
+        let mut service = CodeNavigationService::new();
+        let rust_code =
+            "struct Foo<T>(T);\n\nimpl<T> Foo<T> {\n    fn bar(&self) {}\n}\n\nfn bar() {}\n";
+        service.index_file("foo.rs", rust_code, "rust");
+
+        assert_eq!(service.find_definition("bar", "rust").len(), 2);
+        for query in ["Foo::bar", "Foo.bar", "Foo<T>::bar"] {
+            let defs = service.find_definition(query, "rust");
+            assert_eq!(defs.len(), 1, "{}", query);
+            assert_eq!(defs[0].qualified_name.as_deref(), Some("Foo::bar"));
+            assert_eq!(defs[0].start_line, 4);
+        }
+        assert!(service.find_definition("Other::bar", "rust").is_empty());
+
+        let java_code = "package com.acme;\n\nclass Widget {\n    void resize() {}\n}\n";
+        service.index_file("Widget.java", java_code, "java");
+        let defs = service.find_definition("Widget.resize", "java");
+        assert_eq!(defs.len(), 1);
+        assert_eq!(
+            defs[0].qualified_name.as_deref(),
+            Some("com.acme.Widget#resize")
+        );
+    }
+
+    #[test]
+    fn test_index_file_honors_language_config() {
+        let mut service = CodeNavigationService::new();
+        service.language_config = LanguageConfig {
+            extensions: [("pyi".to_string(), "python".to_string())].into(),
+            disabled: ["cpp".to_string()].into(),
+        };
+
+        service.index_file("stubs/os.pyi", "def getcwd() -> str: ...\n", "unknown");
+        assert_eq!(service.find_definition("getcwd", "python").len(), 1);
+
+        service.index_file("vendor/lib.cpp", "int answer() { return 42; }\n", "cpp");
+        assert!(service.find_definition("answer", "cpp").is_empty());
+    }
+
+    #[test]
+    fn test_clear_file() {
+        let mut service = CodeNavigationService::new();
+
+        let code = "def my_function(): pass";
+        service.index_file("test.py", code, "python");
+
+        // Verify it was indexed
+        let defs = service.find_definition("my_function", "python");
+        assert!(!defs.is_empty());
+
+        // Clear the file
+        service.clear_file("test.py");
+
+        // Verify it's gone
+        let defs = service.find_definition("my_function", "python");
+        assert!(defs.is_empty());
+    }
+
+    #[test]
+    fn test_clear_all() {
+        let mut service = CodeNavigationService::new();
 
-        // Check that queries are registered
-        assert!(service.queries.contains_key("python"));
-        assert!(service.queries.contains_key("rust"));
-        assert!(service.queries.contains_key("go"));
+        service.index_file("test1.py", "def func1(): pass", "python");
+        service.index_file("test2.py", "def func2(): pass", "python");
+
+        // Verify both were indexed
+        assert!(!service.find_definition("func1", "python").is_empty());
+        assert!(!service.find_definition("func2", "python").is_empty());
+
+        // Clear all
+        service.clear_all();
+
+        // Verify both are gone
+        assert!(service.find_definition("func1", "python").is_empty());
+        assert!(service.find_definition("func2", "python").is_empty());
+    }
+
+    #[test]
+    fn test_reindex_file_updates_symbols() {
+        let mut service = CodeNavigationService::new();
+
+        // Index initial version
+        service.index_file("test.py", "def old_func(): pass", "python");
+        assert!(!service.find_definition("old_func", "python").is_empty());
+        assert!(service.find_definition("new_func", "python").is_empty());
+
+        // Reindex with new content
+        service.index_file("test.py", "def new_func(): pass", "python");
+
+        // Old symbol should be gone, new should exist
+        assert!(service.find_definition("old_func", "python").is_empty());
+        assert!(!service.find_definition("new_func", "python").is_empty());
+    }
+
+    #[test]
+    fn test_symbol_info_line_numbers() {
+        let mut service = CodeNavigationService::new();
+
+        let code = r#"
+def func_line_2():
+    pass
+
+def func_line_5():
+    pass
+"#;
+
+        service.index_file("test.py", code, "python");
+
+        let defs = service.find_definition("func_line_2", "python");
+        assert!(!defs.is_empty());
+        assert_eq!(defs[0].start_line, 2);
+
+        let defs = service.find_definition("func_line_5", "python");
+        assert!(!defs.is_empty());
+        assert_eq!(defs[0].start_line, 5);
+    }
+
+    #[test]
+    fn test_symbol_info_serialization() {
+        let symbol = SymbolInfo {
+            name: "test_func".to_string(),
+            kind: "function".to_string(),
+            file_path: "/path/to/file.py".to_string(),
+            lang_family: "python".to_string(),
+            start_line: 10,
+            start_column: 5,
+            end_line: 10,
+            end_column: 14,
+            qualified_name: None,
+        };
+
+        let json = serde_json::to_string(&symbol).unwrap();
+        assert!(json.contains("\"name\":\"test_func\""));
+        assert!(json.contains("\"kind\":\"function\""));
+        assert!(json.contains("\"start_line\":10"));
+
+        // Deserialize back
+        let parsed: SymbolInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.name, "test_func");
+        assert_eq!(parsed.kind, "function");
+        assert_eq!(parsed.start_line, 10);
+    }
+
+    #[test]
+    fn test_index_c_file() {
+        let mut service = CodeNavigationService::new();
+
+        let c_code = r#"
+void my_function() {
+    printf("Hello\n");
+}
+
+struct MyStruct {
+    int field;
+};
+"#;
+
+        service.index_file("test.c", c_code, "c");
+
+        // Check function
+        let func_defs = service.find_definition("my_function", "c_family");
+        assert!(!func_defs.is_empty(), "Should find my_function in C");
+        assert_eq!(func_defs[0].kind, "function");
+
+        // Check struct
+        let struct_defs = service.find_definition("MyStruct", "c_family");
+        assert!(!struct_defs.is_empty(), "Should find MyStruct in C");
+        assert_eq!(struct_defs[0].kind, "struct");
+    }
+
+    #[test]
+    fn test_index_java_file() {
+        let mut service = CodeNavigationService::new();
+
+        let java_code = r#"
+public class MyClass {
+    public void myMethod() {
+        System.out.println("Hello");
+    }
+}
+
+interface MyInterface {
+    void method();
+}
+"#;
+
+        service.index_file("Test.java", java_code, "java");
+
+        // Check class
+        let class_defs = service.find_definition("MyClass", "java");
+        assert!(!class_defs.is_empty(), "Should find MyClass in Java");
+        assert_eq!(class_defs[0].kind, "class");
+
+        // Check method
+        let method_defs = service.find_definition("myMethod", "java");
+        assert!(!method_defs.is_empty(), "Should find myMethod in Java");
+        assert_eq!(method_defs[0].kind, "method");
+
+        // Check interface
+        let interface_defs = service.find_definition("MyInterface", "java");
+        assert!(
+            !interface_defs.is_empty(),
+            "Should find MyInterface in Java"
+        );
+        assert_eq!(interface_defs[0].kind, "interface");
+    }
+
+    #[test]
+    fn test_file_definitions_reverse_index() {
+        let mut service = CodeNavigationService::new();
+
+        service.index_file("test.py", "def func1(): pass\ndef func2(): pass", "python");
+
+        // Check reverse index has the file
+        assert!(service.index.file_definitions.contains_key("test.py"));
+
+        // Check it contains both function names
+        let names = service.index.file_definitions.get("test.py").unwrap();
+        assert!(names.contains("func1"));
+        assert!(names.contains("func2"));
+    }
+
+    #[test]
+    fn test_persisted_index_serialization() {
+        let mut definitions = HashMap::new();
+        definitions.insert(
+            "test_func".to_string(),
+            vec![SymbolInfo {
+                name: "test_func".to_string(),
+                kind: "function".to_string(),
+                file_path: "test.py".to_string(),
+                lang_family: "python".to_string(),
+                start_line: 1,
+                start_column: 1,
+                end_line: 1,
+                end_column: 10,
+                qualified_name: None,
+            }],
+        );
+
+        let mut file_definitions = HashMap::new();
+        let mut names = HashSet::new();
+        names.insert("test_func".to_string());
+        file_definitions.insert("test.py".to_string(), names);
+
+        let persisted = PersistedIndex {
+            version: INDEX_VERSION,
+            root_path: "/project".to_string(),
+            last_updated: 1700000000,
+            file_timestamps: HashMap::new(),
+            definitions,
+            file_definitions,
+        };
+
+        let json = serde_json::to_string(&persisted).unwrap();
+        assert!(json.contains(&format!("\"version\":{}", INDEX_VERSION)));
+        assert!(json.contains("\"root_path\":\"/project\""));
+
+        // Deserialize back
+        let parsed: PersistedIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.version, INDEX_VERSION);
+        assert_eq!(parsed.root_path, "/project");
+        assert!(parsed.definitions.contains_key("test_func"));
+    }
+
+    #[test]
+    fn test_index_metadata_serialization() {
+        let metadata = IndexMetadata {
+            version: INDEX_VERSION,
+            root_path: "/project".to_string(),
+            last_updated: 1700000000,
+            file_count: 10,
+            definition_count: 50,
+            file_timestamps: HashMap::new(),
+        };
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert!(json.contains("\"file_count\":10"));
+        assert!(json.contains("\"definition_count\":50"));
+
+        let parsed: IndexMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.file_count, 10);
+        assert_eq!(parsed.definition_count, 50);
+    }
+
+    #[test]
+    fn test_get_project_hash() {
+        let hash1 = get_project_hash("/project/a");
+        let hash2 = get_project_hash("/project/b");
+        let hash3 = get_project_hash("/project/a"); // Same as hash1
+
+        // Different paths should have different hashes
+        assert_ne!(hash1, hash2);
+
+        // Same path should have same hash
+        assert_eq!(hash1, hash3);
+
+        // Hash should be 16 characters (8 bytes in hex)
+        assert_eq!(hash1.len(), 16);
+    }
+
+    // ============================================================================
+    // Code Summarization Tests
+    // ============================================================================
+
+    #[tokio::test]
+    async fn test_summarize_typescript_code() {
+        let ts_code = r#"
+/**
+ * A sample service for processing data
+ */
+export class DataProcessor {
+    private data: Map<string, number>;
+
+    constructor() {
+        this.data = new Map();
+    }
+
+    /**
+     * Process incoming data
+     * @param input The input to process
+     * @returns The processed result
+     */
+    async process(input: string): Promise<number> {
+        // Some long implementation
+        const result = input.length * 2;
+        for (let i = 0; i < 100; i++) {
+            console.log(i);
+        }
+        return result;
     }
 
-    #[test]
-    fn test_get_lang_family() {
-        assert_eq!(CodeNavigationService::get_lang_family("c"), "c_family");
-        assert_eq!(CodeNavigationService::get_lang_family("cpp"), "c_family");
-        assert_eq!(
-            CodeNavigationService::get_lang_family("typescript"),
-            "js_family"
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_family("javascript"),
-            "js_family"
-        );
-        assert_eq!(CodeNavigationService::get_lang_family("python"), "python");
-        assert_eq!(CodeNavigationService::get_lang_family("rust"), "rust");
-        assert_eq!(CodeNavigationService::get_lang_family("go"), "go");
-        assert_eq!(CodeNavigationService::get_lang_family("java"), "java");
-        assert_eq!(CodeNavigationService::get_lang_family("unknown"), "unknown");
+    private helperMethod(): void {
+        console.log('helper');
     }
+}
 
-    #[test]
-    fn test_get_lang_id_from_path() {
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.py"),
-            Some("python".to_string())
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.rs"),
-            Some("rust".to_string())
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.go"),
-            Some("go".to_string())
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.c"),
-            Some("c".to_string())
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.h"),
-            Some("c".to_string())
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.cpp"),
-            Some("cpp".to_string())
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.cc"),
-            Some("cpp".to_string())
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.hpp"),
-            Some("cpp".to_string())
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.java"),
-            Some("java".to_string())
-        );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.ts"),
-            Some("typescript".to_string())
+interface DataInput {
+    id: string;
+    value: number;
+    timestamp: Date;
+}
+
+const MAX_SIZE = 1000;
+
+export function processAll(items: DataInput[]): void {
+    items.forEach(item => console.log(item));
+}
+"#;
+
+        let result = summarize_code_content(
+            ts_code.to_string(),
+            "typescript".to_string(),
+            "test.ts".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            result.success,
+            "Should successfully summarize TypeScript code"
         );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.tsx"),
-            Some("typescript".to_string())
+        assert!(
+            result.summary.contains("[COMPRESSED:"),
+            "Should have compression marker"
         );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.js"),
-            Some("javascript".to_string())
+        assert!(
+            result.summary.contains("DataProcessor"),
+            "Should include class name"
         );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.jsx"),
-            Some("javascript".to_string())
+        assert!(
+            result.summary.contains("process("),
+            "Should include method signature"
         );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.mjs"),
-            Some("javascript".to_string())
+        assert!(
+            result.summary.contains("DataInput"),
+            "Should include interface"
         );
-        assert_eq!(
-            CodeNavigationService::get_lang_id_from_path("test.txt"),
-            None
+        assert!(result.summary.contains("MAX_SIZE"), "Should include const");
+        // Function body should be replaced with { ... }
+        assert!(
+            result.summary.contains("{ ... }"),
+            "Function bodies should be summarized"
         );
     }
 
-    #[test]
-    fn test_get_symbol_kind() {
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("function.definition"),
-            "function"
-        );
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("class.definition"),
-            "class"
-        );
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("struct.definition"),
-            "struct"
-        );
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("enum.definition"),
-            "enum"
-        );
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("trait.definition"),
-            "trait"
-        );
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("interface.definition"),
-            "interface"
+    #[tokio::test]
+    async fn test_summarize_rust_code() {
+        let rust_code = r#"
+/// A struct for holding configuration
+pub struct Config {
+    pub name: String,
+    pub value: i32,
+}
+
+impl Config {
+    /// Create a new config
+    pub fn new(name: String, value: i32) -> Self {
+        Self { name, value }
+    }
+
+    pub fn process(&self) -> String {
+        format!("{}: {}", self.name, self.value)
+    }
+}
+
+/// Process data with the given config
+pub fn process_data(config: &Config) -> Result<(), String> {
+    // Long implementation
+    println!("Processing...");
+    Ok(())
+}
+
+const MAX_RETRIES: u32 = 3;
+"#;
+
+        let result = summarize_code_content(
+            rust_code.to_string(),
+            "rust".to_string(),
+            "test.rs".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success, "Should successfully summarize Rust code");
+        assert!(
+            result.summary.contains("Config"),
+            "Should include struct name"
         );
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("method.definition"),
-            "method"
+        assert!(
+            result.summary.contains("process_data"),
+            "Should include function name"
         );
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("type.definition"),
-            "type"
+        assert!(
+            result.summary.contains("MAX_RETRIES"),
+            "Should include const"
         );
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("const.definition"),
-            "const"
+    }
+
+    #[tokio::test]
+    async fn test_summarize_python_code() {
+        let python_code = r#"
+"""A module for data processing."""
+
+class DataProcessor:
+    """Processes data efficiently."""
+
+    def __init__(self, config: dict):
+        self.config = config
+        self.data = []
+
+    def process(self, input_data: list) -> list:
+        """Process the input data."""
+        result = []
+        for item in input_data:
+            result.append(item * 2)
+        return result
+
+def helper_function(x: int) -> int:
+    """A helper function."""
+    return x + 1
+
+MAX_SIZE = 1000
+"#;
+
+        let result = summarize_code_content(
+            python_code.to_string(),
+            "python".to_string(),
+            "test.py".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success, "Should successfully summarize Python code");
+        assert!(
+            result.summary.contains("DataProcessor"),
+            "Should include class name"
         );
-        assert_eq!(
-            CodeNavigationService::get_symbol_kind("static.definition"),
-            "static"
+        assert!(
+            result.summary.contains("process"),
+            "Should include method name"
+        );
+        assert!(
+            result.summary.contains("helper_function"),
+            "Should include function name"
         );
-        assert_eq!(CodeNavigationService::get_symbol_kind("unknown"), "symbol");
     }
 
-    #[test]
-    fn test_index_python_file() {
-        let mut service = CodeNavigationService::new();
+    #[tokio::test]
+    async fn test_summarize_unsupported_language() {
+        let markdown_code = r#"
+# Header
 
-        let python_code = r#"
-def my_function():
-    pass
+Some text content.
 
-class MyClass:
-    def method(self):
-        pass
+- Item 1
+- Item 2
 "#;
 
-        service.index_file("test.py", python_code, "python");
-
-        // Check that function definition was indexed
-        let func_defs = service.find_definition("my_function", "python");
-        assert!(!func_defs.is_empty(), "Should find my_function definition");
-        assert_eq!(func_defs[0].name, "my_function");
-        assert_eq!(func_defs[0].kind, "function");
-        assert_eq!(func_defs[0].lang_family, "python");
+        let result = summarize_code_content(
+            markdown_code.to_string(),
+            "markdown".to_string(),
+            "test.md".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
 
-        // Check that class definition was indexed
-        let class_defs = service.find_definition("MyClass", "python");
-        assert!(!class_defs.is_empty(), "Should find MyClass definition");
-        assert_eq!(class_defs[0].name, "MyClass");
-        assert_eq!(class_defs[0].kind, "class");
+        // Should return success=false but not error, keeping original content
+        assert!(!result.success, "Should indicate unsupported language");
+        assert_eq!(
+            result.summary, markdown_code,
+            "Should return original content for unsupported language"
+        );
+        assert_eq!(
+            result.skipped_reason.as_deref(),
+            Some("unsupported language")
+        );
     }
 
-    #[test]
-    fn test_index_rust_file() {
-        let mut service = CodeNavigationService::new();
-
-        let rust_code = r#"
-fn my_function() {
-    println!("Hello");
+    #[tokio::test]
+    async fn test_preview_summary_maps_ranges() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("lib.rs");
+        fs::write(
+            &path,
+            r#"/// Adds numbers
+pub fn add(a: i32, b: i32) -> i32 {
+    let sum = a + b;
+    sum
 }
 
-struct MyStruct {
-    field: i32,
+fn helper() {
+    println!("a");
+    println!("b");
 }
+"#,
+        )
+        .unwrap();
 
-enum MyEnum {
-    Variant1,
-    Variant2,
+        let preview = preview_summary(path.to_string_lossy().to_string(), None)
+            .await
+            .unwrap();
+        assert!(preview.summary.success);
+        let ranges: Vec<(&str, usize, usize)> = preview
+            .segments
+            .iter()
+            .map(|s| (s.name.as_str(), s.original_start_line, s.original_end_line))
+            .collect();
+        assert_eq!(ranges, vec![("add", 2, 5), ("helper", 7, 10)]);
+
+        let summary_lines: Vec<&str> = preview.summary.summary.lines().collect();
+        for segment in &preview.segments {
+            assert_eq!(
+                summary_lines[segment.summary_start_line - 1..segment.summary_end_line].join("\n"),
+                segment.replacement
+            );
+        }
+        assert_eq!(preview.segments[1].replacement, "fn helper() { ... }");
+    }
+
+    #[tokio::test]
+    async fn test_preview_summary_unsupported_file() {
+        assert!(preview_summary("notes.md".to_string(), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_summarize_go_code() {
+        let go_code = r#"
+package main
+
+// Config holds configuration values
+type Config struct {
+    Name  string
+    Value int
 }
 
-trait MyTrait {
-    fn method(&self);
+// NewConfig creates a new Config
+func NewConfig(name string, value int) *Config {
+    return &Config{
+        Name:  name,
+        Value: value,
+    }
 }
 
-const MY_CONST: i32 = 42;
+// Process handles the main logic
+func (c *Config) Process() error {
+    fmt.Println(c.Name)
+    return nil
+}
 
-type MyType = Vec<i32>;
+const MaxRetries = 3
 "#;
 
-        service.index_file("test.rs", rust_code, "rust");
+        let result = summarize_code_content(
+            go_code.to_string(),
+            "go".to_string(),
+            "main.go".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
 
-        // Check function
-        let func_defs = service.find_definition("my_function", "rust");
-        assert!(!func_defs.is_empty(), "Should find my_function");
-        assert_eq!(func_defs[0].kind, "function");
+        assert!(result.success, "Should successfully summarize Go code");
+        assert!(
+            result.summary.contains("Config"),
+            "Should include type name"
+        );
+        assert!(
+            result.summary.contains("NewConfig"),
+            "Should include function name"
+        );
+        assert!(
+            result.summary.contains("Process"),
+            "Should include method name"
+        );
+    }
 
-        // Check struct
-        let struct_defs = service.find_definition("MyStruct", "rust");
-        assert!(!struct_defs.is_empty(), "Should find MyStruct");
-        assert_eq!(struct_defs[0].kind, "struct");
+    #[tokio::test]
+    async fn test_summarize_csharp_code() {
+        let csharp_code = r#"
+namespace Shop.Api;
+
+/// Handles user requests
+[ApiController]
+[Route("api/[controller]")]
+public class UsersController : ControllerBase
+{
+    private readonly IUserStore _store;
+
+    [Required]
+    public string Region { get; set; } = "eu";
+
+    public UsersController(IUserStore store)
+    {
+        _store = store;
+    }
 
-        // Check enum
-        let enum_defs = service.find_definition("MyEnum", "rust");
-        assert!(!enum_defs.is_empty(), "Should find MyEnum");
-        assert_eq!(enum_defs[0].kind, "enum");
+    [HttpGet("{id}")]
+    public async Task<ActionResult<User>> Get(int id)
+    {
+        var user = await _store.Find(id);
+        if (user == null)
+        {
+            return NotFound();
+        }
+        return user;
+    }
 
-        // Check trait
-        let trait_defs = service.find_definition("MyTrait", "rust");
-        assert!(!trait_defs.is_empty(), "Should find MyTrait");
-        assert_eq!(trait_defs[0].kind, "trait");
+    public int Count => _store.Count();
+}
 
-        // Check const
-        let const_defs = service.find_definition("MY_CONST", "rust");
-        assert!(!const_defs.is_empty(), "Should find MY_CONST");
-        assert_eq!(const_defs[0].kind, "const");
+public interface IUserStore
+{
+    Task<User?> Find(int id);
+    int Count();
+}
 
-        // Check type alias
-        let type_defs = service.find_definition("MyType", "rust");
-        assert!(!type_defs.is_empty(), "Should find MyType");
-        assert_eq!(type_defs[0].kind, "type");
+public record User(int Id, string Name);
+"#;
+
+        let result = summarize_code_content(
+            csharp_code.to_string(),
+            "csharp".to_string(),
+            "UsersController.cs".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success, "Should successfully summarize C# code");
+        let summary = &result.summary;
+        assert!(summary.contains("/// Handles user requests"), "{}", summary);
+        assert!(
+            summary.contains("[ApiController]\n[Route(\"api/[controller]\")]\npublic class UsersController : ControllerBase {"),
+            "{}",
+            summary
+        );
+        // The brace inside the attribute's string is not taken for the body
+        assert!(
+            summary.contains(
+                "[HttpGet(\"{id}\")]\npublic async Task<ActionResult<User>> Get(int id) { ... }"
+            ),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("[Required]\npublic string Region { get; set; } = \"eu\";"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("public int Count => ...;"), "{}", summary);
+        assert!(
+            summary.contains("public interface IUserStore"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("public record User(int Id, string Name);"));
+        assert!(!summary.contains("NotFound()"), "{}", summary);
     }
 
     #[test]
-    fn test_index_typescript_file() {
-        let mut service = CodeNavigationService::new();
+    fn test_split_csharp_attributes() {
+        let (attributes, rest) =
+            split_csharp_attributes("[Obsolete(\"use ]\")] [Flags]\n  public enum E { A }");
+        assert_eq!(attributes, vec!["[Obsolete(\"use ]\")]", "[Flags]"]);
+        assert_eq!(rest, "public enum E { A }");
+        assert_eq!(
+            csharp_signature("public void Run(int[] xs)\n{\n    Go();\n}", false),
+            "public void Run(int[] xs) { ... }"
+        );
+    }
 
-        let ts_code = r#"
-function myFunction() {
-    console.log("Hello");
+    #[tokio::test]
+    async fn test_summarize_swift_code() {
+        let swift_code = r#"
+import Foundation
+
+/// Loads users from the API
+@MainActor
+final class UserStore: ObservableObject {
+    @Published private(set) var users: [User] = []
+    let client: APIClient
+
+    init(client: APIClient) {
+        self.client = client
+    }
+
+    func load(page: Int = 1, onDone: @escaping () -> Void = {}) async throws -> [User] {
+        let result = try await client.get("/users?page=\(page)")
+        users = result
+        onDone()
+        return result
+    }
 }
 
-class MyClass {
-    method() {}
+struct User: Codable {
+    let id: Int
+    var name: String
 }
 
-interface MyInterface {
-    prop: string;
+enum Role {
+    case admin
+    case member(since: Date)
 }
 
-type MyType = {
-    field: number;
-};
+protocol Cache {
+    func get(_ key: String) -> Data?
+}
 
-enum MyEnum {
-    A,
-    B,
+extension User {
+    var displayName: String {
+        name.capitalized
+    }
 }
 
-const MY_CONST = 42;
+func greet(_ user: User) -> String {
+    return "Hello, {\(user.name)}"
+}
 "#;
 
-        service.index_file("test.ts", ts_code, "typescript");
+        let result = summarize_code_content(
+            swift_code.to_string(),
+            "swift".to_string(),
+            "UserStore.swift".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
 
-        // Check function
-        let func_defs = service.find_definition("myFunction", "js_family");
-        assert!(!func_defs.is_empty(), "Should find myFunction");
-        assert_eq!(func_defs[0].kind, "function");
+        assert!(result.success, "Should successfully summarize Swift code");
+        let summary = &result.summary;
+        assert!(
+            summary.contains("/// Loads users from the API"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("final class UserStore: ObservableObject {"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("@Published private(set) var users: [User] = []"),
+            "{}",
+            summary
+        );
+        // The closure default sits inside the parameter list, not the body
+        assert!(
+            summary.contains(
+                "func load(page: Int = 1, onDone: @escaping () -> Void = {}) async throws -> [User] { ... }"
+            ),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("init(client: APIClient) { ... }"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("struct User: Codable {"), "{}", summary);
+        assert!(summary.contains("case member(since: Date)"), "{}", summary);
+        assert!(summary.contains("protocol Cache {"), "{}", summary);
+        assert!(
+            summary.contains("var displayName: String { ... }"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("func greet(_ user: User) -> String { ... }"),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("try await"), "{}", summary);
+    }
 
-        // Check class
-        let class_defs = service.find_definition("MyClass", "js_family");
-        assert!(!class_defs.is_empty(), "Should find MyClass");
-        assert_eq!(class_defs[0].kind, "class");
+    #[test]
+    fn test_is_swift_member() {
+        assert!(is_swift_member("@Published private(set) var x = 0"));
+        assert!(is_swift_member("public static func make() -> Self"));
+        assert!(is_swift_member("case a, b"));
+        assert!(is_swift_member("class var shared: Self"));
+        assert!(!is_swift_member("self.client = client"));
+        assert!(!is_swift_member("return result"));
+    }
 
-        // Check interface
-        let interface_defs = service.find_definition("MyInterface", "js_family");
-        assert!(!interface_defs.is_empty(), "Should find MyInterface");
-        assert_eq!(interface_defs[0].kind, "interface");
+    #[tokio::test]
+    async fn test_summarize_ruby_code() {
+        let ruby_code = r##"# frozen_string_literal: true
 
-        // Check type
-        let type_defs = service.find_definition("MyType", "js_family");
-        assert!(!type_defs.is_empty(), "Should find MyType");
-        assert_eq!(type_defs[0].kind, "type");
+# A registered user
+class User < ApplicationRecord
+  ROLES = %w[admin member].freeze
 
-        // Check enum
-        let enum_defs = service.find_definition("MyEnum", "js_family");
-        assert!(!enum_defs.is_empty(), "Should find MyEnum");
-        assert_eq!(enum_defs[0].kind, "enum");
+  has_many :posts, dependent: :destroy
+  validates :email, presence: true
+
+  # Find by email, ignoring case
+  def self.find_by_email(email)
+    where("lower(email) = ?", email.downcase).first
+  end
+
+  def full_name = "#{first_name} #{last_name}"
+
+  def publish(post,
+              notify: true) # sends mail
+    post.update!(published: true)
+    PostMailer.published(post).deliver_later if notify
+  end
+
+  private
+
+  def normalize_email
+    self.email = email.strip
+  end
+end
+
+module Billing
+  TAX_RATE = 0.2
+
+  def total(amount)
+    amount * (1 + TAX_RATE)
+  end
+end
+"##;
+
+        let result = summarize_code_content(
+            ruby_code.to_string(),
+            "ruby".to_string(),
+            "user.rb".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success, "Should successfully summarize Ruby code");
+        let summary = &result.summary;
+        assert!(
+            summary.contains("# A registered user\nclass User < ApplicationRecord"),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("frozen_string_literal"), "{}", summary);
+        assert!(
+            summary.contains("  has_many :posts, dependent: :destroy"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("  def normalize_email ... end"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("  private\n"), "{}", summary);
+        // Methods are listed with their doc comments and without bodies
+        assert!(
+            summary.contains("# Find by email, ignoring case\ndef self.find_by_email(email)"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("def publish(post,\n              notify: true)"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("module Billing"), "{}", summary);
+        assert!(summary.contains("TAX_RATE = 0.2"), "{}", summary);
+        assert!(!summary.contains("deliver_later"), "{}", summary);
+        assert!(!summary.contains("downcase"), "{}", summary);
     }
 
     #[test]
-    fn test_index_go_file() {
-        let mut service = CodeNavigationService::new();
-
-        let go_code = r#"
-package main
+    fn test_ruby_signature() {
+        assert_eq!(ruby_signature("def a; 1; end"), "def a");
+        assert_eq!(ruby_signature("def run(x) # go\n  x\nend"), "def run(x)");
+        assert_eq!(
+            ruby_signature("def name = \"#{first} #{last}\""),
+            "def name = \"#{first} #{last}\""
+        );
+        assert!(opens_ruby_block("validate do |record|"));
+        assert!(opens_ruby_block("class << self"));
+        assert!(!opens_ruby_block("def x = 1"));
+        assert!(!opens_ruby_block(
+            "scope :active, -> { where(active: true) }"
+        ));
+    }
 
-func myFunction() {
-    fmt.Println("Hello")
+    #[tokio::test]
+    async fn test_summarize_scala_code() {
+        let scala_code = r#"
+package shop
+
+/** A customer order */
+final case class Order(
+    id: Long,
+    items: List[Item] = Nil,
+    note: Option[String] = None
+) {
+  def total: BigDecimal = items.map(_.price).sum
+
+  def isEmpty: Boolean = {
+    items.isEmpty
+  }
 }
 
-type MyStruct struct {
-    Field int
+case class Item(sku: String, price: BigDecimal)
+
+trait Repository[A] {
+  def find(id: Long): Option[A]
+  def save(a: A): Unit = {
+    println(s"saving $a")
+  }
 }
 
-func (m *MyStruct) Method() {}
-"#;
+object OrderService extends Repository[Order] {
+  private val cache = scala.collection.mutable.Map.empty[Long, Order]
 
-        service.index_file("test.go", go_code, "go");
+  override def find(id: Long): Option[Order] =
+    cache.get(id)
 
-        // Check function
-        let func_defs = service.find_definition("myFunction", "go");
-        assert!(!func_defs.is_empty(), "Should find myFunction");
-        assert_eq!(func_defs[0].kind, "function");
+  def handler: Order => Unit = order => {
+    cache.update(order.id, order)
+  }
+}
+"#;
 
-        // Check struct type
-        let type_defs = service.find_definition("MyStruct", "go");
-        assert!(!type_defs.is_empty(), "Should find MyStruct");
-        assert_eq!(type_defs[0].kind, "type");
+        let result = summarize_code_content(
+            scala_code.to_string(),
+            "scala".to_string(),
+            "Order.scala".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
 
-        // Check method
-        let method_defs = service.find_definition("Method", "go");
-        assert!(!method_defs.is_empty(), "Should find Method");
-        assert_eq!(method_defs[0].kind, "method");
+        assert!(result.success, "Should successfully summarize Scala code");
+        let summary = &result.summary;
+        // Case class parameter lists are the data model and stay whole
+        assert!(
+            summary.contains("/** A customer order */\nfinal case class Order(\n    id: Long,\n    items: List[Item] = Nil,\n    note: Option[String] = None\n) {"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("case class Item(sku: String, price: BigDecimal)"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("  def isEmpty: Boolean = ..."),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("trait Repository[A] {\n  def find(id: Long): Option[A]\n  def save(a: A): Unit = ...\n}"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("object OrderService extends Repository[Order] {"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("  override def find(id: Long): Option[Order] = ..."),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("def handler: Order => Unit = ..."),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("println"), "{}", summary);
+        assert!(!summary.contains("cache.update"), "{}", summary);
     }
 
     #[test]
-    fn test_find_definition_filters_by_lang_family() {
-        let mut service = CodeNavigationService::new();
-
-        // Index same symbol name in different languages
-        let python_code = "def test_func(): pass";
-        let rust_code = "fn test_func() {}";
-
-        service.index_file("test.py", python_code, "python");
-        service.index_file("test.rs", rust_code, "rust");
+    fn test_scala_signature() {
+        assert_eq!(
+            scala_signature("def add(a: Int, b: Int = 1): Int = a + b"),
+            "def add(a: Int, b: Int = 1): Int = ..."
+        );
+        assert_eq!(
+            scala_signature("def run(): Unit =\n  go()"),
+            "def run(): Unit = ..."
+        );
+        assert_eq!(scala_signature("def size: Int"), "def size: Int");
+        assert_eq!(
+            scala_member_keyword("@inline private[shop] final def x = 1"),
+            "def"
+        );
+    }
 
-        // Should only find Python definition
-        let python_defs = service.find_definition("test_func", "python");
-        assert_eq!(python_defs.len(), 1);
-        assert_eq!(python_defs[0].file_path, "test.py");
+    #[tokio::test]
+    async fn test_summarize_elixir_code() {
+        let elixir_code = r#"defmodule Shop.Cart do
+  @moduledoc """
+  A shopping cart.
+  """
+  use GenServer
+  alias Shop.{Item, Price}
+
+  @max_items 50
+
+  @doc "Starts the cart process."
+  @spec start_link(keyword()) :: GenServer.on_start()
+  def start_link(opts \\ []) do
+    GenServer.start_link(__MODULE__, opts, name: __MODULE__)
+  end
+
+  @doc """
+  Adds an item, up to the limit.
+
+      iex> Shop.Cart.add(%Item{})
+      :ok
+  """
+  def add(%Item{} = item) when is_struct(item) do
+    GenServer.call(__MODULE__, {:add, item})
+  end
+
+  # Sums the prices
+  defp total(items), do: Enum.reduce(items, 0, &Price.add/2)
+
+  defmacro with_cart(do: block) do
+    quote do
+      unquote(block)
+    end
+  end
+end
+"#;
 
-        // Should only find Rust definition
-        let rust_defs = service.find_definition("test_func", "rust");
-        assert_eq!(rust_defs.len(), 1);
-        assert_eq!(rust_defs[0].file_path, "test.rs");
+        let result = summarize_code_content(
+            elixir_code.to_string(),
+            "elixir".to_string(),
+            "cart.ex".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
 
-        // Should find nothing for wrong lang family
-        let js_defs = service.find_definition("test_func", "js_family");
-        assert!(js_defs.is_empty());
+        assert!(result.success, "Should successfully summarize Elixir code");
+        let summary = &result.summary;
+        assert!(
+            summary.contains("defmodule Shop.Cart do\n  @moduledoc \"\"\"\n  A shopping cart.\n  \"\"\"\n  use GenServer\n  alias Shop.{Item, Price}\n"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("@max_items 50"), "{}", summary);
+        // The @doc travels with the definition it documents, not as a comment
+        assert!(
+            summary.contains("@doc \"Starts the cart process.\"\n@spec start_link(keyword()) :: GenServer.on_start()\n\ndef start_link(opts \\\\ []) do ... end"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("@doc \"\"\"\nAdds an item, up to the limit.\n\n    iex> Shop.Cart.add(%Item{})\n    :ok\n\"\"\"\ndef add(%Item{} = item) when is_struct(item) do ... end"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("defp total(items), do: ..."),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("defmacro with_cart(do: block) do ... end"),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("GenServer.call"), "{}", summary);
+        assert!(!summary.contains("Sums the prices"), "{}", summary);
     }
 
     #[test]
-    fn test_clear_file() {
-        let mut service = CodeNavigationService::new();
-
-        let code = "def my_function(): pass";
-        service.index_file("test.py", code, "python");
-
-        // Verify it was indexed
-        let defs = service.find_definition("my_function", "python");
-        assert!(!defs.is_empty());
+    fn test_elixir_signature() {
+        assert_eq!(
+            elixir_signature("def add(a, b), do: a + b"),
+            "def add(a, b), do: ..."
+        );
+        assert_eq!(
+            elixir_signature("def run(x) when x > 0 do\n  x\nend"),
+            "def run(x) when x > 0 do ... end"
+        );
+        assert_eq!(
+            elixir_signature("def greet(name, greeting \\\\ \"hi\")"),
+            "def greet(name, greeting \\\\ \"hi\")"
+        );
+        assert_eq!(
+            extract_elixir_doc(&["@doc false", "def hidden, do: nil"], 1),
+            "@doc false"
+        );
+        assert_eq!(
+            extract_elixir_doc(&["x = \"\"\"", "text", "\"\"\"", "def f, do: x"], 3),
+            ""
+        );
+    }
 
-        // Clear the file
-        service.clear_file("test.py");
+    #[tokio::test]
+    async fn test_summarize_haskell_code() {
+        let haskell_code = r#"{-# LANGUAGE OverloadedStrings #-}
+module Shapes where
 
-        // Verify it's gone
-        let defs = service.find_definition("my_function", "python");
-        assert!(defs.is_empty());
-    }
+import Data.List (sortOn)
 
-    #[test]
-    fn test_clear_all() {
-        let mut service = CodeNavigationService::new();
+-- | A shape in the plane
+data Shape
+  = Circle Double
+  | Rect { width :: Double, height :: Double }
+  deriving (Show, Eq)
 
-        service.index_file("test1.py", "def func1(): pass", "python");
-        service.index_file("test2.py", "def func2(): pass", "python");
+newtype Area = Area Double
 
-        // Verify both were indexed
-        assert!(!service.find_definition("func1", "python").is_empty());
-        assert!(!service.find_definition("func2", "python").is_empty());
+type Shapes = [Shape]
 
-        // Clear all
-        service.clear_all();
+-- | Things with an area
+class HasArea a where
+  area :: a -> Area
+  perimeter :: a -> Double
+  perimeter _ = 0
 
-        // Verify both are gone
-        assert!(service.find_definition("func1", "python").is_empty());
-        assert!(service.find_definition("func2", "python").is_empty());
-    }
+instance HasArea Shape where
+  area (Circle r) = Area (pi * r * r)
+  area (Rect w h) = Area (w * h)
 
-    #[test]
-    fn test_reindex_file_updates_symbols() {
-        let mut service = CodeNavigationService::new();
+-- | Sort shapes by area, smallest first
+sortByArea
+  :: Shapes
+  -> Shapes
+sortByArea = sortOn (\s -> let Area a = area s in a)
 
-        // Index initial version
-        service.index_file("test.py", "def old_func(): pass", "python");
-        assert!(!service.find_definition("old_func", "python").is_empty());
-        assert!(service.find_definition("new_func", "python").is_empty());
+total :: Shapes -> Double
+total shapes = sum [a | s <- shapes, let Area a = area s]
+"#;
 
-        // Reindex with new content
-        service.index_file("test.py", "def new_func(): pass", "python");
+        let result = summarize_code_content(
+            haskell_code.to_string(),
+            "haskell".to_string(),
+            "Shapes.hs".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
 
-        // Old symbol should be gone, new should exist
-        assert!(service.find_definition("old_func", "python").is_empty());
-        assert!(!service.find_definition("new_func", "python").is_empty());
+        assert!(result.success, "Should successfully summarize Haskell code");
+        let summary = &result.summary;
+        assert!(
+            summary.contains("-- | A shape in the plane\ndata Shape\n  = Circle Double\n  | Rect { width :: Double, height :: Double }\n  deriving (Show, Eq)"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("newtype Area = Area Double"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("type Shapes = [Shape]"), "{}", summary);
+        assert!(
+            summary.contains("-- | Things with an area\nclass HasArea a where\n  area :: a -> Area\n  perimeter :: a -> Double\n  ..."),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("instance HasArea Shape where\n  ..."),
+            "{}",
+            summary
+        );
+        // Signatures are the summary and stay verbatim, even across lines
+        assert!(
+            summary.contains(
+                "-- | Sort shapes by area, smallest first\nsortByArea\n  :: Shapes\n  -> Shapes"
+            ),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("total :: Shapes -> Double"), "{}", summary);
+        assert!(!summary.contains("pi * r"), "{}", summary);
+        assert!(!summary.contains("sortOn ("), "{}", summary);
+        assert!(!summary.contains("sum [a"), "{}", summary);
     }
 
     #[test]
-    fn test_symbol_info_line_numbers() {
-        let mut service = CodeNavigationService::new();
-
-        let code = r#"
-def func_line_2():
-    pass
+    fn test_haskell_signature() {
+        assert_eq!(
+            haskell_signature("add :: Num a => a -> a -> a"),
+            "add :: Num a => a -> a -> a"
+        );
+        assert_eq!(haskell_signature("add x y = x + y"), "add x y = ...");
+        assert_eq!(haskell_signature("x == y = not (x /= y)"), "x == y = ...");
+        assert_eq!(
+            extract_haskell_class_summary("class Eq a => Ord a where\n  compare :: a -> a -> Ordering\n  x <= y = compare x y /= GT\n    || False\n  infix 4 <="),
+            "class Eq a => Ord a where\n  compare :: a -> a -> Ordering\n  infix 4 <=\n  ..."
+        );
+    }
 
-def func_line_5():
-    pass
+    #[tokio::test]
+    async fn test_summarize_ocaml_code() {
+        let ocaml_code = r#"(** Ordered values *)
+module type ORDERED = sig
+  type t
+  val compare : t -> t -> int
+end
+
+type 'a tree =
+  | Leaf
+  | Node of 'a tree * 'a * 'a tree
+
+exception Empty
+
+(** Binary search trees over an ordered type *)
+module Make (Ord : ORDERED) : sig
+  val insert : Ord.t -> Ord.t tree -> Ord.t tree
+end = struct
+  let rec insert x = function
+    | Leaf -> Node (Leaf, x, Leaf)
+    | Node (l, y, r) as node ->
+      let c = Ord.compare x y in
+      if c < 0 then Node (insert x l, y, r)
+      else if c > 0 then Node (l, y, insert x r)
+      else node
+end
+
+let default_size = 16
+
+(* Count the nodes *)
+let rec size = function
+  | Leaf -> 0
+  | Node (l, _, r) ->
+    size l + 1 + size r
+
+let rec depth t =
+  match t with
+  | Leaf -> 0
+  | Node (l, _, r) -> 1 + max (depth l) (depth r)
+and balanced t =
+  match t with
+  | Leaf -> true
+  | Node (l, _, r) -> abs (depth l - depth r) <= 1
 "#;
 
-        service.index_file("test.py", code, "python");
+        let result = summarize_code_content(
+            ocaml_code.to_string(),
+            "ocaml".to_string(),
+            "tree.ml".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
 
-        let defs = service.find_definition("func_line_2", "python");
-        assert!(!defs.is_empty());
-        assert_eq!(defs[0].start_line, 2);
+        assert!(result.success, "Should successfully summarize OCaml code");
+        let summary = &result.summary;
+        // Module types are signatures and stay whole
+        assert!(
+            summary.contains("(** Ordered values *)\nmodule type ORDERED = sig\n  type t\n  val compare : t -> t -> int\nend"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("type 'a tree =\n  | Leaf\n  | Node of 'a tree * 'a * 'a tree"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("exception Empty"), "{}", summary);
+        // A functor keeps its parameters and signature constraint
+        assert!(
+            summary.contains("(** Binary search trees over an ordered type *)\nmodule Make (Ord : ORDERED) : sig\n  val insert : Ord.t -> Ord.t tree -> Ord.t tree\nend = struct"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("let rec insert x = ..."), "{}", summary);
+        assert!(summary.contains("let default_size = 16"), "{}", summary);
+        assert!(
+            summary.contains("(* Count the nodes *)\nlet rec size = ..."),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("let rec depth t = ...\nand balanced t = ..."),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("Node (insert x l, y, r)"), "{}", summary);
+    }
 
-        let defs = service.find_definition("func_line_5", "python");
-        assert!(!defs.is_empty());
-        assert_eq!(defs[0].start_line, 5);
+    #[tokio::test]
+    async fn test_summarize_ocaml_interface_keeps_signatures() {
+        let mli = "(** Binary search trees *)\n\ntype 'a t\n\nval empty : 'a t\n\n(** [insert cmp x t] adds [x] to [t] *)\nval insert :\n  ('a -> 'a -> int) ->\n  'a ->\n  'a t ->\n  'a t\n\nmodule Set : sig\n  type elt\n  val mem : elt -> bool\nend\n";
+        let result = summarize_code_content(
+            mli.to_string(),
+            "ocaml_interface".to_string(),
+            "tree.mli".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Nothing to elide: the interface comes back as written
+        assert!(
+            result.summary.contains("(** [insert cmp x t] adds [x] to [t] *)\nval insert :\n  ('a -> 'a -> int) ->\n  'a ->\n  'a t ->\n  'a t"),
+            "{}",
+            result.summary
+        );
+        assert!(
+            result
+                .summary
+                .contains("module Set : sig\n  type elt\n  val mem : elt -> bool\nend"),
+            "{}",
+            result.summary
+        );
     }
 
-    #[test]
-    fn test_symbol_info_serialization() {
-        let symbol = SymbolInfo {
-            name: "test_func".to_string(),
-            kind: "function".to_string(),
-            file_path: "/path/to/file.py".to_string(),
-            lang_family: "python".to_string(),
-            start_line: 10,
-            start_column: 5,
-            end_line: 10,
-            end_column: 14,
-        };
+    #[tokio::test]
+    async fn test_summarize_bash_script() {
+        let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+
+PREFIX="/usr/local"
+export INSTALL_LOG="/tmp/install.log"
+readonly VERSION=1.4.2
+
+# Print usage and exit
+usage() {
+  echo "usage: install.sh [-p prefix] [-v] command"
+  exit 1
+}
 
-        let json = serde_json::to_string(&symbol).unwrap();
-        assert!(json.contains("\"name\":\"test_func\""));
-        assert!(json.contains("\"kind\":\"function\""));
-        assert!(json.contains("\"start_line\":10"));
+function install_binary {
+  local target="$PREFIX/bin/tool"
+  curl -fsSL "https://example.com/tool-$VERSION" -o "$target"
+  chmod +x "$target"
+}
 
-        // Deserialize back
-        let parsed: SymbolInfo = serde_json::from_str(&json).unwrap();
-        assert_eq!(parsed.name, "test_func");
-        assert_eq!(parsed.kind, "function");
-        assert_eq!(parsed.start_line, 10);
+while getopts "p:vh" opt; do
+  case "$opt" in
+    p) PREFIX="$OPTARG" ;;
+    v) set -x ;;
+    h|*)
+      usage
+      ;;
+  esac
+done
+
+case "${1:-install}" in
+  install)
+    install_binary
+    echo "installed" >> "$INSTALL_LOG"
+    ;;
+  uninstall)
+    rm -f "$PREFIX/bin/tool"
+    ;;
+esac
+"#;
+
+        let result = summarize_code_content(
+            script.to_string(),
+            "bash".to_string(),
+            "install.sh".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            result.success,
+            "Should successfully summarize shell scripts"
+        );
+        let summary = &result.summary;
+        assert!(summary.contains("PREFIX=\"/usr/local\""), "{}", summary);
+        assert!(summary.contains("export INSTALL_LOG="), "{}", summary);
+        assert!(summary.contains("readonly VERSION=1.4.2"), "{}", summary);
+        assert!(
+            summary.contains("# Print usage and exit\nusage() { ... }"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("function install_binary { ... }"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("while getopts \"p:vh\" opt; do"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("case \"$opt\" in\n    p) ...\n    v) ...\n    h|*) ...\n  esac"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("case \"${1:-install}\" in\n  install) ...\n  uninstall) ...\nesac"),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("curl"), "{}", summary);
+        assert!(!summary.contains("rm -f"), "{}", summary);
     }
 
-    #[test]
-    fn test_index_c_file() {
-        let mut service = CodeNavigationService::new();
+    #[tokio::test]
+    async fn test_summarize_powershell_script() {
+        let script = r#"#Requires -Version 7
+[CmdletBinding()]
+param(
+    [Parameter(Mandatory)]
+    [string]$Environment,
+    [switch]$Force
+)
+
+<#
+.SYNOPSIS
+    Copies the build output to a server.
+.PARAMETER Server
+    Target host name.
+#>
+function Publish-Build {
+    [CmdletBinding(SupportsShouldProcess)]
+    [OutputType([bool])]
+    param(
+        [Parameter(Mandatory)]
+        [string]$Server,
+        [int]$Retries = 3
+    )
+
+    foreach ($i in 1..$Retries) {
+        if (Test-Connection $Server -Quiet) {
+            Copy-Item ./dist "\\$Server\deploy" -Recurse
+            return $true
+        }
+    }
+    return $false
+}
 
-        let c_code = r#"
-void my_function() {
-    printf("Hello\n");
+# Removes stale artifacts
+function Clear-Artifacts($Path) {
+    Remove-Item $Path -Recurse -Force
 }
 
-struct MyStruct {
-    int field;
-};
+Publish-Build -Server "web-$Environment"
 "#;
 
-        service.index_file("test.c", c_code, "c");
-
-        // Check function
-        let func_defs = service.find_definition("my_function", "c_family");
-        assert!(!func_defs.is_empty(), "Should find my_function in C");
-        assert_eq!(func_defs[0].kind, "function");
+        let result = summarize_code_content(
+            script.to_string(),
+            "powershell".to_string(),
+            "deploy.ps1".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
 
-        // Check struct
-        let struct_defs = service.find_definition("MyStruct", "c_family");
-        assert!(!struct_defs.is_empty(), "Should find MyStruct in C");
-        assert_eq!(struct_defs[0].kind, "struct");
+        assert!(result.success, "Should successfully summarize PowerShell");
+        let summary = &result.summary;
+        assert!(
+            summary.contains(
+                "[CmdletBinding()]\nparam(\n    [Parameter(Mandatory)]\n    [string]$Environment,"
+            ),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains(".SYNOPSIS\n    Copies the build output to a server."),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("#>\nfunction Publish-Build {\n    [CmdletBinding(SupportsShouldProcess)]\n    [OutputType([bool])]\n    param("),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("[int]$Retries = 3\n    )\n    ...\n}"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("# Removes stale artifacts\nfunction Clear-Artifacts($Path) { ... }"),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("Test-Connection"), "{}", summary);
+        assert!(!summary.contains("Remove-Item"), "{}", summary);
+        assert!(!summary.contains("#Requires"), "{}", summary);
     }
 
-    #[test]
-    fn test_index_java_file() {
-        let mut service = CodeNavigationService::new();
+    #[tokio::test]
+    async fn test_summarize_graphql_schema() {
+        let schema = r#"# Accounts and their orders
+"""
+A registered user.
+Created on sign-up.
+"""
+type User implements Node @key(fields: "id") {
+  id: ID!
+  "Display name"
+  name: String
+  """
+  Orders, newest first.
+  """
+  orders(first: Int = 10, after: String): [Order!]!
+}
 
-        let java_code = r#"
-public class MyClass {
-    public void myMethod() {
-        System.out.println("Hello");
-    }
+input OrderFilter {
+  status: OrderStatus
+  # internal only
+  since: DateTime
 }
 
-interface MyInterface {
-    void method();
+interface Node {
+  id: ID!
 }
-"#;
 
-        service.index_file("Test.java", java_code, "java");
+enum OrderStatus {
+  "Awaiting payment"
+  PENDING
+  SHIPPED
+}
 
-        // Check class
-        let class_defs = service.find_definition("MyClass", "java");
-        assert!(!class_defs.is_empty(), "Should find MyClass in Java");
-        assert_eq!(class_defs[0].kind, "class");
+union SearchResult = User | Order
 
-        // Check method
-        let method_defs = service.find_definition("myMethod", "java");
-        assert!(!method_defs.is_empty(), "Should find myMethod in Java");
-        assert_eq!(method_defs[0].kind, "method");
+scalar DateTime
 
-        // Check interface
-        let interface_defs = service.find_definition("MyInterface", "java");
+"Requires a role"
+directive @auth(requires: Role = ADMIN) on OBJECT | FIELD_DEFINITION
+
+query GetUser($id: ID!) {
+  user(id: $id) {
+    name
+  }
+}
+"#;
+
+        let result = summarize_code_content(
+            schema.to_string(),
+            "graphql".to_string(),
+            "schema.graphql".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success, "Should successfully summarize GraphQL");
+        let summary = &result.summary;
         assert!(
-            !interface_defs.is_empty(),
-            "Should find MyInterface in Java"
+            summary.contains(
+                "# Accounts and their orders\n\"\"\"\nA registered user.\nCreated on sign-up.\n\"\"\"\ntype User implements Node @key(fields: \"id\") {\n  id: ID!\n  name: String\n  orders(first: Int = 10, after: String): [Order!]!\n}"
+            ),
+            "{}",
+            summary
         );
-        assert_eq!(interface_defs[0].kind, "interface");
+        assert!(
+            summary.contains("input OrderFilter {\n  status: OrderStatus\n  since: DateTime\n}"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("enum OrderStatus {\n  PENDING\n  SHIPPED\n}"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("interface Node {\n  id: ID!\n}"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("union SearchResult = User | Order"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("scalar DateTime"), "{}", summary);
+        assert!(
+            summary.contains("\"Requires a role\"\ndirective @auth(requires: Role = ADMIN) on OBJECT | FIELD_DEFINITION"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("query GetUser($id: ID!) { ... }"),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("Display name"), "{}", summary);
+        assert!(!summary.contains("newest first"), "{}", summary);
+        assert!(!summary.contains("internal only"), "{}", summary);
+        assert!(!summary.contains("user(id: $id)"), "{}", summary);
     }
 
-    #[test]
-    fn test_file_definitions_reverse_index() {
-        let mut service = CodeNavigationService::new();
+    #[tokio::test]
+    async fn test_summarize_protobuf_file() {
+        let proto = r#"syntax = "proto3";
 
-        service.index_file("test.py", "def func1(): pass\ndef func2(): pass", "python");
+package shop.v1;
 
-        // Check reverse index has the file
-        assert!(service.index.file_definitions.contains_key("test.py"));
+option go_package = "example.com/shop/v1";
 
-        // Check it contains both function names
-        let names = service.index.file_definitions.get("test.py").unwrap();
-        assert!(names.contains("func1"));
-        assert!(names.contains("func2"));
-    }
+// An order placed by a customer
+message Order {
+  option (validate.disabled) = true;
+  reserved 4, 8 to 10;
+  reserved "legacy_total";
 
-    #[test]
-    fn test_persisted_index_serialization() {
-        let mut definitions = HashMap::new();
-        definitions.insert(
-            "test_func".to_string(),
-            vec![SymbolInfo {
-                name: "test_func".to_string(),
-                kind: "function".to_string(),
-                file_path: "test.py".to_string(),
-                lang_family: "python".to_string(),
-                start_line: 1,
-                start_column: 1,
-                end_line: 1,
-                end_column: 10,
-            }],
-        );
+  string id = 1;
+  repeated Item items = 2 [packed = true];
 
-        let mut file_definitions = HashMap::new();
-        let mut names = HashSet::new();
-        names.insert("test_func".to_string());
-        file_definitions.insert("test.py".to_string(), names);
+  message Item {
+    string sku = 1;
+    int32 quantity = 2;
+  }
+}
 
-        let persisted = PersistedIndex {
-            version: INDEX_VERSION,
-            root_path: "/project".to_string(),
-            last_updated: 1700000000,
-            file_timestamps: HashMap::new(),
-            definitions,
-            file_definitions,
-        };
+enum Status {
+  option allow_alias = true;
+  STATUS_UNSPECIFIED = 0;
+  STATUS_PAID = 1;
+}
 
-        let json = serde_json::to_string(&persisted).unwrap();
-        assert!(json.contains(&format!("\"version\":{}", INDEX_VERSION)));
-        assert!(json.contains("\"root_path\":\"/project\""));
+service Orders {
+  rpc GetOrder(GetOrderRequest) returns (Order) {
+    option (google.api.http) = {
+      get: "/v1/orders/{id}"
+    };
+  }
+  rpc Watch(WatchRequest) returns (stream Order) { option deprecated = true; }
+  rpc Cancel(CancelRequest) returns (Order);
+}
+"#;
 
-        // Deserialize back
-        let parsed: PersistedIndex = serde_json::from_str(&json).unwrap();
-        assert_eq!(parsed.version, INDEX_VERSION);
-        assert_eq!(parsed.root_path, "/project");
-        assert!(parsed.definitions.contains_key("test_func"));
+        let result = summarize_code_content(
+            proto.to_string(),
+            "protobuf".to_string(),
+            "orders.proto".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success, "Should successfully summarize protobuf");
+        let summary = &result.summary;
+        assert!(
+            summary.contains("// An order placed by a customer\nmessage Order {\n\n  string id = 1;\n  repeated Item items = 2 [packed = true];\n\n  message Item {\n    string sku = 1;\n    int32 quantity = 2;\n  }\n}"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("enum Status {\n  STATUS_UNSPECIFIED = 0;\n  STATUS_PAID = 1;\n}"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("service Orders {\n  rpc GetOrder(GetOrderRequest) returns (Order);\n  rpc Watch(WatchRequest) returns (stream Order);\n  rpc Cancel(CancelRequest) returns (Order);\n}"),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("reserved"), "{}", summary);
+        assert!(!summary.contains("/v1/orders"), "{}", summary);
+        assert!(!summary.contains("allow_alias"), "{}", summary);
     }
 
-    #[test]
-    fn test_index_metadata_serialization() {
-        let metadata = IndexMetadata {
-            version: INDEX_VERSION,
-            root_path: "/project".to_string(),
-            last_updated: 1700000000,
-            file_count: 10,
-            definition_count: 50,
-            file_timestamps: HashMap::new(),
-        };
+    #[tokio::test]
+    async fn test_summarize_objc_file() {
+        let source = r#"#import "Cart.h"
 
-        let json = serde_json::to_string(&metadata).unwrap();
-        assert!(json.contains("\"file_count\":10"));
-        assert!(json.contains("\"definition_count\":50"));
+/// Items waiting for checkout
+@interface Cart : NSObject {
+    NSMutableArray *_items;
+}
+@property (nonatomic, readonly) NSUInteger count;
+- (void)addItem:(Item *)item quantity:(NSInteger)quantity;
+@end
 
-        let parsed: IndexMetadata = serde_json::from_str(&json).unwrap();
-        assert_eq!(parsed.file_count, 10);
-        assert_eq!(parsed.definition_count, 50);
+@implementation Cart
+
+- (instancetype)init {
+    self = [super init];
+    if (self) {
+        _items = [NSMutableArray array];
     }
+    return self;
+}
 
-    #[test]
-    fn test_get_project_hash() {
-        let hash1 = get_project_hash("/project/a");
-        let hash2 = get_project_hash("/project/b");
-        let hash3 = get_project_hash("/project/a"); // Same as hash1
+- (void)addItem:(Item *)item
+       quantity:(NSInteger)quantity
+{
+    for (NSInteger i = 0; i < quantity; i++) {
+        [_items addObject:item];
+    }
+}
 
-        // Different paths should have different hashes
-        assert_ne!(hash1, hash2);
+@end
 
-        // Same path should have same hash
-        assert_eq!(hash1, hash3);
+static NSString *CartKey(NSString *name) {
+    return [@"cart." stringByAppendingString:name];
+}
+"#;
 
-        // Hash should be 16 characters (8 bytes in hex)
-        assert_eq!(hash1.len(), 16);
-    }
+        let result = summarize_code_content(
+            source.to_string(),
+            "objc".to_string(),
+            "Cart.m".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
 
-    // ============================================================================
-    // Code Summarization Tests
-    // ============================================================================
+        assert!(result.success, "Should successfully summarize Objective-C");
+        let summary = &result.summary;
+        assert!(
+            summary.contains("/// Items waiting for checkout\n@interface Cart : NSObject {\n    NSMutableArray *_items;\n}\n@property (nonatomic, readonly) NSUInteger count;\n- (void)addItem:(Item *)item quantity:(NSInteger)quantity;\n@end"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("@implementation Cart\n\n- (instancetype)init { ... }\n\n- (void)addItem:(Item *)item\n       quantity:(NSInteger)quantity { ... }\n\n@end"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("static NSString *CartKey(NSString *name) { ... }"),
+            "{}",
+            summary
+        );
+        assert!(!summary.contains("[super init]"), "{}", summary);
+        assert!(!summary.contains("addObject"), "{}", summary);
+    }
 
     #[tokio::test]
-    async fn test_summarize_typescript_code() {
-        let ts_code = r#"
-/**
- * A sample service for processing data
- */
-export class DataProcessor {
-    private data: Map<string, number>;
+    async fn test_summarize_julia_file() {
+        let source = r#"module Shapes
+
+using LinearAlgebra
+export Circle, area
+
+"""
+    Shape
+
+Anything with an area.
+"""
+abstract type Shape end
+
+"A circle of radius `r`."
+struct Circle <: Shape
+    r::Float64
+end
+
+"""
+    area(c::Circle)
+
+Area of the circle.
+"""
+function area(c::Circle)
+    r2 = c.r^2
+    result = pi * r2
+    return result
+end
+
+function scale(c::Circle,
+               k::Real)
+    Circle(c.r * k)
+end
+
+perimeter(c::Circle) = 2 * pi * c.r
+
+macro twice(ex)
+    quote
+        $(esc(ex))
+        $(esc(ex))
+    end
+end
+
+end
+"#;
 
-    constructor() {
-        this.data = new Map();
+        let result = summarize_code_content(
+            source.to_string(),
+            "julia".to_string(),
+            "Shapes.jl".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.success, "Should successfully summarize Julia");
+        let summary = &result.summary;
+        assert!(
+            summary.contains("module Shapes\nusing LinearAlgebra\nexport Circle, area\nend"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains(
+                "\"\"\"\n    Shape\n\nAnything with an area.\n\"\"\"\nabstract type Shape end"
+            ),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains(
+                "\"A circle of radius `r`.\"\nstruct Circle <: Shape\n    r::Float64\nend"
+            ),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("Area of the circle.\n\"\"\"\nfunction area(c::Circle)"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("function scale(c::Circle,\n               k::Real)"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("perimeter(c::Circle) = 2 * pi * c.r"),
+            "{}",
+            summary
+        );
+        assert!(summary.contains("macro twice(ex)"), "{}", summary);
+        assert!(!summary.contains("result = pi"), "{}", summary);
+        assert!(!summary.contains("esc(ex)"), "{}", summary);
     }
 
-    /**
-     * Process incoming data
-     * @param input The input to process
-     * @returns The processed result
-     */
-    async process(input: string): Promise<number> {
-        // Some long implementation
-        const result = input.length * 2;
-        for (let i = 0; i < 100; i++) {
-            console.log(i);
-        }
-        return result;
+    #[test]
+    fn test_ocaml_signature() {
+        assert_eq!(
+            ocaml_let_signature("let add x y = x + y"),
+            "let add x y = x + y"
+        );
+        assert_eq!(
+            ocaml_let_signature(
+                "let compare (a : t) (b : t) : int =\n  if a <= b then -1\n  else 1"
+            ),
+            "let compare (a : t) (b : t) : int = ..."
+        );
+        assert_eq!(
+            ocaml_let_signature("let f ?(n = 1) x =\n  x + n"),
+            "let f ?(n = 1) x = ..."
+        );
+        assert_eq!(
+            extract_ocaml_module_summary("module M = Make (Int)"),
+            "module M = Make (Int)"
+        );
+        assert_eq!(
+            extract_ocaml_module_summary("module Counter = struct\n  let count = ref 0\nend"),
+            "module Counter = struct"
+        );
     }
 
-    private helperMethod(): void {
-        console.log('helper');
+    #[tokio::test]
+    async fn test_summarize_is_stable_across_line_endings() {
+        let rust_code = "/// Adds numbers\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\npub struct Point {\n    x: i32,\n}\n";
+        let lf = summarize_code_content(
+            rust_code.to_string(),
+            "rust".to_string(),
+            "lib.rs".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        let again = summarize_code_content(
+            rust_code.to_string(),
+            "rust".to_string(),
+            "lib.rs".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        let crlf = summarize_code_content(
+            rust_code.replace('\n', "\r\n"),
+            "rust".to_string(),
+            "lib.rs".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(lf.summary, again.summary);
+        assert_eq!(lf.summary, crlf.summary);
+        assert!(!crlf.summary.contains('\r'));
     }
-}
-
-interface DataInput {
-    id: string;
-    value: number;
-    timestamp: Date;
-}
-
-const MAX_SIZE = 1000;
 
-export function processAll(items: DataInput[]): void {
-    items.forEach(item => console.log(item));
+    #[tokio::test]
+    async fn test_summarize_multilingual_signatures() {
+        let ts_code = r#"export function 格式化(模板 = "{名前}", opts = { sep: "：" }): string {
+  return 模板;
 }
 "#;
-
         let result = summarize_code_content(
             ts_code.to_string(),
             "typescript".to_string(),
-            "test.ts".to_string(),
+            "fmt.ts".to_string(),
+            None,
         )
         .await
         .unwrap();
+        assert!(result
+            .summary
+            .contains(r#"function 格式化(模板 = "{名前}", opts = { sep: "：" }): string { ... }"#));
 
-        assert!(
-            result.success,
-            "Should successfully summarize TypeScript code"
-        );
-        assert!(
-            result.summary.contains("[COMPRESSED:"),
-            "Should have compression marker"
-        );
-        assert!(
-            result.summary.contains("DataProcessor"),
-            "Should include class name"
-        );
-        assert!(
-            result.summary.contains("process("),
-            "Should include method signature"
-        );
-        assert!(
-            result.summary.contains("DataInput"),
-            "Should include interface"
-        );
-        assert!(result.summary.contains("MAX_SIZE"), "Should include const");
-        // Function body should be replaced with { ... }
-        assert!(
-            result.summary.contains("{ ... }"),
-            "Function bodies should be summarized"
-        );
+        let py_code = "def grüßen(name: str = 'wörld') -> str:\n    return name\n";
+        let result = summarize_code_content(
+            py_code.to_string(),
+            "python".to_string(),
+            "greet.py".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(result
+            .summary
+            .contains("def grüßen(name: str = 'wörld') -> str:\n    ..."));
     }
 
     #[tokio::test]
-    async fn test_summarize_rust_code() {
-        let rust_code = r#"
-/// A struct for holding configuration
-pub struct Config {
-    pub name: String,
-    pub value: i32,
-}
+    async fn test_summary_doc_comment_options() {
+        let license = (0..40)
+            .map(|i| format!("/// License line {}\n", i))
+            .collect::<String>();
+        let content = format!("{}pub fn run() {{\n    work();\n}}\n", license);
+        let summarize = |options: SummaryOptions| {
+            summarize_source(
+                content.clone(),
+                "rust".to_string(),
+                "lib.rs".to_string(),
+                &options,
+            )
+            .unwrap()
+            .summary
+        };
 
-impl Config {
-    /// Create a new config
-    pub fn new(name: String, value: i32) -> Self {
-        Self { name, value }
+        let full = summarize(SummaryOptions::default());
+        assert!(full.contains("/// License line 39"));
+
+        let capped = summarize(SummaryOptions {
+            max_doc_lines: Some(2),
+            ..Default::default()
+        });
+        assert!(capped.contains(
+            "/// License line 0\n/// License line 1\n/// … (doc truncated)\npub fn run() { ... }"
+        ));
+        assert!(!capped.contains("License line 2"));
+
+        let without = summarize(SummaryOptions {
+            include_doc_comments: false,
+            ..Default::default()
+        });
+        assert!(!without.contains("License"));
+        assert!(without.contains("pub fn run() { ... }"));
     }
 
-    pub fn process(&self) -> String {
-        format!("{}: {}", self.name, self.value)
+    #[test]
+    fn test_cap_doc_comment_closes_block() {
+        let doc = "/**\n* Line one\n* Line two\n* Line three\n*/";
+        assert_eq!(
+            cap_doc_comment(doc, 2),
+            "/**\n* Line one\n* … (doc truncated)\n*/"
+        );
+        assert_eq!(cap_doc_comment(doc, 10), doc);
+        assert_eq!(cap_doc_comment("# a\n# b", 1), "# a\n# … (doc truncated)");
     }
-}
 
-/// Process data with the given config
-pub fn process_data(config: &Config) -> Result<(), String> {
-    // Long implementation
-    println!("Processing...");
-    Ok(())
-}
+    #[tokio::test]
+    async fn test_compact_files_are_skipped() {
+        let header = r#"// Public API
+#ifndef API_H
+#define API_H
 
-const MAX_RETRIES: u32 = 3;
+typedef struct Buffer {
+    char *data;
+    int len;
+} Buffer;
+
+Buffer *buffer_new(int capacity);
+void buffer_free(Buffer *buf);
+int buffer_append(Buffer *buf, const char *data, int len);
+
+#endif
 "#;
+        let compaction = SummaryOptions {
+            min_body_ratio: 0.1,
+            ..Default::default()
+        };
+        let result = summarize_code_content(
+            header.to_string(),
+            "c".to_string(),
+            "api.h".to_string(),
+            Some(compaction.clone()),
+        )
+        .await
+        .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.summary, header);
+        assert_eq!(
+            result.skipped_reason.as_deref(),
+            Some("already compact: 0% of code lines are function bodies")
+        );
 
+        let interfaces = "export interface User {\n  id: string;\n  name: string;\n}\n\nexport type Id = string;\n";
         let result = summarize_code_content(
-            rust_code.to_string(),
-            "rust".to_string(),
-            "test.rs".to_string(),
+            interfaces.to_string(),
+            "typescript".to_string(),
+            "types.ts".to_string(),
+            Some(compaction),
         )
         .await
         .unwrap();
+        assert!(result
+            .skipped_reason
+            .unwrap()
+            .starts_with("already compact"));
 
-        assert!(result.success, "Should successfully summarize Rust code");
-        assert!(
-            result.summary.contains("Config"),
-            "Should include struct name"
-        );
-        assert!(
-            result.summary.contains("process_data"),
-            "Should include function name"
-        );
-        assert!(
-            result.summary.contains("MAX_RETRIES"),
-            "Should include const"
-        );
+        // The check is off by default
+        let result = summarize_code_content(
+            interfaces.to_string(),
+            "typescript".to_string(),
+            "types.ts".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(result.success);
+        assert!(result.skipped_reason.is_none());
     }
 
-    #[tokio::test]
-    async fn test_summarize_python_code() {
-        let python_code = r#"
-"""A module for data processing."""
+    #[test]
+    fn test_body_line_ratio_ignores_comments() {
+        let content = "/// Doc\n/// more doc\nfn a() {\n    one();\n    two();\n}\n";
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(content, None).unwrap();
+        // Signature row is code, the two statements and closing brace are body
+        assert_eq!(body_line_ratio(&tree, content), 0.75);
+    }
 
-class DataProcessor:
-    """Processes data efficiently."""
+    #[tokio::test]
+    async fn test_declaration_file_keeps_types() {
+        let dts = r#"export interface Options {
+  retries?: number;
+  onError(err: Error): void;
+}
+/**
+ * Create a client.
+ * @param url Server address
+ * @param options Client options
+ * @returns A connected client
+ */
+export declare function connect(url: string, options?: Options): Promise<Client>;
+export declare function connect(port: number): Promise<Client>;
+"#;
+        let options = SummaryOptions {
+            max_doc_lines: Some(2),
+            ..Default::default()
+        };
+        let result = summarize_code_content(
+            dts.to_string(),
+            "typescript".to_string(),
+            "client.d.ts".to_string(),
+            Some(options),
+        )
+        .await
+        .unwrap();
+        assert!(result.success);
+        assert!(result.summary.contains("  onError(err: Error): void;"));
+        assert!(result.summary.contains(" * … (doc truncated)\n */"));
+        assert!(!result.summary.contains("@returns"));
+        assert!(result
+            .summary
+            .contains("export declare function connect(port: number): Promise<Client>;"));
+    }
 
-    def __init__(self, config: dict):
-        self.config = config
-        self.data = []
+    #[tokio::test]
+    async fn test_symbol_budget_keeps_public_api() {
+        let content = r#"export function render(view: View): string {
+  return layout(view);
+}
 
-    def process(self, input_data: list) -> list:
-        """Process the input data."""
-        result = []
-        for item in input_data:
-            result.append(item * 2)
-        return result
+function layout(view: View): string {
+  return pad(view.body);
+}
 
-def helper_function(x: int) -> int:
-    """A helper function."""
-    return x + 1
+function pad(text: string): string {
+  return `  ${text}`;
+}
 
-MAX_SIZE = 1000
+const DEFAULT_WIDTH = 80;
 "#;
-
+        let options = SummaryOptions {
+            symbol_budget: Some(2),
+            context_symbols: vec!["pad".to_string()],
+            ..Default::default()
+        };
         let result = summarize_code_content(
-            python_code.to_string(),
-            "python".to_string(),
-            "test.py".to_string(),
+            content.to_string(),
+            "typescript".to_string(),
+            "view.ts".to_string(),
+            Some(options),
         )
         .await
         .unwrap();
+        assert!(result.success);
+        assert_eq!(result.omitted_symbols, vec!["layout", "DEFAULT_WIDTH"]);
+        assert!(result
+            .summary
+            .contains("[OMITTED: 2 lower-priority definitions over budget]"));
+        assert!(result
+            .summary
+            .contains("function render(view: View): string { ... }"));
+        assert!(result
+            .summary
+            .contains("function pad(text: string): string { ... }"));
+        assert!(!result.summary.contains("layout("));
+    }
 
-        assert!(result.success, "Should successfully summarize Python code");
-        assert!(
-            result.summary.contains("DataProcessor"),
-            "Should include class name"
-        );
-        assert!(
-            result.summary.contains("process"),
-            "Should include method name"
-        );
+    #[test]
+    fn test_summary_kind_policies() {
+        let rust = "pub const TABLE: [u8; 3] = [\n    1,\n    2,\n    3,\n];\n\npub static NAME: &str = \"x\";\n\npub fn run() {\n    work();\n    more();\n}\n";
+        let options = SummaryOptions {
+            policies: Some(HashMap::from([
+                (CaptureKind::Const, KindPolicy::KeepFull),
+                (CaptureKind::Static, KindPolicy::Drop),
+                (CaptureKind::Function, KindPolicy::FirstLine),
+            ])),
+            ..Default::default()
+        };
+        let summary = summarize_source(
+            rust.to_string(),
+            "rust".to_string(),
+            "lib.rs".to_string(),
+            &options,
+        )
+        .unwrap()
+        .summary;
+        assert!(summary.contains("pub const TABLE: [u8; 3] = [\n    1,\n    2,\n    3,\n];"));
+        assert!(!summary.contains("NAME"));
+        assert!(summary.contains("pub fn run() {\n"));
+        assert!(!summary.contains("work()"));
+
+        let java = "public class User {\n    private String name;\n    private int age;\n\n    public String getName() {\n        return name;\n    }\n}\n";
+        let options = SummaryOptions {
+            policies: Some(HashMap::from([(CaptureKind::Field, KindPolicy::Drop)])),
+            ..Default::default()
+        };
+        let with_fields = summarize_source(
+            java.to_string(),
+            "java".to_string(),
+            "User.java".to_string(),
+            &SummaryOptions::default(),
+        )
+        .unwrap()
+        .summary;
+        let without_fields = summarize_source(
+            java.to_string(),
+            "java".to_string(),
+            "User.java".to_string(),
+            &options,
+        )
+        .unwrap()
+        .summary;
         assert!(
-            result.summary.contains("helper_function"),
-            "Should include function name"
+            with_fields.matches("private String name;").count()
+                > without_fields.matches("private String name;").count()
         );
     }
 
     #[tokio::test]
-    async fn test_summarize_unsupported_language() {
-        let markdown_code = r#"
-# Header
-
-Some text content.
-
-- Item 1
-- Item 2
-"#;
+    async fn test_summary_capture_limit_falls_back_to_outline() {
+        let content = (0..10)
+            .map(|i| format!("pub fn f{}() -> u32 {{\n    {}\n}}\n", i, i))
+            .collect::<String>();
 
-        let result = summarize_code_content(
-            markdown_code.to_string(),
-            "markdown".to_string(),
-            "test.md".to_string(),
+        let full = summarize_code_content(
+            content.clone(),
+            "rust".to_string(),
+            "lib.rs".to_string(),
+            None,
         )
         .await
         .unwrap();
+        assert!(full.truncated.is_none());
+        assert!(!full.summary.contains("[TRUNCATED"));
 
-        // Should return success=false but not error, keeping original content
-        assert!(!result.success, "Should indicate unsupported language");
+        let options = SummaryOptions {
+            max_captures: 3,
+            ..Default::default()
+        };
+        let limited = summarize_code_content(
+            content,
+            "rust".to_string(),
+            "lib.rs".to_string(),
+            Some(options),
+        )
+        .await
+        .unwrap();
+        assert!(limited.success);
         assert_eq!(
-            result.summary, markdown_code,
-            "Should return original content for unsupported language"
+            limited.truncated.as_deref(),
+            Some("stopped after 3 definitions")
         );
+        assert!(limited
+            .summary
+            .contains("[TRUNCATED: stopped after 3 definitions; outline only]"));
+        assert!(limited.summary.contains("pub fn f2() -> u32 { ... }"));
+        assert!(!limited.summary.contains("f3"));
     }
 
     #[tokio::test]
-    async fn test_summarize_go_code() {
-        let go_code = r#"
-package main
-
-// Config holds configuration values
-type Config struct {
-    Name  string
-    Value int
-}
+    async fn test_summary_depth_limit() {
+        let content = format!(
+            "function outer() {{\n{}{}}}\n",
+            "{\n".repeat(200),
+            "}\n".repeat(200)
+        );
+        let result = summarize_code_content(
+            content,
+            "typescript".to_string(),
+            "deep.ts".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(result.success);
+        assert!(result.truncated.unwrap().contains("nesting deeper than 64"));
+        assert!(result.summary.contains("function outer() { ... }"));
+    }
 
-// NewConfig creates a new Config
-func NewConfig(name string, value int) *Config {
-    return &Config{
-        Name:  name,
-        Value: value,
+    #[test]
+    fn test_sort_captures_tie_breakers() {
+        let capture = |kind: &str, start_byte: usize, end_byte: usize| CapturedSymbol {
+            kind: kind.to_string(),
+            name: String::new(),
+            text: String::new(),
+            start_line: 0,
+            end_line: 0,
+            start_byte,
+            end_byte,
+            exported: false,
+        };
+        let mut captures = vec![
+            capture("const_decl", 0, 10),
+            capture("function", 20, 30),
+            capture("arrow_function", 0, 50),
+            capture("const_decl", 0, 10),
+            capture("class", 0, 10),
+        ];
+        sort_captures(&mut captures);
+
+        let order: Vec<(&str, usize)> = captures
+            .iter()
+            .map(|c| (c.kind.as_str(), c.start_byte))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("arrow_function", 0),
+                ("class", 0),
+                ("const_decl", 0),
+                ("function", 20)
+            ]
+        );
     }
-}
 
-// Process handles the main logic
-func (c *Config) Process() error {
-    fmt.Println(c.Name)
-    return nil
-}
+    // ============================================================================
+    // Summary Snapshot Corpus
+    // ============================================================================
 
-const MaxRetries = 3
-"#;
+    /// Compares summaries of tests/summary_corpus/<lang_id>/* against the checked-in
+    /// `.summary` files. Run with UPDATE_SUMMARY_SNAPSHOTS=1 to rewrite them.
+    #[tokio::test]
+    async fn test_summary_corpus_snapshots() {
+        let corpus = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/summary_corpus");
+        let update = std::env::var("UPDATE_SUMMARY_SNAPSHOTS").is_ok_and(|v| v == "1");
 
-        let result =
-            summarize_code_content(go_code.to_string(), "go".to_string(), "main.go".to_string())
+        let mut samples: Vec<(String, PathBuf)> = Vec::new();
+        for lang_dir in fs::read_dir(&corpus).unwrap().flatten() {
+            if !lang_dir.path().is_dir() {
+                continue;
+            }
+            let lang_id = lang_dir.file_name().to_string_lossy().to_string();
+            for file in fs::read_dir(lang_dir.path()).unwrap().flatten() {
+                let path = file.path();
+                if path.extension().is_some_and(|e| e == "summary") {
+                    continue;
+                }
+                samples.push((lang_id.clone(), path));
+            }
+        }
+        samples.sort();
+        assert!(!samples.is_empty(), "Summary corpus should not be empty");
+
+        let mut failures = Vec::new();
+        for (lang_id, path) in samples {
+            let content = fs::read_to_string(&path).unwrap();
+            let file_path = path.to_string_lossy().to_string();
+            let result = summarize_code_content(content, lang_id.clone(), file_path.clone(), None)
                 .await
                 .unwrap();
+            assert!(result.success, "Failed to summarize {}", file_path);
+
+            let actual = format!("{}\n", result.summary);
+            let snapshot_path = PathBuf::from(format!("{}.summary", file_path));
+            if update {
+                fs::write(&snapshot_path, &actual).unwrap();
+                continue;
+            }
+            match fs::read_to_string(&snapshot_path) {
+                Ok(expected) if expected.replace("\r\n", "\n") == actual => {}
+                Ok(expected) => failures.push(crate::text_diff::unified_diff(
+                    &expected.replace("\r\n", "\n"),
+                    &actual,
+                    &snapshot_path.to_string_lossy(),
+                    "actual",
+                    3,
+                )),
+                Err(_) => failures.push(format!(
+                    "Missing snapshot {}",
+                    snapshot_path.to_string_lossy()
+                )),
+            }
+        }
 
-        assert!(result.success, "Should successfully summarize Go code");
-        assert!(
-            result.summary.contains("Config"),
-            "Should include type name"
-        );
-        assert!(
-            result.summary.contains("NewConfig"),
-            "Should include function name"
-        );
         assert!(
-            result.summary.contains("Process"),
-            "Should include method name"
+            failures.is_empty(),
+            "Summary snapshots differ (rerun with UPDATE_SUMMARY_SNAPSHOTS=1 to accept):\n{}",
+            failures.join("\n")
         );
     }
 }
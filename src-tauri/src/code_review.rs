@@ -0,0 +1,409 @@
+// Code review mode: annotate a diff with structured comments
+//
+// The diff for a revision range is parsed into hunks, hunks are grouped by the
+// definition they touch (so a reviewer sees a whole changed function at once), and each
+// chunk is sent to the provider with a summarized outline of its file. Answers are
+// parsed into machine-readable comments anchored to new-file lines.
+
+use crate::code_navigation::{self, CodeNavigationService};
+use crate::git::{diff, repository};
+use crate::next_edit::enclosing_symbol;
+use crate::provider_client::{self, ChatMessage, ProviderConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+const PROVIDER_TIMEOUT_SECS: u64 = 90;
+const MAX_CHUNK_CHARS: usize = 6_000;
+const MAX_CHUNKS: usize = 30;
+const MAX_OUTLINE_CHARS: usize = 3_000;
+
+const REVIEW_SYSTEM_PROMPT: &str = "You are a meticulous code reviewer. Review the diff chunk \
+for bugs, security issues, performance problems and unclear code. Reply with a JSON array only: \
+[{\"line\": <line number in the new file>, \"severity\": \"info\" | \"warning\" | \"error\", \
+\"message\": \"...\", \"suggestion\": \"replacement code or null\"}]. Reply [] when there is nothing to flag.";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub header: String,
+    pub new_start: u32,
+    pub text: String,
+    /// New-file lines added by the hunk
+    pub added_lines: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilePatch {
+    pub path: String,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewChunk {
+    pub path: String,
+    pub symbol: Option<String>,
+    pub text: String,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub path: String,
+    pub line: u32,
+    pub severity: String, // "info", "warning" or "error"
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewResult {
+    pub comments: Vec<ReviewComment>,
+    pub chunks_reviewed: usize,
+    /// Chunks skipped because of the chunk cap or a failed provider call
+    pub chunks_skipped: usize,
+}
+
+/// Parse `+++ b/...`/`@@ ... @@` sections of a unified diff
+pub fn parse_unified_diff(text: &str) -> Vec<FilePatch> {
+    let mut files: Vec<FilePatch> = Vec::new();
+    let mut new_line = 0u32;
+
+    for line in text.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            let path = path.strip_prefix("b/").unwrap_or(path).to_string();
+            files.push(FilePatch {
+                path,
+                hunks: Vec::new(),
+            });
+            continue;
+        }
+        if line.starts_with("--- ") || line.starts_with("diff --git") {
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            let new_start = line
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse::<u32>().ok())
+                .unwrap_or(1);
+            new_line = new_start;
+            file.hunks.push(DiffHunk {
+                header: line.to_string(),
+                new_start,
+                text: String::new(),
+                added_lines: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(hunk) = file.hunks.last_mut() else {
+            continue;
+        };
+        hunk.text.push_str(line);
+        hunk.text.push('\n');
+        match line.chars().next() {
+            Some('+') => {
+                hunk.added_lines.push(new_line);
+                new_line += 1;
+            }
+            Some('-') => {}
+            _ => new_line += 1,
+        }
+    }
+
+    files.retain(|f| !f.hunks.is_empty());
+    files
+}
+
+/// Group hunks by the definition they touch, splitting oversized groups
+pub fn build_chunks(patch: &FilePatch, new_content: Option<&str>) -> Vec<ReviewChunk> {
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&patch.path).unwrap_or_default();
+    let mut groups: BTreeMap<(u32, Option<String>), ReviewChunk> = BTreeMap::new();
+
+    for (idx, hunk) in patch.hunks.iter().enumerate() {
+        let anchor = hunk.added_lines.first().copied().unwrap_or(hunk.new_start);
+        let end = hunk.added_lines.last().copied().unwrap_or(anchor);
+        let symbol = new_content.and_then(|content| enclosing_symbol(content, &lang_id, anchor));
+
+        // Hunks outside any definition stay on their own
+        let key = match &symbol {
+            Some((name, start, _)) => (*start, Some(name.clone())),
+            None => (u32::MAX - idx as u32, None),
+        };
+        let chunk = groups.entry(key).or_insert_with(|| ReviewChunk {
+            path: patch.path.clone(),
+            symbol: symbol.as_ref().map(|(name, _, _)| name.clone()),
+            text: String::new(),
+            start_line: anchor,
+            end_line: end,
+        });
+        chunk.text.push_str(&hunk.header);
+        chunk.text.push('\n');
+        chunk.text.push_str(&hunk.text);
+        chunk.start_line = chunk.start_line.min(anchor);
+        chunk.end_line = chunk.end_line.max(end);
+    }
+
+    let mut chunks = Vec::new();
+    for chunk in groups.into_values() {
+        if chunk.text.len() <= MAX_CHUNK_CHARS {
+            chunks.push(chunk);
+            continue;
+        }
+        let mut part = String::new();
+        for line in chunk.text.lines() {
+            if part.len() + line.len() + 1 > MAX_CHUNK_CHARS && !part.is_empty() {
+                chunks.push(ReviewChunk {
+                    text: std::mem::take(&mut part),
+                    ..chunk.clone()
+                });
+            }
+            part.push_str(line);
+            part.push('\n');
+        }
+        if !part.is_empty() {
+            chunks.push(ReviewChunk {
+                text: part,
+                ..chunk.clone()
+            });
+        }
+    }
+    chunks
+}
+
+/// Parse the provider's JSON array into comments anchored inside the chunk
+pub fn parse_review_answer(answer: &str, chunk: &ReviewChunk) -> Vec<ReviewComment> {
+    let (Some(start), Some(end)) = (answer.find('['), answer.rfind(']')) else {
+        return Vec::new();
+    };
+    if end < start {
+        return Vec::new();
+    }
+    let Ok(Value::Array(items)) = serde_json::from_str::<Value>(&answer[start..=end]) else {
+        return Vec::new();
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let message = item.get("message")?.as_str()?.trim().to_string();
+            if message.is_empty() {
+                return None;
+            }
+            let line = item
+                .get("line")
+                .and_then(|l| l.as_u64())
+                .map(|l| l as u32)
+                .filter(|l| *l >= chunk.start_line && *l <= chunk.end_line)
+                .unwrap_or(chunk.start_line);
+            let severity = match item.get("severity").and_then(|s| s.as_str()) {
+                Some("error") => "error",
+                Some("warning") => "warning",
+                _ => "info",
+            };
+            let suggestion = item
+                .get("suggestion")
+                .and_then(|s| s.as_str())
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| s.to_string());
+            Some(ReviewComment {
+                path: chunk.path.clone(),
+                line,
+                severity: severity.to_string(),
+                message,
+                suggestion,
+            })
+        })
+        .collect()
+}
+
+/// Content of `path` at `head`, or in the working directory when `head` is None
+fn new_file_content(repo_path: &str, head: Option<&str>, path: &str) -> Option<String> {
+    match head {
+        None => std::fs::read_to_string(Path::new(repo_path).join(path)).ok(),
+        Some(head) => {
+            let repo = repository::discover_repository(repo_path).ok()?;
+            let object = repo.revparse_single(&format!("{}:{}", head, path)).ok()?;
+            let blob = object.peel_to_blob().ok()?;
+            String::from_utf8(blob.content().to_vec()).ok()
+        }
+    }
+}
+
+async fn file_outline(path: &str, content: &str) -> String {
+    let lang_id = CodeNavigationService::get_lang_id_from_path(path).unwrap_or_default();
+    let summary = code_navigation::summarize_code_content(
+        content.to_string(),
+        lang_id,
+        path.to_string(),
+        None,
+    )
+    .await
+    .map(|s| s.summary)
+    .unwrap_or_default();
+    summary.chars().take(MAX_OUTLINE_CHARS).collect()
+}
+
+/// Review the diff between `base` and `head` (or the working directory)
+#[tauri::command]
+pub async fn review_diff(
+    provider: ProviderConfig,
+    repo_path: String,
+    base: String,
+    head: Option<String>,
+) -> Result<ReviewResult, String> {
+    let diff_text = {
+        let repo = repository::discover_repository(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        diff::get_range_diff_text(&repo, &base, head.as_deref())
+            .map_err(|e| format!("Failed to get range diff text: {}", e))?
+    };
+
+    let mut chunks = Vec::new();
+    let mut outlines = BTreeMap::new();
+    for patch in parse_unified_diff(&diff_text) {
+        let content = new_file_content(&repo_path, head.as_deref(), &patch.path);
+        if let Some(content) = &content {
+            outlines.insert(patch.path.clone(), file_outline(&patch.path, content).await);
+        }
+        chunks.extend(build_chunks(&patch, content.as_deref()));
+    }
+
+    let total = chunks.len();
+    let mut comments = Vec::new();
+    let mut reviewed = 0;
+    for chunk in chunks.iter().take(MAX_CHUNKS) {
+        let mut prompt = format!("File: {}\n", chunk.path);
+        if let Some(symbol) = &chunk.symbol {
+            prompt.push_str(&format!("Changed definition: {}\n", symbol));
+        }
+        if let Some(outline) = outlines.get(&chunk.path).filter(|o| !o.is_empty()) {
+            prompt.push_str(&format!("\nFile outline:\n{}\n", outline));
+        }
+        prompt.push_str(&format!("\nDiff:\n{}", chunk.text));
+
+        let messages = [
+            ChatMessage::system(REVIEW_SYSTEM_PROMPT),
+            ChatMessage::user(prompt),
+        ];
+        match provider_client::chat_completion(
+            &provider,
+            &messages,
+            Duration::from_secs(PROVIDER_TIMEOUT_SECS),
+        )
+        .await
+        {
+            Ok(answer) => {
+                comments.extend(parse_review_answer(&answer, chunk));
+                reviewed += 1;
+            }
+            Err(e) => log::warn!("Review of {} failed: {}", chunk.path, e),
+        }
+    }
+
+    log::info!(
+        "Reviewed {}/{} diff chunks, {} comments",
+        reviewed,
+        total,
+        comments.len()
+    );
+    Ok(ReviewResult {
+        comments,
+        chunks_reviewed: reviewed,
+        chunks_skipped: total - reviewed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs (modified)
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,5 @@
+ fn a() {
+-    old();
++    new();
++    more();
+ }
+@@ -10,2 +11,2 @@
+ fn b() {
+-    x
++    y
+";
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let files = parse_unified_diff(DIFF);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(files[0].hunks[0].added_lines, vec![2, 3]);
+        assert_eq!(files[0].hunks[1].new_start, 11);
+        assert_eq!(files[0].hunks[1].added_lines, vec![12]);
+    }
+
+    #[test]
+    fn test_build_chunks_groups_by_definition() {
+        let content = "fn a() {\n    new();\n    more();\n}\n\nfn c() {\n    one();\n    two();\n    three();\n}\n\nfn b() {\n    y\n}\n";
+        let patch = FilePatch {
+            path: "src/lib.rs".to_string(),
+            hunks: vec![
+                DiffHunk {
+                    header: "@@ -1,1 +1,1 @@".to_string(),
+                    new_start: 2,
+                    text: "+    new();\n".to_string(),
+                    added_lines: vec![2],
+                },
+                DiffHunk {
+                    header: "@@ -2,1 +3,1 @@".to_string(),
+                    new_start: 3,
+                    text: "+    more();\n".to_string(),
+                    added_lines: vec![3],
+                },
+                DiffHunk {
+                    header: "@@ -12,1 +13,1 @@".to_string(),
+                    new_start: 13,
+                    text: "+    y\n".to_string(),
+                    added_lines: vec![13],
+                },
+            ],
+        };
+        let chunks = build_chunks(&patch, Some(content));
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].symbol.as_deref(), Some("a"));
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (2, 3));
+        assert_eq!(chunks[1].symbol.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_parse_review_answer() {
+        let chunk = ReviewChunk {
+            path: "src/lib.rs".to_string(),
+            symbol: None,
+            text: String::new(),
+            start_line: 10,
+            end_line: 20,
+        };
+        let answer = "Here you go:\n[{\"line\": 12, \"severity\": \"error\", \"message\": \"Possible panic\", \"suggestion\": \"x.get(0)\"},\
+            {\"line\": 99, \"severity\": \"nit\", \"message\": \"Rename\"},\
+            {\"line\": 11, \"message\": \"\"}]";
+        let comments = parse_review_answer(answer, &chunk);
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].severity, "error");
+        assert_eq!(comments[0].suggestion.as_deref(), Some("x.get(0)"));
+        // Out-of-range lines snap to the chunk start; unknown severities become info
+        assert_eq!(comments[1].line, 10);
+        assert_eq!(comments[1].severity, "info");
+        assert!(parse_review_answer("no json", &chunk).is_empty());
+    }
+}
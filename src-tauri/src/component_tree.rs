@@ -0,0 +1,359 @@
+// React/Vue component tree
+//
+// Starting from an entry component, follows the components each file renders (JSX
+// elements in React files, template tags in Vue single-file components) back to their
+// imports and builds a render-tree-like hierarchy. Each node carries the component's
+// props declaration when one can be found, which is what UI change planning needs.
+
+use crate::code_navigation::get_language;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tree_sitter::{Node, Parser};
+
+const DEFAULT_MAX_DEPTH: usize = 8;
+const MAX_PROPS_CHARS: usize = 1_500;
+const RESOLVE_EXTENSIONS: &[&str] = &["tsx", "jsx", "ts", "js", "vue"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentNode {
+    pub name: String,
+    pub file_path: String,
+    pub props: Option<String>,
+    pub children: Vec<ComponentNode>,
+    /// The component renders one of its ancestors
+    pub cycle: bool,
+    /// Children were not expanded (depth limit or already expanded elsewhere)
+    pub truncated: bool,
+}
+
+fn import_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"import\s+(?:type\s+)?([\w$]+\s*,?\s*(?:\{[^}]*\})?|\{[^}]*\})\s*from\s*['"]([^'"]+)['"]"#)
+            .unwrap()
+    })
+}
+
+fn vue_tag_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"<([A-Z][\w]*|[a-z][a-z0-9]*(?:-[a-z0-9]+)+)[\s/>]").unwrap())
+}
+
+/// Local name -> module specifier for every named or default import
+pub fn parse_imports(content: &str) -> HashMap<String, String> {
+    let mut imports = HashMap::new();
+    for caps in import_regex().captures_iter(content) {
+        let clause = &caps[1];
+        let source = caps[2].to_string();
+        let (default, named) = match clause.find('{') {
+            Some(pos) => (&clause[..pos], &clause[pos..]),
+            None => (clause, ""),
+        };
+        let default = default.trim().trim_end_matches(',').trim();
+        if !default.is_empty() {
+            imports.insert(default.to_string(), source.clone());
+        }
+        for item in named.trim_matches(|c| c == '{' || c == '}').split(',') {
+            let item = item.trim().trim_start_matches("type ");
+            let local = item.rsplit(" as ").next().unwrap_or(item).trim();
+            if !local.is_empty() {
+                imports.insert(local.to_string(), source.clone());
+            }
+        }
+    }
+    imports
+}
+
+fn kebab_to_pascal(tag: &str) -> String {
+    tag.split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn collect_jsx_names(node: Node, source: &[u8], out: &mut Vec<String>) {
+    if matches!(
+        node.kind(),
+        "jsx_opening_element" | "jsx_self_closing_element"
+    ) {
+        if let Some(name) = node
+            .child_by_field_name("name")
+            .and_then(|n| n.utf8_text(source).ok())
+        {
+            out.push(name.to_string());
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_jsx_names(child, source, out);
+    }
+}
+
+/// Component names rendered by a file, in order of first use
+pub fn rendered_components(content: &str, is_vue: bool) -> Vec<String> {
+    let mut names = Vec::new();
+    if is_vue {
+        let template = content
+            .find("<template")
+            .and_then(|start| content.rfind("</template>").map(|end| &content[start..end]))
+            .unwrap_or("");
+        names.extend(
+            vue_tag_regex()
+                .captures_iter(template)
+                .map(|caps| kebab_to_pascal(&caps[1])),
+        );
+    } else if let Some(language) = get_language("tsx") {
+        let mut parser = Parser::new();
+        if parser.set_language(&language).is_ok() {
+            if let Some(tree) = parser.parse(content, None) {
+                collect_jsx_names(tree.root_node(), content.as_bytes(), &mut names);
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    names
+        .into_iter()
+        // Intrinsic elements are lowercase; `Foo.Bar` resolves through `Foo`
+        .filter(|name| name.starts_with(|c: char| c.is_ascii_uppercase()))
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+/// Text of the block opened by the `{` at `open`
+fn brace_block(content: &str, open: usize) -> Option<&str> {
+    let mut depth = 0;
+    for (offset, c) in content[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&content[open..open + offset + 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The props declaration of `component` in its file
+pub fn extract_props(content: &str, component: &str) -> Option<String> {
+    let patterns = [
+        format!(
+            r"(?:interface|type)\s+{}Props\b[^{{]*\{{",
+            regex::escape(component)
+        ),
+        r"defineProps\s*(?:<|\()\s*\{".to_string(),
+        r"\bprops\s*:\s*\{".to_string(),
+        r"(?:interface|type)\s+Props\b[^{]*\{".to_string(),
+    ];
+    patterns.iter().find_map(|pattern| {
+        let m = Regex::new(pattern).ok()?.find(content)?;
+        let block = brace_block(content, m.end() - 1)?;
+        let text: String = block.chars().take(MAX_PROPS_CHARS).collect();
+        Some(text)
+    })
+}
+
+/// Resolve a relative or `@/`-aliased import to a file
+pub fn resolve_import(root: &Path, from_file: &Path, specifier: &str) -> Option<PathBuf> {
+    let base = if specifier.starts_with('.') {
+        from_file.parent()?.join(specifier)
+    } else if let Some(rest) = specifier
+        .strip_prefix("@/")
+        .or_else(|| specifier.strip_prefix("~/"))
+    {
+        root.join("src").join(rest)
+    } else {
+        return None;
+    };
+    let found = if base.is_file() {
+        Some(base)
+    } else {
+        RESOLVE_EXTENSIONS
+            .iter()
+            .map(|ext| PathBuf::from(format!("{}.{}", base.display(), ext)))
+            .chain(
+                RESOLVE_EXTENSIONS
+                    .iter()
+                    .map(|ext| base.join(format!("index.{}", ext))),
+            )
+            .find(|candidate| candidate.is_file())
+    };
+    // Normalize `..` so the same file reached via different imports compares equal
+    found.map(|path| fs::canonicalize(&path).unwrap_or(path))
+}
+
+struct TreeBuilder<'a> {
+    root: &'a Path,
+    max_depth: usize,
+    expanded: HashSet<PathBuf>,
+}
+
+impl TreeBuilder<'_> {
+    fn build(&mut self, name: &str, path: &Path, stack: &mut Vec<PathBuf>) -> ComponentNode {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let mut node = ComponentNode {
+            name: name.to_string(),
+            file_path: path.to_string_lossy().to_string(),
+            props: extract_props(&content, name),
+            children: Vec::new(),
+            cycle: false,
+            truncated: false,
+        };
+        if stack.iter().any(|p| p == path) {
+            node.cycle = true;
+            return node;
+        }
+        if stack.len() >= self.max_depth || !self.expanded.insert(path.to_path_buf()) {
+            node.truncated = true;
+            return node;
+        }
+
+        let is_vue = path.extension().is_some_and(|e| e == "vue");
+        let imports = parse_imports(&content);
+        stack.push(path.to_path_buf());
+        for component in rendered_components(&content, is_vue) {
+            let import_name = component.split('.').next().unwrap_or(&component);
+            let Some(child_path) = imports
+                .get(import_name)
+                .and_then(|spec| resolve_import(self.root, path, spec))
+            else {
+                continue;
+            };
+            let child = self.build(&component, &child_path, stack);
+            node.children.push(child);
+        }
+        stack.pop();
+        node
+    }
+}
+
+pub fn build_component_tree(root: &Path, entry: &Path, max_depth: usize) -> ComponentNode {
+    let name = entry
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut builder = TreeBuilder {
+        root,
+        max_depth,
+        expanded: HashSet::new(),
+    };
+    let entry = fs::canonicalize(entry).unwrap_or_else(|_| entry.to_path_buf());
+    builder.build(&name, &entry, &mut Vec::new())
+}
+
+#[tauri::command]
+pub async fn get_component_tree(
+    root_path: String,
+    entry: String,
+    max_depth: Option<usize>,
+) -> Result<ComponentNode, String> {
+    if !Path::new(&entry).is_file() {
+        return Err(format!("Entry component not found: {}", entry));
+    }
+    tokio::task::spawn_blocking(move || {
+        build_component_tree(
+            Path::new(&root_path),
+            Path::new(&entry),
+            max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+        )
+    })
+    .await
+    .map_err(|e| format!("Component tree build failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_imports() {
+        let imports = parse_imports(
+            "import React, { useState } from 'react';\nimport Header from './Header';\nimport { Button as Btn, type Size } from \"@/ui\";\n",
+        );
+        assert_eq!(imports.get("Header").map(String::as_str), Some("./Header"));
+        assert_eq!(imports.get("Btn").map(String::as_str), Some("@/ui"));
+        assert_eq!(imports.get("Size").map(String::as_str), Some("@/ui"));
+        assert_eq!(imports.get("React").map(String::as_str), Some("react"));
+        assert!(!imports.contains_key("Button"));
+    }
+
+    #[test]
+    fn test_rendered_components() {
+        let tsx = "export function App() {\n  const [s] = useState<User>(null);\n  return <Layout><div><Header title=\"x\" /><Icons.Star /><Header /></div></Layout>;\n}\n";
+        assert_eq!(
+            rendered_components(tsx, false),
+            vec!["Layout", "Header", "Icons.Star"]
+        );
+
+        let vue = "<template>\n  <div>\n    <user-card :user=\"u\" />\n    <BaseButton>ok</BaseButton>\n  </div>\n</template>\n";
+        assert_eq!(
+            rendered_components(vue, true),
+            vec!["UserCard", "BaseButton"]
+        );
+    }
+
+    #[test]
+    fn test_extract_props() {
+        let content = "interface HeaderProps extends Base {\n  title: string;\n  meta?: { a: number };\n}\nexport function Header(props: HeaderProps) {}\n";
+        assert_eq!(
+            extract_props(content, "Header").unwrap(),
+            "{\n  title: string;\n  meta?: { a: number };\n}"
+        );
+        let vue =
+            "<script setup lang=\"ts\">\nconst props = defineProps<{ user: User }>();\n</script>\n";
+        assert_eq!(extract_props(vue, "UserCard").unwrap(), "{ user: User }");
+        assert!(extract_props("const x = 1;", "Foo").is_none());
+    }
+
+    #[test]
+    fn test_build_component_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let src = root.join("src");
+        fs::create_dir_all(src.join("components")).unwrap();
+        fs::create_dir_all(src.join("ui")).unwrap();
+        fs::write(
+            src.join("App.tsx"),
+            "import Header from './components/Header';\nimport { Button } from '@/ui';\nexport default function App() { return <main><Header title=\"t\" /><Button /></main>; }\n",
+        )
+        .unwrap();
+        fs::write(
+            src.join("components/Header.tsx"),
+            "import { Button } from '../ui';\nimport App from '../App';\ninterface HeaderProps { title: string }\nexport default function Header(p: HeaderProps) { return <Button><App /></Button>; }\n",
+        )
+        .unwrap();
+        fs::write(
+            src.join("ui/index.tsx"),
+            "export function Button() { return <button />; }\n",
+        )
+        .unwrap();
+
+        let tree = build_component_tree(root, &src.join("App.tsx"), 8);
+        assert_eq!(tree.name, "App");
+        assert_eq!(tree.children.len(), 2);
+
+        let header = &tree.children[0];
+        assert_eq!(header.name, "Header");
+        assert_eq!(header.props.as_deref(), Some("{ title: string }"));
+        assert_eq!(header.children.len(), 2);
+        assert_eq!(header.children[0].name, "Button");
+        assert!(header.children[1].cycle);
+
+        // Button was already expanded under Header
+        assert!(tree.children[1].truncated);
+    }
+}
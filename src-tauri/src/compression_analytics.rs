@@ -0,0 +1,226 @@
+// Per-session compression analytics
+//
+// Each context compaction records what it did: how many files were summarized, how
+// many fell back to their original content because a summary could not be trusted
+// (unsupported language, already compact, truncated outline), the estimated tokens
+// before and after, and the time it took. `get_compression_report` aggregates the
+// records of a session so users can see how much of the context budget compaction
+// actually reclaims.
+
+use crate::database::Database;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::State;
+
+const SCHEMA: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS compression_events (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        conversation_id TEXT NOT NULL,
+        files_compressed INTEGER NOT NULL,
+        fidelity_fallbacks INTEGER NOT NULL,
+        tokens_before INTEGER NOT NULL,
+        tokens_after INTEGER NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        created_at INTEGER NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_compression_events_conversation ON compression_events (conversation_id, created_at)",
+];
+
+/// Statistics of one compaction, as reported by the caller that ran it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompactionStats {
+    /// Files replaced by their summary
+    pub files_compressed: u64,
+    /// Files kept verbatim or as an outline because the summary was not usable
+    pub fidelity_fallbacks: u64,
+    pub tokens_before: u64,
+    pub tokens_after: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionRecord {
+    #[serde(flatten)]
+    pub stats: CompactionStats,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionReport {
+    pub session_id: String,
+    pub compactions: usize,
+    pub files_compressed: u64,
+    pub fidelity_fallbacks: u64,
+    pub tokens_before: u64,
+    pub tokens_after: u64,
+    pub tokens_saved: u64,
+    /// Share of the original tokens that compaction removed, 0.0 to 1.0
+    pub savings_ratio: f64,
+    pub total_duration_ms: u64,
+    /// Oldest first
+    pub records: Vec<CompactionRecord>,
+}
+
+async fn ensure_schema(db: &Database) -> Result<(), String> {
+    for sql in SCHEMA {
+        db.execute(sql, vec![]).await?;
+    }
+    Ok(())
+}
+
+fn row_u64(row: &Value, column: &str) -> u64 {
+    row.get(column).and_then(|v| v.as_i64()).unwrap_or(0).max(0) as u64
+}
+
+pub async fn record_compaction(
+    db: &Database,
+    session_id: &str,
+    stats: &CompactionStats,
+) -> Result<(), String> {
+    ensure_schema(db).await?;
+    db.execute(
+        "INSERT INTO compression_events (conversation_id, files_compressed, fidelity_fallbacks, tokens_before, tokens_after, duration_ms, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        vec![
+            Value::from(session_id),
+            Value::from(stats.files_compressed),
+            Value::from(stats.fidelity_fallbacks),
+            Value::from(stats.tokens_before),
+            Value::from(stats.tokens_after),
+            Value::from(stats.duration_ms),
+            Value::from(chrono::Utc::now().timestamp_millis()),
+        ],
+    )
+    .await?;
+    Ok(())
+}
+
+/// Aggregate the compactions recorded for a session
+pub fn build_report(session_id: &str, records: Vec<CompactionRecord>) -> CompressionReport {
+    let sum = |f: fn(&CompactionStats) -> u64| records.iter().map(|r| f(&r.stats)).sum::<u64>();
+    let tokens_before = sum(|s| s.tokens_before);
+    let tokens_after = sum(|s| s.tokens_after);
+    let tokens_saved = tokens_before.saturating_sub(tokens_after);
+    CompressionReport {
+        session_id: session_id.to_string(),
+        compactions: records.len(),
+        files_compressed: sum(|s| s.files_compressed),
+        fidelity_fallbacks: sum(|s| s.fidelity_fallbacks),
+        tokens_before,
+        tokens_after,
+        tokens_saved,
+        savings_ratio: if tokens_before == 0 {
+            0.0
+        } else {
+            tokens_saved as f64 / tokens_before as f64
+        },
+        total_duration_ms: sum(|s| s.duration_ms),
+        records,
+    }
+}
+
+pub async fn compression_report(
+    db: &Database,
+    session_id: &str,
+) -> Result<CompressionReport, String> {
+    ensure_schema(db).await?;
+    let rows = db
+        .query(
+            "SELECT files_compressed, fidelity_fallbacks, tokens_before, tokens_after, duration_ms, created_at FROM compression_events WHERE conversation_id = ? ORDER BY created_at ASC, id ASC",
+            vec![Value::from(session_id)],
+        )
+        .await?
+        .rows;
+
+    let records = rows
+        .iter()
+        .map(|row| CompactionRecord {
+            stats: CompactionStats {
+                files_compressed: row_u64(row, "files_compressed"),
+                fidelity_fallbacks: row_u64(row, "fidelity_fallbacks"),
+                tokens_before: row_u64(row, "tokens_before"),
+                tokens_after: row_u64(row, "tokens_after"),
+                duration_ms: row_u64(row, "duration_ms"),
+            },
+            created_at: row.get("created_at").and_then(|v| v.as_i64()).unwrap_or(0),
+        })
+        .collect();
+    Ok(build_report(session_id, records))
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn record_compression_stats(
+    db: State<'_, Arc<Database>>,
+    session_id: String,
+    stats: CompactionStats,
+) -> Result<(), String> {
+    record_compaction(&db, &session_id, &stats).await
+}
+
+#[tauri::command]
+pub async fn get_compression_report(
+    db: State<'_, Arc<Database>>,
+    session_id: String,
+) -> Result<CompressionReport, String> {
+    compression_report(&db, &session_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn stats(files: u64, fallbacks: u64, before: u64, after: u64, ms: u64) -> CompactionStats {
+        CompactionStats {
+            files_compressed: files,
+            fidelity_fallbacks: fallbacks,
+            tokens_before: before,
+            tokens_after: after,
+            duration_ms: ms,
+        }
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = build_report("s1", Vec::new());
+        assert_eq!(report.compactions, 0);
+        assert_eq!(report.savings_ratio, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_report_aggregates_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("t.db").to_string_lossy().to_string());
+        db.connect().await.unwrap();
+
+        record_compaction(&db, "s1", &stats(4, 1, 10_000, 4_000, 30))
+            .await
+            .unwrap();
+        record_compaction(&db, "s1", &stats(2, 0, 6_000, 2_000, 12))
+            .await
+            .unwrap();
+        record_compaction(&db, "s2", &stats(9, 9, 1_000, 1_000, 5))
+            .await
+            .unwrap();
+
+        let report = compression_report(&db, "s1").await.unwrap();
+        assert_eq!(report.compactions, 2);
+        assert_eq!(report.files_compressed, 6);
+        assert_eq!(report.fidelity_fallbacks, 1);
+        assert_eq!(report.tokens_before, 16_000);
+        assert_eq!(report.tokens_saved, 10_000);
+        assert!((report.savings_ratio - 0.625).abs() < 1e-9);
+        assert_eq!(report.total_duration_ms, 42);
+        assert_eq!(report.records[0].stats, stats(4, 1, 10_000, 4_000, 30));
+
+        assert_eq!(
+            compression_report(&db, "missing")
+                .await
+                .unwrap()
+                .compactions,
+            0
+        );
+    }
+}
@@ -0,0 +1,306 @@
+// Structured merge conflicts for the merge UI
+//
+// Agent writes merged against user edits (file_merge), git merges and rebases in
+// worktrees all leave conflict markers in files. Rather than handing the raw markers to
+// the frontend, this parses them into regions with the ours/base/theirs lines, their
+// labels and line ranges, so the UI can render a side-by-side merge view, and resolves
+// one region at a time by choice (ours, theirs, base, both) or custom text.
+//
+// Both plain (`<<<<<<<`, `=======`, `>>>>>>>`) and diff3-style (with a `|||||||` base
+// section) markers are understood.
+
+use crate::line_endings;
+use crate::path_policy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// Lines `start..start + count` of a file, 1-based; `count` is 0 for an empty side,
+/// which then sits before line `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineRange {
+    pub start: usize,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictRegion {
+    pub index: usize,
+    /// The whole block, markers included
+    pub range: LineRange,
+    pub ours_label: String,
+    pub ours: Vec<String>,
+    pub ours_range: LineRange,
+    /// Only present with diff3-style markers
+    pub base_label: Option<String>,
+    pub base: Option<Vec<String>>,
+    pub base_range: Option<LineRange>,
+    pub theirs_label: String,
+    pub theirs: Vec<String>,
+    pub theirs_range: LineRange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileConflicts {
+    pub path: String,
+    pub regions: Vec<ConflictRegion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "choice", rename_all = "snake_case")]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    Base,
+    /// Ours followed by theirs
+    Both,
+    Custom {
+        text: String,
+    },
+}
+
+/// The label after a marker, or None when the line is not that marker
+fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() {
+        Some("")
+    } else {
+        rest.strip_prefix(' ').map(str::trim_end)
+    }
+}
+
+fn range(start: usize, end: usize) -> LineRange {
+    LineRange {
+        start: start + 1,
+        count: end - start,
+    }
+}
+
+fn owned(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|l| l.to_string()).collect()
+}
+
+/// Parse the conflict blocks in `content`; unterminated or misordered markers are errors
+pub fn parse_conflicts(content: &str) -> Result<Vec<ConflictRegion>, String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut regions = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(ours_label) = marker_label(lines[i], OURS_MARKER) else {
+            i += 1;
+            continue;
+        };
+        let start = i;
+        let find = |from: usize, marker: &str| {
+            (from..lines.len()).find(|&k| marker_label(lines[k], marker).is_some())
+        };
+        let unterminated = || format!("Unterminated conflict starting at line {}", start + 1);
+
+        let separator = find(start + 1, SEPARATOR_MARKER).ok_or_else(unterminated)?;
+        let end = find(separator + 1, THEIRS_MARKER).ok_or_else(unterminated)?;
+        if let Some(nested) = find(start + 1, OURS_MARKER).filter(|&k| k < end) {
+            return Err(format!(
+                "Conflict starting at line {} contains another at line {}",
+                start + 1,
+                nested + 1
+            ));
+        }
+        let base = find(start + 1, BASE_MARKER).filter(|&k| k < separator);
+        let ours_end = base.unwrap_or(separator);
+
+        regions.push(ConflictRegion {
+            index: regions.len(),
+            range: range(start, end + 1),
+            ours_label: ours_label.to_string(),
+            ours: owned(&lines[start + 1..ours_end]),
+            ours_range: range(start + 1, ours_end),
+            base_label: base.map(|b| {
+                marker_label(lines[b], BASE_MARKER)
+                    .unwrap_or("")
+                    .to_string()
+            }),
+            base: base.map(|b| owned(&lines[b + 1..separator])),
+            base_range: base.map(|b| range(b + 1, separator)),
+            theirs_label: marker_label(lines[end], THEIRS_MARKER)
+                .unwrap_or("")
+                .to_string(),
+            theirs: owned(&lines[separator + 1..end]),
+            theirs_range: range(separator + 1, end),
+        });
+        i = end + 1;
+    }
+    Ok(regions)
+}
+
+/// `content` with region `index` replaced by the chosen lines
+pub fn resolve(content: &str, index: usize, resolution: &Resolution) -> Result<String, String> {
+    let regions = parse_conflicts(content)?;
+    let region = regions
+        .get(index)
+        .ok_or_else(|| format!("No conflict {} (file has {})", index, regions.len()))?;
+    let replacement: Vec<String> = match resolution {
+        Resolution::Ours => region.ours.clone(),
+        Resolution::Theirs => region.theirs.clone(),
+        Resolution::Base => region
+            .base
+            .clone()
+            .ok_or("This conflict has no base section")?,
+        Resolution::Both => [region.ours.clone(), region.theirs.clone()].concat(),
+        Resolution::Custom { text } => text.lines().map(String::from).collect(),
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let before = &lines[..region.range.start - 1];
+    let after = &lines[region.range.start - 1 + region.range.count..];
+    let mut resolved: Vec<String> = owned(before);
+    resolved.extend(replacement);
+    resolved.extend(owned(after));
+
+    let mut text = resolved.join("\n");
+    if content.ends_with('\n') && !text.is_empty() {
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_conflict_regions(
+    path: String,
+    root_path: Option<String>,
+) -> Result<FileConflicts, String> {
+    path_policy::check_optional(&path, root_path.as_deref())?;
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let regions = parse_conflicts(&line_endings::normalize(&content))?;
+    Ok(FileConflicts { path, regions })
+}
+
+/// Resolve one conflict region in place; returns the regions left in the file
+#[tauri::command]
+pub fn resolve_conflict_region(
+    path: String,
+    index: usize,
+    resolution: Resolution,
+    root_path: Option<String>,
+) -> Result<FileConflicts, String> {
+    path_policy::check_optional(&path, root_path.as_deref())?;
+    let original =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let (content, info) = line_endings::normalize_with_info(&original);
+    let resolved = resolve(&content, index, &resolution)?;
+    fs::write(&path, line_endings::convert(&resolved, info.dominant))
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    let regions = parse_conflicts(&resolved)?;
+    log::info!(
+        "Resolved conflict {} in {} ({} left)",
+        index,
+        path,
+        regions.len()
+    );
+    Ok(FileConflicts { path, regions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const DIFF3: &str = "fn main() {\n<<<<<<< HEAD\n    run(1);\n||||||| base\n    run(0);\n=======\n    run(2);\n    log();\n>>>>>>> feature\n}\n";
+
+    #[test]
+    fn test_parse_diff3_conflict() {
+        let regions = parse_conflicts(DIFF3).unwrap();
+        assert_eq!(regions.len(), 1);
+        let region = &regions[0];
+        assert_eq!(region.range, LineRange { start: 2, count: 8 });
+        assert_eq!(region.ours_label, "HEAD");
+        assert_eq!(region.ours, vec!["    run(1);"]);
+        assert_eq!(region.ours_range, LineRange { start: 3, count: 1 });
+        assert_eq!(region.base_label.as_deref(), Some("base"));
+        assert_eq!(region.base, Some(vec!["    run(0);".to_string()]));
+        assert_eq!(region.theirs_label, "feature");
+        assert_eq!(region.theirs, vec!["    run(2);", "    log();"]);
+        assert_eq!(region.theirs_range, LineRange { start: 7, count: 2 });
+    }
+
+    #[test]
+    fn test_parse_two_way_conflicts() {
+        let content = "<<<<<<< current (on disk)\n=======\nadded\n>>>>>>> agent\nmid\n<<<<<<<\na\n=======\nb\n>>>>>>>\n";
+        let regions = parse_conflicts(content).unwrap();
+        assert_eq!(regions.len(), 2);
+        assert!(regions[0].ours.is_empty());
+        assert_eq!(regions[0].ours_range, LineRange { start: 2, count: 0 });
+        assert_eq!(regions[0].base, None);
+        assert_eq!(regions[1].index, 1);
+        assert_eq!(regions[1].ours_label, "");
+
+        assert!(parse_conflicts("<<<<<<< HEAD\na\n=======\nb\n").is_err());
+        // Markers need to stand alone
+        assert!(parse_conflicts("<<<<<<<< not a marker\n")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            resolve(DIFF3, 0, &Resolution::Theirs).unwrap(),
+            "fn main() {\n    run(2);\n    log();\n}\n"
+        );
+        assert_eq!(
+            resolve(DIFF3, 0, &Resolution::Base).unwrap(),
+            "fn main() {\n    run(0);\n}\n"
+        );
+        assert_eq!(
+            resolve(DIFF3, 0, &Resolution::Both).unwrap(),
+            "fn main() {\n    run(1);\n    run(2);\n    log();\n}\n"
+        );
+        let custom = Resolution::Custom {
+            text: "    run(3);\n".to_string(),
+        };
+        assert_eq!(
+            resolve(DIFF3, 0, &custom).unwrap(),
+            "fn main() {\n    run(3);\n}\n"
+        );
+        assert!(resolve(DIFF3, 1, &Resolution::Ours).is_err());
+        assert!(resolve("<<<<<<<\na\n=======\nb\n>>>>>>>\n", 0, &Resolution::Base).is_err());
+    }
+
+    #[test]
+    fn test_resolve_conflict_region_keeps_crlf() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("main.rs");
+        fs::write(&path, DIFF3.replace('\n', "\r\n")).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let conflicts = get_conflict_regions(path.clone(), None).unwrap();
+        assert_eq!(conflicts.regions[0].ours, vec!["    run(1);"]);
+
+        let left = resolve_conflict_region(path.clone(), 0, Resolution::Ours, None).unwrap();
+        assert!(left.regions.is_empty());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "fn main() {\r\n    run(1);\r\n}\r\n"
+        );
+    }
+
+    #[test]
+    fn test_resolution_serde() {
+        let custom: Resolution =
+            serde_json::from_str(r#"{"choice": "custom", "text": "x"}"#).unwrap();
+        assert_eq!(
+            custom,
+            Resolution::Custom {
+                text: "x".to_string()
+            }
+        );
+        let ours: Resolution = serde_json::from_str(r#"{"choice": "ours"}"#).unwrap();
+        assert_eq!(ours, Resolution::Ours);
+    }
+}
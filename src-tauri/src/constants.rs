@@ -77,6 +77,7 @@ pub const CODE_EXTENSIONS: &[&str] = &[
     "yaml",
     "yml",
     "toml",
+    "gradle",
     "ini",
     "cfg",
     "conf",
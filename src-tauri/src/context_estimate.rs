@@ -0,0 +1,243 @@
+// Context window estimates
+//
+// A request that overflows the model's context window fails with a 400 after the whole
+// prompt was sent. `estimate_context` predicts the size of a proposed context assembly
+// before anything is sent: the text of each item, the framing every message adds, the
+// tool schemas and the instructions a provider injects when tools are present, and a
+// fixed cost per image. Counts are estimates from character counts, tuned per model
+// family (Claude's tokenizer splits code finer than OpenAI's), not tokenizer output;
+// they are meant to warn early, with the near-limit threshold leaving room for error.
+
+use serde::{Deserialize, Serialize};
+
+/// Used when the model's context length is not known, as in the frontend model config
+const DEFAULT_CONTEXT_WINDOW: u64 = 200_000;
+/// Room kept for the response when the model's output limit is not given
+const DEFAULT_RESERVED_OUTPUT: u64 = 8_192;
+/// Share of the input budget above which the estimate is reported as near the limit
+const NEAR_LIMIT_RATIO: f64 = 0.9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextItemKind {
+    System,
+    User,
+    Assistant,
+    ToolResult,
+    /// JSON schema of one tool offered to the model
+    ToolSchema,
+    /// An attached image; its content is not measured
+    Image,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextItem {
+    pub kind: ContextItemKind,
+    #[serde(default)]
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelLimits {
+    /// Model id as configured, with or without a provider prefix (`openai/gpt-5.1`)
+    pub id: String,
+    #[serde(default)]
+    pub context_length: Option<u64>,
+    #[serde(default)]
+    pub max_output_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextEstimate {
+    pub model: String,
+    pub context_window: u64,
+    pub reserved_output: u64,
+    /// Tokens per item, in request order, including per-message framing
+    pub item_tokens: Vec<u64>,
+    /// Request framing and tool instructions not attributable to one item
+    pub overhead_tokens: u64,
+    pub total_tokens: u64,
+    /// Input tokens left after the reserved output; negative when overflowing
+    pub available_tokens: i64,
+    pub fits: bool,
+    pub near_limit: bool,
+}
+
+/// How a model family tokenizes and frames a request
+struct TokenProfile {
+    chars_per_token: f64,
+    /// Role markers and separators around each message
+    message_overhead: u64,
+    /// Priming of the reply, once per request
+    request_overhead: u64,
+    /// Name and wrapping of each tool definition
+    tool_overhead: u64,
+    /// Instructions the provider adds when any tool is offered
+    tools_preamble: u64,
+    image_tokens: u64,
+}
+
+fn profile(model_id: &str) -> TokenProfile {
+    let id = model_id.to_lowercase();
+    let model = id.rsplit('/').next().unwrap_or(&id);
+    if model.contains("claude") {
+        TokenProfile {
+            chars_per_token: 3.5,
+            message_overhead: 5,
+            request_overhead: 3,
+            tool_overhead: 10,
+            tools_preamble: 350,
+            image_tokens: 1_600,
+        }
+    } else if model.starts_with("gpt")
+        || (model.starts_with('o') && model[1..].starts_with(char::is_numeric))
+    {
+        TokenProfile {
+            chars_per_token: 4.0,
+            message_overhead: 4,
+            request_overhead: 3,
+            tool_overhead: 10,
+            tools_preamble: 20,
+            image_tokens: 765,
+        }
+    } else if model.contains("gemini") {
+        TokenProfile {
+            chars_per_token: 4.0,
+            message_overhead: 4,
+            request_overhead: 3,
+            tool_overhead: 10,
+            tools_preamble: 20,
+            image_tokens: 258,
+        }
+    } else {
+        TokenProfile {
+            chars_per_token: 3.8,
+            message_overhead: 4,
+            request_overhead: 3,
+            tool_overhead: 10,
+            tools_preamble: 100,
+            image_tokens: 1_000,
+        }
+    }
+}
+
+/// CJK characters count one token each; other text is divided by the family's ratio
+pub(crate) fn text_tokens(text: &str, chars_per_token: f64) -> u64 {
+    let (cjk, other) = text.chars().fold((0u64, 0u64), |(cjk, other), c| {
+        if crate::is_cjk_char(c) {
+            (cjk + 1, other)
+        } else {
+            (cjk, other + 1)
+        }
+    });
+    cjk + (other as f64 / chars_per_token).ceil() as u64
+}
+
+pub fn estimate(items: &[ContextItem], model: &ModelLimits) -> ContextEstimate {
+    let profile = profile(&model.id);
+    let item_tokens: Vec<u64> = items
+        .iter()
+        .map(|item| match item.kind {
+            ContextItemKind::Image => profile.image_tokens,
+            ContextItemKind::ToolSchema => {
+                text_tokens(&item.content, profile.chars_per_token) + profile.tool_overhead
+            }
+            _ => text_tokens(&item.content, profile.chars_per_token) + profile.message_overhead,
+        })
+        .collect();
+    let has_tools = items
+        .iter()
+        .any(|item| item.kind == ContextItemKind::ToolSchema);
+    let overhead_tokens =
+        profile.request_overhead + if has_tools { profile.tools_preamble } else { 0 };
+    let total_tokens = item_tokens.iter().sum::<u64>() + overhead_tokens;
+
+    let context_window = model.context_length.unwrap_or(DEFAULT_CONTEXT_WINDOW);
+    let reserved_output = model
+        .max_output_tokens
+        .unwrap_or(DEFAULT_RESERVED_OUTPUT)
+        .min(context_window);
+    let budget = context_window - reserved_output;
+    ContextEstimate {
+        model: model.id.clone(),
+        context_window,
+        reserved_output,
+        item_tokens,
+        overhead_tokens,
+        total_tokens,
+        available_tokens: budget as i64 - total_tokens as i64,
+        fits: total_tokens <= budget,
+        near_limit: total_tokens as f64 >= budget as f64 * NEAR_LIMIT_RATIO,
+    }
+}
+
+/// Predicted token usage of `items` sent to `model`, without calling the provider
+#[tauri::command]
+pub fn estimate_context(items: Vec<ContextItem>, model: ModelLimits) -> ContextEstimate {
+    estimate(&items, &model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(kind: ContextItemKind, content: &str) -> ContextItem {
+        ContextItem {
+            kind,
+            content: content.to_string(),
+        }
+    }
+
+    fn model(id: &str, context_length: u64, max_output_tokens: u64) -> ModelLimits {
+        ModelLimits {
+            id: id.to_string(),
+            context_length: Some(context_length),
+            max_output_tokens: Some(max_output_tokens),
+        }
+    }
+
+    #[test]
+    fn test_overhead_per_message_tool_and_image() {
+        let items = vec![
+            item(ContextItemKind::System, &"a".repeat(400)),
+            item(ContextItemKind::User, "你好"),
+            item(ContextItemKind::ToolSchema, &"b".repeat(40)),
+            item(ContextItemKind::Image, ""),
+        ];
+        let estimate = estimate(&items, &model("openai/gpt-5.1", 400_000, 128_000));
+
+        assert_eq!(estimate.item_tokens, vec![104, 6, 20, 765]);
+        assert_eq!(estimate.overhead_tokens, 23);
+        assert_eq!(estimate.total_tokens, 918);
+        assert_eq!(estimate.available_tokens, 272_000 - 918);
+        assert!(estimate.fits);
+        assert!(!estimate.near_limit);
+    }
+
+    #[test]
+    fn test_overflow_and_model_families() {
+        let items = vec![item(ContextItemKind::User, &"x".repeat(35_000))];
+
+        let claude = estimate(&items, &model("claude-sonnet-4", 12_000, 1_000));
+        assert_eq!(claude.item_tokens, vec![10_005]);
+        assert!(claude.fits);
+        assert!(claude.near_limit);
+
+        let gpt = estimate(&items, &model("gpt-4o", 12_000, 4_000));
+        assert_eq!(gpt.item_tokens, vec![8_754]);
+        assert!(!gpt.fits);
+        assert_eq!(gpt.available_tokens, 8_000 - 8_757);
+
+        let unknown = estimate(
+            &items,
+            &ModelLimits {
+                id: "local-model".to_string(),
+                context_length: None,
+                max_output_tokens: None,
+            },
+        );
+        assert_eq!(unknown.context_window, DEFAULT_CONTEXT_WINDOW);
+        assert_eq!(unknown.reserved_output, DEFAULT_RESERVED_OUTPUT);
+        assert!(unknown.fits);
+    }
+}
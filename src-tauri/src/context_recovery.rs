@@ -0,0 +1,285 @@
+// Context overflow recovery
+//
+// A provider rejects a request whose prompt exceeds the model's context window, and the
+// turn fails even though most of the prompt is history the model could do without.
+// `complete_with_recovery` catches that error and retries with the history compacted,
+// one tier at a time from the least to the most lossy: older tool results are cut to
+// their first lines, repeated messages are dropped, and finally everything between the
+// system prompt and the latest turns is replaced by a synopsis. Only tiers that change
+// something count as an attempt, and each one is reported so the caller can tell the
+// user what the model no longer sees.
+
+use crate::context_estimate::text_tokens;
+use crate::conversation_synopsis::{synopsis, DEFAULT_SYNOPSIS_BUDGET};
+use crate::provider_client::{self, ChatMessage, ProviderConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+
+const PROVIDER_TIMEOUT_SECS: u64 = 120;
+/// Token estimates use the generic ratio; they only describe what was saved
+const CHARS_PER_TOKEN: f64 = 4.0;
+/// Older tool results longer than this many lines are cut
+const MAX_TOOL_RESULT_LINES: usize = 20;
+/// Lines kept from the start of a cut tool result
+const KEPT_TOOL_RESULT_LINES: usize = 10;
+/// Messages at the end of the conversation that are never synopsized
+const KEEP_RECENT_MESSAGES: usize = 4;
+
+/// Phrases providers use in context-length errors
+const OVERFLOW_CUES: &[&str] = &[
+    "context_length_exceeded",
+    "context length",
+    "context window",
+    "prompt is too long",
+    "input is too long",
+    "too many tokens",
+    "maximum number of tokens",
+    "exceeds the token limit",
+    "reduce the length",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionTier {
+    SummarizeToolResults,
+    DropDuplicates,
+    SynopsizeHistory,
+}
+
+const TIERS: [CompactionTier; 3] = [
+    CompactionTier::SummarizeToolResults,
+    CompactionTier::DropDuplicates,
+    CompactionTier::SynopsizeHistory,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sacrifice {
+    pub tier: CompactionTier,
+    /// Messages cut, dropped or folded into the synopsis
+    pub messages_affected: usize,
+    pub tokens_before: u64,
+    pub tokens_after: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveredCompletion {
+    pub content: String,
+    /// Tiers applied before the request fit, in order; empty when it fit the first time
+    pub sacrificed: Vec<Sacrifice>,
+    /// The messages that were finally sent
+    pub messages: Vec<ChatMessage>,
+}
+
+pub fn is_context_overflow(error: &str) -> bool {
+    let error = error.to_lowercase();
+    OVERFLOW_CUES.iter().any(|cue| error.contains(cue))
+}
+
+fn tokens(messages: &[ChatMessage]) -> u64 {
+    messages
+        .iter()
+        .map(|m| text_tokens(&m.content, CHARS_PER_TOKEN))
+        .sum()
+}
+
+/// Index of the last user message; what follows it is the turn in progress
+fn current_turn_start(messages: &[ChatMessage]) -> usize {
+    messages
+        .iter()
+        .rposition(|m| m.role == "user")
+        .unwrap_or(messages.len())
+}
+
+fn summarize_tool_results(messages: &mut [ChatMessage]) -> usize {
+    let current = current_turn_start(messages);
+    let mut affected = 0;
+    for message in &mut messages[..current] {
+        if message.role != "tool" {
+            continue;
+        }
+        let lines: Vec<&str> = message.content.lines().collect();
+        if lines.len() <= MAX_TOOL_RESULT_LINES {
+            continue;
+        }
+        message.content = format!(
+            "{}\n[... tool result truncated: {} of {} lines omitted]",
+            lines[..KEPT_TOOL_RESULT_LINES].join("\n"),
+            lines.len() - KEPT_TOOL_RESULT_LINES,
+            lines.len()
+        );
+        affected += 1;
+    }
+    affected
+}
+
+/// Drop messages repeated later in the conversation, keeping the latest copy
+fn drop_duplicates(messages: &mut Vec<ChatMessage>) -> usize {
+    let mut seen = HashSet::new();
+    let mut keep: Vec<bool> = messages
+        .iter()
+        .rev()
+        .map(|m| m.role == "system" || seen.insert((m.role.clone(), m.content.clone())))
+        .collect();
+    keep.reverse();
+    let before = messages.len();
+    let mut flags = keep.into_iter();
+    messages.retain(|_| flags.next().unwrap_or(true));
+    before - messages.len()
+}
+
+/// Replace the history between the leading system messages and the latest turns with
+/// a synopsis of it
+fn synopsize_history(messages: &mut Vec<ChatMessage>) -> usize {
+    let head = messages.iter().take_while(|m| m.role == "system").count();
+    let tail =
+        current_turn_start(messages).min(messages.len().saturating_sub(KEEP_RECENT_MESSAGES));
+    if tail <= head {
+        return 0;
+    }
+    let summary = synopsis(&messages[head..tail], DEFAULT_SYNOPSIS_BUDGET);
+    let affected = tail - head;
+    messages.splice(head..tail, [ChatMessage::system(summary.text)]);
+    affected
+}
+
+/// `messages` compacted by `tier`, with what it cost
+pub fn compact(messages: &[ChatMessage], tier: CompactionTier) -> (Vec<ChatMessage>, Sacrifice) {
+    let mut compacted = messages.to_vec();
+    let messages_affected = match tier {
+        CompactionTier::SummarizeToolResults => summarize_tool_results(&mut compacted),
+        CompactionTier::DropDuplicates => drop_duplicates(&mut compacted),
+        CompactionTier::SynopsizeHistory => synopsize_history(&mut compacted),
+    };
+    let sacrifice = Sacrifice {
+        tier,
+        messages_affected,
+        tokens_before: tokens(messages),
+        tokens_after: tokens(&compacted),
+    };
+    (compacted, sacrifice)
+}
+
+/// Chat completion that, when the provider reports a context overflow, compacts the
+/// conversation one tier further and retries
+#[tauri::command]
+pub async fn complete_with_recovery(
+    provider: ProviderConfig,
+    messages: Vec<ChatMessage>,
+) -> Result<RecoveredCompletion, String> {
+    let mut messages = messages;
+    let mut sacrificed = Vec::new();
+    let mut tiers = TIERS.iter();
+    loop {
+        let error = match provider_client::chat_completion(
+            &provider,
+            &messages,
+            Duration::from_secs(PROVIDER_TIMEOUT_SECS),
+        )
+        .await
+        {
+            Ok(content) => {
+                return Ok(RecoveredCompletion {
+                    content,
+                    sacrificed,
+                    messages,
+                })
+            }
+            Err(e) if is_context_overflow(&e) => e,
+            Err(e) => return Err(e),
+        };
+
+        let (compacted, sacrifice) = loop {
+            let Some(tier) = tiers.next() else {
+                return Err(format!(
+                    "{} (still too large after all compaction tiers)",
+                    error
+                ));
+            };
+            let (compacted, sacrifice) = compact(&messages, *tier);
+            if sacrifice.messages_affected > 0 {
+                break (compacted, sacrifice);
+            }
+        };
+        log::info!(
+            "Context overflow for {}: applied {:?} to {} messages ({} -> {} tokens)",
+            provider.model,
+            sacrifice.tier,
+            sacrifice.messages_affected,
+            sacrifice.tokens_before,
+            sacrifice.tokens_after
+        );
+        messages = compacted;
+        sacrificed.push(sacrifice);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_context_overflow() {
+        assert!(is_context_overflow(
+            "Provider returned status 400: This model's maximum context length is 128000 tokens"
+        ));
+        assert!(is_context_overflow(
+            "Provider returned status 400: prompt is too long: 210000 tokens > 200000 maximum"
+        ));
+        assert!(!is_context_overflow("Provider returned status 429"));
+        assert!(!is_context_overflow("Request failed: connection refused"));
+    }
+
+    #[test]
+    fn test_tiers() {
+        let long_result = (1..=30)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let messages = vec![
+            message("system", "You are a coding agent."),
+            message("user", "Read the log. We decided to use SQLite."),
+            message("tool", &long_result),
+            message("assistant", "Done."),
+            message("user", "Check again."),
+            message("assistant", "Done."),
+            message("user", "Now fix it."),
+            message("tool", &long_result),
+        ];
+
+        // Only the tool result before the current turn is cut
+        let (cut, sacrifice) = compact(&messages, CompactionTier::SummarizeToolResults);
+        assert_eq!(sacrifice.messages_affected, 1);
+        assert!(cut[2]
+            .content
+            .ends_with("[... tool result truncated: 20 of 30 lines omitted]"));
+        assert_eq!(cut[7].content, long_result);
+        assert!(sacrifice.tokens_after < sacrifice.tokens_before);
+
+        // The earlier "Done." is dropped
+        let (deduped, sacrifice) = compact(&cut, CompactionTier::DropDuplicates);
+        assert_eq!(sacrifice.messages_affected, 1);
+        assert_eq!(deduped.len(), 7);
+        assert_eq!(deduped[3].content, "Check again.");
+
+        let (summarized, sacrifice) = compact(&deduped, CompactionTier::SynopsizeHistory);
+        assert_eq!(sacrifice.messages_affected, 2);
+        let roles: Vec<&str> = summarized.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(
+            roles,
+            ["system", "system", "user", "assistant", "user", "tool"]
+        );
+        assert!(summarized[1].content.contains("We decided to use SQLite."));
+
+        // Nothing is left to fold into a synopsis
+        let (_, sacrifice) = compact(&summarized, CompactionTier::SynopsizeHistory);
+        assert_eq!(sacrifice.messages_affected, 0);
+    }
+}
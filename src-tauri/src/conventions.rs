@@ -0,0 +1,591 @@
+// Project convention learning
+// Derives naming styles, test layout, error-handling patterns and preferred libraries
+// from the indexed code and manifest files, and renders them as a compact markdown
+// document the context assembler can include in the system prompt.
+
+use crate::code_navigation::{resolve_nav, CodeNavState, CodeNavigationService, SymbolInfo};
+use crate::path_utils;
+use crate::walker::{WalkerConfig, WorkspaceWalker};
+use crate::workspace_state::WorkspaceState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+/// Upper bound on files visited when scanning the workspace
+const MAX_FILES: usize = 5_000;
+/// Upper bound on files whose content is read for pattern counting
+const MAX_CONTENT_FILES: usize = 1_500;
+const MAX_FILE_SIZE: u64 = 256 * 1024;
+/// A style must cover at least this share of samples to be reported as the convention
+const DOMINANCE_THRESHOLD: f64 = 0.6;
+const MIN_SAMPLES: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamingConvention {
+    pub lang_family: String,
+    pub category: String, // "functions", "types", "constants", "files"
+    pub style: String,
+    pub share: f64,
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternCount {
+    pub lang_family: String,
+    pub pattern: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectConventions {
+    pub root_path: String,
+    pub naming: Vec<NamingConvention>,
+    pub test_layout: Vec<String>,
+    pub error_handling: Vec<PatternCount>,
+    pub libraries: BTreeMap<String, Vec<String>>,
+    pub document: String,
+}
+
+/// Classify an identifier's casing style
+pub fn classify_case(name: &str) -> &'static str {
+    let name = name.trim_start_matches('_');
+    if name.is_empty() || !name.chars().next().is_some_and(|c| c.is_alphabetic()) {
+        return "other";
+    }
+    let has_upper = name.chars().any(|c| c.is_uppercase());
+    let has_lower = name.chars().any(|c| c.is_lowercase());
+    let has_underscore = name.contains('_');
+    let has_dash = name.contains('-');
+    let first_upper = name.chars().next().is_some_and(|c| c.is_uppercase());
+
+    if has_dash && !has_upper && !has_underscore {
+        "kebab-case"
+    } else if has_dash {
+        "other"
+    } else if has_upper && !has_lower {
+        "SCREAMING_SNAKE_CASE"
+    } else if has_underscore && !has_upper {
+        "snake_case"
+    } else if has_underscore {
+        "other"
+    } else if first_upper {
+        "PascalCase"
+    } else if has_upper {
+        "camelCase"
+    } else {
+        // Single lowercase word fits several styles; don't let it vote
+        "lowercase"
+    }
+}
+
+fn category_for_kind(kind: &str) -> &'static str {
+    match kind {
+        "function" | "method" => "functions",
+        "class" | "struct" | "enum" | "trait" | "interface" | "type" => "types",
+        "const" | "static" => "constants",
+        _ => "other",
+    }
+}
+
+/// Pick the dominant style from counts, if there is one
+fn dominant_style(counts: &HashMap<&'static str, usize>) -> Option<(&'static str, f64, usize)> {
+    let voting: usize = counts
+        .iter()
+        .filter(|(style, _)| **style != "lowercase" && **style != "other")
+        .map(|(_, n)| n)
+        .sum();
+    if voting < MIN_SAMPLES {
+        return None;
+    }
+    let mut ranked: Vec<(&&'static str, &usize)> = counts
+        .iter()
+        .filter(|(style, _)| **style != "lowercase" && **style != "other")
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let (style, count) = ranked.first()?;
+    let share = **count as f64 / voting as f64;
+    (share >= DOMINANCE_THRESHOLD).then_some((**style, share, voting))
+}
+
+fn naming_from_symbols(symbols: &[SymbolInfo]) -> Vec<NamingConvention> {
+    let mut buckets: BTreeMap<(String, &'static str), HashMap<&'static str, usize>> =
+        BTreeMap::new();
+    for symbol in symbols {
+        let category = category_for_kind(&symbol.kind);
+        if category == "other" {
+            continue;
+        }
+        *buckets
+            .entry((symbol.lang_family.clone(), category))
+            .or_default()
+            .entry(classify_case(&symbol.name))
+            .or_default() += 1;
+    }
+
+    buckets
+        .into_iter()
+        .filter_map(|((lang_family, category), counts)| {
+            dominant_style(&counts).map(|(style, share, samples)| NamingConvention {
+                lang_family,
+                category: category.to_string(),
+                style: style.to_string(),
+                share,
+                samples,
+            })
+        })
+        .collect()
+}
+
+fn naming_from_files(files: &[String]) -> Vec<NamingConvention> {
+    let mut buckets: BTreeMap<String, HashMap<&'static str, usize>> = BTreeMap::new();
+    for file in files {
+        let Some(lang_id) = CodeNavigationService::get_lang_id_from_path(file) else {
+            continue;
+        };
+        let stem = Path::new(file)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.split('.').next())
+            .unwrap_or("");
+        if stem == "index" || stem == "mod" || stem == "main" || stem == "lib" {
+            continue;
+        }
+        let family = CodeNavigationService::get_lang_family(&lang_id).to_string();
+        *buckets
+            .entry(family)
+            .or_default()
+            .entry(classify_case(stem))
+            .or_default() += 1;
+    }
+
+    buckets
+        .into_iter()
+        .filter_map(|(lang_family, counts)| {
+            dominant_style(&counts).map(|(style, share, samples)| NamingConvention {
+                lang_family,
+                category: "files".to_string(),
+                style: style.to_string(),
+                share,
+                samples,
+            })
+        })
+        .collect()
+}
+
+/// Describe where tests live, based on relative file paths
+fn detect_test_layout(files: &[String], rust_inline_tests: usize) -> Vec<String> {
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for file in files {
+        let normalized = path_utils::to_slash(file);
+        let name = path_utils::file_name(&normalized);
+        let layout = if normalized.contains("/__tests__/") || normalized.starts_with("__tests__/") {
+            Some("JS/TS tests in `__tests__/` directories")
+        } else if name.contains(".test.") || name.contains(".spec.") {
+            Some("JS/TS tests colocated as `*.test.*` / `*.spec.*` next to sources")
+        } else if name.ends_with("_test.go") {
+            Some("Go tests colocated as `*_test.go`")
+        } else if name.starts_with("test_") && name.ends_with(".py") {
+            Some("Python tests as `test_*.py`")
+        } else if normalized.starts_with("tests/") && name.ends_with(".rs") {
+            Some("Rust integration tests in `tests/`")
+        } else if normalized.contains("src/test/java/") {
+            Some("Java tests under `src/test/java/`")
+        } else {
+            None
+        };
+        if let Some(layout) = layout {
+            *counts.entry(layout).or_default() += 1;
+        }
+    }
+    if rust_inline_tests > 0 {
+        counts.insert(
+            "Rust unit tests inline in `#[cfg(test)] mod tests`",
+            rust_inline_tests,
+        );
+    }
+
+    let mut ranked: Vec<(&str, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    ranked
+        .into_iter()
+        .map(|(layout, n)| format!("{} ({} files)", layout, n))
+        .collect()
+}
+
+/// Error-handling idioms per language family: (family, label, needle)
+const ERROR_PATTERNS: &[(&str, &str, &str)] = &[
+    ("rust", "`Result<_, String>` errors", "Result<"),
+    ("rust", "`?` propagation", ")?"),
+    ("rust", "`.map_err(...)`", ".map_err("),
+    ("rust", "`.unwrap()`", ".unwrap()"),
+    ("rust", "`anyhow`", "anyhow::"),
+    ("rust", "`thiserror`", "thiserror"),
+    ("js_family", "`try/catch`", "try {"),
+    ("js_family", "`throw new Error`", "throw new "),
+    ("js_family", "`.catch(...)`", ".catch("),
+    ("python", "`try/except`", "except "),
+    ("python", "`raise`", "raise "),
+    ("go", "`if err != nil`", "if err != nil"),
+    ("go", "`fmt.Errorf` wrapping", "fmt.Errorf("),
+    ("java", "`try/catch`", "catch ("),
+    ("java", "`throws` declarations", "throws "),
+];
+
+fn count_error_patterns(
+    family: &str,
+    content: &str,
+    counts: &mut BTreeMap<(String, String), usize>,
+) {
+    for (pattern_family, label, needle) in ERROR_PATTERNS {
+        if *pattern_family != family {
+            continue;
+        }
+        let n = content.matches(needle).count();
+        if n > 0 {
+            *counts
+                .entry((family.to_string(), label.to_string()))
+                .or_default() += n;
+        }
+    }
+}
+
+/// Read declared dependencies from common manifest files at the project root
+fn detect_libraries(root: &Path) -> BTreeMap<String, Vec<String>> {
+    let mut libraries = BTreeMap::new();
+
+    if let Ok(raw) = fs::read_to_string(root.join("package.json")) {
+        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&raw) {
+            let mut deps: Vec<String> = ["dependencies", "devDependencies"]
+                .iter()
+                .filter_map(|key| json.get(key).and_then(|d| d.as_object()))
+                .flat_map(|obj| obj.keys().cloned())
+                .collect();
+            deps.sort();
+            deps.dedup();
+            if !deps.is_empty() {
+                libraries.insert("npm".to_string(), deps);
+            }
+        }
+    }
+
+    for manifest in ["Cargo.toml", "src-tauri/Cargo.toml"] {
+        if let Ok(raw) = fs::read_to_string(root.join(manifest)) {
+            let deps = parse_cargo_dependencies(&raw);
+            if !deps.is_empty() {
+                libraries
+                    .entry("cargo".to_string())
+                    .or_insert_with(Vec::new)
+                    .extend(deps);
+            }
+        }
+    }
+    if let Some(deps) = libraries.get_mut("cargo") {
+        deps.sort();
+        deps.dedup();
+    }
+
+    if let Ok(raw) = fs::read_to_string(root.join("requirements.txt")) {
+        let deps: Vec<String> = raw
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('-'))
+            .filter_map(|l| {
+                l.split(|c: char| "=<>~![; ".contains(c))
+                    .next()
+                    .map(String::from)
+            })
+            .collect();
+        if !deps.is_empty() {
+            libraries.insert("pip".to_string(), deps);
+        }
+    }
+
+    if let Ok(raw) = fs::read_to_string(root.join("go.mod")) {
+        let deps: Vec<String> = raw
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.starts_with("module") && !l.starts_with("go ") && !l.starts_with("//"))
+            .map(|l| l.trim_start_matches("require").trim())
+            .filter_map(|l| {
+                let name = l.split_whitespace().next()?;
+                name.contains('.').then(|| name.to_string())
+            })
+            .collect();
+        if !deps.is_empty() {
+            libraries.insert("go".to_string(), deps);
+        }
+    }
+
+    libraries
+}
+
+/// Extract crate names from the dependency tables of a Cargo manifest
+fn parse_cargo_dependencies(raw: &str) -> Vec<String> {
+    let mut deps = Vec::new();
+    let mut in_deps = false;
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_deps = line.ends_with("dependencies]") && !line.contains("build-dependencies");
+            continue;
+        }
+        if !in_deps || line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((name, _)) = line.split_once('=') {
+            deps.push(name.trim().trim_matches('"').to_string());
+        }
+    }
+    deps
+}
+
+/// Render the conventions as a compact markdown document
+fn render_document(conventions: &ProjectConventions) -> String {
+    let mut doc = String::from("# Project conventions\n");
+
+    if !conventions.naming.is_empty() {
+        doc.push_str("\n## Naming\n");
+        for n in &conventions.naming {
+            doc.push_str(&format!(
+                "- {} {}: {} ({:.0}% of {})\n",
+                n.lang_family,
+                n.category,
+                n.style,
+                n.share * 100.0,
+                n.samples
+            ));
+        }
+    }
+
+    if !conventions.test_layout.is_empty() {
+        doc.push_str("\n## Tests\n");
+        for layout in &conventions.test_layout {
+            doc.push_str(&format!("- {}\n", layout));
+        }
+    }
+
+    if !conventions.error_handling.is_empty() {
+        doc.push_str("\n## Error handling\n");
+        for p in &conventions.error_handling {
+            doc.push_str(&format!(
+                "- {}: {} ({}×)\n",
+                p.lang_family, p.pattern, p.count
+            ));
+        }
+    }
+
+    if !conventions.libraries.is_empty() {
+        doc.push_str("\n## Libraries\n");
+        for (ecosystem, deps) in &conventions.libraries {
+            let shown: Vec<&str> = deps.iter().take(25).map(String::as_str).collect();
+            let more = deps.len().saturating_sub(shown.len());
+            doc.push_str(&format!("- {}: {}", ecosystem, shown.join(", ")));
+            if more > 0 {
+                doc.push_str(&format!(" (+{} more)", more));
+            }
+            doc.push('\n');
+        }
+    }
+
+    doc.trim_end().to_string()
+}
+
+/// Analyze a workspace. `symbols` are the indexed definitions under `root_path`.
+pub fn analyze_conventions(root_path: &str, symbols: &[SymbolInfo]) -> ProjectConventions {
+    let root = Path::new(root_path);
+    let walker = WorkspaceWalker::new(root_path, WalkerConfig::for_list_files()).build();
+
+    let mut files = Vec::new();
+    let mut content_files = 0;
+    let mut rust_inline_tests = 0;
+    let mut error_counts: BTreeMap<(String, String), usize> = BTreeMap::new();
+
+    for entry in walker.flatten() {
+        if files.len() >= MAX_FILES {
+            break;
+        }
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let relative = path_utils::path_to_slash(relative);
+
+        if content_files < MAX_CONTENT_FILES {
+            if let Some(lang_id) = CodeNavigationService::get_lang_id_from_path(&relative) {
+                let small = entry
+                    .metadata()
+                    .map(|m| m.len() <= MAX_FILE_SIZE)
+                    .unwrap_or(false);
+                if small {
+                    if let Ok(content) = fs::read_to_string(path) {
+                        content_files += 1;
+                        let family = CodeNavigationService::get_lang_family(&lang_id);
+                        if family == "rust" && content.contains("#[cfg(test)]") {
+                            rust_inline_tests += 1;
+                        }
+                        count_error_patterns(family, &content, &mut error_counts);
+                    }
+                }
+            }
+        }
+        files.push(relative);
+    }
+
+    let mut naming = naming_from_symbols(symbols);
+    naming.extend(naming_from_files(&files));
+
+    let mut error_handling: Vec<PatternCount> = error_counts
+        .into_iter()
+        .map(|((lang_family, pattern), count)| PatternCount {
+            lang_family,
+            pattern,
+            count,
+        })
+        .collect();
+    error_handling.sort_by(|a, b| {
+        a.lang_family
+            .cmp(&b.lang_family)
+            .then(b.count.cmp(&a.count))
+            .then(a.pattern.cmp(&b.pattern))
+    });
+
+    let mut conventions = ProjectConventions {
+        root_path: root_path.to_string(),
+        naming,
+        test_layout: detect_test_layout(&files, rust_inline_tests),
+        error_handling,
+        libraries: detect_libraries(root),
+        document: String::new(),
+    };
+    conventions.document = render_document(&conventions);
+    conventions
+}
+
+#[tauri::command]
+pub async fn analyze_project_conventions(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    root_path: String,
+) -> Result<ProjectConventions, String> {
+    let symbols = {
+        let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        service.definitions_under(&root_path)
+    };
+
+    tokio::task::spawn_blocking(move || analyze_conventions(&root_path, &symbols))
+        .await
+        .map_err(|e| format!("Convention analysis failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn symbol(name: &str, kind: &str, family: &str) -> SymbolInfo {
+        SymbolInfo {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            file_path: "/p/x".to_string(),
+            lang_family: family.to_string(),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 1,
+            qualified_name: None,
+        }
+    }
+
+    #[test]
+    fn test_classify_case() {
+        assert_eq!(classify_case("fetch_user"), "snake_case");
+        assert_eq!(classify_case("fetchUser"), "camelCase");
+        assert_eq!(classify_case("UserService"), "PascalCase");
+        assert_eq!(classify_case("MAX_RETRIES"), "SCREAMING_SNAKE_CASE");
+        assert_eq!(classify_case("file-service"), "kebab-case");
+        assert_eq!(classify_case("_private_helper"), "snake_case");
+        assert_eq!(classify_case("run"), "lowercase");
+        assert_eq!(classify_case("9lives"), "other");
+    }
+
+    #[test]
+    fn test_naming_requires_dominance() {
+        let mut symbols: Vec<SymbolInfo> = (0..6)
+            .map(|i| symbol(&format!("do_thing_{}", i), "function", "rust"))
+            .collect();
+        symbols.push(symbol("doThing", "function", "rust"));
+        let naming = naming_from_symbols(&symbols);
+        assert_eq!(naming.len(), 1);
+        assert_eq!(naming[0].style, "snake_case");
+        assert_eq!(naming[0].category, "functions");
+
+        // A 50/50 split is not a convention
+        let mixed: Vec<SymbolInfo> = (0..6)
+            .map(|i| {
+                let name = if i % 2 == 0 { "fooBar" } else { "foo_bar" };
+                symbol(name, "function", "js_family")
+            })
+            .collect();
+        assert!(naming_from_symbols(&mixed).is_empty());
+    }
+
+    #[test]
+    fn test_detect_test_layout() {
+        let files = vec![
+            "src/a.test.ts".to_string(),
+            "src/b.test.ts".to_string(),
+            "pkg/x_test.go".to_string(),
+            "src/c.ts".to_string(),
+        ];
+        let layout = detect_test_layout(&files, 0);
+        assert_eq!(layout.len(), 2);
+        assert!(layout[0].contains("*.test.*"));
+        assert!(layout[0].contains("2 files"));
+    }
+
+    #[test]
+    fn test_parse_cargo_dependencies() {
+        let raw = "[package]\nname = \"x\"\n\n[dependencies]\nserde = \"1\"\ntokio = { version = \"1\" }\n\n[build-dependencies]\ntauri-build = \"2\"\n\n[dev-dependencies]\ntempfile = \"3\"\n";
+        assert_eq!(
+            parse_cargo_dependencies(raw),
+            vec!["serde", "tokio", "tempfile"]
+        );
+    }
+
+    #[test]
+    fn test_analyze_conventions_document() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{"dependencies": {"react": "18"}, "devDependencies": {"vitest": "1"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/user-service.ts"),
+            "export function load() { try { x() } catch (e) { throw new Error('x') } }",
+        )
+        .unwrap();
+        fs::write(root.join("src/user-service.test.ts"), "test('x', () => {})").unwrap();
+
+        let conventions = analyze_conventions(root.to_str().unwrap(), &[]);
+        assert_eq!(conventions.libraries["npm"], vec!["react", "vitest"]);
+        assert!(conventions
+            .error_handling
+            .iter()
+            .any(|p| p.pattern == "`throw new Error`"));
+        assert!(conventions.document.starts_with("# Project conventions"));
+        assert!(conventions.document.contains("## Libraries"));
+        assert!(conventions.document.contains("*.test.*"));
+    }
+}
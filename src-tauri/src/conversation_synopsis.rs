@@ -0,0 +1,430 @@
+// Conversation synopses
+//
+// When old turns are dropped to make room, the session loses what was settled in them.
+// `summarize_conversation` condenses those turns into a synopsis the next request can
+// carry instead: decisions made, files touched, TODOs still open and constraints the
+// user set. Extraction is by cue phrases and path patterns rather than a model call, so
+// it is instant and deterministic and can run on every truncation. Code blocks are
+// skipped. Constraints are only taken from user and system messages, since those are
+// the ones the assistant must keep honoring.
+//
+// Within the token budget, items are kept by priority: constraints, then decisions
+// (newest first), open TODOs, and files by how often they came up.
+
+use crate::code_navigation::CodeNavigationService;
+use crate::context_estimate::text_tokens;
+use crate::provider_client::ChatMessage;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+pub const DEFAULT_SYNOPSIS_BUDGET: u64 = 1_000;
+/// Token estimates use the generic ratio; the synopsis is small enough for it not to matter
+const CHARS_PER_TOKEN: f64 = 4.0;
+/// Sentences longer than this many chars are cut
+const MAX_ITEM_CHARS: usize = 200;
+
+const DECISION_CUES: &[&str] = &[
+    "decided",
+    "decision",
+    "we'll use",
+    "we will use",
+    "let's use",
+    "let's go with",
+    "going with",
+    "chose",
+    "instead of",
+    "switched to",
+    "settled on",
+    "agreed",
+];
+const TODO_CUES: &[&str] = &[
+    "still need",
+    "next step",
+    "remaining",
+    "follow up",
+    "follow-up",
+    "not yet",
+    "left to do",
+];
+/// Extensions that name files without being source code
+const DATA_EXTENSIONS: &[&str] = &[
+    "json", "md", "toml", "yaml", "yml", "lock", "sql", "txt", "env", "xml", "csv", "ini",
+];
+/// Library names that look like file names
+const NOT_FILES: &[&str] = &[
+    "node.js",
+    "vue.js",
+    "next.js",
+    "nuxt.js",
+    "react.js",
+    "three.js",
+    "d3.js",
+    "express.js",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileMention {
+    pub path: String,
+    pub mentions: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversationSynopsis {
+    pub decisions: Vec<String>,
+    pub files: Vec<FileMention>,
+    pub todos: Vec<String>,
+    pub constraints: Vec<String>,
+    /// The synopsis as Markdown, ready to stand in for the summarized turns
+    pub text: String,
+    pub messages_summarized: usize,
+    /// Extracted items left out to stay within the budget
+    pub omitted: usize,
+    pub estimated_tokens: u64,
+}
+
+fn path_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"(?:^|[\s`'"(\[])((?:\.{1,2}/)?(?:[\w.@-]+/)*[\w@-][\w.@-]*\.[A-Za-z][A-Za-z0-9]{0,7})"#)
+            .unwrap()
+    })
+}
+
+fn constraint_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\b(must|never|always|don't|do not|should not|shouldn't|make sure|only use|avoid)\b")
+            .unwrap()
+    })
+}
+
+fn is_file_path(candidate: &str) -> bool {
+    if NOT_FILES.contains(&candidate.to_lowercase().as_str()) {
+        return false;
+    }
+    let extension = candidate.rsplit('.').next().unwrap_or("").to_lowercase();
+    candidate.contains('/')
+        || DATA_EXTENSIONS.contains(&extension.as_str())
+        || CodeNavigationService::get_lang_id_from_path(candidate).is_some()
+}
+
+/// Prose lines of a message, outside fenced code blocks
+fn prose_lines(content: &str) -> Vec<&str> {
+    let mut in_fence = false;
+    content
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                return false;
+            }
+            !in_fence && !trimmed.is_empty()
+        })
+        .collect()
+}
+
+fn sentences(line: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        let at_break = line[i + c.len_utf8()..].starts_with(' ');
+        if matches!(c, '.' | '!' | '?') && at_break {
+            result.push(line[start..=i].trim());
+            start = i + 1;
+        }
+    }
+    result.push(line[start..].trim());
+    result.retain(|s| !s.is_empty());
+    result
+}
+
+fn clip(text: &str) -> String {
+    let text = text.trim_start_matches(['-', '*', '+', '>', ' ']).trim();
+    if text.chars().count() <= MAX_ITEM_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(MAX_ITEM_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn push_unique(items: &mut Vec<String>, seen: &mut HashSet<String>, item: String) {
+    if seen.insert(item.to_lowercase()) {
+        items.push(item);
+    }
+}
+
+struct Extracted {
+    decisions: Vec<String>,
+    files: Vec<FileMention>,
+    todos: Vec<String>,
+    constraints: Vec<String>,
+}
+
+fn extract(messages: &[ChatMessage]) -> Extracted {
+    let mut decisions = Vec::new();
+    let mut todos = Vec::new();
+    let mut constraints = Vec::new();
+    let mut seen = HashSet::new();
+    let mut done: HashSet<String> = HashSet::new();
+    let mut file_order: Vec<String> = Vec::new();
+    let mut file_counts: HashMap<String, usize> = HashMap::new();
+
+    for message in messages {
+        let from_user = matches!(message.role.as_str(), "user" | "system");
+        for line in prose_lines(&message.content) {
+            for capture in path_regex().captures_iter(line) {
+                let path = capture[1].trim_start_matches("./").to_string();
+                if is_file_path(&path) {
+                    let count = file_counts.entry(path.clone()).or_insert(0);
+                    if *count == 0 {
+                        file_order.push(path);
+                    }
+                    *count += 1;
+                }
+            }
+
+            let trimmed = line.trim_start_matches(['-', '*', ' ']);
+            if let Some(item) = trimmed.strip_prefix("[ ]") {
+                push_unique(&mut todos, &mut seen, clip(item));
+                continue;
+            }
+            if let Some(item) = trimmed
+                .strip_prefix("[x]")
+                .or_else(|| trimmed.strip_prefix("[X]"))
+            {
+                done.insert(clip(item).to_lowercase());
+                continue;
+            }
+
+            for sentence in sentences(line) {
+                let lower = sentence.to_lowercase();
+                if sentence.contains("TODO") || TODO_CUES.iter().any(|cue| lower.contains(cue)) {
+                    push_unique(&mut todos, &mut seen, clip(sentence));
+                } else if DECISION_CUES.iter().any(|cue| lower.contains(cue)) {
+                    push_unique(&mut decisions, &mut seen, clip(sentence));
+                } else if from_user && constraint_regex().is_match(sentence) {
+                    push_unique(&mut constraints, &mut seen, clip(sentence));
+                }
+            }
+        }
+    }
+    // Checklist items ticked off later are no longer open
+    todos.retain(|todo| !done.contains(&todo.to_lowercase()));
+
+    let mut files: Vec<FileMention> = file_order
+        .into_iter()
+        .map(|path| FileMention {
+            mentions: file_counts[&path],
+            path,
+        })
+        .collect();
+    // Most mentioned first; ties keep the order of first mention
+    files.sort_by_key(|f| std::cmp::Reverse(f.mentions));
+
+    Extracted {
+        decisions,
+        files,
+        todos,
+        constraints,
+    }
+}
+
+fn tokens(text: &str) -> u64 {
+    text_tokens(text, CHARS_PER_TOKEN)
+}
+
+/// Section heading and bullet lines
+fn section(title: &str, items: &[String]) -> String {
+    let mut text = format!("## {}\n", title);
+    for item in items {
+        text.push_str(&format!("- {}\n", item));
+    }
+    text
+}
+
+pub fn synopsis(messages: &[ChatMessage], budget: u64) -> ConversationSynopsis {
+    let extracted = extract(messages);
+    let header = format!("[SYNOPSIS of {} earlier messages]\n", messages.len());
+    let mut used = tokens(&header);
+    let mut omitted = 0;
+
+    // Take items in priority order while they fit; a section's heading is paid for with
+    // its first item
+    let mut take = |items: Vec<String>, title: &str| -> Vec<String> {
+        let mut kept = Vec::new();
+        for item in items {
+            let heading = if kept.is_empty() {
+                tokens(&format!("## {}\n", title))
+            } else {
+                0
+            };
+            let cost = heading + tokens(&format!("- {}\n", item));
+            if used + cost <= budget {
+                used += cost;
+                kept.push(item);
+            } else {
+                omitted += 1;
+            }
+        }
+        kept
+    };
+
+    let constraints = take(extracted.constraints, "Constraints");
+    let mut decisions = take(extracted.decisions.into_iter().rev().collect(), "Decisions");
+    decisions.reverse();
+    let todos = take(extracted.todos, "Open TODOs");
+    let file_lines = take(
+        extracted
+            .files
+            .iter()
+            .map(|f| format!("{} ({}×)", f.path, f.mentions))
+            .collect(),
+        "Files touched",
+    );
+    let files: Vec<FileMention> = extracted.files.into_iter().take(file_lines.len()).collect();
+
+    let mut text = header;
+    for (title, items) in [
+        ("Decisions", &decisions),
+        ("Files touched", &file_lines),
+        ("Open TODOs", &todos),
+        ("Constraints", &constraints),
+    ] {
+        if !items.is_empty() {
+            text.push_str(&section(title, items));
+        }
+    }
+    let text = text.trim_end().to_string();
+
+    ConversationSynopsis {
+        estimated_tokens: tokens(&text),
+        decisions,
+        files,
+        todos,
+        constraints,
+        text,
+        messages_summarized: messages.len(),
+        omitted,
+    }
+}
+
+/// Structured synopsis of `messages` within `budget` tokens, to stand in for old turns
+/// that are truncated
+#[tauri::command]
+pub fn summarize_conversation(
+    messages: Vec<ChatMessage>,
+    budget: Option<u64>,
+) -> ConversationSynopsis {
+    synopsis(&messages, budget.unwrap_or(DEFAULT_SYNOPSIS_BUDGET))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    fn conversation() -> Vec<ChatMessage> {
+        vec![
+            message(
+                "user",
+                "Add retries to the uploader in src/upload/client.ts. Do not add new dependencies. Thanks!",
+            ),
+            message(
+                "assistant",
+                "I looked at src/upload/client.ts and package.json.\nWe'll use exponential backoff instead of a fixed delay.\n```ts\nconst next = \"src/ignored.ts\";\n```\n- [ ] Add tests for the backoff\n- [ ] Update README.md",
+            ),
+            message(
+                "user",
+                "Looks good. Node.js 18 is the target, so make sure fetch is used directly.",
+            ),
+            message(
+                "assistant",
+                "Updated src/upload/client.ts.\n- [x] Update README.md\nTODO: handle 429 responses with Retry-After.",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_extracts_sections() {
+        let synopsis = synopsis(&conversation(), DEFAULT_SYNOPSIS_BUDGET);
+
+        assert_eq!(
+            synopsis.decisions,
+            vec!["We'll use exponential backoff instead of a fixed delay."]
+        );
+        assert_eq!(
+            synopsis.files,
+            vec![
+                FileMention {
+                    path: "src/upload/client.ts".to_string(),
+                    mentions: 3,
+                },
+                FileMention {
+                    path: "README.md".to_string(),
+                    mentions: 2,
+                },
+                FileMention {
+                    path: "package.json".to_string(),
+                    mentions: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            synopsis.todos,
+            vec![
+                "Add tests for the backoff",
+                "TODO: handle 429 responses with Retry-After.",
+            ]
+        );
+        assert_eq!(
+            synopsis.constraints,
+            vec![
+                "Do not add new dependencies.",
+                "Node.js 18 is the target, so make sure fetch is used directly.",
+            ]
+        );
+        assert_eq!(synopsis.omitted, 0);
+        assert!(synopsis
+            .text
+            .starts_with("[SYNOPSIS of 4 earlier messages]\n## Decisions\n- We'll use"));
+        assert!(synopsis
+            .text
+            .contains("## Files touched\n- src/upload/client.ts (3×)"));
+        assert!(!synopsis.text.contains("ignored.ts"));
+    }
+
+    #[test]
+    fn test_budget_keeps_constraints_and_newest_decisions() {
+        let messages = vec![
+            message("user", "Never touch the migrations folder."),
+            message("assistant", "We decided to keep the v1 API."),
+            message("assistant", "We switched to SQLite for the cache."),
+        ];
+        let full = synopsis(&messages, DEFAULT_SYNOPSIS_BUDGET);
+        assert_eq!(full.decisions.len(), 2);
+
+        // Room for the header (9 tokens), the constraint and one decision (14 each with
+        // their headings)
+        let budget = 40;
+        let tight = synopsis(&messages, budget);
+        assert_eq!(
+            tight.constraints,
+            vec!["Never touch the migrations folder."]
+        );
+        assert_eq!(
+            tight.decisions,
+            vec!["We switched to SQLite for the cache."]
+        );
+        assert_eq!(tight.omitted, 1);
+        assert!(tight.estimated_tokens <= budget);
+    }
+}
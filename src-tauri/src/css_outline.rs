@@ -0,0 +1,232 @@
+// CSS and SCSS selector summaries
+//
+// A stylesheet's declarations rarely matter when the agent is looking for where a class
+// is styled. The summary keeps one line per rule with its selector and the body elided,
+// the headers of `@media`, `@supports`, `@mixin` and control-flow blocks with the rules
+// nested in them, `@keyframes` as a single line, and variable definitions (`$name` and
+// `--name`). Top-level `@use`/`@import` lines are kept; `@include` and `@extend` are
+// treated as declarations and dropped.
+
+use crate::code_navigation::CodeSummary;
+use tree_sitter::{Language, Node, Parser};
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn named_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor).collect()
+}
+
+/// The `{ ... }` body of a rule or at-rule
+fn body(node: Node) -> Option<Node> {
+    named_children(node)
+        .into_iter()
+        .find(|n| matches!(n.kind(), "block" | "keyframe_block_list"))
+}
+
+/// Everything before the body: the selector list or the at-rule's prelude
+fn header(node: Node, body: Node, source: &str) -> String {
+    collapse(&source[node.start_byte()..body.start_byte()])
+}
+
+fn is_variable(declaration: &str) -> bool {
+    declaration.starts_with('$') || declaration.starts_with("--")
+}
+
+fn outline(node: Node, source: &str, depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    for child in named_children(node) {
+        let kind = child.kind();
+        match kind {
+            "rule_set" => {
+                let Some(block) = body(child) else {
+                    continue;
+                };
+                lines.push(format!(
+                    "{}{} {{ … }}",
+                    indent,
+                    header(child, block, source)
+                ));
+                outline(block, source, depth + 1, lines);
+            }
+            "include_statement" | "extend_statement" => {}
+            "declaration" => {
+                let declaration = collapse(text(child, source));
+                if is_variable(&declaration) {
+                    lines.push(format!("{}{}", indent, declaration));
+                }
+            }
+            _ if kind == "at_rule" || kind.ends_with("_statement") => match body(child) {
+                // Keyframe steps and function bodies are never worth listing
+                Some(block) if matches!(kind, "keyframes_statement" | "function_statement") => {
+                    lines.push(format!(
+                        "{}{} {{ … }}",
+                        indent,
+                        header(child, block, source)
+                    ));
+                }
+                Some(block) => {
+                    let at = lines.len();
+                    outline(block, source, depth + 1, lines);
+                    let mut line = format!("{}{}", indent, header(child, block, source));
+                    if lines.len() == at {
+                        line.push_str(" { … }");
+                    }
+                    lines.insert(at, line);
+                }
+                None if depth == 0 => lines.push(collapse(text(child, source))),
+                None => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+fn language(lang_id: &str) -> Option<Language> {
+    match lang_id {
+        "css" => Some(tree_sitter_css::LANGUAGE.into()),
+        "scss" => Some(tree_sitter_scss::LANGUAGE.into()),
+        _ => None,
+    }
+}
+
+/// Selector outline of a CSS or SCSS stylesheet
+pub fn css_outline(content: &str, lang_id: &str) -> Result<String, String> {
+    let language =
+        language(lang_id).ok_or_else(|| format!("Unsupported stylesheet language: {}", lang_id))?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| format!("Failed to set language for {}: {:?}", lang_id, e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| format!("Failed to parse {} content", lang_id))?;
+    let mut lines = Vec::new();
+    outline(tree.root_node(), content, 0, &mut lines);
+    Ok(lines.join("\n"))
+}
+
+pub fn summarize_css(content: &str, lang_id: &str) -> Result<CodeSummary, String> {
+    let original_lines = content.lines().count();
+    let outline = css_outline(content, lang_id)?;
+    if outline.is_empty() {
+        return Ok(CodeSummary::unchanged(
+            content.to_string(),
+            original_lines,
+            lang_id.to_string(),
+            "no rules",
+        ));
+    }
+    Ok(CodeSummary {
+        success: true,
+        summary: format!(
+            "[COMPRESSED: Original {} lines → selectors only, declarations elided]\n\n{}",
+            original_lines, outline
+        ),
+        original_lines,
+        lang_id: lang_id.to_string(),
+        truncated: None,
+        skipped_reason: None,
+        omitted_symbols: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_css_outline() {
+        let css = r#":root {
+  --brand: #0a6cff;
+  --gap:   8px;
+}
+
+/* Cards */
+.card,
+.panel {
+  padding: var(--gap);
+  color: var(--brand);
+}
+
+@media (max-width: 600px) {
+  .card {
+    padding: 0;
+  }
+}
+
+@keyframes fade {
+  from { opacity: 0; }
+  to { opacity: 1; }
+}
+"#;
+        assert_eq!(
+            css_outline(css, "css").unwrap(),
+            [
+                ":root { … }",
+                "  --brand: #0a6cff;",
+                "  --gap: 8px;",
+                ".card, .panel { … }",
+                "@media (max-width: 600px)",
+                "  .card { … }",
+                "@keyframes fade { … }",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_summarize_scss() {
+        let scss = r#"@use "sass:math";
+
+$primary: #333;
+$breakpoint: 600px;
+
+@mixin respond($width) {
+  @media (max-width: $width) {
+    @content;
+  }
+}
+
+.nav {
+  color: $primary;
+  @include respond($breakpoint) {
+    display: none;
+  }
+
+  &__item {
+    padding: math.div(10px, 2);
+
+    &:hover {
+      color: red;
+    }
+  }
+}
+"#;
+        let result = summarize_css(scss, "scss").unwrap();
+        assert!(result.success);
+        let summary = &result.summary;
+        assert!(summary.starts_with("[COMPRESSED: Original 25 lines → selectors only"));
+        for kept in [
+            "@use \"sass:math\";",
+            "$primary: #333;",
+            "@mixin respond($width)",
+            ".nav { … }",
+            "  &__item { … }",
+            "    &:hover { … }",
+        ] {
+            assert!(summary.contains(kept), "missing {:?} in {}", kept, summary);
+        }
+        assert!(!summary.contains("padding"), "{}", summary);
+        assert!(!summary.contains("color: red"), "{}", summary);
+
+        let empty = summarize_css("/* nothing */\n", "css").unwrap();
+        assert!(!empty.success);
+    }
+}
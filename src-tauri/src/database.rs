@@ -245,6 +245,45 @@ impl Database {
         Ok(results)
     }
 
+    /// Run statements atomically. The connection lock is held from BEGIN to COMMIT so
+    /// statements issued by other tasks cannot land inside the transaction, and any
+    /// failure rolls everything back.
+    pub async fn transaction(
+        &self,
+        statements: Vec<(String, Vec<serde_json::Value>)>,
+    ) -> Result<Vec<QueryResult>, String> {
+        let lock = self.conn.lock().await;
+        let conn = lock.as_ref().ok_or("Database not connected")?;
+
+        conn.execute("BEGIN IMMEDIATE", ())
+            .await
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let mut results = Vec::new();
+        for (sql, params) in statements {
+            let libsql_params: Vec<libsql::Value> =
+                params.iter().map(json_to_libsql_value).collect();
+            match conn.execute(&sql, libsql_params).await {
+                Ok(rows_affected) => results.push(QueryResult {
+                    rows: vec![],
+                    rows_affected,
+                }),
+                Err(e) => {
+                    if let Err(rollback_error) = conn.execute("ROLLBACK", ()).await {
+                        log::error!("Failed to roll back transaction: {}", rollback_error);
+                    }
+                    return Err(format!("Execute error: {}", e));
+                }
+            }
+        }
+
+        if let Err(e) = conn.execute("COMMIT", ()).await {
+            let _ = conn.execute("ROLLBACK", ()).await;
+            return Err(format!("Failed to commit transaction: {}", e));
+        }
+        Ok(results)
+    }
+
     /// Close the database connection gracefully
     /// This should be called when the application exits to release file handles
     #[allow(dead_code)]
@@ -626,6 +665,51 @@ mod tests {
         assert_eq!(count, &serde_json::Value::Number(3.into()));
     }
 
+    #[tokio::test]
+    async fn test_transaction_rolls_back_on_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("transaction_test.db");
+
+        let database = Database::new(db_path.to_string_lossy().to_string());
+        database.connect().await.expect("Failed to connect");
+        database
+            .execute(
+                "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)",
+                vec![],
+            )
+            .await
+            .expect("Failed to create table");
+
+        // The second insert violates the primary key, so the first must not persist
+        let statements = vec![
+            (
+                "INSERT INTO items (id, name) VALUES (1, 'a')".to_string(),
+                vec![],
+            ),
+            (
+                "INSERT INTO items (id, name) VALUES (1, 'b')".to_string(),
+                vec![],
+            ),
+        ];
+        assert!(database.transaction(statements).await.is_err());
+
+        let count = database
+            .query("SELECT COUNT(*) as count FROM items", vec![])
+            .await
+            .unwrap();
+        assert_eq!(count.rows[0]["count"], serde_json::Value::Number(0.into()));
+
+        // The connection is usable again after the rollback
+        let results = database
+            .transaction(vec![(
+                "INSERT INTO items (id, name) VALUES (?, ?)".to_string(),
+                vec![serde_json::json!(2), serde_json::json!("c")],
+            )])
+            .await
+            .unwrap();
+        assert_eq!(results[0].rows_affected, 1);
+    }
+
     #[tokio::test]
     async fn test_query_with_multiple_rows() {
         // Test query returning multiple rows
@@ -0,0 +1,140 @@
+// TypeScript declaration files
+//
+// A `.d.ts` file is nothing but signatures and types, which are exactly what the model
+// needs from it; the regular summarizer would reduce interfaces to a few lines and drop
+// overloads. Declaration files are kept verbatim except for long JSDoc blocks, which
+// are capped, and namespace bodies nested deeper than DTS_MAX_NAMESPACE_DEPTH, which
+// are elided.
+
+use crate::code_navigation::cap_doc_comment;
+use tree_sitter::{Node, Tree};
+
+/// JSDoc blocks longer than this are capped when the caller sets no doc limit
+pub const DTS_DEFAULT_MAX_DOC_LINES: usize = 8;
+/// Namespaces nested deeper than this keep their header but lose their body
+pub const DTS_MAX_NAMESPACE_DEPTH: usize = 2;
+
+pub fn is_declaration_file(file_path: &str) -> bool {
+    [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|ext| file_path.ends_with(ext))
+}
+
+fn is_namespace(node: &Node) -> bool {
+    matches!(node.kind(), "internal_module" | "module")
+}
+
+/// Trim a parsed declaration file. Returns None when there is nothing to trim.
+pub fn summarize_declaration_file(
+    tree: &Tree,
+    content: &str,
+    max_doc_lines: usize,
+) -> Option<String> {
+    // (start byte, end byte, replacement)
+    let mut edits: Vec<(usize, usize, String)> = Vec::new();
+    let mut stack = vec![(tree.root_node(), 0usize)];
+
+    while let Some((node, depth)) = stack.pop() {
+        if node.kind() == "comment" {
+            let text = &content[node.byte_range()];
+            if text.starts_with("/**") && text.lines().count() > max_doc_lines {
+                edits.push((
+                    node.start_byte(),
+                    node.end_byte(),
+                    cap_doc_comment(text, max_doc_lines),
+                ));
+            }
+            continue;
+        }
+
+        let depth = if is_namespace(&node) {
+            depth + 1
+        } else {
+            depth
+        };
+        if depth > DTS_MAX_NAMESPACE_DEPTH && is_namespace(&node) {
+            if let Some(body) = node.child_by_field_name("body") {
+                edits.push((body.start_byte(), body.end_byte(), "{ ... }".to_string()));
+                continue;
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            stack.push((child, depth));
+        }
+    }
+
+    if edits.is_empty() {
+        return None;
+    }
+
+    // Edits never overlap: comments have no children and elided bodies are not entered
+    edits.sort_by_key(|(start, _, _)| *start);
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+    for (start, end, replacement) in edits {
+        result.push_str(&content[last..start]);
+        result.push_str(&replacement);
+        last = end;
+    }
+    result.push_str(&content[last..]);
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn trim(content: &str, max_doc_lines: usize) -> Option<String> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TSX.into())
+            .unwrap();
+        let tree = parser.parse(content, None).unwrap();
+        summarize_declaration_file(&tree, content, max_doc_lines)
+    }
+
+    #[test]
+    fn test_is_declaration_file() {
+        assert!(is_declaration_file("types/index.d.ts"));
+        assert!(is_declaration_file("lib.d.mts"));
+        assert!(!is_declaration_file("index.ts"));
+        assert!(!is_declaration_file("dts.ts"));
+    }
+
+    #[test]
+    fn test_long_jsdoc_is_capped() {
+        let content = "/**\n * Line 1\n * Line 2\n * Line 3\n * Line 4\n */\nexport declare function f(a: string): number;\n/** Short */\nexport type Id = string;\n";
+        assert_eq!(
+            trim(content, 3).unwrap(),
+            "/**\n * Line 1\n * Line 2\n * … (doc truncated)\n */\nexport declare function f(a: string): number;\n/** Short */\nexport type Id = string;\n"
+        );
+    }
+
+    #[test]
+    fn test_deep_namespaces_are_elided() {
+        let content = r#"declare namespace A {
+  interface Root { id: string }
+  namespace B {
+    type Mid = number;
+    namespace C {
+      interface Deep { x: number }
+    }
+  }
+}
+"#;
+        let trimmed = trim(content, DTS_DEFAULT_MAX_DOC_LINES).unwrap();
+        assert!(trimmed.contains("interface Root { id: string }"));
+        assert!(trimmed.contains("type Mid = number;"));
+        assert!(trimmed.contains("namespace C { ... }"));
+        assert!(!trimmed.contains("Deep"));
+    }
+
+    #[test]
+    fn test_nothing_to_trim() {
+        let content = "export interface Point {\n  x: number;\n  y: number;\n}\n";
+        assert!(trim(content, DTS_DEFAULT_MAX_DOC_LINES).is_none());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::path_utils;
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -59,10 +60,6 @@ impl DirectoryTreeBuilder {
         }
     }
 
-    fn normalize_path(path: &Path) -> String {
-        path.to_string_lossy().replace('\\', "/")
-    }
-
     /// Build a gitignore matcher for the given root path
     fn build_gitignore_matcher(root_path: &Path) -> Option<Gitignore> {
         let mut builder = GitignoreBuilder::new(root_path);
@@ -85,7 +82,7 @@ impl DirectoryTreeBuilder {
         }
 
         let now = Self::get_current_timestamp();
-        let path_key = Self::normalize_path(root);
+        let path_key = path_utils::path_to_slash(root);
 
         // Check cache first
         if let Ok(cache) = self.cache.lock() {
@@ -130,7 +127,7 @@ impl DirectoryTreeBuilder {
             .to_string_lossy()
             .to_string();
 
-        let path_str = Self::normalize_path(path);
+        let path_str = path_utils::path_to_slash(path);
         let (modified_time, size) = Self::get_file_metadata(path).unwrap_or((timestamp, 0));
 
         // Check if this path is git-ignored
@@ -254,7 +251,7 @@ impl DirectoryTreeBuilder {
         }
 
         let now = Self::get_current_timestamp();
-        let cache_key = format!("{}_children", Self::normalize_path(path));
+        let cache_key = format!("{}_children", path_utils::path_to_slash(path));
 
         // Check cache
         if let Ok(cache) = self.cache.lock() {
@@ -344,7 +341,7 @@ impl DirectoryTreeBuilder {
     /// Invalidate specific path cache
     pub fn invalidate_path(&self, path: &str) {
         if let Ok(mut cache) = self.cache.lock() {
-            let normalized = Self::normalize_path(Path::new(path));
+            let normalized = path_utils::path_to_slash(Path::new(path));
             cache.remove(&normalized);
             cache.remove(&format!("{}_children", normalized));
         }
@@ -0,0 +1,489 @@
+// Doc comment generation for specific symbols
+//
+// For each requested symbol we locate its definition with tree-sitter, collect a few call
+// sites from the reference search, and ask the provider for a plain-text description.
+// The comment markers are applied here in the language's idiom (`///`, docstrings,
+// JSDoc/Javadoc blocks, Go `// Name ...`), so placement is deterministic, and each
+// insertion is syntax-checked before it is returned as an insert-ready edit.
+
+use crate::code_navigation::{get_language, resolve_nav, CodeNavState, CodeNavigationService};
+use crate::inline_edit::DEFINITION_KINDS;
+use crate::line_endings;
+use crate::provider_client::{self, ChatMessage, ProviderConfig};
+use crate::syntax_check;
+use crate::workspace_state::WorkspaceState;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::time::Duration;
+use tauri::State;
+use tree_sitter::{Node, Parser};
+
+const PROVIDER_TIMEOUT_SECS: u64 = 60;
+const MAX_CALL_SITES: usize = 5;
+const MAX_DEFINITION_CHARS: usize = 6_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinitionTarget {
+    /// 0-based row where the doc comment goes (before attributes/decorators)
+    pub insert_row: usize,
+    /// 0-based first row of the definition itself
+    pub start_row: usize,
+    pub indent: String,
+    pub text: String,
+    pub has_doc: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocEdit {
+    pub symbol: String,
+    /// 1-based line the text is inserted before
+    pub insert_line: u32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedSymbol {
+    pub symbol: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocGenerationResult {
+    pub path: String,
+    pub edits: Vec<DocEdit>,
+    pub skipped: Vec<SkippedSymbol>,
+}
+
+pub(crate) fn find_named<'a>(node: Node<'a>, source: &[u8], name: &str) -> Option<Node<'a>> {
+    if DEFINITION_KINDS.contains(&node.kind()) {
+        let node_name = node
+            .child_by_field_name("name")
+            .or_else(|| {
+                // Arrow functions are named by their declarator
+                node.parent()
+                    .filter(|p| p.kind() == "variable_declarator")
+                    .and_then(|p| p.child_by_field_name("name"))
+            })
+            .and_then(|n| n.utf8_text(source).ok());
+        if node_name == Some(name) {
+            return Some(node);
+        }
+    }
+    let mut cursor = node.walk();
+    let children: Vec<Node<'a>> = node.children(&mut cursor).collect();
+    children
+        .into_iter()
+        .find_map(|child| find_named(child, source, name))
+}
+
+fn is_doc_line(line: &str, lang_id: &str) -> bool {
+    let trimmed = line.trim_start();
+    match lang_id {
+        "rust" => trimmed.starts_with("///") || trimmed.starts_with("//!"),
+        "go" => trimmed.starts_with("//"),
+        "python" => false,
+        _ => trimmed.starts_with("/**") || trimmed.starts_with('*') || trimmed.ends_with("*/"),
+    }
+}
+
+fn is_attribute_line(line: &str, lang_id: &str) -> bool {
+    let trimmed = line.trim_start();
+    match lang_id {
+        "rust" => trimmed.starts_with("#["),
+        "python" | "java" | "typescript" | "javascript" => trimmed.starts_with('@'),
+        _ => false,
+    }
+}
+
+/// Locate a definition by name and work out where its doc comment belongs
+pub fn find_definition_target(
+    content: &str,
+    lang_id: &str,
+    name: &str,
+) -> Option<DefinitionTarget> {
+    let language = get_language(lang_id)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content, None)?;
+    let mut node = find_named(tree.root_node(), content.as_bytes(), name)?;
+
+    // Exported/declared forms: document the outer statement
+    while let Some(parent) = node.parent() {
+        if matches!(
+            parent.kind(),
+            "export_statement"
+                | "lexical_declaration"
+                | "variable_declarator"
+                | "decorated_definition"
+        ) {
+            node = parent;
+        } else {
+            break;
+        }
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let start_row = node.start_position().row;
+    let def_line = lines.get(start_row).copied().unwrap_or("");
+    let indent: String = def_line.chars().take_while(|c| c.is_whitespace()).collect();
+
+    // Attributes/decorators that are part of the node stay below the comment
+    let mut insert_row = start_row;
+    while insert_row > 0 && is_attribute_line(lines[insert_row - 1], lang_id) {
+        insert_row -= 1;
+    }
+
+    let text = node.utf8_text(content.as_bytes()).ok()?;
+    let has_doc = if lang_id == "python" {
+        // Docstrings go on the first line of the body, after the `def` header
+        insert_row = lines
+            .iter()
+            .enumerate()
+            .skip(start_row)
+            .find(|(_, l)| l.trim_end().ends_with(':'))
+            .map(|(i, _)| i + 1)
+            .unwrap_or(start_row + 1);
+        let body_first = lines
+            .iter()
+            .skip(insert_row)
+            .find(|l| !l.trim().is_empty())
+            .map(|l| l.trim_start())
+            .unwrap_or("");
+        body_first.starts_with("\"\"\"") || body_first.starts_with("'''")
+    } else {
+        insert_row > 0 && is_doc_line(lines[insert_row - 1], lang_id)
+    };
+
+    Some(DefinitionTarget {
+        insert_row,
+        start_row,
+        indent,
+        text: text.chars().take(MAX_DEFINITION_CHARS).collect(),
+        has_doc,
+    })
+}
+
+/// Wrap plain doc text in the language's comment idiom
+pub fn format_doc_comment(lang_id: &str, name: &str, description: &str, indent: &str) -> String {
+    let lines: Vec<&str> = description.trim().lines().map(|l| l.trim_end()).collect();
+    let mut out = String::new();
+    match lang_id {
+        "rust" => {
+            for line in &lines {
+                out.push_str(&format!(
+                    "{}///{}{}\n",
+                    indent,
+                    if line.is_empty() { "" } else { " " },
+                    line
+                ));
+            }
+        }
+        "go" => {
+            for (i, line) in lines.iter().enumerate() {
+                // Go doc comments start with the symbol name
+                let line = if i == 0 && !line.starts_with(name) {
+                    format!("{} {}", name, lowercase_first(line))
+                } else {
+                    line.to_string()
+                };
+                out.push_str(&format!(
+                    "{}//{}{}\n",
+                    indent,
+                    if line.is_empty() { "" } else { " " },
+                    line
+                ));
+            }
+        }
+        "python" => {
+            let body_indent = format!("{}    ", indent);
+            if lines.len() == 1 {
+                out.push_str(&format!("{}\"\"\"{}\"\"\"\n", body_indent, lines[0]));
+            } else {
+                out.push_str(&format!(
+                    "{}\"\"\"{}\n",
+                    body_indent,
+                    lines.first().unwrap_or(&"")
+                ));
+                for line in lines.iter().skip(1) {
+                    if line.is_empty() {
+                        out.push('\n');
+                    } else {
+                        out.push_str(&format!("{}{}\n", body_indent, line));
+                    }
+                }
+                out.push_str(&format!("{}\"\"\"\n", body_indent));
+            }
+        }
+        _ => {
+            out.push_str(&format!("{}/**\n", indent));
+            for line in &lines {
+                out.push_str(&format!(
+                    "{} *{}{}\n",
+                    indent,
+                    if line.is_empty() { "" } else { " " },
+                    line
+                ));
+            }
+            out.push_str(&format!("{} */\n", indent));
+        }
+    }
+    out
+}
+
+fn lowercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Insert `text` before 0-based `row`, converted to the content's line endings
+pub fn insert_at_row(content: &str, row: usize, text: &str) -> String {
+    let ending = line_endings::detect(content).dominant;
+    let text = &line_endings::convert(text, ending);
+    let mut out = String::with_capacity(content.len() + text.len());
+    let mut inserted = false;
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        if idx == row {
+            out.push_str(text);
+            inserted = true;
+        }
+        out.push_str(line);
+    }
+    if !inserted {
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push_str(ending.as_str());
+        }
+        out.push_str(text);
+    }
+    out
+}
+
+fn idiom_hint(lang_id: &str) -> &'static str {
+    match lang_id {
+        "rust" => "rustdoc: a one-line summary, then details; use # Errors / # Panics sections when relevant",
+        "python" => "a PEP 257 docstring: a one-line summary, then Args/Returns/Raises sections when relevant",
+        "go" => "a Go doc comment: full sentences starting with the symbol name",
+        "java" => "Javadoc with @param, @return and @throws tags",
+        "typescript" | "javascript" => "JSDoc with @param and @returns tags",
+        _ => "a Doxygen-style comment with @param and @return tags",
+    }
+}
+
+/// Options for `generate_docs`; omitted fields take their defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocGenerationOptions {
+    /// Project searched for call sites to show the model; none are shown when unset
+    pub root_path: Option<String>,
+    /// Rewrite existing doc comments instead of skipping documented symbols
+    pub overwrite: bool,
+}
+
+#[tauri::command]
+pub async fn generate_docs(
+    nav_state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    provider: ProviderConfig,
+    path: String,
+    symbols: Vec<String>,
+    options: Option<DocGenerationOptions>,
+) -> Result<DocGenerationResult, String> {
+    let options = options.unwrap_or_default();
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&path)
+        .ok_or_else(|| format!("Unsupported file type: {}", path))?;
+    let lang_family = CodeNavigationService::get_lang_family(&lang_id);
+    let baseline_issues = syntax_check::check_syntax(&content, &lang_id)?.issues.len();
+
+    let nav = resolve_nav(&nav_state, &workspaces, workspace_id.as_deref())?;
+
+    let mut result = DocGenerationResult {
+        path: path.clone(),
+        edits: Vec::new(),
+        skipped: Vec::new(),
+    };
+
+    for symbol in symbols {
+        let skip = |reason: &str| SkippedSymbol {
+            symbol: symbol.clone(),
+            reason: reason.to_string(),
+        };
+        let Some(target) = find_definition_target(&content, &lang_id, &symbol) else {
+            result.skipped.push(skip("Definition not found"));
+            continue;
+        };
+        if target.has_doc && !options.overwrite {
+            result.skipped.push(skip("Already documented"));
+            continue;
+        }
+
+        let call_sites: Vec<String> = match &options.root_path {
+            Some(root) => {
+                let service = nav
+                    .read()
+                    .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+                service
+                    .find_references_hybrid(&symbol, lang_family, root)
+                    .into_iter()
+                    .filter(|r| {
+                        !(r.file_path == path && r.start_line as usize == target.start_row + 1)
+                    })
+                    .take(MAX_CALL_SITES)
+                    .filter_map(|r| {
+                        let text = fs::read_to_string(&r.file_path).ok()?;
+                        let line = text.lines().nth(r.start_line.saturating_sub(1) as usize)?;
+                        Some(format!("{}:{}: {}", r.file_path, r.start_line, line.trim()))
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        let mut prompt = format!("Definition:\n{}\n", target.text);
+        if !call_sites.is_empty() {
+            prompt.push_str(&format!("\nCall sites:\n{}\n", call_sites.join("\n")));
+        }
+        let messages = [
+            ChatMessage::system(format!(
+                "Write documentation for the given {} definition as {}. Reply with the \
+                 documentation text only, without comment markers, quotes or code fences.",
+                lang_id,
+                idiom_hint(&lang_id)
+            )),
+            ChatMessage::user(prompt),
+        ];
+        let answer = match provider_client::chat_completion(
+            &provider,
+            &messages,
+            Duration::from_secs(PROVIDER_TIMEOUT_SECS),
+        )
+        .await
+        {
+            Ok(answer) => provider_client::strip_code_fence(&answer),
+            Err(e) => {
+                result
+                    .skipped
+                    .push(skip(&format!("Provider call failed: {}", e)));
+                continue;
+            }
+        };
+        if answer.trim().is_empty() {
+            result
+                .skipped
+                .push(skip("Provider returned no documentation"));
+            continue;
+        }
+
+        let text = format_doc_comment(&lang_id, &symbol, &answer, &target.indent);
+        let updated = insert_at_row(&content, target.insert_row, &text);
+        let issues = syntax_check::check_syntax(&updated, &lang_id)?.issues.len();
+        if issues > baseline_issues {
+            result
+                .skipped
+                .push(skip("Generated comment breaks the syntax"));
+            continue;
+        }
+
+        result.edits.push(DocEdit {
+            symbol,
+            insert_line: target.insert_row as u32 + 1,
+            text,
+        });
+    }
+
+    // Bottom-up, so applying edits in order doesn't shift later insert lines
+    result
+        .edits
+        .sort_by_key(|e| std::cmp::Reverse(e.insert_line));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_definition_target_rust() {
+        let content = "struct A;\n\n#[inline]\npub fn add(a: i32) -> i32 {\n    a\n}\n\n/// Doc\nfn documented() {}\n";
+        let target = find_definition_target(content, "rust", "add").unwrap();
+        assert_eq!(target.start_row, 3);
+        assert_eq!(target.insert_row, 2);
+        assert!(!target.has_doc);
+
+        let target = find_definition_target(content, "rust", "documented").unwrap();
+        assert!(target.has_doc);
+        assert!(find_definition_target(content, "rust", "missing").is_none());
+    }
+
+    #[test]
+    fn test_find_definition_target_python() {
+        let content = "class A:\n    def run(self, x):\n        return x\n\n    def done(self):\n        \"\"\"Done.\"\"\"\n";
+        let target = find_definition_target(content, "python", "run").unwrap();
+        assert_eq!(target.insert_row, 2);
+        assert_eq!(target.indent, "    ");
+        assert!(!target.has_doc);
+        assert!(
+            find_definition_target(content, "python", "done")
+                .unwrap()
+                .has_doc
+        );
+    }
+
+    #[test]
+    fn test_find_definition_target_exported_arrow() {
+        let content = "export const handler = (req) => {\n  return req;\n};\n";
+        let target = find_definition_target(content, "typescript", "handler").unwrap();
+        assert_eq!(target.insert_row, 0);
+    }
+
+    #[test]
+    fn test_format_doc_comment() {
+        assert_eq!(
+            format_doc_comment("rust", "add", "Adds one.\n\nNever fails.", ""),
+            "/// Adds one.\n///\n/// Never fails.\n"
+        );
+        assert_eq!(
+            format_doc_comment("go", "Add", "Returns the sum.", ""),
+            "// Add returns the sum.\n"
+        );
+        assert_eq!(
+            format_doc_comment("python", "run", "Run it.", "    "),
+            "        \"\"\"Run it.\"\"\"\n"
+        );
+        assert_eq!(
+            format_doc_comment("typescript", "f", "Does f.\n@returns nothing", "  "),
+            "  /**\n   * Does f.\n   * @returns nothing\n   */\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_at_row() {
+        assert_eq!(insert_at_row("a\nb\n", 1, "x\n"), "a\nx\nb\n");
+        assert_eq!(insert_at_row("a\nb", 5, "x\n"), "a\nb\nx\n");
+        assert_eq!(
+            insert_at_row("a\r\nb\r\n", 1, "/// x\n"),
+            "a\r\n/// x\r\nb\r\n"
+        );
+    }
+
+    #[test]
+    fn test_inserted_docstring_is_valid_python() {
+        let content = "def run(x):\n    return x\n";
+        let target = find_definition_target(content, "python", "run").unwrap();
+        let text = format_doc_comment("python", "run", "Run it.", &target.indent);
+        let updated = insert_at_row(content, target.insert_row, &text);
+        assert_eq!(
+            updated,
+            "def run(x):\n    \"\"\"Run it.\"\"\"\n    return x\n"
+        );
+        assert!(
+            syntax_check::check_syntax(&updated, "python")
+                .unwrap()
+                .valid
+        );
+    }
+}
@@ -0,0 +1,596 @@
+// Downloads of remote resources
+//
+// Grammar WASM files, local models and doc sets are fetched with `download_file`. A
+// download streams into `<dest>.part` next to a small `<dest>.part.json` that records
+// the URL and the response's ETag or Last-Modified, so an interrupted download resumes
+// with a Range request instead of starting over (a changed file on the server,
+// detected through If-Range, restarts it). Without either validator a change could
+// not be detected, so the download starts over. The SHA-256 is computed while streaming and checked before the file is moved
+// into place. Every URL, including each redirect hop, must pass the download policy
+// in ~/.talkcody/download-policy.json: HTTPS only and an allowlist of hosts by default.
+// Progress is reported through `download-progress` events.
+
+use crate::http_client;
+use crate::offline_mode;
+use crate::path_policy;
+use futures_util::StreamExt;
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use url::Url;
+
+const POLICY_FILE: &str = "download-policy.json";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_REDIRECTS: usize = 10;
+
+/// Hosts allowed when no policy file exists. Subdomains are included, which covers
+/// GitHub release assets (objects.githubusercontent.com) and Hugging Face's CDN.
+const DEFAULT_ALLOWED_HOSTS: &[&str] = &[
+    "github.com",
+    "githubusercontent.com",
+    "huggingface.co",
+    "hf.co",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadPolicy {
+    pub require_https: bool,
+    /// Hosts (and their subdomains) downloads may come from; `*` allows any host
+    pub allowed_hosts: Vec<String>,
+    /// Largest download accepted, in bytes
+    pub max_bytes: Option<u64>,
+}
+
+impl Default for DownloadPolicy {
+    fn default() -> Self {
+        Self {
+            require_https: true,
+            allowed_hosts: DEFAULT_ALLOWED_HOSTS
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            max_bytes: None,
+        }
+    }
+}
+
+fn host_allowed(host: &str, allowed_hosts: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_lowercase();
+    allowed_hosts.iter().any(|allowed| {
+        let allowed = allowed.trim().to_lowercase();
+        allowed == "*" || host == allowed || host.ends_with(&format!(".{}", allowed))
+    })
+}
+
+impl DownloadPolicy {
+    /// The policy in `path`, or the default when it is missing or invalid
+    pub fn load_from(path: &Path) -> Self {
+        let Ok(raw) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&raw).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid download policy {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn load() -> Self {
+        match dirs::home_dir() {
+            Some(home) => Self::load_from(&home.join(".talkcody").join(POLICY_FILE)),
+            None => Self::default(),
+        }
+    }
+
+    pub fn check_url(&self, url: &Url) -> Result<(), String> {
+        match url.scheme() {
+            "https" => {}
+            "http" if !self.require_https => {}
+            scheme => {
+                return Err(format!(
+                    "Download blocked by policy: {} URLs are not allowed ({})",
+                    scheme, url
+                ))
+            }
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("Download blocked by policy: no host in {}", url))?;
+        if !host_allowed(host, &self.allowed_hosts) {
+            return Err(format!(
+                "Download blocked by policy: {} is not an allowed host",
+                host
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_size(&self, bytes: u64) -> Result<(), String> {
+        match self.max_bytes {
+            Some(max) if bytes > max => Err(format!(
+                "Download blocked by policy: {} bytes exceeds the {} byte limit",
+                bytes, max
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    Started,
+    Downloading,
+    Verifying,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub id: String,
+    pub url: String,
+    pub status: DownloadStatus,
+    pub downloaded: u64,
+    /// None when the server does not report a length
+    pub total: Option<u64>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadOutcome {
+    pub id: String,
+    pub path: String,
+    pub bytes: u64,
+    pub sha256: String,
+    /// Whether an earlier partial download was continued
+    pub resumed: bool,
+}
+
+/// Sidecar of a partial download
+#[derive(Debug, Serialize, Deserialize)]
+struct PartialMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl PartialMeta {
+    /// If-Range value telling the server to send the rest only if the file is
+    /// unchanged. Weak ETags are not allowed in If-Range.
+    fn validator(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
+
+fn with_suffix(dest: &Path, suffix: &str) -> PathBuf {
+    let mut path = dest.as_os_str().to_os_string();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn discard_partial(part: &Path, meta: &Path) {
+    let _ = fs::remove_file(part);
+    let _ = fs::remove_file(meta);
+}
+
+/// SHA-256 state and length of what is already on disk
+fn hash_file(path: &Path) -> Result<(Sha256, u64), String> {
+    let mut file =
+        File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut length = 0u64;
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            return Ok((hasher, length));
+        }
+        hasher.update(&buffer[..read]);
+        length += read as u64;
+    }
+}
+
+/// Compare a computed digest with the expected hex digest, ignoring case
+pub fn verify_sha256(actual: &str, expected: &str) -> Result<(), String> {
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Checksum mismatch: expected sha256 {}, got {}",
+            expected.trim(),
+            actual
+        ))
+    }
+}
+
+fn build_client(policy: &DownloadPolicy) -> Result<reqwest::Client, String> {
+    let policy = policy.clone();
+    http_client::builder()?
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if let Err(e) = policy.check_url(attempt.url()) {
+                attempt.error(e)
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Download `url` to `dest`, resuming a partial download of the same URL. Progress is
+/// passed to `on_progress`; the partial file is kept on network errors so a retry can
+/// resume, and discarded on a checksum mismatch.
+pub async fn download(
+    id: &str,
+    url: &str,
+    dest: &Path,
+    expected_sha256: Option<&str>,
+    policy: &DownloadPolicy,
+    on_progress: &(dyn Fn(&DownloadProgress) + Sync),
+) -> Result<DownloadOutcome, String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    offline_mode::check_url(url, "download")?;
+    policy.check_url(&parsed)?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let part = with_suffix(dest, ".part");
+    let meta_path = with_suffix(dest, ".part.json");
+    let meta: Option<PartialMeta> = fs::read_to_string(&meta_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok());
+    let mut offset = match (&meta, fs::metadata(&part)) {
+        (Some(meta), Ok(existing)) if meta.url == url && meta.validator().is_some() => {
+            existing.len()
+        }
+        _ => {
+            discard_partial(&part, &meta_path);
+            0
+        }
+    };
+
+    let progress = |status, downloaded, total, message: Option<String>| {
+        on_progress(&DownloadProgress {
+            id: id.to_string(),
+            url: url.to_string(),
+            status,
+            downloaded,
+            total,
+            message,
+        })
+    };
+    progress(DownloadStatus::Started, offset, None, None);
+
+    let client = build_client(policy)?;
+    let mut response = None;
+    // A second attempt only happens when the server rejects the resume range
+    for _ in 0..2 {
+        let mut request = client.get(parsed.clone());
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+            if let Some(validator) = meta.as_ref().and_then(PartialMeta::validator) {
+                request = request.header(IF_RANGE, validator);
+            }
+        }
+        let sent = request
+            .send()
+            .await
+            .map_err(|e| format!("Download of {} failed: {}", url, e))?;
+        if sent.status() == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+            discard_partial(&part, &meta_path);
+            offset = 0;
+            continue;
+        }
+        response = Some(sent);
+        break;
+    }
+    let response = response.ok_or_else(|| format!("Download of {} failed", url))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Download of {} failed: HTTP {}",
+            url,
+            response.status()
+        ));
+    }
+
+    // 206 continues the partial file; 200 means the server sent the whole file again
+    let expected_range = format!("bytes {}-", offset);
+    let resumed = offset > 0
+        && response.status() == StatusCode::PARTIAL_CONTENT
+        && response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with(&expected_range));
+    if !resumed {
+        offset = 0;
+    }
+    let total = response.content_length().map(|len| len + offset);
+    if let Some(total) = total {
+        // Resuming would only fail again
+        if let Err(e) = policy.check_size(total) {
+            discard_partial(&part, &meta_path);
+            return Err(e);
+        }
+    }
+
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let meta = PartialMeta {
+        url: url.to_string(),
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    };
+    fs::write(
+        &meta_path,
+        serde_json::to_string(&meta).map_err(|e| e.to_string())?,
+    )
+    .map_err(|e| format!("Failed to write {}: {}", meta_path.display(), e))?;
+
+    let (mut hasher, mut downloaded) = if resumed {
+        hash_file(&part)?
+    } else {
+        (Sha256::new(), 0)
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part)
+        .map_err(|e| format!("Failed to open {}: {}", part.display(), e))?;
+
+    let mut stream = response.bytes_stream();
+    let mut last_progress = Instant::now();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Download of {} interrupted: {}", url, e))?;
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to write {}: {}", part.display(), e))?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        if let Err(e) = policy.check_size(downloaded) {
+            drop(file);
+            discard_partial(&part, &meta_path);
+            return Err(e);
+        }
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            progress(DownloadStatus::Downloading, downloaded, total, None);
+            last_progress = Instant::now();
+        }
+    }
+    file.flush()
+        .map_err(|e| format!("Failed to write {}: {}", part.display(), e))?;
+    drop(file);
+
+    progress(DownloadStatus::Verifying, downloaded, total, None);
+    let sha256 = hex::encode(hasher.finalize());
+    if let Some(expected) = expected_sha256 {
+        if let Err(e) = verify_sha256(&sha256, expected) {
+            discard_partial(&part, &meta_path);
+            return Err(e);
+        }
+    }
+
+    fs::rename(&part, dest)
+        .map_err(|e| format!("Failed to move download to {}: {}", dest.display(), e))?;
+    let _ = fs::remove_file(&meta_path);
+    progress(DownloadStatus::Completed, downloaded, total, None);
+    log::info!(
+        "Downloaded {} to {} ({} bytes{})",
+        url,
+        dest.display(),
+        downloaded,
+        if resumed { ", resumed" } else { "" }
+    );
+
+    Ok(DownloadOutcome {
+        id: id.to_string(),
+        path: dest.to_string_lossy().to_string(),
+        bytes: downloaded,
+        sha256,
+        resumed,
+    })
+}
+
+// Tauri commands
+
+/// Download `url` to `dest`, emitting `download-progress` events tagged with
+/// `download_id` (generated when not given). With `root_path`, `dest` must be inside
+/// the workspace.
+#[tauri::command]
+pub async fn download_file(
+    app: AppHandle,
+    url: String,
+    dest: String,
+    sha256: Option<String>,
+    download_id: Option<String>,
+    root_path: Option<String>,
+) -> Result<DownloadOutcome, String> {
+    path_policy::check_optional(&dest, root_path.as_deref())?;
+    let id = download_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let emit = |progress: &DownloadProgress| {
+        if let Err(e) = app.emit("download-progress", progress) {
+            log::error!("Failed to emit download progress: {}", e);
+        }
+    };
+
+    let result = download(
+        &id,
+        &url,
+        Path::new(&dest),
+        sha256.as_deref(),
+        &DownloadPolicy::load(),
+        &emit,
+    )
+    .await;
+    if let Err(e) = &result {
+        emit(&DownloadProgress {
+            id,
+            url,
+            status: DownloadStatus::Failed,
+            downloaded: 0,
+            total: None,
+            message: Some(e.clone()),
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_default_policy() {
+        let policy = DownloadPolicy::default();
+        assert!(policy
+            .check_url(&url("https://github.com/tree-sitter/x/releases/a.wasm"))
+            .is_ok());
+        assert!(policy
+            .check_url(&url("https://objects.githubusercontent.com/a"))
+            .is_ok());
+        assert!(policy.check_url(&url("http://github.com/a")).is_err());
+        assert!(policy.check_url(&url("https://example.com/a")).is_err());
+        // A suffix match must be a whole label
+        assert!(policy.check_url(&url("https://evilgithub.com/a")).is_err());
+        assert!(policy.check_url(&url("file:///etc/passwd")).is_err());
+    }
+
+    #[test]
+    fn test_policy_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(POLICY_FILE);
+        fs::write(
+            &path,
+            r#"{"require_https": false, "allowed_hosts": ["*"], "max_bytes": 10}"#,
+        )
+        .unwrap();
+        let policy = DownloadPolicy::load_from(&path);
+        assert!(policy.check_url(&url("http://localhost:8080/x")).is_ok());
+        assert!(policy.check_size(10).is_ok());
+        assert!(policy.check_size(11).is_err());
+
+        // Missing fields fall back to their defaults
+        fs::write(&path, r#"{"max_bytes": 5}"#).unwrap();
+        let policy = DownloadPolicy::load_from(&path);
+        assert!(policy.require_https);
+        assert_eq!(policy.allowed_hosts.len(), DEFAULT_ALLOWED_HOSTS.len());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(DownloadPolicy::load_from(&path).require_https);
+    }
+
+    #[test]
+    fn test_hash_and_verify() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("data.bin");
+        fs::write(&path, b"abc").unwrap();
+
+        let (hasher, length) = hash_file(&path).unwrap();
+        let digest = hex::encode(hasher.finalize());
+        assert_eq!(length, 3);
+        assert_eq!(
+            digest,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(verify_sha256(&digest, &digest.to_uppercase()).is_ok());
+        assert!(verify_sha256(&digest, "00").is_err());
+    }
+
+    #[test]
+    fn test_partial_validator() {
+        let meta = |etag: Option<&str>, last_modified: Option<&str>| PartialMeta {
+            url: "https://github.com/a".to_string(),
+            etag: etag.map(str::to_string),
+            last_modified: last_modified.map(str::to_string),
+        };
+        let modified = "Wed, 21 Oct 2015 07:28:00 GMT";
+        assert_eq!(
+            meta(Some("\"v1\""), Some(modified)).validator(),
+            Some("\"v1\"")
+        );
+        assert_eq!(
+            meta(Some("W/\"v1\""), Some(modified)).validator(),
+            Some(modified)
+        );
+        assert_eq!(meta(Some("W/\"v1\""), None).validator(), None);
+        assert_eq!(meta(None, None).validator(), None);
+
+        // Sidecars written before Last-Modified was recorded still load
+        let old: PartialMeta =
+            serde_json::from_str(r#"{"url": "https://github.com/a", "etag": null}"#).unwrap();
+        assert_eq!(old.validator(), None);
+    }
+
+    #[tokio::test]
+    async fn test_partial_without_validator_restarts() {
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("model.gguf");
+        let part = with_suffix(&dest, ".part");
+        let meta_path = with_suffix(&dest, ".part.json");
+        let url = "http://127.0.0.1:9/model.gguf";
+        fs::write(&part, b"stale").unwrap();
+        fs::write(
+            &meta_path,
+            format!(
+                r#"{{"url": "{}", "etag": null, "last_modified": null}}"#,
+                url
+            ),
+        )
+        .unwrap();
+
+        let policy = DownloadPolicy {
+            require_https: false,
+            allowed_hosts: vec!["*".to_string()],
+            max_bytes: None,
+        };
+        // Nothing listens on the port; the stale partial is gone before the request
+        assert!(download("id", url, &dest, None, &policy, &|_| {})
+            .await
+            .is_err());
+        assert!(!part.exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_rejected_by_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("model.gguf");
+        let result = download(
+            "id",
+            "http://example.com/model.gguf",
+            &dest,
+            None,
+            &DownloadPolicy::default(),
+            &|_| {},
+        )
+        .await;
+        assert!(result.unwrap_err().contains("blocked by policy"));
+        assert!(!dest.exists());
+    }
+}
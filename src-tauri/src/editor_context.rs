@@ -0,0 +1,391 @@
+// Open-editors and cursor context provider
+//
+// The frontend registers which files are open in each window, which one is active, and
+// the cursor/selection in it. That state becomes the highest-priority context for the
+// agent and the default scope for navigation: definitions in open files rank first, and
+// an omitted symbol name resolves to the identifier under the cursor.
+
+use crate::code_navigation::{resolve_nav, CodeNavState, CodeNavigationService, SymbolInfo};
+use crate::workspace_state::WorkspaceState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use tauri::State;
+
+/// Lines above and below the cursor included as context
+const CURSOR_WINDOW_LINES: usize = 20;
+const DEFAULT_MAX_CONTEXT_CHARS: usize = 24_000;
+
+/// 1-based editor position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursorPosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelectionRange {
+    pub start: CursorPosition,
+    pub end: CursorPosition,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EditorContext {
+    pub open_files: Vec<String>,
+    pub active_file: Option<String>,
+    pub cursor: Option<CursorPosition>,
+    pub selection: Option<SelectionRange>,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextItem {
+    pub path: String,
+    pub kind: String, // "selection", "cursor_window" or "open_file"
+    /// Lower is more important
+    pub priority: u32,
+    pub start_line: Option<u32>,
+    pub end_line: Option<u32>,
+    pub content: String,
+}
+
+/// Tauri state: window label -> editor context
+#[derive(Default)]
+pub struct EditorContextState(pub Mutex<HashMap<String, EditorContext>>);
+
+impl EditorContextState {
+    pub fn get(&self, window_label: &str) -> Result<EditorContext, String> {
+        let contexts = self.0.lock().map_err(|e| e.to_string())?;
+        Ok(contexts.get(window_label).cloned().unwrap_or_default())
+    }
+}
+
+/// Text covered by a selection (1-based, end column exclusive)
+pub fn selection_text(content: &str, selection: &SelectionRange) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let start_line = selection.start.line.max(1) as usize - 1;
+    let end_line = (selection.end.line.max(1) as usize - 1).min(lines.len().saturating_sub(1));
+    if start_line >= lines.len() || start_line > end_line {
+        return String::new();
+    }
+
+    let mut out = Vec::new();
+    for (idx, line) in lines.iter().enumerate().take(end_line + 1).skip(start_line) {
+        let chars: Vec<char> = line.chars().collect();
+        let from = if idx == start_line {
+            (selection.start.column.max(1) as usize - 1).min(chars.len())
+        } else {
+            0
+        };
+        let to = if idx == end_line {
+            (selection.end.column.max(1) as usize - 1).min(chars.len())
+        } else {
+            chars.len()
+        };
+        out.push(chars[from..to.max(from)].iter().collect::<String>());
+    }
+    out.join("\n")
+}
+
+/// Identifier under a 1-based cursor position
+pub fn word_at(content: &str, cursor: CursorPosition) -> Option<String> {
+    let line = content.lines().nth(cursor.line.checked_sub(1)? as usize)?;
+    let chars: Vec<char> = line.chars().collect();
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    let mut idx = (cursor.column.max(1) as usize - 1).min(chars.len());
+    // Cursor just past the end of a word still counts as on it
+    if idx == chars.len() || !is_ident(chars[idx]) {
+        if idx > 0 && is_ident(chars[idx - 1]) {
+            idx -= 1;
+        } else {
+            return None;
+        }
+    }
+    let start = (0..=idx).rev().take_while(|&i| is_ident(chars[i])).last()?;
+    let end = (idx..chars.len())
+        .take_while(|&i| is_ident(chars[i]))
+        .last()?;
+    Some(chars[start..=end].iter().collect())
+}
+
+/// Order symbols by editor scope: active file, then other open files, then the rest
+pub fn rank_by_editor_scope(symbols: &mut [SymbolInfo], ctx: &EditorContext) {
+    symbols.sort_by_key(|s| {
+        if ctx.active_file.as_deref() == Some(s.file_path.as_str()) {
+            0
+        } else if ctx.open_files.iter().any(|f| f == &s.file_path) {
+            1
+        } else {
+            2
+        }
+    });
+}
+
+/// Build prioritized context items from the editor state, within `max_chars`
+pub fn assemble_context(ctx: &EditorContext, max_chars: usize) -> Vec<ContextItem> {
+    let mut items = Vec::new();
+
+    if let Some(active) = &ctx.active_file {
+        if let Ok(content) = fs::read_to_string(active) {
+            if let Some(selection) = &ctx.selection {
+                let text = selection_text(&content, selection);
+                if !text.is_empty() {
+                    items.push(ContextItem {
+                        path: active.clone(),
+                        kind: "selection".to_string(),
+                        priority: 0,
+                        start_line: Some(selection.start.line),
+                        end_line: Some(selection.end.line),
+                        content: text,
+                    });
+                }
+            }
+            if let Some(cursor) = ctx.cursor {
+                let lines: Vec<&str> = content.lines().collect();
+                let line_idx = (cursor.line.max(1) as usize - 1).min(lines.len());
+                let start = line_idx.saturating_sub(CURSOR_WINDOW_LINES);
+                let end = (line_idx + CURSOR_WINDOW_LINES + 1).min(lines.len());
+                if start < end {
+                    items.push(ContextItem {
+                        path: active.clone(),
+                        kind: "cursor_window".to_string(),
+                        priority: 1,
+                        start_line: Some(start as u32 + 1),
+                        end_line: Some(end as u32),
+                        content: lines[start..end].join("\n"),
+                    });
+                }
+            }
+        }
+    }
+
+    for path in &ctx.open_files {
+        if ctx.active_file.as_ref() == Some(path) {
+            continue;
+        }
+        items.push(ContextItem {
+            path: path.clone(),
+            kind: "open_file".to_string(),
+            priority: 2,
+            start_line: None,
+            end_line: None,
+            content: String::new(),
+        });
+    }
+
+    // Items are in priority order; keep the longest prefix that fits
+    let mut used = 0;
+    let fits = items
+        .iter()
+        .take_while(|item| {
+            used += item.content.len() + item.path.len();
+            used <= max_chars
+        })
+        .count();
+    items.truncate(fits);
+    items
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn editor_context_set_open_files(
+    state: State<'_, EditorContextState>,
+    window_label: String,
+    open_files: Vec<String>,
+    active_file: Option<String>,
+) -> Result<(), String> {
+    let mut contexts = state.0.lock().map_err(|e| e.to_string())?;
+    let ctx = contexts.entry(window_label).or_default();
+    // Cursor state belongs to the previously active file
+    if ctx.active_file != active_file {
+        ctx.cursor = None;
+        ctx.selection = None;
+    }
+    ctx.open_files = open_files;
+    ctx.active_file = active_file;
+    ctx.updated_at = chrono::Utc::now().timestamp_millis();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn editor_context_update_cursor(
+    state: State<'_, EditorContextState>,
+    window_label: String,
+    path: String,
+    cursor: Option<CursorPosition>,
+    selection: Option<SelectionRange>,
+) -> Result<(), String> {
+    let mut contexts = state.0.lock().map_err(|e| e.to_string())?;
+    let ctx = contexts.entry(window_label).or_default();
+    if !ctx.open_files.contains(&path) {
+        ctx.open_files.push(path.clone());
+    }
+    ctx.active_file = Some(path);
+    ctx.cursor = cursor;
+    ctx.selection = selection;
+    ctx.updated_at = chrono::Utc::now().timestamp_millis();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn editor_context_get(
+    state: State<'_, EditorContextState>,
+    window_label: String,
+) -> Result<EditorContext, String> {
+    state.get(&window_label)
+}
+
+#[tauri::command]
+pub fn editor_context_clear(
+    state: State<'_, EditorContextState>,
+    window_label: String,
+) -> Result<(), String> {
+    let mut contexts = state.0.lock().map_err(|e| e.to_string())?;
+    contexts.remove(&window_label);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn editor_context_assemble(
+    state: State<'_, EditorContextState>,
+    window_label: String,
+    max_chars: Option<usize>,
+) -> Result<Vec<ContextItem>, String> {
+    let ctx = state.get(&window_label)?;
+    Ok(assemble_context(
+        &ctx,
+        max_chars.unwrap_or(DEFAULT_MAX_CONTEXT_CHARS),
+    ))
+}
+
+/// Find definitions scoped to the editor: `symbol_name` defaults to the identifier under
+/// the cursor, and results in open files are returned first
+#[tauri::command]
+pub async fn editor_find_definition(
+    editor_state: State<'_, EditorContextState>,
+    nav_state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    window_label: String,
+    symbol_name: Option<String>,
+) -> Result<Vec<SymbolInfo>, String> {
+    let ctx = editor_state.get(&window_label)?;
+    let active = ctx
+        .active_file
+        .clone()
+        .ok_or_else(|| "No active editor".to_string())?;
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&active)
+        .ok_or_else(|| format!("Unsupported file type: {}", active))?;
+
+    let symbol_name = match symbol_name {
+        Some(name) => name,
+        None => {
+            let cursor = ctx.cursor.ok_or_else(|| "No cursor position".to_string())?;
+            let content = fs::read_to_string(&active)
+                .map_err(|e| format!("Failed to read {}: {}", active, e))?;
+            word_at(&content, cursor).ok_or_else(|| "No symbol under cursor".to_string())?
+        }
+    };
+
+    let nav = resolve_nav(&nav_state, &workspaces, workspace_id.as_deref())?;
+    let mut results = {
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        service.find_definition(
+            &symbol_name,
+            CodeNavigationService::get_lang_family(&lang_id),
+        )
+    };
+    rank_by_editor_scope(&mut results, &ctx);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn pos(line: u32, column: u32) -> CursorPosition {
+        CursorPosition { line, column }
+    }
+
+    #[test]
+    fn test_word_at() {
+        let content = "let foo_bar = baz(1);\n";
+        assert_eq!(word_at(content, pos(1, 6)).as_deref(), Some("foo_bar"));
+        // Just past the end of the word
+        assert_eq!(word_at(content, pos(1, 12)).as_deref(), Some("foo_bar"));
+        assert_eq!(word_at(content, pos(1, 15)).as_deref(), Some("baz"));
+        assert_eq!(word_at(content, pos(1, 13)), None);
+        assert_eq!(word_at(content, pos(5, 1)), None);
+    }
+
+    #[test]
+    fn test_selection_text() {
+        let content = "first line\nsecond line\nthird";
+        let selection = SelectionRange {
+            start: pos(1, 7),
+            end: pos(2, 7),
+        };
+        assert_eq!(selection_text(content, &selection), "line\nsecond");
+    }
+
+    #[test]
+    fn test_rank_by_editor_scope() {
+        let symbol = |path: &str| SymbolInfo {
+            name: "f".to_string(),
+            kind: "function".to_string(),
+            file_path: path.to_string(),
+            lang_family: "rust".to_string(),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 1,
+            qualified_name: None,
+        };
+        let ctx = EditorContext {
+            open_files: vec!["/b.rs".to_string(), "/c.rs".to_string()],
+            active_file: Some("/c.rs".to_string()),
+            ..Default::default()
+        };
+        let mut symbols = vec![symbol("/a.rs"), symbol("/b.rs"), symbol("/c.rs")];
+        rank_by_editor_scope(&mut symbols, &ctx);
+        let order: Vec<_> = symbols.iter().map(|s| s.file_path.as_str()).collect();
+        assert_eq!(order, vec!["/c.rs", "/b.rs", "/a.rs"]);
+    }
+
+    #[test]
+    fn test_assemble_context_priorities() {
+        let temp_dir = TempDir::new().unwrap();
+        let active = temp_dir.path().join("main.rs");
+        let content: String = (1..=100).map(|i| format!("line {}\n", i)).collect();
+        fs::write(&active, content).unwrap();
+        let active = active.to_string_lossy().to_string();
+
+        let ctx = EditorContext {
+            open_files: vec![active.clone(), "/other.rs".to_string()],
+            active_file: Some(active.clone()),
+            cursor: Some(pos(50, 1)),
+            selection: Some(SelectionRange {
+                start: pos(50, 1),
+                end: pos(50, 8),
+            }),
+            updated_at: 0,
+        };
+        let items = assemble_context(&ctx, DEFAULT_MAX_CONTEXT_CHARS);
+        let kinds: Vec<_> = items.iter().map(|i| i.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["selection", "cursor_window", "open_file"]);
+        assert_eq!(items[0].content, "line 50");
+        assert_eq!(items[1].start_line, Some(30));
+        assert_eq!(items[1].end_line, Some(70));
+
+        // A tight budget keeps only the most important item
+        let items = assemble_context(&ctx, 100);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, "selection");
+    }
+}
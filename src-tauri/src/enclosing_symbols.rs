@@ -0,0 +1,225 @@
+// Enclosing symbol chains
+//
+// Breadcrumbs, search results and diagnostics are easier to read with the declaration
+// they sit in: `Cart > total` rather than a bare line number. The chain for a line is
+// every definition the summarization query captures around it, outermost first. Each
+// one is named by the definition-query name directly inside it, so a Rust `impl` block,
+// which declares no name of its own, is not mistaken for its first method and falls
+// back to its type instead.
+
+use crate::capture_kinds::{is_helper_capture, CaptureKind};
+use crate::code_navigation::{get_language, CodeNavigationService};
+use crate::grammar_cache::{self, QuerySet};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Parser, QueryCursor};
+
+/// Fallback names taken from a definition's first line are cut at this many chars
+const MAX_FALLBACK_NAME_LEN: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnclosingSymbol {
+    pub name: String,
+    pub kind: CaptureKind,
+    /// 1-based, inclusive
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+fn contains(outer: Node, inner: Node) -> bool {
+    outer.start_byte() <= inner.start_byte() && inner.end_byte() <= outer.end_byte()
+}
+
+/// Name for a definition the definition query does not name: its `name` or `type` field,
+/// or its first line up to the body
+fn fallback_name(node: Node, source: &str) -> String {
+    if let Some(field) = node
+        .child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("type"))
+    {
+        return source[field.byte_range()].to_string();
+    }
+    let text = &source[node.byte_range()];
+    let first = text.lines().next().unwrap_or("");
+    let head = first.split(['{', ':']).next().unwrap_or(first).trim();
+    head.chars().take(MAX_FALLBACK_NAME_LEN).collect()
+}
+
+/// Definitions around 1-based `line`, outermost first
+pub fn enclosing_symbols(
+    content: &str,
+    lang_id: &str,
+    line: u32,
+) -> Result<Vec<EnclosingSymbol>, String> {
+    let language =
+        get_language(lang_id).ok_or_else(|| format!("Unsupported language: {}", lang_id))?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| format!("Failed to set language for {}: {:?}", lang_id, e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| format!("Failed to parse {} content", lang_id))?;
+    let root = tree.root_node();
+    let source = content.as_bytes();
+
+    // Every captured definition; a node captured by several patterns keeps a callable
+    // kind over a value kind (a TS `const` holding an arrow function)
+    let mut definitions: Vec<(Node, CaptureKind)> = Vec::new();
+    let query = grammar_cache::query(QuerySet::Summarization, lang_id)?;
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, root, source);
+    while let Some(m) = matches.next() {
+        for capture in m.captures {
+            let capture_name = query.capture_names()[capture.index as usize];
+            if is_helper_capture(capture_name) {
+                continue;
+            }
+            let Some(kind) = CaptureKind::from_capture_name(capture_name) else {
+                continue;
+            };
+            match definitions
+                .iter_mut()
+                .find(|(n, _)| n.id() == capture.node.id())
+            {
+                Some(existing) if kind.is_callable() => existing.1 = kind,
+                Some(_) => {}
+                None => definitions.push((capture.node, kind)),
+            }
+        }
+    }
+
+    let row = line.saturating_sub(1) as usize;
+    let mut enclosing: Vec<(Node, CaptureKind)> = definitions
+        .iter()
+        .copied()
+        .filter(|(node, _)| node.start_position().row <= row && row <= node.end_position().row)
+        .collect();
+    if enclosing.is_empty() {
+        return Ok(Vec::new());
+    }
+    enclosing.sort_by_key(|(node, _)| (node.start_byte(), std::cmp::Reverse(node.end_byte())));
+
+    // Declared names, in source order
+    let mut names: Vec<Node> = Vec::new();
+    let query = grammar_cache::query(QuerySet::Definitions, lang_id)?;
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, root, source);
+    while let Some(m) = matches.next() {
+        for capture in m.captures {
+            if !is_helper_capture(query.capture_names()[capture.index as usize]) {
+                names.push(capture.node);
+            }
+        }
+    }
+    names.sort_by_key(|n| n.start_byte());
+
+    Ok(enclosing
+        .into_iter()
+        .map(|(node, kind)| {
+            // The first name inside the definition that no nested definition claims
+            let declared = names.iter().find(|name| {
+                contains(node, **name)
+                    && !definitions.iter().any(|(other, _)| {
+                        other.id() != node.id()
+                            && contains(node, *other)
+                            && contains(*other, **name)
+                    })
+            });
+            EnclosingSymbol {
+                name: match declared {
+                    Some(name) => content[name.byte_range()].to_string(),
+                    None => fallback_name(node, content),
+                },
+                kind,
+                start_line: node.start_position().row as u32 + 1,
+                end_line: node.end_position().row as u32 + 1,
+            }
+        })
+        .collect())
+}
+
+/// Chain of declarations enclosing `line` in the file at `file_path`, or in `content`
+/// when given, outermost first
+#[tauri::command]
+pub fn get_enclosing_symbols(
+    file_path: String,
+    line: u32,
+    content: Option<String>,
+    lang_id: Option<String>,
+) -> Result<Vec<EnclosingSymbol>, String> {
+    let content = match content {
+        Some(content) => content,
+        None => fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to read {}: {}", file_path, e))?,
+    };
+    let lang_id = lang_id
+        .or_else(|| CodeNavigationService::get_lang_id_from_path(&file_path))
+        .ok_or_else(|| format!("Could not determine the language of {}", file_path))?;
+    enclosing_symbols(&content, &lang_id, line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn chain(symbols: &[EnclosingSymbol]) -> Vec<(&str, CaptureKind, u32, u32)> {
+        symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind, s.start_line, s.end_line))
+            .collect()
+    }
+
+    #[test]
+    fn test_python_class_and_method() {
+        let code = r#"class Cart:
+    """Items in the basket."""
+
+    def total(self):
+        return sum(self.items)
+
+def main():
+    pass
+"#;
+        let symbols = enclosing_symbols(code, "python", 5).unwrap();
+        assert_eq!(
+            chain(&symbols),
+            vec![
+                ("Cart", CaptureKind::Class, 1, 5),
+                ("total", CaptureKind::Function, 4, 5),
+            ]
+        );
+        assert_eq!(
+            chain(&enclosing_symbols(code, "python", 2).unwrap()),
+            vec![("Cart", CaptureKind::Class, 1, 5)]
+        );
+        assert!(enclosing_symbols(code, "python", 6).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rust_impl_is_named_by_its_type() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("cart.rs");
+        fs::write(
+            &path,
+            "impl Cart {\n    fn total(&self) -> u32 {\n        0\n    }\n\n    fn clear(&mut self) {}\n}\n",
+        )
+        .unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        // Between the methods only the impl encloses the line
+        let symbols = get_enclosing_symbols(path.clone(), 5, None, None).unwrap();
+        assert_eq!(chain(&symbols), vec![("Cart", CaptureKind::Impl, 1, 7)]);
+
+        let symbols = get_enclosing_symbols(path, 3, None, None).unwrap();
+        assert_eq!(
+            chain(&symbols),
+            vec![
+                ("Cart", CaptureKind::Impl, 1, 7),
+                ("total", CaptureKind::Function, 2, 4),
+            ]
+        );
+    }
+}
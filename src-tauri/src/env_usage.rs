@@ -0,0 +1,268 @@
+// Environment variable usage scanner
+//
+// Finds reads of environment variables across the workspace (process.env,
+// import.meta.env, std::env::var, env!, os.environ, os.getenv, os.Getenv,
+// System.getenv) and cross-references them with the documented variables in
+// .env.example, reporting variables that are used but undocumented and documented
+// variables that nothing reads.
+
+use crate::code_navigation::CodeNavigationService;
+use crate::walker::{WalkerConfig, WorkspaceWalker};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const EXAMPLE_FILES: &[&str] = &[
+    ".env.example",
+    ".env.sample",
+    ".env.template",
+    ".env.dist",
+    "example.env",
+];
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvUsage {
+    pub file_path: String,
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvVariable {
+    pub name: String,
+    pub documented: bool,
+    pub usages: Vec<EnvUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvUsageReport {
+    pub example_file: Option<String>,
+    pub variables: Vec<EnvVariable>,
+    /// Read in code but missing from the example file
+    pub undocumented: Vec<String>,
+    /// Listed in the example file but never read
+    pub unused: Vec<String>,
+}
+
+fn env_read_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r#"(?x)
+            (?:process\.env|import\.meta\.env)(?:\.([A-Za-z_][A-Za-z0-9_]*)|\[\s*["'`]([A-Za-z_][A-Za-z0-9_]*)["'`]\s*\])
+            | (?:env::var(?:_os)?|option_env!|env!|os\.getenv|os\.environ\.get|os\.Getenv|os\.LookupEnv|System\.getenv|getenv)\(\s*["']([A-Za-z_][A-Za-z0-9_]*)["']
+            | os\.environ\[\s*["']([A-Za-z_][A-Za-z0-9_]*)["']\s*\]
+            "#,
+        )
+        .unwrap()
+    })
+}
+
+/// Variable names read on `line`
+pub fn find_env_reads(line: &str) -> Vec<String> {
+    env_read_regex()
+        .captures_iter(line)
+        .filter_map(|caps| {
+            (1..=4)
+                .find_map(|i| caps.get(i))
+                .map(|m| m.as_str().to_string())
+        })
+        .collect()
+}
+
+/// Variable names declared in a dotenv-style file
+pub fn parse_env_example(content: &str) -> BTreeSet<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, _) = line.split_once('=')?;
+            let name = name.trim();
+            (!name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+                .then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Combine usages and documented names into a report
+pub fn build_report(
+    usages: BTreeMap<String, Vec<EnvUsage>>,
+    documented: &BTreeSet<String>,
+    example_file: Option<String>,
+) -> EnvUsageReport {
+    let undocumented = if example_file.is_some() {
+        usages
+            .keys()
+            .filter(|name| !documented.contains(*name))
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let unused = documented
+        .iter()
+        .filter(|name| !usages.contains_key(*name))
+        .cloned()
+        .collect();
+    let variables = usages
+        .into_iter()
+        .map(|(name, usages)| EnvVariable {
+            documented: documented.contains(&name),
+            name,
+            usages,
+        })
+        .collect();
+
+    EnvUsageReport {
+        example_file,
+        variables,
+        undocumented,
+        unused,
+    }
+}
+
+fn scan_workspace(root_path: &str) -> EnvUsageReport {
+    let root = Path::new(root_path);
+    let example_path = EXAMPLE_FILES
+        .iter()
+        .map(|name| root.join(name))
+        .find(|path| path.is_file());
+    let documented = example_path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| parse_env_example(&content))
+        .unwrap_or_default();
+
+    let mut usages: BTreeMap<String, Vec<EnvUsage>> = BTreeMap::new();
+    for entry in WorkspaceWalker::new(root_path, WalkerConfig::for_list_files())
+        .build()
+        .flatten()
+    {
+        let path = entry.path();
+        if !path.is_file() || path.metadata().map(|m| m.len()).unwrap_or(0) > MAX_FILE_BYTES {
+            continue;
+        }
+        let path_str = path.to_string_lossy().to_string();
+        if CodeNavigationService::get_lang_id_from_path(&path_str).is_none() {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        for (idx, line) in content.lines().enumerate() {
+            for name in find_env_reads(line) {
+                usages.entry(name).or_default().push(EnvUsage {
+                    file_path: path_str.clone(),
+                    line: idx as u32 + 1,
+                });
+            }
+        }
+    }
+
+    build_report(
+        usages,
+        &documented,
+        example_path.map(|p| p.to_string_lossy().to_string()),
+    )
+}
+
+#[tauri::command]
+pub async fn scan_env_usage(root_path: String) -> Result<EnvUsageReport, String> {
+    let report = tokio::task::spawn_blocking(move || scan_workspace(&root_path))
+        .await
+        .map_err(|e| format!("Environment scan failed: {}", e))?;
+    log::info!(
+        "Found {} environment variables ({} undocumented, {} unused)",
+        report.variables.len(),
+        report.undocumented.len(),
+        report.unused.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_env_reads() {
+        assert_eq!(
+            find_env_reads("const url = process.env.API_URL || process.env['FALLBACK_URL'];"),
+            vec!["API_URL", "FALLBACK_URL"]
+        );
+        assert_eq!(find_env_reads("import.meta.env.VITE_KEY"), vec!["VITE_KEY"]);
+        assert_eq!(
+            find_env_reads(
+                r#"let key = std::env::var("OPENAI_KEY").ok(); env!("CARGO_PKG_VERSION")"#
+            ),
+            vec!["OPENAI_KEY", "CARGO_PKG_VERSION"]
+        );
+        assert_eq!(
+            find_env_reads(r#"os.environ["DB_URL"], os.getenv('DEBUG'), os.environ.get("PORT")"#),
+            vec!["DB_URL", "DEBUG", "PORT"]
+        );
+        assert_eq!(find_env_reads(r#"os.Getenv("HOME")"#), vec!["HOME"]);
+        assert_eq!(
+            find_env_reads(r#"System.getenv("JAVA_OPTS")"#),
+            vec!["JAVA_OPTS"]
+        );
+        assert!(find_env_reads("process.env").is_empty());
+    }
+
+    #[test]
+    fn test_parse_env_example() {
+        let names = parse_env_example(
+            "# API settings\nAPI_URL=https://example.com\nexport SECRET=\n\nnot a var\nBAD NAME=1\n",
+        );
+        assert_eq!(
+            names.into_iter().collect::<Vec<_>>(),
+            vec!["API_URL", "SECRET"]
+        );
+    }
+
+    #[test]
+    fn test_scan_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join(".env.example"), "API_URL=\nLEGACY_TOKEN=\n").unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("src/config.ts"),
+            "export const api = process.env.API_URL;\nexport const key = process.env.API_KEY;\n",
+        )
+        .unwrap();
+
+        let report = scan_workspace(&root.to_string_lossy());
+        assert!(report.example_file.unwrap().ends_with(".env.example"));
+        assert_eq!(report.undocumented, vec!["API_KEY"]);
+        assert_eq!(report.unused, vec!["LEGACY_TOKEN"]);
+        let api_key = report
+            .variables
+            .iter()
+            .find(|v| v.name == "API_KEY")
+            .unwrap();
+        assert!(!api_key.documented);
+        assert_eq!(api_key.usages[0].line, 2);
+    }
+
+    #[test]
+    fn test_report_without_example_file() {
+        let mut usages = BTreeMap::new();
+        usages.insert(
+            "PORT".to_string(),
+            vec![EnvUsage {
+                file_path: "/app/main.py".to_string(),
+                line: 3,
+            }],
+        );
+        let report = build_report(usages, &BTreeSet::new(), None);
+        assert!(report.undocumented.is_empty());
+        assert_eq!(report.variables.len(), 1);
+    }
+}
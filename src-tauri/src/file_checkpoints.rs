@@ -0,0 +1,267 @@
+// Per-session file checkpoints
+//
+// Before the agent changes a file, the caller records the file's previous content
+// against the message that caused the change, so a session can be rewound to any
+// message. Contents are stored once per hash in `checkpoint_blobs` and checkpoint rows
+// only reference them. Forking a session (see session_fork.rs) is therefore
+// copy-on-write: the fork gets its own checkpoint rows that point at the same blobs.
+
+use crate::database::Database;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
+
+const SCHEMA: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS checkpoint_blobs (
+        hash TEXT PRIMARY KEY,
+        content TEXT NOT NULL
+    )",
+    // content_hash is NULL when the file did not exist before the change
+    "CREATE TABLE IF NOT EXISTS file_checkpoints (
+        id TEXT PRIMARY KEY,
+        conversation_id TEXT NOT NULL,
+        message_id TEXT NOT NULL,
+        file_path TEXT NOT NULL,
+        content_hash TEXT,
+        created_at INTEGER NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_file_checkpoints_conversation ON file_checkpoints (conversation_id, created_at)",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileCheckpoint {
+    pub id: String,
+    pub conversation_id: String,
+    pub message_id: String,
+    pub file_path: String,
+    /// `None` when the file did not exist before the change
+    pub content_hash: Option<String>,
+    pub created_at: i64,
+}
+
+pub async fn ensure_schema(db: &Database) -> Result<(), String> {
+    for sql in SCHEMA {
+        db.execute(sql, vec![]).await?;
+    }
+    Ok(())
+}
+
+fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+fn str_field(row: &Value, key: &str) -> String {
+    row.get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn checkpoint_from_row(row: &Value) -> FileCheckpoint {
+    FileCheckpoint {
+        id: str_field(row, "id"),
+        conversation_id: str_field(row, "conversation_id"),
+        message_id: str_field(row, "message_id"),
+        file_path: str_field(row, "file_path"),
+        content_hash: row
+            .get("content_hash")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        created_at: row.get("created_at").and_then(|v| v.as_i64()).unwrap_or(0),
+    }
+}
+
+/// Record the content `file_path` had before `message_id` changed it
+pub async fn record_checkpoint(
+    db: &Database,
+    conversation_id: &str,
+    message_id: &str,
+    file_path: &str,
+    content: Option<&str>,
+) -> Result<FileCheckpoint, String> {
+    ensure_schema(db).await?;
+
+    let checkpoint = FileCheckpoint {
+        id: uuid::Uuid::new_v4().to_string(),
+        conversation_id: conversation_id.to_string(),
+        message_id: message_id.to_string(),
+        file_path: file_path.to_string(),
+        content_hash: content.map(content_hash),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+
+    let mut statements = Vec::new();
+    if let (Some(content), Some(hash)) = (content, &checkpoint.content_hash) {
+        statements.push((
+            "INSERT OR IGNORE INTO checkpoint_blobs (hash, content) VALUES (?, ?)".to_string(),
+            vec![Value::from(hash.as_str()), Value::from(content)],
+        ));
+    }
+    statements.push((
+        "INSERT INTO file_checkpoints (id, conversation_id, message_id, file_path, content_hash, created_at) VALUES (?, ?, ?, ?, ?, ?)".to_string(),
+        vec![
+            Value::from(checkpoint.id.as_str()),
+            Value::from(conversation_id),
+            Value::from(message_id),
+            Value::from(file_path),
+            checkpoint
+                .content_hash
+                .as_deref()
+                .map(Value::from)
+                .unwrap_or(Value::Null),
+            Value::from(checkpoint.created_at),
+        ],
+    ));
+    db.transaction(statements).await?;
+
+    Ok(checkpoint)
+}
+
+/// Checkpoints of a conversation, oldest first
+pub async fn list_checkpoints(
+    db: &Database,
+    conversation_id: &str,
+) -> Result<Vec<FileCheckpoint>, String> {
+    ensure_schema(db).await?;
+    let rows = db
+        .query(
+            "SELECT * FROM file_checkpoints WHERE conversation_id = ? ORDER BY created_at ASC, id ASC",
+            vec![Value::from(conversation_id)],
+        )
+        .await?
+        .rows;
+    Ok(rows.iter().map(checkpoint_from_row).collect())
+}
+
+/// Content recorded by a checkpoint; `None` when the file did not exist
+pub async fn read_checkpoint(db: &Database, checkpoint_id: &str) -> Result<Option<String>, String> {
+    ensure_schema(db).await?;
+    let row = db
+        .query(
+            "SELECT c.content_hash AS content_hash, b.content AS content FROM file_checkpoints c LEFT JOIN checkpoint_blobs b ON b.hash = c.content_hash WHERE c.id = ?",
+            vec![Value::from(checkpoint_id)],
+        )
+        .await?
+        .rows
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Checkpoint not found: {}", checkpoint_id))?;
+
+    match row.get("content_hash").and_then(|v| v.as_str()) {
+        None => Ok(None),
+        Some(hash) => row
+            .get("content")
+            .and_then(|v| v.as_str())
+            .map(|content| Some(content.to_string()))
+            .ok_or_else(|| format!("Checkpoint content missing: {}", hash)),
+    }
+}
+
+/// INSERT statements giving a fork its own rows for the parent's checkpoints. Only
+/// checkpoints of messages copied into the fork (keys of `id_map`) are kept; the
+/// blobs are shared.
+pub fn fork_statements(
+    checkpoints: &[FileCheckpoint],
+    id_map: &HashMap<String, String>,
+    new_conversation_id: &str,
+) -> Vec<(String, Vec<Value>)> {
+    checkpoints
+        .iter()
+        .filter_map(|checkpoint| {
+            let new_message_id = id_map.get(&checkpoint.message_id)?;
+            Some((
+                "INSERT INTO file_checkpoints (id, conversation_id, message_id, file_path, content_hash, created_at) VALUES (?, ?, ?, ?, ?, ?)".to_string(),
+                vec![
+                    Value::from(uuid::Uuid::new_v4().to_string()),
+                    Value::from(new_conversation_id),
+                    Value::from(new_message_id.as_str()),
+                    Value::from(checkpoint.file_path.as_str()),
+                    checkpoint
+                        .content_hash
+                        .as_deref()
+                        .map(Value::from)
+                        .unwrap_or(Value::Null),
+                    Value::from(checkpoint.created_at),
+                ],
+            ))
+        })
+        .collect()
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn session_record_checkpoint(
+    db: State<'_, Arc<Database>>,
+    conversation_id: String,
+    message_id: String,
+    file_path: String,
+    content: Option<String>,
+) -> Result<FileCheckpoint, String> {
+    record_checkpoint(
+        &db,
+        &conversation_id,
+        &message_id,
+        &file_path,
+        content.as_deref(),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn session_list_checkpoints(
+    db: State<'_, Arc<Database>>,
+    conversation_id: String,
+) -> Result<Vec<FileCheckpoint>, String> {
+    list_checkpoints(&db, &conversation_id).await
+}
+
+#[tauri::command]
+pub async fn session_read_checkpoint(
+    db: State<'_, Arc<Database>>,
+    checkpoint_id: String,
+) -> Result<Option<String>, String> {
+    read_checkpoint(&db, &checkpoint_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_record_and_read_checkpoints() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db = Database::new(db_path.to_string_lossy().to_string());
+        db.connect().await.unwrap();
+
+        let first = record_checkpoint(&db, "c1", "m1", "src/lib.rs", Some("fn a() {}"))
+            .await
+            .unwrap();
+        let second = record_checkpoint(&db, "c1", "m2", "src/main.rs", Some("fn a() {}"))
+            .await
+            .unwrap();
+        let created = record_checkpoint(&db, "c1", "m2", "src/new.rs", None)
+            .await
+            .unwrap();
+
+        // Identical contents share one blob
+        assert_eq!(first.content_hash, second.content_hash);
+        let blobs = db
+            .query("SELECT COUNT(*) AS count FROM checkpoint_blobs", vec![])
+            .await
+            .unwrap();
+        assert_eq!(blobs.rows[0]["count"], 1);
+
+        assert_eq!(
+            read_checkpoint(&db, &first.id).await.unwrap().as_deref(),
+            Some("fn a() {}")
+        );
+        assert_eq!(read_checkpoint(&db, &created.id).await.unwrap(), None);
+        assert_eq!(list_checkpoints(&db, "c1").await.unwrap().len(), 3);
+    }
+}
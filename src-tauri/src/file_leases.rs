@@ -0,0 +1,406 @@
+// Advisory file leases between the editor and the agent
+//
+// The editor takes a lease on every open file and flags it dirty while it has unsaved
+// changes; the agent takes a lease while it is editing a file. Leases are advisory: they
+// don't stop anything on their own, but the agent's guarded write path consults them and
+// either blocks the write or hands it to the frontend to merge into the editor buffer.
+
+use crate::file_merge::{self, ConflictStrategy, MergeWriteResult, ReadSnapshotState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+
+/// Leases expire if not renewed so a crashed window can't hold a file forever
+const DEFAULT_LEASE_TTL_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaseHolder {
+    Editor,
+    Agent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileLease {
+    pub path: String,
+    pub holder: LeaseHolder,
+    /// Window label or session id of the holder
+    pub owner_id: String,
+    /// Editor only: the buffer has unsaved changes
+    pub dirty: bool,
+    pub acquired_at: i64,
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteDecision {
+    Allowed,
+    Blocked,
+    MergeRequired,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteCheck {
+    pub decision: WriteDecision,
+    pub reason: Option<String>,
+    pub lease: Option<FileLease>,
+}
+
+/// What the agent's write does when the editor has unsaved changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirtyEditorPolicy {
+    Block,
+    Merge,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardedWriteResult {
+    pub decision: WriteDecision,
+    pub reason: Option<String>,
+    pub write: Option<MergeWriteResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LeaseEvent {
+    path: String,
+    leases: Vec<FileLease>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct MergeRequiredEvent {
+    path: String,
+    session_id: String,
+    /// The agent's intended content, to be merged into the editor buffer
+    content: String,
+    /// Content the agent based its edit on, if it was recorded
+    base: Option<String>,
+}
+
+#[derive(Default)]
+pub struct LeaseTable {
+    leases: HashMap<String, HashMap<LeaseHolder, FileLease>>,
+}
+
+#[derive(Default)]
+pub struct LeaseState(pub Mutex<LeaseTable>);
+
+impl LeaseTable {
+    pub fn acquire(
+        &mut self,
+        path: &str,
+        holder: LeaseHolder,
+        owner_id: &str,
+        ttl_secs: u64,
+        now: i64,
+    ) -> FileLease {
+        let expires_at = now + (ttl_secs as i64) * 1000;
+        let entry = self.leases.entry(path.to_string()).or_default();
+        let lease = entry.entry(holder).or_insert_with(|| FileLease {
+            path: path.to_string(),
+            holder,
+            owner_id: owner_id.to_string(),
+            dirty: false,
+            acquired_at: now,
+            expires_at,
+        });
+        lease.owner_id = owner_id.to_string();
+        lease.expires_at = expires_at;
+        lease.clone()
+    }
+
+    pub fn release(&mut self, path: &str, holder: LeaseHolder) -> bool {
+        let Some(entry) = self.leases.get_mut(path) else {
+            return false;
+        };
+        let removed = entry.remove(&holder).is_some();
+        if entry.is_empty() {
+            self.leases.remove(path);
+        }
+        removed
+    }
+
+    pub fn set_dirty(&mut self, path: &str, dirty: bool) -> Result<FileLease, String> {
+        let lease = self
+            .leases
+            .get_mut(path)
+            .and_then(|entry| entry.get_mut(&LeaseHolder::Editor))
+            .ok_or_else(|| format!("No editor lease for {}", path))?;
+        lease.dirty = dirty;
+        Ok(lease.clone())
+    }
+
+    /// Drop expired leases
+    pub fn prune(&mut self, now: i64) {
+        for entry in self.leases.values_mut() {
+            entry.retain(|_, lease| lease.expires_at > now);
+        }
+        self.leases.retain(|_, entry| !entry.is_empty());
+    }
+
+    pub fn leases_for(&self, path: &str) -> Vec<FileLease> {
+        self.leases
+            .get(path)
+            .map(|entry| entry.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn all(&self) -> Vec<FileLease> {
+        self.leases
+            .values()
+            .flat_map(|entry| entry.values().cloned())
+            .collect()
+    }
+
+    /// Decide whether `writer` may write `path` given the other party's lease
+    pub fn check_write(
+        &self,
+        path: &str,
+        writer: LeaseHolder,
+        policy: DirtyEditorPolicy,
+    ) -> WriteCheck {
+        let other = match writer {
+            LeaseHolder::Agent => LeaseHolder::Editor,
+            LeaseHolder::Editor => LeaseHolder::Agent,
+        };
+        let lease = self
+            .leases
+            .get(path)
+            .and_then(|entry| entry.get(&other))
+            .cloned();
+
+        match (&lease, writer) {
+            (Some(l), LeaseHolder::Agent) if l.dirty => WriteCheck {
+                decision: match policy {
+                    DirtyEditorPolicy::Block => WriteDecision::Blocked,
+                    DirtyEditorPolicy::Merge => WriteDecision::MergeRequired,
+                },
+                reason: Some(format!("{} has unsaved changes in the editor", path)),
+                lease,
+            },
+            // The editor saving over an agent lease is allowed; the agent's merge path
+            // picks up the change on its next write
+            _ => WriteCheck {
+                decision: WriteDecision::Allowed,
+                reason: None,
+                lease,
+            },
+        }
+    }
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn emit_lease_changed(app: &AppHandle, path: &str, table: &LeaseTable) {
+    let event = LeaseEvent {
+        path: path.to_string(),
+        leases: table.leases_for(path),
+    };
+    if let Err(e) = app.emit("file-lease-changed", &event) {
+        log::error!("Failed to emit file-lease-changed event: {}", e);
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn lease_acquire(
+    app: AppHandle,
+    state: State<'_, LeaseState>,
+    path: String,
+    holder: LeaseHolder,
+    owner_id: String,
+    ttl_secs: Option<u64>,
+) -> Result<FileLease, String> {
+    let mut table = state.0.lock().map_err(|e| e.to_string())?;
+    let now = now_millis();
+    table.prune(now);
+    let lease = table.acquire(
+        &path,
+        holder,
+        &owner_id,
+        ttl_secs.unwrap_or(DEFAULT_LEASE_TTL_SECS),
+        now,
+    );
+    emit_lease_changed(&app, &path, &table);
+    Ok(lease)
+}
+
+#[tauri::command]
+pub fn lease_release(
+    app: AppHandle,
+    state: State<'_, LeaseState>,
+    path: String,
+    holder: LeaseHolder,
+) -> Result<bool, String> {
+    let mut table = state.0.lock().map_err(|e| e.to_string())?;
+    let released = table.release(&path, holder);
+    if released {
+        emit_lease_changed(&app, &path, &table);
+    }
+    Ok(released)
+}
+
+#[tauri::command]
+pub fn lease_set_dirty(
+    app: AppHandle,
+    state: State<'_, LeaseState>,
+    path: String,
+    dirty: bool,
+) -> Result<FileLease, String> {
+    let mut table = state.0.lock().map_err(|e| e.to_string())?;
+    let lease = table.set_dirty(&path, dirty)?;
+    emit_lease_changed(&app, &path, &table);
+    Ok(lease)
+}
+
+#[tauri::command]
+pub fn lease_list(state: State<'_, LeaseState>) -> Result<Vec<FileLease>, String> {
+    let mut table = state.0.lock().map_err(|e| e.to_string())?;
+    table.prune(now_millis());
+    Ok(table.all())
+}
+
+#[tauri::command]
+pub fn lease_check_write(
+    state: State<'_, LeaseState>,
+    path: String,
+    holder: LeaseHolder,
+    policy: Option<DirtyEditorPolicy>,
+) -> Result<WriteCheck, String> {
+    let mut table = state.0.lock().map_err(|e| e.to_string())?;
+    table.prune(now_millis());
+    Ok(table.check_write(&path, holder, policy.unwrap_or(DirtyEditorPolicy::Block)))
+}
+
+/// Agent write that respects editor leases. Clean files go through the three-way merge
+/// write path; files with unsaved editor changes are blocked or handed to the frontend
+/// via a `file-lease-merge-required` event.
+#[tauri::command]
+pub fn lease_guarded_write(
+    app: AppHandle,
+    leases: State<'_, LeaseState>,
+    snapshots: State<'_, ReadSnapshotState>,
+    session_id: String,
+    path: String,
+    content: String,
+    policy: Option<DirtyEditorPolicy>,
+) -> Result<GuardedWriteResult, String> {
+    let check = {
+        let mut table = leases.0.lock().map_err(|e| e.to_string())?;
+        table.prune(now_millis());
+        table.check_write(
+            &path,
+            LeaseHolder::Agent,
+            policy.unwrap_or(DirtyEditorPolicy::Block),
+        )
+    };
+
+    match check.decision {
+        WriteDecision::Allowed => {
+            let write = file_merge::write_with_merge(
+                &snapshots,
+                &session_id,
+                &path,
+                &content,
+                ConflictStrategy::Markers,
+            )?;
+            if write.written {
+                if let Err(e) = app.emit("file-lease-agent-wrote", &path) {
+                    log::error!("Failed to emit file-lease-agent-wrote event: {}", e);
+                }
+            }
+            Ok(GuardedWriteResult {
+                decision: check.decision,
+                reason: None,
+                write: Some(write),
+            })
+        }
+        WriteDecision::Blocked => {
+            log::info!(
+                "Blocked agent write to {}: editor has unsaved changes",
+                path
+            );
+            if let Err(e) = app.emit("file-lease-write-blocked", &path) {
+                log::error!("Failed to emit file-lease-write-blocked event: {}", e);
+            }
+            Ok(GuardedWriteResult {
+                decision: check.decision,
+                reason: check.reason,
+                write: None,
+            })
+        }
+        WriteDecision::MergeRequired => {
+            let event = MergeRequiredEvent {
+                path: path.clone(),
+                base: snapshots.get(&session_id, &path)?,
+                session_id,
+                content,
+            };
+            if let Err(e) = app.emit("file-lease-merge-required", &event) {
+                log::error!("Failed to emit file-lease-merge-required event: {}", e);
+            }
+            Ok(GuardedWriteResult {
+                decision: check.decision,
+                reason: check.reason,
+                write: None,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_and_release() {
+        let mut table = LeaseTable::default();
+        table.acquire("/a.rs", LeaseHolder::Editor, "main", 60, 0);
+        table.acquire("/a.rs", LeaseHolder::Agent, "s1", 60, 0);
+        assert_eq!(table.leases_for("/a.rs").len(), 2);
+
+        assert!(table.release("/a.rs", LeaseHolder::Agent));
+        assert!(!table.release("/a.rs", LeaseHolder::Agent));
+        assert_eq!(table.leases_for("/a.rs").len(), 1);
+    }
+
+    #[test]
+    fn test_dirty_editor_blocks_agent() {
+        let mut table = LeaseTable::default();
+        table.acquire("/a.rs", LeaseHolder::Editor, "main", 60, 0);
+        let check = table.check_write("/a.rs", LeaseHolder::Agent, DirtyEditorPolicy::Block);
+        assert_eq!(check.decision, WriteDecision::Allowed);
+
+        table.set_dirty("/a.rs", true).unwrap();
+        let check = table.check_write("/a.rs", LeaseHolder::Agent, DirtyEditorPolicy::Block);
+        assert_eq!(check.decision, WriteDecision::Blocked);
+        let check = table.check_write("/a.rs", LeaseHolder::Agent, DirtyEditorPolicy::Merge);
+        assert_eq!(check.decision, WriteDecision::MergeRequired);
+
+        // The editor is never blocked by the agent
+        table.acquire("/a.rs", LeaseHolder::Agent, "s1", 60, 0);
+        let check = table.check_write("/a.rs", LeaseHolder::Editor, DirtyEditorPolicy::Block);
+        assert_eq!(check.decision, WriteDecision::Allowed);
+    }
+
+    #[test]
+    fn test_set_dirty_requires_editor_lease() {
+        let mut table = LeaseTable::default();
+        assert!(table.set_dirty("/a.rs", true).is_err());
+    }
+
+    #[test]
+    fn test_prune_expired() {
+        let mut table = LeaseTable::default();
+        table.acquire("/a.rs", LeaseHolder::Editor, "main", 1, 0);
+        table.acquire("/b.rs", LeaseHolder::Editor, "main", 60, 0);
+        table.prune(5_000);
+        assert!(table.leases_for("/a.rs").is_empty());
+        assert_eq!(table.all().len(), 1);
+    }
+}
@@ -0,0 +1,416 @@
+// Three-way merge for agent writes
+//
+// The agent's read tool records a snapshot of each file it reads (per session). When the
+// agent later writes the file, the write is merged against the user's on-disk changes
+// using the snapshot as the common base instead of clobbering them.
+
+use crate::conflict_regions::LineRange;
+use crate::line_endings;
+use crate::path_policy;
+use crate::text_diff::{diff_slices, DiffOp};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use tauri::State;
+
+const MARKER_CURRENT: &str = "<<<<<<< current (on disk)";
+const MARKER_SEPARATOR: &str = "=======";
+const MARKER_AGENT: &str = ">>>>>>> agent";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// 1-based line in the merged output where the conflict block starts
+    pub line: usize,
+    pub current: Vec<String>,
+    pub agent: Vec<String>,
+    /// What both sides changed from
+    pub base: Vec<String>,
+    /// The lines of each side in its own file, for the merge UI
+    pub base_range: LineRange,
+    pub current_range: LineRange,
+    pub agent_range: LineRange,
+    /// The marker block in the merged output
+    pub output_range: LineRange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeResult {
+    pub content: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// Write the file with git-style conflict markers
+    Markers,
+    /// Leave the file untouched and report the conflicts
+    Reject,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeWriteResult {
+    pub written: bool,
+    /// True when the file changed on disk since the agent read it
+    pub merged: bool,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Tauri state: session id -> (path -> content at the agent's last read/write)
+#[derive(Default)]
+pub struct ReadSnapshotState(pub Mutex<HashMap<String, HashMap<String, String>>>);
+
+/// Map each base line index to its index in `other` when the line is unchanged
+fn base_matches(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; base.len()];
+    let (mut b, mut o) = (0, 0);
+    for op in diff_slices(base, other) {
+        match op {
+            DiffOp::Equal(_) => {
+                matches[b] = Some(o);
+                b += 1;
+                o += 1;
+            }
+            DiffOp::Delete(_) => b += 1,
+            DiffOp::Insert(_) => o += 1,
+        }
+    }
+    matches
+}
+
+/// diff3-style merge of `current` (user's disk content) and `agent` against `base`
+pub fn merge3(base: &str, current: &str, agent: &str) -> MergeResult {
+    let base_lines: Vec<&str> = base.lines().collect();
+    let cur_lines: Vec<&str> = current.lines().collect();
+    let agent_lines: Vec<&str> = agent.lines().collect();
+
+    let cur_matches = base_matches(&base_lines, &cur_lines);
+    let agent_matches = base_matches(&base_lines, &agent_lines);
+
+    let mut out: Vec<String> = Vec::new();
+    let mut conflicts = Vec::new();
+    let (mut i, mut c, mut a) = (0, 0, 0);
+
+    loop {
+        // Next base line that is unchanged on both sides
+        let sync = (i..base_lines.len()).find_map(|k| match (cur_matches[k], agent_matches[k]) {
+            (Some(ck), Some(ak)) => Some((k, ck, ak)),
+            _ => None,
+        });
+        let (base_end, cur_end, agent_end) = match sync {
+            Some(s) => s,
+            None => (base_lines.len(), cur_lines.len(), agent_lines.len()),
+        };
+
+        let base_chunk = &base_lines[i..base_end];
+        let cur_chunk = &cur_lines[c..cur_end];
+        let agent_chunk = &agent_lines[a..agent_end];
+
+        if cur_chunk == base_chunk {
+            out.extend(agent_chunk.iter().map(|l| l.to_string()));
+        } else if agent_chunk == base_chunk || agent_chunk == cur_chunk {
+            out.extend(cur_chunk.iter().map(|l| l.to_string()));
+        } else {
+            conflicts.push(MergeConflict {
+                line: out.len() + 1,
+                current: cur_chunk.iter().map(|l| l.to_string()).collect(),
+                agent: agent_chunk.iter().map(|l| l.to_string()).collect(),
+                base: base_chunk.iter().map(|l| l.to_string()).collect(),
+                base_range: LineRange {
+                    start: i + 1,
+                    count: base_chunk.len(),
+                },
+                current_range: LineRange {
+                    start: c + 1,
+                    count: cur_chunk.len(),
+                },
+                agent_range: LineRange {
+                    start: a + 1,
+                    count: agent_chunk.len(),
+                },
+                output_range: LineRange {
+                    start: out.len() + 1,
+                    count: cur_chunk.len() + agent_chunk.len() + 3,
+                },
+            });
+            out.push(MARKER_CURRENT.to_string());
+            out.extend(cur_chunk.iter().map(|l| l.to_string()));
+            out.push(MARKER_SEPARATOR.to_string());
+            out.extend(agent_chunk.iter().map(|l| l.to_string()));
+            out.push(MARKER_AGENT.to_string());
+        }
+
+        match sync {
+            Some((k, ck, ak)) => {
+                out.push(base_lines[k].to_string());
+                i = k + 1;
+                c = ck + 1;
+                a = ak + 1;
+            }
+            None => break,
+        }
+    }
+
+    let mut content = out.join("\n");
+    if !content.is_empty() && (agent.ends_with('\n') || current.ends_with('\n')) {
+        content.push('\n');
+    }
+    MergeResult { content, conflicts }
+}
+
+impl ReadSnapshotState {
+    pub fn record(&self, session_id: &str, path: &str, content: String) -> Result<(), String> {
+        let mut sessions = self.0.lock().map_err(|e| e.to_string())?;
+        sessions
+            .entry(session_id.to_string())
+            .or_default()
+            .insert(path.to_string(), content);
+        Ok(())
+    }
+
+    pub fn get(&self, session_id: &str, path: &str) -> Result<Option<String>, String> {
+        let sessions = self.0.lock().map_err(|e| e.to_string())?;
+        Ok(sessions
+            .get(session_id)
+            .and_then(|files| files.get(path))
+            .cloned())
+    }
+}
+
+/// Write `content` for the agent, merging against changes made on disk since the
+/// agent's last recorded read of `path`
+pub fn write_with_merge(
+    snapshots: &ReadSnapshotState,
+    session_id: &str,
+    path: &str,
+    content: &str,
+    strategy: ConflictStrategy,
+) -> Result<MergeWriteResult, String> {
+    let base = snapshots.get(session_id, path)?;
+    let current = fs::read_to_string(path).ok();
+    let current_ending = current
+        .as_deref()
+        .filter(|c| c.contains('\n') || c.contains('\r'))
+        .map(|c| line_endings::detect(c).dominant);
+
+    let (to_write, merged, conflicts) = match (base, current) {
+        (Some(base), Some(current)) if base != current => {
+            let result = merge3(&base, &current, content);
+            (result.content, true, result.conflicts)
+        }
+        _ => (content.to_string(), false, Vec::new()),
+    };
+    // Keep the file's existing line endings; agent content is usually LF
+    let ending = current_ending.unwrap_or_else(|| line_endings::detect(content).dominant);
+    let to_write = line_endings::convert(&to_write, ending);
+
+    if !conflicts.is_empty() && strategy == ConflictStrategy::Reject {
+        log::info!(
+            "Rejected agent write to {}: {} merge conflicts",
+            path,
+            conflicts.len()
+        );
+        return Ok(MergeWriteResult {
+            written: false,
+            merged,
+            conflicts,
+        });
+    }
+
+    fs::write(path, &to_write).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    snapshots.record(session_id, path, to_write)?;
+    if merged {
+        log::info!(
+            "Merged agent write to {} with on-disk changes ({} conflicts)",
+            path,
+            conflicts.len()
+        );
+    }
+
+    Ok(MergeWriteResult {
+        written: true,
+        merged,
+        conflicts,
+    })
+}
+
+// Tauri commands
+
+/// Record the content the agent saw when reading `path`; returns that content.
+/// With `root_path`, paths whose links lead outside the workspace are refused.
+#[tauri::command]
+pub fn merge_record_read(
+    state: State<'_, ReadSnapshotState>,
+    session_id: String,
+    path: String,
+    root_path: Option<String>,
+) -> Result<String, String> {
+    path_policy::check_optional(&path, root_path.as_deref())?;
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    state.record(&session_id, &path, content.clone())?;
+    Ok(content)
+}
+
+/// Write through the three-way merge; `root_path` enforces workspace containment
+#[tauri::command]
+pub fn merge_write_file(
+    state: State<'_, ReadSnapshotState>,
+    session_id: String,
+    path: String,
+    content: String,
+    strategy: Option<ConflictStrategy>,
+    root_path: Option<String>,
+) -> Result<MergeWriteResult, String> {
+    path_policy::check_optional(&path, root_path.as_deref())?;
+    write_with_merge(
+        &state,
+        &session_id,
+        &path,
+        &content,
+        strategy.unwrap_or(ConflictStrategy::Markers),
+    )
+}
+
+#[tauri::command]
+pub fn merge_three_way(base: String, current: String, agent: String) -> MergeResult {
+    merge3(&base, &current, &agent)
+}
+
+#[tauri::command]
+pub fn merge_clear_session(
+    state: State<'_, ReadSnapshotState>,
+    session_id: String,
+) -> Result<(), String> {
+    let mut sessions = state.0.lock().map_err(|e| e.to_string())?;
+    sessions.remove(&session_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merge_non_overlapping_changes() {
+        let base = "a\nb\nc\nd\ne\n";
+        let current = "A\nb\nc\nd\ne\n";
+        let agent = "a\nb\nc\nd\nE\n";
+        let result = merge3(base, current, agent);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.content, "A\nb\nc\nd\nE\n");
+    }
+
+    #[test]
+    fn test_merge_identical_changes() {
+        let result = merge3("a\nb\n", "a\nx\n", "a\nx\n");
+        assert!(result.conflicts.is_empty());
+        assert_eq!(result.content, "a\nx\n");
+    }
+
+    #[test]
+    fn test_merge_conflict_markers() {
+        let result = merge3("a\nb\nc\n", "a\nuser\nc\n", "a\nagent\nc\n");
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].line, 2);
+        assert_eq!(result.conflicts[0].base, vec!["b"]);
+        assert_eq!(
+            result.conflicts[0].agent_range,
+            LineRange { start: 2, count: 1 }
+        );
+        assert_eq!(
+            result.conflicts[0].output_range,
+            LineRange { start: 2, count: 5 }
+        );
+        let regions = crate::conflict_regions::parse_conflicts(&result.content).unwrap();
+        assert_eq!(regions[0].range, result.conflicts[0].output_range);
+        assert_eq!(
+            result.content,
+            format!(
+                "a\n{}\nuser\n{}\nagent\n{}\nc\n",
+                MARKER_CURRENT, MARKER_SEPARATOR, MARKER_AGENT
+            )
+        );
+    }
+
+    #[test]
+    fn test_write_with_merge_preserves_user_edits() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("f.txt");
+        let path = path.to_string_lossy().to_string();
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+        let state = ReadSnapshotState::default();
+        state
+            .record("s1", &path, fs::read_to_string(&path).unwrap())
+            .unwrap();
+        fs::write(&path, "ONE\ntwo\nthree\n").unwrap();
+
+        let result = write_with_merge(
+            &state,
+            "s1",
+            &path,
+            "one\ntwo\nTHREE\n",
+            ConflictStrategy::Reject,
+        )
+        .unwrap();
+        assert!(result.written);
+        assert!(result.merged);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "ONE\ntwo\nTHREE\n");
+    }
+
+    #[test]
+    fn test_write_preserves_crlf() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("f.txt");
+        let path = path.to_string_lossy().to_string();
+        fs::write(&path, "one\r\ntwo\r\n").unwrap();
+
+        let state = ReadSnapshotState::default();
+        state
+            .record("s1", &path, fs::read_to_string(&path).unwrap())
+            .unwrap();
+        fs::write(&path, "ONE\r\ntwo\r\n").unwrap();
+
+        let result =
+            write_with_merge(&state, "s1", &path, "one\nTWO\n", ConflictStrategy::Reject).unwrap();
+        assert!(result.merged);
+        assert!(result.conflicts.is_empty());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "ONE\r\nTWO\r\n");
+    }
+
+    #[test]
+    fn test_write_with_merge_rejects_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("f.txt");
+        let path = path.to_string_lossy().to_string();
+        fs::write(&path, "x\n").unwrap();
+
+        let state = ReadSnapshotState::default();
+        state.record("s1", &path, "x\n".to_string()).unwrap();
+        fs::write(&path, "user\n").unwrap();
+
+        let result =
+            write_with_merge(&state, "s1", &path, "agent\n", ConflictStrategy::Reject).unwrap();
+        assert!(!result.written);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "user\n");
+    }
+
+    #[test]
+    fn test_write_without_snapshot_overwrites() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("f.txt");
+        let path = path.to_string_lossy().to_string();
+        fs::write(&path, "old\n").unwrap();
+
+        let state = ReadSnapshotState::default();
+        let result =
+            write_with_merge(&state, "s1", &path, "new\n", ConflictStrategy::Markers).unwrap();
+        assert!(result.written);
+        assert!(!result.merged);
+        assert_eq!(state.get("s1", &path).unwrap().as_deref(), Some("new\n"));
+    }
+}
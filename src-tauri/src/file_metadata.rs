@@ -0,0 +1,209 @@
+// File metadata and the executable bit
+//
+// `stat_file` reports what an agent needs to know about a file before acting on it:
+// size, modification time, permissions, whether it is executable and its git status.
+// `chmod_executable` lets an agent make a script it wrote runnable. It only touches
+// regular files inside the workspace (see `path_policy`) and only adds execute bits
+// where read bits are already set, like `chmod +x` under a typical umask. On Windows,
+// where executability comes from the extension, it changes nothing.
+
+use crate::git::{repository, status, types::GitFileStatus};
+use crate::path_policy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+#[cfg(not(unix))]
+const WINDOWS_EXECUTABLE_EXTENSIONS: &[&str] = &["exe", "bat", "cmd", "com", "ps1"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStat {
+    pub path: String,
+    pub size: u64,
+    /// Milliseconds since the Unix epoch
+    pub modified_ms: Option<i64>,
+    pub is_file: bool,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    pub readonly: bool,
+    /// Unix permission bits (e.g. 0o755); None on Windows
+    pub mode: Option<u32>,
+    pub executable: bool,
+    /// None when the file is unmodified or not in a git repository
+    pub git_status: Option<GitFileStatus>,
+    pub git_staged: bool,
+}
+
+#[cfg(unix)]
+fn mode_of(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn mode_of(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn is_executable(_path: &Path, metadata: &fs::Metadata) -> bool {
+    mode_of(metadata).is_some_and(|mode| mode & 0o111 != 0) && metadata.is_file()
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path, metadata: &fs::Metadata) -> bool {
+    metadata.is_file()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| WINDOWS_EXECUTABLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Git status of `path`, if it is inside a repository
+fn git_status_of(path: &Path) -> Option<(GitFileStatus, bool)> {
+    let repo = repository::discover_repository(path.parent()?).ok()?;
+    let workdir = repo.workdir()?.canonicalize().ok()?;
+    let canonical = path.canonicalize().ok()?;
+    let relative = canonical.strip_prefix(&workdir).ok()?;
+    status::get_file_status(&repo, relative).ok().flatten()
+}
+
+pub fn stat(path: &Path) -> Result<FileStat, String> {
+    let link_metadata = fs::symlink_metadata(path)
+        .map_err(|e| format!("Failed to stat {}: {}", path.display(), e))?;
+    // Describe what a symlink points to, falling back to the link itself if it dangles
+    let metadata = fs::metadata(path).unwrap_or_else(|_| link_metadata.clone());
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64);
+    let (git_status, git_staged) = match git_status_of(path) {
+        Some((status, staged)) => (Some(status), staged),
+        None => (None, false),
+    };
+
+    Ok(FileStat {
+        path: path.to_string_lossy().to_string(),
+        size: metadata.len(),
+        modified_ms,
+        is_file: metadata.is_file(),
+        is_dir: metadata.is_dir(),
+        is_symlink: link_metadata.file_type().is_symlink(),
+        readonly: metadata.permissions().readonly(),
+        mode: mode_of(&metadata),
+        executable: is_executable(path, &metadata),
+        git_status,
+        git_staged,
+    })
+}
+
+#[cfg(unix)]
+fn add_execute_bits(path: &Path, metadata: &fs::Metadata) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = metadata.permissions().mode();
+    // r-- -> r-x for each of user, group and other
+    let new_mode = mode | ((mode & 0o444) >> 2);
+    if new_mode != mode {
+        fs::set_permissions(path, fs::Permissions::from_mode(new_mode))
+            .map_err(|e| format!("Failed to chmod {}: {}", path.display(), e))?;
+        log::info!(
+            "Made {} executable ({:o} -> {:o})",
+            path.display(),
+            mode & 0o7777,
+            new_mode & 0o7777
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn add_execute_bits(_path: &Path, _metadata: &fs::Metadata) -> Result<(), String> {
+    Ok(())
+}
+
+/// Make a regular file inside `workspace_root` executable
+pub fn make_executable(path: &Path, workspace_root: &Path) -> Result<FileStat, String> {
+    let resolved = path_policy::resolve_within(path, workspace_root)?;
+    let metadata = fs::metadata(&resolved)
+        .map_err(|e| format!("Failed to stat {}: {}", resolved.display(), e))?;
+    if !metadata.is_file() {
+        return Err(format!("Not a regular file: {}", resolved.display()));
+    }
+    add_execute_bits(&resolved, &metadata)?;
+    stat(&resolved)
+}
+
+// Tauri commands
+
+/// Size, modification time, permissions and git status of `path`. With `root_path`,
+/// paths that resolve outside the workspace are refused.
+#[tauri::command]
+pub fn stat_file(path: String, root_path: Option<String>) -> Result<FileStat, String> {
+    path_policy::check_optional(&path, root_path.as_deref())?;
+    stat(Path::new(&path))
+}
+
+/// Add execute permission to a file inside the workspace
+#[tauri::command]
+pub fn chmod_executable(path: String, root_path: String) -> Result<FileStat, String> {
+    make_executable(Path::new(&path), Path::new(&root_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stat_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        fs::write(&path, "hello").unwrap();
+
+        let info = stat(&path).unwrap();
+        assert_eq!(info.size, 5);
+        assert!(info.is_file);
+        assert!(!info.is_dir);
+        assert!(!info.executable);
+        assert!(info.modified_ms.unwrap() > 0);
+        assert!(info.git_status.is_none());
+        assert!(stat(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_chmod_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let script = temp_dir.path().join("run.sh");
+        fs::write(&script, "#!/bin/sh\necho hi\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o640)).unwrap();
+
+        let info = make_executable(&script, temp_dir.path()).unwrap();
+        assert!(info.executable);
+        assert_eq!(info.mode, Some(0o750));
+
+        // Directories and paths outside the workspace are refused
+        assert!(make_executable(temp_dir.path(), temp_dir.path()).is_err());
+        let outside = TempDir::new().unwrap();
+        let other = outside.path().join("x.sh");
+        fs::write(&other, "").unwrap();
+        let error = make_executable(&other, temp_dir.path()).unwrap_err();
+        assert!(error.contains("escapes_workspace"));
+    }
+
+    #[test]
+    fn test_git_status_of_untracked_file() {
+        let temp_dir = TempDir::new().unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+        let path = temp_dir.path().join("new.rs");
+        fs::write(&path, "fn main() {}").unwrap();
+
+        let info = stat(&path).unwrap();
+        assert!(matches!(info.git_status, Some(GitFileStatus::Untracked)));
+        assert!(!info.git_staged);
+    }
+}
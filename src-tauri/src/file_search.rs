@@ -1,4 +1,5 @@
 use crate::constants::{is_code_extension, is_code_filename};
+use crate::path_utils;
 use crate::walker::{WalkerConfig, WorkspaceWalker};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -73,9 +74,7 @@ impl HighPerformanceFileSearch {
                 }
 
                 if let Ok(relative_path) = path.strip_prefix(root_path) {
-                    let relative_path_str = relative_path.to_string_lossy();
-                    // Normalize path separators to forward slashes for cross-platform search
-                    let normalized_path = relative_path_str.replace('\\', "/");
+                    let normalized_path = path_utils::path_to_slash(relative_path);
 
                     if let Some(search_result) = self.match_path(&normalized_path, path, &keywords)
                     {
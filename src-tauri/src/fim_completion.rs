@@ -0,0 +1,436 @@
+// Fill-in-the-middle completion backend
+//
+// Builds FIM prompts from the editor buffer around the cursor plus repo-map snippets
+// (signatures of symbols used near the cursor), streams completions from a fast
+// OpenAI-compatible `/completions` endpoint, and returns them ranked. Requests are
+// debounced per editor: a newer request for the same key cancels older ones, both
+// before the call is made and while it is streaming.
+
+use crate::code_navigation::{resolve_nav, CodeNavState, CodeNavigationService};
+use crate::http_client;
+use crate::offline_mode;
+use crate::provider_client::ProviderConfig;
+use crate::syntax_check;
+use crate::workspace_state::WorkspaceState;
+use futures_util::StreamExt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+const DEFAULT_DEBOUNCE_MS: u64 = 150;
+const DEFAULT_MAX_TOKENS: u32 = 128;
+const MAX_CHOICES: u32 = 4;
+const MAX_PREFIX_CHARS: usize = 8_000;
+const MAX_SUFFIX_CHARS: usize = 2_000;
+/// Lines before the cursor scanned for identifiers to look up in the index
+const REPO_MAP_SCAN_LINES: usize = 30;
+const MAX_REPO_MAP_SNIPPETS: usize = 5;
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FimRequest {
+    /// Debounce key, typically the editor/window id
+    pub request_key: String,
+    pub path: String,
+    /// Current buffer; may contain unsaved changes
+    pub content: String,
+    /// 1-based cursor position
+    pub line: u32,
+    pub column: u32,
+    pub max_tokens: Option<u32>,
+    pub n: Option<u32>,
+    pub debounce_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FimCompletion {
+    pub text: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FimResponse {
+    /// True when a newer request for the same key superseded this one
+    pub cancelled: bool,
+    pub completions: Vec<FimCompletion>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct FimChunkEvent<'a> {
+    request_key: &'a str,
+    index: usize,
+    text: &'a str,
+}
+
+/// Tauri state: latest request generation per debounce key
+#[derive(Default)]
+pub struct FimState {
+    latest: Mutex<HashMap<String, u64>>,
+    counter: AtomicU64,
+}
+
+impl FimState {
+    fn begin(&self, key: &str) -> Result<u64, String> {
+        let generation = self.counter.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut latest = self.latest.lock().map_err(|e| e.to_string())?;
+        latest.insert(key.to_string(), generation);
+        Ok(generation)
+    }
+
+    fn is_latest(&self, key: &str, generation: u64) -> bool {
+        self.latest
+            .lock()
+            .map(|latest| latest.get(key) == Some(&generation))
+            .unwrap_or(false)
+    }
+}
+
+fn identifier_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b[A-Za-z_][A-Za-z0-9_]{2,}\b").unwrap())
+}
+
+/// Byte offset of a 1-based line/column (columns counted in chars), clamped to the buffer
+pub fn cursor_offset(content: &str, line: u32, column: u32) -> usize {
+    let mut offset = 0;
+    for (idx, text) in content.split_inclusive('\n').enumerate() {
+        if idx + 1 == line.max(1) as usize {
+            let body = text.trim_end_matches(['\n', '\r']);
+            let col = body
+                .char_indices()
+                .nth(column.max(1) as usize - 1)
+                .map(|(i, _)| i)
+                .unwrap_or(body.len());
+            return offset + col;
+        }
+        offset += text.len();
+    }
+    content.len()
+}
+
+/// Split the buffer at the cursor, keeping the end of the prefix and the start of the suffix
+pub fn split_prefix_suffix(content: &str, line: u32, column: u32) -> (String, String) {
+    let offset = cursor_offset(content, line, column);
+    let (prefix, suffix) = content.split_at(offset);
+
+    let prefix_start = prefix
+        .char_indices()
+        .rev()
+        .nth(MAX_PREFIX_CHARS.saturating_sub(1))
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    let suffix_end = suffix
+        .char_indices()
+        .nth(MAX_SUFFIX_CHARS)
+        .map(|(i, _)| i)
+        .unwrap_or(suffix.len());
+    (
+        prefix[prefix_start..].to_string(),
+        suffix[..suffix_end].to_string(),
+    )
+}
+
+fn line_comment(lang_id: &str) -> &'static str {
+    match lang_id {
+        "python" => "#",
+        _ => "//",
+    }
+}
+
+/// Signatures of symbols used near the cursor that are defined in other files
+fn repo_map_snippets(
+    service: &CodeNavigationService,
+    prefix: &str,
+    path: &str,
+    lang_id: &str,
+) -> Vec<String> {
+    let tail: Vec<&str> = prefix.lines().rev().take(REPO_MAP_SCAN_LINES).collect();
+    let names: BTreeSet<&str> = tail
+        .iter()
+        .flat_map(|line| identifier_regex().find_iter(line).map(|m| m.as_str()))
+        .collect();
+    let lang_family = CodeNavigationService::get_lang_family(lang_id);
+
+    let mut file_cache: BTreeMap<String, Option<String>> = BTreeMap::new();
+    let mut snippets = Vec::new();
+    for name in names {
+        for symbol in service.find_definition(name, lang_family) {
+            if symbol.file_path == path || snippets.len() >= MAX_REPO_MAP_SNIPPETS {
+                continue;
+            }
+            let content = file_cache
+                .entry(symbol.file_path.clone())
+                .or_insert_with(|| fs::read_to_string(&symbol.file_path).ok());
+            if let Some(line) = content
+                .as_deref()
+                .and_then(|c| c.lines().nth(symbol.start_line.saturating_sub(1) as usize))
+            {
+                snippets.push(format!("{}: {}", symbol.file_path, line.trim()));
+            }
+        }
+    }
+    snippets
+}
+
+/// Prefix with repo-map snippets prepended as comments
+pub fn build_fim_prefix(prefix: &str, snippets: &[String], lang_id: &str) -> String {
+    if snippets.is_empty() {
+        return prefix.to_string();
+    }
+    let comment = line_comment(lang_id);
+    let mut out = format!("{} Related definitions:\n", comment);
+    for snippet in snippets {
+        out.push_str(&format!("{} {}\n", comment, snippet));
+    }
+    out.push('\n');
+    out.push_str(prefix);
+    out
+}
+
+/// Drain complete `data:` events from an SSE buffer
+pub fn drain_sse_events(buffer: &mut String) -> Vec<Value> {
+    let mut events = Vec::new();
+    while let Some(pos) = buffer.find('\n') {
+        let line: String = buffer.drain(..=pos).collect();
+        let Some(data) = line.trim().strip_prefix("data:") else {
+            continue;
+        };
+        let data = data.trim();
+        if data == "[DONE]" {
+            continue;
+        }
+        if let Ok(value) = serde_json::from_str(data) {
+            events.push(value);
+        }
+    }
+    events
+}
+
+/// Clean up, de-duplicate and score raw completions
+pub fn rank_completions(
+    raw: &[String],
+    prefix: &str,
+    suffix: &str,
+    lang_id: &str,
+) -> Vec<FimCompletion> {
+    let suffix_head = suffix.lines().find(|l| !l.trim().is_empty()).map(str::trim);
+    let baseline_issues = syntax_check::check_syntax(&format!("{}{}", prefix, suffix), lang_id)
+        .map(|r| r.issues.len())
+        .unwrap_or(0);
+
+    let mut seen = BTreeSet::new();
+    let mut ranked = Vec::new();
+    for text in raw {
+        let mut text = text.trim_end().to_string();
+        // Models often repeat the line that follows the cursor
+        if let Some(head) = suffix_head {
+            if let Some(stripped) = text.trim_end().strip_suffix(head) {
+                text = stripped.trim_end().to_string();
+            }
+        }
+        if text.trim().is_empty() || !seen.insert(text.clone()) {
+            continue;
+        }
+
+        let mut score = 1.0;
+        if let Ok(check) =
+            syntax_check::check_syntax(&format!("{}{}{}", prefix, text, suffix), lang_id)
+        {
+            if check.supported && check.issues.len() <= baseline_issues {
+                score += 0.5;
+            }
+        }
+        let line_count = text.lines().count();
+        if line_count > 20 {
+            score -= 0.2;
+        }
+        ranked.push(FimCompletion { text, score });
+    }
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    ranked
+}
+
+#[tauri::command]
+pub async fn fim_complete(
+    app: AppHandle,
+    state: State<'_, FimState>,
+    nav_state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    provider: ProviderConfig,
+    request: FimRequest,
+) -> Result<FimResponse, String> {
+    let provider = offline_mode::route_provider(&provider)?;
+    let cancelled = FimResponse {
+        cancelled: true,
+        completions: Vec::new(),
+    };
+    let generation = state.begin(&request.request_key)?;
+    tokio::time::sleep(Duration::from_millis(
+        request.debounce_ms.unwrap_or(DEFAULT_DEBOUNCE_MS),
+    ))
+    .await;
+    if !state.is_latest(&request.request_key, generation) {
+        return Ok(cancelled);
+    }
+
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&request.path).unwrap_or_default();
+    let (prefix, suffix) = split_prefix_suffix(&request.content, request.line, request.column);
+    let nav = resolve_nav(&nav_state, &workspaces, workspace_id.as_deref())?;
+    let snippets = {
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        repo_map_snippets(&service, &prefix, &request.path, &lang_id)
+    };
+    let prompt = build_fim_prefix(&prefix, &snippets, &lang_id);
+
+    let n = request.n.unwrap_or(1).clamp(1, MAX_CHOICES);
+    let url = format!("{}/completions", provider.base_url.trim_end_matches('/'));
+    let body = json!({
+        "model": provider.model,
+        "prompt": prompt,
+        "suffix": suffix,
+        "max_tokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+        "temperature": if n > 1 { 0.4 } else { 0.0 },
+        "n": n,
+        "stream": true,
+    });
+
+    let client = http_client::builder()?
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let mut http_request = client.post(&url).json(&body);
+    if let Some(key) = &provider.api_key {
+        http_request = http_request.bearer_auth(key);
+    }
+    let response = http_request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Provider returned status {}",
+            response.status().as_u16()
+        ));
+    }
+
+    let mut texts = vec![String::new(); n as usize];
+    let mut buffer = String::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        if !state.is_latest(&request.request_key, generation) {
+            return Ok(cancelled);
+        }
+        let chunk = chunk.map_err(|e| format!("Failed to read stream: {}", e))?;
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+        for event in drain_sse_events(&mut buffer) {
+            let Some(choices) = event.get("choices").and_then(|c| c.as_array()) else {
+                continue;
+            };
+            for choice in choices {
+                let index = choice.get("index").and_then(|i| i.as_u64()).unwrap_or(0) as usize;
+                let Some(text) = choice.get("text").and_then(|t| t.as_str()) else {
+                    continue;
+                };
+                if let Some(slot) = texts.get_mut(index) {
+                    slot.push_str(text);
+                    let event = FimChunkEvent {
+                        request_key: &request.request_key,
+                        index,
+                        text,
+                    };
+                    if let Err(e) = app.emit("fim-completion-chunk", &event) {
+                        log::error!("Failed to emit fim-completion-chunk event: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(FimResponse {
+        cancelled: false,
+        completions: rank_completions(&texts, &prefix, &suffix, &lang_id),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_prefix_suffix() {
+        let content = "fn main() {\n    let x = ;\n}\n";
+        let (prefix, suffix) = split_prefix_suffix(content, 2, 13);
+        assert_eq!(prefix, "fn main() {\n    let x = ");
+        assert_eq!(suffix, ";\n}\n");
+    }
+
+    #[test]
+    fn test_cursor_offset_unicode() {
+        let content = "let s = \"héllo\";\n";
+        // Columns count chars, so column 12 is the 'l' after the two-byte 'é'
+        let offset = cursor_offset(content, 1, 12);
+        assert_eq!(&content[..offset], "let s = \"hé");
+        assert_eq!(cursor_offset(content, 9, 1), content.len());
+    }
+
+    #[test]
+    fn test_build_fim_prefix() {
+        let prefix = build_fim_prefix("x = ", &["a.py: def helper(y):".to_string()], "python");
+        assert_eq!(
+            prefix,
+            "# Related definitions:\n# a.py: def helper(y):\n\nx = "
+        );
+        assert_eq!(build_fim_prefix("x = ", &[], "python"), "x = ");
+    }
+
+    #[test]
+    fn test_drain_sse_events() {
+        let mut buffer =
+            "data: {\"choices\":[{\"index\":0,\"text\":\"a\"}]}\n\ndata: [DONE]\ndata: {\"par"
+                .to_string();
+        let events = drain_sse_events(&mut buffer);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["choices"][0]["text"], "a");
+        assert_eq!(buffer, "data: {\"par");
+    }
+
+    #[test]
+    fn test_rank_completions() {
+        let prefix = "fn main() {\n    let x = 1";
+        let suffix = ";\n}\n";
+        let raw = vec![
+            "+ (".to_string(),
+            "0".to_string(),
+            "0".to_string(),
+            "   ".to_string(),
+        ];
+        let ranked = rank_completions(&raw, prefix, suffix, "rust");
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].text, "0");
+        assert!(ranked[0].score > ranked[1].score);
+    }
+
+    #[test]
+    fn test_debounce_generations() {
+        let state = FimState::default();
+        let first = state.begin("editor").unwrap();
+        assert!(state.is_latest("editor", first));
+        let second = state.begin("editor").unwrap();
+        assert!(!state.is_latest("editor", first));
+        assert!(state.is_latest("editor", second));
+    }
+}
@@ -0,0 +1,252 @@
+// Code folding ranges
+//
+// The editor folds the same things the summarizer collapses: a definition's folds come
+// from the language's summarization query, so "fold all" shows roughly what a summary
+// keeps. Runs of line comments and block comments fold as comments, and consecutive
+// import statements fold into one imports range. Lines are 1-based and inclusive;
+// ranges covering a single line are not reported.
+
+use crate::capture_kinds::{is_helper_capture, CaptureKind};
+use crate::code_navigation::{get_language, CodeNavigationService};
+use crate::grammar_cache::{self, QuerySet};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Parser, QueryCursor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FoldingKind {
+    Comment,
+    Imports,
+    /// A definition or other block
+    Region,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoldingRange {
+    pub start_line: u32,
+    pub end_line: u32,
+    pub kind: FoldingKind,
+    /// Capture kind of the definition for region folds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition_kind: Option<CaptureKind>,
+}
+
+/// Statements that bring names into scope, across the supported grammars
+const IMPORT_KINDS: &[&str] = &[
+    "import_statement",
+    "import_from_statement",
+    "future_import_statement",
+    "import_declaration",
+    "use_declaration",
+    "extern_crate_declaration",
+    "preproc_include",
+    "using_directive",
+    "import",
+    "open_module",
+];
+
+fn rows(node: Node) -> (u32, u32) {
+    (
+        node.start_position().row as u32 + 1,
+        node.end_position().row as u32 + 1,
+    )
+}
+
+/// Extend the last run when `start` follows it directly, otherwise start a new one
+fn extend_runs(runs: &mut Vec<(u32, u32)>, start: u32, end: u32) {
+    match runs.last_mut() {
+        Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+        _ => runs.push((start, end)),
+    }
+}
+
+fn push_runs(runs: Vec<(u32, u32)>, kind: FoldingKind, ranges: &mut Vec<FoldingRange>) {
+    ranges.extend(runs.into_iter().filter(|(start, end)| end > start).map(
+        |(start_line, end_line)| FoldingRange {
+            start_line,
+            end_line,
+            kind,
+            definition_kind: None,
+        },
+    ));
+}
+
+/// Multi-line comments, and runs of comments on consecutive lines
+fn comment_ranges(root: Node, ranges: &mut Vec<FoldingRange>) {
+    let mut runs = Vec::new();
+    let mut cursor = root.walk();
+    'walk: loop {
+        let node = cursor.node();
+        let is_comment = node.kind().contains("comment");
+        if is_comment {
+            let (start, end) = rows(node);
+            extend_runs(&mut runs, start, end);
+        }
+        if !is_comment && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
+        }
+    }
+    push_runs(runs, FoldingKind::Comment, ranges);
+}
+
+/// Consecutive top-level import statements; comments between them do not break a run
+fn import_ranges(root: Node, ranges: &mut Vec<FoldingRange>) {
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    let mut in_run = false;
+    let mut cursor = root.walk();
+    for child in root.named_children(&mut cursor) {
+        if child.kind().contains("comment") {
+            continue;
+        }
+        let is_import = IMPORT_KINDS.contains(&child.kind());
+        if is_import {
+            let (start, end) = rows(child);
+            match runs.last_mut() {
+                Some(last) if in_run => last.1 = end,
+                _ => runs.push((start, end)),
+            }
+        }
+        in_run = is_import;
+    }
+    push_runs(runs, FoldingKind::Imports, ranges);
+}
+
+pub fn folding_ranges(content: &str, lang_id: &str) -> Result<Vec<FoldingRange>, String> {
+    let language =
+        get_language(lang_id).ok_or_else(|| format!("Unsupported language: {}", lang_id))?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| format!("Failed to set language for {}: {:?}", lang_id, e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| format!("Failed to parse {} content", lang_id))?;
+    let root = tree.root_node();
+
+    // One fold per start line; the outermost node starting there wins
+    let mut regions: BTreeMap<u32, FoldingRange> = BTreeMap::new();
+    let query = grammar_cache::query(QuerySet::Summarization, lang_id)?;
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, root, content.as_bytes());
+    while let Some(m) = matches.next() {
+        for capture in m.captures {
+            let capture_name = query.capture_names()[capture.index as usize];
+            if is_helper_capture(capture_name) {
+                continue;
+            }
+            let (start, end) = rows(capture.node);
+            if end == start || regions.get(&start).is_some_and(|r| r.end_line >= end) {
+                continue;
+            }
+            regions.insert(
+                start,
+                FoldingRange {
+                    start_line: start,
+                    end_line: end,
+                    kind: FoldingKind::Region,
+                    definition_kind: CaptureKind::from_capture_name(capture_name),
+                },
+            );
+        }
+    }
+
+    let mut ranges: Vec<FoldingRange> = regions.into_values().collect();
+    comment_ranges(root, &mut ranges);
+    import_ranges(root, &mut ranges);
+    ranges.sort_by_key(|r| (r.start_line, std::cmp::Reverse(r.end_line)));
+    ranges.dedup_by_key(|r| r.start_line);
+    Ok(ranges)
+}
+
+/// Folding ranges for the file at `file_path`, or for `content` when given
+#[tauri::command]
+pub fn get_folding_ranges(
+    file_path: String,
+    content: Option<String>,
+    lang_id: Option<String>,
+) -> Result<Vec<FoldingRange>, String> {
+    let content = match content {
+        Some(content) => content,
+        None => fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to read {}: {}", file_path, e))?,
+    };
+    let lang_id = lang_id
+        .or_else(|| CodeNavigationService::get_lang_id_from_path(&file_path))
+        .ok_or_else(|| format!("Could not determine the language of {}", file_path))?;
+    folding_ranges(&content, &lang_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn summary(ranges: &[FoldingRange]) -> Vec<(u32, u32, FoldingKind)> {
+        ranges
+            .iter()
+            .map(|r| (r.start_line, r.end_line, r.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_python_folding_ranges() {
+        let code = r#"import os
+import sys
+from typing import List
+
+# Helpers for paths
+# used by the CLI
+class Paths:
+    def join(self, parts: List[str]) -> str:
+        return os.path.join(*parts)
+
+    def one_liner(self): return 1
+
+def main():
+    print(Paths().join(sys.argv))
+"#;
+        let ranges = folding_ranges(code, "python").unwrap();
+        assert_eq!(
+            summary(&ranges),
+            vec![
+                (1, 3, FoldingKind::Imports),
+                (5, 6, FoldingKind::Comment),
+                (7, 11, FoldingKind::Region),
+                (8, 9, FoldingKind::Region),
+                (13, 14, FoldingKind::Region),
+            ]
+        );
+        assert_eq!(ranges[2].definition_kind, Some(CaptureKind::Class));
+        assert_eq!(ranges[3].definition_kind, Some(CaptureKind::Function));
+    }
+
+    #[test]
+    fn test_rust_folding_ranges_from_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("lib.rs");
+        fs::write(
+            &path,
+            "use std::fmt;\nuse std::io;\n\n/*\n * Shapes\n */\nstruct Point {\n    x: i32,\n}\n",
+        )
+        .unwrap();
+
+        let ranges = get_folding_ranges(path.to_string_lossy().to_string(), None, None).unwrap();
+        assert_eq!(
+            summary(&ranges),
+            vec![
+                (1, 2, FoldingKind::Imports),
+                (4, 6, FoldingKind::Comment),
+                (7, 9, FoldingKind::Region),
+            ]
+        );
+        assert!(get_folding_ranges("notes.txt".to_string(), Some(String::new()), None).is_err());
+    }
+}
@@ -209,6 +209,27 @@ pub fn get_raw_diff_text(repo: &Repository) -> Result<String, GitError> {
     format_diff_as_text(diff)
 }
 
+/// Generates raw diff text between `base` and `head` revisions (any revspec git accepts).
+/// Without `head`, diffs `base` against the working directory including the index.
+pub fn get_range_diff_text(
+    repo: &Repository,
+    base: &str,
+    head: Option<&str>,
+) -> Result<String, GitError> {
+    let mut opts = DiffOptions::new();
+    let base_tree = repo.revparse_single(base)?.peel_to_tree()?;
+
+    let diff = match head {
+        Some(head) => {
+            let head_tree = repo.revparse_single(head)?.peel_to_tree()?;
+            repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut opts))?
+        }
+        None => repo.diff_tree_to_workdir_with_index(Some(&base_tree), Some(&mut opts))?,
+    };
+
+    format_diff_as_text(diff)
+}
+
 /// Formats a git2::Diff as human-readable text similar to `git diff` output
 fn format_diff_as_text(diff: Diff) -> Result<String, GitError> {
     use std::cell::RefCell;
@@ -563,6 +584,30 @@ mod tests {
         assert!(diff_text.contains('-'), "Should contain deletions");
     }
 
+    #[test]
+    fn test_get_range_diff_text() {
+        let temp_dir = create_temp_git_repo_with_commit();
+        let readme = temp_dir.path().join("README.md");
+        std::fs::write(&readme, "# Second\nLine 2\nLine 3\n").unwrap();
+
+        Command::new("git")
+            .args(["commit", "-am", "Second"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+
+        let repo = Repository::open(temp_dir.path()).unwrap();
+        let diff_text = get_range_diff_text(&repo, "HEAD~1", Some("HEAD")).unwrap();
+        assert!(diff_text.contains("-# Initial"));
+        assert!(diff_text.contains("+# Second"));
+
+        // Against the working directory there is nothing left to show
+        let diff_text = get_range_diff_text(&repo, "HEAD", None).unwrap();
+        assert!(diff_text.is_empty());
+
+        assert!(get_range_diff_text(&repo, "no-such-rev", None).is_err());
+    }
+
     #[test]
     fn test_get_raw_diff_text_empty_when_no_changes() {
         let temp_dir = create_temp_git_repo_with_commit();
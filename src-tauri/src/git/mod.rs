@@ -89,6 +89,20 @@ pub async fn git_get_raw_diff_text(repo_path: String) -> Result<String, String>
     diff::get_raw_diff_text(&repo).map_err(|e| format!("Failed to get raw diff text: {}", e))
 }
 
+/// Gets raw diff text between two revisions, or between a revision and the working directory
+#[tauri::command]
+pub async fn git_get_range_diff_text(
+    repo_path: String,
+    base: String,
+    head: Option<String>,
+) -> Result<String, String> {
+    let repo = repository::discover_repository(&repo_path)
+        .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+    diff::get_range_diff_text(&repo, &base, head.as_deref())
+        .map_err(|e| format!("Failed to get range diff text: {}", e))
+}
+
 // ============================================================================
 // Worktree Commands
 // ============================================================================
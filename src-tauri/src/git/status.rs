@@ -102,6 +102,37 @@ fn status_to_git_file_status(status: Status, is_staged: bool) -> GitFileStatus {
     }
 }
 
+/// (status, is_staged) of one status entry; staged changes take priority.
+/// None for unmodified and ignored files.
+fn classify_status(status: Status) -> Option<(GitFileStatus, bool)> {
+    if status.is_conflicted() {
+        Some((GitFileStatus::Conflicted, false))
+    } else if status.intersects(
+        Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED | Status::INDEX_RENAMED,
+    ) {
+        Some((status_to_git_file_status(status, true), true))
+    } else if status.intersects(Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_RENAMED) {
+        Some((status_to_git_file_status(status, false), false))
+    } else if status.is_wt_new() {
+        Some((GitFileStatus::Untracked, false))
+    } else {
+        None
+    }
+}
+
+/// Status of a single file, given relative to the repository's working directory.
+/// None when the file is unmodified, ignored or unknown to git.
+pub fn get_file_status(
+    repo: &Repository,
+    relative_path: &std::path::Path,
+) -> Result<Option<(GitFileStatus, bool)>, GitError> {
+    match repo.status_file(relative_path) {
+        Ok(status) => Ok(classify_status(status)),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
 /// Gets a map of all file statuses in the repository
 /// Returns a map of file path to (status, is_staged)
 pub fn get_all_file_statuses(
@@ -116,27 +147,8 @@ pub fn get_all_file_statuses(
 
     for entry in statuses.iter() {
         let path = entry.path().unwrap_or("").to_string();
-        let status = entry.status();
-
-        if status.is_conflicted() {
-            result.insert(path, (GitFileStatus::Conflicted, false));
-            continue;
-        }
-
-        // Prioritize staged status
-        if status.intersects(
-            Status::INDEX_NEW
-                | Status::INDEX_MODIFIED
-                | Status::INDEX_DELETED
-                | Status::INDEX_RENAMED,
-        ) {
-            let git_status = status_to_git_file_status(status, true);
-            result.insert(path.clone(), (git_status, true));
-        } else if status.intersects(Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_RENAMED) {
-            let git_status = status_to_git_file_status(status, false);
-            result.insert(path.clone(), (git_status, false));
-        } else if status.is_wt_new() {
-            result.insert(path, (GitFileStatus::Untracked, false));
+        if let Some(file_status) = classify_status(entry.status()) {
+            result.insert(path, file_status);
         }
     }
 
@@ -1,3 +1,4 @@
+use crate::path_utils;
 use crate::walker::{validate_path_in_workspace, WalkerConfig, WorkspaceWalker};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -119,23 +120,12 @@ impl HighPerformanceGlob {
 
     /// Match glob pattern against file path
     fn matches_glob_pattern(&self, file_path: &str, pattern: &str, root_path: &str) -> bool {
-        // Convert absolute path to relative path for matching
-        let relative_path = if file_path.starts_with(root_path) {
-            let root_len = root_path.len();
-            let mut rel_path = &file_path[root_len..];
-            // Remove leading slash if present
-            if rel_path.starts_with('/') || rel_path.starts_with('\\') {
-                rel_path = &rel_path[1..];
-            }
-            rel_path
-        } else {
-            file_path
-        };
-
-        // Normalize path separators for cross-platform compatibility
-        // Windows uses \ while patterns typically use /
-        let normalized_path = relative_path.replace('\\', "/");
-        let normalized_pattern = pattern.replace('\\', "/");
+        // Match relative to the root, with '/' separators whatever the platform uses;
+        // files outside the root are matched by their full path
+        let normalized_path =
+            path_utils::relative_to(file_path, root_path, path_utils::CASE_INSENSITIVE)
+                .unwrap_or_else(|| path_utils::to_slash(file_path));
+        let normalized_pattern = path_utils::to_slash(pattern);
 
         self.glob_match(&normalized_path, &normalized_pattern)
     }
@@ -0,0 +1,225 @@
+// Gradle build script summaries
+//
+// A build.gradle is read for what the project applies and depends on, and for the tasks
+// it adds; the android, publishing and signing blocks past the first screen are
+// configuration boilerplate. The summary keeps `plugins` and `dependencies` blocks
+// whole, `apply` lines and top-level assignments (`group`, `version`), and the
+// declaration of each task with its body elided. Every other block is reduced to its
+// header. `buildscript`, `allprojects` and `subprojects` hold their own plugins and
+// dependencies, so their bodies are summarized by the same rules. Build scripts are
+// written one statement per line with closures in braces, so lines are scanned rather
+// than parsed with a Groovy grammar.
+
+use crate::code_navigation::CodeSummary;
+use crate::text_slice::{before_in_code, brace_balance};
+
+/// Blocks kept verbatim, comments aside
+const KEPT_BLOCKS: &[&str] = &["plugins", "dependencies"];
+/// Blocks whose statements are summarized like the top level
+const NESTED_SCRIPTS: &[&str] = &[
+    "buildscript",
+    "allprojects",
+    "subprojects",
+    "project",
+    "configure",
+];
+
+/// A block's statements, each running until the braces it opens are closed. Blank
+/// lines and comments between statements are dropped.
+fn statements<'a>(lines: &[&'a str]) -> Vec<Vec<&'a str>> {
+    let mut statements = Vec::new();
+    let mut in_block_comment = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+        i += 1;
+        if in_block_comment || trimmed.starts_with("/*") {
+            in_block_comment = !trimmed.contains("*/");
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        let mut statement = vec![line];
+        let mut depth = brace_balance(line, "gradle");
+        while depth > 0 {
+            let Some(next) = lines.get(i) else {
+                break;
+            };
+            depth += brace_balance(next, "gradle");
+            statement.push(next);
+            i += 1;
+        }
+        statements.push(statement);
+    }
+    statements
+}
+
+/// `tasks.register`, `dependencies`, `version`: the dotted name a statement starts with
+fn leading_name(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+fn outline_block(block: &[&str], lines: &mut Vec<String>) {
+    for statement in statements(block) {
+        let first_line = statement[0];
+        let indent = &first_line[..first_line.len() - first_line.trim_start().len()];
+        let name = leading_name(first_line.trim_start());
+        // The closure the statement configures opens on its first line
+        let Some(head) = before_in_code(first_line, "{", "gradle") else {
+            lines.push(first_line.to_string());
+            continue;
+        };
+        let head = head.trim_end();
+
+        if KEPT_BLOCKS.contains(&name) {
+            lines.extend(
+                statement
+                    .iter()
+                    .filter(|line| {
+                        let line = line.trim();
+                        !(line.is_empty() || line.starts_with("//"))
+                    })
+                    .map(|line| line.to_string()),
+            );
+        } else if NESTED_SCRIPTS.contains(&name)
+            && statement.len() > 2
+            && statement[statement.len() - 1].trim() == "}"
+        {
+            lines.push(format!("{} {{", head));
+            outline_block(&statement[1..statement.len() - 1], lines);
+            lines.push(format!("{}}}", indent));
+        } else {
+            lines.push(format!("{} {{ ... }}", head));
+        }
+    }
+}
+
+pub fn gradle_outline(content: &str) -> String {
+    let source: Vec<&str> = content.lines().map(str::trim_end).collect();
+    let mut lines = Vec::new();
+    outline_block(&source, &mut lines);
+    lines.join("\n")
+}
+
+pub fn summarize_gradle(content: &str) -> CodeSummary {
+    let original_lines = content.lines().count();
+    let outline = gradle_outline(content);
+    if outline.is_empty() {
+        return CodeSummary::unchanged(
+            content.to_string(),
+            original_lines,
+            "gradle".to_string(),
+            "no declarations",
+        );
+    }
+    CodeSummary {
+        success: true,
+        summary: format!(
+            "[COMPRESSED: Original {} lines → plugins, dependencies and task declarations, other blocks elided]\n\n{}",
+            original_lines, outline
+        ),
+        original_lines,
+        lang_id: "gradle".to_string(),
+        truncated: None,
+        skipped_reason: None,
+        omitted_symbols: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_android_app_script() {
+        let script = r#"// App module
+plugins {
+    id 'com.android.application'
+    // Needed for Compose
+    id 'org.jetbrains.kotlin.android'
+}
+
+group = 'com.example'
+version = '1.2.0'
+
+android {
+    namespace 'com.example.app'
+    compileSdk 34
+
+    defaultConfig {
+        minSdk 24
+        targetSdk 34
+    }
+}
+
+dependencies {
+    implementation 'androidx.core:core-ktx:1.12.0'
+    testImplementation 'junit:junit:4.13.2'
+}
+
+task copyAssets(type: Copy) {
+    from 'assets'
+    into "$buildDir/assets"
+}
+
+tasks.register('lintAll') {
+    dependsOn 'lint'
+}
+"#;
+        let outline = gradle_outline(script);
+        assert_eq!(
+            outline,
+            r#"plugins {
+    id 'com.android.application'
+    id 'org.jetbrains.kotlin.android'
+}
+group = 'com.example'
+version = '1.2.0'
+android { ... }
+dependencies {
+    implementation 'androidx.core:core-ktx:1.12.0'
+    testImplementation 'junit:junit:4.13.2'
+}
+task copyAssets(type: Copy) { ... }
+tasks.register('lintAll') { ... }"#
+        );
+    }
+
+    #[test]
+    fn test_nested_scripts() {
+        let script = r#"buildscript {
+    repositories {
+        mavenCentral()
+    }
+    dependencies {
+        classpath 'com.android.tools.build:gradle:8.2.0'
+    }
+}
+
+subprojects {
+    apply plugin: 'java'
+    compileJava.options.encoding = 'UTF-8'
+}
+"#;
+        let summary = summarize_gradle(script);
+        assert!(summary.success);
+        assert!(summary.summary.ends_with(
+            r#"buildscript {
+    repositories { ... }
+    dependencies {
+        classpath 'com.android.tools.build:gradle:8.2.0'
+    }
+}
+subprojects {
+    apply plugin: 'java'
+    compileJava.options.encoding = 'UTF-8'
+}"#
+        ));
+        assert!(!summarize_gradle("// nothing yet\n").success);
+    }
+}
@@ -0,0 +1,197 @@
+// Lazy grammar loading and parser warm-up
+//
+// Compiling a tree-sitter query walks the grammar's whole state table, and doing it
+// for every language up front (or once per file, as indexing used to) stalled the
+// first summarize or index of a large session. Queries are now compiled the first
+// time a language needs them and shared afterwards. `warm_up_grammars` compiles them
+// ahead of time on a background thread, for the languages a project actually uses,
+// so that first request finds them ready.
+
+use crate::code_navigation::{get_language, get_summarization_query, CodeNavigationService};
+use crate::walker::{WalkerConfig, WorkspaceWalker};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Instant;
+use tree_sitter::Query;
+
+/// Files sampled when detecting a project's languages
+const DETECTION_SAMPLE_FILES: usize = 2_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuerySet {
+    Definitions,
+    Summarization,
+}
+
+impl QuerySet {
+    fn source(self, lang_id: &str) -> &'static str {
+        match self {
+            QuerySet::Definitions => CodeNavigationService::get_definition_query(lang_id),
+            QuerySet::Summarization => get_summarization_query(lang_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrammarStatus {
+    /// Languages with at least one compiled query, sorted
+    pub loaded: Vec<String>,
+}
+
+type QueryCache = RwLock<HashMap<(QuerySet, String), Arc<Query>>>;
+
+fn cache() -> &'static QueryCache {
+    static CACHE: OnceLock<QueryCache> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// The compiled query, compiling it on first use. Err for unsupported languages,
+/// languages without such a query, and queries that do not compile.
+pub fn query(set: QuerySet, lang_id: &str) -> Result<Arc<Query>, String> {
+    let key = (set, lang_id.to_string());
+    if let Some(query) = cache().read().ok().and_then(|c| c.get(&key).cloned()) {
+        return Ok(query);
+    }
+
+    let language =
+        get_language(lang_id).ok_or_else(|| format!("Unsupported language: {}", lang_id))?;
+    let source = set.source(lang_id);
+    if source.is_empty() {
+        return Err(format!("No {:?} query for {}", set, lang_id));
+    }
+    let start = Instant::now();
+    let query = Arc::new(
+        Query::new(&language, source)
+            .map_err(|e| format!("Failed to compile {:?} query for {}: {:?}", set, lang_id, e))?,
+    );
+    log::debug!(
+        "Compiled {:?} query for {} in {:.2}ms",
+        set,
+        lang_id,
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+
+    // Two threads may compile the same query; the first one stored wins
+    let mut cache = cache()
+        .write()
+        .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+    Ok(cache.entry(key).or_insert(query).clone())
+}
+
+/// Compile every query of each language; returns the languages that are now loaded
+pub fn warm_up(lang_ids: &[String]) -> Vec<String> {
+    let start = Instant::now();
+    let mut warmed = Vec::new();
+    for lang_id in lang_ids {
+        let loaded = [QuerySet::Definitions, QuerySet::Summarization]
+            .into_iter()
+            .filter(|set| query(*set, lang_id).is_ok())
+            .count();
+        if loaded > 0 {
+            warmed.push(lang_id.clone());
+        }
+    }
+    log::info!(
+        "Warmed up grammars for {:?} in {:.2}ms",
+        warmed,
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+    warmed
+}
+
+/// Supported languages under `root_path`, most files first
+pub fn detect_languages(root_path: &str) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let walker = WorkspaceWalker::new(root_path, WalkerConfig::for_list_files()).build();
+    for entry in walker
+        .flatten()
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .take(DETECTION_SAMPLE_FILES)
+    {
+        let path = entry.path().to_string_lossy();
+        if let Some(lang_id) = CodeNavigationService::get_lang_id_from_path(&path) {
+            *counts.entry(lang_id).or_default() += 1;
+        }
+    }
+
+    let mut langs: Vec<(String, usize)> = counts.into_iter().collect();
+    langs.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    langs.into_iter().map(|(lang_id, _)| lang_id).collect()
+}
+
+pub fn status() -> GrammarStatus {
+    let mut loaded: Vec<String> = cache()
+        .read()
+        .map(|c| c.keys().map(|(_, lang_id)| lang_id.clone()).collect())
+        .unwrap_or_default();
+    loaded.sort();
+    loaded.dedup();
+    GrammarStatus { loaded }
+}
+
+/// Compile grammars in the background, for the given languages or for those detected
+/// under `root_path`. Returns the languages that will be warmed without waiting.
+#[tauri::command]
+pub fn warm_up_grammars(
+    root_path: Option<String>,
+    lang_ids: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    let lang_ids = match (lang_ids, root_path) {
+        (Some(lang_ids), _) => lang_ids,
+        (None, Some(root)) => detect_languages(&root),
+        (None, None) => return Err("Either root_path or lang_ids is required".to_string()),
+    };
+    let targets = lang_ids.clone();
+    tauri::async_runtime::spawn_blocking(move || warm_up(&lang_ids));
+    Ok(targets)
+}
+
+#[tauri::command]
+pub fn get_grammar_status() -> GrammarStatus {
+    status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_query_is_compiled_once() {
+        let first = query(QuerySet::Definitions, "go").unwrap();
+        let second = query(QuerySet::Definitions, "go").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(status().loaded.contains(&"go".to_string()));
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        assert!(query(QuerySet::Summarization, "cobol").is_err());
+        assert!(warm_up(&["cobol".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_warm_up_loads_both_queries() {
+        assert_eq!(warm_up(&["java".to_string()]), vec!["java"]);
+        let cache = cache().read().unwrap();
+        assert!(cache.contains_key(&(QuerySet::Definitions, "java".to_string())));
+        assert!(cache.contains_key(&(QuerySet::Summarization, "java".to_string())));
+    }
+
+    #[test]
+    fn test_detect_languages_by_file_count() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        for name in ["a.rs", "b.rs", "src/c.rs", "web.ts", "README.md"] {
+            fs::write(root.join(name), "").unwrap();
+        }
+        assert_eq!(
+            detect_languages(&root.to_string_lossy()),
+            vec!["rust", "typescript"]
+        );
+    }
+}
@@ -0,0 +1,222 @@
+// GraphQL schema summaries
+//
+// A schema is read for its types. The summary keeps every type system definition
+// (types, inputs, interfaces, enums, unions, scalars, directives, the schema and their
+// extensions) with its fields, arguments and enum values, and the comments and
+// description directly above it. Descriptions on members and comments inside a
+// definition are dropped. Operations and fragments keep their signature with the
+// selection set elided. No GraphQL grammar is published for the tree-sitter version
+// the app uses, and definitions are brace-delimited blocks at the top level, so lines
+// are scanned directly.
+
+use crate::code_navigation::CodeSummary;
+use crate::text_slice::{before_in_code, brace_balance};
+
+/// Executable definitions; their selection sets are elided
+const OPERATIONS: &[&str] = &["query", "mutation", "subscription", "fragment"];
+/// A line starting with one of these continues the definition above it, as when a
+/// union lists its members on their own lines
+const CONTINUATIONS: &[&str] = &["|", "=", "&", "{", "@", "implements", "on "];
+
+/// A top-level definition with the comments and description above it
+struct Definition<'a> {
+    comments: Vec<&'a str>,
+    description: Vec<&'a str>,
+    body: Vec<&'a str>,
+}
+
+fn definitions(content: &str) -> Vec<Definition<'_>> {
+    let lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+    let mut definitions = Vec::new();
+    let mut comments = Vec::new();
+    let mut description = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+        i += 1;
+        if trimmed.is_empty() {
+            comments.clear();
+            description.clear();
+        } else if trimmed.starts_with('#') {
+            comments.push(line);
+        } else if let Some(rest) = trimmed.strip_prefix("\"\"\"") {
+            // Block string description, to its closing quotes
+            description.push(line);
+            if !rest.contains("\"\"\"") {
+                while let Some(next) = lines.get(i) {
+                    description.push(next);
+                    i += 1;
+                    if next.contains("\"\"\"") {
+                        break;
+                    }
+                }
+            }
+        } else if trimmed.starts_with('"') {
+            description.push(line);
+        } else {
+            // The definition runs until its braces close and no continuation follows
+            let mut body = vec![line];
+            let mut depth = brace_balance(line, "graphql");
+            while let Some(next) = lines.get(i) {
+                let next_trimmed = next.trim_start();
+                let continues = depth > 0
+                    || CONTINUATIONS
+                        .iter()
+                        .any(|prefix| next_trimmed.starts_with(prefix));
+                if !continues {
+                    break;
+                }
+                depth += brace_balance(next, "graphql");
+                body.push(next);
+                i += 1;
+            }
+            definitions.push(Definition {
+                comments: std::mem::take(&mut comments),
+                description: std::mem::take(&mut description),
+                body,
+            });
+        }
+    }
+    definitions
+}
+
+/// GraphQL definition with its fields, arguments and enum values. The definition's own
+/// description is kept; descriptions on its members and comments inside it are dropped.
+fn graphql_definition_summary(text: &str) -> String {
+    let text = text.trim();
+    let description_end = if let Some(rest) = text.strip_prefix("\"\"\"") {
+        rest.find("\"\"\"").map(|end| end + 6)
+    } else if text.starts_with('"') {
+        text.find('\n')
+    } else {
+        None
+    };
+    let (description, definition) = text.split_at(description_end.unwrap_or(0));
+
+    // Block string descriptions, with the whitespace after them
+    let mut body = String::new();
+    let mut rest = definition;
+    while let Some(open) = rest.find("\"\"\"") {
+        body.push_str(&rest[..open]);
+        let after = &rest[open + 3..];
+        let close = after.find("\"\"\"").map_or(after.len(), |end| end + 3);
+        rest = after[close..].trim_start();
+        // The indentation before the string now indents the member after it
+        if !body.is_empty() && !body.ends_with(char::is_whitespace) {
+            body.push(' ');
+        }
+    }
+    body.push_str(rest);
+
+    // Single-line string descriptions and comments
+    let kept: Vec<&str> = body
+        .lines()
+        .filter(|line| {
+            let line = line.trim();
+            !(line.is_empty()
+                || line.starts_with('#')
+                || (line.len() >= 2 && line.starts_with('"') && line.ends_with('"')))
+        })
+        .collect();
+    let definition = kept.join("\n");
+    if description.is_empty() {
+        definition
+    } else {
+        format!("{}\n{}", description.trim_end(), definition)
+    }
+}
+
+fn definition_summary(definition: &Definition) -> String {
+    let body = definition.body.join("\n");
+    let keyword = body.split_whitespace().next().unwrap_or_default();
+    let kept = if body.starts_with('{') || OPERATIONS.contains(&keyword) {
+        match before_in_code(&body, "{", "graphql").map(str::trim) {
+            // An anonymous query is only a selection set
+            Some("") => "{ ... }".to_string(),
+            Some(signature) => format!("{} {{ ... }}", signature),
+            None => body.clone(),
+        }
+    } else if definition.description.is_empty() {
+        graphql_definition_summary(&body)
+    } else {
+        graphql_definition_summary(&format!("{}\n{}", definition.description.join("\n"), body))
+    };
+    definition
+        .comments
+        .iter()
+        .map(|line| line.to_string())
+        .chain(std::iter::once(kept))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn graphql_outline(content: &str) -> String {
+    definitions(content)
+        .iter()
+        .map(definition_summary)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+pub fn summarize_graphql(content: &str) -> CodeSummary {
+    let original_lines = content.lines().count();
+    let outline = graphql_outline(content);
+    if outline.is_empty() {
+        return CodeSummary::unchanged(
+            content.to_string(),
+            original_lines,
+            "graphql".to_string(),
+            "no definitions",
+        );
+    }
+    CodeSummary {
+        success: true,
+        summary: format!(
+            "[COMPRESSED: Original {} lines → type definitions with their fields, operation signatures]\n\n{}",
+            original_lines, outline
+        ),
+        original_lines,
+        lang_id: "graphql".to_string(),
+        truncated: None,
+        skipped_reason: None,
+        omitted_symbols: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_definitions_spanning_lines() {
+        let schema = r#"# Search
+union SearchResult =
+  | User
+  | Order
+
+type Order
+  implements Node
+{
+  id: ID!
+  "Line items"
+  items: [Item!]!
+}
+
+mutation Checkout($cart: ID!)
+{
+  checkout(cart: $cart) { id }
+}
+"#;
+        assert_eq!(
+            graphql_outline(schema),
+            [
+                "# Search\nunion SearchResult =\n  | User\n  | Order",
+                "type Order\n  implements Node\n{\n  id: ID!\n  items: [Item!]!\n}",
+                "mutation Checkout($cart: ID!) { ... }",
+            ]
+            .join("\n\n")
+        );
+        assert!(!summarize_graphql("# nothing yet\n").success);
+    }
+}
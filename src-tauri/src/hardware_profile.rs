@@ -0,0 +1,424 @@
+// Hardware capability detection
+//
+// Local inference and embeddings need to know what the machine can run: how much
+// memory is free, which SIMD extensions the CPU has (llama.cpp-style CPU kernels want
+// AVX2 or NEON), and which GPU backends exist. Metal is assumed on macOS; CUDA is
+// detected through `nvidia-smi`, ROCm through its runtime, and Vulkan through its
+// loader library. GPUs and CPU features are probed once per run; memory is read on
+// every call. `get_hardware_profile` also suggests a backend and the largest model
+// file that should fit.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Percent of GPU memory a model may take, leaving room for the KV cache and context
+const VRAM_BUDGET_PERCENT: u64 = 80;
+/// Percent of available system memory a CPU or unified-memory model may take
+const RAM_BUDGET_PERCENT: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    Metal,
+    Cuda,
+    Rocm,
+    Vulkan,
+    Cpu,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuInfo {
+    pub arch: String,
+    pub logical_cores: usize,
+    /// SIMD extensions relevant to inference kernels, e.g. "avx2", "neon"
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub name: String,
+    pub vendor: String,
+    pub vram_bytes: Option<u64>,
+    pub driver_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareProfile {
+    pub os: String,
+    pub total_memory_bytes: u64,
+    pub available_memory_bytes: u64,
+    /// Apple Silicon: the GPU shares system memory
+    pub unified_memory: bool,
+    pub cpu: CpuInfo,
+    pub gpus: Vec<GpuInfo>,
+    pub metal: bool,
+    pub cuda: bool,
+    pub rocm: bool,
+    pub vulkan: bool,
+    pub recommended_backend: Backend,
+    /// Largest model file that should load with room to spare
+    pub recommended_max_model_bytes: u64,
+}
+
+/// The parts of the profile that do not change while the app runs
+#[derive(Debug, Clone)]
+struct StaticProfile {
+    cpu: CpuInfo,
+    gpus: Vec<GpuInfo>,
+    metal: bool,
+    cuda: bool,
+    rocm: bool,
+    vulkan: bool,
+    unified_memory: bool,
+}
+
+fn quiet_command(program: &str) -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new(program);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // Hide the console window to avoid flashing cmd.exe
+        cmd.creation_flags(0x08000000);
+    }
+    cmd
+}
+
+#[cfg(target_arch = "x86_64")]
+fn cpu_features() -> Vec<String> {
+    let mut features = Vec::new();
+    macro_rules! probe {
+        ($($name:tt),*) => {
+            $(if std::is_x86_feature_detected!($name) {
+                features.push($name.to_string());
+            })*
+        };
+    }
+    probe!("sse4.2", "avx", "avx2", "fma", "f16c", "avx512f");
+    features
+}
+
+#[cfg(target_arch = "aarch64")]
+fn cpu_features() -> Vec<String> {
+    let mut features = Vec::new();
+    macro_rules! probe {
+        ($($name:tt),*) => {
+            $(if std::arch::is_aarch64_feature_detected!($name) {
+                features.push($name.to_string());
+            })*
+        };
+    }
+    probe!("neon", "dotprod", "fp16", "i8mm", "sve");
+    features
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn cpu_features() -> Vec<String> {
+    Vec::new()
+}
+
+/// GPUs from `nvidia-smi --query-gpu=name,memory.total,driver_version --format=csv,noheader,nounits`
+fn parse_nvidia_smi(output: &str) -> Vec<GpuInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let name = fields.first().filter(|n| !n.is_empty())?;
+            Some(GpuInfo {
+                name: name.to_string(),
+                vendor: "NVIDIA".to_string(),
+                // Reported in MiB
+                vram_bytes: fields
+                    .get(1)
+                    .and_then(|m| m.parse::<u64>().ok())
+                    .map(|mib| mib * 1024 * 1024),
+                driver_version: fields
+                    .get(2)
+                    .map(|d| d.to_string())
+                    .filter(|d| !d.is_empty()),
+            })
+        })
+        .collect()
+}
+
+fn detect_nvidia() -> Vec<GpuInfo> {
+    quiet_command("nvidia-smi")
+        .args([
+            "--query-gpu=name,memory.total,driver_version",
+            "--format=csv,noheader,nounits",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+fn vendor_name(pci_vendor: &str) -> Option<&'static str> {
+    match pci_vendor.trim().to_lowercase().as_str() {
+        "0x10de" => Some("NVIDIA"),
+        "0x1002" => Some("AMD"),
+        "0x8086" => Some("Intel"),
+        _ => None,
+    }
+}
+
+/// Display adapters the kernel knows about, for GPUs no vendor tool reported
+#[cfg(target_os = "linux")]
+fn detect_drm_gpus() -> Vec<GpuInfo> {
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+    let mut gpus = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // card0, card1, ... but not connectors such as card0-HDMI-A-1
+        if !name.starts_with("card") || name.contains('-') {
+            continue;
+        }
+        let device = entry.path().join("device");
+        let Some(vendor) = std::fs::read_to_string(device.join("vendor"))
+            .ok()
+            .and_then(|v| vendor_name(&v))
+        else {
+            continue;
+        };
+        let vram_bytes = std::fs::read_to_string(device.join("mem_info_vram_total"))
+            .ok()
+            .and_then(|v| v.trim().parse().ok());
+        gpus.push(GpuInfo {
+            name: format!("{} GPU ({})", vendor, name),
+            vendor: vendor.to_string(),
+            vram_bytes,
+            driver_version: None,
+        });
+    }
+    gpus.sort_by(|a, b| a.name.cmp(&b.name));
+    gpus
+}
+
+#[cfg(not(target_os = "linux"))]
+fn detect_drm_gpus() -> Vec<GpuInfo> {
+    Vec::new()
+}
+
+fn detect_vulkan() -> bool {
+    let candidates: &[&str] = if cfg!(windows) {
+        &[r"C:\Windows\System32\vulkan-1.dll"]
+    } else if cfg!(target_os = "macos") {
+        // MoltenVK through the Vulkan SDK or Homebrew
+        &[
+            "/usr/local/lib/libvulkan.1.dylib",
+            "/opt/homebrew/lib/libvulkan.1.dylib",
+        ]
+    } else {
+        &[
+            "/usr/lib/x86_64-linux-gnu/libvulkan.so.1",
+            "/usr/lib/aarch64-linux-gnu/libvulkan.so.1",
+            "/usr/lib64/libvulkan.so.1",
+            "/usr/lib/libvulkan.so.1",
+        ]
+    };
+    candidates.iter().any(|path| Path::new(path).exists())
+}
+
+fn detect_rocm() -> bool {
+    cfg!(target_os = "linux") && Path::new("/dev/kfd").exists() && Path::new("/opt/rocm").exists()
+}
+
+fn detect_static() -> StaticProfile {
+    let mut gpus = detect_nvidia();
+    let cuda = !gpus.is_empty();
+    // NVIDIA cards are already listed with more detail
+    gpus.extend(
+        detect_drm_gpus()
+            .into_iter()
+            .filter(|gpu| !(cuda && gpu.vendor == "NVIDIA")),
+    );
+
+    let apple_silicon = cfg!(all(target_os = "macos", target_arch = "aarch64"));
+    if cfg!(target_os = "macos") {
+        gpus.push(GpuInfo {
+            name: if apple_silicon {
+                "Apple GPU".to_string()
+            } else {
+                "Metal GPU".to_string()
+            },
+            vendor: "Apple".to_string(),
+            vram_bytes: None,
+            driver_version: None,
+        });
+    }
+
+    StaticProfile {
+        cpu: CpuInfo {
+            arch: std::env::consts::ARCH.to_string(),
+            logical_cores: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            features: cpu_features(),
+        },
+        gpus,
+        metal: cfg!(target_os = "macos"),
+        cuda,
+        rocm: detect_rocm(),
+        vulkan: detect_vulkan(),
+        unified_memory: apple_silicon,
+    }
+}
+
+fn static_profile() -> &'static StaticProfile {
+    static PROFILE: OnceLock<StaticProfile> = OnceLock::new();
+    PROFILE.get_or_init(|| {
+        let profile = detect_static();
+        log::info!(
+            "Hardware: {} cores {:?}, GPUs {:?}",
+            profile.cpu.logical_cores,
+            profile.cpu.features,
+            profile.gpus.iter().map(|g| &g.name).collect::<Vec<_>>()
+        );
+        profile
+    })
+}
+
+/// Backend and model size budget: the largest dedicated GPU when there is one, unified
+/// memory on Apple Silicon, otherwise the CPU with available RAM
+fn recommend(profile: &StaticProfile, available_memory: u64) -> (Backend, u64) {
+    let ram_budget = available_memory * RAM_BUDGET_PERCENT / 100;
+    if profile.metal {
+        let budget = if profile.unified_memory {
+            ram_budget
+        } else {
+            ram_budget.min(4 * 1024 * 1024 * 1024)
+        };
+        return (Backend::Metal, budget);
+    }
+
+    let largest_vram = |vendor: &str| {
+        profile
+            .gpus
+            .iter()
+            .filter(|g| g.vendor == vendor)
+            .filter_map(|g| g.vram_bytes)
+            .max()
+    };
+    let vram_budget = |vram: u64| vram * VRAM_BUDGET_PERCENT / 100;
+    if profile.cuda {
+        if let Some(vram) = largest_vram("NVIDIA") {
+            return (Backend::Cuda, vram_budget(vram));
+        }
+    }
+    if profile.rocm {
+        if let Some(vram) = largest_vram("AMD") {
+            return (Backend::Rocm, vram_budget(vram));
+        }
+    }
+    if profile.vulkan {
+        if let Some(vram) = profile.gpus.iter().filter_map(|g| g.vram_bytes).max() {
+            return (Backend::Vulkan, vram_budget(vram));
+        }
+    }
+    (Backend::Cpu, ram_budget)
+}
+
+pub fn hardware_profile() -> HardwareProfile {
+    let profile = static_profile();
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    let total_memory_bytes = system.total_memory();
+    let available_memory_bytes = system.available_memory();
+    let (recommended_backend, recommended_max_model_bytes) =
+        recommend(profile, available_memory_bytes);
+
+    HardwareProfile {
+        os: std::env::consts::OS.to_string(),
+        total_memory_bytes,
+        available_memory_bytes,
+        unified_memory: profile.unified_memory,
+        cpu: profile.cpu.clone(),
+        gpus: profile.gpus.clone(),
+        metal: profile.metal,
+        cuda: profile.cuda,
+        rocm: profile.rocm,
+        vulkan: profile.vulkan,
+        recommended_backend,
+        recommended_max_model_bytes,
+    }
+}
+
+/// Memory, CPU features and GPU backends, with a suggested backend and model size
+#[tauri::command]
+pub async fn get_hardware_profile() -> Result<HardwareProfile, String> {
+    tauri::async_runtime::spawn_blocking(hardware_profile)
+        .await
+        .map_err(|e| format!("Hardware detection failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    fn profile(gpus: Vec<GpuInfo>) -> StaticProfile {
+        StaticProfile {
+            cpu: CpuInfo {
+                arch: "x86_64".to_string(),
+                logical_cores: 8,
+                features: vec!["avx2".to_string()],
+            },
+            cuda: gpus.iter().any(|g| g.vendor == "NVIDIA"),
+            gpus,
+            metal: false,
+            rocm: false,
+            vulkan: false,
+            unified_memory: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_nvidia_smi() {
+        let gpus = parse_nvidia_smi(
+            "NVIDIA GeForce RTX 4090, 24564, 550.54.14\nTesla T4, 15360, 535.1\n\n",
+        );
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 4090");
+        assert_eq!(gpus[0].vram_bytes, Some(24564 * 1024 * 1024));
+        assert_eq!(gpus[1].driver_version.as_deref(), Some("535.1"));
+        assert!(parse_nvidia_smi("").is_empty());
+    }
+
+    #[test]
+    fn test_recommend() {
+        let cpu_only = profile(Vec::new());
+        assert_eq!(recommend(&cpu_only, 10 * GIB), (Backend::Cpu, 6 * GIB));
+
+        let nvidia = profile(vec![GpuInfo {
+            name: "RTX".to_string(),
+            vendor: "NVIDIA".to_string(),
+            vram_bytes: Some(10 * GIB),
+            driver_version: None,
+        }]);
+        assert_eq!(recommend(&nvidia, GIB), (Backend::Cuda, 8 * GIB));
+
+        let mut apple = profile(Vec::new());
+        apple.metal = true;
+        apple.unified_memory = true;
+        assert_eq!(recommend(&apple, 20 * GIB), (Backend::Metal, 12 * GIB));
+    }
+
+    #[test]
+    fn test_vendor_name() {
+        assert_eq!(vendor_name("0x10de\n"), Some("NVIDIA"));
+        assert_eq!(vendor_name("0x1AF4"), None);
+    }
+
+    #[test]
+    fn test_hardware_profile() {
+        let profile = hardware_profile();
+        assert!(profile.cpu.logical_cores >= 1);
+        assert!(profile.total_memory_bytes >= profile.available_memory_bytes);
+    }
+}
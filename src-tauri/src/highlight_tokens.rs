@@ -0,0 +1,381 @@
+// Syntax highlighting tokens
+//
+// The frontend's code views each ran their own JS highlighter, which disagreed with the
+// backend about what a file even is (Elixir, OCaml and PowerShell were plain text). The
+// grammars bundled for navigation can highlight every supported language instead. The
+// `highlights.scm` files shipped with the grammar crates use different capture names
+// per grammar and some grammars ship none, so tokens are classified from node kinds and
+// their position in the tree into one small scope set the frontend styles once.
+//
+// Tokens never overlap and never span lines: a block comment or a multi-line string
+// becomes one token per line, which is what editor decoration APIs expect.
+
+use crate::code_navigation::{get_language, CodeNavigationService};
+use crate::position_encoding::{LineIndex, Position, PositionEncoding};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tree_sitter::{Language, Node, Parser, TreeCursor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightScope {
+    Comment,
+    String,
+    Number,
+    /// `true`, `nil`, and SCREAMING_CASE names
+    Constant,
+    Keyword,
+    /// Names of defined and called functions and methods
+    Function,
+    /// Type names, including the names of type definitions
+    Type,
+    /// Fields, properties and mapping keys
+    Property,
+    /// HTML and CSS tag names
+    Tag,
+    Attribute,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighlightToken {
+    pub scope: HighlightScope,
+    pub line: u32,
+    pub start_column: u32,
+    /// Exclusive
+    pub end_column: u32,
+}
+
+const CONSTANT_KINDS: &[&str] = &[
+    "true",
+    "false",
+    "null",
+    "nil",
+    "none",
+    "None",
+    "True",
+    "False",
+    "boolean",
+    "boolean_literal",
+    "null_literal",
+    "nil_literal",
+    "unit",
+];
+
+fn is_string(kind: &str) -> bool {
+    kind.contains("string")
+        || kind.contains("char_literal")
+        || kind.contains("character_literal")
+        || kind.contains("heredoc")
+        || kind.ends_with("quote_scalar")
+        || kind.contains("attribute_value")
+        || kind == "block_scalar"
+}
+
+fn is_number(kind: &str) -> bool {
+    !kind.contains("type")
+        && (kind.contains("integer")
+            || kind.contains("float")
+            || kind.contains("number")
+            || matches!(kind, "int_literal" | "imaginary_literal" | "decimal"))
+}
+
+/// Anonymous tokens spelled like words are the grammar's keywords
+fn is_keyword(node: Node) -> bool {
+    let kind = node.kind();
+    !node.is_named()
+        && kind.len() > 1
+        && kind.starts_with(|c: char| c.is_ascii_alphabetic())
+        && kind.chars().all(|c| c.is_ascii_alphabetic() || c == '_')
+}
+
+fn is_definition_of(parent: &str, kinds: &[&str]) -> bool {
+    kinds.iter().any(|k| parent.contains(k))
+}
+
+/// Whether `field` of `parent` is the callee of a call: `f()`, `obj.f()`, `pkg.F()`
+fn is_callee(node: Node, field: Option<&str>) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    if field == Some("function") && parent.kind().contains("call") {
+        return true;
+    }
+    // The member part of a member expression that is itself the callee
+    matches!(field, Some("field" | "property" | "attribute"))
+        && parent.parent().is_some_and(|call| {
+            call.kind().contains("call") && call.child_by_field_name("function") == Some(parent)
+        })
+}
+
+/// Keys of `key: value` pairs in object literals, YAML mappings and hashes
+fn is_key(node: Node, field: Option<&str>) -> bool {
+    field == Some("key") && node.parent().is_some_and(|p| p.kind().contains("pair"))
+}
+
+fn classify_name(node: Node, field: Option<&str>, source: &str) -> Option<HighlightScope> {
+    let kind = node.kind();
+    let parent = node.parent().map(|p| p.kind()).unwrap_or("");
+    if kind.contains("type") {
+        return Some(HighlightScope::Type);
+    }
+    if is_callee(node, field) {
+        return Some(HighlightScope::Function);
+    }
+    if matches!(field, Some("name")) {
+        if is_definition_of(parent, &["function", "method", "constructor", "invocation"]) {
+            return Some(HighlightScope::Function);
+        }
+        if is_definition_of(
+            parent,
+            &[
+                "class",
+                "struct",
+                "interface",
+                "enum",
+                "trait",
+                "type",
+                "module",
+            ],
+        ) {
+            return Some(HighlightScope::Type);
+        }
+    }
+    if kind.contains("field") || kind.contains("property") {
+        return Some(HighlightScope::Property);
+    }
+    match kind {
+        "tag_name" => return Some(HighlightScope::Tag),
+        "attribute_name" => return Some(HighlightScope::Attribute),
+        _ => {}
+    }
+    let text = &source[node.byte_range()];
+    let screaming = text.len() > 1
+        && text.chars().any(|c| c.is_ascii_uppercase())
+        && text
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    if screaming || kind == "constant" {
+        return Some(HighlightScope::Constant);
+    }
+    None
+}
+
+/// Scope of the node under `cursor`, and whether its children are covered by it
+fn classify(cursor: &TreeCursor, source: &str) -> (Option<HighlightScope>, bool) {
+    let node = cursor.node();
+    let kind = node.kind();
+    let field = cursor.field_name();
+
+    if is_key(node, field) {
+        return (Some(HighlightScope::Property), true);
+    }
+    if kind.contains("comment") {
+        return (Some(HighlightScope::Comment), true);
+    }
+    if is_string(kind) {
+        return (Some(HighlightScope::String), true);
+    }
+    if CONSTANT_KINDS.contains(&kind) {
+        return (Some(HighlightScope::Constant), true);
+    }
+    if node.is_named() && is_number(kind) {
+        return (Some(HighlightScope::Number), true);
+    }
+    if is_keyword(node) {
+        return (Some(HighlightScope::Keyword), true);
+    }
+    if node.is_named() && node.child_count() == 0 {
+        return (classify_name(node, field, source), true);
+    }
+    (None, false)
+}
+
+/// Grammars used for navigation, plus the markup and config ones only the outline
+/// summarizers parse
+fn language(lang_id: &str) -> Option<Language> {
+    get_language(lang_id).or_else(|| match lang_id {
+        "html" => Some(tree_sitter_html::LANGUAGE.into()),
+        "css" => Some(tree_sitter_css::LANGUAGE.into()),
+        "scss" => Some(tree_sitter_scss::LANGUAGE.into()),
+        "yaml" => Some(tree_sitter_yaml::LANGUAGE.into()),
+        "toml" => Some(tree_sitter_toml_ng::LANGUAGE.into()),
+        _ => None,
+    })
+}
+
+/// Split a node's range into one token per line
+fn push_token(tokens: &mut Vec<HighlightToken>, scope: HighlightScope, node: Node, lines: &[&str]) {
+    let start = node.start_position();
+    let end = node.end_position();
+    for row in start.row..=end.row {
+        let from = if row == start.row { start.column } else { 0 };
+        let to = if row == end.row {
+            end.column
+        } else {
+            lines.get(row).map_or(0, |l| l.trim_end_matches('\r').len())
+        };
+        if to > from {
+            tokens.push(HighlightToken {
+                scope,
+                line: row as u32 + 1,
+                start_column: from as u32 + 1,
+                end_column: to as u32 + 1,
+            });
+        }
+    }
+}
+
+pub fn highlight(content: &str, lang_id: &str) -> Result<Vec<HighlightToken>, String> {
+    let language = language(lang_id).ok_or_else(|| format!("Unsupported language: {}", lang_id))?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| format!("Failed to set language for {}: {:?}", lang_id, e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| format!("Failed to parse {} content", lang_id))?;
+
+    let lines: Vec<&str> = content.split('\n').collect();
+    let mut tokens = Vec::new();
+    let mut cursor = tree.walk();
+    'walk: loop {
+        let (scope, covered) = classify(&cursor, content);
+        if let Some(scope) = scope {
+            push_token(&mut tokens, scope, cursor.node(), &lines);
+        }
+        if !covered && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Highlight tokens for `content`, or for the file at `file_path` when no content is
+/// given. The language defaults to the one of the file's extension.
+#[tauri::command]
+pub fn get_highlight_tokens(
+    file_path: Option<String>,
+    content: Option<String>,
+    lang_id: Option<String>,
+    encoding: Option<PositionEncoding>,
+) -> Result<Vec<HighlightToken>, String> {
+    let content = match (content, &file_path) {
+        (Some(content), _) => content,
+        (None, Some(path)) => {
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?
+        }
+        (None, None) => return Err("Either file_path or content is required".to_string()),
+    };
+    let lang_id = lang_id
+        .or_else(|| {
+            file_path
+                .as_deref()
+                .and_then(CodeNavigationService::get_lang_id_from_path)
+        })
+        .ok_or("Could not determine the language")?;
+
+    let mut tokens = highlight(&content, &lang_id)?;
+    if encoding == Some(PositionEncoding::Utf16) {
+        let index = LineIndex::new(&content);
+        for token in &mut tokens {
+            for column in [&mut token.start_column, &mut token.end_column] {
+                *column = index
+                    .to_utf16(Position {
+                        line: token.line,
+                        column: *column,
+                    })
+                    .column;
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(content: &str, lang_id: &str) -> Vec<(HighlightScope, String)> {
+        let lines: Vec<&str> = content.lines().collect();
+        highlight(content, lang_id)
+            .unwrap()
+            .into_iter()
+            .map(|t| {
+                let line = lines[t.line as usize - 1];
+                let text = &line[t.start_column as usize - 1..t.end_column as usize - 1];
+                (t.scope, text.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_tokens() {
+        let code = "/* a\n   b */\nfn add(p: Point) -> u32 {\n    let s = \"hi\";\n    p.x.max(MAX_X) + 1\n}\n";
+        let tokens = scopes(code, "rust");
+        for expected in [
+            (HighlightScope::Comment, "/* a"),
+            (HighlightScope::Comment, "   b */"),
+            (HighlightScope::Keyword, "fn"),
+            (HighlightScope::Function, "add"),
+            (HighlightScope::Type, "Point"),
+            (HighlightScope::Type, "u32"),
+            (HighlightScope::Keyword, "let"),
+            (HighlightScope::String, "\"hi\""),
+            (HighlightScope::Property, "x"),
+            (HighlightScope::Function, "max"),
+            (HighlightScope::Constant, "MAX_X"),
+            (HighlightScope::Number, "1"),
+        ] {
+            assert!(
+                tokens.contains(&(expected.0, expected.1.to_string())),
+                "missing {:?} in {:?}",
+                expected,
+                tokens
+            );
+        }
+        assert!(!tokens.iter().any(|(_, text)| text == "s" || text == "p"));
+    }
+
+    #[test]
+    fn test_markup_tokens() {
+        let tokens = scopes("<a href=\"/\">hi</a>\n", "html");
+        assert!(tokens.contains(&(HighlightScope::Tag, "a".to_string())));
+        assert!(tokens.contains(&(HighlightScope::Attribute, "href".to_string())));
+        assert!(tokens.contains(&(HighlightScope::String, "\"/\"".to_string())));
+
+        let tokens = scopes("name: web\nreplicas: 3\n", "yaml");
+        assert!(tokens.contains(&(HighlightScope::Property, "replicas".to_string())));
+        assert!(tokens.contains(&(HighlightScope::Number, "3".to_string())));
+        assert!(tokens.contains(&(HighlightScope::String, "web".to_string())));
+    }
+
+    #[test]
+    fn test_python_tokens_with_utf16_columns() {
+        let tokens = get_highlight_tokens(
+            None,
+            Some("x = \"é\"; len(True)  # 🎉\n".to_string()),
+            Some("python".to_string()),
+            Some(PositionEncoding::Utf16),
+        )
+        .unwrap();
+        let find = |scope| tokens.iter().find(|t| t.scope == scope).unwrap();
+        assert_eq!(
+            (
+                find(HighlightScope::String).start_column,
+                find(HighlightScope::String).end_column
+            ),
+            (5, 8)
+        );
+        assert_eq!(find(HighlightScope::Function).start_column, 10);
+        assert_eq!(find(HighlightScope::Constant).start_column, 14);
+        let comment = find(HighlightScope::Comment);
+        assert_eq!((comment.start_column, comment.end_column), (21, 25));
+
+        assert!(get_highlight_tokens(None, None, None, None).is_err());
+    }
+}
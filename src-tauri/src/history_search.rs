@@ -0,0 +1,487 @@
+// Full-text search across past sessions
+// Maintains an FTS5 index over the `messages` table (kept in sync with triggers)
+// and exposes ranked, highlighted search results grouped by conversation. The index
+// is created by the database migrations (`migrate_history_index`, run from
+// turso-database-init.ts once the `messages` table exists), not on each search.
+
+use crate::database::Database;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tauri::State;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// FTS snippet brackets around matched terms. Chat messages carry no control
+/// characters, so these mark matches only and the rest of the text can be escaped.
+const MATCH_START: &str = "\u{2}";
+const MATCH_END: &str = "\u{3}";
+
+const FTS_SETUP: &[&str] = &[
+    "CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+        content,
+        message_id UNINDEXED,
+        conversation_id UNINDEXED,
+        role UNINDEXED,
+        tokenize = 'unicode61 remove_diacritics 2'
+    )",
+    "CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN
+        INSERT INTO messages_fts (content, message_id, conversation_id, role)
+        VALUES (new.content, new.id, new.conversation_id, new.role);
+    END",
+    "CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+        DELETE FROM messages_fts WHERE message_id = old.id;
+    END",
+    "CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE OF content ON messages BEGIN
+        UPDATE messages_fts SET content = new.content WHERE message_id = old.id;
+    END",
+];
+
+/// The triggers write into `messages_fts`, so they must go before the table does
+const FTS_TEARDOWN: &[&str] = &[
+    "DROP TRIGGER IF EXISTS messages_fts_insert",
+    "DROP TRIGGER IF EXISTS messages_fts_delete",
+    "DROP TRIGGER IF EXISTS messages_fts_update",
+    "DROP TABLE IF EXISTS messages_fts",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistorySearchFilters {
+    pub project_id: Option<String>,
+    pub conversation_id: Option<String>,
+    pub role: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageMatch {
+    pub message_id: String,
+    pub conversation_id: String,
+    pub role: String,
+    pub timestamp: i64,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMatch {
+    pub conversation_id: String,
+    pub title: String,
+    pub project_id: String,
+    pub match_count: usize,
+    pub best_rank: f64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistorySearchResult {
+    pub query: String,
+    pub sessions: Vec<SessionMatch>,
+    pub messages: Vec<MessageMatch>,
+}
+
+/// Create the FTS table and triggers, and backfill existing messages the first time
+pub async fn ensure_fts_index(db: &Database) -> Result<(), String> {
+    for sql in FTS_SETUP {
+        db.execute(sql, vec![]).await?;
+    }
+
+    let indexed = db
+        .query("SELECT COUNT(*) AS count FROM messages_fts", vec![])
+        .await?;
+    let count = indexed
+        .rows
+        .first()
+        .and_then(|r| r.get("count"))
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
+
+    if count == 0 {
+        let result = db
+            .execute(
+                "INSERT INTO messages_fts (content, message_id, conversation_id, role)
+                 SELECT content, id, conversation_id, role FROM messages",
+                vec![],
+            )
+            .await?;
+        if result.rows_affected > 0 {
+            log::info!(
+                "Backfilled FTS index with {} messages",
+                result.rows_affected
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Drop the index with its triggers and build it again from the `messages` table
+pub async fn rebuild_fts_index(db: &Database) -> Result<(), String> {
+    for sql in FTS_TEARDOWN {
+        db.execute(sql, vec![]).await?;
+    }
+    ensure_fts_index(db).await
+}
+
+/// Convert free-form user input into a safe FTS5 MATCH expression.
+/// Each term is quoted so punctuation can't produce syntax errors; a trailing `*`
+/// is kept as a prefix match and `"quoted phrases"` are preserved.
+pub fn build_fts_query(input: &str) -> Option<String> {
+    let mut terms = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '"' {
+            chars.next();
+            let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+            let phrase = phrase.trim();
+            if !phrase.is_empty() {
+                terms.push(format!("\"{}\"", phrase.replace('"', "")));
+            }
+            continue;
+        }
+
+        let mut word = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() || c == '"' {
+                break;
+            }
+            word.push(c);
+            chars.next();
+        }
+        let prefix = word.ends_with('*');
+        let cleaned = word.trim_end_matches('*').replace('"', "");
+        if cleaned.is_empty() {
+            continue;
+        }
+        if prefix {
+            terms.push(format!("\"{}\"*", cleaned));
+        } else {
+            terms.push(format!("\"{}\"", cleaned));
+        }
+    }
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+fn str_field(row: &Value, key: &str) -> String {
+    row.get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn int_field(row: &Value, key: &str) -> i64 {
+    row.get(key).and_then(|v| v.as_i64()).unwrap_or(0)
+}
+
+fn float_field(row: &Value, key: &str) -> f64 {
+    row.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0)
+}
+
+/// An FTS snippet as HTML: message text escaped, matched terms wrapped in `<mark>`
+fn snippet_html(snippet: &str) -> String {
+    snippet
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
+/// Search message history, returning matching messages and the sessions they belong to
+pub async fn search_messages(
+    db: &Database,
+    query: &str,
+    filters: &HistorySearchFilters,
+) -> Result<HistorySearchResult, String> {
+    let Some(fts_query) = build_fts_query(query) else {
+        return Ok(HistorySearchResult {
+            query: query.to_string(),
+            sessions: Vec::new(),
+            messages: Vec::new(),
+        });
+    };
+
+    let limit = filters.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let mut sql = String::from(
+        "SELECT f.message_id AS message_id, f.conversation_id AS conversation_id, f.role AS role,
+                m.timestamp AS timestamp,
+                snippet(f, 0, ?, ?, '…', 16) AS snippet,
+                bm25(f) AS score,
+                c.title AS title, c.project_id AS project_id, c.updated_at AS updated_at
+         FROM messages_fts f
+         JOIN messages m ON m.id = f.message_id
+         JOIN conversations c ON c.id = f.conversation_id
+         WHERE f MATCH ?",
+    );
+    let mut params = vec![
+        Value::from(MATCH_START),
+        Value::from(MATCH_END),
+        Value::from(fts_query),
+    ];
+
+    if let Some(project_id) = &filters.project_id {
+        sql.push_str(" AND c.project_id = ?");
+        params.push(Value::from(project_id.as_str()));
+    }
+    if let Some(conversation_id) = &filters.conversation_id {
+        sql.push_str(" AND f.conversation_id = ?");
+        params.push(Value::from(conversation_id.as_str()));
+    }
+    if let Some(role) = &filters.role {
+        sql.push_str(" AND f.role = ?");
+        params.push(Value::from(role.as_str()));
+    }
+    if let Some(since) = filters.since {
+        sql.push_str(" AND m.timestamp >= ?");
+        params.push(Value::from(since));
+    }
+    if let Some(until) = filters.until {
+        sql.push_str(" AND m.timestamp <= ?");
+        params.push(Value::from(until));
+    }
+    sql.push_str(&format!(" ORDER BY score ASC LIMIT {}", limit));
+
+    let rows = db
+        .query(&sql, params)
+        .await
+        .map_err(|e| {
+            if e.contains("no such table") {
+                "History index is missing; run the database migrations".to_string()
+            } else {
+                e
+            }
+        })?
+        .rows;
+
+    let mut messages = Vec::with_capacity(rows.len());
+    let mut sessions: Vec<SessionMatch> = Vec::new();
+
+    for row in &rows {
+        let conversation_id = str_field(row, "conversation_id");
+        // bm25 is lower-is-better; rows arrive sorted so the first hit is the session's best
+        let rank = float_field(row, "score");
+
+        match sessions
+            .iter_mut()
+            .find(|s| s.conversation_id == conversation_id)
+        {
+            Some(session) => session.match_count += 1,
+            None => sessions.push(SessionMatch {
+                conversation_id: conversation_id.clone(),
+                title: str_field(row, "title"),
+                project_id: str_field(row, "project_id"),
+                match_count: 1,
+                best_rank: rank,
+                updated_at: int_field(row, "updated_at"),
+            }),
+        }
+
+        messages.push(MessageMatch {
+            message_id: str_field(row, "message_id"),
+            conversation_id,
+            role: str_field(row, "role"),
+            timestamp: int_field(row, "timestamp"),
+            snippet: snippet_html(&str_field(row, "snippet")),
+            rank,
+        });
+    }
+
+    Ok(HistorySearchResult {
+        query: query.to_string(),
+        sessions,
+        messages,
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn search_history(
+    db: State<'_, Arc<Database>>,
+    query: String,
+    filters: Option<HistorySearchFilters>,
+) -> Result<HistorySearchResult, String> {
+    search_messages(&db, &query, &filters.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn migrate_history_index(db: State<'_, Arc<Database>>) -> Result<(), String> {
+    ensure_fts_index(&db).await
+}
+
+#[tauri::command]
+pub async fn rebuild_history_index(db: State<'_, Arc<Database>>) -> Result<(), String> {
+    rebuild_fts_index(&db).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn setup_db(temp_dir: &TempDir) -> Database {
+        let db_path = temp_dir.path().join("test.db");
+        let db = Database::new(db_path.to_string_lossy().to_string());
+        db.connect().await.unwrap();
+        db.execute(
+            "CREATE TABLE conversations (id TEXT PRIMARY KEY, title TEXT NOT NULL, project_id TEXT NOT NULL DEFAULT 'default', created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        db.execute(
+            "CREATE TABLE messages (id TEXT PRIMARY KEY, conversation_id TEXT NOT NULL, role TEXT NOT NULL, content TEXT NOT NULL, timestamp INTEGER NOT NULL, assistant_id TEXT, position_index INTEGER DEFAULT 0)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    async fn insert_message(db: &Database, id: &str, conversation_id: &str, content: &str) {
+        db.execute(
+            "INSERT INTO messages (id, conversation_id, role, content, timestamp) VALUES (?, ?, 'user', ?, 1)",
+            vec![json!(id), json!(conversation_id), json!(content)],
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_build_fts_query() {
+        assert_eq!(build_fts_query("auth bug").unwrap(), "\"auth\" \"bug\"");
+        assert_eq!(build_fts_query("auth*").unwrap(), "\"auth\"*");
+        assert_eq!(
+            build_fts_query("\"token refresh\" jwt").unwrap(),
+            "\"token refresh\" \"jwt\""
+        );
+        assert_eq!(build_fts_query("a-b:c()").unwrap(), "\"a-b:c()\"");
+        assert!(build_fts_query("   ").is_none());
+        assert!(build_fts_query("\"\" *").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_history_groups_by_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = setup_db(&temp_dir).await;
+
+        db.execute(
+            "INSERT INTO conversations (id, title, created_at, updated_at) VALUES ('c1', 'Fix auth', 1, 1), ('c2', 'Styling', 1, 2)",
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        // Messages inserted before the index exists are backfilled
+        insert_message(&db, "m1", "c1", "The auth middleware rejects valid tokens").await;
+        ensure_fts_index(&db).await.unwrap();
+        // Messages inserted afterwards are picked up by the trigger
+        insert_message(&db, "m2", "c1", "Fixed the auth bug by refreshing tokens").await;
+        insert_message(&db, "m3", "c2", "Update button colors").await;
+
+        let result = search_messages(&db, "auth", &HistorySearchFilters::default())
+            .await
+            .unwrap();
+        assert_eq!(result.messages.len(), 2);
+        assert_eq!(result.sessions.len(), 1);
+        assert_eq!(result.sessions[0].conversation_id, "c1");
+        assert_eq!(result.sessions[0].match_count, 2);
+        assert!(result.messages[0].snippet.contains("<mark>auth</mark>"));
+
+        // Message text is escaped; only the match markers are markup
+        insert_message(
+            &db,
+            "m4",
+            "c2",
+            "Use <b>bold</b> & <img onerror=x> for banners",
+        )
+        .await;
+        let result = search_messages(&db, "banners", &HistorySearchFilters::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            result.messages[0].snippet,
+            "Use &lt;b&gt;bold&lt;/b&gt; &amp; &lt;img onerror=x&gt; for <mark>banners</mark>"
+        );
+
+        // Deleted messages disappear from the index
+        db.execute("DELETE FROM messages WHERE id = 'm1'", vec![])
+            .await
+            .unwrap();
+        let result = search_messages(&db, "auth", &HistorySearchFilters::default())
+            .await
+            .unwrap();
+        assert_eq!(result.messages.len(), 1);
+        assert_eq!(result.messages[0].message_id, "m2");
+    }
+
+    #[tokio::test]
+    async fn test_search_history_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = setup_db(&temp_dir).await;
+        db.execute(
+            "INSERT INTO conversations (id, title, project_id, created_at, updated_at) VALUES ('c1', 'a', 'p1', 1, 1), ('c2', 'b', 'p2', 1, 1)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        insert_message(&db, "m1", "c1", "database migration").await;
+        insert_message(&db, "m2", "c2", "database schema").await;
+        ensure_fts_index(&db).await.unwrap();
+
+        let filters = HistorySearchFilters {
+            project_id: Some("p2".to_string()),
+            ..Default::default()
+        };
+        let result = search_messages(&db, "database", &filters).await.unwrap();
+        assert_eq!(result.messages.len(), 1);
+        assert_eq!(result.messages[0].conversation_id, "c2");
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_keeps_triggers_working() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = setup_db(&temp_dir).await;
+        db.execute(
+            "INSERT INTO conversations (id, title, created_at, updated_at) VALUES ('c1', 'a', 1, 1)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        insert_message(&db, "m1", "c1", "flaky websocket reconnect").await;
+        ensure_fts_index(&db).await.unwrap();
+
+        rebuild_fts_index(&db).await.unwrap();
+        // Inserts after a rebuild must not hit a trigger whose table is gone
+        insert_message(&db, "m2", "c1", "websocket heartbeat").await;
+
+        let result = search_messages(&db, "websocket", &HistorySearchFilters::default())
+            .await
+            .unwrap();
+        assert_eq!(result.messages.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_without_index_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = setup_db(&temp_dir).await;
+        let err = search_messages(&db, "auth", &HistorySearchFilters::default())
+            .await
+            .unwrap_err();
+        assert!(err.contains("migrations"), "{}", err);
+    }
+}
@@ -0,0 +1,272 @@
+// HTML structural summaries
+//
+// Email bodies and server templates run to thousands of lines of nested tables and
+// inline styles that say little about the page. The summary is an outline of the
+// element tree: one line per element written as a selector (`div#main.card`), the
+// element's own text collapsed to a short quote, runs of identical siblings (table
+// rows, list items) folded into a count, and `<script>`, `<style>` and `<template>`
+// kept as landmarks.
+
+use crate::code_navigation::CodeSummary;
+use tree_sitter::{Node, Parser};
+
+/// Elements nested deeper than this are elided
+pub const HTML_MAX_DEPTH: usize = 12;
+/// Collapsed text is cut to this many characters
+const MAX_TEXT_CHARS: usize = 60;
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+fn named_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor).collect()
+}
+
+/// The `start_tag` or `self_closing_tag` of an element
+fn start_tag(element: Node) -> Option<Node> {
+    named_children(element)
+        .into_iter()
+        .find(|n| matches!(n.kind(), "start_tag" | "self_closing_tag"))
+}
+
+fn attribute_value<'a>(tag: Node, source: &'a str, name: &str) -> Option<&'a str> {
+    named_children(tag)
+        .into_iter()
+        .filter(|n| n.kind() == "attribute")
+        .find_map(|attr| {
+            let children = named_children(attr);
+            let attr_name = children.iter().find(|n| n.kind() == "attribute_name")?;
+            if !text(*attr_name, source).eq_ignore_ascii_case(name) {
+                return None;
+            }
+            let value = children.iter().find(|n| n.kind() != "attribute_name")?;
+            Some(text(*value, source).trim_matches(|c| c == '"' || c == '\''))
+        })
+}
+
+/// `tag#id.class.class`
+fn selector(element: Node, source: &str) -> String {
+    let Some(tag) = start_tag(element) else {
+        return element.kind().to_string();
+    };
+    let name = named_children(tag)
+        .into_iter()
+        .find(|n| n.kind() == "tag_name")
+        .map(|n| text(n, source).to_ascii_lowercase())
+        .unwrap_or_default();
+    let mut selector = name;
+    if let Some(id) = attribute_value(tag, source, "id").filter(|id| !id.is_empty()) {
+        selector.push('#');
+        selector.push_str(id);
+    }
+    if let Some(classes) = attribute_value(tag, source, "class") {
+        for class in classes.split_whitespace() {
+            selector.push('.');
+            selector.push_str(class);
+        }
+    }
+    selector
+}
+
+/// The element's own text, whitespace collapsed and cut to MAX_TEXT_CHARS
+fn own_text(element: Node, source: &str) -> Option<String> {
+    let joined = named_children(element)
+        .into_iter()
+        .filter(|n| n.kind() == "text")
+        .map(|n| text(n, source))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let collapsed = joined.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+    if collapsed.chars().count() > MAX_TEXT_CHARS {
+        let cut: String = collapsed.chars().take(MAX_TEXT_CHARS).collect();
+        Some(format!("\"{}…\"", cut.trim_end()))
+    } else {
+        Some(format!("\"{}\"", collapsed))
+    }
+}
+
+fn is_element(node: &Node) -> bool {
+    matches!(node.kind(), "element" | "script_element" | "style_element")
+}
+
+fn outline(node: Node, source: &str, depth: usize, lines: &mut Vec<String>) {
+    let children: Vec<Node> = named_children(node)
+        .into_iter()
+        .filter(is_element)
+        .collect();
+    let indent = "  ".repeat(depth);
+    if depth > HTML_MAX_DEPTH {
+        if !children.is_empty() {
+            lines.push(format!("{}...", indent));
+        }
+        return;
+    }
+
+    let mut i = 0;
+    while i < children.len() {
+        let child = children[i];
+        let child_selector = selector(child, source);
+        // Identical siblings are shown once
+        let repeats = children[i + 1..]
+            .iter()
+            .take_while(|c| c.kind() == child.kind() && selector(**c, source) == child_selector)
+            .count();
+
+        match child.kind() {
+            "script_element" | "style_element" => {
+                let body_lines = named_children(child)
+                    .into_iter()
+                    .find(|n| n.kind() == "raw_text")
+                    .map_or(0, |raw| text(raw, source).trim().lines().count());
+                let src = start_tag(child).and_then(|tag| attribute_value(tag, source, "src"));
+                let detail = match (src, body_lines) {
+                    (Some(src), _) => format!(" src=\"{}\"", src),
+                    (None, 0) => String::new(),
+                    (None, n) => format!(" ({} lines)", n),
+                };
+                lines.push(format!("{}<{}>{}", indent, child_selector, detail));
+            }
+            _ => {
+                let mut line = format!("{}<{}>", indent, child_selector);
+                if let Some(own) = own_text(child, source) {
+                    line.push(' ');
+                    line.push_str(&own);
+                }
+                lines.push(line);
+                outline(child, source, depth + 1, lines);
+            }
+        }
+        if repeats > 0 {
+            lines.push(format!(
+                "{}... {} more <{}>",
+                indent, repeats, child_selector
+            ));
+        }
+        i += repeats + 1;
+    }
+}
+
+/// Outline of an HTML document
+pub fn html_outline(content: &str) -> Result<String, String> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_html::LANGUAGE.into())
+        .map_err(|e| format!("Failed to set language for html: {:?}", e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| "Failed to parse html content".to_string())?;
+    let mut lines = Vec::new();
+    outline(tree.root_node(), content, 0, &mut lines);
+    Ok(lines.join("\n"))
+}
+
+pub fn summarize_html(content: &str) -> Result<CodeSummary, String> {
+    let original_lines = content.lines().count();
+    let outline = html_outline(content)?;
+    if outline.is_empty() {
+        return Ok(CodeSummary::unchanged(
+            content.to_string(),
+            original_lines,
+            "html".to_string(),
+            "no elements",
+        ));
+    }
+    Ok(CodeSummary {
+        success: true,
+        summary: format!(
+            "[COMPRESSED: Original {} lines → HTML outline, text collapsed]\n\n{}",
+            original_lines, outline
+        ),
+        original_lines,
+        lang_id: "html".to_string(),
+        truncated: None,
+        skipped_reason: None,
+        omitted_symbols: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Order   confirmation</title>
+  <style>
+    body { font-family: sans-serif; }
+    .total { font-weight: bold; }
+  </style>
+  <script src="/analytics.js"></script>
+</head>
+<body>
+  <!-- header -->
+  <div id="main" class="container wide">
+    <h1>Thanks for your order, we are getting it ready and will email you when it ships</h1>
+    <table class="items">
+      <tr class="item"><td>Socks</td><td>2</td></tr>
+      <tr class="item"><td>Hat</td><td>1</td></tr>
+      <tr class="item"><td>Scarf</td><td>1</td></tr>
+      <tr class="total"><td>Total</td><td>4</td></tr>
+    </table>
+    <template id="row"><tr class="item"><td></td></tr></template>
+    <br/>
+  </div>
+  <script>
+    track('order');
+    render();
+  </script>
+</body>
+</html>
+"#;
+
+    #[test]
+    fn test_html_outline() {
+        let outline = html_outline(PAGE).unwrap();
+        assert_eq!(
+            outline,
+            [
+                "<html>",
+                "  <head>",
+                "    <title> \"Order confirmation\"",
+                "    <style> (2 lines)",
+                "    <script> src=\"/analytics.js\"",
+                "  <body>",
+                "    <div#main.container.wide>",
+                "      <h1> \"Thanks for your order, we are getting it ready and will emai…\"",
+                "      <table.items>",
+                "        <tr.item>",
+                "          <td> \"Socks\"",
+                "          <td> \"2\"",
+                "        ... 2 more <tr.item>",
+                "        <tr.total>",
+                "          <td> \"Total\"",
+                "          <td> \"4\"",
+                "      <template#row>",
+                "        <tr.item>",
+                "          <td>",
+                "      <br>",
+                "    <script> (2 lines)",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_summarize_html() {
+        let result = summarize_html(PAGE).unwrap();
+        assert!(result.success);
+        assert!(result
+            .summary
+            .starts_with("[COMPRESSED: Original 29 lines → HTML outline"));
+        assert!(!result.summary.contains("font-family"));
+
+        let empty = summarize_html("just text\n").unwrap();
+        assert!(!empty.success);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::http_client;
+use crate::offline_mode;
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -156,8 +158,9 @@ pub async fn proxy_fetch(request: ProxyRequest) -> Result<ProxyResponse, String>
 
     // Validate URL to prevent SSRF attacks
     validate_url(&request.url)?;
+    offline_mode::check_url(&request.url, "fetch")?;
 
-    let client = reqwest::Client::new();
+    let client = http_client::client()?;
 
     // Build the request
     let mut req_builder = match request.method.to_uppercase().as_str() {
@@ -258,8 +261,9 @@ pub async fn proxy_fetch_stream(request: ProxyRequest) -> Result<ProxyResponse,
 
     // Validate URL to prevent SSRF attacks
     validate_url(&request.url)?;
+    offline_mode::check_url(&request.url, "fetch")?;
 
-    let client = reqwest::Client::new();
+    let client = http_client::client()?;
 
     // Build the request
     let mut req_builder = match request.method.to_uppercase().as_str() {
@@ -406,8 +410,9 @@ pub async fn stream_fetch(
 
     // Validate URL to prevent SSRF attacks
     validate_url(&request.url)?;
+    offline_mode::check_url(&request.url, "fetch")?;
 
-    let client = reqwest::Client::builder()
+    let client = http_client::builder()?
         .connect_timeout(Duration::from_secs(10))
         .build()
         .map_err(|e| format!("Failed to build client: {}", e))?;
@@ -0,0 +1,769 @@
+// Import insertion
+//
+// Agent patches often fail on imports: a second `import { a } from './x'` next to an
+// existing one, a `use` inserted in the middle of a function, a duplicate that breaks
+// the build. `ensure_import` parses the file, finds its import block and either reports
+// the symbol as already imported, extends the statement that imports from the same
+// module (`import { a, b }`, `from x import a, b`, `use x::{a, b}`), or inserts a new
+// statement after the last import in the file's own quoting and semicolon style.
+//
+// Go and C# import packages and namespaces rather than symbols, so for them only
+// `module` matters.
+
+use crate::code_navigation::{get_language, CodeNavigationService};
+use crate::line_endings;
+use crate::path_policy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tree_sitter::{Node, Parser};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    AlreadyImported,
+    Extended,
+    Inserted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportEdit {
+    pub path: String,
+    pub action: ImportAction,
+    /// The statement that provides the symbol after the edit
+    pub statement: String,
+    /// 1-based line of that statement
+    pub line: usize,
+}
+
+/// Insertions into the source, and the byte range of the providing statement once they
+/// are applied
+struct Plan {
+    action: ImportAction,
+    inserts: Vec<(usize, String)>,
+    statement: (usize, usize),
+}
+
+impl Plan {
+    fn exists(node: Node) -> Self {
+        Plan {
+            action: ImportAction::AlreadyImported,
+            inserts: Vec::new(),
+            statement: (node.start_byte(), node.end_byte()),
+        }
+    }
+
+    /// Insertions inside `node`, which keeps its start
+    fn extend(node: Node, inserts: Vec<(usize, String)>) -> Self {
+        let added: usize = inserts.iter().map(|(_, text)| text.len()).sum();
+        Plan {
+            action: ImportAction::Extended,
+            statement: (node.start_byte(), node.end_byte() + added),
+            inserts,
+        }
+    }
+
+    /// `text` inserted at `at`, with the statement `prefix` bytes into it
+    fn insert(at: usize, text: String, prefix: usize, statement_len: usize) -> Self {
+        Plan {
+            action: ImportAction::Inserted,
+            statement: (at + prefix, at + prefix + statement_len),
+            inserts: vec![(at, text)],
+        }
+    }
+}
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+fn named_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor).collect()
+}
+
+fn unquote(literal: &str) -> &str {
+    literal.trim_matches(|c| c == '"' || c == '\'' || c == '`')
+}
+
+fn is_comment(node: &Node) -> bool {
+    node.kind().contains("comment")
+}
+
+/// Byte offset just past the line that `offset` is on
+fn line_end(source: &str, offset: usize) -> usize {
+    source[offset..]
+        .find('\n')
+        .map_or(source.len(), |pos| offset + pos + 1)
+}
+
+fn line_indent(source: &str, offset: usize) -> &str {
+    let start = source[..offset].rfind('\n').map_or(0, |pos| pos + 1);
+    let line = &source[start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Add `symbol` after the last item of a comma-separated list, on its own line when the
+/// list spans several
+fn append_to_list(source: &str, list: Node, last_item: Node, symbol: &str) -> (usize, String) {
+    let at = last_item.end_byte();
+    if text(list, source).contains('\n') {
+        let indent = line_indent(source, last_item.start_byte());
+        (at, format!(",\n{}{}", indent, symbol))
+    } else {
+        (at, format!(", {}", symbol))
+    }
+}
+
+/// A new statement on the line after `last`, the file's last import
+fn insert_after_import(source: &str, last: Node, statement: String) -> Plan {
+    let at = line_end(source, last.end_byte());
+    let len = statement.len();
+    if at == source.len() && !source.ends_with('\n') {
+        Plan::insert(at, format!("\n{}\n", statement), 1, len)
+    } else {
+        Plan::insert(at, format!("{}\n", statement), 0, len)
+    }
+}
+
+/// A new statement in a file without imports: after `anchor` (a package clause or the
+/// leading comments) separated by a blank line, or at the top
+fn insert_first_import(source: &str, anchor: Option<Node>, statement: String) -> Plan {
+    let len = statement.len();
+    match anchor {
+        Some(anchor) => {
+            let at = line_end(source, anchor.end_byte());
+            let newline = if at == source.len() && !source.ends_with('\n') {
+                "\n"
+            } else {
+                ""
+            };
+            Plan::insert(
+                at,
+                format!("{}\n{}\n", newline, statement),
+                newline.len() + 1,
+                len,
+            )
+        }
+        None if source.trim().is_empty() => Plan::insert(0, format!("{}\n", statement), 0, len),
+        None => Plan::insert(0, format!("{}\n\n", statement), 0, len),
+    }
+}
+
+/// Last of the file's leading nodes that `skip` accepts (comments, docstrings, directives)
+fn leading_anchor<'t>(root: Node<'t>, skip: impl Fn(&Node) -> bool) -> Option<Node<'t>> {
+    named_children(root)
+        .into_iter()
+        .take_while(|n| skip(n))
+        .last()
+}
+
+fn is_string_statement(node: &Node) -> bool {
+    node.kind() == "expression_statement"
+        && node
+            .named_child(0)
+            .is_some_and(|child| child.kind() == "string")
+}
+
+fn plan_js(root: Node, source: &str, symbol: &str, module: &str) -> Plan {
+    let imports: Vec<Node> = named_children(root)
+        .into_iter()
+        .filter(|n| n.kind() == "import_statement")
+        .collect();
+    let from_module: Vec<(Node, Node)> = imports
+        .iter()
+        .filter(|imp| {
+            imp.child_by_field_name("source")
+                .is_some_and(|s| unquote(text(s, source)) == module)
+        })
+        .filter_map(|imp| {
+            named_children(*imp)
+                .into_iter()
+                .find(|n| n.kind() == "import_clause")
+                .map(|clause| (*imp, clause))
+        })
+        .collect();
+
+    for (imp, clause) in &from_module {
+        for child in named_children(*clause) {
+            let imported = match child.kind() {
+                "identifier" => text(child, source) == symbol,
+                "named_imports" => named_children(child).into_iter().any(|spec| {
+                    let local = spec
+                        .child_by_field_name("alias")
+                        .or_else(|| spec.child_by_field_name("name"));
+                    local.is_some_and(|local| text(local, source) == symbol)
+                }),
+                _ => false,
+            };
+            if imported {
+                return Plan::exists(*imp);
+            }
+        }
+    }
+
+    // Type-only imports can't carry a value
+    for (imp, clause) in &from_module {
+        if text(*imp, source).starts_with("import type") {
+            continue;
+        }
+        let children = named_children(*clause);
+        if children.iter().any(|c| c.kind() == "namespace_import") {
+            continue;
+        }
+        if let Some(named) = children.iter().find(|c| c.kind() == "named_imports") {
+            let specs: Vec<Node> = named_children(*named)
+                .into_iter()
+                .filter(|s| s.kind() == "import_specifier")
+                .collect();
+            if let Some(last) = specs.last() {
+                return Plan::extend(*imp, vec![append_to_list(source, *named, *last, symbol)]);
+            }
+        } else if let Some(default) = children.iter().find(|c| c.kind() == "identifier") {
+            return Plan::extend(
+                *imp,
+                vec![(default.end_byte(), format!(", {{ {} }}", symbol))],
+            );
+        }
+    }
+
+    // Follow the file's style: quote character and semicolons
+    let first_source = imports
+        .first()
+        .and_then(|imp| imp.child_by_field_name("source"))
+        .map(|s| text(s, source));
+    let quote = match first_source.and_then(|s| s.chars().next()) {
+        Some('"') => '"',
+        _ => '\'',
+    };
+    let semicolon = imports
+        .first()
+        .is_none_or(|imp| text(*imp, source).trim_end().ends_with(';'));
+    let statement = format!(
+        "import {{ {} }} from {}{}{}{}",
+        symbol,
+        quote,
+        module,
+        quote,
+        if semicolon { ";" } else { "" }
+    );
+    match imports.last() {
+        Some(last) => insert_after_import(source, *last, statement),
+        None => {
+            // After leading comments and directives (`'use client'`)
+            let anchor = leading_anchor(root, |n| {
+                is_comment(n) || n.kind() == "hash_bang_line" || is_string_statement(n)
+            });
+            insert_first_import(source, anchor, statement)
+        }
+    }
+}
+
+/// Python: `from module import symbol`, or `import module` when `symbol` is empty
+fn plan_python(root: Node, source: &str, symbol: &str, module: &str) -> Plan {
+    let imports: Vec<Node> = named_children(root)
+        .into_iter()
+        .filter(|n| {
+            matches!(
+                n.kind(),
+                "import_statement" | "import_from_statement" | "future_import_statement"
+            )
+        })
+        .collect();
+    fn names<'a>(imp: Node<'a>) -> Vec<Node<'a>> {
+        let mut cursor = imp.walk();
+        imp.children_by_field_name("name", &mut cursor).collect()
+    }
+    // The name a dotted or aliased import binds
+    let bound = |name: Node| -> String {
+        match name.kind() {
+            "aliased_import" => name
+                .child_by_field_name("alias")
+                .map(|alias| text(alias, source).to_string())
+                .unwrap_or_default(),
+            _ => text(name, source).to_string(),
+        }
+    };
+
+    if symbol.is_empty() {
+        for imp in imports.iter().filter(|n| n.kind() == "import_statement") {
+            if names(*imp).into_iter().any(|name| {
+                let imported = name.child_by_field_name("name").unwrap_or(name);
+                text(imported, source) == module
+            }) {
+                return Plan::exists(*imp);
+            }
+        }
+    } else {
+        let from_module: Vec<Node> = imports
+            .iter()
+            .copied()
+            .filter(|n| n.kind() == "import_from_statement")
+            .filter(|n| {
+                n.child_by_field_name("module_name")
+                    .is_some_and(|m| text(m, source) == module)
+            })
+            .collect();
+        for imp in &from_module {
+            let wildcard = named_children(*imp)
+                .iter()
+                .any(|n| n.kind() == "wildcard_import");
+            if wildcard || names(*imp).into_iter().any(|name| bound(name) == symbol) {
+                return Plan::exists(*imp);
+            }
+        }
+        if let Some((imp, last)) = from_module
+            .iter()
+            .find_map(|imp| names(*imp).last().map(|last| (*imp, *last)))
+        {
+            return Plan::extend(imp, vec![append_to_list(source, imp, last, symbol)]);
+        }
+    }
+
+    let statement = if symbol.is_empty() {
+        format!("import {}", module)
+    } else {
+        format!("from {} import {}", module, symbol)
+    };
+    match imports.last() {
+        Some(last) => insert_after_import(source, *last, statement),
+        None => {
+            // After the module docstring and leading comments
+            let anchor = leading_anchor(root, |n| is_comment(n) || is_string_statement(n));
+            insert_first_import(source, anchor, statement)
+        }
+    }
+}
+
+/// Whether a `use` tree under `prefix` brings `wanted` (`module::symbol`) into scope
+fn rust_use_provides(node: Node, source: &str, prefix: &str, wanted: &str, module: &str) -> bool {
+    let squash = |node: Node| -> String {
+        text(node, source)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect()
+    };
+    match node.kind() {
+        "scoped_use_list" => {
+            let path = node
+                .child_by_field_name("path")
+                .map(|p| format!("{}{}::", prefix, squash(p)))
+                .unwrap_or_else(|| prefix.to_string());
+            node.child_by_field_name("list")
+                .is_some_and(|list| rust_use_provides(list, source, &path, wanted, module))
+        }
+        "use_list" => named_children(node)
+            .into_iter()
+            .any(|item| rust_use_provides(item, source, prefix, wanted, module)),
+        "use_as_clause" => node
+            .child_by_field_name("alias")
+            .is_some_and(|alias| wanted.rsplit("::").next() == Some(text(alias, source))),
+        "use_wildcard" => format!("{}{}", prefix, squash(node)) == format!("{}::*", module),
+        "self" => prefix.trim_end_matches("::") == wanted,
+        _ => format!("{}{}", prefix, squash(node)) == wanted,
+    }
+}
+
+fn plan_rust(root: Node, source: &str, symbol: &str, module: &str) -> Plan {
+    let uses: Vec<Node> = named_children(root)
+        .into_iter()
+        .filter(|n| n.kind() == "use_declaration")
+        .collect();
+    let wanted = format!("{}::{}", module, symbol);
+    for decl in &uses {
+        if let Some(argument) = decl.child_by_field_name("argument") {
+            if rust_use_provides(argument, source, "", &wanted, module) {
+                return Plan::exists(*decl);
+            }
+        }
+    }
+
+    // Extending a `pub use` would change what the module exports
+    for decl in uses.iter().filter(|d| {
+        !named_children(**d)
+            .iter()
+            .any(|n| n.kind() == "visibility_modifier")
+    }) {
+        let Some(argument) = decl.child_by_field_name("argument") else {
+            continue;
+        };
+        let Some(path) = argument.child_by_field_name("path") else {
+            continue;
+        };
+        if text(path, source) != module {
+            continue;
+        }
+        match argument.kind() {
+            "scoped_use_list" => {
+                let Some(list) = argument.child_by_field_name("list") else {
+                    continue;
+                };
+                let items: Vec<Node> = named_children(list)
+                    .into_iter()
+                    .filter(|n| !is_comment(n))
+                    .collect();
+                if let Some(last) = items.last() {
+                    return Plan::extend(*decl, vec![append_to_list(source, list, *last, symbol)]);
+                }
+            }
+            // `use module::a;` becomes `use module::{a, symbol};`
+            "scoped_identifier" => {
+                if let Some(name) = argument.child_by_field_name("name") {
+                    return Plan::extend(
+                        *decl,
+                        vec![
+                            (name.start_byte(), "{".to_string()),
+                            (name.end_byte(), format!(", {}}}", symbol)),
+                        ],
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let statement = format!("use {};", wanted);
+    match uses.last() {
+        Some(last) => insert_after_import(source, *last, statement),
+        None => {
+            let anchor = leading_anchor(root, |n| {
+                is_comment(n) || n.kind() == "inner_attribute_item"
+            });
+            insert_first_import(source, anchor, statement)
+        }
+    }
+}
+
+/// Go imports packages; `symbol` is not used
+fn plan_go(root: Node, source: &str, module: &str) -> Plan {
+    let decls: Vec<Node> = named_children(root)
+        .into_iter()
+        .filter(|n| n.kind() == "import_declaration")
+        .collect();
+    fn specs<'a>(decl: Node<'a>) -> Vec<Node<'a>> {
+        named_children(decl)
+            .into_iter()
+            .flat_map(|n| match n.kind() {
+                "import_spec_list" => named_children(n)
+                    .into_iter()
+                    .filter(|s| s.kind() == "import_spec")
+                    .collect(),
+                "import_spec" => vec![n],
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+    for decl in &decls {
+        for spec in specs(*decl) {
+            if spec
+                .child_by_field_name("path")
+                .is_some_and(|p| unquote(text(p, source)) == module)
+            {
+                return Plan::exists(spec);
+            }
+        }
+    }
+
+    // A new line in the first grouped import
+    for decl in &decls {
+        let grouped = named_children(*decl)
+            .iter()
+            .any(|n| n.kind() == "import_spec_list");
+        if let Some(last) = specs(*decl).last().filter(|_| grouped) {
+            let at = line_end(source, last.end_byte());
+            let spec = format!("{}\"{}\"", line_indent(source, last.start_byte()), module);
+            let len = spec.len();
+            return Plan {
+                action: ImportAction::Extended,
+                statement: (at, at + len),
+                inserts: vec![(at, format!("{}\n", spec))],
+            };
+        }
+    }
+
+    let statement = format!("import \"{}\"", module);
+    match decls.last() {
+        Some(last) => insert_after_import(source, *last, statement),
+        None => {
+            let anchor = named_children(root)
+                .into_iter()
+                .find(|n| n.kind() == "package_clause");
+            insert_first_import(source, anchor, statement)
+        }
+    }
+}
+
+fn plan_java(root: Node, source: &str, symbol: &str, module: &str) -> Plan {
+    let imports: Vec<Node> = named_children(root)
+        .into_iter()
+        .filter(|n| n.kind() == "import_declaration")
+        .collect();
+    let provided = [
+        format!("import{}.{};", module, symbol),
+        format!("import{}.*;", module),
+    ];
+    for imp in &imports {
+        let squashed: String = text(*imp, source)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        if provided.contains(&squashed) {
+            return Plan::exists(*imp);
+        }
+    }
+
+    let statement = format!("import {}.{};", module, symbol);
+    match imports.last() {
+        Some(last) => insert_after_import(source, *last, statement),
+        None => {
+            let anchor = named_children(root)
+                .into_iter()
+                .find(|n| n.kind() == "package_declaration");
+            insert_first_import(source, anchor, statement)
+        }
+    }
+}
+
+/// C# imports namespaces; `symbol` is not used
+fn plan_csharp(root: Node, source: &str, module: &str) -> Plan {
+    let usings: Vec<Node> = named_children(root)
+        .into_iter()
+        .filter(|n| n.kind() == "using_directive")
+        .collect();
+    let wanted = format!("using {};", module);
+    if let Some(existing) = usings.iter().find(|u| {
+        text(**u, source)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            == wanted
+    }) {
+        return Plan::exists(*existing);
+    }
+    match usings.last() {
+        Some(last) => insert_after_import(source, *last, wanted),
+        None => {
+            let anchor = leading_anchor(root, is_comment);
+            insert_first_import(source, anchor, wanted)
+        }
+    }
+}
+
+/// Make `symbol` from `module` available in `content`; returns the new content and what
+/// was done. `content` is expected LF-normalized.
+pub fn ensure_import_in(
+    content: &str,
+    lang_id: &str,
+    symbol: &str,
+    module: &str,
+) -> Result<(String, ImportAction, String, usize), String> {
+    let language = get_language(lang_id)
+        .ok_or_else(|| format!("Import insertion is not supported for {}", lang_id))?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| format!("Failed to set language for {}: {:?}", lang_id, e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| format!("Failed to parse {} content", lang_id))?;
+    let root = tree.root_node();
+
+    let plan = match lang_id {
+        "typescript" | "javascript" | "tsx" | "jsx" => plan_js(root, content, symbol, module),
+        "python" => plan_python(root, content, symbol, module),
+        "rust" => plan_rust(root, content, symbol, module),
+        "go" => plan_go(root, content, module),
+        "java" => plan_java(root, content, symbol, module),
+        "csharp" => plan_csharp(root, content, module),
+        _ => return Err(format!("Import insertion is not supported for {}", lang_id)),
+    };
+
+    let mut updated = content.to_string();
+    let mut inserts = plan.inserts;
+    inserts.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+    for (at, text) in &inserts {
+        updated.insert_str(*at, text);
+    }
+    let (start, end) = plan.statement;
+    let statement = updated[start..end].to_string();
+    let line = updated[..start].matches('\n').count() + 1;
+    Ok((updated, plan.action, statement, line))
+}
+
+// Tauri commands
+
+/// Import `symbol` from `module` in the file at `path`, writing the file if an import
+/// had to be added. For Python, an empty `symbol` imports the module itself.
+#[tauri::command]
+pub fn ensure_import(
+    path: String,
+    symbol: String,
+    module: String,
+    root_path: Option<String>,
+) -> Result<ImportEdit, String> {
+    path_policy::check_optional(&path, root_path.as_deref())?;
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&path)
+        .ok_or_else(|| format!("Unsupported file type: {}", path))?;
+    let original =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let (content, info) = line_endings::normalize_with_info(&original);
+
+    let (updated, action, statement, line) =
+        ensure_import_in(&content, &lang_id, symbol.trim(), module.trim())?;
+    if action != ImportAction::AlreadyImported {
+        fs::write(&path, line_endings::convert(&updated, info.dominant))
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+        log::info!("Import of {} from {} added to {}", symbol, module, path);
+    }
+    Ok(ImportEdit {
+        path,
+        action,
+        statement,
+        line,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn ensure(content: &str, lang_id: &str, symbol: &str, module: &str) -> (String, ImportAction) {
+        let (updated, action, _, _) = ensure_import_in(content, lang_id, symbol, module).unwrap();
+        (updated, action)
+    }
+
+    #[test]
+    fn test_typescript_imports() {
+        let content = "import { ref } from \"vue\"\nimport Foo from './foo'\n\nconst x = 1\n";
+        assert_eq!(
+            ensure(content, "typescript", "ref", "vue").1,
+            ImportAction::AlreadyImported
+        );
+        assert_eq!(
+            ensure(content, "typescript", "computed", "vue").0,
+            "import { ref, computed } from \"vue\"\nimport Foo from './foo'\n\nconst x = 1\n"
+        );
+        assert_eq!(
+            ensure(content, "typescript", "bar", "./foo").0,
+            "import { ref } from \"vue\"\nimport Foo, { bar } from './foo'\n\nconst x = 1\n"
+        );
+        // New statements follow the first import's quotes and lack of semicolons
+        let (updated, action, statement, line) =
+            ensure_import_in(content, "typescript", "h", "preact").unwrap();
+        assert_eq!(action, ImportAction::Inserted);
+        assert_eq!(statement, "import { h } from \"preact\"");
+        assert_eq!(line, 3);
+        assert!(updated.starts_with("import { ref } from \"vue\"\nimport Foo from './foo'\nimport { h } from \"preact\"\n\n"));
+
+        let multi_line = "import {\n  a,\n  b,\n} from './x';\n";
+        assert_eq!(
+            ensure(multi_line, "typescript", "c", "./x").0,
+            "import {\n  a,\n  b,\n  c,\n} from './x';\n"
+        );
+        assert_eq!(
+            ensure("'use client';\n\nexport {};\n", "javascript", "a", "b").0,
+            "'use client';\n\nimport { a } from 'b';\n\nexport {};\n"
+        );
+    }
+
+    #[test]
+    fn test_python_imports() {
+        let content = "\"\"\"Docs\"\"\"\nimport os\nfrom typing import List\n\nx = 1\n";
+        assert_eq!(
+            ensure(content, "python", "List", "typing").1,
+            ImportAction::AlreadyImported
+        );
+        assert_eq!(
+            ensure(content, "python", "", "os").1,
+            ImportAction::AlreadyImported
+        );
+        assert_eq!(
+            ensure(content, "python", "Dict", "typing").0,
+            "\"\"\"Docs\"\"\"\nimport os\nfrom typing import List, Dict\n\nx = 1\n"
+        );
+        assert_eq!(
+            ensure(content, "python", "", "sys").0,
+            "\"\"\"Docs\"\"\"\nimport os\nfrom typing import List\nimport sys\n\nx = 1\n"
+        );
+        assert_eq!(
+            ensure("\"\"\"Docs\"\"\"\n\nx = 1\n", "python", "path", "os").0,
+            "\"\"\"Docs\"\"\"\n\nfrom os import path\n\nx = 1\n"
+        );
+        assert_eq!(
+            ensure("from x import *\n", "python", "y", "x").1,
+            ImportAction::AlreadyImported
+        );
+    }
+
+    #[test]
+    fn test_rust_imports() {
+        let content = "//! Crate docs\nuse std::collections::HashMap;\nuse std::io::{Read, Write};\n\nfn main() {}\n";
+        assert_eq!(
+            ensure(content, "rust", "Write", "std::io").1,
+            ImportAction::AlreadyImported
+        );
+        assert_eq!(
+            ensure(content, "rust", "BufRead", "std::io").0,
+            "//! Crate docs\nuse std::collections::HashMap;\nuse std::io::{Read, Write, BufRead};\n\nfn main() {}\n"
+        );
+        assert_eq!(
+            ensure(content, "rust", "HashSet", "std::collections").0,
+            "//! Crate docs\nuse std::collections::{HashMap, HashSet};\nuse std::io::{Read, Write};\n\nfn main() {}\n"
+        );
+        let (updated, action, statement, line) =
+            ensure_import_in(content, "rust", "Arc", "std::sync").unwrap();
+        assert_eq!(action, ImportAction::Inserted);
+        assert_eq!(statement, "use std::sync::Arc;");
+        assert_eq!(line, 4);
+        assert!(updated.contains("use std::io::{Read, Write};\nuse std::sync::Arc;\n\nfn main"));
+        assert_eq!(
+            ensure("use std::fmt::*;\n", "rust", "Display", "std::fmt").1,
+            ImportAction::AlreadyImported
+        );
+    }
+
+    #[test]
+    fn test_go_and_java_imports() {
+        let go = "package main\n\nimport (\n\t\"fmt\"\n)\n\nfunc main() {}\n";
+        assert_eq!(ensure(go, "go", "", "fmt").1, ImportAction::AlreadyImported);
+        assert_eq!(
+            ensure(go, "go", "", "os").0,
+            "package main\n\nimport (\n\t\"fmt\"\n\t\"os\"\n)\n\nfunc main() {}\n"
+        );
+        assert_eq!(
+            ensure("package main\n\nfunc main() {}\n", "go", "", "os").0,
+            "package main\n\nimport \"os\"\n\nfunc main() {}\n"
+        );
+
+        let java = "package app;\n\nimport java.util.*;\n\nclass A {}\n";
+        assert_eq!(
+            ensure(java, "java", "List", "java.util").1,
+            ImportAction::AlreadyImported
+        );
+        assert_eq!(
+            ensure(java, "java", "Path", "java.nio.file").0,
+            "package app;\n\nimport java.util.*;\nimport java.nio.file.Path;\n\nclass A {}\n"
+        );
+    }
+
+    #[test]
+    fn test_ensure_import_writes_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("app.ts");
+        fs::write(&path, "import { a } from './a';\r\n\r\na();\r\n").unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let edit = ensure_import(path.clone(), "b".into(), "./a".into(), None).unwrap();
+        assert_eq!(edit.action, ImportAction::Extended);
+        assert_eq!(edit.statement, "import { a, b } from './a';");
+        assert_eq!(edit.line, 1);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "import { a, b } from './a';\r\n\r\na();\r\n"
+        );
+
+        let again = ensure_import(path, "b".into(), "./a".into(), None).unwrap();
+        assert_eq!(again.action, ImportAction::AlreadyImported);
+        assert!(ensure_import_in("x", "haskell", "a", "b").is_err());
+    }
+}
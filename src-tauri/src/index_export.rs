@@ -0,0 +1,378 @@
+// Code index export
+//
+// Exports the definitions index so other tools can consume it:
+// - `json`: the persisted index format itself, versioned by INDEX_VERSION
+// - `scip`: a SCIP index in the protobuf JSON mapping (`metadata`, `documents` with
+//   definition occurrences and symbol information); `protoc --encode` or any protobuf
+//   library turns it into the binary `index.scip`
+// - `lsif`: LSIF 0.4.3 JSON lines with a document per file and a range, result set and
+//   definition result per symbol
+//
+// Only definitions are indexed, so only definition occurrences are exported.
+
+use crate::code_navigation::{resolve_nav, CodeNavState, PersistedIndex, SymbolInfo};
+use crate::position_encoding;
+use crate::workspace_state::WorkspaceState;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+const TOOL_NAME: &str = "talkcody";
+const SCIP_SCHEME: &str = "scip-talkcody";
+const LSIF_VERSION: &str = "0.4.3";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Json,
+    Scip,
+    Lsif,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexExport {
+    pub format: ExportFormat,
+    /// Where the export was written, when an output path was given
+    pub output_path: Option<String>,
+    /// The export itself, when no output path was given
+    pub content: Option<String>,
+    pub document_count: usize,
+    pub symbol_count: usize,
+}
+
+/// Symbols grouped by file, files and symbols in source order
+fn documents(index: &PersistedIndex) -> BTreeMap<&str, Vec<&SymbolInfo>> {
+    let mut documents: BTreeMap<&str, Vec<&SymbolInfo>> = BTreeMap::new();
+    for symbol in index.definitions.values().flatten() {
+        documents
+            .entry(symbol.file_path.as_str())
+            .or_default()
+            .push(symbol);
+    }
+    for symbols in documents.values_mut() {
+        symbols.sort_by_key(|s| (s.start_line, s.start_column, &s.name));
+    }
+    documents
+}
+
+fn relative_path(root_path: &str, file_path: &str) -> String {
+    Path::new(file_path)
+        .strip_prefix(root_path)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| file_path.replace('\\', "/"))
+}
+
+fn file_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    }
+}
+
+/// A SCIP descriptor name, backquoted unless it is a plain identifier
+fn scip_name(name: &str) -> String {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '+' | '-' | '$'))
+    {
+        name.to_string()
+    } else {
+        format!("`{}`", name.replace('`', "``"))
+    }
+}
+
+/// Global SCIP symbol: `scip-talkcody . . . Outer#method().`
+fn scip_symbol(symbol: &SymbolInfo) -> String {
+    let qualified = symbol.qualified_name.as_deref().unwrap_or(&symbol.name);
+    let mut segments = crate::qualified_names::segments(qualified);
+    if segments.is_empty() {
+        segments.push(symbol.name.clone());
+    }
+    let last = segments.pop().unwrap_or_default();
+    let mut descriptors: String = segments
+        .iter()
+        .map(|scope| format!("{}#", scip_name(scope)))
+        .collect();
+    let name = scip_name(&last);
+    descriptors.push_str(&match symbol.kind.as_str() {
+        "function" | "method" => format!("{}().", name),
+        "class" | "struct" | "interface" | "enum" | "trait" | "type" => format!("{}#", name),
+        "module" | "namespace" => format!("{}/", name),
+        _ => format!("{}.", name),
+    });
+    format!("{} . . . {}", SCIP_SCHEME, descriptors)
+}
+
+/// 0-based SCIP range; three elements when the range is on one line
+fn scip_range(symbol: &SymbolInfo) -> Vec<u32> {
+    let start_line = symbol.start_line.saturating_sub(1);
+    let end_line = symbol.end_line.saturating_sub(1);
+    let start_column = symbol.start_column.saturating_sub(1);
+    let end_column = symbol.end_column.saturating_sub(1);
+    if start_line == end_line {
+        vec![start_line, start_column, end_column]
+    } else {
+        vec![start_line, start_column, end_line, end_column]
+    }
+}
+
+pub fn export_scip(index: &PersistedIndex) -> Value {
+    let documents: Vec<Value> = documents(index)
+        .into_iter()
+        .map(|(file_path, symbols)| {
+            let language = symbols
+                .first()
+                .map_or("", |s| s.lang_family.as_str())
+                .to_string();
+            let occurrences: Vec<Value> = symbols
+                .iter()
+                .map(|s| {
+                    json!({
+                        "range": scip_range(s),
+                        "symbol": scip_symbol(s),
+                        // SymbolRole.Definition
+                        "symbolRoles": 1,
+                    })
+                })
+                .collect();
+            let information: Vec<Value> = symbols
+                .iter()
+                .map(|s| json!({ "symbol": scip_symbol(s), "displayName": s.name }))
+                .collect();
+            json!({
+                "relativePath": relative_path(&index.root_path, file_path),
+                "language": language,
+                "positionEncoding": "UTF8CodeUnitOffsetFromLineStart",
+                "occurrences": occurrences,
+                "symbols": information,
+            })
+        })
+        .collect();
+    json!({
+        "metadata": {
+            "version": "UnspecifiedProtocolVersion",
+            "toolInfo": { "name": TOOL_NAME, "version": env!("CARGO_PKG_VERSION") },
+            "projectRoot": file_uri(&index.root_path),
+            "textDocumentEncoding": "UTF8",
+        },
+        "documents": documents,
+    })
+}
+
+/// LSIF graph as JSON lines. Columns are expected in UTF-16 code units.
+pub fn export_lsif(index: &PersistedIndex) -> Vec<Value> {
+    let mut elements = Vec::new();
+    let mut next_id = 0u64;
+    let mut id = || {
+        next_id += 1;
+        next_id
+    };
+    elements.push(json!({
+        "id": id(),
+        "type": "vertex",
+        "label": "metaData",
+        "version": LSIF_VERSION,
+        "projectRoot": file_uri(&index.root_path),
+        "positionEncoding": "utf-16",
+        "toolInfo": { "name": TOOL_NAME, "version": env!("CARGO_PKG_VERSION") },
+    }));
+
+    for (file_path, symbols) in documents(index) {
+        let document = id();
+        elements.push(json!({
+            "id": document,
+            "type": "vertex",
+            "label": "document",
+            "uri": file_uri(file_path),
+            "languageId": symbols.first().map_or("", |s| s.lang_family.as_str()),
+        }));
+        let mut ranges = Vec::new();
+        for symbol in symbols {
+            let range = id();
+            let result_set = id();
+            let definition = id();
+            ranges.push(range);
+            elements.extend([
+                json!({
+                    "id": range,
+                    "type": "vertex",
+                    "label": "range",
+                    "start": {
+                        "line": symbol.start_line.saturating_sub(1),
+                        "character": symbol.start_column.saturating_sub(1),
+                    },
+                    "end": {
+                        "line": symbol.end_line.saturating_sub(1),
+                        "character": symbol.end_column.saturating_sub(1),
+                    },
+                }),
+                json!({ "id": result_set, "type": "vertex", "label": "resultSet" }),
+                json!({ "id": id(), "type": "edge", "label": "next", "outV": range, "inV": result_set }),
+                json!({ "id": definition, "type": "vertex", "label": "definitionResult" }),
+                json!({
+                    "id": id(),
+                    "type": "edge",
+                    "label": "textDocument/definition",
+                    "outV": result_set,
+                    "inV": definition,
+                }),
+                json!({
+                    "id": id(),
+                    "type": "edge",
+                    "label": "item",
+                    "outV": definition,
+                    "inVs": [range],
+                    "document": document,
+                }),
+            ]);
+        }
+        elements.push(json!({
+            "id": id(),
+            "type": "edge",
+            "label": "contains",
+            "outV": document,
+            "inVs": ranges,
+        }));
+    }
+    elements
+}
+
+pub fn export_index(index: &PersistedIndex, format: ExportFormat) -> Result<String, String> {
+    let serialized = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(index),
+        ExportFormat::Scip => serde_json::to_string_pretty(&export_scip(index)),
+        ExportFormat::Lsif => export_lsif(index)
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|lines| lines.join("\n") + "\n"),
+    };
+    serialized.map_err(|e| format!("Failed to serialize index export: {}", e))
+}
+
+// Tauri commands
+
+/// Export the in-memory index for `root_path`. With `output_path` the export is written
+/// there; otherwise it is returned as `content`.
+#[tauri::command]
+pub async fn code_nav_export_index(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    root_path: String,
+    format: ExportFormat,
+    output_path: Option<String>,
+) -> Result<IndexExport, String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let mut index = {
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        service.to_persisted(&root_path, HashMap::new())
+    };
+    // LSIF positions are UTF-16 by specification
+    if format == ExportFormat::Lsif {
+        for symbols in index.definitions.values_mut() {
+            position_encoding::symbols_to_utf16(symbols);
+        }
+    }
+
+    let content = export_index(&index, format)?;
+    let document_count = index.file_definitions.len();
+    let symbol_count = index.definitions.values().map(Vec::len).sum();
+    let output_path = match output_path {
+        Some(path) => {
+            fs::write(&path, &content)
+                .map_err(|e| format!("Failed to write index export {}: {}", path, e))?;
+            log::info!(
+                "Exported {} symbols from {} as {:?} to {}",
+                symbol_count,
+                root_path,
+                format,
+                path
+            );
+            Some(path)
+        }
+        None => None,
+    };
+    Ok(IndexExport {
+        format,
+        content: output_path.is_none().then_some(content),
+        output_path,
+        document_count,
+        symbol_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_navigation::CodeNavigationService;
+
+    fn indexed() -> PersistedIndex {
+        let mut service = CodeNavigationService::new();
+        service.index_file(
+            "/repo/src/widget.rs",
+            "struct Widget;\n\nimpl Widget {\n    fn resize(&self) {}\n}\n",
+            "rust",
+        );
+        service.index_file("/repo/main.py", "def main():\n    pass\n", "python");
+        service.to_persisted("/repo", HashMap::new())
+    }
+
+    #[test]
+    fn test_export_scip() {
+        let scip = export_scip(&indexed());
+        assert_eq!(scip["metadata"]["projectRoot"], "file:///repo");
+        let documents = scip["documents"].as_array().unwrap();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0]["relativePath"], "main.py");
+        let widget = &documents[1];
+        assert_eq!(widget["relativePath"], "src/widget.rs");
+        let symbols: Vec<&str> = widget["occurrences"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o["symbol"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            symbols,
+            vec![
+                "scip-talkcody . . . Widget#",
+                "scip-talkcody . . . Widget#resize().",
+            ]
+        );
+        assert_eq!(widget["occurrences"][1]["range"], json!([3, 7, 13]));
+    }
+
+    #[test]
+    fn test_export_lsif_and_json() {
+        let index = indexed();
+        let lsif = export_index(&index, ExportFormat::Lsif).unwrap();
+        let elements: Vec<Value> = lsif
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(elements[0]["label"], "metaData");
+        let count = |label: &str| elements.iter().filter(|e| e["label"] == label).count();
+        assert_eq!(count("document"), 2);
+        assert_eq!(count("range"), 3);
+        assert_eq!(count("textDocument/definition"), 3);
+        assert_eq!(count("contains"), 2);
+        // Ids are unique
+        let mut ids: Vec<u64> = elements.iter().map(|e| e["id"].as_u64().unwrap()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), elements.len());
+
+        let json = export_index(&index, ExportFormat::Json).unwrap();
+        let parsed: PersistedIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.definitions.len(), index.definitions.len());
+    }
+}
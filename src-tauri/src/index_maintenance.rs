@@ -0,0 +1,493 @@
+// Index maintenance
+//
+// Keeps the persisted code navigation indexes and the app database healthy:
+// - outdated index files are migrated version by version instead of thrown away
+// - corrupt index files are deleted so the next load reports no index and the
+//   workspace is re-indexed from scratch
+// - entries for files that no longer exist are compacted out
+// - the database gets an integrity check and a periodic VACUUM
+//
+// Runs shortly after startup and then daily; progress is emitted as
+// `index-maintenance-progress` events.
+
+use crate::code_navigation::{get_index_dir, PersistedIndex, INDEX_VERSION};
+use crate::database::Database;
+use crate::qualified_names;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Minimum time between database VACUUMs
+const VACUUM_INTERVAL_SECS: i64 = 7 * 24 * 60 * 60;
+const STATE_FILE: &str = "maintenance.json";
+
+/// Upgrades raw index JSON from `version - 1` to `version`
+struct IndexMigration {
+    version: u32,
+    apply: fn(&mut Value),
+}
+
+const INDEX_MIGRATIONS: &[IndexMigration] = &[
+    IndexMigration {
+        version: 2,
+        // Version 2 dropped reference indexing; references are searched on demand
+        apply: |index| {
+            if let Some(object) = index.as_object_mut() {
+                object.remove("references");
+                object.remove("file_references");
+            }
+        },
+    },
+    IndexMigration {
+        version: 3,
+        // Version 3 keys symbols without generics or scope (`List<T>` -> `List`,
+        // `Widget::resize` -> `resize`). Qualified names need the source, so migrated
+        // symbols get theirs when their file is next re-indexed.
+        apply: rekey_definitions,
+    },
+];
+
+fn rekey_definitions(index: &mut Value) {
+    if let Some(Value::Object(definitions)) = index.get_mut("definitions") {
+        let mut rekeyed = serde_json::Map::new();
+        for (_, symbols) in std::mem::take(definitions) {
+            let Value::Array(symbols) = symbols else {
+                continue;
+            };
+            for mut symbol in symbols {
+                let Some(key) = symbol
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(qualified_names::index_key)
+                else {
+                    continue;
+                };
+                symbol["name"] = Value::from(key.clone());
+                if let Value::Array(bucket) = rekeyed
+                    .entry(key)
+                    .or_insert_with(|| Value::Array(Vec::new()))
+                {
+                    bucket.push(symbol);
+                }
+            }
+        }
+        *definitions = rekeyed;
+    }
+    if let Some(Value::Object(files)) = index.get_mut("file_definitions") {
+        for names in files.values_mut() {
+            if let Value::Array(names) = names {
+                let mut keys: Vec<String> = names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(qualified_names::index_key)
+                    .collect();
+                keys.sort();
+                keys.dedup();
+                *names = keys.into_iter().map(Value::from).collect();
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum IndexFile {
+    Valid {
+        index: PersistedIndex,
+        migrated_from: Option<u32>,
+    },
+    Corrupt(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceReport {
+    pub indexes_checked: usize,
+    pub indexes_migrated: usize,
+    pub indexes_compacted: usize,
+    /// Corrupt indexes that were deleted and will be rebuilt on next load
+    pub indexes_rebuilt: usize,
+    pub entries_removed: usize,
+    /// None when the database is not connected
+    pub database_ok: Option<bool>,
+    pub database_vacuumed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceProgress {
+    pub phase: String, // "indexes", "database" or "done"
+    pub current: usize,
+    pub total: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MaintenanceState {
+    last_vacuum: Option<i64>,
+}
+
+/// Bring raw index JSON up to INDEX_VERSION
+pub fn migrate_index_value(mut value: Value) -> Result<(PersistedIndex, Option<u32>), String> {
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or("Index has no version")? as u32;
+    if version > INDEX_VERSION {
+        return Err(format!("Index version {} is newer than supported", version));
+    }
+    for migration in INDEX_MIGRATIONS.iter().filter(|m| m.version > version) {
+        (migration.apply)(&mut value);
+        value["version"] = Value::from(migration.version);
+    }
+    let index: PersistedIndex =
+        serde_json::from_value(value).map_err(|e| format!("Invalid index: {}", e))?;
+    if index.version != INDEX_VERSION {
+        return Err(format!("No migration path from version {}", version));
+    }
+    Ok((index, (version != INDEX_VERSION).then_some(version)))
+}
+
+/// Read and migrate an index file; anything that cannot be loaded is reported as corrupt
+pub fn read_index_file(path: &Path) -> Result<IndexFile, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("Failed to read index file: {}", e))?;
+    let value: Value = match serde_json::from_str(&json) {
+        Ok(value) => value,
+        Err(e) => return Ok(IndexFile::Corrupt(format!("Invalid JSON: {}", e))),
+    };
+    Ok(match migrate_index_value(value) {
+        Ok((index, migrated_from)) => IndexFile::Valid {
+            index,
+            migrated_from,
+        },
+        Err(reason) => IndexFile::Corrupt(reason),
+    })
+}
+
+pub fn write_index_file(path: &Path, index: &PersistedIndex) -> Result<(), String> {
+    let json =
+        serde_json::to_string(index).map_err(|e| format!("Failed to serialize index: {}", e))?;
+    // Write then rename so a crash mid-write cannot leave a truncated index behind
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json).map_err(|e| format!("Failed to write index file: {}", e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace index file: {}", e))
+}
+
+/// Drop files that no longer exist and symbols whose file is not tracked.
+/// Returns the number of removed entries.
+pub fn compact_index(index: &mut PersistedIndex, exists: impl Fn(&str) -> bool) -> usize {
+    let before_files = index.file_definitions.len() + index.file_timestamps.len();
+    index.file_definitions.retain(|path, _| exists(path));
+    index.file_timestamps.retain(|path, _| exists(path));
+    let mut removed = before_files - index.file_definitions.len() - index.file_timestamps.len();
+
+    let tracked = &index.file_definitions;
+    for symbols in index.definitions.values_mut() {
+        let before = symbols.len();
+        symbols.retain(|s| tracked.contains_key(&s.file_path));
+        removed += before - symbols.len();
+    }
+    index.definitions.retain(|_, symbols| !symbols.is_empty());
+    removed
+}
+
+fn emit_progress(app: &AppHandle, phase: &str, current: usize, total: usize, message: String) {
+    let progress = MaintenanceProgress {
+        phase: phase.to_string(),
+        current,
+        total,
+        message,
+    };
+    if let Err(e) = app.emit("index-maintenance-progress", &progress) {
+        log::error!("Failed to emit index-maintenance-progress event: {}", e);
+    }
+}
+
+/// Migrate, compact or delete every persisted index in `index_dir`
+pub fn maintain_index_dir(
+    index_dir: &Path,
+    mut on_progress: impl FnMut(usize, usize, &str),
+) -> MaintenanceReport {
+    let mut report = MaintenanceReport::default();
+    let Ok(entries) = fs::read_dir(index_dir) else {
+        return report;
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    paths.sort();
+
+    let total = paths.len();
+    for (idx, path) in paths.iter().enumerate() {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        on_progress(idx + 1, total, &name);
+        report.indexes_checked += 1;
+
+        match read_index_file(path) {
+            Ok(IndexFile::Valid {
+                mut index,
+                migrated_from,
+            }) => {
+                let removed = compact_index(&mut index, |p| Path::new(p).exists());
+                if migrated_from.is_some() {
+                    report.indexes_migrated += 1;
+                }
+                if removed > 0 {
+                    report.indexes_compacted += 1;
+                    report.entries_removed += removed;
+                }
+                if migrated_from.is_some() || removed > 0 {
+                    if let Err(e) = write_index_file(path, &index) {
+                        log::error!("Failed to rewrite index {}: {}", name, e);
+                    }
+                }
+            }
+            Ok(IndexFile::Corrupt(reason)) => {
+                log::warn!("Deleting corrupt index {}: {}", name, reason);
+                if fs::remove_file(path).is_ok() {
+                    report.indexes_rebuilt += 1;
+                }
+            }
+            Err(e) => log::warn!("Skipping index {}: {}", name, e),
+        }
+    }
+    report
+}
+
+/// Integrity check plus a VACUUM when the last one is older than VACUUM_INTERVAL_SECS.
+/// Returns (integrity ok, vacuumed); None when the database is not connected.
+async fn maintain_database(database: &Database, state_path: &Path) -> Option<(bool, bool)> {
+    let result = database.query("PRAGMA quick_check", vec![]).await.ok()?;
+    let ok = result
+        .rows
+        .first()
+        .and_then(|row| row.as_object())
+        .and_then(|row| row.values().next())
+        .and_then(Value::as_str)
+        == Some("ok");
+    if !ok {
+        // The database holds user data, so it is reported rather than rebuilt
+        log::error!("Database integrity check failed: {:?}", result.rows);
+        return Some((false, false));
+    }
+
+    let mut state: MaintenanceState = fs::read_to_string(state_path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let now = chrono::Utc::now().timestamp();
+    if state
+        .last_vacuum
+        .is_some_and(|last| now - last < VACUUM_INTERVAL_SECS)
+    {
+        return Some((true, false));
+    }
+    if let Err(e) = database.execute("VACUUM", vec![]).await {
+        log::warn!("Database VACUUM failed: {}", e);
+        return Some((true, false));
+    }
+    state.last_vacuum = Some(now);
+    if let Ok(json) = serde_json::to_string(&state) {
+        let _ = fs::write(state_path, json);
+    }
+    Some((true, true))
+}
+
+pub async fn run_maintenance(app: &AppHandle) -> Result<MaintenanceReport, String> {
+    let index_dir = get_index_dir(app)?;
+    let progress_app = app.clone();
+    let mut report = tokio::task::spawn_blocking(move || {
+        maintain_index_dir(&index_dir, |current, total, name| {
+            emit_progress(
+                &progress_app,
+                "indexes",
+                current,
+                total,
+                format!("Checking index {}", name),
+            )
+        })
+    })
+    .await
+    .map_err(|e| format!("Index maintenance failed: {}", e))?;
+
+    if let Some(database) = app.try_state::<Arc<Database>>() {
+        emit_progress(app, "database", 1, 1, "Checking database".to_string());
+        let app_data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+        if let Some((ok, vacuumed)) =
+            maintain_database(&database, &app_data_dir.join(STATE_FILE)).await
+        {
+            report.database_ok = Some(ok);
+            report.database_vacuumed = vacuumed;
+        }
+    }
+
+    emit_progress(app, "done", 1, 1, "Maintenance finished".to_string());
+    log::info!(
+        "Index maintenance: {} checked, {} migrated, {} compacted ({} entries), {} rebuilt",
+        report.indexes_checked,
+        report.indexes_migrated,
+        report.indexes_compacted,
+        report.entries_removed,
+        report.indexes_rebuilt
+    );
+    Ok(report)
+}
+
+/// Run maintenance shortly after startup and then once a day
+pub fn start_background_maintenance(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_maintenance(&app).await {
+                log::error!("Index maintenance failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn run_index_maintenance(app_handle: AppHandle) -> Result<MaintenanceReport, String> {
+    run_maintenance(&app_handle).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_navigation::SymbolInfo;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn symbol(name: &str, file_path: &str) -> SymbolInfo {
+        SymbolInfo {
+            name: name.to_string(),
+            kind: "function".to_string(),
+            file_path: file_path.to_string(),
+            lang_family: "rust".to_string(),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 2,
+            qualified_name: None,
+        }
+    }
+
+    fn index_with(files: &[(&str, &str)]) -> PersistedIndex {
+        let mut index = PersistedIndex {
+            version: INDEX_VERSION,
+            root_path: "/repo".to_string(),
+            last_updated: 0,
+            file_timestamps: HashMap::new(),
+            definitions: HashMap::new(),
+            file_definitions: HashMap::new(),
+        };
+        for (name, path) in files {
+            index
+                .definitions
+                .entry(name.to_string())
+                .or_default()
+                .push(symbol(name, path));
+            index
+                .file_definitions
+                .entry(path.to_string())
+                .or_default()
+                .insert(name.to_string());
+            index.file_timestamps.insert(path.to_string(), 1);
+        }
+        index
+    }
+
+    #[test]
+    fn test_migrate_v1_index() {
+        let v1 = json!({
+            "version": 1,
+            "root_path": "/repo",
+            "last_updated": 5,
+            "file_timestamps": {},
+            "definitions": {},
+            "file_definitions": {},
+            "references": {"a": []},
+        });
+        let (index, migrated_from) = migrate_index_value(v1).unwrap();
+        assert_eq!(index.version, INDEX_VERSION);
+        assert_eq!(migrated_from, Some(1));
+
+        let v2 = json!({
+            "version": 2,
+            "root_path": "/repo",
+            "last_updated": 5,
+            "file_timestamps": {},
+            "definitions": {
+                "List<T>": [serde_json::to_value(symbol("List<T>", "/repo/list.rs")).unwrap()],
+                "resize": [serde_json::to_value(symbol("resize", "/repo/a.cpp")).unwrap()],
+                "Widget::resize": [serde_json::to_value(symbol("Widget::resize", "/repo/b.cpp")).unwrap()],
+            },
+            "file_definitions": {"/repo/list.rs": ["List<T>"], "/repo/b.cpp": ["Widget::resize"]},
+        });
+        let (index, migrated_from) = migrate_index_value(v2).unwrap();
+        assert_eq!(migrated_from, Some(2));
+        assert_eq!(index.definitions["List"][0].name, "List");
+        assert_eq!(index.definitions["resize"].len(), 2);
+        assert!(!index.definitions.contains_key("Widget::resize"));
+        assert!(index.file_definitions["/repo/b.cpp"].contains("resize"));
+
+        let newer = json!({"version": INDEX_VERSION + 1});
+        assert!(migrate_index_value(newer).is_err());
+        assert!(migrate_index_value(json!({"root_path": "/repo"})).is_err());
+    }
+
+    #[test]
+    fn test_compact_index() {
+        let mut index = index_with(&[("keep", "/repo/a.rs"), ("gone", "/repo/b.rs")]);
+        // Symbol pointing at a file the index does not track
+        index
+            .definitions
+            .get_mut("keep")
+            .unwrap()
+            .push(symbol("keep", "/repo/orphan.rs"));
+
+        let removed = compact_index(&mut index, |p| p == "/repo/a.rs");
+        assert_eq!(removed, 4);
+        assert_eq!(index.definitions.len(), 1);
+        assert_eq!(index.definitions["keep"].len(), 1);
+        assert!(!index.file_definitions.contains_key("/repo/b.rs"));
+    }
+
+    #[test]
+    fn test_maintain_index_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let source = dir.join("main.rs");
+        fs::write(&source, "fn main() {}\n").unwrap();
+        let source = source.to_string_lossy().to_string();
+
+        let healthy = index_with(&[("main", &source)]);
+        write_index_file(&dir.join("healthy.json"), &healthy).unwrap();
+        let stale = index_with(&[("main", &source), ("old", "/missing/old.rs")]);
+        write_index_file(&dir.join("stale.json"), &stale).unwrap();
+        fs::write(dir.join("corrupt.json"), "{\"version\": 2, \"defin").unwrap();
+
+        let mut progress = Vec::new();
+        let report = maintain_index_dir(dir, |current, total, _| progress.push((current, total)));
+
+        assert_eq!(report.indexes_checked, 3);
+        assert_eq!(report.indexes_compacted, 1);
+        assert_eq!(report.indexes_rebuilt, 1);
+        assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+        assert!(!dir.join("corrupt.json").exists());
+        match read_index_file(&dir.join("stale.json")).unwrap() {
+            IndexFile::Valid { index, .. } => assert_eq!(index.file_definitions.len(), 1),
+            IndexFile::Corrupt(reason) => panic!("unexpected corrupt index: {}", reason),
+        }
+    }
+}
@@ -0,0 +1,403 @@
+// Inline edit: selection + instruction -> validated patch
+//
+// Builds a minimal context for the selected lines (enclosing definition, imports, and
+// the definitions of identifiers the selection uses), asks the provider for replacement
+// code, splices it into the file, syntax-checks the result and returns a ready-to-apply
+// patch. Nothing is written to disk here; the frontend applies the patch.
+
+use crate::code_navigation::{get_language, resolve_nav, CodeNavState, CodeNavigationService};
+use crate::line_endings;
+use crate::provider_client::{self, ChatMessage, ProviderConfig};
+use crate::syntax_check::{self, SyntaxCheckResult};
+use crate::text_diff;
+use crate::workspace_state::WorkspaceState;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::State;
+use tree_sitter::{Node, Parser, Point};
+
+const PROVIDER_TIMEOUT_SECS: u64 = 60;
+const MAX_IMPORT_LINES: usize = 40;
+const MAX_REFERENCED_DEFINITIONS: usize = 10;
+/// Enclosing definitions longer than this are reduced to their first line
+const MAX_ENCLOSING_CHARS: usize = 4_000;
+
+/// Node kinds treated as definitions when looking for the enclosing symbol
+pub(crate) const DEFINITION_KINDS: &[&str] = &[
+    "function_item",
+    "function_definition",
+    "function_declaration",
+    "method_declaration",
+    "method_definition",
+    "constructor_declaration",
+    "arrow_function",
+    "func_literal",
+    "class_declaration",
+    "class_definition",
+    "class_specifier",
+    "struct_item",
+    "struct_specifier",
+    "enum_item",
+    "impl_item",
+    "trait_item",
+    "interface_declaration",
+    "type_declaration",
+];
+
+const IMPORT_PREFIXES: &[&str] = &[
+    "import ",
+    "from ",
+    "use ",
+    "#include",
+    "package ",
+    "extern crate ",
+];
+
+/// 1-based inclusive line range
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LineRange {
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InlineEditContext {
+    pub selection: String,
+    pub enclosing_symbol: Option<String>,
+    pub imports: Vec<String>,
+    /// `path:line: text` of definitions the selection refers to
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InlineEditResult {
+    pub path: String,
+    pub range: LineRange,
+    pub original: String,
+    pub replacement: String,
+    /// Unified diff of the whole file
+    pub diff: String,
+    pub syntax: SyntaxCheckResult,
+    /// False when the edit introduces syntax errors into a file that had none
+    pub valid: bool,
+}
+
+fn identifier_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b[A-Za-z_][A-Za-z0-9_]*\b").unwrap())
+}
+
+/// Validate and clamp a range against the file's line count
+fn checked_range(content: &str, range: LineRange) -> Result<(usize, usize), String> {
+    let line_count = content.lines().count().max(1);
+    if range.start_line == 0 || range.start_line > range.end_line {
+        return Err(format!(
+            "Invalid line range {}-{}",
+            range.start_line, range.end_line
+        ));
+    }
+    let start = range.start_line as usize - 1;
+    if start >= line_count {
+        return Err(format!(
+            "Line {} is past the end of the file ({} lines)",
+            range.start_line, line_count
+        ));
+    }
+    let end = (range.end_line as usize).min(line_count);
+    Ok((start, end))
+}
+
+/// Smallest definition node enclosing the given rows
+pub(crate) fn enclosing_definition<'a>(
+    root: Node<'a>,
+    start_row: usize,
+    end_row: usize,
+) -> Option<Node<'a>> {
+    let mut node =
+        root.descendant_for_point_range(Point::new(start_row, 0), Point::new(end_row, 0))?;
+    loop {
+        if DEFINITION_KINDS.contains(&node.kind()) {
+            return Some(node);
+        }
+        node = node.parent()?;
+    }
+}
+
+/// Gather the context sent alongside the instruction
+pub fn build_inline_context(
+    content: &str,
+    lang_id: &str,
+    range: LineRange,
+) -> Result<InlineEditContext, String> {
+    let (start, end) = checked_range(content, range)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let selection = lines[start..end].join("\n");
+
+    let imports = lines
+        .iter()
+        .filter(|line| {
+            let trimmed = line.trim_start();
+            IMPORT_PREFIXES.iter().any(|p| trimmed.starts_with(p)) || trimmed.contains("require(")
+        })
+        .take(MAX_IMPORT_LINES)
+        .map(|line| line.to_string())
+        .collect();
+
+    let enclosing_symbol = get_language(lang_id).and_then(|language| {
+        let mut parser = Parser::new();
+        parser.set_language(&language).ok()?;
+        let tree = parser.parse(content, None)?;
+        let node = enclosing_definition(tree.root_node(), start, end.saturating_sub(1))?;
+        let text = node.utf8_text(content.as_bytes()).ok()?;
+        if text.len() > MAX_ENCLOSING_CHARS {
+            text.lines().next().map(|l| l.to_string())
+        } else {
+            Some(text.to_string())
+        }
+    });
+
+    Ok(InlineEditContext {
+        selection,
+        enclosing_symbol,
+        imports,
+        references: Vec::new(),
+    })
+}
+
+/// Replace lines `range` of `content` with `replacement`, keeping the trailing newline
+/// and the content's line-ending style
+pub fn apply_replacement(
+    content: &str,
+    range: LineRange,
+    replacement: &str,
+) -> Result<String, String> {
+    let (start, end) = checked_range(content, range)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out: Vec<&str> = Vec::with_capacity(lines.len());
+    out.extend_from_slice(&lines[..start]);
+    if !replacement.is_empty() {
+        out.extend(replacement.lines());
+    }
+    out.extend_from_slice(&lines[end.min(lines.len())..]);
+    let mut result = out.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    Ok(line_endings::convert(
+        &result,
+        line_endings::detect(content).dominant,
+    ))
+}
+
+fn build_prompt(path: &str, context: &InlineEditContext, instruction: &str) -> String {
+    let mut prompt = format!("File: {}\n\n", path);
+    if !context.imports.is_empty() {
+        prompt.push_str("Imports:\n");
+        prompt.push_str(&context.imports.join("\n"));
+        prompt.push_str("\n\n");
+    }
+    if let Some(enclosing) = &context.enclosing_symbol {
+        prompt.push_str("Enclosing definition:\n");
+        prompt.push_str(enclosing);
+        prompt.push_str("\n\n");
+    }
+    if !context.references.is_empty() {
+        prompt.push_str("Referenced definitions:\n");
+        prompt.push_str(&context.references.join("\n"));
+        prompt.push_str("\n\n");
+    }
+    prompt.push_str("Selected code:\n");
+    prompt.push_str(&context.selection);
+    prompt.push_str("\n\nInstruction: ");
+    prompt.push_str(instruction);
+    prompt
+}
+
+/// Build the patch for a replacement and validate it
+pub fn build_edit_result(
+    path: &str,
+    content: &str,
+    lang_id: &str,
+    range: LineRange,
+    replacement: String,
+) -> Result<InlineEditResult, String> {
+    let (start, end) = checked_range(content, range)?;
+    let original = content.lines().collect::<Vec<_>>()[start..end].join("\n");
+    let updated = apply_replacement(content, range, &replacement)?;
+
+    let before = syntax_check::check_syntax(content, lang_id)?;
+    let syntax = syntax_check::check_syntax(&updated, lang_id)?;
+    // Only blame the edit for errors it introduced
+    let valid = syntax.valid || !before.valid;
+
+    Ok(InlineEditResult {
+        path: path.to_string(),
+        range,
+        original,
+        replacement,
+        diff: text_diff::unified_diff(
+            content,
+            &updated,
+            &format!("a/{}", path),
+            &format!("b/{}", path),
+            3,
+        ),
+        syntax,
+        valid,
+    })
+}
+
+#[tauri::command]
+pub async fn inline_edit(
+    nav_state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    provider: ProviderConfig,
+    path: String,
+    range: LineRange,
+    instruction: String,
+) -> Result<InlineEditResult, String> {
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&path).unwrap_or_default();
+    let mut context = build_inline_context(&content, &lang_id, range)?;
+
+    // Definitions of identifiers used in the selection, from the symbol index
+    {
+        let nav = resolve_nav(&nav_state, &workspaces, workspace_id.as_deref())?;
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        let lang_family = CodeNavigationService::get_lang_family(&lang_id);
+        let names: BTreeSet<&str> = identifier_regex()
+            .find_iter(&context.selection)
+            .map(|m| m.as_str())
+            .collect();
+        for name in names {
+            for symbol in service.find_definition(name, lang_family) {
+                if context.references.len() >= MAX_REFERENCED_DEFINITIONS {
+                    break;
+                }
+                let line = fs::read_to_string(&symbol.file_path)
+                    .ok()
+                    .and_then(|c| {
+                        c.lines()
+                            .nth(symbol.start_line.saturating_sub(1) as usize)
+                            .map(|l| l.trim().to_string())
+                    })
+                    .unwrap_or_default();
+                context.references.push(format!(
+                    "{}:{}: {}",
+                    symbol.file_path, symbol.start_line, line
+                ));
+            }
+        }
+    }
+
+    let messages = [
+        ChatMessage::system(
+            "You edit code. Rewrite only the selected code according to the instruction. \
+             Reply with the replacement code only: no explanations, no markdown fences. \
+             Preserve the original indentation.",
+        ),
+        ChatMessage::user(build_prompt(&path, &context, &instruction)),
+    ];
+    let answer = provider_client::chat_completion(
+        &provider,
+        &messages,
+        Duration::from_secs(PROVIDER_TIMEOUT_SECS),
+    )
+    .await?;
+    let replacement = provider_client::strip_code_fence(&answer);
+
+    let result = build_edit_result(&path, &content, &lang_id, range, replacement)?;
+    if !result.valid {
+        log::warn!(
+            "Inline edit for {} introduced {} syntax issues",
+            path,
+            result.syntax.issues.len()
+        );
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str =
+        "use std::fmt;\n\nfn add(a: i32, b: i32) -> i32 {\n    let sum = a + b;\n    sum\n}\n";
+
+    fn range(start_line: u32, end_line: u32) -> LineRange {
+        LineRange {
+            start_line,
+            end_line,
+        }
+    }
+
+    #[test]
+    fn test_build_inline_context() {
+        let context = build_inline_context(SOURCE, "rust", range(4, 4)).unwrap();
+        assert_eq!(context.selection, "    let sum = a + b;");
+        assert_eq!(context.imports, vec!["use std::fmt;"]);
+        assert!(context
+            .enclosing_symbol
+            .unwrap()
+            .starts_with("fn add(a: i32, b: i32)"));
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert!(build_inline_context(SOURCE, "rust", range(0, 1)).is_err());
+        assert!(build_inline_context(SOURCE, "rust", range(5, 4)).is_err());
+        assert!(build_inline_context(SOURCE, "rust", range(50, 60)).is_err());
+    }
+
+    #[test]
+    fn test_apply_replacement() {
+        let updated = apply_replacement(SOURCE, range(4, 5), "    a + b").unwrap();
+        assert_eq!(
+            updated,
+            "use std::fmt;\n\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_replacement_preserves_crlf() {
+        let crlf = SOURCE.replace('\n', "\r\n");
+        let updated = apply_replacement(&crlf, range(4, 5), "    a + b").unwrap();
+        assert_eq!(
+            updated,
+            "use std::fmt;\r\n\r\nfn add(a: i32, b: i32) -> i32 {\r\n    a + b\r\n}\r\n"
+        );
+    }
+
+    #[test]
+    fn test_build_edit_result_validates() {
+        let ok = build_edit_result(
+            "lib.rs",
+            SOURCE,
+            "rust",
+            range(4, 5),
+            "    a + b".to_string(),
+        )
+        .unwrap();
+        assert!(ok.valid);
+        assert!(ok.diff.contains("+    a + b"));
+
+        let broken = build_edit_result(
+            "lib.rs",
+            SOURCE,
+            "rust",
+            range(4, 5),
+            "    a + (b".to_string(),
+        )
+        .unwrap();
+        assert!(!broken.valid);
+        assert!(!broken.syntax.issues.is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+// Per-workspace language overrides
+//
+// The built-in extension table misses project conventions (`.mjsx` files that are
+// really TSX, `.pyi` stubs) and some workspaces would rather not pay for parsing a
+// language at all, e.g. a vendored C++ tree in a Python project. Overrides are read
+// from ~/.talkcody/languages.json (global) and <project>/.talkcody/languages.json:
+//
+//   { "extensions": { "mjsx": "tsx", "pyi": "python" }, "disabled": ["cpp"] }
+//
+// Extension targets may be a lang id or another extension. Project extensions win over
+// global ones; disabled languages from both files are combined.
+
+use crate::code_navigation::CodeNavigationService;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "languages.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguageConfig {
+    /// extension (without the dot) -> lang id or extension
+    pub extensions: BTreeMap<String, String>,
+    /// Lang ids that are never parsed or indexed
+    pub disabled: BTreeSet<String>,
+}
+
+fn normalize_extension(ext: &str) -> String {
+    ext.trim_start_matches('.').to_lowercase()
+}
+
+/// `tsx` and `.pyi`-style targets name an extension; anything else is taken as a lang id
+fn normalize_target(target: &str) -> String {
+    let target = target.trim_start_matches('.');
+    CodeNavigationService::get_lang_id_from_path(&format!("file.{}", target))
+        .unwrap_or_else(|| target.to_string())
+}
+
+fn extension(file_path: &str) -> Option<String> {
+    let name = Path::new(file_path).file_name()?.to_str()?;
+    let (_, ext) = name.rsplit_once('.')?;
+    Some(ext.to_lowercase())
+}
+
+impl LanguageConfig {
+    fn normalized(self) -> Self {
+        Self {
+            extensions: self
+                .extensions
+                .into_iter()
+                .map(|(ext, target)| (normalize_extension(&ext), normalize_target(&target)))
+                .collect(),
+            disabled: self.disabled,
+        }
+    }
+
+    pub fn is_disabled(&self, lang_id: &str) -> bool {
+        self.disabled.contains(lang_id)
+    }
+
+    fn override_for(&self, file_path: &str) -> Option<&String> {
+        self.extensions.get(&extension(file_path)?)
+    }
+
+    /// Language of `file_path` after overrides; None when unknown or disabled
+    pub fn lang_id_for_path(&self, file_path: &str) -> Option<String> {
+        let lang_id = match self.override_for(file_path) {
+            Some(lang_id) => lang_id.clone(),
+            None => CodeNavigationService::get_lang_id_from_path(file_path)?,
+        };
+        (!self.is_disabled(&lang_id)).then_some(lang_id)
+    }
+
+    /// Language to index `file_path` with when the caller already picked `lang_id`
+    pub fn resolve(&self, file_path: &str, lang_id: &str) -> Option<String> {
+        let lang_id = self.override_for(file_path).map_or(lang_id, String::as_str);
+        (!self.is_disabled(lang_id)).then(|| lang_id.to_string())
+    }
+}
+
+/// Get the global config file (~/.talkcody/languages.json)
+fn get_global_config_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody").join(CONFIG_FILE))
+}
+
+/// Get the project config file (<root>/.talkcody/languages.json)
+fn get_project_config_path(root_path: &str) -> PathBuf {
+    Path::new(root_path).join(".talkcody").join(CONFIG_FILE)
+}
+
+fn read_config(path: &Path) -> LanguageConfig {
+    let Ok(raw) = fs::read_to_string(path) else {
+        return LanguageConfig::default();
+    };
+    serde_json::from_str::<LanguageConfig>(&raw)
+        .map(LanguageConfig::normalized)
+        .unwrap_or_else(|e| {
+            log::warn!(
+                "Ignoring invalid language config in {}: {}",
+                path.display(),
+                e
+            );
+            LanguageConfig::default()
+        })
+}
+
+/// Load the config, with project extensions overriding global ones
+pub fn load(global_path: Option<&Path>, root_path: Option<&str>) -> LanguageConfig {
+    let mut config = global_path.map(read_config).unwrap_or_default();
+    if let Some(root) = root_path {
+        let project = read_config(&get_project_config_path(root));
+        config.extensions.extend(project.extensions);
+        config.disabled.extend(project.disabled);
+    }
+    config
+}
+
+/// Load the global and project config for a workspace rooted at `root_path`
+pub fn load_for_workspace(root_path: &str) -> LanguageConfig {
+    let global_path = get_global_config_path().ok();
+    load(global_path.as_deref(), Some(root_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_extension_overrides_and_disabled() {
+        let config = LanguageConfig {
+            extensions: BTreeMap::from([
+                ("mjsx".to_string(), "tsx".to_string()),
+                ("pyi".to_string(), "python".to_string()),
+            ]),
+            disabled: BTreeSet::from(["cpp".to_string()]),
+        }
+        .normalized();
+
+        assert_eq!(
+            config.lang_id_for_path("src/App.mjsx").as_deref(),
+            Some("typescript")
+        );
+        assert_eq!(
+            config.lang_id_for_path("stubs/os.PYI").as_deref(),
+            Some("python")
+        );
+        assert_eq!(config.lang_id_for_path("main.rs").as_deref(), Some("rust"));
+        assert_eq!(config.lang_id_for_path("vendor/lib.cpp"), None);
+        assert_eq!(config.lang_id_for_path("Makefile"), None);
+
+        assert_eq!(
+            config.resolve("stubs/os.pyi", "unknown").as_deref(),
+            Some("python")
+        );
+        assert_eq!(config.resolve("a.go", "go").as_deref(), Some("go"));
+        assert_eq!(config.resolve("a.hpp", "cpp"), None);
+    }
+
+    #[test]
+    fn test_project_config_merges_over_global() {
+        let temp_dir = TempDir::new().unwrap();
+        let global = temp_dir.path().join("global.json");
+        fs::write(
+            &global,
+            r#"{"extensions": {".pyi": "python", "tpl": "html"}, "disabled": ["cpp"]}"#,
+        )
+        .unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir_all(root.join(".talkcody")).unwrap();
+        fs::write(
+            root.join(".talkcody").join(CONFIG_FILE),
+            r#"{"extensions": {"tpl": "vue"}, "disabled": ["java"]}"#,
+        )
+        .unwrap();
+
+        let config = load(Some(&global), root.to_str());
+        assert_eq!(
+            config.extensions.get("pyi").map(String::as_str),
+            Some("python")
+        );
+        assert_eq!(
+            config.extensions.get("tpl").map(String::as_str),
+            Some("vue")
+        );
+        assert!(config.is_disabled("cpp"));
+        assert!(config.is_disabled("java"));
+
+        fs::write(&global, "not json").unwrap();
+        assert_eq!(load(Some(&global), None), LanguageConfig::default());
+    }
+}
@@ -0,0 +1,117 @@
+// Large-file reading for indexing
+//
+// Generated megafiles (bundles, lockfile-like sources, protobuf output) dominate peak
+// memory when indexing reads each file into a String. Files above a threshold are
+// memory-mapped instead and fed to tree-sitter through its callback-based input, so
+// only the pages the parser touches are resident. The map is of a private copy: a
+// mapped file that is truncated underneath the parser raises SIGBUS and takes the
+// whole app down, and editors and formatters truncate files while rewriting them.
+
+use memmap2::Mmap;
+use std::fs::{self, File};
+use std::io;
+use std::ops::Deref;
+use tree_sitter::{Parser, Point, Tree};
+
+/// Files at or above this size are memory-mapped when no threshold is given
+pub const DEFAULT_MMAP_THRESHOLD: u64 = 4 * 1024 * 1024;
+
+/// Bytes handed to tree-sitter per input callback
+const PARSE_CHUNK_BYTES: usize = 64 * 1024;
+
+/// File contents, either read into memory or mapped
+pub enum SourceBytes {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl Deref for SourceBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            SourceBytes::Owned(bytes) => bytes,
+            SourceBytes::Mapped(map) => map,
+        }
+    }
+}
+
+/// Map a copy of `file` in an unnamed temporary file. The copy has no path, so
+/// nothing but this process can write to or truncate it while it is mapped.
+fn map_private_copy(mut file: File) -> io::Result<Mmap> {
+    let mut copy = tempfile::tempfile()?;
+    io::copy(&mut file, &mut copy)?;
+    // SAFETY: the copy is unlinked and this process never writes to it again, so the
+    // mapped bytes cannot change or shrink while the map is alive.
+    unsafe { Mmap::map(&copy) }
+}
+
+/// Read `path`, memory-mapping a private copy of it when it is at least
+/// `mmap_threshold` bytes. Falls back to a regular read if mapping fails.
+pub fn read_source(path: &str, mmap_threshold: u64) -> io::Result<SourceBytes> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    // Empty files cannot be mapped on every platform
+    if len > 0 && len >= mmap_threshold {
+        match map_private_copy(file) {
+            Ok(map) => {
+                log::debug!("Memory-mapped {} ({} bytes) for parsing", path, len);
+                return Ok(SourceBytes::Mapped(map));
+            }
+            Err(e) => log::warn!("Failed to memory-map {}, reading instead: {}", path, e),
+        }
+    }
+    fs::read(path).map(SourceBytes::Owned)
+}
+
+/// Parse `source` through tree-sitter's callback input in fixed-size chunks
+pub fn parse_bytes(parser: &mut Parser, source: &[u8]) -> Option<Tree> {
+    parser.parse_with(
+        &mut |offset: usize, _: Point| {
+            let start = offset.min(source.len());
+            let end = (start + PARSE_CHUNK_BYTES).min(source.len());
+            &source[start..end]
+        },
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_source_threshold() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("gen.ts");
+        fs::write(&path, "export const a = 1;\n".repeat(100)).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let small = read_source(&path, DEFAULT_MMAP_THRESHOLD).unwrap();
+        assert!(matches!(small, SourceBytes::Owned(_)));
+        let mapped = read_source(&path, 1024).unwrap();
+        assert!(matches!(mapped, SourceBytes::Mapped(_)));
+        assert_eq!(&*small, &*mapped);
+
+        // Truncating the original leaves the mapped copy readable
+        fs::write(&path, "").unwrap();
+        assert_eq!(mapped.len(), small.len());
+        assert!(mapped.ends_with(b"export const a = 1;\n"));
+    }
+
+    #[test]
+    fn test_parse_bytes_across_chunks() {
+        // Larger than one chunk so the parser has to request more input
+        let source = "fn f() {}\n".repeat(PARSE_CHUNK_BYTES / 5);
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        let tree = parse_bytes(&mut parser, source.as_bytes()).unwrap();
+        let root = tree.root_node();
+        assert!(!root.has_error());
+        assert_eq!(root.child_count(), PARSE_CHUNK_BYTES / 5);
+        assert_eq!(root.end_byte(), source.len());
+    }
+}
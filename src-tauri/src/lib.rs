@@ -1,27 +1,113 @@
 mod analytics;
+mod api_routes;
 mod archive;
+mod asset_inventory;
+mod audit_log;
 mod background_tasks;
+mod capture_kinds;
 mod code_navigation;
+mod code_review;
+mod component_tree;
+mod compression_analytics;
+mod conflict_regions;
 mod constants;
+mod context_estimate;
+mod context_recovery;
+mod conventions;
+mod conversation_synopsis;
+mod css_outline;
+mod custom_commands;
 mod database;
+mod declaration_files;
 mod device_id;
 mod directory_tree;
+mod doc_generation;
 mod dock_menu;
+mod download_manager;
+mod editor_context;
+mod enclosing_symbols;
+mod env_usage;
+mod file_checkpoints;
+mod file_leases;
+mod file_merge;
+mod file_metadata;
 mod file_search;
 mod file_watcher;
+mod fim_completion;
+mod folding_ranges;
 mod git;
 mod glob;
+mod gradle_outline;
+mod grammar_cache;
+mod graphql_outline;
+mod hardware_profile;
+mod highlight_tokens;
+mod history_search;
+mod html_outline;
+mod http_client;
 mod http_proxy;
+mod imports;
+mod index_export;
+mod index_maintenance;
+mod inline_edit;
+mod language_config;
+mod large_file;
+mod line_endings;
 mod lint;
 mod list_files;
 mod lsp;
+mod markdown_outline;
+mod model_manager;
+mod next_edit;
 mod oauth_callback_server;
+mod offline_mode;
+mod output_guardrails;
+mod patch_minimize;
+mod path_policy;
+mod path_utils;
+mod plugin_host;
+mod position_encoding;
+mod precise_index;
+mod prompt_templates;
+mod protobuf_outline;
+mod provider_client;
+mod qualified_names;
+mod query_debug;
+mod safe_delete;
+mod schema_drift;
+mod scratchpad;
 mod script_executor;
+mod script_tools;
 mod search;
+mod selection_ranges;
+mod session_fork;
+mod session_tagging;
+mod stacktrace;
+mod stream_write;
+mod string_index;
+mod summary_batch;
+mod summary_policy;
+mod symbol_context;
+mod symbol_docs;
+mod symbol_match;
+mod symbol_priority;
+mod syntax_check;
+mod telemetry;
 mod terminal;
+mod test_scaffold;
+mod text_diff;
+mod text_slice;
+mod toml_outline;
+mod type_error_context;
+mod vue_sfc;
 mod walker;
+mod watch_mode;
 mod websocket;
 mod window_manager;
+mod workflow_recorder;
+mod workspace_state;
+mod workspace_stats;
+mod yaml_outline;
 
 use analytics::AnalyticsState;
 use archive::{
@@ -29,20 +115,30 @@ use archive::{
 };
 use code_navigation::{CodeNavState, CodeNavigationService};
 use database::Database;
+use editor_context::EditorContextState;
+use file_leases::LeaseState;
+use file_merge::ReadSnapshotState;
 use file_watcher::FileWatcher;
+use fim_completion::FimState;
+use next_edit::EditHistoryState;
+use scratchpad::ScratchpadState;
 use script_executor::{ScriptExecutionRequest, ScriptExecutionResult, ScriptExecutor};
 use serde::{Deserialize, Serialize};
 use std::process::Stdio;
 use std::sync::OnceLock;
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant, SystemTime};
+use stream_write::StreamWriteState;
+use string_index::StringIndexState;
 use tauri::{AppHandle, Emitter, Manager, State, WindowEvent};
 use tokio::io::BufReader;
 use tokio::process::Command as TokioCommand;
 use tokio::sync::Mutex as TokioMutex;
 use tokio::time::Duration as TokioDuration;
+use watch_mode::WatchModeState;
 use websocket::WebSocketState;
 use window_manager::{create_window, WindowRegistry, WindowState};
+use workspace_state::WorkspaceState;
 
 // Global app handle for dock menu and other cross-module access
 // This is initialized once during app setup and provides safe static access to the AppHandle
@@ -632,6 +728,16 @@ pub fn run() {
             window_registry: WindowRegistry::new(),
         })
         .manage(AnalyticsState::new())
+        .manage(ScratchpadState::default())
+        .manage(ReadSnapshotState::default())
+        .manage(LeaseState::default())
+        .manage(WatchModeState::default())
+        .manage(EditorContextState::default())
+        .manage(FimState::default())
+        .manage(EditHistoryState::default())
+        .manage(StringIndexState::default())
+        .manage(StreamWriteState::default())
+        .manage(WorkspaceState::default())
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
             if let Err(e) = app.emit("single-instance", Payload { args: argv, cwd }) {
@@ -654,6 +760,7 @@ pub fn run() {
             app.manage(ws_state);
             let code_nav_state = CodeNavState(RwLock::new(CodeNavigationService::new()));
             app.manage(code_nav_state);
+            index_maintenance::start_background_maintenance(app.handle().clone());
             let lsp_state = lsp::LspState(tokio::sync::Mutex::new(lsp::LspRegistry::new()));
             app.manage(lsp_state);
 
@@ -754,6 +861,7 @@ pub fn run() {
             git::git_get_line_changes,
             git::git_get_all_file_diffs,
             git::git_get_raw_diff_text,
+            git::git_get_range_diff_text,
             git::git_get_default_worktree_root,
             git::git_acquire_worktree,
             git::git_release_worktree,
@@ -780,7 +888,11 @@ pub fn run() {
             terminal::pty_kill,
             code_navigation::code_nav_index_file,
             code_navigation::code_nav_index_files_batch,
+            code_navigation::code_nav_index_paths,
             code_navigation::code_nav_find_definition,
+            code_navigation::code_nav_search_symbols,
+            code_navigation::code_nav_get_lang_from_path,
+            code_navigation::code_nav_load_language_config,
             code_navigation::code_nav_find_references_hybrid,
             code_navigation::code_nav_clear_file,
             code_navigation::code_nav_clear_all,
@@ -809,10 +921,167 @@ pub fn run() {
             lsp::lsp_download_server,
             oauth_callback_server::start_oauth_callback_server,
             device_id::get_device_id,
+            prompt_templates::prompt_list_templates,
+            prompt_templates::prompt_render,
+            custom_commands::custom_command_list,
+            custom_commands::custom_command_execute,
+            session_fork::session_fork,
+            session_fork::session_list_forks,
+            file_checkpoints::session_record_checkpoint,
+            file_checkpoints::session_list_checkpoints,
+            file_checkpoints::session_read_checkpoint,
+            history_search::search_history,
+            history_search::migrate_history_index,
+            history_search::rebuild_history_index,
+            session_tagging::session_generate_tags,
+            session_tagging::session_list_by_tag,
+            session_tagging::session_list_tags,
+            conventions::analyze_project_conventions,
+            syntax_check::check_code_syntax,
+            scratchpad::scratch_write,
+            scratchpad::scratch_read,
+            scratchpad::scratch_list,
+            scratchpad::scratch_diff,
+            scratchpad::scratch_validate,
+            scratchpad::scratch_summarize,
+            scratchpad::scratch_promote,
+            scratchpad::scratch_discard,
+            file_merge::merge_record_read,
+            file_merge::merge_write_file,
+            file_merge::merge_three_way,
+            file_merge::merge_clear_session,
+            file_leases::lease_acquire,
+            file_leases::lease_release,
+            file_leases::lease_set_dirty,
+            file_leases::lease_list,
+            file_leases::lease_check_write,
+            file_leases::lease_guarded_write,
+            watch_mode::watch_mode_start,
+            watch_mode::watch_mode_stop,
+            watch_mode::watch_mode_status,
+            editor_context::editor_context_set_open_files,
+            editor_context::editor_context_update_cursor,
+            editor_context::editor_context_get,
+            editor_context::editor_context_clear,
+            editor_context::editor_context_assemble,
+            editor_context::editor_find_definition,
+            inline_edit::inline_edit,
+            fim_completion::fim_complete,
+            next_edit::edit_history_record,
+            next_edit::edit_history_list,
+            next_edit::edit_history_clear,
+            next_edit::predict_next_edits,
+            code_review::review_diff,
+            doc_generation::generate_docs,
+            test_scaffold::scaffold_tests,
+            type_error_context::build_type_error_context,
+            stacktrace::resolve_stacktrace,
+            string_index::string_index_file,
+            string_index::string_index_clear_file,
+            string_index::string_index_build,
+            string_index::find_string_origin,
+            env_usage::scan_env_usage,
+            api_routes::get_api_routes,
+            component_tree::get_component_tree,
+            schema_drift::check_schema_drift,
+            asset_inventory::inventory_assets,
+            workspace_stats::get_workspace_stats,
+            line_endings::get_line_ending_info,
+            line_endings::convert_line_endings,
+            position_encoding::convert_positions,
+            index_maintenance::run_index_maintenance,
+            workspace_state::workspace_open,
+            workspace_state::workspace_release,
+            workspace_state::workspace_list,
+            workspace_state::workspace_get_setting,
+            workspace_state::workspace_set_setting,
+            summary_batch::summarize_code_batch,
+            summary_batch::get_summary_metrics,
+            symbol_context::get_symbol_context,
+            capture_kinds::introspect_queries,
+            summary_policy::get_summary_policies,
+            summary_policy::set_summary_policies,
+            code_navigation::preview_summary,
+            compression_analytics::record_compression_stats,
+            compression_analytics::get_compression_report,
+            grammar_cache::warm_up_grammars,
+            grammar_cache::get_grammar_status,
+            file_metadata::stat_file,
+            file_metadata::chmod_executable,
+            safe_delete::delete_path,
+            safe_delete::restore_deleted,
+            safe_delete::list_deleted,
+            archive::inspect_archive,
+            archive::extract_archive,
+            download_manager::download_file,
+            model_manager::list_model_sources,
+            model_manager::download_model,
+            model_manager::list_models,
+            model_manager::verify_model,
+            model_manager::delete_model,
+            hardware_profile::get_hardware_profile,
+            offline_mode::get_offline_status,
+            offline_mode::set_offline_mode,
+            http_client::get_network_settings,
+            http_client::set_network_settings,
+            audit_log::get_audit_settings,
+            audit_log::set_audit_settings,
+            audit_log::record_model_call,
+            audit_log::list_audit_records,
+            audit_log::export_audit_log,
+            telemetry::get_telemetry_status,
+            telemetry::set_telemetry_enabled,
+            telemetry::record_feature_usage,
+            telemetry::record_timing_sample,
+            telemetry::get_telemetry_preview,
+            telemetry::export_telemetry,
+            telemetry::upload_telemetry,
+            plugin_host::list_plugins,
+            plugin_host::list_plugin_tools,
+            plugin_host::grant_plugin_capabilities,
+            plugin_host::invoke_plugin_tool,
+            script_tools::list_script_tools,
+            script_tools::invoke_script_tool,
+            workflow_recorder::start_workflow_recording,
+            workflow_recorder::record_workflow_step,
+            workflow_recorder::stop_workflow_recording,
+            workflow_recorder::save_workflow,
+            workflow_recorder::list_workflows,
+            workflow_recorder::delete_workflow,
+            workflow_recorder::replay_workflow,
+            conflict_regions::get_conflict_regions,
+            conflict_regions::resolve_conflict_region,
+            stream_write::stream_write_begin,
+            stream_write::stream_write_chunk,
+            stream_write::stream_write_status,
+            stream_write::stream_write_finish,
+            stream_write::stream_write_abort,
+            patch_minimize::minimize_rewrite,
+            patch_minimize::minimize_file_rewrite,
+            imports::ensure_import,
+            index_export::code_nav_export_index,
+            precise_index::code_nav_import_precise,
+            precise_index::code_nav_precise_definition,
+            precise_index::code_nav_precise_references,
+            query_debug::debug_query,
+            highlight_tokens::get_highlight_tokens,
+            folding_ranges::get_folding_ranges,
+            selection_ranges::expand_selection,
+            enclosing_symbols::get_enclosing_symbols,
+            symbol_docs::get_symbol_docs,
+            context_estimate::estimate_context,
+            conversation_synopsis::summarize_conversation,
+            context_recovery::complete_with_recovery,
+            output_guardrails::validate_agent_actions,
         ])
         .on_window_event(|window, event| {
             // Clean up resources when main window is destroyed
             if let WindowEvent::Destroyed = event {
+                if let Some(workspaces) = window.try_state::<WorkspaceState>() {
+                    if let Err(e) = workspaces.release_window(window.label()) {
+                        log::error!("Failed to release workspaces for {}: {}", window.label(), e);
+                    }
+                }
                 if window.label() == "main" {
                     log::info!("Main window destroyed, cleaning up resources");
 
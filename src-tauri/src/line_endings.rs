@@ -0,0 +1,135 @@
+// Line-ending normalization
+//
+// Files from Windows contributors use CRLF (sometimes mixed with LF). Line math and
+// summaries work on LF-normalized text; the detected style is reported alongside so
+// writes can convert back and leave the file's line endings untouched.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    /// Classic Mac OS line endings (lone `\r`)
+    Cr,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineEndingInfo {
+    /// Most common style; LF when the text has no line breaks or on ties
+    pub dominant: LineEnding,
+    /// True when more than one style occurs
+    pub mixed: bool,
+    pub lf: usize,
+    pub crlf: usize,
+    pub cr: usize,
+}
+
+/// Count the line-ending styles used in `text`
+pub fn detect(text: &str) -> LineEndingInfo {
+    let (mut lf, mut crlf, mut cr) = (0, 0, 0);
+    let mut bytes = text.bytes().peekable();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'\r' if bytes.peek() == Some(&b'\n') => {
+                bytes.next();
+                crlf += 1;
+            }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => {}
+        }
+    }
+
+    let dominant = if crlf > lf && crlf >= cr {
+        LineEnding::Crlf
+    } else if cr > lf && cr > crlf {
+        LineEnding::Cr
+    } else {
+        LineEnding::Lf
+    };
+    let styles_used = [lf, crlf, cr].iter().filter(|&&n| n > 0).count();
+
+    LineEndingInfo {
+        dominant,
+        mixed: styles_used > 1,
+        lf,
+        crlf,
+        cr,
+    }
+}
+
+/// Convert all line endings in `text` to `\n`
+pub fn normalize(text: &str) -> String {
+    if !text.contains('\r') {
+        return text.to_string();
+    }
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Normalize `text` and report its original style
+pub fn normalize_with_info(text: &str) -> (String, LineEndingInfo) {
+    (normalize(text), detect(text))
+}
+
+/// Convert `text` (any line endings) to `ending`
+pub fn convert(text: &str, ending: LineEnding) -> String {
+    let normalized = normalize(text);
+    match ending {
+        LineEnding::Lf => normalized,
+        _ => normalized.replace('\n', ending.as_str()),
+    }
+}
+
+/// Line-ending style of the file at `path`
+#[tauri::command]
+pub fn get_line_ending_info(path: String) -> Result<LineEndingInfo, String> {
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Ok(detect(&content))
+}
+
+/// Convert `content` to `ending` before it is written
+#[tauri::command]
+pub fn convert_line_endings(content: String, ending: LineEnding) -> String {
+    convert(&content, ending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let info = detect("a\r\nb\r\nc\n");
+        assert_eq!(info.dominant, LineEnding::Crlf);
+        assert!(info.mixed);
+        assert_eq!((info.lf, info.crlf, info.cr), (1, 2, 0));
+
+        let info = detect("a\nb\n");
+        assert_eq!(info.dominant, LineEnding::Lf);
+        assert!(!info.mixed);
+
+        assert_eq!(detect("no breaks").dominant, LineEnding::Lf);
+        assert_eq!(detect("a\rb\r").dominant, LineEnding::Cr);
+    }
+
+    #[test]
+    fn test_normalize_and_convert() {
+        assert_eq!(normalize("a\r\nb\rc\n"), "a\nb\nc\n");
+        assert_eq!(convert("a\nb\r\n", LineEnding::Crlf), "a\r\nb\r\n");
+        assert_eq!(convert("a\r\nb\r\n", LineEnding::Lf), "a\nb\n");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::constants::is_binary_extension;
+use crate::path_utils;
 use crate::walker::{WalkerConfig, WorkspaceWalker};
 use ignore::{WalkParallel, WalkState};
 use std::collections::BTreeMap;
@@ -10,10 +11,6 @@ use std::sync::Arc;
 /// Default maximum number of files to return
 const DEFAULT_MAX_FILES: usize = 1000;
 
-fn normalize_seps(path: &Path) -> String {
-    path.to_string_lossy().replace('\\', "/")
-}
-
 #[tauri::command]
 pub fn list_project_files(
     directory_path: String,
@@ -79,7 +76,7 @@ pub fn list_project_files(
                         Err(_) => path.as_path(),
                     };
                     let parent = rel.parent().unwrap_or(Path::new(""));
-                    let group_key = normalize_seps(parent);
+                    let group_key = path_utils::path_to_slash(parent);
                     let name = entry.file_name().to_string_lossy().to_string();
 
                     // Increment counter and send tuple to collector
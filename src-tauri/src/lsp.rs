@@ -6,8 +6,9 @@
 //
 // LSP servers are automatically downloaded to ~/.talkcody/lsp-servers/
 
+use crate::http_client;
+use crate::offline_mode;
 use flate2::read::GzDecoder;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::io::Read;
@@ -343,7 +344,7 @@ async fn download_rust_analyzer(app: &AppHandle) -> Result<PathBuf, String> {
     );
 
     // Download the file
-    let client = Client::new();
+    let client = http_client::client()?;
     let response = client
         .get(&download_url)
         .send()
@@ -1187,6 +1188,7 @@ pub fn lsp_get_server_status(language: String) -> Result<LspServerStatus, String
 #[tauri::command]
 pub async fn lsp_download_server(app: AppHandle, language: String) -> Result<String, String> {
     log::info!("Downloading LSP server for: {}", language);
+    offline_mode::ensure_online("LSP server download")?;
 
     match language.as_str() {
         "rust" => {
@@ -0,0 +1,278 @@
+// Markdown document summaries
+//
+// Design docs and READMEs are read for their structure: what sections exist and what
+// each one is about. The summary keeps every heading, the first sentence of the text
+// that opens each section (or its first list item), and one line per fenced code block
+// with its language and length. Front matter, tables, HTML comments and the rest of
+// the prose are dropped. Markdown is simple enough at this level that lines are scanned
+// directly instead of parsed.
+
+use crate::code_navigation::CodeSummary;
+use crate::text_slice::slice_to;
+
+/// Sentences longer than this many bytes are cut
+const MAX_SENTENCE_LEN: usize = 200;
+
+fn heading_level(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    let rest = &trimmed[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with([' ', '\t'])))
+        .then_some(level)
+}
+
+/// Level of a setext underline (`===` for 1, `---` for 2)
+fn setext_level(line: &str) -> Option<usize> {
+    let trimmed = line.trim();
+    if trimmed.len() < 2 {
+        return None;
+    }
+    if trimmed.chars().all(|c| c == '=') {
+        Some(1)
+    } else if trimmed.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// The fence marker (``` or ~~~, possibly longer) opening a code block
+fn fence(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|&c| c == marker).count();
+    (len >= 3).then(|| &trimmed[..len])
+}
+
+fn is_list_item(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = trimmed.strip_prefix(bullet) {
+            return Some(rest);
+        }
+    }
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        let rest = &trimmed[digits..];
+        if let Some(item) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return Some(item);
+        }
+    }
+    None
+}
+
+/// Text up to the end of the first sentence
+fn first_sentence(text: &str) -> String {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+        let at_break = chars
+            .get(i + 1)
+            .is_none_or(|(_, next)| next.is_whitespace());
+        // `e.g.` and `i.e.` do not end a sentence
+        let abbreviation = text[..offset].ends_with("e.g") || text[..offset].ends_with("i.e");
+        if at_break && !abbreviation {
+            return text[..offset + c.len_utf8()].to_string();
+        }
+    }
+    text.to_string()
+}
+
+fn truncate(sentence: &str) -> String {
+    if sentence.len() <= MAX_SENTENCE_LEN {
+        return sentence.to_string();
+    }
+    format!("{}…", slice_to(sentence, MAX_SENTENCE_LEN))
+}
+
+/// Heading, lead sentence and code block outline of a Markdown document
+pub fn markdown_outline(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+
+    // YAML front matter
+    if lines.first().is_some_and(|l| l.trim_end() == "---") {
+        if let Some(end) = lines.iter().skip(1).position(|l| l.trim_end() == "---") {
+            i = end + 2;
+        }
+    }
+
+    // Whether the current section still needs its lead sentence
+    let mut wants_lead = true;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+
+        if let Some(marker) = fence(line) {
+            let language = line.trim_start()[marker.len()..].trim();
+            let body_start = i + 1;
+            let mut end = body_start;
+            while end < lines.len() && !lines[end].trim_start().starts_with(marker) {
+                end += 1;
+            }
+            out.push(format!(
+                "{}{} … ({} lines)",
+                marker,
+                language,
+                end - body_start
+            ));
+            i = end + 1;
+            continue;
+        }
+        if heading_level(line).is_some() {
+            out.push(trimmed.to_string());
+            wants_lead = true;
+            i += 1;
+            continue;
+        }
+        if !trimmed.is_empty() {
+            if let Some(level) = lines.get(i + 1).and_then(|next| setext_level(next)) {
+                out.push(format!("{} {}", "#".repeat(level), trimmed));
+                wants_lead = true;
+                i += 2;
+                continue;
+            }
+        }
+        if trimmed.is_empty() || trimmed.starts_with("<!--") || trimmed.starts_with('|') {
+            // HTML comments and tables are skipped whole
+            if trimmed.starts_with("<!--") {
+                while i < lines.len() && !lines[i].contains("-->") {
+                    i += 1;
+                }
+            }
+            i += 1;
+            continue;
+        }
+
+        // A paragraph, list item or quote runs until a blank line or another block
+        let item = is_list_item(line);
+        let mut paragraph = vec![item.unwrap_or(trimmed).trim_start_matches('>').trim()];
+        i += 1;
+        while i < lines.len() {
+            let next = lines[i];
+            if next.trim().is_empty()
+                || heading_level(next).is_some()
+                || fence(next).is_some()
+                || is_list_item(next).is_some()
+            {
+                break;
+            }
+            paragraph.push(next.trim().trim_start_matches('>').trim());
+            i += 1;
+        }
+        if wants_lead {
+            let sentence = truncate(&first_sentence(&paragraph.join(" ")));
+            out.push(match item {
+                Some(_) => format!("- {}", sentence),
+                None => sentence,
+            });
+            wants_lead = false;
+        }
+    }
+    out.join("\n")
+}
+
+pub fn summarize_markdown(content: &str) -> CodeSummary {
+    let original_lines = content.lines().count();
+    let outline = markdown_outline(content);
+    if outline.is_empty() {
+        return CodeSummary::unchanged(
+            content.to_string(),
+            original_lines,
+            "markdown".to_string(),
+            "no text",
+        );
+    }
+    CodeSummary {
+        success: true,
+        summary: format!(
+            "[COMPRESSED: Original {} lines → headings, first sentences and code block outlines]\n\n{}",
+            original_lines, outline
+        ),
+        original_lines,
+        lang_id: "markdown".to_string(),
+        truncated: None,
+        skipped_reason: None,
+        omitted_symbols: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_outline() {
+        let doc = r#"---
+title: Index design
+---
+
+# Index design
+
+The index stores definitions only. References are searched on demand,
+e.g. with ripgrep.
+
+<!--
+TODO: diagrams
+-->
+
+## Storage
+Files are written atomically! Readers never see a partial index.
+
+```rust
+fn save() {}
+fn load() {}
+```
+
+| field | type |
+| ----- | ---- |
+
+Migration
+---------
+
+- Bump the version. Then rewrite.
+- Add a test.
+
+~~~
+plain
+~~~
+"#;
+        assert_eq!(
+            markdown_outline(doc),
+            [
+                "# Index design",
+                "The index stores definitions only.",
+                "## Storage",
+                "Files are written atomically!",
+                "```rust … (2 lines)",
+                "## Migration",
+                "- Bump the version.",
+                "~~~ … (1 lines)",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_summarize_markdown() {
+        let long = format!("# Notes\n\n{} end\n", "word ".repeat(60));
+        let result = summarize_markdown(&long);
+        assert!(result.success);
+        assert!(result
+            .summary
+            .starts_with("[COMPRESSED: Original 3 lines → headings"));
+        assert!(result.summary.ends_with('…'));
+
+        assert_eq!(
+            first_sentence("Use e.g. this. Then that."),
+            "Use e.g. this."
+        );
+        assert!(!summarize_markdown("\n\n").success);
+    }
+}
@@ -0,0 +1,444 @@
+// Local model management
+//
+// Local inference and embeddings run GGUF and ONNX models kept under
+// ~/.talkcody/models/<model id>/. The models that can be installed are configured in
+// ~/.talkcody/model-sources.json (id, URL, expected SHA-256); downloads go through
+// `download_manager`, so they resume, report progress and obey the download policy.
+// Installed models are recorded in ~/.talkcody/models/manifest.json with their hash,
+// which `verify_model` re-checks against the file on disk.
+
+use crate::download_manager::{self, DownloadPolicy, DownloadProgress};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+const SOURCES_FILE: &str = "model-sources.json";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Serializes read-modify-write cycles of the manifest
+static MANIFEST_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelFormat {
+    Gguf,
+    Onnx,
+}
+
+impl ModelFormat {
+    fn from_file_name(name: &str) -> Option<Self> {
+        let lower = name.to_lowercase();
+        if lower.ends_with(".gguf") {
+            Some(ModelFormat::Gguf)
+        } else if lower.ends_with(".onnx") {
+            Some(ModelFormat::Onnx)
+        } else {
+            None
+        }
+    }
+}
+
+/// A model that can be installed, from model-sources.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSource {
+    pub id: String,
+    pub name: Option<String>,
+    pub url: String,
+    /// Hex SHA-256 of the file; downloads without one are refused
+    pub sha256: String,
+    /// File name on disk; defaults to the last segment of the URL
+    pub file_name: Option<String>,
+    /// What the model is for, e.g. "embeddings" or "completion"
+    pub purpose: Option<String>,
+    pub size_bytes: Option<u64>,
+}
+
+impl ModelSource {
+    fn file_name(&self) -> String {
+        self.file_name.clone().unwrap_or_else(|| {
+            let path = self.url.split(['?', '#']).next().unwrap_or(&self.url);
+            path.rsplit('/').next().unwrap_or(path).to_string()
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledModel {
+    pub id: String,
+    pub file_name: String,
+    pub format: ModelFormat,
+    pub sha256: String,
+    pub size_bytes: u64,
+    pub source_url: String,
+    pub purpose: Option<String>,
+    /// Milliseconds since the Unix epoch
+    pub installed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelEntry {
+    #[serde(flatten)]
+    pub model: InstalledModel,
+    pub path: String,
+    /// False when the file was removed outside the app
+    pub present: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInventory {
+    pub models_dir: String,
+    pub models: Vec<ModelEntry>,
+    /// Bytes used by everything under the models directory, partial downloads included
+    pub disk_usage_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVerification {
+    pub id: String,
+    pub valid: bool,
+    pub expected_sha256: String,
+    pub actual_sha256: Option<String>,
+    pub message: Option<String>,
+}
+
+fn get_talkcody_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody"))
+}
+
+fn get_models_dir() -> Result<PathBuf, String> {
+    Ok(get_talkcody_dir()?.join("models"))
+}
+
+pub fn load_sources(path: &Path) -> Result<Vec<ModelSource>, String> {
+    match fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str(&raw)
+            .map_err(|e| format!("Invalid model sources in {}: {}", path.display(), e)),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn read_manifest(models_dir: &Path) -> Vec<InstalledModel> {
+    fs::read_to_string(models_dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_manifest(models_dir: &Path, models: &[InstalledModel]) -> Result<(), String> {
+    fs::create_dir_all(models_dir)
+        .map_err(|e| format!("Failed to create {}: {}", models_dir.display(), e))?;
+    let json = serde_json::to_string_pretty(models).map_err(|e| e.to_string())?;
+    fs::write(models_dir.join(MANIFEST_FILE), json)
+        .map_err(|e| format!("Failed to write model manifest: {}", e))
+}
+
+/// Model ids become directory names
+fn validate_model_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id != "."
+        && id != ".."
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid model id: {}", id))
+    }
+}
+
+fn model_path(models_dir: &Path, model: &InstalledModel) -> PathBuf {
+    models_dir.join(&model.id).join(&model.file_name)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            return Ok(hex::encode(hasher.finalize()));
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+/// Record a downloaded model, replacing an earlier install of the same id
+pub fn register_model(models_dir: &Path, model: InstalledModel) -> Result<(), String> {
+    let _guard = MANIFEST_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut manifest = read_manifest(models_dir);
+    manifest.retain(|m| m.id != model.id);
+    manifest.push(model);
+    manifest.sort_by(|a, b| a.id.cmp(&b.id));
+    write_manifest(models_dir, &manifest)
+}
+
+pub fn inventory(models_dir: &Path) -> ModelInventory {
+    let models = read_manifest(models_dir)
+        .into_iter()
+        .map(|model| {
+            let path = model_path(models_dir, &model);
+            ModelEntry {
+                path: path.to_string_lossy().to_string(),
+                present: path.is_file(),
+                model,
+            }
+        })
+        .collect();
+    ModelInventory {
+        models_dir: models_dir.to_string_lossy().to_string(),
+        models,
+        disk_usage_bytes: dir_size(models_dir),
+    }
+}
+
+pub fn verify(models_dir: &Path, id: &str) -> Result<ModelVerification, String> {
+    let model = read_manifest(models_dir)
+        .into_iter()
+        .find(|m| m.id == id)
+        .ok_or_else(|| format!("Model {} is not installed", id))?;
+    let path = model_path(models_dir, &model);
+    let (actual, message) = match sha256_file(&path) {
+        Ok(actual) => (Some(actual), None),
+        Err(e) => (None, Some(e)),
+    };
+    let valid = actual
+        .as_deref()
+        .is_some_and(|a| download_manager::verify_sha256(a, &model.sha256).is_ok());
+    Ok(ModelVerification {
+        id: model.id,
+        valid,
+        expected_sha256: model.sha256,
+        actual_sha256: actual,
+        message: message.or_else(|| (!valid).then(|| "Checksum mismatch".to_string())),
+    })
+}
+
+/// Remove a model's files (including a partial download) and its manifest entry
+pub fn remove(models_dir: &Path, id: &str) -> Result<u64, String> {
+    validate_model_id(id)?;
+    let _guard = MANIFEST_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut manifest = read_manifest(models_dir);
+    let installed = manifest.iter().any(|m| m.id == id);
+    let dir = models_dir.join(id);
+    if !installed && !dir.exists() {
+        return Err(format!("Model {} is not installed", id));
+    }
+
+    let freed = dir_size(&dir);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)
+            .map_err(|e| format!("Failed to delete {}: {}", dir.display(), e))?;
+    }
+    manifest.retain(|m| m.id != id);
+    write_manifest(models_dir, &manifest)?;
+    log::info!("Deleted model {} ({} bytes)", id, freed);
+    Ok(freed)
+}
+
+// Tauri commands
+
+/// Models that can be installed, from ~/.talkcody/model-sources.json
+#[tauri::command]
+pub fn list_model_sources() -> Result<Vec<ModelSource>, String> {
+    load_sources(&get_talkcody_dir()?.join(SOURCES_FILE))
+}
+
+/// Download and verify a configured model. Progress is emitted as
+/// `download-progress` events with the model id as the download id.
+#[tauri::command]
+pub async fn download_model(app: AppHandle, model_id: String) -> Result<ModelEntry, String> {
+    validate_model_id(&model_id)?;
+    let source = list_model_sources()?
+        .into_iter()
+        .find(|s| s.id == model_id)
+        .ok_or_else(|| format!("No model source with id {}", model_id))?;
+    let file_name = source.file_name();
+    let format = ModelFormat::from_file_name(&file_name).ok_or_else(|| {
+        format!(
+            "Unsupported model format (expected .gguf or .onnx): {}",
+            file_name
+        )
+    })?;
+    if file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+        return Err(format!("Invalid model file name: {}", file_name));
+    }
+
+    let models_dir = get_models_dir()?;
+    let dest = models_dir.join(&model_id).join(&file_name);
+    let emit = |progress: &DownloadProgress| {
+        if let Err(e) = app.emit("download-progress", progress) {
+            log::error!("Failed to emit download progress: {}", e);
+        }
+    };
+    let outcome = download_manager::download(
+        &model_id,
+        &source.url,
+        &dest,
+        Some(source.sha256.as_str()),
+        &DownloadPolicy::load(),
+        &emit,
+    )
+    .await?;
+
+    let model = InstalledModel {
+        id: model_id,
+        file_name,
+        format,
+        sha256: outcome.sha256,
+        size_bytes: outcome.bytes,
+        source_url: source.url,
+        purpose: source.purpose,
+        installed_at: chrono::Utc::now().timestamp_millis(),
+    };
+    register_model(&models_dir, model.clone())?;
+    Ok(ModelEntry {
+        path: outcome.path,
+        present: true,
+        model,
+    })
+}
+
+/// Installed models and the disk space the models directory uses
+#[tauri::command]
+pub fn list_models() -> Result<ModelInventory, String> {
+    Ok(inventory(&get_models_dir()?))
+}
+
+/// Re-hash an installed model and compare it with the recorded checksum
+#[tauri::command]
+pub async fn verify_model(model_id: String) -> Result<ModelVerification, String> {
+    let models_dir = get_models_dir()?;
+    tauri::async_runtime::spawn_blocking(move || verify(&models_dir, &model_id))
+        .await
+        .map_err(|e| format!("Verification task failed: {}", e))?
+}
+
+/// Delete an installed model; returns the bytes freed
+#[tauri::command]
+pub fn delete_model(model_id: String) -> Result<u64, String> {
+    remove(&get_models_dir()?, &model_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn install(models_dir: &Path, id: &str, content: &[u8]) -> InstalledModel {
+        let model = InstalledModel {
+            id: id.to_string(),
+            file_name: "model.gguf".to_string(),
+            format: ModelFormat::Gguf,
+            sha256: sha256_file_of(content),
+            size_bytes: content.len() as u64,
+            source_url: "https://huggingface.co/org/repo/model.gguf".to_string(),
+            purpose: Some("embeddings".to_string()),
+            installed_at: 0,
+        };
+        fs::create_dir_all(models_dir.join(id)).unwrap();
+        fs::write(model_path(models_dir, &model), content).unwrap();
+        register_model(models_dir, model.clone()).unwrap();
+        model
+    }
+
+    fn sha256_file_of(content: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+        hex::encode(Sha256::digest(content))
+    }
+
+    #[test]
+    fn test_inventory_and_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let models_dir = temp_dir.path();
+        install(models_dir, "small", b"GGUF0123");
+        install(models_dir, "tiny", b"GGUF");
+        // A partial download counts towards disk use
+        fs::create_dir_all(models_dir.join("big")).unwrap();
+        fs::write(models_dir.join("big/model.gguf.part"), b"12").unwrap();
+
+        let listed = inventory(models_dir);
+        assert_eq!(listed.models.len(), 2);
+        assert!(listed.models.iter().all(|m| m.present));
+        let manifest_size = fs::metadata(models_dir.join(MANIFEST_FILE)).unwrap().len();
+        assert_eq!(listed.disk_usage_bytes, 8 + 4 + 2 + manifest_size);
+
+        assert_eq!(remove(models_dir, "small").unwrap(), 8);
+        assert_eq!(remove(models_dir, "big").unwrap(), 2);
+        assert_eq!(inventory(models_dir).models.len(), 1);
+        assert!(remove(models_dir, "small").is_err());
+        assert!(remove(models_dir, "..").is_err());
+    }
+
+    #[test]
+    fn test_verify_detects_corruption() {
+        let temp_dir = TempDir::new().unwrap();
+        let models_dir = temp_dir.path();
+        let model = install(models_dir, "m", b"GGUF weights");
+        assert!(verify(models_dir, "m").unwrap().valid);
+
+        fs::write(model_path(models_dir, &model), b"GGUF weightz").unwrap();
+        let result = verify(models_dir, "m").unwrap();
+        assert!(!result.valid);
+        assert!(result.actual_sha256.is_some());
+
+        fs::remove_file(model_path(models_dir, &model)).unwrap();
+        let result = verify(models_dir, "m").unwrap();
+        assert!(!result.valid);
+        assert!(result.actual_sha256.is_none());
+        assert!(verify(models_dir, "other").is_err());
+    }
+
+    #[test]
+    fn test_sources_and_file_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(SOURCES_FILE);
+        assert!(load_sources(&path).unwrap().is_empty());
+
+        fs::write(
+            &path,
+            r#"[{"id": "nomic-embed", "url": "https://huggingface.co/x/resolve/main/nomic.Q4.gguf?download=true", "sha256": "ab"}]"#,
+        )
+        .unwrap();
+        let sources = load_sources(&path).unwrap();
+        assert_eq!(sources[0].file_name(), "nomic.Q4.gguf");
+        assert_eq!(
+            ModelFormat::from_file_name("encoder.ONNX"),
+            Some(ModelFormat::Onnx)
+        );
+        assert_eq!(ModelFormat::from_file_name("weights.bin"), None);
+
+        fs::write(&path, "{").unwrap();
+        assert!(load_sources(&path).is_err());
+    }
+
+    #[test]
+    fn test_validate_model_id() {
+        assert!(validate_model_id("llama-3.2-1b_q4").is_ok());
+        assert!(validate_model_id("../x").is_err());
+        assert!(validate_model_id("").is_err());
+    }
+}
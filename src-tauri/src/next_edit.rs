@@ -0,0 +1,348 @@
+// Next-edit prediction from recent edit history
+//
+// The frontend reports each edit the user or agent makes. For the most recent edits we
+// resolve the enclosing definition and propose follow-up sites from the reference graph:
+// call sites of a just-changed function and sibling definitions with the same name
+// (other trait impls, overrides, interface implementations), so the UI can offer
+// "apply here too" jumps.
+
+use crate::code_navigation::{
+    get_language, resolve_nav, CodeNavState, CodeNavigationService, SymbolInfo,
+};
+use crate::inline_edit::enclosing_definition;
+use crate::workspace_state::WorkspaceState;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::sync::Mutex;
+use tauri::State;
+use tree_sitter::Parser;
+
+/// Edits remembered per session
+const MAX_HISTORY: usize = 50;
+/// Only the latest few edits drive predictions
+const RECENT_EDITS_CONSIDERED: usize = 5;
+const DEFAULT_PREDICTION_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditEvent {
+    pub path: String,
+    /// 1-based inclusive line range of the edit
+    pub start_line: u32,
+    pub end_line: u32,
+    /// Name of the definition the edit landed in, when one was found
+    pub symbol: Option<String>,
+    /// Line range of that definition
+    pub symbol_start_line: Option<u32>,
+    pub symbol_end_line: Option<u32>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditPrediction {
+    pub path: String,
+    pub line: u32,
+    pub column: u32,
+    pub symbol: String,
+    pub reason: String, // "call_site" or "sibling_definition"
+    pub score: f64,
+}
+
+/// Tauri state: session id -> recent edits, newest last
+#[derive(Default)]
+pub struct EditHistoryState(pub Mutex<HashMap<String, VecDeque<EditEvent>>>);
+
+/// Name and 1-based line range of the definition enclosing `line`
+pub fn enclosing_symbol(content: &str, lang_id: &str, line: u32) -> Option<(String, u32, u32)> {
+    let language = get_language(lang_id)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content, None)?;
+    let row = line.saturating_sub(1) as usize;
+    let mut node = enclosing_definition(tree.root_node(), row, row)?;
+
+    // Anonymous definitions (arrow functions) take the name of their declarator
+    loop {
+        if let Some(name) = node.child_by_field_name("name") {
+            let name = name.utf8_text(content.as_bytes()).ok()?.to_string();
+            return Some((
+                name,
+                node.start_position().row as u32 + 1,
+                node.end_position().row as u32 + 1,
+            ));
+        }
+        node = node.parent()?;
+    }
+}
+
+fn within(event: &EditEvent, path: &str, line: u32) -> bool {
+    if event.path != path {
+        return false;
+    }
+    let (start, end) = match (event.symbol_start_line, event.symbol_end_line) {
+        (Some(start), Some(end)) => (start, end),
+        _ => (event.start_line, event.end_line),
+    };
+    line >= start && line <= end
+}
+
+/// Score candidate sites for each recent edit. `candidates` pairs an edit index (0 =
+/// newest) with the call sites and sibling definitions found for its symbol.
+pub fn rank_predictions(
+    recent: &[EditEvent],
+    candidates: &[(usize, Vec<SymbolInfo>, Vec<SymbolInfo>)],
+    limit: usize,
+) -> Vec<EditPrediction> {
+    let mut seen: HashSet<(String, u32)> = HashSet::new();
+    let mut predictions = Vec::new();
+
+    for (edit_idx, call_sites, siblings) in candidates {
+        let Some(edit) = recent.get(*edit_idx) else {
+            continue;
+        };
+        let Some(symbol) = &edit.symbol else {
+            continue;
+        };
+        let recency = 1.0 / (*edit_idx as f64 + 1.0);
+
+        let sites = call_sites
+            .iter()
+            .map(|s| (s, "call_site", 1.0))
+            .chain(siblings.iter().map(|s| (s, "sibling_definition", 0.8)));
+        for (site, reason, weight) in sites {
+            // Skip anything the user already touched
+            if recent
+                .iter()
+                .any(|e| within(e, &site.file_path, site.start_line))
+            {
+                continue;
+            }
+            if !seen.insert((site.file_path.clone(), site.start_line)) {
+                continue;
+            }
+            predictions.push(EditPrediction {
+                path: site.file_path.clone(),
+                line: site.start_line,
+                column: site.start_column,
+                symbol: symbol.clone(),
+                reason: reason.to_string(),
+                score: recency * weight,
+            });
+        }
+    }
+
+    predictions.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.line.cmp(&b.line))
+    });
+    predictions.truncate(limit);
+    predictions
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn edit_history_record(
+    state: State<'_, EditHistoryState>,
+    session_id: String,
+    path: String,
+    start_line: u32,
+    end_line: u32,
+) -> Result<EditEvent, String> {
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&path).unwrap_or_default();
+    let symbol = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| enclosing_symbol(&content, &lang_id, start_line));
+
+    let event = EditEvent {
+        path,
+        start_line,
+        end_line: end_line.max(start_line),
+        symbol: symbol.as_ref().map(|(name, _, _)| name.clone()),
+        symbol_start_line: symbol.as_ref().map(|(_, start, _)| *start),
+        symbol_end_line: symbol.as_ref().map(|(_, _, end)| *end),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    };
+
+    let mut sessions = state.0.lock().map_err(|e| e.to_string())?;
+    let history = sessions.entry(session_id).or_default();
+    history.push_back(event.clone());
+    while history.len() > MAX_HISTORY {
+        history.pop_front();
+    }
+    Ok(event)
+}
+
+#[tauri::command]
+pub fn edit_history_list(
+    state: State<'_, EditHistoryState>,
+    session_id: String,
+) -> Result<Vec<EditEvent>, String> {
+    let sessions = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(sessions
+        .get(&session_id)
+        .map(|h| h.iter().rev().cloned().collect())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn edit_history_clear(
+    state: State<'_, EditHistoryState>,
+    session_id: String,
+) -> Result<(), String> {
+    let mut sessions = state.0.lock().map_err(|e| e.to_string())?;
+    sessions.remove(&session_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn predict_next_edits(
+    state: State<'_, EditHistoryState>,
+    nav_state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    session_id: String,
+    root_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<EditPrediction>, String> {
+    // Newest first
+    let recent: Vec<EditEvent> = {
+        let sessions = state.0.lock().map_err(|e| e.to_string())?;
+        sessions
+            .get(&session_id)
+            .map(|h| {
+                h.iter()
+                    .rev()
+                    .take(RECENT_EDITS_CONSIDERED)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let nav = resolve_nav(&nav_state, &workspaces, workspace_id.as_deref())?;
+    let service = nav
+        .read()
+        .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+    let mut candidates = Vec::new();
+    let mut looked_up = HashSet::new();
+    for (idx, edit) in recent.iter().enumerate() {
+        let Some(symbol) = &edit.symbol else {
+            continue;
+        };
+        if !looked_up.insert(symbol.clone()) {
+            continue;
+        }
+        let lang_id = CodeNavigationService::get_lang_id_from_path(&edit.path).unwrap_or_default();
+        let lang_family = CodeNavigationService::get_lang_family(&lang_id);
+
+        let call_sites = service.find_references_hybrid(symbol, lang_family, &root_path);
+        let siblings: Vec<SymbolInfo> = service
+            .find_definition(symbol, lang_family)
+            .into_iter()
+            .filter(|d| !within(edit, &d.file_path, d.start_line))
+            .collect();
+        // Definitions also match the reference search; report them as siblings only
+        let call_sites = call_sites
+            .into_iter()
+            .filter(|r| {
+                !siblings
+                    .iter()
+                    .any(|d| d.file_path == r.file_path && d.start_line == r.start_line)
+            })
+            .collect();
+        candidates.push((idx, call_sites, siblings));
+    }
+
+    Ok(rank_predictions(
+        &recent,
+        &candidates,
+        limit.unwrap_or(DEFAULT_PREDICTION_LIMIT),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site(path: &str, line: u32) -> SymbolInfo {
+        SymbolInfo {
+            name: "parse".to_string(),
+            kind: "reference".to_string(),
+            file_path: path.to_string(),
+            lang_family: "rust".to_string(),
+            start_line: line,
+            start_column: 5,
+            end_line: line,
+            end_column: 10,
+            qualified_name: None,
+        }
+    }
+
+    fn edit(path: &str, symbol: &str, start: u32, end: u32) -> EditEvent {
+        EditEvent {
+            path: path.to_string(),
+            start_line: start,
+            end_line: start,
+            symbol: Some(symbol.to_string()),
+            symbol_start_line: Some(start),
+            symbol_end_line: Some(end),
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_enclosing_symbol() {
+        let content =
+            "fn helper() {}\n\nfn parse(input: &str) -> i32 {\n    let x = 1;\n    x\n}\n";
+        let (name, start, end) = enclosing_symbol(content, "rust", 4).unwrap();
+        assert_eq!(name, "parse");
+        assert_eq!((start, end), (3, 6));
+        assert!(enclosing_symbol("let x = 1;\n", "rust", 1).is_none());
+    }
+
+    #[test]
+    fn test_enclosing_symbol_arrow_function() {
+        let content = "const handler = (req) => {\n  return req.body;\n};\n";
+        let (name, _, _) = enclosing_symbol(content, "typescript", 2).unwrap();
+        assert_eq!(name, "handler");
+    }
+
+    #[test]
+    fn test_rank_predictions() {
+        let recent = vec![edit("/src/a.rs", "parse", 10, 20)];
+        let candidates = vec![(
+            0,
+            vec![
+                site("/src/b.rs", 3),
+                site("/src/a.rs", 15), // inside the edited function
+                site("/src/b.rs", 3),  // duplicate
+            ],
+            vec![site("/src/c.rs", 40)],
+        )];
+        let predictions = rank_predictions(&recent, &candidates, 10);
+        assert_eq!(predictions.len(), 2);
+        assert_eq!(predictions[0].path, "/src/b.rs");
+        assert_eq!(predictions[0].reason, "call_site");
+        assert_eq!(predictions[1].reason, "sibling_definition");
+        assert!(predictions[0].score > predictions[1].score);
+    }
+
+    #[test]
+    fn test_rank_predictions_prefers_recent_edits() {
+        let recent = vec![
+            edit("/src/a.rs", "newer", 1, 5),
+            edit("/src/a.rs", "older", 10, 20),
+        ];
+        let candidates = vec![
+            (1, vec![site("/src/old.rs", 1)], vec![]),
+            (0, vec![site("/src/new.rs", 1)], vec![]),
+        ];
+        let predictions = rank_predictions(&recent, &candidates, 1);
+        assert_eq!(predictions.len(), 1);
+        assert_eq!(predictions[0].symbol, "newer");
+    }
+}
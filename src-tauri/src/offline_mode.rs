@@ -0,0 +1,315 @@
+// Offline mode
+//
+// A global switch for working without network access. While it is on, backend model
+// calls go to the configured local provider (e.g. a llama.cpp or Ollama server on
+// localhost) instead of the remote one, loopback requests keep working, and anything
+// else that needs the network (downloads, remote fetches, LSP server installs) is
+// refused up front instead of waiting for a timeout. Refusals come back as an
+// `OfflineError`, which converts to a JSON string (`{"offline_error": {...}}`) so the
+// frontend can tell them apart from real failures and switch to a local fallback.
+// Code summaries are computed locally and are not affected. The setting persists in
+// ~/.talkcody/offline.json.
+
+use crate::model_manager;
+use crate::provider_client::ProviderConfig;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use url::Url;
+
+const SETTINGS_FILE: &str = "offline.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OfflineSettings {
+    pub offline: bool,
+    /// OpenAI-compatible endpoint used for backend model calls while offline
+    pub local_provider: Option<ProviderConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfflineError {
+    /// What was refused, e.g. "download" or "model call"
+    pub capability: String,
+    /// The host that would have been contacted
+    pub target: Option<String>,
+    /// What to use instead, when there is something
+    pub fallback: Option<String>,
+}
+
+impl fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Offline: {} is unavailable", self.capability)?;
+        if let Some(target) = &self.target {
+            write!(f, " ({})", target)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<OfflineError> for String {
+    fn from(error: OfflineError) -> Self {
+        serde_json::json!({ "offline_error": error }).to_string()
+    }
+}
+
+/// How each capability behaves under the current setting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineStatus {
+    pub offline: bool,
+    pub local_provider: Option<ProviderConfig>,
+    /// Backend model calls can be served (by the remote or the local provider)
+    pub model_calls_available: bool,
+    /// Installed local models (~/.talkcody/models)
+    pub local_models: Vec<String>,
+    pub downloads_available: bool,
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody").join(SETTINGS_FILE))
+}
+
+fn settings() -> &'static RwLock<OfflineSettings> {
+    static SETTINGS: OnceLock<RwLock<OfflineSettings>> = OnceLock::new();
+    SETTINGS.get_or_init(|| {
+        let loaded = settings_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        RwLock::new(loaded)
+    })
+}
+
+pub fn current() -> OfflineSettings {
+    settings().read().map(|s| s.clone()).unwrap_or_default()
+}
+
+pub fn is_offline() -> bool {
+    current().offline
+}
+
+/// Whether `url` points at this machine, which stays reachable offline
+pub fn is_loopback_url(url: &str) -> bool {
+    let Ok(parsed) = Url::parse(url) else {
+        return false;
+    };
+    match parsed.host_str() {
+        Some("localhost") => true,
+        Some(host) => host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback()),
+        None => false,
+    }
+}
+
+fn host_of(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+}
+
+/// Refuse a request to `url` for `capability` if offline and it leaves this machine
+pub fn check_url_with(
+    settings: &OfflineSettings,
+    url: &str,
+    capability: &str,
+) -> Result<(), OfflineError> {
+    if !settings.offline || is_loopback_url(url) {
+        return Ok(());
+    }
+    Err(OfflineError {
+        capability: capability.to_string(),
+        target: host_of(url),
+        fallback: None,
+    })
+}
+
+pub fn check_url(url: &str, capability: &str) -> Result<(), String> {
+    check_url_with(&current(), url, capability).map_err(String::from)
+}
+
+/// Refuse a capability that always needs the network (e.g. installing a server
+/// whose download URL is resolved later)
+pub fn ensure_online(capability: &str) -> Result<(), String> {
+    if !is_offline() {
+        return Ok(());
+    }
+    Err(OfflineError {
+        capability: capability.to_string(),
+        target: None,
+        fallback: None,
+    }
+    .into())
+}
+
+/// The provider a backend model call should use: `provider` itself when online or
+/// already local, otherwise the configured local provider
+pub fn route_provider_with(
+    settings: &OfflineSettings,
+    provider: &ProviderConfig,
+) -> Result<ProviderConfig, OfflineError> {
+    if !settings.offline || is_loopback_url(&provider.base_url) {
+        return Ok(provider.clone());
+    }
+    match &settings.local_provider {
+        Some(local) => {
+            log::debug!(
+                "Offline: routing model call from {} to {}",
+                provider.base_url,
+                local.base_url
+            );
+            Ok(local.clone())
+        }
+        None => Err(OfflineError {
+            capability: "model call".to_string(),
+            target: host_of(&provider.base_url),
+            fallback: Some("Configure a local provider for offline mode".to_string()),
+        }),
+    }
+}
+
+pub fn route_provider(provider: &ProviderConfig) -> Result<ProviderConfig, String> {
+    route_provider_with(&current(), provider).map_err(String::from)
+}
+
+fn status_for(settings: OfflineSettings) -> OfflineStatus {
+    let local_models = model_manager::list_models()
+        .map(|inventory| {
+            inventory
+                .models
+                .into_iter()
+                .filter(|entry| entry.present)
+                .map(|entry| entry.model.id)
+                .collect()
+        })
+        .unwrap_or_default();
+    OfflineStatus {
+        offline: settings.offline,
+        model_calls_available: !settings.offline || settings.local_provider.is_some(),
+        downloads_available: !settings.offline,
+        local_provider: settings.local_provider,
+        local_models,
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_offline_status() -> OfflineStatus {
+    status_for(current())
+}
+
+/// Turn offline mode on or off. `local_provider` replaces the stored one when given.
+#[tauri::command]
+pub fn set_offline_mode(
+    offline: bool,
+    local_provider: Option<ProviderConfig>,
+) -> Result<OfflineStatus, String> {
+    if let Some(local) = &local_provider {
+        if !is_loopback_url(&local.base_url) {
+            return Err(format!(
+                "Local provider must run on this machine, got {}",
+                local.base_url
+            ));
+        }
+    }
+
+    let updated = {
+        let mut guard = settings().write().map_err(|e| e.to_string())?;
+        guard.offline = offline;
+        if local_provider.is_some() {
+            guard.local_provider = local_provider;
+        }
+        guard.clone()
+    };
+    let path = settings_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(&updated).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    log::info!(
+        "Offline mode {}",
+        if offline { "enabled" } else { "disabled" }
+    );
+    Ok(status_for(updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(base_url: &str) -> ProviderConfig {
+        ProviderConfig {
+            base_url: base_url.to_string(),
+            api_key: None,
+            model: "m".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_loopback_url() {
+        assert!(is_loopback_url("http://localhost:11434/v1"));
+        assert!(is_loopback_url("http://127.0.0.1:8080"));
+        assert!(is_loopback_url("http://[::1]:8080/v1"));
+        assert!(!is_loopback_url("https://api.openai.com/v1"));
+        assert!(!is_loopback_url("http://192.168.1.5:8080"));
+        assert!(!is_loopback_url("not a url"));
+    }
+
+    #[test]
+    fn test_check_url_only_refuses_remote_hosts_when_offline() {
+        let online = OfflineSettings::default();
+        assert!(check_url_with(&online, "https://github.com/x", "download").is_ok());
+
+        let offline = OfflineSettings {
+            offline: true,
+            local_provider: None,
+        };
+        assert!(check_url_with(&offline, "http://localhost:3000", "fetch").is_ok());
+        let error = check_url_with(&offline, "https://github.com/x", "download").unwrap_err();
+        assert_eq!(error.target.as_deref(), Some("github.com"));
+
+        let message: String = error.into();
+        let parsed: serde_json::Value = serde_json::from_str(&message).unwrap();
+        assert_eq!(parsed["offline_error"]["capability"], "download");
+    }
+
+    #[test]
+    fn test_route_provider() {
+        let remote = provider("https://api.openai.com/v1");
+        let local = provider("http://127.0.0.1:8080/v1");
+
+        let online = OfflineSettings::default();
+        assert_eq!(
+            route_provider_with(&online, &remote).unwrap().base_url,
+            remote.base_url
+        );
+
+        let mut offline = OfflineSettings {
+            offline: true,
+            local_provider: None,
+        };
+        // Local providers keep working; remote ones fail fast without a fallback
+        assert_eq!(
+            route_provider_with(&offline, &local).unwrap().base_url,
+            local.base_url
+        );
+        let error = route_provider_with(&offline, &remote).unwrap_err();
+        assert!(error.fallback.is_some());
+
+        offline.local_provider = Some(local.clone());
+        assert_eq!(
+            route_provider_with(&offline, &remote).unwrap().base_url,
+            local.base_url
+        );
+    }
+}
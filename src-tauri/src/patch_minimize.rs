@@ -0,0 +1,249 @@
+// Minimal patches for agent rewrites
+//
+// When the agent rewrites a function (or a whole file) it often re-emits the untouched
+// lines with different indentation or trailing whitespace, and the resulting git diff
+// buries the real change in noise. Before writing, the rewrite is diffed against the
+// original with whitespace-insensitive line keys: lines that only changed whitespace
+// and sit away from any real change get their original text back, while reindented
+// lines next to a change (a block wrapped in a new `if`) keep the agent's version.
+//
+// Leading whitespace is only ignored for languages where indentation carries no
+// meaning; for Python, Haskell and files of unknown type only trailing whitespace is.
+
+use crate::code_navigation::CodeNavigationService;
+use crate::line_endings;
+use crate::path_policy;
+use crate::text_diff::{self, DiffStats};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Languages whose indentation is syntax
+const INDENT_SENSITIVE: &[&str] = &["python", "haskell"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinimizedPatch {
+    /// The rewrite with whitespace-only noise reverted; this is what should be written
+    pub content: String,
+    /// Unified diff from the original to `content`, for the approval UI
+    pub diff: String,
+    pub stats: DiffStats,
+    /// Lines whose whitespace-only change was dropped
+    pub reverted_lines: usize,
+}
+
+fn line_key(line: &str, indent_sensitive: bool) -> &str {
+    if indent_sensitive {
+        line.trim_end()
+    } else {
+        line.trim()
+    }
+}
+
+enum Entry {
+    /// Same line up to whitespace: (old index, new index)
+    Same(usize, usize),
+    Insert(usize),
+    Delete,
+}
+
+/// Revert whitespace-only changes in `rewritten` that are not part of a real change.
+/// Both texts are expected LF-normalized.
+pub fn minimize(original: &str, rewritten: &str, lang_id: Option<&str>) -> (String, usize) {
+    let indent_sensitive = lang_id.is_none_or(|lang| INDENT_SENSITIVE.contains(&lang));
+    let old: Vec<&str> = original.lines().collect();
+    let new: Vec<&str> = rewritten.lines().collect();
+    let old_keys: Vec<&str> = old.iter().map(|l| line_key(l, indent_sensitive)).collect();
+    let new_keys: Vec<&str> = new.iter().map(|l| line_key(l, indent_sensitive)).collect();
+
+    let (mut i, mut j) = (0, 0);
+    let entries: Vec<Entry> = text_diff::diff_slices(&old_keys, &new_keys)
+        .into_iter()
+        .map(|op| match op {
+            text_diff::DiffOp::Equal(_) => {
+                i += 1;
+                j += 1;
+                Entry::Same(i - 1, j - 1)
+            }
+            text_diff::DiffOp::Insert(_) => {
+                j += 1;
+                Entry::Insert(j - 1)
+            }
+            text_diff::DiffOp::Delete(_) => {
+                i += 1;
+                Entry::Delete
+            }
+        })
+        .collect();
+
+    let reformatted = |e: &Entry| matches!(e, Entry::Same(o, n) if old[*o] != new[*n]);
+    let changed = |e: Option<&Entry>| matches!(e, Some(Entry::Insert(_) | Entry::Delete));
+
+    let mut lines: Vec<&str> = Vec::with_capacity(new.len());
+    let mut reverted = 0;
+    let mut k = 0;
+    while k < entries.len() {
+        if !reformatted(&entries[k]) {
+            match entries[k] {
+                Entry::Same(_, n) | Entry::Insert(n) => lines.push(new[n]),
+                Entry::Delete => {}
+            }
+            k += 1;
+            continue;
+        }
+        // A run of whitespace-only changes belongs to a real change it touches
+        let end = (k..entries.len())
+            .find(|&e| !reformatted(&entries[e]))
+            .unwrap_or(entries.len());
+        let keep_new = changed(k.checked_sub(1).map(|p| &entries[p])) || changed(entries.get(end));
+        for entry in &entries[k..end] {
+            if let Entry::Same(o, n) = entry {
+                if keep_new {
+                    lines.push(new[*n]);
+                } else {
+                    lines.push(old[*o]);
+                    reverted += 1;
+                }
+            }
+        }
+        k = end;
+    }
+
+    let mut content = lines.join("\n");
+    if rewritten.ends_with('\n') && !content.is_empty() {
+        content.push('\n');
+    }
+    (content, reverted)
+}
+
+/// Minimize `rewritten` against `original` and render the patch for review
+pub fn minimize_patch(
+    original: &str,
+    rewritten: &str,
+    lang_id: Option<&str>,
+    label: &str,
+) -> MinimizedPatch {
+    let (original, info) = line_endings::normalize_with_info(original);
+    let rewritten = line_endings::normalize(rewritten);
+    let (content, reverted_lines) = minimize(&original, &rewritten, lang_id);
+    let diff = text_diff::unified_diff(
+        &original,
+        &content,
+        &format!("a/{}", label),
+        &format!("b/{}", label),
+        3,
+    );
+    MinimizedPatch {
+        stats: text_diff::diff_stats(&text_diff::diff_lines(&original, &content)),
+        content: line_endings::convert(&content, info.dominant),
+        diff,
+        reverted_lines,
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn minimize_rewrite(
+    original: String,
+    rewritten: String,
+    lang_id: Option<String>,
+) -> MinimizedPatch {
+    minimize_patch(&original, &rewritten, lang_id.as_deref(), "original")
+}
+
+/// Minimize a rewrite of the file at `path` against its content on disk. A file that
+/// does not exist yet is returned as written.
+#[tauri::command]
+pub fn minimize_file_rewrite(
+    path: String,
+    content: String,
+    root_path: Option<String>,
+) -> Result<MinimizedPatch, String> {
+    path_policy::check_optional(&path, root_path.as_deref())?;
+    let original = match fs::read_to_string(&path) {
+        Ok(original) => original,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path, e)),
+    };
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&path);
+    let patch = minimize_patch(&original, &content, lang_id.as_deref(), &path);
+    log::info!(
+        "Minimized rewrite of {}: +{} -{}, {} whitespace-only line(s) reverted",
+        path,
+        patch.stats.added,
+        patch.stats.removed,
+        patch.reverted_lines
+    );
+    Ok(patch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const ORIGINAL: &str = "fn a() {\n    one();\n}\n\nfn b() {\n    two();\n}\n";
+
+    #[test]
+    fn test_reverts_unrelated_reformatting() {
+        // b() was reformatted with tabs and trailing spaces, a() really changed
+        let rewritten = "fn a() {\n    one();\n    three();\n}\n\nfn b() {  \n\ttwo();\n}\n";
+        let (content, reverted) = minimize(ORIGINAL, rewritten, Some("rust"));
+        assert_eq!(
+            content,
+            "fn a() {\n    one();\n    three();\n}\n\nfn b() {\n    two();\n}\n"
+        );
+        assert_eq!(reverted, 2);
+    }
+
+    #[test]
+    fn test_keeps_reindentation_around_a_change() {
+        let rewritten =
+            "fn a() {\n    if ready() {\n        one();\n    }\n}\n\nfn b() {\n    two();\n}\n";
+        let (content, reverted) = minimize(ORIGINAL, rewritten, Some("rust"));
+        assert_eq!(content, rewritten);
+        assert_eq!(reverted, 0);
+    }
+
+    #[test]
+    fn test_indentation_counts_in_python() {
+        let original = "def f():\n    if x:\n        a()\n    b()\n";
+        // Indenting b() moves it into the `if`, which is a real change
+        let rewritten = "def f():\n    if x:\n        a()\n        b()\n";
+        let (content, reverted) = minimize(original, rewritten, Some("python"));
+        assert_eq!(content, rewritten);
+        assert_eq!(reverted, 0);
+
+        let (content, reverted) = minimize(
+            original,
+            "def f():   \n    if x:\n        a()\n    b()\n",
+            Some("python"),
+        );
+        assert_eq!(content, original);
+        assert_eq!(reverted, 1);
+    }
+
+    #[test]
+    fn test_minimize_file_rewrite_keeps_crlf() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("lib.rs");
+        fs::write(&path, ORIGINAL.replace('\n', "\r\n")).unwrap();
+
+        let rewritten = "fn a() {\n  one();\n}\n\nfn b() {\n    two();\n    three();\n}\n";
+        let patch = minimize_file_rewrite(
+            path.to_string_lossy().to_string(),
+            rewritten.to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            patch.content,
+            "fn a() {\r\n    one();\r\n}\r\n\r\nfn b() {\r\n    two();\r\n    three();\r\n}\r\n"
+        );
+        assert_eq!(patch.reverted_lines, 1);
+        assert_eq!(patch.stats.added, 1);
+        assert_eq!(patch.stats.removed, 0);
+        assert!(patch.diff.contains("+    three();\n"));
+        assert!(!patch.diff.contains("-    one();"));
+    }
+}
@@ -0,0 +1,219 @@
+// Workspace containment policy for symlinks and junctions
+//
+// A path inside the workspace can still lead out of it: a symlink (or, on Windows, a
+// junction) anywhere along it may point elsewhere, and a chain of links can loop.
+// `resolve_within` follows every link along a path, including links that dangle and
+// paths that do not exist yet, and only accepts the result if it stays under the
+// canonical workspace root. Violations come back as a `PathPolicyError`, which
+// converts to a JSON string (`{"policy_error": {"kind": ...}}`) so the frontend can
+// tell a policy refusal from an I/O failure.
+
+use crate::path_utils;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Links followed while resolving one path before it is treated as a cycle (the
+/// same limit as Linux's ELOOP)
+const MAX_SYMLINK_HOPS: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PathPolicyError {
+    /// The path resolves to a location outside the workspace root
+    EscapesWorkspace {
+        path: String,
+        resolved: String,
+        workspace_root: String,
+    },
+    /// Following the path's links never reaches a real file or directory
+    SymlinkCycle { path: String },
+    /// A link or the workspace root could not be read
+    Unresolvable { path: String, reason: String },
+}
+
+impl fmt::Display for PathPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathPolicyError::EscapesWorkspace { path, resolved, .. } => {
+                write!(f, "{} resolves outside the workspace ({})", path, resolved)
+            }
+            PathPolicyError::SymlinkCycle { path } => write!(f, "Symlink cycle at {}", path),
+            PathPolicyError::Unresolvable { path, reason } => {
+                write!(f, "Cannot resolve {}: {}", path, reason)
+            }
+        }
+    }
+}
+
+impl From<PathPolicyError> for String {
+    fn from(error: PathPolicyError) -> Self {
+        serde_json::json!({ "policy_error": error }).to_string()
+    }
+}
+
+/// Follow the links in `path` component by component. Missing components are kept
+/// as they are, so paths that will be created resolve too.
+fn resolve_links(
+    path: &Path,
+    hops: &mut usize,
+    original: &Path,
+) -> Result<PathBuf, PathPolicyError> {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component.as_os_str()),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => {
+                resolved.push(name);
+                // Windows junctions report as symlinks here too
+                let is_link = fs::symlink_metadata(&resolved)
+                    .map(|m| m.file_type().is_symlink())
+                    .unwrap_or(false);
+                if !is_link {
+                    continue;
+                }
+                *hops += 1;
+                if *hops > MAX_SYMLINK_HOPS {
+                    return Err(PathPolicyError::SymlinkCycle {
+                        path: path_utils::path_to_slash(original),
+                    });
+                }
+                let target =
+                    fs::read_link(&resolved).map_err(|e| PathPolicyError::Unresolvable {
+                        path: path_utils::path_to_slash(&resolved),
+                        reason: e.to_string(),
+                    })?;
+                resolved.pop();
+                let target = PathBuf::from(path_utils::strip_verbatim(&target.to_string_lossy()));
+                let target = if target.is_absolute() {
+                    target
+                } else {
+                    resolved.join(target)
+                };
+                resolved = resolve_links(&target, hops, original)?;
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Resolve `path` (absolute, or relative to `workspace_root`) through all of its links
+/// and require the result to stay under the workspace root
+pub fn resolve_within(path: &Path, workspace_root: &Path) -> Result<PathBuf, PathPolicyError> {
+    let root = path_utils::canonicalize(workspace_root).map_err(|reason| {
+        PathPolicyError::Unresolvable {
+            path: path_utils::path_to_slash(workspace_root),
+            reason,
+        }
+    })?;
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        root.join(path)
+    };
+    let resolved = resolve_links(&absolute, &mut 0, path)?;
+
+    let resolved_str = resolved.to_string_lossy();
+    let root_str = root.to_string_lossy();
+    if !path_utils::is_within(&resolved_str, &root_str) {
+        return Err(PathPolicyError::EscapesWorkspace {
+            path: path_utils::path_to_slash(path),
+            resolved: path_utils::normalize_lexically(&resolved_str),
+            workspace_root: path_utils::normalize_lexically(&root_str),
+        });
+    }
+    Ok(resolved)
+}
+
+/// `resolve_within` for commands whose workspace root is optional: without a root
+/// there is nothing to enforce
+pub fn check_optional(path: &str, workspace_root: Option<&str>) -> Result<(), String> {
+    match workspace_root {
+        Some(root) => resolve_within(Path::new(path), Path::new(root))
+            .map(|_| ())
+            .map_err(String::from),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plain_paths() {
+        let root = TempDir::new().unwrap();
+        fs::create_dir_all(root.path().join("src")).unwrap();
+        fs::write(root.path().join("src/main.rs"), "").unwrap();
+
+        assert!(resolve_within(Path::new("src/main.rs"), root.path()).is_ok());
+        // Not created yet
+        assert!(resolve_within(Path::new("src/new/mod.rs"), root.path()).is_ok());
+        assert!(matches!(
+            resolve_within(Path::new("../outside.txt"), root.path()),
+            Err(PathPolicyError::EscapesWorkspace { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_symlink_escape() {
+        use std::os::unix::fs::symlink;
+
+        let root = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("secret.txt"), "").unwrap();
+        symlink(outside.path(), root.path().join("link")).unwrap();
+        // Dangling links are followed as well; writing through one would create the file outside
+        symlink(
+            outside.path().join("later.txt"),
+            root.path().join("dangling"),
+        )
+        .unwrap();
+        symlink("src", root.path().join("inner")).unwrap();
+        fs::create_dir_all(root.path().join("src")).unwrap();
+
+        let error = resolve_within(Path::new("link/secret.txt"), root.path()).unwrap_err();
+        assert!(matches!(error, PathPolicyError::EscapesWorkspace { .. }));
+        assert!(resolve_within(Path::new("dangling"), root.path()).is_err());
+        assert!(resolve_within(Path::new("inner/lib.rs"), root.path()).is_ok());
+
+        let message = String::from(error);
+        assert!(
+            message.contains(r#""kind":"escapes_workspace""#),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_symlink_cycle() {
+        use std::os::unix::fs::symlink;
+
+        let root = TempDir::new().unwrap();
+        symlink(root.path().join("b"), root.path().join("a")).unwrap();
+        symlink(root.path().join("a"), root.path().join("b")).unwrap();
+
+        assert_eq!(
+            resolve_within(Path::new("a/file.txt"), root.path()),
+            Err(PathPolicyError::SymlinkCycle {
+                path: "a/file.txt".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_optional() {
+        assert!(check_optional("/anywhere", None).is_ok());
+        let root = TempDir::new().unwrap();
+        let error = check_optional("/", Some(&root.path().to_string_lossy())).unwrap_err();
+        assert!(error.contains("policy_error"));
+    }
+}
@@ -0,0 +1,285 @@
+// Cross-platform path handling
+//
+// Paths reach the backend from the frontend, from git, from tool output and from the
+// file system, with either separator, Windows drive letters, UNC shares
+// (`\\server\share\...`) and the verbatim form `std::fs::canonicalize` returns on
+// Windows (`\\?\C:\...`). Splitting on '/' or comparing strings breaks on all of
+// them. Everything that shows a path to the user or the model goes through this
+// module: paths are displayed with '/' separators, relative to the workspace when they
+// are inside it, and compared case-insensitively on file systems that are.
+
+use std::path::{Path, PathBuf};
+
+/// Whether paths on this platform's default file system compare case-insensitively
+pub const CASE_INSENSITIVE: bool = cfg!(any(windows, target_os = "macos"));
+
+/// Replace backslashes with '/'
+pub fn to_slash(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// `Path` with '/' separators
+pub fn path_to_slash(path: &Path) -> String {
+    to_slash(&path.to_string_lossy())
+}
+
+/// Drop the `\\?\` verbatim prefix: `\\?\C:\x` -> `C:/x`, `\\?\UNC\srv\share` -> `//srv/share`.
+/// Paths without the prefix are returned unchanged.
+pub fn strip_verbatim(path: &str) -> String {
+    let slashed = to_slash(path);
+    if let Some(rest) = slashed.strip_prefix("//?/UNC/") {
+        format!("//{}", rest)
+    } else if let Some(rest) = slashed
+        .strip_prefix("//?/")
+        .or_else(|| slashed.strip_prefix("/??/"))
+    {
+        // `\??\` is the NT form junction targets are read back in
+        rest.to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Split a '/'-separated path into its root (`/`, `C:/`, `//server/share/` or empty for
+/// relative paths) and the rest
+fn split_root(path: &str) -> (&str, &str) {
+    if let Some(rest) = path.strip_prefix("//") {
+        // UNC: the server and share are part of the root
+        let root_len = match rest.find('/') {
+            Some(server_end) => match rest[server_end + 1..].find('/') {
+                Some(share_end) => server_end + share_end + 2,
+                None => rest.len(),
+            },
+            None => rest.len(),
+        };
+        return path.split_at(2 + root_len);
+    }
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let end = if bytes.get(2) == Some(&b'/') { 3 } else { 2 };
+        return (&path[..end], &path[end..]);
+    }
+    if let Some(rest) = path.strip_prefix('/') {
+        return ("/", rest);
+    }
+    ("", path)
+}
+
+/// Resolve `.` and `..` and collapse repeated separators without touching the file
+/// system. The result uses '/' separators; `..` above the root is dropped.
+pub fn normalize_lexically(path: &str) -> String {
+    let slashed = strip_verbatim(&to_slash(path));
+    let (root, rest) = split_root(&slashed);
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in rest.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if segments.last().is_some_and(|s| *s != "..") {
+                    segments.pop();
+                } else if root.is_empty() {
+                    segments.push("..");
+                }
+            }
+            _ => segments.push(segment),
+        }
+    }
+    let joined = segments.join("/");
+    if root.is_empty() && joined.is_empty() {
+        ".".to_string()
+    } else {
+        format!("{}{}", root, joined)
+    }
+}
+
+/// Roots compare without their trailing separator (`//srv/share/` and `//srv/share`)
+fn root_key(root: &str) -> &str {
+    if root.len() > 1 {
+        root.trim_end_matches('/')
+    } else {
+        root
+    }
+}
+
+fn segments_equal(a: &str, b: &str, case_insensitive: bool) -> bool {
+    if case_insensitive {
+        a.eq_ignore_ascii_case(b) || a.to_lowercase() == b.to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+/// `path` relative to `root` with '/' separators, or None when it is not inside `root`.
+/// The root itself yields an empty string.
+pub fn relative_to(path: &str, root: &str, case_insensitive: bool) -> Option<String> {
+    let path = normalize_lexically(path);
+    let root = normalize_lexically(root);
+    let (path_root, path_rest) = split_root(&path);
+    let (root_root, root_rest) = split_root(&root);
+    if !segments_equal(root_key(path_root), root_key(root_root), case_insensitive) {
+        return None;
+    }
+
+    let mut path_segments = path_rest.split('/').filter(|s| !s.is_empty());
+    // A relative root normalizes to "."
+    for root_segment in root_rest.split('/').filter(|s| !s.is_empty() && *s != ".") {
+        match path_segments.next() {
+            Some(segment) if segments_equal(segment, root_segment, case_insensitive) => {}
+            _ => return None,
+        }
+    }
+    Some(path_segments.collect::<Vec<_>>().join("/"))
+}
+
+/// Whether `path` is `root` or inside it
+pub fn is_within(path: &str, root: &str) -> bool {
+    relative_to(path, root, CASE_INSENSITIVE).is_some()
+}
+
+/// How a path is shown to the user and the model: workspace-relative when inside the
+/// workspace, otherwise the full path, always with '/' separators
+pub fn display_path(path: &str, workspace_root: Option<&str>) -> String {
+    match workspace_root.and_then(|root| relative_to(path, root, CASE_INSENSITIVE)) {
+        Some(relative) if relative.is_empty() => ".".to_string(),
+        Some(relative) => relative,
+        None => normalize_lexically(path),
+    }
+}
+
+/// `path` relative to `root` for paths produced by walking `root`
+pub fn relative_display(path: &Path, root: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => path_to_slash(relative),
+        Err(_) => display_path(&path.to_string_lossy(), Some(&root.to_string_lossy())),
+    }
+}
+
+/// Last component of a path written with either separator
+pub fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// Canonical form of `path` without the Windows verbatim prefix. Paths that do not
+/// exist yet are resolved through their deepest existing ancestor.
+pub fn canonicalize(path: &Path) -> Result<PathBuf, String> {
+    let mut existing = path.to_path_buf();
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                missing.push(name.to_os_string());
+                existing = parent.to_path_buf();
+            }
+            _ => break,
+        }
+    }
+    let base = if existing.as_os_str().is_empty() {
+        std::env::current_dir()
+            .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?
+    } else {
+        existing
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?
+    };
+    let mut resolved = PathBuf::from(strip_verbatim(&base.to_string_lossy()));
+    for name in missing.into_iter().rev() {
+        resolved.push(name);
+    }
+    Ok(PathBuf::from(normalize_lexically_native(&resolved)))
+}
+
+/// `normalize_lexically` keeping the platform's separator
+fn normalize_lexically_native(path: &Path) -> String {
+    let normalized = normalize_lexically(&path.to_string_lossy());
+    if cfg!(windows) {
+        normalized.replace('/', "\\")
+    } else {
+        normalized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_strip_verbatim() {
+        assert_eq!(strip_verbatim(r"\\?\C:\work\app"), "C:/work/app");
+        assert_eq!(strip_verbatim(r"\\?\UNC\srv\share\x"), "//srv/share/x");
+        assert_eq!(strip_verbatim(r"C:\work"), r"C:\work");
+    }
+
+    #[test]
+    fn test_normalize_lexically() {
+        assert_eq!(normalize_lexically(r"C:\work\.\app\..\lib"), "C:/work/lib");
+        assert_eq!(normalize_lexically("/a//b/../c/"), "/a/c");
+        assert_eq!(normalize_lexically("/.."), "/");
+        assert_eq!(normalize_lexically("../a/./b"), "../a/b");
+        assert_eq!(normalize_lexically("a/.."), ".");
+        assert_eq!(
+            normalize_lexically(r"\\srv\share\dir\..\f.txt"),
+            "//srv/share/f.txt"
+        );
+    }
+
+    #[test]
+    fn test_relative_to() {
+        assert_eq!(
+            relative_to(r"C:\Work\App\src\main.rs", "c:/work/app", true).as_deref(),
+            Some("src/main.rs")
+        );
+        assert_eq!(
+            relative_to(r"C:\Work\App\src\main.rs", "c:/work/app", false),
+            None
+        );
+        assert_eq!(
+            relative_to("/work/app", "/work/app/", false).as_deref(),
+            Some("")
+        );
+        // A shared prefix is not containment
+        assert_eq!(relative_to("/work/application/x", "/work/app", false), None);
+        assert_eq!(
+            relative_to("./src/a.rs", ".", false).as_deref(),
+            Some("src/a.rs")
+        );
+        assert_eq!(
+            relative_to(r"\\srv\share\proj\a.ts", "//srv/share/proj", false).as_deref(),
+            Some("a.ts")
+        );
+        assert_eq!(relative_to(r"\\srv\other\proj", "//srv/share", false), None);
+    }
+
+    #[test]
+    fn test_display_path() {
+        assert_eq!(
+            display_path("/work/app/src/a.rs", Some("/work/app")),
+            "src/a.rs"
+        );
+        assert_eq!(display_path("/work/app", Some("/work/app")), ".");
+        assert_eq!(
+            display_path(r"D:\other\b.rs", Some("/work/app")),
+            "D:/other/b.rs"
+        );
+        assert_eq!(display_path("/tmp/x/../y", None), "/tmp/y");
+    }
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name(r"C:\work\main.rs"), "main.rs");
+        assert_eq!(file_name("src/lib.rs"), "lib.rs");
+        assert_eq!(file_name("lib.rs"), "lib.rs");
+    }
+
+    #[test]
+    fn test_canonicalize_missing_tail() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        let root = canonicalize(temp_dir.path()).unwrap();
+
+        let resolved = canonicalize(&temp_dir.path().join("src/../new/file.rs")).unwrap();
+        assert_eq!(resolved, root.join("new").join("file.rs"));
+        assert_eq!(relative_display(&resolved, &root), "new/file.rs");
+    }
+}
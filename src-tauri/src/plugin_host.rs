@@ -0,0 +1,981 @@
+// WASM plugins for extra agent tools
+//
+// A plugin is a directory in ~/.talkcody/plugins/ with a `plugin.json` manifest and a
+// WebAssembly module. The manifest lists the tools the plugin provides (name,
+// description, JSON input schema) and the capabilities it needs: `fs-read` (read files
+// in the workspace), `net` (HTTP GET) and `exec` (run a program in the workspace).
+// A plugin stays disabled until the user grants every capability it declares, and
+// `list_plugin_tools` only reports tools of enabled plugins, for the frontend to add
+// to its tool registry. Grants are bound to the SHA-256 of the module they were given
+// for: when the module changes the plugin is disabled until the user grants again.
+//
+// Modules run in wasmtime with no WASI, so the only way out of the sandbox is the
+// host functions in the `talkcody` import module, and only those for granted
+// capabilities are linked. A module importing anything else is refused before it
+// runs. Each call gets a fuel budget (CPU) and a memory cap.
+//
+// ABI: the module exports `memory`, `alloc(len: i32) -> i32` and
+// `call(tool_ptr, tool_len, input_ptr, input_len) -> i64`, where the tool name and
+// the JSON input are UTF-8 in guest memory and the result is a UTF-8 JSON string
+// returned as `ptr << 32 | len`. Host functions take `(ptr, len)` of their argument
+// and return a packed `{"ok": true, "data": ...}` or `{"ok": false, "error": ...}`:
+//   log(message)               always available, returns nothing
+//   read_file(path)            fs-read; path relative to the workspace root
+//   http_get(url)              net; returns {status, body}
+//   exec({"program", "args"})  exec; returns {exit_code, stdout, stderr}; the program
+//                              gets a minimal environment, not the app's
+
+use crate::http_client;
+use crate::offline_mode;
+use crate::path_policy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use wasmtime::{Caller, Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+const MANIFEST_FILE: &str = "plugin.json";
+const GRANTS_FILE: &str = "grants.json";
+const HOST_MODULE: &str = "talkcody";
+/// Roughly a few seconds of guest execution
+const DEFAULT_FUEL: u64 = 2_000_000_000;
+const DEFAULT_MEMORY_BYTES: usize = 128 * 1024 * 1024;
+const MAX_READ_BYTES: u64 = 8 * 1024 * 1024;
+const MAX_HTTP_BYTES: usize = 8 * 1024 * 1024;
+const HTTP_TIMEOUT_SECS: u64 = 30;
+const EXEC_TIMEOUT_SECS: u64 = 60;
+/// Environment variables an exec'd program inherits
+const EXEC_PASSTHROUGH_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USERPROFILE",
+    "SYSTEMROOT",
+    "TMPDIR",
+    "TEMP",
+    "TMP",
+];
+
+/// Serializes read-modify-write cycles of the grants file
+static GRANTS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    FsRead,
+    Net,
+    Exec,
+}
+
+impl Capability {
+    fn as_str(self) -> &'static str {
+        match self {
+            Capability::FsRead => "fs-read",
+            Capability::Net => "net",
+            Capability::Exec => "exec",
+        }
+    }
+
+    /// The capability a host import requires; None for imports every plugin may use
+    fn for_import(name: &str) -> Result<Option<Capability>, String> {
+        match name {
+            "log" => Ok(None),
+            "read_file" => Ok(Some(Capability::FsRead)),
+            "http_get" => Ok(Some(Capability::Net)),
+            "exec" => Ok(Some(Capability::Exec)),
+            _ => Err(format!("unknown host function {}::{}", HOST_MODULE, name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginToolSpec {
+    pub name: String,
+    pub description: String,
+    #[serde(default = "empty_object_schema")]
+    pub input_schema: Value,
+}
+
+fn empty_object_schema() -> Value {
+    json!({ "type": "object" })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Module file, relative to the plugin directory
+    pub module: String,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    pub tools: Vec<PluginToolSpec>,
+}
+
+/// Capabilities granted to a plugin, for the module with this hash only
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PluginGrant {
+    module_hash: String,
+    capabilities: Vec<Capability>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInfo {
+    pub path: String,
+    pub manifest: Option<PluginManifest>,
+    pub granted: Vec<Capability>,
+    /// Every declared capability is granted and the manifest is valid
+    pub enabled: bool,
+    /// Capabilities were granted for a different module; the user has to grant again
+    #[serde(default)]
+    pub module_changed: bool,
+    pub error: Option<String>,
+}
+
+/// A tool as registered in the frontend's tool registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginTool {
+    pub plugin: String,
+    pub name: String,
+    pub description: String,
+    pub input_schema: Value,
+    pub capabilities: Vec<Capability>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SandboxLimits {
+    pub fuel: u64,
+    pub memory_bytes: usize,
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        Self {
+            fuel: DEFAULT_FUEL,
+            memory_bytes: DEFAULT_MEMORY_BYTES,
+        }
+    }
+}
+
+struct HostState {
+    plugin: String,
+    workspace_root: Option<PathBuf>,
+    limits: StoreLimits,
+}
+
+fn get_plugins_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody").join("plugins"))
+}
+
+fn validate_plugin_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid plugin name {:?}: use lowercase letters, digits, - and _",
+            name
+        ))
+    }
+}
+
+pub fn load_manifest(plugin_dir: &Path) -> Result<PluginManifest, String> {
+    let path = plugin_dir.join(MANIFEST_FILE);
+    let raw = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let manifest: PluginManifest =
+        serde_json::from_str(&raw).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+    validate_plugin_name(&manifest.name)?;
+    if Path::new(&manifest.module)
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(format!(
+            "Module path must stay inside the plugin: {}",
+            manifest.module
+        ));
+    }
+    if manifest.tools.is_empty() {
+        return Err(format!("Plugin {} declares no tools", manifest.name));
+    }
+    Ok(manifest)
+}
+
+fn load_grants(plugins_dir: &Path) -> BTreeMap<String, PluginGrant> {
+    fs::read_to_string(plugins_dir.join(GRANTS_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn module_hash(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn read_module(plugin_dir: &Path, manifest: &PluginManifest) -> Result<Vec<u8>, String> {
+    let path = plugin_dir.join(&manifest.module);
+    fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Capabilities granted for the module with `hash`, and whether a grant exists for
+/// another module
+fn granted_for(grant: Option<&PluginGrant>, hash: Option<&str>) -> (Vec<Capability>, bool) {
+    match grant {
+        Some(grant) if Some(grant.module_hash.as_str()) == hash => {
+            (grant.capabilities.clone(), false)
+        }
+        Some(_) => (Vec::new(), true),
+        None => (Vec::new(), false),
+    }
+}
+
+fn is_enabled(manifest: &PluginManifest, granted: &[Capability]) -> bool {
+    manifest.capabilities.iter().all(|c| granted.contains(c))
+}
+
+pub fn discover(plugins_dir: &Path) -> Vec<PluginInfo> {
+    let grants = load_grants(plugins_dir);
+    let mut dirs: Vec<PathBuf> = fs::read_dir(plugins_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+
+    dirs.into_iter()
+        .map(|dir| {
+            let path = dir.to_string_lossy().to_string();
+            match load_manifest(&dir) {
+                Ok(manifest) => {
+                    let module = read_module(&dir, &manifest);
+                    let hash = module.as_deref().ok().map(module_hash);
+                    let (granted, module_changed) =
+                        granted_for(grants.get(&manifest.name), hash.as_deref());
+                    PluginInfo {
+                        path,
+                        enabled: module.is_ok() && is_enabled(&manifest, &granted),
+                        manifest: Some(manifest),
+                        granted,
+                        module_changed,
+                        error: module.err(),
+                    }
+                }
+                Err(e) => PluginInfo {
+                    path,
+                    manifest: None,
+                    granted: Vec::new(),
+                    enabled: false,
+                    module_changed: false,
+                    error: Some(e),
+                },
+            }
+        })
+        .collect()
+}
+
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Engine::new(&config).expect("valid wasmtime config")
+    })
+}
+
+/// Compiled modules by content hash. Compiling the bytes that were hashed means the
+/// module that runs is the one the grant was checked against.
+fn load_module(bytes: &[u8], hash: &str) -> Result<Module, String> {
+    static MODULES: OnceLock<Mutex<HashMap<String, Module>>> = OnceLock::new();
+    let cache = MODULES.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(module) = cache.lock().map_err(|e| e.to_string())?.get(hash) {
+        return Ok(module.clone());
+    }
+    let module = Module::new(engine(), bytes)
+        .map_err(|e| format!("Failed to compile module {}: {:#}", hash, e))?;
+    cache
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(hash.to_string(), module.clone());
+    Ok(module)
+}
+
+/// Refuse modules that import host functions they have no capability for, or
+/// anything outside the host module
+fn check_imports(module: &Module, plugin: &str, granted: &[Capability]) -> Result<(), String> {
+    for import in module.imports() {
+        if import.module() != HOST_MODULE {
+            return Err(format!(
+                "Plugin {} imports {}::{}, which the host does not provide",
+                plugin,
+                import.module(),
+                import.name()
+            ));
+        }
+        let required = Capability::for_import(import.name())
+            .map_err(|e| format!("Plugin {} imports an {}", plugin, e))?;
+        if let Some(capability) = required {
+            if !granted.contains(&capability) {
+                return Err(format!(
+                    "Plugin {} uses {} without the {} capability",
+                    plugin,
+                    import.name(),
+                    capability.as_str()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn pack(ptr: i32, len: usize) -> i64 {
+    (((ptr as u32 as u64) << 32) | (len as u32 as u64)) as i64
+}
+
+fn unpack(packed: i64) -> (usize, usize) {
+    let packed = packed as u64;
+    ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize)
+}
+
+fn read_guest_string(
+    caller: &mut Caller<'_, HostState>,
+    ptr: i32,
+    len: i32,
+) -> Result<String, String> {
+    let memory = caller
+        .get_export("memory")
+        .and_then(|e| e.into_memory())
+        .ok_or("Plugin exports no memory")?;
+    let mut buffer = vec![0u8; len.max(0) as usize];
+    memory
+        .read(&*caller, ptr as u32 as usize, &mut buffer)
+        .map_err(|e| e.to_string())?;
+    String::from_utf8(buffer).map_err(|e| e.to_string())
+}
+
+/// Copy `bytes` into guest memory through its allocator; 0 when that fails
+fn write_guest(caller: &mut Caller<'_, HostState>, bytes: &[u8]) -> i64 {
+    let mut write = || -> Result<i64, String> {
+        let alloc = caller
+            .get_export("alloc")
+            .and_then(|e| e.into_func())
+            .ok_or("Plugin exports no alloc")?
+            .typed::<i32, i32>(&*caller)
+            .map_err(|e| e.to_string())?;
+        let ptr = alloc
+            .call(&mut *caller, bytes.len() as i32)
+            .map_err(|e| e.to_string())?;
+        let memory = caller
+            .get_export("memory")
+            .and_then(|e| e.into_memory())
+            .ok_or("Plugin exports no memory")?;
+        memory
+            .write(&mut *caller, ptr as u32 as usize, bytes)
+            .map_err(|e| e.to_string())?;
+        Ok(pack(ptr, bytes.len()))
+    };
+    write().unwrap_or_else(|e| {
+        log::warn!("Failed to pass a host result to the plugin: {}", e);
+        0
+    })
+}
+
+fn host_response(result: Result<Value, String>) -> Vec<u8> {
+    match result {
+        Ok(data) => json!({ "ok": true, "data": data }),
+        Err(error) => json!({ "ok": false, "error": error }),
+    }
+    .to_string()
+    .into_bytes()
+}
+
+fn workspace_root(state: &HostState) -> Result<&Path, String> {
+    state
+        .workspace_root
+        .as_deref()
+        .ok_or_else(|| "No workspace is open".to_string())
+}
+
+fn host_read_file(state: &HostState, path: &str) -> Result<Value, String> {
+    let root = workspace_root(state)?;
+    let resolved = path_policy::resolve_within(&root.join(path), root).map_err(String::from)?;
+    let size = fs::metadata(&resolved)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?
+        .len();
+    if size > MAX_READ_BYTES {
+        return Err(format!("{} is larger than {} bytes", path, MAX_READ_BYTES));
+    }
+    fs::read_to_string(&resolved)
+        .map(Value::String)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+fn host_http_get(url: &str) -> Result<Value, String> {
+    offline_mode::check_url(url, "plugin network access")?;
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Unsupported URL scheme: {}", parsed.scheme()));
+    }
+    let response = http_client::blocking_client()?
+        .get(url)
+        .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+        .send()
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status().as_u16();
+    let mut body = Vec::new();
+    response
+        .take(MAX_HTTP_BYTES as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    if body.len() > MAX_HTTP_BYTES {
+        return Err(format!("Response is larger than {} bytes", MAX_HTTP_BYTES));
+    }
+    Ok(json!({ "status": status, "body": String::from_utf8_lossy(&body) }))
+}
+
+#[derive(Deserialize)]
+struct ExecRequest {
+    program: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+fn host_exec(state: &HostState, request: &str) -> Result<Value, String> {
+    let request: ExecRequest =
+        serde_json::from_str(request).map_err(|e| format!("Invalid exec request: {}", e))?;
+    let root = workspace_root(state)?;
+    let mut cmd = Command::new(&request.program);
+    cmd.args(&request.args)
+        .current_dir(root)
+        .env_clear()
+        .envs(
+            EXEC_PASSTHROUGH_ENV
+                .iter()
+                .filter_map(|k| std::env::var(k).ok().map(|v| (*k, v))),
+        )
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // Hide the console window to avoid flashing cmd.exe
+        cmd.creation_flags(0x08000000);
+    }
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", request.program, e))?;
+
+    // Drain both pipes while waiting so a chatty program cannot block on a full pipe
+    let mut stdout = child.stdout.take().ok_or("No stdout")?;
+    let mut stderr = child.stderr.take().ok_or("No stderr")?;
+    let stdout_reader = std::thread::spawn(move || {
+        let mut out = Vec::new();
+        let _ = stdout.read_to_end(&mut out);
+        out
+    });
+    let stderr_reader = std::thread::spawn(move || {
+        let mut out = Vec::new();
+        let _ = stderr.read_to_end(&mut out);
+        out
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(EXEC_TIMEOUT_SECS);
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break Some(status),
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            None => std::thread::sleep(Duration::from_millis(20)),
+        }
+    };
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+    let Some(status) = status else {
+        return Err(format!(
+            "{} timed out after {}s",
+            request.program, EXEC_TIMEOUT_SECS
+        ));
+    };
+    Ok(json!({
+        "exit_code": status.code(),
+        "stdout": String::from_utf8_lossy(&stdout),
+        "stderr": String::from_utf8_lossy(&stderr),
+    }))
+}
+
+fn link_host_functions(
+    linker: &mut Linker<HostState>,
+    granted: &[Capability],
+) -> Result<(), String> {
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "log",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                if let Ok(message) = read_guest_string(&mut caller, ptr, len) {
+                    log::info!("[plugin {}] {}", caller.data().plugin, message);
+                }
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    if granted.contains(&Capability::FsRead) {
+        linker
+            .func_wrap(
+                HOST_MODULE,
+                "read_file",
+                |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i64 {
+                    let result = read_guest_string(&mut caller, ptr, len)
+                        .and_then(|path| host_read_file(caller.data(), &path));
+                    write_guest(&mut caller, &host_response(result))
+                },
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    if granted.contains(&Capability::Net) {
+        linker
+            .func_wrap(
+                HOST_MODULE,
+                "http_get",
+                |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i64 {
+                    let result = read_guest_string(&mut caller, ptr, len)
+                        .and_then(|url| host_http_get(&url));
+                    write_guest(&mut caller, &host_response(result))
+                },
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    if granted.contains(&Capability::Exec) {
+        linker
+            .func_wrap(
+                HOST_MODULE,
+                "exec",
+                |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> i64 {
+                    let result = read_guest_string(&mut caller, ptr, len)
+                        .and_then(|request| host_exec(caller.data(), &request));
+                    write_guest(&mut caller, &host_response(result))
+                },
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Run one tool call in a fresh sandbox and return the JSON text the plugin produced
+pub fn run_tool(
+    module: &Module,
+    plugin: &str,
+    granted: &[Capability],
+    workspace_root: Option<PathBuf>,
+    tool: &str,
+    input: &str,
+    limits: SandboxLimits,
+) -> Result<String, String> {
+    check_imports(module, plugin, granted)?;
+    let mut linker = Linker::new(engine());
+    link_host_functions(&mut linker, granted)?;
+
+    let state = HostState {
+        plugin: plugin.to_string(),
+        workspace_root,
+        limits: StoreLimitsBuilder::new()
+            .memory_size(limits.memory_bytes)
+            .instances(1)
+            .build(),
+    };
+    let mut store = Store::new(engine(), state);
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(limits.fuel).map_err(|e| e.to_string())?;
+
+    let failed = |store: &Store<HostState>, e: wasmtime::Error| {
+        if store.get_fuel().is_ok_and(|fuel| fuel == 0) {
+            format!("Plugin {} exceeded its CPU budget", plugin)
+        } else {
+            format!("Plugin {} failed: {:#}", plugin, e)
+        }
+    };
+    let instance = linker
+        .instantiate(&mut store, module)
+        .map_err(|e| failed(&store, e))?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| format!("Plugin {} exports no memory", plugin))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&mut store, "alloc")
+        .map_err(|e| format!("Plugin {} has no usable alloc: {:#}", plugin, e))?;
+    let call = instance
+        .get_typed_func::<(i32, i32, i32, i32), i64>(&mut store, "call")
+        .map_err(|e| format!("Plugin {} has no usable call: {:#}", plugin, e))?;
+
+    let copy_in = |store: &mut Store<HostState>, bytes: &[u8]| -> Result<i32, String> {
+        let ptr = alloc
+            .call(&mut *store, bytes.len() as i32)
+            .map_err(|e| failed(store, e))?;
+        memory
+            .write(&mut *store, ptr as u32 as usize, bytes)
+            .map_err(|e| format!("Plugin {} returned a bad allocation: {}", plugin, e))?;
+        Ok(ptr)
+    };
+    let tool_ptr = copy_in(&mut store, tool.as_bytes())?;
+    let input_ptr = copy_in(&mut store, input.as_bytes())?;
+
+    let packed = call
+        .call(
+            &mut store,
+            (tool_ptr, tool.len() as i32, input_ptr, input.len() as i32),
+        )
+        .map_err(|e| failed(&store, e))?;
+    let (ptr, len) = unpack(packed);
+    let mut output = vec![0u8; len];
+    memory
+        .read(&store, ptr, &mut output)
+        .map_err(|e| format!("Plugin {} returned an invalid result: {}", plugin, e))?;
+    String::from_utf8(output)
+        .map_err(|e| format!("Plugin {} returned invalid UTF-8: {}", plugin, e))
+}
+
+fn find_plugin(plugins_dir: &Path, name: &str) -> Result<(PathBuf, PluginManifest), String> {
+    validate_plugin_name(name)?;
+    discover(plugins_dir)
+        .into_iter()
+        .find_map(|info| {
+            let manifest = info.manifest?;
+            (manifest.name == name).then(|| (PathBuf::from(info.path), manifest))
+        })
+        .ok_or_else(|| format!("No plugin named {}", name))
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn list_plugins() -> Result<Vec<PluginInfo>, String> {
+    Ok(discover(&get_plugins_dir()?))
+}
+
+/// Tools of enabled plugins, for the frontend's tool registry
+#[tauri::command]
+pub fn list_plugin_tools() -> Result<Vec<PluginTool>, String> {
+    Ok(discover(&get_plugins_dir()?)
+        .into_iter()
+        .filter(|info| info.enabled)
+        .filter_map(|info| info.manifest)
+        .flat_map(|manifest| {
+            let PluginManifest {
+                name,
+                capabilities,
+                tools,
+                ..
+            } = manifest;
+            tools.into_iter().map(move |tool| PluginTool {
+                plugin: name.clone(),
+                name: tool.name,
+                description: tool.description,
+                input_schema: tool.input_schema,
+                capabilities: capabilities.clone(),
+            })
+        })
+        .collect())
+}
+
+/// Replace the capabilities granted to a plugin's current module; an empty list
+/// revokes all of them
+#[tauri::command]
+pub fn grant_plugin_capabilities(
+    plugin: String,
+    capabilities: Vec<Capability>,
+) -> Result<PluginInfo, String> {
+    let plugins_dir = get_plugins_dir()?;
+    let (dir, manifest) = find_plugin(&plugins_dir, &plugin)?;
+    let hash = module_hash(&read_module(&dir, &manifest)?);
+    let mut granted = capabilities;
+    granted.sort();
+    granted.dedup();
+
+    let _guard = GRANTS_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut grants = load_grants(&plugins_dir);
+    grants.insert(
+        plugin.clone(),
+        PluginGrant {
+            module_hash: hash.clone(),
+            capabilities: granted.clone(),
+        },
+    );
+    let json = serde_json::to_string_pretty(&grants).map_err(|e| e.to_string())?;
+    fs::write(plugins_dir.join(GRANTS_FILE), json)
+        .map_err(|e| format!("Failed to write plugin grants: {}", e))?;
+    log::info!("Plugin {} (module {}) granted {:?}", plugin, hash, granted);
+    Ok(PluginInfo {
+        path: dir.to_string_lossy().to_string(),
+        enabled: is_enabled(&manifest, &granted),
+        manifest: Some(manifest),
+        granted,
+        module_changed: false,
+        error: None,
+    })
+}
+
+/// Call a plugin tool. File and exec access are confined to `root_path`.
+#[tauri::command]
+pub async fn invoke_plugin_tool(
+    plugin: String,
+    tool: String,
+    input: Value,
+    root_path: Option<String>,
+) -> Result<Value, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let plugins_dir = get_plugins_dir()?;
+        let (dir, manifest) = find_plugin(&plugins_dir, &plugin)?;
+        let bytes = read_module(&dir, &manifest)?;
+        let hash = module_hash(&bytes);
+        let (granted, module_changed) =
+            granted_for(load_grants(&plugins_dir).get(&plugin), Some(&hash));
+        if module_changed {
+            return Err(format!(
+                "The module of plugin {} changed since its capabilities were granted; grant them again",
+                plugin
+            ));
+        }
+        if !is_enabled(&manifest, &granted) {
+            return Err(format!(
+                "Plugin {} needs {:?} but was granted {:?}",
+                plugin, manifest.capabilities, granted
+            ));
+        }
+        if !manifest.tools.iter().any(|t| t.name == tool) {
+            return Err(format!("Plugin {} has no tool {}", plugin, tool));
+        }
+        let module = load_module(&bytes, &hash)?;
+        let output = run_tool(
+            &module,
+            &plugin,
+            &manifest.capabilities,
+            root_path.map(PathBuf::from),
+            &tool,
+            &input.to_string(),
+            SandboxLimits::default(),
+        )?;
+        Ok(serde_json::from_str(&output).unwrap_or(Value::String(output)))
+    })
+    .await
+    .map_err(|e| format!("Plugin task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Bump allocator shared by the test modules
+    const ALLOC: &str = r#"
+        (memory (export "memory") 1)
+        (global $next (mut i32) (i32.const 1024))
+        (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+    "#;
+
+    fn module(body: &str) -> Module {
+        let wat = format!("(module {} {})", body, ALLOC);
+        Module::new(engine(), wat).unwrap()
+    }
+
+    fn echo_module() -> Module {
+        module(
+            r#"(func (export "call") (param i32 i32 i32 i32) (result i64)
+                (i64.or
+                    (i64.shl (i64.extend_i32_u (local.get 2)) (i64.const 32))
+                    (i64.extend_i32_u (local.get 3))))"#,
+        )
+    }
+
+    fn read_file_module() -> Module {
+        module(
+            r#"(import "talkcody" "read_file" (func $read_file (param i32 i32) (result i64)))
+            (func (export "call") (param i32 i32 i32 i32) (result i64)
+                (call $read_file (local.get 2) (local.get 3)))"#,
+        )
+    }
+
+    #[test]
+    fn test_manifest_validation() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest = json!({
+            "name": "word-count",
+            "version": "0.1.0",
+            "module": "plugin.wasm",
+            "capabilities": ["fs-read"],
+            "tools": [{"name": "count_words", "description": "Count words in a file"}]
+        });
+        fs::write(temp_dir.path().join(MANIFEST_FILE), manifest.to_string()).unwrap();
+        let loaded = load_manifest(temp_dir.path()).unwrap();
+        assert_eq!(loaded.capabilities, vec![Capability::FsRead]);
+        assert_eq!(loaded.tools[0].input_schema, json!({"type": "object"}));
+
+        let escaping = json!({
+            "name": "evil", "version": "1", "module": "../x.wasm",
+            "tools": [{"name": "t", "description": ""}]
+        });
+        fs::write(temp_dir.path().join(MANIFEST_FILE), escaping.to_string()).unwrap();
+        assert!(load_manifest(temp_dir.path()).is_err());
+        assert!(validate_plugin_name("Bad Name").is_err());
+    }
+
+    #[test]
+    fn test_discover_requires_every_capability_granted() {
+        let plugins_dir = TempDir::new().unwrap();
+        let dir = plugins_dir.path().join("fetcher");
+        fs::create_dir_all(&dir).unwrap();
+        let manifest = json!({
+            "name": "fetcher", "version": "1", "module": "plugin.wasm",
+            "capabilities": ["net", "fs-read"],
+            "tools": [{"name": "fetch", "description": "Fetch a URL"}]
+        });
+        fs::write(dir.join(MANIFEST_FILE), manifest.to_string()).unwrap();
+        fs::write(dir.join("plugin.wasm"), b"v1").unwrap();
+        let hash = module_hash(b"v1");
+        let grant = |capabilities: Value| {
+            let grants = json!({"fetcher": {"module_hash": hash, "capabilities": capabilities}});
+            fs::write(plugins_dir.path().join(GRANTS_FILE), grants.to_string()).unwrap();
+        };
+
+        assert!(!discover(plugins_dir.path())[0].enabled);
+        grant(json!(["net"]));
+        assert!(!discover(plugins_dir.path())[0].enabled);
+        grant(json!(["net", "fs-read"]));
+        assert!(discover(plugins_dir.path())[0].enabled);
+    }
+
+    #[test]
+    fn test_grants_are_revoked_when_the_module_changes() {
+        let plugins_dir = TempDir::new().unwrap();
+        let dir = plugins_dir.path().join("fetcher");
+        fs::create_dir_all(&dir).unwrap();
+        let manifest = json!({
+            "name": "fetcher", "version": "1", "module": "plugin.wasm",
+            "capabilities": ["net"],
+            "tools": [{"name": "fetch", "description": "Fetch a URL"}]
+        });
+        fs::write(dir.join(MANIFEST_FILE), manifest.to_string()).unwrap();
+        fs::write(dir.join("plugin.wasm"), b"v1").unwrap();
+        let grants =
+            json!({"fetcher": {"module_hash": module_hash(b"v1"), "capabilities": ["net"]}});
+        fs::write(plugins_dir.path().join(GRANTS_FILE), grants.to_string()).unwrap();
+        assert!(discover(plugins_dir.path())[0].enabled);
+
+        fs::write(dir.join("plugin.wasm"), b"v2").unwrap();
+        let info = &discover(plugins_dir.path())[0];
+        assert!(!info.enabled);
+        assert!(info.module_changed);
+        assert!(info.granted.is_empty());
+    }
+
+    #[test]
+    fn test_run_tool_round_trips_json() {
+        let output = run_tool(
+            &echo_module(),
+            "echo",
+            &[],
+            None,
+            "echo",
+            r#"{"text":"héllo"}"#,
+            SandboxLimits::default(),
+        )
+        .unwrap();
+        assert_eq!(output, r#"{"text":"héllo"}"#);
+    }
+
+    #[test]
+    fn test_host_functions_need_their_capability() {
+        let workspace = TempDir::new().unwrap();
+        fs::write(workspace.path().join("notes.txt"), "hello").unwrap();
+        let module = read_file_module();
+        let run = |granted: &[Capability], path: &str| {
+            run_tool(
+                &module,
+                "reader",
+                granted,
+                Some(workspace.path().to_path_buf()),
+                "read",
+                path,
+                SandboxLimits::default(),
+            )
+        };
+
+        let error = run(&[], "notes.txt").unwrap_err();
+        assert!(error.contains("fs-read"), "{}", error);
+
+        let output: Value =
+            serde_json::from_str(&run(&[Capability::FsRead], "notes.txt").unwrap()).unwrap();
+        assert_eq!(output, json!({"ok": true, "data": "hello"}));
+
+        // Paths are confined to the workspace
+        let output: Value =
+            serde_json::from_str(&run(&[Capability::FsRead], "../outside.txt").unwrap()).unwrap();
+        assert_eq!(output["ok"], false);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_does_not_inherit_the_app_environment() {
+        let workspace = TempDir::new().unwrap();
+        std::env::set_var("TALKCODY_PLUGIN_TEST_SECRET", "hunter2");
+        let state = HostState {
+            plugin: "env".to_string(),
+            workspace_root: Some(workspace.path().to_path_buf()),
+            limits: StoreLimitsBuilder::new().build(),
+        };
+        let output = host_exec(&state, r#"{"program": "env"}"#).unwrap();
+        let stdout = output["stdout"].as_str().unwrap();
+        assert!(
+            !stdout.contains("TALKCODY_PLUGIN_TEST_SECRET"),
+            "{}",
+            stdout
+        );
+        assert!(stdout.contains("PATH="), "{}", stdout);
+    }
+
+    #[test]
+    fn test_imports_outside_the_host_module_are_refused() {
+        let module = module(
+            r#"(import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))
+            (func (export "call") (param i32 i32 i32 i32) (result i64) (i64.const 0))"#,
+        );
+        let error =
+            run_tool(&module, "p", &[], None, "t", "{}", SandboxLimits::default()).unwrap_err();
+        assert!(error.contains("does not provide"), "{}", error);
+    }
+
+    #[test]
+    fn test_fuel_limit_stops_runaway_plugins() {
+        let module = module(
+            r#"(func (export "call") (param i32 i32 i32 i32) (result i64)
+                (loop $spin (br $spin))
+                (i64.const 0))"#,
+        );
+        let limits = SandboxLimits {
+            fuel: 100_000,
+            ..SandboxLimits::default()
+        };
+        let error = run_tool(&module, "spin", &[], None, "t", "{}", limits).unwrap_err();
+        assert!(error.contains("CPU budget"), "{}", error);
+    }
+}
@@ -0,0 +1,218 @@
+// Position encoding
+//
+// Tree-sitter and the regex-based scanners report columns in UTF-8 bytes, while editor
+// components (Monaco, CodeMirror) count UTF-16 code units. The two only agree on ASCII
+// lines; on lines with emoji or CJK text, highlights drift. Range-returning commands
+// take an optional `encoding` and convert their columns with these helpers.
+//
+// Lines and columns are 1-based throughout, matching SymbolInfo and SyntaxIssue.
+
+use crate::code_navigation::SymbolInfo;
+use crate::text_slice::floor_char_boundary;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PositionEncoding {
+    /// Byte columns, as reported by tree-sitter
+    #[default]
+    Utf8,
+    /// UTF-16 code units, as used by browser-based editors
+    Utf16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Convert a 1-based byte column on `line` to a 1-based UTF-16 column
+pub fn utf16_column(line: &str, byte_column: u32) -> u32 {
+    let offset = floor_char_boundary(line, byte_column.saturating_sub(1) as usize);
+    line[..offset].encode_utf16().count() as u32 + 1
+}
+
+/// Convert a 1-based UTF-16 column on `line` to a 1-based byte column
+pub fn byte_column(line: &str, utf16_column: u32) -> u32 {
+    let target = utf16_column.saturating_sub(1) as usize;
+    let mut units = 0;
+    for (offset, ch) in line.char_indices() {
+        if units >= target {
+            return offset as u32 + 1;
+        }
+        units += ch.len_utf16();
+    }
+    line.len() as u32 + 1
+}
+
+/// Line lookup for converting many positions in the same content
+pub struct LineIndex<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(content: &'a str) -> Self {
+        Self {
+            lines: content.lines().collect(),
+        }
+    }
+
+    /// Text of 1-based `line`, without its line ending
+    pub fn line(&self, line: u32) -> Option<&'a str> {
+        self.lines.get((line as usize).checked_sub(1)?).copied()
+    }
+
+    pub fn to_utf16(&self, position: Position) -> Position {
+        match self.line(position.line) {
+            Some(text) => Position {
+                line: position.line,
+                column: utf16_column(text, position.column),
+            },
+            None => position,
+        }
+    }
+
+    pub fn to_utf8(&self, position: Position) -> Position {
+        match self.line(position.line) {
+            Some(text) => Position {
+                line: position.line,
+                column: byte_column(text, position.column),
+            },
+            None => position,
+        }
+    }
+}
+
+/// Reads each file at most once while converting positions across files.
+/// Positions in unreadable files are left unchanged.
+#[derive(Default)]
+pub struct FileContents {
+    files: HashMap<String, Option<String>>,
+}
+
+impl FileContents {
+    pub fn utf16_position(&mut self, file_path: &str, position: Position) -> Position {
+        let content = self
+            .files
+            .entry(file_path.to_string())
+            .or_insert_with(|| fs::read_to_string(file_path).ok());
+        match content {
+            Some(content) => LineIndex::new(content).to_utf16(position),
+            None => position,
+        }
+    }
+}
+
+/// Convert symbol ranges from byte to UTF-16 columns in place
+pub fn symbols_to_utf16(symbols: &mut [SymbolInfo]) {
+    let mut files = FileContents::default();
+    for symbol in symbols {
+        let start = files.utf16_position(
+            &symbol.file_path,
+            Position {
+                line: symbol.start_line,
+                column: symbol.start_column,
+            },
+        );
+        let end = files.utf16_position(
+            &symbol.file_path,
+            Position {
+                line: symbol.end_line,
+                column: symbol.end_column,
+            },
+        );
+        symbol.start_column = start.column;
+        symbol.end_column = end.column;
+    }
+}
+
+/// Convert positions in `content` between byte and UTF-16 columns
+#[tauri::command]
+pub fn convert_positions(
+    content: String,
+    positions: Vec<Position>,
+    to: PositionEncoding,
+) -> Vec<Position> {
+    let index = LineIndex::new(&content);
+    positions
+        .into_iter()
+        .map(|position| match to {
+            PositionEncoding::Utf16 => index.to_utf16(position),
+            PositionEncoding::Utf8 => index.to_utf8(position),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_utf16_column() {
+        // "é" is 2 bytes / 1 unit, "😀" is 4 bytes / 2 units, "中" is 3 bytes / 1 unit
+        let line = "é😀中x";
+        assert_eq!(utf16_column(line, 1), 1);
+        assert_eq!(utf16_column(line, 3), 2);
+        assert_eq!(utf16_column(line, 7), 4);
+        assert_eq!(utf16_column(line, 10), 5);
+        assert_eq!(utf16_column(line, 11), 6);
+        // Past the end and inside a char are clamped
+        assert_eq!(utf16_column(line, 100), 6);
+        assert_eq!(utf16_column(line, 4), 2);
+    }
+
+    #[test]
+    fn test_byte_column_roundtrip() {
+        let line = "let s = \"😀\"; // 中文";
+        for (offset, _) in line.char_indices() {
+            let byte = offset as u32 + 1;
+            assert_eq!(byte_column(line, utf16_column(line, byte)), byte);
+        }
+        assert_eq!(byte_column(line, 1000), line.len() as u32 + 1);
+    }
+
+    #[test]
+    fn test_convert_positions() {
+        let content = "plain\r\nconst 名前 = 1;\n";
+        let converted = convert_positions(
+            content.to_string(),
+            vec![
+                Position { line: 1, column: 3 },
+                Position {
+                    line: 2,
+                    column: 13,
+                },
+                Position { line: 9, column: 4 },
+            ],
+            PositionEncoding::Utf16,
+        );
+        assert_eq!(converted[0].column, 3);
+        assert_eq!(converted[1].column, 9);
+        assert_eq!(converted[2], Position { line: 9, column: 4 });
+    }
+
+    #[test]
+    fn test_symbols_to_utf16() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("a.ts");
+        fs::write(&path, "const 😀 = 1; function greet() {}\n").unwrap();
+        let mut symbols = vec![SymbolInfo {
+            name: "greet".to_string(),
+            kind: "function".to_string(),
+            file_path: path.to_string_lossy().to_string(),
+            lang_family: "js_family".to_string(),
+            start_line: 1,
+            start_column: 26,
+            end_line: 1,
+            end_column: 31,
+            qualified_name: None,
+        }];
+        symbols_to_utf16(&mut symbols);
+        assert_eq!(symbols[0].start_column, 24);
+        assert_eq!(symbols[0].end_column, 29);
+    }
+}
@@ -0,0 +1,814 @@
+// Precompiled code intelligence
+//
+// Tree-sitter definitions plus text search get most navigation right, but cannot tell
+// two `render` methods apart or follow a re-export. Compiler-backed indexers can:
+// scip-typescript, rust-analyzer and friends write SCIP (`index.scip`) or LSIF
+// (`dump.lsif`) dumps, usually in CI. Importing such a dump merges its occurrences
+// into the navigation service, and definition/reference lookups at a position then
+// answer from the compiler's resolution instead of name matching.
+//
+// Accepted formats: binary SCIP, SCIP in the protobuf JSON mapping (what
+// `code_nav_export_index` writes), and LSIF as JSON lines or a JSON array. Paths in
+// the dump are taken relative to its project root and re-rooted at the workspace, so a
+// dump built on a CI machine applies locally. Positions are kept as the indexer wrote
+// them; they can be off on lines whose text differs from the indexed commit.
+
+use crate::code_navigation::{resolve_nav, CodeNavState, CodeNavigationService, SymbolInfo};
+use crate::workspace_state::WorkspaceState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+/// SymbolRole.Definition in SCIP occurrences
+const SCIP_DEFINITION_ROLE: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DumpFormat {
+    Scip,
+    Lsif,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreciseImport {
+    pub format: DumpFormat,
+    pub documents: usize,
+    pub occurrences: usize,
+    pub symbols: usize,
+}
+
+/// A symbol occurrence; lines and columns are 1-based like SymbolInfo
+#[derive(Debug, Clone, PartialEq)]
+struct Occurrence {
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+    /// Indexer-specific symbol id, shared by all occurrences of one symbol
+    symbol: String,
+    definition: bool,
+}
+
+impl Occurrence {
+    fn contains(&self, line: u32, column: u32) -> bool {
+        (self.start_line, self.start_column) <= (line, column)
+            && (line, column) < (self.end_line, self.end_column)
+    }
+
+    fn span(&self) -> (u32, u32) {
+        (
+            self.end_line.saturating_sub(self.start_line),
+            self.end_column.saturating_sub(self.start_column),
+        )
+    }
+}
+
+/// Occurrences imported from SCIP/LSIF dumps, by absolute file path
+#[derive(Debug, Default)]
+pub struct PreciseIndex {
+    files: HashMap<String, Vec<Occurrence>>,
+    display_names: HashMap<String, String>,
+}
+
+impl PreciseIndex {
+    /// Take over `other`'s documents, replacing what was imported for the same files
+    pub fn merge(&mut self, other: PreciseIndex) {
+        self.files.extend(other.files);
+        self.display_names.extend(other.display_names);
+    }
+
+    fn occurrence_count(&self) -> usize {
+        self.files.values().map(Vec::len).sum()
+    }
+
+    fn symbol_count(&self) -> usize {
+        self.files
+            .values()
+            .flatten()
+            .map(|o| o.symbol.as_str())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// The innermost occurrence covering the position
+    fn symbol_at(&self, file_path: &str, line: u32, column: u32) -> Option<&str> {
+        self.files
+            .get(file_path)?
+            .iter()
+            .filter(|o| o.contains(line, column))
+            .min_by_key(|o| o.span())
+            .map(|o| o.symbol.as_str())
+    }
+
+    fn occurrences_of<'a>(
+        &'a self,
+        symbol: &'a str,
+    ) -> impl Iterator<Item = (&'a String, &'a Occurrence)> + 'a {
+        self.files.iter().flat_map(move |(file_path, occurrences)| {
+            occurrences
+                .iter()
+                .filter(move |o| o.symbol == symbol)
+                .map(move |o| (file_path, o))
+        })
+    }
+
+    fn to_symbol(&self, file_path: &str, occurrence: &Occurrence) -> SymbolInfo {
+        let name = self
+            .display_names
+            .get(&occurrence.symbol)
+            .cloned()
+            .unwrap_or_else(|| descriptor_name(&occurrence.symbol));
+        let lang_family = CodeNavigationService::get_lang_id_from_path(file_path)
+            .map_or("unknown", |lang| {
+                CodeNavigationService::get_lang_family(&lang)
+            });
+        SymbolInfo {
+            name,
+            kind: descriptor_kind(&occurrence.symbol).to_string(),
+            file_path: file_path.to_string(),
+            lang_family: lang_family.to_string(),
+            start_line: occurrence.start_line,
+            start_column: occurrence.start_column,
+            end_line: occurrence.end_line,
+            end_column: occurrence.end_column,
+            qualified_name: None,
+        }
+    }
+
+    fn lookup(
+        &self,
+        file_path: &str,
+        line: u32,
+        column: u32,
+        keep: impl Fn(&Occurrence) -> bool,
+    ) -> Vec<SymbolInfo> {
+        let Some(symbol) = self.symbol_at(file_path, line, column) else {
+            return Vec::new();
+        };
+        let mut found: Vec<SymbolInfo> = self
+            .occurrences_of(symbol)
+            .filter(|(_, o)| keep(o))
+            .map(|(path, o)| self.to_symbol(path, o))
+            .collect();
+        found.sort_by(|a, b| {
+            (&a.file_path, a.start_line, a.start_column).cmp(&(
+                &b.file_path,
+                b.start_line,
+                b.start_column,
+            ))
+        });
+        found
+    }
+
+    /// Definitions of the symbol at the position, wherever they are
+    pub fn definitions_at(&self, file_path: &str, line: u32, column: u32) -> Vec<SymbolInfo> {
+        self.lookup(file_path, line, column, |o| o.definition)
+    }
+
+    /// Every occurrence of the symbol at the position, definitions included
+    pub fn references_at(&self, file_path: &str, line: u32, column: u32) -> Vec<SymbolInfo> {
+        self.lookup(file_path, line, column, |_| true)
+    }
+}
+
+/// The name in a SCIP symbol's last descriptor: `... src/`app.ts`/Foo#render().` -> `render`
+fn descriptor_name(symbol: &str) -> String {
+    let mut rest = symbol.trim_end_matches(['.', '#', '/', ':', '!']);
+    // Method disambiguator: `render(+1)`
+    if rest.ends_with(')') {
+        if let Some(open) = rest.rfind('(') {
+            rest = &rest[..open];
+        }
+    }
+    if let Some(quoted) = rest.strip_suffix('`') {
+        return quoted
+            .rfind('`')
+            .map_or(quoted, |open| &quoted[open + 1..])
+            .to_string();
+    }
+    rest.rsplit([' ', '#', '.', '/', ':'])
+        .next()
+        .unwrap_or(rest)
+        .to_string()
+}
+
+fn descriptor_kind(symbol: &str) -> &'static str {
+    if symbol.ends_with(").") {
+        "function"
+    } else if symbol.ends_with('#') {
+        "class"
+    } else if symbol.ends_with('/') {
+        "module"
+    } else {
+        "symbol"
+    }
+}
+
+/// 0-based SCIP range (`[line, start, end]` or `[start line, start, end line, end]`)
+/// as a 1-based occurrence
+fn scip_occurrence(range: &[u32], symbol: String, roles: u64) -> Option<Occurrence> {
+    let (start_line, start_column, end_line, end_column) = match *range {
+        [line, start, end] => (line, start, line, end),
+        [start_line, start, end_line, end] => (start_line, start, end_line, end),
+        _ => return None,
+    };
+    Some(Occurrence {
+        start_line: start_line + 1,
+        start_column: start_column + 1,
+        end_line: end_line + 1,
+        end_column: end_column + 1,
+        symbol,
+        definition: roles & SCIP_DEFINITION_ROLE != 0,
+    })
+}
+
+/// A decoded SCIP document, whichever encoding it came from
+#[derive(Default)]
+struct ScipDocument {
+    relative_path: String,
+    occurrences: Vec<(Vec<u32>, String, u64)>,
+    display_names: Vec<(String, String)>,
+}
+
+impl PreciseIndex {
+    fn add_scip_document(&mut self, root_path: &str, document: ScipDocument) {
+        let file_path = join_root(root_path, &document.relative_path);
+        // Local symbols are only unique within their document
+        let scoped = |symbol: String| {
+            if symbol.starts_with("local ") {
+                format!("{}:{}", document.relative_path, symbol)
+            } else {
+                symbol
+            }
+        };
+        let occurrences: Vec<Occurrence> = document
+            .occurrences
+            .into_iter()
+            .filter(|(_, symbol, _)| !symbol.is_empty())
+            .filter_map(|(range, symbol, roles)| scip_occurrence(&range, scoped(symbol), roles))
+            .collect();
+        for (symbol, name) in document.display_names {
+            if !name.is_empty() {
+                self.display_names.insert(scoped(symbol), name);
+            }
+        }
+        self.files.insert(file_path, occurrences);
+    }
+}
+
+fn join_root(root_path: &str, relative_path: &str) -> String {
+    Path::new(root_path)
+        .join(relative_path)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Minimal protobuf wire-format reader, enough for the SCIP messages used here
+struct ProtoReader<'a> {
+    buf: &'a [u8],
+}
+
+enum ProtoValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.buf.len() {
+            return Err("Truncated protobuf message".to_string());
+        }
+        let (taken, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(taken)
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Invalid protobuf varint".to_string())
+    }
+
+    fn next_field(&mut self) -> Result<Option<(u64, ProtoValue<'a>)>, String> {
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let value = match key & 7 {
+            0 => ProtoValue::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                ProtoValue::Fixed
+            }
+            2 => {
+                let len = self.varint()? as usize;
+                ProtoValue::Bytes(self.take(len)?)
+            }
+            5 => {
+                self.take(4)?;
+                ProtoValue::Fixed
+            }
+            wire_type => return Err(format!("Unsupported protobuf wire type {}", wire_type)),
+        };
+        Ok(Some((key >> 3, value)))
+    }
+}
+
+fn proto_string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+/// Occurrence { 1: range (packed int32), 2: symbol, 3: symbol_roles }
+fn decode_scip_occurrence(bytes: &[u8]) -> Result<(Vec<u32>, String, u64), String> {
+    let mut reader = ProtoReader::new(bytes);
+    let (mut range, mut symbol, mut roles) = (Vec::new(), String::new(), 0);
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, ProtoValue::Bytes(packed)) => {
+                let mut packed = ProtoReader::new(packed);
+                while !packed.buf.is_empty() {
+                    range.push(packed.varint()? as u32);
+                }
+            }
+            (1, ProtoValue::Varint(v)) => range.push(v as u32),
+            (2, ProtoValue::Bytes(b)) => symbol = proto_string(b),
+            (3, ProtoValue::Varint(v)) => roles = v,
+            _ => {}
+        }
+    }
+    Ok((range, symbol, roles))
+}
+
+/// SymbolInformation { 1: symbol, 6: display_name }
+fn decode_scip_symbol(bytes: &[u8]) -> Result<(String, String), String> {
+    let mut reader = ProtoReader::new(bytes);
+    let (mut symbol, mut display_name) = (String::new(), String::new());
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, ProtoValue::Bytes(b)) => symbol = proto_string(b),
+            (6, ProtoValue::Bytes(b)) => display_name = proto_string(b),
+            _ => {}
+        }
+    }
+    Ok((symbol, display_name))
+}
+
+/// Document { 1: relative_path, 2: occurrences, 3: symbols }
+fn decode_scip_document(bytes: &[u8]) -> Result<ScipDocument, String> {
+    let mut reader = ProtoReader::new(bytes);
+    let mut document = ScipDocument::default();
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, ProtoValue::Bytes(b)) => document.relative_path = proto_string(b),
+            (2, ProtoValue::Bytes(b)) => document.occurrences.push(decode_scip_occurrence(b)?),
+            (3, ProtoValue::Bytes(b)) => document.display_names.push(decode_scip_symbol(b)?),
+            _ => {}
+        }
+    }
+    Ok(document)
+}
+
+/// Binary SCIP: Index { 1: metadata, 2: documents, 3: external_symbols }
+pub fn parse_scip(bytes: &[u8], root_path: &str) -> Result<PreciseIndex, String> {
+    let mut reader = ProtoReader::new(bytes);
+    let mut index = PreciseIndex::default();
+    while let Some((field, value)) = reader.next_field()? {
+        if let (2, ProtoValue::Bytes(b)) = (field, value) {
+            let document = decode_scip_document(b)?;
+            index.add_scip_document(root_path, document);
+        }
+    }
+    Ok(index)
+}
+
+/// SCIP in the protobuf JSON mapping
+pub fn parse_scip_json(value: &Value, root_path: &str) -> Result<PreciseIndex, String> {
+    let documents = value["documents"]
+        .as_array()
+        .ok_or("SCIP JSON has no documents")?;
+    let mut index = PreciseIndex::default();
+    for document in documents {
+        let str_field = |v: &Value, key: &str| v[key].as_str().unwrap_or_default().to_string();
+        let occurrences = document["occurrences"]
+            .as_array()
+            .map(|occurrences| {
+                occurrences
+                    .iter()
+                    .map(|o| {
+                        let range = o["range"]
+                            .as_array()
+                            .map(|r| {
+                                r.iter()
+                                    .filter_map(Value::as_u64)
+                                    .map(|n| n as u32)
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        (
+                            range,
+                            str_field(o, "symbol"),
+                            o["symbolRoles"].as_u64().unwrap_or(0),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let display_names = document["symbols"]
+            .as_array()
+            .map(|symbols| {
+                symbols
+                    .iter()
+                    .map(|s| (str_field(s, "symbol"), str_field(s, "displayName")))
+                    .collect()
+            })
+            .unwrap_or_default();
+        index.add_scip_document(
+            root_path,
+            ScipDocument {
+                relative_path: str_field(document, "relativePath"),
+                occurrences,
+                display_names,
+            },
+        );
+    }
+    Ok(index)
+}
+
+fn uri_path(uri: &str) -> &str {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+    // `file:///C:/repo` -> `C:/repo`
+    match path.as_bytes() {
+        [b'/', _, b':', ..] => &path[1..],
+        _ => path,
+    }
+}
+
+fn lsif_position(position: &Value) -> (u32, u32) {
+    (
+        position["line"].as_u64().unwrap_or(0) as u32 + 1,
+        position["character"].as_u64().unwrap_or(0) as u32 + 1,
+    )
+}
+
+/// LSIF dump as JSON lines or a JSON array
+pub fn parse_lsif(text: &str, root_path: &str) -> Result<PreciseIndex, String> {
+    let elements: Vec<Value> = if text.trim_start().starts_with('[') {
+        serde_json::from_str(text).map_err(|e| format!("Invalid LSIF: {}", e))?
+    } else {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(|e| format!("Invalid LSIF: {}", e)))
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut project_root = String::new();
+    let mut documents: HashMap<u64, String> = HashMap::new();
+    let mut ranges: HashMap<u64, &Value> = HashMap::new();
+    let mut range_document: HashMap<u64, u64> = HashMap::new();
+    let mut next: HashMap<u64, u64> = HashMap::new();
+    let mut definition_results: HashSet<u64> = HashSet::new();
+    let mut definition_ranges: HashSet<u64> = HashSet::new();
+
+    for element in &elements {
+        let id = element["id"].as_u64().unwrap_or(0);
+        let label = element["label"].as_str().unwrap_or_default();
+        let in_vs = || {
+            element["inVs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_u64)
+        };
+        let out_v = element["outV"].as_u64().unwrap_or(0);
+        match (element["type"].as_str(), label) {
+            (Some("vertex"), "metaData") => {
+                project_root = uri_path(element["projectRoot"].as_str().unwrap_or_default())
+                    .trim_end_matches('/')
+                    .to_string();
+            }
+            (Some("vertex"), "document") => {
+                let uri = element["uri"].as_str().unwrap_or_default();
+                documents.insert(id, uri_path(uri).to_string());
+            }
+            (Some("vertex"), "range") => {
+                ranges.insert(id, element);
+            }
+            (Some("vertex"), "definitionResult") => {
+                definition_results.insert(id);
+            }
+            (Some("edge"), "contains") => {
+                for range in in_vs() {
+                    range_document.insert(range, out_v);
+                }
+            }
+            (Some("edge"), "next") => {
+                if let Some(in_v) = element["inV"].as_u64() {
+                    next.insert(out_v, in_v);
+                }
+            }
+            (Some("edge"), "item") => {
+                let is_definition = definition_results.contains(&out_v)
+                    || element["property"].as_str() == Some("definitions");
+                for range in in_vs() {
+                    if is_definition {
+                        definition_ranges.insert(range);
+                    }
+                    if let Some(document) = element["document"].as_u64() {
+                        range_document.entry(range).or_insert(document);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let result_set = |range: u64| {
+        let mut current = next.get(&range).copied()?;
+        // Result sets can chain; the guard stops on malformed cycles
+        for _ in 0..16 {
+            match next.get(&current) {
+                Some(&following) => current = following,
+                None => break,
+            }
+        }
+        Some(current)
+    };
+
+    let mut index = PreciseIndex::default();
+    for (&id, range) in &ranges {
+        let (Some(symbol), Some(document)) = (
+            result_set(id),
+            range_document.get(&id).and_then(|d| documents.get(d)),
+        ) else {
+            continue;
+        };
+        let symbol = format!("lsif:{}", symbol);
+        let relative = document
+            .strip_prefix(&project_root)
+            .unwrap_or(document)
+            .trim_start_matches('/');
+        let (start_line, start_column) = lsif_position(&range["start"]);
+        let (end_line, end_column) = lsif_position(&range["end"]);
+        let definition = definition_ranges.contains(&id);
+        if let Some(text) = range["tag"]["text"].as_str() {
+            if definition || !index.display_names.contains_key(&symbol) {
+                index.display_names.insert(symbol.clone(), text.to_string());
+            }
+        }
+        index
+            .files
+            .entry(join_root(root_path, relative))
+            .or_default()
+            .push(Occurrence {
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+                symbol,
+                definition,
+            });
+    }
+    Ok(index)
+}
+
+/// Parse a dump, telling the formats apart by content
+pub fn parse_dump(bytes: &[u8], root_path: &str) -> Result<(DumpFormat, PreciseIndex), String> {
+    // Binary SCIP starts with the metadata or a document field key
+    let text = match bytes.first() {
+        Some(0x0a | 0x12) | None => None,
+        Some(_) => std::str::from_utf8(bytes).ok().map(str::trim_start),
+    };
+    match text {
+        Some(text) if text.starts_with('[') => Ok((DumpFormat::Lsif, parse_lsif(text, root_path)?)),
+        Some(text) if text.starts_with('{') => {
+            let first_line = text.lines().next().unwrap_or_default();
+            match serde_json::from_str::<Value>(first_line) {
+                // JSON lines: every element is a vertex or an edge
+                Ok(element) if element.get("type").is_some() => {
+                    Ok((DumpFormat::Lsif, parse_lsif(text, root_path)?))
+                }
+                _ => {
+                    let value: Value = serde_json::from_str(text)
+                        .map_err(|e| format!("Invalid SCIP JSON: {}", e))?;
+                    Ok((DumpFormat::Scip, parse_scip_json(&value, root_path)?))
+                }
+            }
+        }
+        _ => Ok((DumpFormat::Scip, parse_scip(bytes, root_path)?)),
+    }
+}
+
+// Tauri commands
+
+/// Import a SCIP or LSIF dump for the workspace at `root_path`
+#[tauri::command]
+pub async fn code_nav_import_precise(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    root_path: String,
+    dump_path: String,
+) -> Result<PreciseImport, String> {
+    let bytes = fs::read(&dump_path).map_err(|e| format!("Failed to read {}: {}", dump_path, e))?;
+    let (format, imported) = parse_dump(&bytes, &root_path)?;
+    let report = PreciseImport {
+        format,
+        documents: imported.files.len(),
+        occurrences: imported.occurrence_count(),
+        symbols: imported.symbol_count(),
+    };
+
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let mut service = nav
+        .write()
+        .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+    service.precise.merge(imported);
+    log::info!(
+        "Imported {:?} dump {} ({} documents, {} occurrences)",
+        format,
+        dump_path,
+        report.documents,
+        report.occurrences
+    );
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn code_nav_precise_definition(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    file_path: String,
+    line: u32,
+    column: u32,
+) -> Result<Vec<SymbolInfo>, String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let service = nav
+        .read()
+        .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+    Ok(service.precise.definitions_at(&file_path, line, column))
+}
+
+#[tauri::command]
+pub async fn code_nav_precise_references(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    file_path: String,
+    line: u32,
+    column: u32,
+) -> Result<Vec<SymbolInfo>, String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let service = nav
+        .read()
+        .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+    Ok(service.precise.references_at(&file_path, line, column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index_export;
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn bytes_field(field: u64, bytes: &[u8], out: &mut Vec<u8>) {
+        varint(field << 3 | 2, out);
+        varint(bytes.len() as u64, out);
+        out.extend_from_slice(bytes);
+    }
+
+    fn occurrence(range: &[u64], symbol: &str, roles: u64) -> Vec<u8> {
+        let mut packed = Vec::new();
+        for &n in range {
+            varint(n, &mut packed);
+        }
+        let mut out = Vec::new();
+        bytes_field(1, &packed, &mut out);
+        bytes_field(2, symbol.as_bytes(), &mut out);
+        varint(3 << 3, &mut out);
+        varint(roles, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_parse_binary_scip() {
+        let render = "scip-typescript npm app 1.0 src/`view.ts`/View#render().";
+        let mut view = Vec::new();
+        bytes_field(1, b"src/view.ts", &mut view);
+        bytes_field(2, &occurrence(&[2, 2, 8], render, 1), &mut view);
+        bytes_field(2, &occurrence(&[5, 6, 7], "local 0", 1), &mut view);
+        let mut main = Vec::new();
+        bytes_field(1, b"src/main.ts", &mut main);
+        bytes_field(2, &occurrence(&[9, 7, 13], render, 0), &mut main);
+        bytes_field(2, &occurrence(&[3, 6, 7], "local 0", 1), &mut main);
+        let mut index = Vec::new();
+        bytes_field(1, b"\x1a\x0ffile:///ci/repo", &mut index);
+        bytes_field(2, &view, &mut index);
+        bytes_field(2, &main, &mut index);
+
+        let (format, precise) = parse_dump(&index, "/work/repo").unwrap();
+        assert_eq!(format, DumpFormat::Scip);
+        assert_eq!(precise.occurrence_count(), 4);
+        // `local 0` in two documents are different symbols
+        assert_eq!(precise.symbol_count(), 3);
+
+        let definitions = precise.definitions_at("/work/repo/src/main.ts", 10, 9);
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].name, "render");
+        assert_eq!(definitions[0].kind, "function");
+        assert_eq!(definitions[0].file_path, "/work/repo/src/view.ts");
+        assert_eq!(
+            (definitions[0].start_line, definitions[0].start_column),
+            (3, 3)
+        );
+        assert_eq!(definitions[0].lang_family, "js_family");
+
+        let references = precise.references_at("/work/repo/src/view.ts", 3, 3);
+        assert_eq!(references.len(), 2);
+        assert!(precise
+            .definitions_at("/work/repo/src/main.ts", 10, 1)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_parse_exported_scip_json() {
+        let mut service = CodeNavigationService::new();
+        service.index_file(
+            "/ci/repo/lib.rs",
+            "struct Widget;\n\nimpl Widget {\n    fn resize(&self) {}\n}\n",
+            "rust",
+        );
+        let exported = index_export::export_index(
+            &service.to_persisted("/ci/repo", HashMap::new()),
+            index_export::ExportFormat::Scip,
+        )
+        .unwrap();
+
+        let (format, precise) = parse_dump(exported.as_bytes(), "/work/repo").unwrap();
+        assert_eq!(format, DumpFormat::Scip);
+        let definitions = precise.definitions_at("/work/repo/lib.rs", 4, 9);
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].name, "resize");
+    }
+
+    #[test]
+    fn test_parse_lsif() {
+        let lsif = r#"{"id":1,"type":"vertex","label":"metaData","version":"0.4.3","projectRoot":"file:///ci/repo"}
+{"id":2,"type":"vertex","label":"document","uri":"file:///ci/repo/a.py","languageId":"python"}
+{"id":3,"type":"vertex","label":"range","start":{"line":0,"character":4},"end":{"line":0,"character":9},"tag":{"type":"definition","text":"greet"}}
+{"id":4,"type":"vertex","label":"range","start":{"line":3,"character":0},"end":{"line":3,"character":5}}
+{"id":5,"type":"vertex","label":"resultSet"}
+{"id":6,"type":"edge","label":"next","outV":3,"inV":5}
+{"id":7,"type":"edge","label":"next","outV":4,"inV":5}
+{"id":8,"type":"vertex","label":"definitionResult"}
+{"id":9,"type":"edge","label":"textDocument/definition","outV":5,"inV":8}
+{"id":10,"type":"edge","label":"item","outV":8,"inVs":[3],"document":2}
+{"id":11,"type":"vertex","label":"referenceResult"}
+{"id":12,"type":"edge","label":"item","outV":11,"inVs":[4],"document":2,"property":"references"}
+{"id":13,"type":"edge","label":"contains","outV":2,"inVs":[3,4]}
+"#;
+        let (format, precise) = parse_dump(lsif.as_bytes(), "/work/repo").unwrap();
+        assert_eq!(format, DumpFormat::Lsif);
+
+        let definitions = precise.definitions_at("/work/repo/a.py", 4, 2);
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].name, "greet");
+        assert_eq!(
+            (definitions[0].start_line, definitions[0].start_column),
+            (1, 5)
+        );
+        assert_eq!(precise.references_at("/work/repo/a.py", 1, 5).len(), 2);
+    }
+
+    #[test]
+    fn test_descriptor_name() {
+        assert_eq!(descriptor_name("npm pkg 1.0 src/`a.ts`/Foo#bar()."), "bar");
+        assert_eq!(
+            descriptor_name("rust-analyzer cargo std 1.0 fmt/Display#"),
+            "Display"
+        );
+        assert_eq!(descriptor_name("npm pkg 1.0 `weird name`."), "weird name");
+        assert_eq!(descriptor_name("npm pkg 1.0 Foo#render(+1)."), "render");
+    }
+}
@@ -0,0 +1,402 @@
+// Prompt template library
+// Templates are markdown files with an optional frontmatter block, loaded from
+// ~/.talkcody/prompts/ (global) and <project>/.talkcody/prompts/ (per-project overrides).
+//
+// Syntax inside the template body:
+// - `{{name}}`            substitutes a variable
+// - `{{name|fallback}}`   substitutes a variable, or the fallback text when it is not provided
+// - `{{> other}}`         includes another template (rendered with the same variables)
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Maximum include depth, guards against runaway recursion in malformed templates
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub description: String,
+    pub variables: Vec<String>,
+    pub body: String,
+    pub source: String, // "global" or "project"
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedPrompt {
+    pub name: String,
+    pub content: String,
+    pub included: Vec<String>,
+}
+
+/// Get the global prompts directory (~/.talkcody/prompts/)
+fn get_global_prompts_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody").join("prompts"))
+}
+
+/// Get the project prompts directory (<root>/.talkcody/prompts/)
+fn get_project_prompts_dir(root_path: &str) -> PathBuf {
+    Path::new(root_path).join(".talkcody").join("prompts")
+}
+
+/// Split a markdown document into its frontmatter key/value pairs and body.
+/// Frontmatter is a leading `---` fenced block of `key: value` lines.
+pub fn parse_frontmatter(raw: &str) -> (BTreeMap<String, String>, String) {
+    let mut fields = BTreeMap::new();
+    let normalized = raw.trim_start_matches('\u{feff}');
+
+    let rest = match normalized.strip_prefix("---") {
+        Some(rest) if rest.starts_with('\n') || rest.starts_with("\r\n") => rest,
+        _ => return (fields, normalized.to_string()),
+    };
+
+    let mut body_start = None;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let trimmed = line.trim();
+        if trimmed == "---" {
+            body_start = Some(offset);
+            break;
+        }
+        if let Some((key, value)) = trimmed.split_once(':') {
+            let value = value.trim().trim_matches('"').trim_matches('\'');
+            fields.insert(key.trim().to_string(), value.to_string());
+        }
+    }
+
+    match body_start {
+        Some(pos) => (
+            fields,
+            rest[pos..].trim_start_matches(['\r', '\n']).to_string(),
+        ),
+        // Unterminated frontmatter: treat the whole document as body
+        None => (BTreeMap::new(), normalized.to_string()),
+    }
+}
+
+fn parse_template(name: &str, raw: &str, source: &str, path: &Path) -> PromptTemplate {
+    let (fields, body) = parse_frontmatter(raw);
+
+    let mut variables: Vec<String> = fields
+        .get("variables")
+        .map(|v| {
+            v.trim_matches(|c| c == '[' || c == ']')
+                .split(',')
+                .map(|s| s.trim().trim_matches('"').to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Fall back to the placeholders actually used in the body
+    if variables.is_empty() {
+        variables = extract_placeholders(&body);
+    }
+
+    PromptTemplate {
+        name: fields
+            .get("name")
+            .cloned()
+            .unwrap_or_else(|| name.to_string()),
+        description: fields.get("description").cloned().unwrap_or_default(),
+        variables,
+        body,
+        source: source.to_string(),
+        path: path.to_string_lossy().to_string(),
+    }
+}
+
+/// Collect the distinct variable names referenced in a template body
+fn extract_placeholders(body: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let inner = after[..end].trim();
+        if !inner.starts_with('>') {
+            let name = inner.split('|').next().unwrap_or("").trim().to_string();
+            if !name.is_empty() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+fn load_dir(dir: &Path, source: &str, templates: &mut BTreeMap<String, PromptTemplate>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        match fs::read_to_string(&path) {
+            Ok(raw) => {
+                let template = parse_template(stem, &raw, source, &path);
+                templates.insert(template.name.clone(), template);
+            }
+            Err(e) => log::warn!("Failed to read prompt template {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// Load all templates, with project templates overriding global ones of the same name
+pub fn load_templates(
+    global_dir: Option<&Path>,
+    root_path: Option<&str>,
+) -> BTreeMap<String, PromptTemplate> {
+    let mut templates = BTreeMap::new();
+    if let Some(dir) = global_dir {
+        load_dir(dir, "global", &mut templates);
+    }
+    if let Some(root) = root_path {
+        load_dir(&get_project_prompts_dir(root), "project", &mut templates);
+    }
+    templates
+}
+
+/// Render a template by name, resolving includes and substituting variables
+pub fn render_prompt(
+    templates: &BTreeMap<String, PromptTemplate>,
+    name: &str,
+    vars: &HashMap<String, String>,
+) -> Result<RenderedPrompt, String> {
+    let mut stack = Vec::new();
+    let mut included = Vec::new();
+    let mut missing = Vec::new();
+    let content = render_inner(
+        templates,
+        name,
+        vars,
+        &mut stack,
+        &mut included,
+        &mut missing,
+    )?;
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "Missing variables for prompt '{}': {}",
+            name,
+            missing.join(", ")
+        ));
+    }
+
+    Ok(RenderedPrompt {
+        name: name.to_string(),
+        content,
+        included,
+    })
+}
+
+fn render_inner(
+    templates: &BTreeMap<String, PromptTemplate>,
+    name: &str,
+    vars: &HashMap<String, String>,
+    stack: &mut Vec<String>,
+    included: &mut Vec<String>,
+    missing: &mut Vec<String>,
+) -> Result<String, String> {
+    if stack.iter().any(|n| n == name) {
+        return Err(format!(
+            "Circular include detected: {} -> {}",
+            stack.join(" -> "),
+            name
+        ));
+    }
+    if stack.len() >= MAX_INCLUDE_DEPTH {
+        return Err(format!("Include depth exceeded while rendering '{}'", name));
+    }
+
+    let template = templates
+        .get(name)
+        .ok_or_else(|| format!("Prompt template not found: {}", name))?;
+
+    stack.push(name.to_string());
+
+    let body = &template.body;
+    let mut output = String::with_capacity(body.len());
+    let mut rest = body.as_str();
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            // Unterminated placeholder: keep the remaining text verbatim
+            output.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+
+        let inner = after[..end].trim();
+        if let Some(include) = inner.strip_prefix('>') {
+            let include = include.trim();
+            let rendered = render_inner(templates, include, vars, stack, included, missing)?;
+            if !included.iter().any(|n| n == include) {
+                included.push(include.to_string());
+            }
+            output.push_str(&rendered);
+        } else {
+            let (var_name, fallback) = match inner.split_once('|') {
+                Some((n, f)) => (n.trim(), Some(f.trim())),
+                None => (inner, None),
+            };
+            match (vars.get(var_name), fallback) {
+                (Some(value), _) => output.push_str(value),
+                (None, Some(fallback)) => output.push_str(fallback),
+                (None, None) => {
+                    if !missing.iter().any(|m| m == var_name) {
+                        missing.push(var_name.to_string());
+                    }
+                }
+            }
+        }
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+
+    stack.pop();
+    Ok(output)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn prompt_list_templates(root_path: Option<String>) -> Result<Vec<PromptTemplate>, String> {
+    let global_dir = get_global_prompts_dir()?;
+    let templates = load_templates(Some(&global_dir), root_path.as_deref());
+    Ok(templates.into_values().collect())
+}
+
+#[tauri::command]
+pub fn prompt_render(
+    name: String,
+    vars: HashMap<String, String>,
+    root_path: Option<String>,
+) -> Result<RenderedPrompt, String> {
+    let global_dir = get_global_prompts_dir()?;
+    let templates = load_templates(Some(&global_dir), root_path.as_deref());
+    render_prompt(&templates, &name, &vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn template(name: &str, body: &str) -> PromptTemplate {
+        PromptTemplate {
+            name: name.to_string(),
+            description: String::new(),
+            variables: extract_placeholders(body),
+            body: body.to_string(),
+            source: "global".to_string(),
+            path: String::new(),
+        }
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_frontmatter() {
+        let raw = "---\nname: review\ndescription: \"Review code\"\nvariables: [lang, diff]\n---\nReview this {{lang}} diff";
+        let (fields, body) = parse_frontmatter(raw);
+        assert_eq!(fields.get("name").unwrap(), "review");
+        assert_eq!(fields.get("description").unwrap(), "Review code");
+        assert_eq!(body, "Review this {{lang}} diff");
+
+        let t = parse_template("x", raw, "global", Path::new("x.md"));
+        assert_eq!(t.name, "review");
+        assert_eq!(t.variables, vec!["lang", "diff"]);
+    }
+
+    #[test]
+    fn test_parse_without_frontmatter() {
+        let (fields, body) = parse_frontmatter("Hello {{who}}");
+        assert!(fields.is_empty());
+        assert_eq!(body, "Hello {{who}}");
+    }
+
+    #[test]
+    fn test_render_variables_and_fallbacks() {
+        let mut templates = BTreeMap::new();
+        templates.insert(
+            "greet".to_string(),
+            template("greet", "Hello {{ who }}, mode={{mode|fast}}"),
+        );
+
+        let rendered = render_prompt(&templates, "greet", &vars(&[("who", "world")])).unwrap();
+        assert_eq!(rendered.content, "Hello world, mode=fast");
+    }
+
+    #[test]
+    fn test_render_reports_missing_variables() {
+        let mut templates = BTreeMap::new();
+        templates.insert("t".to_string(), template("t", "{{a}} {{b}} {{a}}"));
+
+        let err = render_prompt(&templates, "t", &HashMap::new()).unwrap_err();
+        assert!(err.contains("a, b"));
+    }
+
+    #[test]
+    fn test_render_includes() {
+        let mut templates = BTreeMap::new();
+        templates.insert("rules".to_string(), template("rules", "Be {{tone}}."));
+        templates.insert(
+            "system".to_string(),
+            template("system", "You are helpful. {{> rules}}"),
+        );
+
+        let rendered = render_prompt(&templates, "system", &vars(&[("tone", "brief")])).unwrap();
+        assert_eq!(rendered.content, "You are helpful. Be brief.");
+        assert_eq!(rendered.included, vec!["rules"]);
+    }
+
+    #[test]
+    fn test_render_detects_include_cycles() {
+        let mut templates = BTreeMap::new();
+        templates.insert("a".to_string(), template("a", "{{> b}}"));
+        templates.insert("b".to_string(), template("b", "{{> a}}"));
+
+        let err = render_prompt(&templates, "a", &HashMap::new()).unwrap_err();
+        assert!(err.contains("Circular include"));
+    }
+
+    #[test]
+    fn test_project_templates_override_global() {
+        let global = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        let project_prompts = get_project_prompts_dir(project.path().to_str().unwrap());
+        fs::create_dir_all(&project_prompts).unwrap();
+
+        fs::write(global.path().join("system.md"), "global prompt").unwrap();
+        fs::write(global.path().join("other.md"), "other prompt").unwrap();
+        fs::write(project_prompts.join("system.md"), "project prompt").unwrap();
+
+        let templates = load_templates(Some(global.path()), project.path().to_str());
+        assert_eq!(templates.len(), 2);
+        assert_eq!(templates["system"].body, "project prompt");
+        assert_eq!(templates["system"].source, "project");
+        assert_eq!(templates["other"].source, "global");
+    }
+}
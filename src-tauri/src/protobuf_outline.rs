@@ -0,0 +1,181 @@
+// Protobuf summaries
+//
+// A .proto file is read for its messages, enums and services. The summary keeps each
+// top-level definition with the comments directly above it; nested messages and enums
+// stay inside their parent. `option` statements and `reserved` ranges are dropped, and
+// an rpc whose block held only options becomes a plain `rpc ...;` declaration. The
+// syntax, package, import and file option lines are left out. Definitions are
+// brace-delimited blocks, so lines are scanned directly; that keeps the summarizer
+// off a grammar crate that would have to track tree-sitter's ABI.
+
+use crate::code_navigation::CodeSummary;
+use crate::text_slice::{before_in_code, brace_balance};
+
+/// Keywords that open a top-level definition
+const DEFINITIONS: &[&str] = &["message", "enum", "service", "extend"];
+
+fn ends_statement(line: &str) -> bool {
+    let code = before_in_code(line, "//", "protobuf").unwrap_or(line);
+    code.trim_end().ends_with([';', '}'])
+}
+
+/// Top-level definitions, each with the comment lines directly above it
+fn definitions(content: &str) -> Vec<(Vec<&str>, Vec<&str>)> {
+    let lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+    let mut definitions = Vec::new();
+    let mut comments = Vec::new();
+    let mut in_block_comment = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+        i += 1;
+        if in_block_comment || trimmed.starts_with("/*") {
+            comments.push(line);
+            in_block_comment = !trimmed.contains("*/");
+            continue;
+        }
+        if trimmed.is_empty() {
+            comments.clear();
+            continue;
+        }
+        if trimmed.starts_with("//") {
+            comments.push(line);
+            continue;
+        }
+
+        // A statement runs until its braces close on a line ending in `;` or `}`
+        let mut statement = vec![line];
+        let mut depth = brace_balance(line, "protobuf");
+        while !(depth <= 0 && ends_statement(statement[statement.len() - 1])) {
+            let Some(next) = lines.get(i) else {
+                break;
+            };
+            depth += brace_balance(next, "protobuf");
+            statement.push(next);
+            i += 1;
+        }
+        let keyword = trimmed.split_whitespace().next().unwrap_or_default();
+        let leading = std::mem::take(&mut comments);
+        if DEFINITIONS.contains(&keyword) {
+            definitions.push((leading, statement));
+        }
+    }
+    definitions
+}
+
+/// Protobuf message, enum or service without its `option` statements and `reserved`
+/// ranges. An rpc whose block held only options becomes a plain `rpc ...;` declaration.
+fn protobuf_definition_summary(text: &str) -> String {
+    let is_statement = |line: &str, keyword: &str| {
+        line.strip_prefix(keyword)
+            .is_some_and(|rest| rest.starts_with([' ', '(']))
+    };
+
+    let mut kept: Vec<String> = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if is_statement(trimmed, "option") || is_statement(trimmed, "reserved") {
+            // Aggregate options (`option (google.api.http) = { ... };`) span lines
+            let mut depth = 0i32;
+            let mut current = trimmed;
+            loop {
+                depth += current.matches('{').count() as i32 - current.matches('}').count() as i32;
+                if depth <= 0 && current.ends_with(';') {
+                    break;
+                }
+                match lines.next() {
+                    Some(next) => current = next.trim(),
+                    None => break,
+                }
+            }
+            continue;
+        }
+        if trimmed == "}"
+            && kept
+                .last()
+                .is_some_and(|prev| prev.trim_start().starts_with("rpc ") && prev.ends_with('{'))
+        {
+            let rpc = kept.pop().unwrap_or_default();
+            kept.push(format!("{};", rpc.trim_end_matches('{').trim_end()));
+            continue;
+        }
+        if trimmed.starts_with("rpc ") && trimmed.ends_with('}') {
+            // `rpc Get(A) returns (B) { option ...; }` on one line
+            if let Some(open) = line.find('{') {
+                kept.push(format!("{};", line[..open].trim_end()));
+                continue;
+            }
+        }
+        kept.push(line.trim_end().to_string());
+    }
+    kept.join("\n")
+}
+
+pub fn protobuf_outline(content: &str) -> String {
+    definitions(content)
+        .into_iter()
+        .map(|(comments, statement)| {
+            let mut kept: Vec<String> = comments.iter().map(|line| line.to_string()).collect();
+            kept.push(protobuf_definition_summary(&statement.join("\n")));
+            kept.join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+pub fn summarize_protobuf(content: &str) -> CodeSummary {
+    let original_lines = content.lines().count();
+    let outline = protobuf_outline(content);
+    if outline.is_empty() {
+        return CodeSummary::unchanged(
+            content.to_string(),
+            original_lines,
+            "protobuf".to_string(),
+            "no definitions",
+        );
+    }
+    CodeSummary {
+        success: true,
+        summary: format!(
+            "[COMPRESSED: Original {} lines → messages, enums and services with their options elided]\n\n{}",
+            original_lines, outline
+        ),
+        original_lines,
+        lang_id: "protobuf".to_string(),
+        truncated: None,
+        skipped_reason: None,
+        omitted_symbols: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_level_statements_are_left_out() {
+        let proto = r#"syntax = "proto3";
+import "google/api/annotations.proto";
+
+option (custom.file) = {
+  name: "orders"
+};
+
+/* Empty request */
+message Ping {}
+
+service Health
+{
+  rpc Check(Ping) returns (Ping) {
+  }
+}
+"#;
+        assert_eq!(
+            protobuf_outline(proto),
+            "/* Empty request */\nmessage Ping {}\n\nservice Health\n{\n  rpc Check(Ping) returns (Ping);\n}"
+        );
+        assert!(!summarize_protobuf("syntax = \"proto3\";\n").success);
+    }
+}
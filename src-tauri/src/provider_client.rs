@@ -0,0 +1,163 @@
+// Minimal client for backend-side model calls
+//
+// The frontend owns provider configuration; features that need a model call from the
+// backend receive an OpenAI-compatible endpoint and call its chat completions API here.
+
+use crate::audit_log::{self, AuditMessage, ModelCallInput, TokenUsage};
+use crate::http_client;
+use crate::offline_mode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: "system".to_string(),
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// Run a chat completion at temperature 0 and return the first message's content. In
+/// offline mode the call goes to the local provider instead (see `offline_mode`). The
+/// call is recorded in the audit log when that is enabled.
+pub async fn chat_completion(
+    provider: &ProviderConfig,
+    messages: &[ChatMessage],
+    timeout: Duration,
+) -> Result<String, String> {
+    let provider = &offline_mode::route_provider(provider)?;
+    let started = Instant::now();
+    let result = send_chat_completion(provider, messages, timeout).await;
+
+    let (response, error, usage) = match &result {
+        Ok((content, usage)) => (Some(content.clone()), None, usage.clone()),
+        Err(e) => (None, Some(e.clone()), TokenUsage::default()),
+    };
+    audit_log::record_backend_call(ModelCallInput {
+        provider: provider.base_url.clone(),
+        model: provider.model.clone(),
+        session_id: None,
+        duration_ms: started.elapsed().as_millis() as u64,
+        status: if result.is_ok() { "ok" } else { "error" }.to_string(),
+        error,
+        usage,
+        prompt: messages
+            .iter()
+            .map(|m| AuditMessage {
+                role: m.role.clone(),
+                content: m.content.clone(),
+            })
+            .collect(),
+        response,
+        tool_calls: Vec::new(),
+    });
+    result.map(|(content, _)| content)
+}
+
+async fn send_chat_completion(
+    provider: &ProviderConfig,
+    messages: &[ChatMessage],
+    timeout: Duration,
+) -> Result<(String, TokenUsage), String> {
+    let url = format!(
+        "{}/chat/completions",
+        provider.base_url.trim_end_matches('/')
+    );
+    let body = json!({
+        "model": provider.model,
+        "temperature": 0,
+        "messages": messages,
+    });
+
+    let client = http_client::builder()?
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let mut request = client.post(&url).json(&body);
+    if let Some(key) = &provider.api_key {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    let payload: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    if !status.is_success() {
+        // The provider's message tells callers why, e.g. a context-length overflow
+        return Err(
+            match payload.pointer("/error/message").and_then(Value::as_str) {
+                Some(message) => {
+                    format!("Provider returned status {}: {}", status.as_u16(), message)
+                }
+                None => format!("Provider returned status {}", status.as_u16()),
+            },
+        );
+    }
+
+    let content = payload
+        .pointer("/choices/0/message/content")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| "Provider response has no message content".to_string())?;
+    let usage = TokenUsage {
+        input_tokens: payload
+            .pointer("/usage/prompt_tokens")
+            .and_then(Value::as_u64),
+        output_tokens: payload
+            .pointer("/usage/completion_tokens")
+            .and_then(Value::as_u64),
+    };
+    Ok((content, usage))
+}
+
+/// Strip a surrounding markdown code fence, which models add despite instructions
+pub fn strip_code_fence(answer: &str) -> String {
+    let trimmed = answer.trim();
+    if !trimmed.starts_with("```") {
+        return answer.trim_matches('\n').to_string();
+    }
+    let mut lines: Vec<&str> = trimmed.lines().skip(1).collect();
+    if lines.last().map(|l| l.trim() == "```").unwrap_or(false) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_code_fence() {
+        assert_eq!(strip_code_fence("```rust\nfn a() {}\n```"), "fn a() {}");
+        assert_eq!(strip_code_fence("```\nx\ny\n```\n"), "x\ny");
+        assert_eq!(strip_code_fence("\n    let x = 1;\n"), "    let x = 1;");
+    }
+}
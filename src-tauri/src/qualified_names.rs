@@ -0,0 +1,216 @@
+// Qualified symbol names
+//
+// The index keys definitions by their bare name, so `bar` finds every `bar`, but a
+// search for `Foo.bar` found nothing. Each definition now records its qualified name,
+// built from the classes, modules, namespaces and impl types around it in the
+// language's own notation (`Foo.bar`, `module::Type::method`, `pkg.Class#method`,
+// `Mod::Class#method`), and lookups accept any separator: `Foo.bar`, `Foo::bar` and
+// `Foo#bar` all match a definition whose qualified name ends in those segments.
+//
+// Generic parameters (`Foo<T>`) are dropped from keys and queries alike.
+
+use tree_sitter::Node;
+
+/// Parts of node kinds that open a named scope (`class_declaration`, `impl_item`,
+/// `mod_item`, `namespace_definition`, ...)
+const SCOPE_KIND_PARTS: &[&str] = &[
+    "class",
+    "interface",
+    "struct",
+    "trait",
+    "impl",
+    "mod",
+    "module",
+    "namespace",
+    "object",
+    "enum",
+    "protocol",
+    "extension",
+];
+
+/// `name` without `<...>` generic parameters; operators keep their angle brackets
+pub fn strip_generics(name: &str) -> String {
+    if name.starts_with("operator") {
+        return name.to_string();
+    }
+    let mut depth = 0usize;
+    let stripped: String = name
+        .chars()
+        .filter(|&c| match c {
+            '<' => {
+                depth += 1;
+                false
+            }
+            '>' if depth > 0 => {
+                depth -= 1;
+                false
+            }
+            _ => depth == 0,
+        })
+        .collect();
+    let stripped = stripped.trim();
+    if stripped.is_empty() {
+        name.to_string()
+    } else {
+        stripped.to_string()
+    }
+}
+
+/// Index key for a captured name: generics dropped, and for a name written with its
+/// scope (`Foo::bar` in an out-of-line C++ definition) only the last segment
+pub fn index_key(name: &str) -> String {
+    let name = strip_generics(name);
+    match name.rsplit_once("::") {
+        Some((_, last)) if !last.is_empty() => last.to_string(),
+        _ => name,
+    }
+}
+
+/// Segments of a qualified name or query, whatever the separator
+pub fn segments(name: &str) -> Vec<String> {
+    strip_generics(name)
+        .replace("::", ".")
+        .replace('#', ".")
+        .split('.')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Whether a definition with qualified name `qualified` answers `query` segments
+pub fn matches_query(qualified: &str, query: &[String]) -> bool {
+    segments(qualified).ends_with(query)
+}
+
+/// Name of the scope that `node` opens, if it is a class, module, impl and the like
+fn scope_name(node: Node, source: &[u8]) -> Option<String> {
+    let kind = node.kind();
+    if !SCOPE_KIND_PARTS.iter().any(|part| kind.contains(part)) {
+        return None;
+    }
+    // Rust impls are named by their self type
+    let name = node
+        .child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("type"))?;
+    let text = strip_generics(name.utf8_text(source).ok()?);
+    // `impl fmt::Display for crate::Foo` qualifies as `Foo`
+    Some(text.rsplit("::").next().unwrap_or(&text).to_string())
+}
+
+/// The receiver type of a Go method: `func (s *Server[T]) Start()` -> `Server`
+fn go_receiver_type(method: Node, source: &[u8]) -> Option<String> {
+    let receiver = method.child_by_field_name("receiver")?;
+    let mut cursor = receiver.walk();
+    let parameter = receiver
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "parameter_declaration")?;
+    let type_text = parameter
+        .child_by_field_name("type")?
+        .utf8_text(source)
+        .ok()?;
+    let type_name = type_text
+        .trim_start_matches('*')
+        .split('[')
+        .next()
+        .unwrap_or(type_text)
+        .trim();
+    (!type_name.is_empty()).then(|| type_name.to_string())
+}
+
+fn java_package(node: Node, source: &[u8]) -> Option<String> {
+    let mut root = node;
+    while let Some(parent) = root.parent() {
+        root = parent;
+    }
+    let mut cursor = root.walk();
+    let package = root
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "package_declaration")?;
+    let mut cursor = package.walk();
+    let name = package
+        .named_children(&mut cursor)
+        .find(|n| matches!(n.kind(), "scoped_identifier" | "identifier"))?;
+    name.utf8_text(source).ok().map(String::from)
+}
+
+/// Qualified name of the definition named by `name_node`, or None when it is not nested
+/// in any scope. `callable` definitions are joined to their scope with the language's
+/// member separator (`#` in Java and Ruby).
+pub fn qualified_name(
+    name_node: Node,
+    source: &[u8],
+    lang_id: &str,
+    name: &str,
+    callable: bool,
+) -> Option<String> {
+    let mut scopes: Vec<String> = Vec::new();
+    let mut current = name_node.parent();
+    while let Some(node) = current {
+        // The definition itself is not one of its scopes
+        let declares_self = node
+            .child_by_field_name("name")
+            .is_some_and(|n| n.id() == name_node.id());
+        if !declares_self {
+            if let Some(scope) = scope_name(node, source) {
+                scopes.push(scope);
+            }
+        }
+        if lang_id == "go" && node.kind() == "method_declaration" {
+            scopes.extend(go_receiver_type(node, source));
+        }
+        current = node.parent();
+    }
+    scopes.reverse();
+
+    let package = if lang_id == "java" {
+        java_package(name_node, source)
+    } else {
+        None
+    };
+    if scopes.is_empty() && package.is_none() {
+        return None;
+    }
+
+    let (scope_separator, member_separator) = match lang_id {
+        "rust" | "c" | "cpp" => ("::", "::"),
+        "ruby" => ("::", "#"),
+        "java" => (".", "#"),
+        _ => (".", "."),
+    };
+    let mut qualified = package.map(|p| format!("{}.", p)).unwrap_or_default();
+    qualified.push_str(&scopes.join(scope_separator));
+    if !scopes.is_empty() {
+        qualified.push_str(if callable {
+            member_separator
+        } else {
+            scope_separator
+        });
+    }
+    qualified.push_str(name);
+    Some(qualified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_generics_and_keys() {
+        assert_eq!(strip_generics("Map<K, Vec<V>>"), "Map");
+        assert_eq!(strip_generics("operator<<"), "operator<<");
+        assert_eq!(index_key("Widget::resize"), "resize");
+        assert_eq!(index_key("List<T>"), "List");
+        assert_eq!(index_key("Foo.Bar"), "Foo.Bar");
+    }
+
+    #[test]
+    fn test_query_matching() {
+        let query = segments("Foo<T>.bar");
+        assert_eq!(query, vec!["Foo", "bar"]);
+        assert!(matches_query("com.acme.Foo#bar", &query));
+        assert!(matches_query("util::Foo::bar", &query));
+        assert!(!matches_query("Other.bar", &query));
+        assert!(!matches_query("bar", &query));
+    }
+}
@@ -0,0 +1,232 @@
+// Tree-sitter query playground
+//
+// Writing a query against a grammar means guessing node kinds and field names, then
+// checking what matched. `debug_query` parses a snippet, prints its syntax tree with
+// field names and positions, and runs the query on it, reporting every capture with
+// its kind under the capture convention and its range. A query that does not compile
+// still returns the tree along with the error's position, so the two can be compared
+// side by side. Positions are 1-based, like `SymbolInfo`.
+
+use crate::capture_kinds::{is_helper_capture, CaptureKind};
+use crate::code_navigation::get_language;
+use crate::text_slice::slice_to;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Parser, Query, QueryCursor};
+
+/// Nodes printed before the tree is cut off
+const MAX_TREE_NODES: usize = 5_000;
+/// Captures reported before the rest are dropped
+const MAX_CAPTURES: usize = 1_000;
+/// Capture text longer than this many bytes is cut
+const MAX_CAPTURE_TEXT: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugCapture {
+    pub capture_name: String,
+    /// None when the capture does not follow the naming convention
+    pub kind: Option<CaptureKind>,
+    pub pattern_index: usize,
+    pub node_kind: String,
+    pub text: String,
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryDebugResult {
+    /// Named nodes as an indented s-expression with field names and ranges
+    pub tree: String,
+    /// Whether the snippet has syntax errors
+    pub has_error: bool,
+    pub captures: Vec<DebugCapture>,
+    /// Set when the query does not compile
+    pub error: Option<String>,
+    /// Set when the tree or the captures were cut off
+    pub truncated: bool,
+}
+
+fn position(point: tree_sitter::Point) -> String {
+    format!("[{}, {}]", point.row + 1, point.column + 1)
+}
+
+fn write_tree(
+    node: Node,
+    field: Option<&str>,
+    depth: usize,
+    out: &mut String,
+    remaining: &mut usize,
+) {
+    if *remaining == 0 {
+        return;
+    }
+    *remaining -= 1;
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&"  ".repeat(depth));
+    if let Some(field) = field {
+        let _ = write!(out, "{}: ", field);
+    }
+    let missing = if node.is_missing() { "MISSING " } else { "" };
+    let _ = write!(
+        out,
+        "({}{} {} - {}",
+        missing,
+        node.kind(),
+        position(node.start_position()),
+        position(node.end_position())
+    );
+
+    let mut cursor = node.walk();
+    if cursor.goto_first_child() {
+        loop {
+            let child = cursor.node();
+            if child.is_named() || child.is_missing() {
+                write_tree(child, cursor.field_name(), depth + 1, out, remaining);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+    out.push(')');
+}
+
+fn run_query(query: &Query, root: Node, source: &str) -> (Vec<DebugCapture>, bool) {
+    let mut captures = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, root, source.as_bytes());
+    while let Some(m) = matches.next() {
+        for capture in m.captures {
+            let capture_name = query.capture_names()[capture.index as usize];
+            if is_helper_capture(capture_name) {
+                continue;
+            }
+            if captures.len() == MAX_CAPTURES {
+                return (captures, true);
+            }
+            let node = capture.node;
+            captures.push(DebugCapture {
+                capture_name: capture_name.to_string(),
+                kind: CaptureKind::from_capture_name(capture_name),
+                pattern_index: m.pattern_index,
+                node_kind: node.kind().to_string(),
+                text: slice_to(&source[node.byte_range()], MAX_CAPTURE_TEXT).to_string(),
+                start_line: node.start_position().row as u32 + 1,
+                start_column: node.start_position().column as u32 + 1,
+                end_line: node.end_position().row as u32 + 1,
+                end_column: node.end_position().column as u32 + 1,
+            });
+        }
+    }
+    (captures, false)
+}
+
+pub fn debug(content: &str, lang_id: &str, query_source: &str) -> Result<QueryDebugResult, String> {
+    let language =
+        get_language(lang_id).ok_or_else(|| format!("Unsupported language: {}", lang_id))?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| format!("Failed to set language for {}: {:?}", lang_id, e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| format!("Failed to parse {} content", lang_id))?;
+    let root = tree.root_node();
+
+    let mut printed = String::new();
+    let mut remaining = MAX_TREE_NODES;
+    write_tree(root, None, 0, &mut printed, &mut remaining);
+    let mut truncated = remaining == 0;
+
+    let (captures, error) = match Query::new(&language, query_source) {
+        Ok(query) => {
+            let (captures, cut) = run_query(&query, root, content);
+            truncated |= cut;
+            (captures, None)
+        }
+        Err(e) => (
+            Vec::new(),
+            Some(format!(
+                "{:?} error at {}:{}: {}",
+                e.kind,
+                e.row + 1,
+                e.column + 1,
+                e.message
+            )),
+        ),
+    };
+
+    Ok(QueryDebugResult {
+        tree: printed,
+        has_error: root.has_error(),
+        captures,
+        error,
+        truncated,
+    })
+}
+
+/// Parse `content` as `lang_id` and run `query` on it, for iterating on custom queries
+#[tauri::command]
+pub fn debug_query(
+    content: String,
+    lang_id: String,
+    query: String,
+) -> Result<QueryDebugResult, String> {
+    debug(&content, &lang_id, &query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_query_reports_tree_and_captures() {
+        let result = debug(
+            "fn add(a: i32) -> i32 {\n    a\n}\n",
+            "rust",
+            "(function_item name: (identifier) @function.definition) @function",
+        )
+        .unwrap();
+
+        assert!(result.error.is_none());
+        assert!(!result.has_error);
+        assert!(!result.truncated);
+        assert!(
+            result.tree.starts_with(
+                "(source_file [1, 1] - [4, 1]\n  (function_item [1, 1] - [3, 2]\n    name: (identifier [1, 4] - [1, 7])"
+            ),
+            "{}",
+            result.tree
+        );
+
+        let names: Vec<_> = result
+            .captures
+            .iter()
+            .map(|c| (c.capture_name.as_str(), c.kind, c.text.as_str()))
+            .collect();
+        assert!(names.contains(&("function.definition", Some(CaptureKind::Function), "add")));
+        let definition = result
+            .captures
+            .iter()
+            .find(|c| c.capture_name == "function")
+            .unwrap();
+        assert_eq!(definition.node_kind, "function_item");
+        assert_eq!((definition.start_line, definition.end_line), (1, 3));
+    }
+
+    #[test]
+    fn test_debug_query_with_invalid_query() {
+        let result = debug("x = 1\n", "python", "(assignment left: (nope) @x)").unwrap();
+        assert!(result.captures.is_empty());
+        let error = result.error.unwrap();
+        assert!(error.starts_with("NodeType error at 1:"), "{}", error);
+        assert!(result.tree.starts_with("(module"), "{}", result.tree);
+
+        assert!(debug_query("x".into(), "cobol".into(), "(x)".into()).is_err());
+    }
+}
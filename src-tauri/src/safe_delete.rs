@@ -0,0 +1,374 @@
+// Reversible deletes
+//
+// Agent-initiated deletions go to a trash instead of being unlinked, so every one of
+// them can be undone. Where the OS trash can be listed and restored programmatically
+// (Windows and freedesktop systems) files go there and show up in the user's own
+// trash; elsewhere (macOS), or when the OS trash refuses a path (e.g. a mount without
+// a trash directory), they are moved to ~/.talkcody/trash/<id>/. Every deletion is
+// recorded in ~/.talkcody/trash/index.json so `restore_deleted(id)` can put it back.
+
+use crate::path_policy;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const INDEX_FILE: &str = "index.json";
+
+/// Serializes read-modify-write cycles of the index
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashLocation {
+    /// The operating system's trash
+    Os,
+    /// ~/.talkcody/trash/<id>/
+    App,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedEntry {
+    pub id: String,
+    pub original_path: String,
+    pub is_dir: bool,
+    pub location: TrashLocation,
+    /// Milliseconds since the Unix epoch
+    pub deleted_at: i64,
+}
+
+fn get_trash_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody").join("trash"))
+}
+
+fn read_index(trash_dir: &Path) -> Vec<DeletedEntry> {
+    fs::read_to_string(trash_dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn write_index(trash_dir: &Path, entries: &[DeletedEntry]) -> Result<(), String> {
+    fs::create_dir_all(trash_dir)
+        .map_err(|e| format!("Failed to create {}: {}", trash_dir.display(), e))?;
+    let json = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+    fs::write(trash_dir.join(INDEX_FILE), json)
+        .map_err(|e| format!("Failed to write trash index: {}", e))
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(windows)]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    use std::os::windows::fs::{symlink_dir, symlink_file, FileTypeExt};
+    let target = fs::read_link(from)?;
+    if fs::symlink_metadata(from)?.file_type().is_symlink_dir() {
+        symlink_dir(target, to)
+    } else {
+        symlink_file(target, to)
+    }
+}
+
+/// Copy a tree, recreating symlinks as links rather than copying what they point to,
+/// which may be outside the workspace
+fn copy_recursive(from: &Path, to: &Path) -> std::io::Result<()> {
+    let file_type = fs::symlink_metadata(from)?.file_type();
+    if file_type.is_symlink() {
+        copy_symlink(from, to)
+    } else if file_type.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+/// Rename, falling back to copy-and-remove across file systems
+fn move_path(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_recursive(from, to)
+        .and_then(|_| {
+            if fs::symlink_metadata(from)?.is_dir() {
+                fs::remove_dir_all(from)
+            } else {
+                fs::remove_file(from)
+            }
+        })
+        .map_err(|e| format!("Failed to move {}: {}", from.display(), e))
+}
+
+fn app_trash_path(trash_dir: &Path, entry: &DeletedEntry) -> PathBuf {
+    let name = Path::new(&entry.original_path)
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_else(|| "item".into());
+    trash_dir.join(&entry.id).join(name)
+}
+
+#[cfg(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+mod os_trash {
+    use super::DeletedEntry;
+    use std::path::Path;
+
+    pub const SUPPORTED: bool = true;
+
+    pub fn delete(path: &Path) -> Result<(), String> {
+        trash::delete(path).map_err(|e| e.to_string())
+    }
+
+    /// Restore the most recent trash item deleted from the entry's original path
+    pub fn restore(entry: &DeletedEntry) -> Result<(), String> {
+        let original = Path::new(&entry.original_path);
+        let item = trash::os_limited::list()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|item| item.original_path() == original)
+            .max_by_key(|item| item.time_deleted)
+            .ok_or_else(|| format!("{} is no longer in the trash", entry.original_path))?;
+        trash::os_limited::restore_all([item]).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(any(
+    windows,
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+mod os_trash {
+    use super::DeletedEntry;
+    use std::path::Path;
+
+    pub const SUPPORTED: bool = false;
+
+    pub fn delete(_path: &Path) -> Result<(), String> {
+        Err("OS trash restore is not supported on this platform".to_string())
+    }
+
+    pub fn restore(_entry: &DeletedEntry) -> Result<(), String> {
+        Err("OS trash restore is not supported on this platform".to_string())
+    }
+}
+
+/// Move `path` to the trash and record it. `use_os_trash` is false in tests and on
+/// platforms where OS trash items cannot be restored.
+pub fn delete_to_trash(
+    path: &Path,
+    trash_dir: &Path,
+    use_os_trash: bool,
+) -> Result<DeletedEntry, String> {
+    let metadata = fs::symlink_metadata(path)
+        .map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+    // Resolve the parent only, so a symlink is trashed itself rather than its target
+    let (parent, name) = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => (parent, name),
+        _ => return Err(format!("Refusing to delete {}", path.display())),
+    };
+    let original = crate::path_utils::canonicalize(parent)?.join(name);
+    let mut entry = DeletedEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        original_path: original.to_string_lossy().to_string(),
+        is_dir: metadata.is_dir(),
+        location: TrashLocation::Os,
+        deleted_at: chrono::Utc::now().timestamp_millis(),
+    };
+
+    let os_result = if use_os_trash {
+        os_trash::delete(&original)
+    } else {
+        Err("OS trash disabled".to_string())
+    };
+    if let Err(e) = os_result {
+        if use_os_trash {
+            log::warn!(
+                "OS trash failed for {}, using app trash: {}",
+                original.display(),
+                e
+            );
+        }
+        entry.location = TrashLocation::App;
+        move_path(&original, &app_trash_path(trash_dir, &entry))?;
+    }
+
+    let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut index = read_index(trash_dir);
+    index.push(entry.clone());
+    write_index(trash_dir, &index)?;
+    log::info!(
+        "Deleted {} to {:?} trash ({})",
+        entry.original_path,
+        entry.location,
+        entry.id
+    );
+    Ok(entry)
+}
+
+/// Put a deleted path back. Fails without changes if something now occupies it.
+pub fn restore_from_trash(id: &str, trash_dir: &Path) -> Result<DeletedEntry, String> {
+    let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut index = read_index(trash_dir);
+    let position = index
+        .iter()
+        .position(|entry| entry.id == id)
+        .ok_or_else(|| format!("No deleted item with id {}", id))?;
+    let entry = index[position].clone();
+
+    let original = Path::new(&entry.original_path);
+    if fs::symlink_metadata(original).is_ok() {
+        return Err(format!(
+            "Cannot restore {}: the path exists again",
+            entry.original_path
+        ));
+    }
+    match entry.location {
+        TrashLocation::Os => os_trash::restore(&entry)?,
+        TrashLocation::App => {
+            move_path(&app_trash_path(trash_dir, &entry), original)?;
+            let _ = fs::remove_dir(trash_dir.join(&entry.id));
+        }
+    }
+
+    index.remove(position);
+    write_index(trash_dir, &index)?;
+    Ok(entry)
+}
+
+// Tauri commands
+
+/// Move a file or directory to the trash. With `root_path`, paths that resolve
+/// outside the workspace are refused.
+#[tauri::command]
+pub fn delete_path(path: String, root_path: Option<String>) -> Result<DeletedEntry, String> {
+    path_policy::check_optional(&path, root_path.as_deref())?;
+    delete_to_trash(Path::new(&path), &get_trash_dir()?, os_trash::SUPPORTED)
+}
+
+#[tauri::command]
+pub fn restore_deleted(id: String) -> Result<DeletedEntry, String> {
+    restore_from_trash(&id, &get_trash_dir()?)
+}
+
+/// Deletions that can still be restored, most recent first
+#[tauri::command]
+pub fn list_deleted() -> Result<Vec<DeletedEntry>, String> {
+    let mut entries = read_index(&get_trash_dir()?);
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_delete_and_restore_file() {
+        let workspace = TempDir::new().unwrap();
+        let trash = TempDir::new().unwrap();
+        let path = workspace.path().join("notes.txt");
+        fs::write(&path, "keep me").unwrap();
+
+        let entry = delete_to_trash(&path, trash.path(), false).unwrap();
+        assert_eq!(entry.location, TrashLocation::App);
+        assert!(!path.exists());
+        assert_eq!(read_index(trash.path()).len(), 1);
+
+        restore_from_trash(&entry.id, trash.path()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "keep me");
+        assert!(read_index(trash.path()).is_empty());
+        assert!(!trash.path().join(&entry.id).exists());
+    }
+
+    #[test]
+    fn test_delete_and_restore_directory() {
+        let workspace = TempDir::new().unwrap();
+        let trash = TempDir::new().unwrap();
+        let dir = workspace.path().join("src");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("nested/a.rs"), "fn a() {}").unwrap();
+
+        let entry = delete_to_trash(&dir, trash.path(), false).unwrap();
+        assert!(entry.is_dir);
+        assert!(!dir.exists());
+
+        restore_from_trash(&entry.id, trash.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("nested/a.rs")).unwrap(),
+            "fn a() {}"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_delete_directory_with_symlink() {
+        let workspace = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let trash = TempDir::new().unwrap();
+        let secret = outside.path().join("secret.txt");
+        fs::write(&secret, "outside").unwrap();
+        let dir = workspace.path().join("src");
+        fs::create_dir_all(&dir).unwrap();
+        std::os::unix::fs::symlink(&secret, dir.join("link")).unwrap();
+
+        // The copy behind a cross-filesystem move keeps the link a link
+        let copy = trash.path().join("copy");
+        copy_recursive(&dir, &copy).unwrap();
+        let copied = fs::symlink_metadata(copy.join("link")).unwrap();
+        assert!(copied.file_type().is_symlink());
+        assert_eq!(fs::read_link(copy.join("link")).unwrap(), secret);
+
+        let entry = delete_to_trash(&dir, trash.path(), false).unwrap();
+        restore_from_trash(&entry.id, trash.path()).unwrap();
+        assert_eq!(fs::read_link(dir.join("link")).unwrap(), secret);
+        assert_eq!(fs::read_to_string(&secret).unwrap(), "outside");
+    }
+
+    #[test]
+    fn test_restore_refuses_to_overwrite() {
+        let workspace = TempDir::new().unwrap();
+        let trash = TempDir::new().unwrap();
+        let path = workspace.path().join("a.txt");
+        fs::write(&path, "old").unwrap();
+
+        let entry = delete_to_trash(&path, trash.path(), false).unwrap();
+        fs::write(&path, "new").unwrap();
+        assert!(restore_from_trash(&entry.id, trash.path()).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        // Still restorable once the path is free
+        fs::remove_file(&path).unwrap();
+        restore_from_trash(&entry.id, trash.path()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    }
+
+    #[test]
+    fn test_unknown_id_and_missing_path() {
+        let trash = TempDir::new().unwrap();
+        assert!(restore_from_trash("nope", trash.path()).is_err());
+        assert!(delete_to_trash(&trash.path().join("missing"), trash.path(), false).is_err());
+    }
+}
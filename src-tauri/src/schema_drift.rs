@@ -0,0 +1,706 @@
+// Schema-to-model drift checker
+//
+// Replays SQL migration files (CREATE TABLE, ALTER TABLE ADD/DROP/RENAME COLUMN,
+// DROP TABLE) in file order to get the current schema, extracts the ORM models and
+// row types the code uses (Prisma, Drizzle, SQLAlchemy, Diesel, sqlx FromRow) and
+// reports columns that exist on one side but not the other.
+
+use crate::walker::{WalkerConfig, WorkspaceWalker};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaTable {
+    pub name: String,
+    pub columns: BTreeSet<String>,
+    /// Migration that last changed the table
+    pub source_file: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelDefinition {
+    pub name: String,
+    pub table: String,
+    pub fields: BTreeSet<String>,
+    pub framework: String,
+    pub file_path: String,
+    /// Row types may select a subset of columns; only extra fields are drift
+    pub partial: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableDrift {
+    pub table: String,
+    pub model: String,
+    pub framework: String,
+    pub model_file: String,
+    pub schema_file: String,
+    /// Columns in the schema the model does not declare
+    pub missing_in_model: Vec<String>,
+    /// Model fields with no column in the schema
+    pub missing_in_schema: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaDriftReport {
+    pub tables: usize,
+    pub models: usize,
+    pub drift: Vec<TableDrift>,
+    /// Models mapped to a table no migration creates
+    pub unknown_tables: Vec<String>,
+}
+
+macro_rules! cached_regex {
+    ($name:ident, $pattern:expr) => {
+        fn $name() -> &'static Regex {
+            static RE: OnceLock<Regex> = OnceLock::new();
+            RE.get_or_init(|| Regex::new($pattern).unwrap())
+        }
+    };
+}
+
+const IDENT: &str = r#"[`"\[]?(\w+)[`"\]]?"#;
+
+fn create_table_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)\bCREATE\s+(?:TEMP(?:ORARY)?\s+)?TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?(?:{ident}\.)?{ident}\s*\(",
+            ident = IDENT
+        ))
+        .unwrap()
+    })
+}
+
+fn alter_table_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?is)\bALTER\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:ONLY\s+)?(?:{ident}\.)?{ident}\s+([^;]+)",
+            ident = IDENT
+        ))
+        .unwrap()
+    })
+}
+
+fn drop_table_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)\bDROP\s+TABLE\s+(?:IF\s+EXISTS\s+)?(?:{ident}\.)?{ident}",
+            ident = IDENT
+        ))
+        .unwrap()
+    })
+}
+
+cached_regex!(sql_comment_regex, r"(?s)--[^\n]*|/\*.*?\*/");
+cached_regex!(prisma_model_regex, r"\bmodel\s+(\w+)\s*\{");
+cached_regex!(prisma_map_regex, r#"@map\(\s*"(\w+)"\s*\)"#);
+cached_regex!(prisma_table_map_regex, r#"@@map\(\s*"(\w+)"\s*\)"#);
+cached_regex!(
+    drizzle_table_regex,
+    r#"\b(?:pg|mysql|sqlite)Table\(\s*['"](\w+)['"]\s*,\s*\{"#
+);
+cached_regex!(
+    drizzle_column_regex,
+    r#"(?m)^\s*(\w+)\s*:\s*\w+\(\s*(?:['"](\w+)['"])?"#
+);
+cached_regex!(python_class_regex, r"(?m)^class\s+(\w+)\s*\(");
+cached_regex!(tablename_regex, r#"__tablename__\s*=\s*['"](\w+)['"]"#);
+cached_regex!(
+    sqlalchemy_column_regex,
+    r#"(?m)^\s+(\w+)\s*(?::[^=]+)?=\s*(?:\w+\.)?(?:Column|mapped_column)\(\s*(?:['"](\w+)['"])?"#
+);
+cached_regex!(diesel_table_regex, r"(\w+)\s*(?:\([^)]*\))?\s*\{([^{}]*)\}");
+cached_regex!(diesel_column_regex, r"(\w+)\s*->");
+cached_regex!(
+    from_row_regex,
+    r"#\[derive\([^)]*\bFromRow\b[^)]*\)\]\s*(?:#\[[^\]]*\]\s*)*(?:pub\s+)?struct\s+(\w+)\s*\{"
+);
+cached_regex!(
+    rust_field_regex,
+    r"(?m)^\s*(?:pub(?:\([^)]*\))?\s+)?(\w+)\s*:"
+);
+
+/// Text between the opening delimiter at `open` and its match, exclusive
+fn delimited(content: &str, open: usize, open_char: char, close_char: char) -> Option<&str> {
+    let mut depth = 0;
+    for (offset, c) in content[open..].char_indices() {
+        if c == open_char {
+            depth += 1;
+        } else if c == close_char {
+            depth -= 1;
+            if depth == 0 {
+                return Some(&content[open + 1..open + offset]);
+            }
+        }
+    }
+    None
+}
+
+/// Split on commas that are not nested inside parentheses
+fn split_top_level(body: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in body.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&body[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&body[start..]);
+    parts
+}
+
+fn unquote(name: &str) -> String {
+    name.trim_matches(|c| c == '`' || c == '"' || c == '[' || c == ']')
+        .to_lowercase()
+}
+
+fn column_name(definition: &str) -> Option<String> {
+    let first = definition.split_whitespace().next()?;
+    let keyword = first.to_uppercase();
+    if matches!(
+        keyword.as_str(),
+        "CONSTRAINT" | "PRIMARY" | "FOREIGN" | "UNIQUE" | "CHECK" | "INDEX" | "KEY" | "EXCLUDE"
+    ) {
+        return None;
+    }
+    Some(unquote(first))
+}
+
+/// Apply one migration file to the schema
+pub fn apply_migration(schema: &mut BTreeMap<String, SchemaTable>, sql: &str, source_file: &str) {
+    let sql = sql_comment_regex().replace_all(sql, "");
+    // Statements are applied in order so later ALTERs see earlier CREATEs
+    for statement in sql.split(';') {
+        if let Some(caps) = create_table_regex().captures(statement) {
+            let table = caps[2].to_lowercase();
+            let open = caps.get(0).unwrap().end() - 1;
+            let Some(body) = delimited(statement, open, '(', ')') else {
+                continue;
+            };
+            let columns = split_top_level(body)
+                .into_iter()
+                .filter_map(column_name)
+                .collect();
+            schema.insert(
+                table.clone(),
+                SchemaTable {
+                    name: table,
+                    columns,
+                    source_file: source_file.to_string(),
+                },
+            );
+        } else if let Some(caps) = alter_table_regex().captures(statement) {
+            let table = caps[2].to_lowercase();
+            let Some(entry) = schema.get_mut(&table) else {
+                continue;
+            };
+            entry.source_file = source_file.to_string();
+            let mut renamed = None;
+            for action in split_top_level(&caps[3]) {
+                let words: Vec<&str> = action.split_whitespace().collect();
+                let upper: Vec<String> = words.iter().map(|w| w.to_uppercase()).collect();
+                let upper: Vec<&str> = upper.iter().map(String::as_str).collect();
+                // Skip the optional COLUMN and IF [NOT] EXISTS keywords
+                let name_after = |idx: usize| {
+                    words
+                        .iter()
+                        .zip(&upper)
+                        .skip(idx)
+                        .find(|(_, u)| !matches!(**u, "COLUMN" | "IF" | "NOT" | "EXISTS"))
+                        .map(|(w, _)| unquote(w))
+                };
+                match upper.as_slice() {
+                    ["ADD", "CONSTRAINT", ..] | ["ADD", "PRIMARY", ..] | ["ADD", "FOREIGN", ..] => {
+                    }
+                    ["ADD", ..] => {
+                        if let Some(name) = name_after(1) {
+                            entry.columns.insert(name);
+                        }
+                    }
+                    ["DROP", "CONSTRAINT", ..] => {}
+                    ["DROP", ..] => {
+                        if let Some(name) = name_after(1) {
+                            entry.columns.remove(&name);
+                        }
+                    }
+                    ["RENAME", "COLUMN", _, "TO", _] | ["RENAME", _, "TO", _] => {
+                        let from = unquote(words[words.len() - 3]);
+                        let to = unquote(words[words.len() - 1]);
+                        if entry.columns.remove(&from) {
+                            entry.columns.insert(to);
+                        }
+                    }
+                    ["RENAME", "TO", _] => renamed = Some(unquote(words[2])),
+                    _ => {}
+                }
+            }
+            if let Some(renamed) = renamed {
+                if let Some(mut moved) = schema.remove(&table) {
+                    moved.name = renamed.clone();
+                    schema.insert(renamed, moved);
+                }
+            }
+        } else if let Some(caps) = drop_table_regex().captures(statement) {
+            schema.remove(&caps[2].to_lowercase());
+        }
+    }
+}
+
+/// `createdAt` -> `created_at`
+pub fn to_snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (idx, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if idx > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn model(
+    name: &str,
+    table: &str,
+    fields: BTreeSet<String>,
+    framework: &str,
+    file_path: &str,
+    partial: bool,
+) -> ModelDefinition {
+    ModelDefinition {
+        name: name.to_string(),
+        table: table.to_lowercase(),
+        fields,
+        framework: framework.to_string(),
+        file_path: file_path.to_string(),
+        partial,
+    }
+}
+
+/// Models declared in a Prisma schema
+pub fn parse_prisma_models(content: &str, file_path: &str) -> Vec<ModelDefinition> {
+    let blocks: Vec<(String, &str)> = prisma_model_regex()
+        .captures_iter(content)
+        .filter_map(|caps| {
+            let open = caps.get(0)?.end() - 1;
+            Some((caps[1].to_string(), delimited(content, open, '{', '}')?))
+        })
+        .collect();
+    let model_names: BTreeSet<&str> = blocks.iter().map(|(name, _)| name.as_str()).collect();
+
+    blocks
+        .iter()
+        .map(|(name, body)| {
+            let table = prisma_table_map_regex()
+                .captures(body)
+                .map(|caps| caps[1].to_string())
+                .unwrap_or_else(|| name.clone());
+            let fields = body
+                .lines()
+                .map(str::trim)
+                .filter(|line| {
+                    !line.is_empty() && !line.starts_with("//") && !line.starts_with('@')
+                })
+                .filter_map(|line| {
+                    let mut words = line.split_whitespace();
+                    let field = words.next()?;
+                    let field_type = words.next()?.trim_end_matches(['?', '!']);
+                    // Relation fields have no column of their own
+                    if field_type.ends_with("[]")
+                        || model_names.contains(field_type)
+                        || line.contains("@relation")
+                    {
+                        return None;
+                    }
+                    Some(
+                        prisma_map_regex()
+                            .captures(line)
+                            .map(|caps| caps[1].to_string())
+                            .unwrap_or_else(|| field.to_string()),
+                    )
+                })
+                .collect();
+            model(name, &table, fields, "prisma", file_path, false)
+        })
+        .collect()
+}
+
+/// Tables declared with Drizzle's pgTable/mysqlTable/sqliteTable
+pub fn parse_drizzle_models(content: &str, file_path: &str) -> Vec<ModelDefinition> {
+    drizzle_table_regex()
+        .captures_iter(content)
+        .filter_map(|caps| {
+            let open = caps.get(0)?.end() - 1;
+            let body = delimited(content, open, '{', '}')?;
+            let fields = drizzle_column_regex()
+                .captures_iter(body)
+                .map(|c| c.get(2).map(|m| m.as_str()).unwrap_or(&c[1]).to_string())
+                .collect();
+            Some(model(
+                &caps[1], &caps[1], fields, "drizzle", file_path, false,
+            ))
+        })
+        .collect()
+}
+
+/// SQLAlchemy declarative models (classes with `__tablename__`)
+pub fn parse_sqlalchemy_models(content: &str, file_path: &str) -> Vec<ModelDefinition> {
+    let classes: Vec<(usize, String)> = python_class_regex()
+        .captures_iter(content)
+        .map(|caps| (caps.get(0).unwrap().start(), caps[1].to_string()))
+        .collect();
+
+    classes
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, (start, name))| {
+            let end = classes
+                .get(idx + 1)
+                .map(|(s, _)| *s)
+                .unwrap_or(content.len());
+            let body = &content[*start..end];
+            let table = tablename_regex().captures(body)?[1].to_string();
+            let fields = sqlalchemy_column_regex()
+                .captures_iter(body)
+                .map(|c| c.get(2).map(|m| m.as_str()).unwrap_or(&c[1]).to_string())
+                .collect();
+            Some(model(name, &table, fields, "sqlalchemy", file_path, false))
+        })
+        .collect()
+}
+
+/// Diesel `table!` declarations and sqlx `FromRow` structs
+pub fn parse_rust_models(content: &str, file_path: &str) -> Vec<ModelDefinition> {
+    let mut models = Vec::new();
+    for (start, _) in content.match_indices("table!") {
+        let Some(open) = content[start..].find('{').map(|o| start + o) else {
+            continue;
+        };
+        let Some(body) = delimited(content, open, '{', '}') else {
+            continue;
+        };
+        for caps in diesel_table_regex().captures_iter(body) {
+            let fields = diesel_column_regex()
+                .captures_iter(&caps[2])
+                .map(|c| c[1].to_string())
+                .collect();
+            models.push(model(
+                &caps[1], &caps[1], fields, "diesel", file_path, false,
+            ));
+        }
+    }
+
+    for caps in from_row_regex().captures_iter(content) {
+        let open = caps.get(0).unwrap().end() - 1;
+        let Some(body) = delimited(content, open, '{', '}') else {
+            continue;
+        };
+        let fields = rust_field_regex()
+            .captures_iter(body)
+            .map(|c| c[1].to_string())
+            .collect();
+        // Struct names map to tables by convention: User -> users
+        let table = format!("{}s", to_snake_case(&caps[1]));
+        models.push(model(&caps[1], &table, fields, "sqlx", file_path, true));
+    }
+    models
+}
+
+/// Compare models against the schema
+pub fn compare(
+    schema: &BTreeMap<String, SchemaTable>,
+    models: &[ModelDefinition],
+) -> SchemaDriftReport {
+    let mut drift = Vec::new();
+    let mut unknown_tables = BTreeSet::new();
+
+    for model in models {
+        let table = schema
+            .get(&model.table)
+            .or_else(|| schema.get(&to_snake_case(&model.table)))
+            .or_else(|| schema.get(model.table.trim_end_matches('s')));
+        let Some(table) = table else {
+            if !model.partial {
+                unknown_tables.insert(model.table.clone());
+            }
+            continue;
+        };
+
+        let normalized: BTreeSet<String> = model
+            .fields
+            .iter()
+            .map(|f| {
+                let lower = f.to_lowercase();
+                if table.columns.contains(&lower) {
+                    lower
+                } else {
+                    to_snake_case(f)
+                }
+            })
+            .collect();
+        let missing_in_schema: Vec<String> = normalized
+            .iter()
+            .filter(|f| !table.columns.contains(*f))
+            .cloned()
+            .collect();
+        let missing_in_model: Vec<String> = if model.partial {
+            Vec::new()
+        } else {
+            table
+                .columns
+                .iter()
+                .filter(|c| !normalized.contains(*c))
+                .cloned()
+                .collect()
+        };
+
+        if !missing_in_schema.is_empty() || !missing_in_model.is_empty() {
+            drift.push(TableDrift {
+                table: table.name.clone(),
+                model: model.name.clone(),
+                framework: model.framework.clone(),
+                model_file: model.file_path.clone(),
+                schema_file: table.source_file.clone(),
+                missing_in_model,
+                missing_in_schema,
+            });
+        }
+    }
+
+    SchemaDriftReport {
+        tables: schema.len(),
+        models: models.len(),
+        drift,
+        unknown_tables: unknown_tables.into_iter().collect(),
+    }
+}
+
+fn scan_workspace(root_path: &str) -> SchemaDriftReport {
+    let mut migrations: Vec<PathBuf> = Vec::new();
+    let mut models = Vec::new();
+
+    for entry in WorkspaceWalker::new(root_path, WalkerConfig::for_list_files())
+        .build()
+        .flatten()
+    {
+        let path = entry.path();
+        if !path.is_file() || path.metadata().map(|m| m.len()).unwrap_or(0) > MAX_FILE_BYTES {
+            continue;
+        }
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if ext == "sql" {
+            migrations.push(path.to_path_buf());
+            continue;
+        }
+        if !matches!(ext, "prisma" | "ts" | "js" | "py" | "rs") {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let path_str = path.to_string_lossy().to_string();
+        match ext {
+            "prisma" => models.extend(parse_prisma_models(&content, &path_str)),
+            "ts" | "js" if content.contains("Table(") => {
+                models.extend(parse_drizzle_models(&content, &path_str))
+            }
+            "py" if content.contains("__tablename__") => {
+                models.extend(parse_sqlalchemy_models(&content, &path_str))
+            }
+            "rs" if content.contains("table!") || content.contains("FromRow") => {
+                models.extend(parse_rust_models(&content, &path_str))
+            }
+            _ => {}
+        }
+    }
+
+    // Migration tools name files so lexical order is application order
+    migrations.sort();
+    let mut schema = BTreeMap::new();
+    for migration in &migrations {
+        if let Ok(sql) = fs::read_to_string(migration) {
+            apply_migration(&mut schema, &sql, &migration.to_string_lossy());
+        }
+    }
+    compare(&schema, &models)
+}
+
+#[tauri::command]
+pub async fn check_schema_drift(root_path: String) -> Result<SchemaDriftReport, String> {
+    let report = tokio::task::spawn_blocking(move || scan_workspace(&root_path))
+        .await
+        .map_err(|e| format!("Schema drift check failed: {}", e))?;
+    log::info!(
+        "Schema drift: {} tables, {} models, {} drifting",
+        report.tables,
+        report.models,
+        report.drift.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn columns(schema: &BTreeMap<String, SchemaTable>, table: &str) -> Vec<String> {
+        schema[table].columns.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_apply_migrations() {
+        let mut schema = BTreeMap::new();
+        apply_migration(
+            &mut schema,
+            "-- users\nCREATE TABLE IF NOT EXISTS \"users\" (\n  id INTEGER PRIMARY KEY,\n  email TEXT NOT NULL,\n  price NUMERIC(10, 2),\n  CONSTRAINT email_unique UNIQUE (email)\n);\nCREATE TABLE tmp (x INT);",
+            "001.sql",
+        );
+        assert_eq!(columns(&schema, "users"), vec!["email", "id", "price"]);
+
+        apply_migration(
+            &mut schema,
+            "ALTER TABLE users ADD COLUMN created_at TIMESTAMP, DROP COLUMN price;\nALTER TABLE users RENAME COLUMN email TO email_address;\nDROP TABLE IF EXISTS tmp;\nALTER TABLE users RENAME TO accounts;",
+            "002.sql",
+        );
+        assert_eq!(
+            columns(&schema, "accounts"),
+            vec!["created_at", "email_address", "id"]
+        );
+        assert_eq!(schema["accounts"].source_file, "002.sql");
+        assert!(!schema.contains_key("users"));
+        assert!(!schema.contains_key("tmp"));
+    }
+
+    #[test]
+    fn test_parse_prisma_models() {
+        let content = "model User {\n  id        Int      @id\n  createdAt DateTime @map(\"created_at\")\n  posts     Post[]\n  profile   Profile?\n  @@map(\"users\")\n}\n\nmodel Profile {\n  id Int @id\n}\n";
+        let models = parse_prisma_models(content, "schema.prisma");
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].table, "users");
+        assert_eq!(
+            models[0].fields.iter().cloned().collect::<Vec<_>>(),
+            vec!["created_at", "id"]
+        );
+    }
+
+    #[test]
+    fn test_parse_drizzle_and_sqlalchemy() {
+        let ts = "export const users = pgTable('users', {\n  id: serial('id').primaryKey(),\n  fullName: text('full_name'),\n  age: integer(),\n});\n";
+        let models = parse_drizzle_models(ts, "schema.ts");
+        assert_eq!(
+            models[0].fields.iter().cloned().collect::<Vec<_>>(),
+            vec!["age", "full_name", "id"]
+        );
+
+        let py = "class User(Base):\n    __tablename__ = 'users'\n    id = Column(Integer, primary_key=True)\n    email: Mapped[str] = mapped_column(String)\n    name = Column('display_name', String)\n\nclass Helper(object):\n    x = 1\n";
+        let models = parse_sqlalchemy_models(py, "models.py");
+        assert_eq!(models.len(), 1);
+        assert_eq!(
+            models[0].fields.iter().cloned().collect::<Vec<_>>(),
+            vec!["display_name", "email", "id"]
+        );
+    }
+
+    #[test]
+    fn test_parse_rust_models() {
+        let content = "diesel::table! {\n    users (id) {\n        id -> Int4,\n        email -> Text,\n    }\n}\n\n#[derive(Debug, sqlx::FromRow)]\npub struct AuditLog {\n    pub id: i64,\n    pub action: String,\n}\n";
+        let models = parse_rust_models(content, "schema.rs");
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].framework, "diesel");
+        assert_eq!(models[0].table, "users");
+        assert_eq!(models[1].table, "audit_logs");
+        assert!(models[1].partial);
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut schema = BTreeMap::new();
+        apply_migration(
+            &mut schema,
+            "CREATE TABLE users (id INT, email TEXT, created_at TIMESTAMP);",
+            "001.sql",
+        );
+        let models = vec![
+            model(
+                "User",
+                "users",
+                ["id", "email", "nickname"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                "prisma",
+                "schema.prisma",
+                false,
+            ),
+            model(
+                "UserRow",
+                "user_rows",
+                ["id"].iter().map(|s| s.to_string()).collect(),
+                "sqlx",
+                "db.rs",
+                true,
+            ),
+            model(
+                "Order",
+                "orders",
+                BTreeSet::new(),
+                "prisma",
+                "schema.prisma",
+                false,
+            ),
+        ];
+        let report = compare(&schema, &models);
+        assert_eq!(report.drift.len(), 1);
+        assert_eq!(report.drift[0].missing_in_model, vec!["created_at"]);
+        assert_eq!(report.drift[0].missing_in_schema, vec!["nickname"]);
+        assert_eq!(report.unknown_tables, vec!["orders"]);
+    }
+
+    #[test]
+    fn test_scan_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("migrations")).unwrap();
+        fs::write(
+            root.join("migrations/001_init.sql"),
+            "CREATE TABLE users (id INT, email TEXT);",
+        )
+        .unwrap();
+        fs::write(
+            root.join("migrations/002_name.sql"),
+            "ALTER TABLE users ADD name TEXT;",
+        )
+        .unwrap();
+        fs::write(
+            root.join("schema.prisma"),
+            "model User {\n  id Int @id\n  email String\n  @@map(\"users\")\n}\n",
+        )
+        .unwrap();
+
+        let report = scan_workspace(&root.to_string_lossy());
+        assert_eq!(report.tables, 1);
+        assert_eq!(report.drift.len(), 1);
+        assert_eq!(report.drift[0].missing_in_model, vec!["name"]);
+        assert!(report.drift[0].schema_file.ends_with("002_name.sql"));
+    }
+}
@@ -0,0 +1,461 @@
+// Scratchpad: an in-memory overlay file system for agent drafts
+//
+// The agent can draft files per session, read them back (falling through to disk for
+// files it hasn't drafted), diff/validate/summarize them, and only write to disk when
+// the drafts are promoted. Promotion refuses to overwrite files that changed on disk
+// since the draft was started unless forced.
+
+use crate::code_navigation::{self, CodeNavigationService, CodeSummary};
+use crate::path_policy;
+use crate::syntax_check::{self, SyntaxCheckResult};
+use crate::text_diff;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+#[derive(Debug, Clone)]
+struct DraftFile {
+    content: String,
+    /// Disk content when the draft was started; `None` when the file did not exist
+    base_content: Option<String>,
+    created_at: i64,
+    updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftInfo {
+    pub path: String,
+    pub is_new: bool,
+    pub size: usize,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScratchReadResult {
+    pub path: String,
+    pub content: String,
+    pub from_draft: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromoteResult {
+    pub written: Vec<String>,
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct Scratchpad {
+    drafts: BTreeMap<String, DraftFile>,
+}
+
+/// Tauri state: session id -> scratchpad
+#[derive(Default)]
+pub struct ScratchpadState(pub Mutex<HashMap<String, Scratchpad>>);
+
+/// Resolve `path` against the workspace root, rejecting `..` escapes and symlinks that
+/// lead out of the workspace
+fn resolve_path(root_path: &str, path: &str) -> Result<PathBuf, String> {
+    let candidate = Path::new(path);
+    if candidate
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+    {
+        return Err(format!("Path must not contain '..': {}", path));
+    }
+    let resolved = if candidate.is_absolute() {
+        candidate.to_path_buf()
+    } else {
+        Path::new(root_path).join(candidate)
+    };
+    if !resolved.starts_with(root_path) {
+        return Err(format!("Path is outside the workspace: {}", path));
+    }
+    path_policy::resolve_within(&resolved, Path::new(root_path))?;
+    Ok(resolved)
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+impl Scratchpad {
+    pub fn write(&mut self, path: &Path, content: String) -> DraftInfo {
+        let key = path.to_string_lossy().to_string();
+        let now = now_millis();
+        let draft = self.drafts.entry(key.clone()).or_insert_with(|| DraftFile {
+            content: String::new(),
+            base_content: fs::read_to_string(path).ok(),
+            created_at: now,
+            updated_at: now,
+        });
+        draft.content = content;
+        draft.updated_at = now;
+        Self::info(&key, draft)
+    }
+
+    pub fn read(&self, path: &Path) -> Result<ScratchReadResult, String> {
+        let key = path.to_string_lossy().to_string();
+        if let Some(draft) = self.drafts.get(&key) {
+            return Ok(ScratchReadResult {
+                path: key,
+                content: draft.content.clone(),
+                from_draft: true,
+            });
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Ok(ScratchReadResult {
+            path: key,
+            content,
+            from_draft: false,
+        })
+    }
+
+    pub fn list(&self) -> Vec<DraftInfo> {
+        self.drafts
+            .iter()
+            .map(|(path, draft)| Self::info(path, draft))
+            .collect()
+    }
+
+    /// Unified diff between the current disk content and the draft
+    pub fn diff(&self, path: &Path) -> Result<String, String> {
+        let key = path.to_string_lossy().to_string();
+        let draft = self
+            .drafts
+            .get(&key)
+            .ok_or_else(|| format!("No draft for {}", key))?;
+        let disk = fs::read_to_string(path).unwrap_or_default();
+        Ok(text_diff::unified_diff(
+            &disk,
+            &draft.content,
+            &format!("a/{}", key),
+            &format!("b/{}", key),
+            3,
+        ))
+    }
+
+    /// Write drafts to disk. Drafts whose file changed on disk since drafting are
+    /// reported as conflicts and kept, unless `force` is set.
+    pub fn promote(
+        &mut self,
+        root: &Path,
+        paths: Option<&[PathBuf]>,
+        force: bool,
+    ) -> Result<PromoteResult, String> {
+        let keys: Vec<String> = match paths {
+            Some(paths) => paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            None => self.drafts.keys().cloned().collect(),
+        };
+
+        let mut result = PromoteResult {
+            written: Vec::new(),
+            conflicts: Vec::new(),
+        };
+
+        for key in keys {
+            let Some(draft) = self.drafts.get(&key) else {
+                return Err(format!("No draft for {}", key));
+            };
+            let path = Path::new(&key);
+            // Symlinks created since the draft was started must not redirect the write
+            path_policy::resolve_within(path, root)?;
+
+            let current = fs::read_to_string(path).ok();
+            if !force && current != draft.base_content {
+                result.conflicts.push(key);
+                continue;
+            }
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory for {}: {}", key, e))?;
+            }
+            fs::write(path, &draft.content)
+                .map_err(|e| format!("Failed to write {}: {}", key, e))?;
+            self.drafts.remove(&key);
+            result.written.push(key);
+        }
+
+        Ok(result)
+    }
+
+    pub fn discard(&mut self, paths: Option<&[PathBuf]>) -> usize {
+        match paths {
+            Some(paths) => paths
+                .iter()
+                .filter(|p| self.drafts.remove(&*p.to_string_lossy()).is_some())
+                .count(),
+            None => {
+                let count = self.drafts.len();
+                self.drafts.clear();
+                count
+            }
+        }
+    }
+
+    fn info(path: &str, draft: &DraftFile) -> DraftInfo {
+        DraftInfo {
+            path: path.to_string(),
+            is_new: draft.base_content.is_none(),
+            size: draft.content.len(),
+            created_at: draft.created_at,
+            updated_at: draft.updated_at,
+        }
+    }
+}
+
+fn lang_id_for(path: &Path) -> String {
+    CodeNavigationService::get_lang_id_from_path(&path.to_string_lossy()).unwrap_or_default()
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn scratch_write(
+    state: State<'_, ScratchpadState>,
+    session_id: String,
+    root_path: String,
+    path: String,
+    content: String,
+) -> Result<DraftInfo, String> {
+    let resolved = resolve_path(&root_path, &path)?;
+    let mut sessions = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(sessions
+        .entry(session_id)
+        .or_default()
+        .write(&resolved, content))
+}
+
+#[tauri::command]
+pub fn scratch_read(
+    state: State<'_, ScratchpadState>,
+    session_id: String,
+    root_path: String,
+    path: String,
+) -> Result<ScratchReadResult, String> {
+    let resolved = resolve_path(&root_path, &path)?;
+    let sessions = state.0.lock().map_err(|e| e.to_string())?;
+    match sessions.get(&session_id) {
+        Some(pad) => pad.read(&resolved),
+        None => Scratchpad::default().read(&resolved),
+    }
+}
+
+#[tauri::command]
+pub fn scratch_list(
+    state: State<'_, ScratchpadState>,
+    session_id: String,
+) -> Result<Vec<DraftInfo>, String> {
+    let sessions = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(sessions
+        .get(&session_id)
+        .map(|pad| pad.list())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn scratch_diff(
+    state: State<'_, ScratchpadState>,
+    session_id: String,
+    root_path: String,
+    path: String,
+) -> Result<String, String> {
+    let resolved = resolve_path(&root_path, &path)?;
+    let sessions = state.0.lock().map_err(|e| e.to_string())?;
+    sessions
+        .get(&session_id)
+        .ok_or_else(|| format!("No scratchpad for session {}", session_id))?
+        .diff(&resolved)
+}
+
+#[tauri::command]
+pub fn scratch_validate(
+    state: State<'_, ScratchpadState>,
+    session_id: String,
+    root_path: String,
+    path: String,
+) -> Result<SyntaxCheckResult, String> {
+    let resolved = resolve_path(&root_path, &path)?;
+    let content = {
+        let sessions = state.0.lock().map_err(|e| e.to_string())?;
+        match sessions.get(&session_id) {
+            Some(pad) => pad.read(&resolved)?.content,
+            None => Scratchpad::default().read(&resolved)?.content,
+        }
+    };
+    syntax_check::check_syntax(&content, &lang_id_for(&resolved))
+}
+
+#[tauri::command]
+pub async fn scratch_summarize(
+    state: State<'_, ScratchpadState>,
+    session_id: String,
+    root_path: String,
+    path: String,
+) -> Result<CodeSummary, String> {
+    let resolved = resolve_path(&root_path, &path)?;
+    let content = {
+        let sessions = state.0.lock().map_err(|e| e.to_string())?;
+        match sessions.get(&session_id) {
+            Some(pad) => pad.read(&resolved)?.content,
+            None => Scratchpad::default().read(&resolved)?.content,
+        }
+    };
+    code_navigation::summarize_code_content(
+        content,
+        lang_id_for(&resolved),
+        resolved.to_string_lossy().to_string(),
+        None,
+    )
+    .await
+}
+
+#[tauri::command]
+pub fn scratch_promote(
+    state: State<'_, ScratchpadState>,
+    session_id: String,
+    root_path: String,
+    paths: Option<Vec<String>>,
+    force: Option<bool>,
+) -> Result<PromoteResult, String> {
+    let resolved = paths
+        .map(|paths| {
+            paths
+                .iter()
+                .map(|p| resolve_path(&root_path, p))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+    let mut sessions = state.0.lock().map_err(|e| e.to_string())?;
+    let pad = sessions
+        .get_mut(&session_id)
+        .ok_or_else(|| format!("No scratchpad for session {}", session_id))?;
+    let result = pad.promote(
+        Path::new(&root_path),
+        resolved.as_deref(),
+        force.unwrap_or(false),
+    )?;
+    log::info!(
+        "Promoted {} scratchpad drafts for session {} ({} conflicts)",
+        result.written.len(),
+        session_id,
+        result.conflicts.len()
+    );
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn scratch_discard(
+    state: State<'_, ScratchpadState>,
+    session_id: String,
+    root_path: String,
+    paths: Option<Vec<String>>,
+) -> Result<usize, String> {
+    let resolved = paths
+        .map(|paths| {
+            paths
+                .iter()
+                .map(|p| resolve_path(&root_path, p))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+    let mut sessions = state.0.lock().map_err(|e| e.to_string())?;
+    let Some(pad) = sessions.get_mut(&session_id) else {
+        return Ok(0);
+    };
+    let discarded = pad.discard(resolved.as_deref());
+    if pad.drafts.is_empty() {
+        sessions.remove(&session_id);
+    }
+    Ok(discarded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_path_rejects_escapes() {
+        assert!(resolve_path("/project", "src/main.rs").is_ok());
+        assert!(resolve_path("/project", "../etc/passwd").is_err());
+        assert!(resolve_path("/project", "/other/file").is_err());
+    }
+
+    #[test]
+    fn test_draft_overlays_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("a.txt");
+        fs::write(&file, "disk").unwrap();
+
+        let mut pad = Scratchpad::default();
+        assert!(!pad.read(&file).unwrap().from_draft);
+
+        let info = pad.write(&file, "draft".to_string());
+        assert!(!info.is_new);
+        let read = pad.read(&file).unwrap();
+        assert!(read.from_draft);
+        assert_eq!(read.content, "draft");
+        // Disk is untouched until promotion
+        assert_eq!(fs::read_to_string(&file).unwrap(), "disk");
+
+        let diff = pad.diff(&file).unwrap();
+        assert!(diff.contains("-disk\n+draft"));
+    }
+
+    #[test]
+    fn test_promote_writes_new_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("src/new.rs");
+
+        let mut pad = Scratchpad::default();
+        assert!(pad.write(&file, "fn a() {}".to_string()).is_new);
+
+        let result = pad.promote(temp_dir.path(), None, false).unwrap();
+        assert_eq!(result.written.len(), 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "fn a() {}");
+        assert!(pad.list().is_empty());
+    }
+
+    #[test]
+    fn test_promote_detects_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("a.txt");
+        fs::write(&file, "v1").unwrap();
+
+        let mut pad = Scratchpad::default();
+        pad.write(&file, "agent".to_string());
+        fs::write(&file, "user edit").unwrap();
+
+        let result = pad.promote(temp_dir.path(), None, false).unwrap();
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "user edit");
+
+        let result = pad.promote(temp_dir.path(), None, true).unwrap();
+        assert_eq!(result.written.len(), 1);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "agent");
+    }
+
+    #[test]
+    fn test_discard() {
+        let temp_dir = TempDir::new().unwrap();
+        let a = temp_dir.path().join("a.txt");
+        let b = temp_dir.path().join("b.txt");
+        let mut pad = Scratchpad::default();
+        pad.write(&a, "a".to_string());
+        pad.write(&b, "b".to_string());
+
+        assert_eq!(pad.discard(Some(std::slice::from_ref(&a))), 1);
+        assert_eq!(pad.list().len(), 1);
+        assert_eq!(pad.discard(None), 1);
+        assert!(pad.list().is_empty());
+    }
+}
@@ -0,0 +1,530 @@
+// User-defined tools written as JavaScript/TypeScript scripts
+//
+// A script tool is a directory in ~/.talkcody/script-tools/ with a `tool.json` manifest
+// (name, description, JSON input schema, entry script, permissions) and the script
+// itself. Power users can add tools this way without rebuilding the app; the frontend
+// registers what `list_script_tools` returns in its tool registry.
+//
+// Scripts run under Deno with its permission flags as the sandbox: read access to the
+// workspace and the tool's own directory, nothing else unless the manifest asks for
+// `write` (workspace only) or `net` (listed hosts only). No environment, subprocess or
+// FFI access is granted. Node is used only when the manifest opts in with
+// `"runtime": "node"`, for `.js`/`.mjs` scripts only, with `--permission`; Node's
+// permission model covers the filesystem, child processes and workers but not the
+// network, which `list_script_tools` reports through `network_sandboxed`. A tool
+// without that opt-in never falls back to Node when Deno is missing, since its `net`
+// list would not be enforced.
+//
+// Host API: the script receives `{"input": ..., "workspace_root": ...}` as JSON on
+// stdin and prints its result as JSON on stdout. Anything on stderr is kept as logs.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+
+const MANIFEST_FILE: &str = "tool.json";
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const MAX_TIMEOUT_SECS: u64 = 300;
+const MAX_OUTPUT_BYTES: u64 = 4 * 1024 * 1024;
+/// Environment variables the runtimes need to locate their caches and system libraries
+const PASSTHROUGH_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USERPROFILE",
+    "SYSTEMROOT",
+    "TMPDIR",
+    "TEMP",
+    "TMP",
+    "DENO_DIR",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptRuntime {
+    Deno,
+    Node,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptPermissions {
+    /// Allow writing inside the workspace
+    pub write: bool,
+    /// Hosts the script may connect to, e.g. `api.github.com` or `localhost:8080`
+    pub net: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptToolManifest {
+    pub name: String,
+    pub description: String,
+    #[serde(default = "empty_object_schema")]
+    pub input_schema: Value,
+    /// Entry script, relative to the tool directory
+    pub script: String,
+    #[serde(default)]
+    pub runtime: Option<ScriptRuntime>,
+    #[serde(default)]
+    pub permissions: ScriptPermissions,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+fn empty_object_schema() -> Value {
+    json!({ "type": "object" })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptToolInfo {
+    pub path: String,
+    pub manifest: Option<ScriptToolManifest>,
+    /// Runtime the tool would run with now; None when none is usable
+    pub runtime: Option<ScriptRuntime>,
+    pub network_sandboxed: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptToolOutput {
+    pub result: Value,
+    pub logs: String,
+    pub duration_ms: u64,
+}
+
+fn get_tools_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody").join("script-tools"))
+}
+
+fn validate_tool_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid tool name {:?}: use lowercase letters, digits and _",
+            name
+        ))
+    }
+}
+
+fn is_typescript(script: &str) -> bool {
+    matches!(
+        Path::new(script).extension().and_then(|e| e.to_str()),
+        Some("ts" | "mts" | "tsx")
+    )
+}
+
+pub fn load_manifest(tool_dir: &Path) -> Result<ScriptToolManifest, String> {
+    let path = tool_dir.join(MANIFEST_FILE);
+    let raw = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let manifest: ScriptToolManifest =
+        serde_json::from_str(&raw).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+    validate_tool_name(&manifest.name)?;
+    if Path::new(&manifest.script)
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(format!(
+            "Script path must stay inside the tool: {}",
+            manifest.script
+        ));
+    }
+    if !tool_dir.join(&manifest.script).is_file() {
+        return Err(format!("Script not found: {}", manifest.script));
+    }
+    if let Some(host) = manifest
+        .permissions
+        .net
+        .iter()
+        .find(|h| h.is_empty() || h.contains([',', '/', ' ']))
+    {
+        return Err(format!("Invalid network host {:?}", host));
+    }
+    Ok(manifest)
+}
+
+/// Pick the runtime for a tool given which runtimes are installed. Node is only used
+/// when the manifest asks for it.
+fn select_runtime(
+    manifest: &ScriptToolManifest,
+    deno_available: bool,
+    node_available: bool,
+) -> Result<ScriptRuntime, String> {
+    if manifest.runtime != Some(ScriptRuntime::Node) {
+        return if deno_available {
+            Ok(ScriptRuntime::Deno)
+        } else {
+            Err(
+                "Deno is not installed; set \"runtime\": \"node\" in tool.json to run this tool \
+                 with Node, which cannot restrict network access"
+                    .to_string(),
+            )
+        };
+    }
+    if is_typescript(&manifest.script) {
+        return Err(format!(
+            "{} is TypeScript, which needs Deno",
+            manifest.script
+        ));
+    }
+    if node_available {
+        Ok(ScriptRuntime::Node)
+    } else {
+        Err("Node is not installed".to_string())
+    }
+}
+
+fn detect_runtime(manifest: &ScriptToolManifest) -> Result<ScriptRuntime, String> {
+    select_runtime(
+        manifest,
+        which::which("deno").is_ok(),
+        which::which("node").is_ok(),
+    )
+}
+
+/// Command-line arguments that run `script` with the manifest's permissions
+fn runtime_args(
+    runtime: ScriptRuntime,
+    manifest: &ScriptToolManifest,
+    tool_dir: &Path,
+    workspace_root: Option<&Path>,
+) -> Vec<String> {
+    let mut readable = vec![tool_dir.to_string_lossy().to_string()];
+    readable.extend(workspace_root.map(|r| r.to_string_lossy().to_string()));
+    let writable = workspace_root
+        .filter(|_| manifest.permissions.write)
+        .map(|r| r.to_string_lossy().to_string());
+    let script = tool_dir
+        .join(&manifest.script)
+        .to_string_lossy()
+        .to_string();
+
+    let mut args = Vec::new();
+    match runtime {
+        ScriptRuntime::Deno => {
+            args.extend(["run", "--no-prompt", "--no-config"].map(String::from));
+            args.push(format!("--allow-read={}", readable.join(",")));
+            if let Some(dir) = writable {
+                args.push(format!("--allow-write={}", dir));
+            }
+            if !manifest.permissions.net.is_empty() {
+                args.push(format!(
+                    "--allow-net={}",
+                    manifest.permissions.net.join(",")
+                ));
+            }
+        }
+        ScriptRuntime::Node => {
+            args.push("--permission".to_string());
+            args.extend(
+                readable
+                    .iter()
+                    .map(|dir| format!("--allow-fs-read={}", dir)),
+            );
+            if let Some(dir) = writable {
+                args.push(format!("--allow-fs-write={}", dir));
+            }
+        }
+    }
+    args.push(script);
+    args
+}
+
+/// The script's result is the last JSON value it printed; plain text is kept as a string
+fn parse_output(stdout: &str) -> Value {
+    let trimmed = stdout.trim();
+    serde_json::from_str(trimmed)
+        .ok()
+        .or_else(|| {
+            trimmed
+                .lines()
+                .last()
+                .and_then(|line| serde_json::from_str(line).ok())
+        })
+        .unwrap_or_else(|| Value::String(trimmed.to_string()))
+}
+
+pub fn discover(tools_dir: &Path) -> Vec<ScriptToolInfo> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(tools_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+
+    dirs.into_iter()
+        .map(|dir| {
+            let path = dir.to_string_lossy().to_string();
+            let manifest = match load_manifest(&dir) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    return ScriptToolInfo {
+                        path,
+                        manifest: None,
+                        runtime: None,
+                        network_sandboxed: false,
+                        error: Some(e),
+                    }
+                }
+            };
+            let (runtime, error) = match detect_runtime(&manifest) {
+                Ok(runtime) => (Some(runtime), None),
+                Err(e) => (None, Some(e)),
+            };
+            ScriptToolInfo {
+                path,
+                manifest: Some(manifest),
+                runtime,
+                network_sandboxed: runtime == Some(ScriptRuntime::Deno),
+                error,
+            }
+        })
+        .collect()
+}
+
+async fn read_capped(mut reader: impl tokio::io::AsyncRead + Unpin) -> String {
+    let mut buffer = Vec::new();
+    let _ = (&mut reader)
+        .take(MAX_OUTPUT_BYTES)
+        .read_to_end(&mut buffer)
+        .await;
+    // Keep draining so the script never blocks on a full pipe
+    let _ = tokio::io::copy(&mut reader, &mut tokio::io::sink()).await;
+    String::from_utf8_lossy(&buffer).to_string()
+}
+
+pub async fn run_script_tool(
+    tool_dir: &Path,
+    manifest: &ScriptToolManifest,
+    input: Value,
+    workspace_root: Option<&Path>,
+) -> Result<ScriptToolOutput, String> {
+    let runtime = detect_runtime(manifest)?;
+    let program = match runtime {
+        ScriptRuntime::Deno => "deno",
+        ScriptRuntime::Node => "node",
+    };
+    let mut cmd = Command::new(program);
+    cmd.args(runtime_args(runtime, manifest, tool_dir, workspace_root))
+        .current_dir(workspace_root.unwrap_or(tool_dir))
+        .env_clear()
+        .envs(
+            PASSTHROUGH_ENV
+                .iter()
+                .filter_map(|k| std::env::var(k).ok().map(|v| (*k, v))),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(windows)]
+    {
+        // Hide the console window to avoid flashing cmd.exe
+        cmd.creation_flags(0x08000000);
+    }
+
+    let started = std::time::Instant::now();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", program, e))?;
+    let payload = json!({
+        "input": input,
+        "workspace_root": workspace_root.map(|r| r.to_string_lossy().to_string()),
+    })
+    .to_string();
+    let mut stdin = child.stdin.take().ok_or("Failed to open stdin")?;
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+    let stdout_task = tokio::spawn(read_capped(stdout));
+    let stderr_task = tokio::spawn(read_capped(stderr));
+    // A script that never reads stdin closes the pipe; that is not an error
+    let _ = stdin.write_all(payload.as_bytes()).await;
+    drop(stdin);
+
+    let timeout = manifest
+        .timeout_secs
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .min(MAX_TIMEOUT_SECS);
+    let status = match tokio::time::timeout(Duration::from_secs(timeout), child.wait()).await {
+        Ok(status) => status.map_err(|e| format!("Failed to wait for {}: {}", program, e))?,
+        Err(_) => {
+            let _ = child.kill().await;
+            return Err(format!(
+                "Tool {} timed out after {}s",
+                manifest.name, timeout
+            ));
+        }
+    };
+    let stdout = stdout_task.await.map_err(|e| e.to_string())?;
+    let logs = stderr_task.await.map_err(|e| e.to_string())?;
+    if !status.success() {
+        let code = status.code().unwrap_or(-1);
+        return Err(format!(
+            "Tool {} exited with code {}: {}",
+            manifest.name,
+            code,
+            logs.trim()
+        ));
+    }
+    Ok(ScriptToolOutput {
+        result: parse_output(&stdout),
+        logs,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_script_tools() -> Result<Vec<ScriptToolInfo>, String> {
+    let tools_dir = get_tools_dir()?;
+    tauri::async_runtime::spawn_blocking(move || discover(&tools_dir))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run a script tool. File access is limited to `root_path` and the tool's directory.
+#[tauri::command]
+pub async fn invoke_script_tool(
+    tool: String,
+    input: Value,
+    root_path: Option<String>,
+) -> Result<ScriptToolOutput, String> {
+    validate_tool_name(&tool)?;
+    let tools_dir = get_tools_dir()?;
+    let info = discover(&tools_dir)
+        .into_iter()
+        .find(|info| info.manifest.as_ref().is_some_and(|m| m.name == tool))
+        .ok_or_else(|| format!("No script tool named {}", tool))?;
+    let manifest = info.manifest.ok_or("Missing manifest")?;
+    let root = root_path.map(PathBuf::from);
+    if let Some(root) = &root {
+        if !root.is_dir() {
+            return Err(format!("Workspace does not exist: {}", root.display()));
+        }
+    }
+    log::info!("Running script tool {}", tool);
+    run_script_tool(Path::new(&info.path), &manifest, input, root.as_deref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn manifest(script: &str, permissions: ScriptPermissions) -> ScriptToolManifest {
+        ScriptToolManifest {
+            name: "word_count".to_string(),
+            description: "Count words".to_string(),
+            input_schema: empty_object_schema(),
+            script: script.to_string(),
+            runtime: None,
+            permissions,
+            timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_load_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("main.ts"), "console.log('{}')").unwrap();
+        let write = |value: Value| {
+            fs::write(temp_dir.path().join(MANIFEST_FILE), value.to_string()).unwrap()
+        };
+
+        write(json!({"name": "word_count", "description": "Count words", "script": "main.ts"}));
+        let loaded = load_manifest(temp_dir.path()).unwrap();
+        assert_eq!(loaded.permissions, ScriptPermissions::default());
+        assert_eq!(loaded.input_schema, json!({"type": "object"}));
+
+        write(json!({"name": "word_count", "description": "", "script": "../main.ts"}));
+        assert!(load_manifest(temp_dir.path()).is_err());
+        write(json!({"name": "word_count", "description": "", "script": "missing.ts"}));
+        assert!(load_manifest(temp_dir.path()).is_err());
+        write(json!({
+            "name": "word_count", "description": "", "script": "main.ts",
+            "permissions": {"net": ["a.com,b.com"]}
+        }));
+        assert!(load_manifest(temp_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_select_runtime() {
+        let ts = manifest("main.ts", ScriptPermissions::default());
+        assert_eq!(select_runtime(&ts, true, true), Ok(ScriptRuntime::Deno));
+        assert!(select_runtime(&ts, false, true).is_err());
+
+        // No silent fallback to Node, which would not enforce the net list
+        let js = manifest(
+            "main.js",
+            ScriptPermissions {
+                write: false,
+                net: vec!["api.github.com".to_string()],
+            },
+        );
+        assert!(select_runtime(&js, false, true).is_err());
+        let forced = ScriptToolManifest {
+            runtime: Some(ScriptRuntime::Node),
+            ..js
+        };
+        assert_eq!(select_runtime(&forced, true, true), Ok(ScriptRuntime::Node));
+        assert_eq!(
+            select_runtime(&forced, false, true),
+            Ok(ScriptRuntime::Node)
+        );
+    }
+
+    #[test]
+    fn test_runtime_args_are_read_only_by_default() {
+        let tool_dir = Path::new("/tools/word_count");
+        let root = Path::new("/work");
+        let read_only = manifest("main.ts", ScriptPermissions::default());
+        let args = runtime_args(ScriptRuntime::Deno, &read_only, tool_dir, Some(root));
+        assert!(args.contains(&"--allow-read=/tools/word_count,/work".to_string()));
+        assert!(!args.iter().any(|a| a.starts_with("--allow-write")
+            || a.starts_with("--allow-net")
+            || a.starts_with("--allow-run")
+            || a.starts_with("--allow-env")));
+        assert_eq!(args.last().unwrap(), "/tools/word_count/main.ts");
+
+        let permissive = manifest(
+            "main.js",
+            ScriptPermissions {
+                write: true,
+                net: vec!["api.github.com".to_string()],
+            },
+        );
+        let args = runtime_args(ScriptRuntime::Deno, &permissive, tool_dir, Some(root));
+        assert!(args.contains(&"--allow-write=/work".to_string()));
+        assert!(args.contains(&"--allow-net=api.github.com".to_string()));
+
+        let args = runtime_args(ScriptRuntime::Node, &permissive, tool_dir, Some(root));
+        assert_eq!(args[0], "--permission");
+        assert!(args.contains(&"--allow-fs-write=/work".to_string()));
+    }
+
+    #[test]
+    fn test_parse_output() {
+        assert_eq!(parse_output("{\"count\": 3}\n"), json!({"count": 3}));
+        assert_eq!(
+            parse_output("progress...\n{\"count\": 3}"),
+            json!({"count": 3})
+        );
+        assert_eq!(parse_output("plain text"), json!("plain text"));
+    }
+}
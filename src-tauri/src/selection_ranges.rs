@@ -0,0 +1,201 @@
+// Syntax-aware selection expansion
+//
+// Smart select grows a selection one syntactic step at a time: identifier, expression,
+// statement, block, function, class, file. Each step is a named node enclosing the
+// selection; for a node wrapped in brackets (`{ ... }`, `( ... )`, `[ ... ]`) the
+// contents between the brackets come first, so a block's statements can be selected
+// without its braces. Steps that would select the same text are reported once, and
+// every step is strictly larger than the selection it expands.
+
+use crate::code_navigation::{get_language, CodeNavigationService};
+use crate::position_encoding::{LineIndex, Position, PositionEncoding};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tree_sitter::{Node, Parser, Point};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectionRange {
+    pub start: Position,
+    /// Exclusive
+    pub end: Position,
+    /// Node kind, or `<kind>_contents` for the inside of a bracketed node
+    pub kind: String,
+}
+
+const BRACKETS: &[(&str, &str)] = &[("{", "}"), ("(", ")"), ("[", "]")];
+
+fn to_point(position: Position) -> Point {
+    Point::new(
+        position.line.saturating_sub(1) as usize,
+        position.column.saturating_sub(1) as usize,
+    )
+}
+
+fn to_position(point: Point) -> Position {
+    Position {
+        line: point.row as u32 + 1,
+        column: point.column as u32 + 1,
+    }
+}
+
+/// The range between a node's opening and closing bracket
+fn bracket_contents(node: Node) -> Option<(Point, Point)> {
+    let count = node.child_count();
+    if count < 2 {
+        return None;
+    }
+    let open = node.child(0)?;
+    let close = node.child(count - 1)?;
+    BRACKETS
+        .iter()
+        .any(|(o, c)| open.kind() == *o && close.kind() == *c)
+        .then(|| (open.end_position(), close.start_position()))
+}
+
+pub fn selection_ranges(
+    content: &str,
+    lang_id: &str,
+    start: Position,
+    end: Position,
+) -> Result<Vec<SelectionRange>, String> {
+    let language =
+        get_language(lang_id).ok_or_else(|| format!("Unsupported language: {}", lang_id))?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| format!("Failed to set language for {}: {:?}", lang_id, e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| format!("Failed to parse {} content", lang_id))?;
+
+    let (start, end) = (to_point(start), to_point(end));
+    let Some(mut node) = tree.root_node().descendant_for_point_range(start, end) else {
+        return Ok(Vec::new());
+    };
+
+    let mut ranges: Vec<SelectionRange> = Vec::new();
+    let mut push = |from: Point, to: Point, kind: String| {
+        let grows = from <= start && to >= end && (from, to) != (start, end);
+        let repeated = ranges
+            .last()
+            .is_some_and(|last| (to_point(last.start), to_point(last.end)) == (from, to));
+        if grows && !repeated {
+            ranges.push(SelectionRange {
+                start: to_position(from),
+                end: to_position(to),
+                kind,
+            });
+        }
+    };
+
+    loop {
+        if node.is_named() {
+            if let Some((from, to)) = bracket_contents(node) {
+                push(from, to, format!("{}_contents", node.kind()));
+            }
+            push(
+                node.start_position(),
+                node.end_position(),
+                node.kind().to_string(),
+            );
+        }
+        match node.parent() {
+            Some(parent) => node = parent,
+            None => break,
+        }
+    }
+    Ok(ranges)
+}
+
+/// Ranges enclosing the selection from `start` to `end`, smallest first
+#[tauri::command]
+pub fn expand_selection(
+    file_path: String,
+    start: Position,
+    end: Position,
+    content: Option<String>,
+    lang_id: Option<String>,
+    encoding: Option<PositionEncoding>,
+) -> Result<Vec<SelectionRange>, String> {
+    let content = match content {
+        Some(content) => content,
+        None => fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to read {}: {}", file_path, e))?,
+    };
+    let lang_id = lang_id
+        .or_else(|| CodeNavigationService::get_lang_id_from_path(&file_path))
+        .ok_or_else(|| format!("Could not determine the language of {}", file_path))?;
+
+    let utf16 = encoding == Some(PositionEncoding::Utf16);
+    let index = LineIndex::new(&content);
+    let (start, end) = if utf16 {
+        (index.to_utf8(start), index.to_utf8(end))
+    } else {
+        (start, end)
+    };
+    let mut ranges = selection_ranges(&content, &lang_id, start, end)?;
+    if utf16 {
+        for range in &mut ranges {
+            range.start = index.to_utf16(range.start);
+            range.end = index.to_utf16(range.end);
+        }
+    }
+    Ok(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(line: u32, column: u32) -> Position {
+        Position { line, column }
+    }
+
+    #[test]
+    fn test_expand_from_cursor() {
+        let code = "class Cart:\n    def total(self):\n        return sum(self.items)\n";
+        // Cursor inside `items`
+        let ranges = selection_ranges(code, "python", pos(3, 27), pos(3, 27)).unwrap();
+        let kinds: Vec<&str> = ranges.iter().map(|r| r.kind.as_str()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "identifier",
+                "attribute",
+                "argument_list",
+                "call",
+                "return_statement",
+                "function_definition",
+                "class_definition",
+                "module",
+            ]
+        );
+        assert_eq!((ranges[0].start, ranges[0].end), (pos(3, 25), pos(3, 30)));
+        assert_eq!((ranges[2].start, ranges[2].end), (pos(3, 19), pos(3, 31)));
+        // Steps covering the same text as the previous one are dropped: the argument
+        // list's contents are exactly `self.items`, and each body block spans exactly
+        // its single statement or method
+        assert!(!kinds.contains(&"argument_list_contents"));
+        assert!(!kinds.contains(&"block"));
+    }
+
+    #[test]
+    fn test_expand_existing_selection_with_utf16() {
+        let code = "fn f() {\n    let s = \"é\"; g(s);\n}\n";
+        // `g(s)` selected, in UTF-16 columns
+        let ranges = expand_selection(
+            "lib.rs".to_string(),
+            pos(2, 18),
+            pos(2, 22),
+            Some(code.to_string()),
+            None,
+            Some(PositionEncoding::Utf16),
+        )
+        .unwrap();
+        assert_eq!(ranges[0].kind, "expression_statement");
+        assert_eq!((ranges[0].start, ranges[0].end), (pos(2, 18), pos(2, 23)));
+        assert_eq!(ranges[1].kind, "block_contents");
+        assert_eq!((ranges[1].start, ranges[1].end), (pos(1, 9), pos(3, 1)));
+        assert_eq!(ranges.last().unwrap().kind, "source_file");
+    }
+}
@@ -0,0 +1,443 @@
+// Session branching: fork a conversation at an arbitrary message into a new session.
+//
+// Forks are copy-on-write: message rows up to and including the fork point are copied
+// into the new conversation, while attachment rows keep pointing at the same files on
+// disk and file checkpoint rows keep pointing at the same content blobs (see
+// file_checkpoints.rs). Lineage is recorded in `conversation_forks` so the UI can show
+// the branch tree. All rows of a fork are written in one transaction.
+
+use crate::database::Database;
+use crate::file_checkpoints;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
+
+const CREATE_FORKS_TABLE: &str = "CREATE TABLE IF NOT EXISTS conversation_forks (
+    conversation_id TEXT PRIMARY KEY,
+    parent_conversation_id TEXT NOT NULL,
+    forked_at_message_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (conversation_id) REFERENCES conversations (id) ON DELETE CASCADE
+)";
+
+/// SQL text and its bound parameters, run together in one transaction
+type Statement = (String, Vec<Value>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkResult {
+    pub conversation_id: String,
+    pub parent_conversation_id: String,
+    pub forked_at_message_id: String,
+    pub messages_copied: usize,
+    pub attachments_copied: usize,
+    pub checkpoints_copied: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkInfo {
+    pub conversation_id: String,
+    pub parent_conversation_id: String,
+    pub forked_at_message_id: String,
+    pub created_at: i64,
+}
+
+fn str_field(row: &Value, key: &str) -> String {
+    row.get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn field(row: &Value, key: &str) -> Value {
+    row.get(key).cloned().unwrap_or(Value::Null)
+}
+
+/// Select the messages that belong to the fork: everything up to and including the fork point.
+/// `messages` must already be in conversation order.
+fn messages_up_to<'a>(messages: &'a [Value], message_id: &str) -> Option<&'a [Value]> {
+    let idx = messages
+        .iter()
+        .position(|m| m.get("id").and_then(|v| v.as_str()) == Some(message_id))?;
+    Some(&messages[..=idx])
+}
+
+/// Build the INSERT statements for a fork. Returns the statements and the old→new message id map.
+fn build_fork_statements(
+    conversation: &Value,
+    messages: &[Value],
+    attachments: &[Value],
+    new_conversation_id: &str,
+    title: &str,
+    forked_at_message_id: &str,
+    now: i64,
+) -> (Vec<Statement>, HashMap<String, String>) {
+    let mut statements: Vec<Statement> = Vec::new();
+    let mut id_map = HashMap::new();
+
+    statements.push((
+        "INSERT INTO conversations (id, title, project_id, created_at, updated_at, message_count, cost, input_token, output_token, context_usage, settings) VALUES (?, ?, ?, ?, ?, ?, 0, 0, 0, NULL, ?)".to_string(),
+        vec![
+            Value::from(new_conversation_id),
+            Value::from(title),
+            field(conversation, "project_id"),
+            Value::from(now),
+            Value::from(now),
+            Value::from(messages.len() as i64),
+            field(conversation, "settings"),
+        ],
+    ));
+
+    for message in messages {
+        let new_id = uuid::Uuid::new_v4().to_string();
+        id_map.insert(str_field(message, "id"), new_id.clone());
+        statements.push((
+            "INSERT INTO messages (id, conversation_id, role, content, timestamp, assistant_id, position_index) VALUES (?, ?, ?, ?, ?, ?, ?)".to_string(),
+            vec![
+                Value::from(new_id),
+                Value::from(new_conversation_id),
+                field(message, "role"),
+                field(message, "content"),
+                field(message, "timestamp"),
+                field(message, "assistant_id"),
+                field(message, "position_index"),
+            ],
+        ));
+    }
+
+    for attachment in attachments {
+        let Some(new_message_id) = id_map.get(&str_field(attachment, "message_id")) else {
+            continue;
+        };
+        statements.push((
+            "INSERT INTO message_attachments (id, message_id, type, filename, file_path, mime_type, size, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)".to_string(),
+            vec![
+                Value::from(uuid::Uuid::new_v4().to_string()),
+                Value::from(new_message_id.as_str()),
+                field(attachment, "type"),
+                field(attachment, "filename"),
+                field(attachment, "file_path"),
+                field(attachment, "mime_type"),
+                field(attachment, "size"),
+                field(attachment, "created_at"),
+            ],
+        ));
+    }
+
+    statements.push((
+        "INSERT INTO conversation_forks (conversation_id, parent_conversation_id, forked_at_message_id, created_at) VALUES (?, ?, ?, ?)".to_string(),
+        vec![
+            Value::from(new_conversation_id),
+            field(conversation, "id"),
+            Value::from(forked_at_message_id),
+            Value::from(now),
+        ],
+    ));
+
+    (statements, id_map)
+}
+
+/// Fork `conversation_id` at `message_id` into a new conversation
+pub async fn fork_conversation(
+    db: &Database,
+    conversation_id: &str,
+    message_id: &str,
+    title: Option<String>,
+) -> Result<ForkResult, String> {
+    db.execute(CREATE_FORKS_TABLE, vec![]).await?;
+    file_checkpoints::ensure_schema(db).await?;
+
+    let conversation = db
+        .query(
+            "SELECT * FROM conversations WHERE id = ?",
+            vec![Value::from(conversation_id)],
+        )
+        .await?
+        .rows
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Conversation not found: {}", conversation_id))?;
+
+    let all_messages = db
+        .query(
+            "SELECT * FROM messages WHERE conversation_id = ? ORDER BY position_index ASC, timestamp ASC",
+            vec![Value::from(conversation_id)],
+        )
+        .await?
+        .rows;
+
+    let messages = messages_up_to(&all_messages, message_id).ok_or_else(|| {
+        format!(
+            "Message {} not found in conversation {}",
+            message_id, conversation_id
+        )
+    })?;
+
+    let attachments = db
+        .query(
+            "SELECT a.* FROM message_attachments a JOIN messages m ON a.message_id = m.id WHERE m.conversation_id = ?",
+            vec![Value::from(conversation_id)],
+        )
+        .await?
+        .rows;
+
+    let new_conversation_id = uuid::Uuid::new_v4().to_string();
+    let title = title.unwrap_or_else(|| format!("{} (fork)", str_field(&conversation, "title")));
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let (mut statements, id_map) = build_fork_statements(
+        &conversation,
+        messages,
+        &attachments,
+        &new_conversation_id,
+        &title,
+        message_id,
+        now,
+    );
+    let attachments_copied = statements.len() - messages.len() - 2;
+
+    let checkpoints = file_checkpoints::list_checkpoints(db, conversation_id).await?;
+    let checkpoint_statements =
+        file_checkpoints::fork_statements(&checkpoints, &id_map, &new_conversation_id);
+    let checkpoints_copied = checkpoint_statements.len();
+    statements.extend(checkpoint_statements);
+
+    db.transaction(statements)
+        .await
+        .map_err(|e| format!("Failed to fork conversation: {}", e))?;
+
+    log::info!(
+        "Forked conversation {} at message {} into {} ({} messages)",
+        conversation_id,
+        message_id,
+        new_conversation_id,
+        id_map.len()
+    );
+
+    Ok(ForkResult {
+        conversation_id: new_conversation_id,
+        parent_conversation_id: conversation_id.to_string(),
+        forked_at_message_id: message_id.to_string(),
+        messages_copied: id_map.len(),
+        attachments_copied,
+        checkpoints_copied,
+    })
+}
+
+/// List the direct forks of a conversation
+pub async fn list_forks(db: &Database, conversation_id: &str) -> Result<Vec<ForkInfo>, String> {
+    db.execute(CREATE_FORKS_TABLE, vec![]).await?;
+    let rows = db
+        .query(
+            "SELECT * FROM conversation_forks WHERE parent_conversation_id = ? ORDER BY created_at ASC",
+            vec![Value::from(conversation_id)],
+        )
+        .await?
+        .rows;
+
+    Ok(rows
+        .iter()
+        .map(|row| ForkInfo {
+            conversation_id: str_field(row, "conversation_id"),
+            parent_conversation_id: str_field(row, "parent_conversation_id"),
+            forked_at_message_id: str_field(row, "forked_at_message_id"),
+            created_at: row.get("created_at").and_then(|v| v.as_i64()).unwrap_or(0),
+        })
+        .collect())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn session_fork(
+    db: State<'_, Arc<Database>>,
+    conversation_id: String,
+    message_id: String,
+    title: Option<String>,
+) -> Result<ForkResult, String> {
+    fork_conversation(&db, &conversation_id, &message_id, title).await
+}
+
+#[tauri::command]
+pub async fn session_list_forks(
+    db: State<'_, Arc<Database>>,
+    conversation_id: String,
+) -> Result<Vec<ForkInfo>, String> {
+    list_forks(&db, &conversation_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    async fn setup_db(temp_dir: &TempDir) -> Database {
+        let db_path = temp_dir.path().join("test.db");
+        let db = Database::new(db_path.to_string_lossy().to_string());
+        db.connect().await.unwrap();
+        db.execute(
+            "CREATE TABLE conversations (id TEXT PRIMARY KEY, title TEXT NOT NULL, project_id TEXT NOT NULL DEFAULT 'default', created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL, message_count INTEGER DEFAULT 0, cost REAL DEFAULT 0, input_token INTEGER DEFAULT 0, output_token INTEGER DEFAULT 0, context_usage REAL DEFAULT NULL, settings TEXT DEFAULT NULL)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        db.execute(
+            "CREATE TABLE messages (id TEXT PRIMARY KEY, conversation_id TEXT NOT NULL, role TEXT NOT NULL, content TEXT NOT NULL, timestamp INTEGER NOT NULL, assistant_id TEXT, position_index INTEGER DEFAULT 0)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        db.execute(
+            "CREATE TABLE message_attachments (id TEXT PRIMARY KEY, message_id TEXT NOT NULL, type TEXT NOT NULL, filename TEXT NOT NULL, file_path TEXT NOT NULL, mime_type TEXT NOT NULL, size INTEGER NOT NULL, created_at INTEGER NOT NULL)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        db
+    }
+
+    #[test]
+    fn test_messages_up_to() {
+        let messages = vec![
+            json!({"id": "m1"}),
+            json!({"id": "m2"}),
+            json!({"id": "m3"}),
+        ];
+        assert_eq!(messages_up_to(&messages, "m2").unwrap().len(), 2);
+        assert_eq!(messages_up_to(&messages, "m3").unwrap().len(), 3);
+        assert!(messages_up_to(&messages, "missing").is_none());
+    }
+
+    #[test]
+    fn test_build_fork_statements_skips_attachments_after_fork_point() {
+        let conversation = json!({"id": "c1", "project_id": "p1", "settings": null});
+        let messages = vec![json!({"id": "m1", "role": "user", "content": "hi"})];
+        let attachments = vec![
+            json!({"id": "a1", "message_id": "m1", "file_path": "/tmp/a.png"}),
+            json!({"id": "a2", "message_id": "m2", "file_path": "/tmp/b.png"}),
+        ];
+
+        let (statements, id_map) = build_fork_statements(
+            &conversation,
+            &messages,
+            &attachments,
+            "c2",
+            "fork",
+            "m1",
+            0,
+        );
+
+        // conversation + 1 message + 1 attachment + lineage row
+        assert_eq!(statements.len(), 4);
+        assert_eq!(id_map.len(), 1);
+        assert_ne!(id_map["m1"], "m1");
+    }
+
+    #[tokio::test]
+    async fn test_fork_conversation_copies_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = setup_db(&temp_dir).await;
+
+        db.execute(
+            "INSERT INTO conversations (id, title, created_at, updated_at) VALUES ('c1', 'Auth fix', 1, 1)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        for (i, id) in ["m1", "m2", "m3"].iter().enumerate() {
+            db.execute(
+                "INSERT INTO messages (id, conversation_id, role, content, timestamp, position_index) VALUES (?, 'c1', 'user', ?, ?, ?)",
+                vec![json!(id), json!(format!("message {}", i)), json!(i), json!(i)],
+            )
+            .await
+            .unwrap();
+        }
+        db.execute(
+            "INSERT INTO message_attachments (id, message_id, type, filename, file_path, mime_type, size, created_at) VALUES ('a1', 'm1', 'image', 'a.png', '/tmp/a.png', 'image/png', 10, 1)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        let kept = file_checkpoints::record_checkpoint(&db, "c1", "m2", "a.rs", Some("old"))
+            .await
+            .unwrap();
+        file_checkpoints::record_checkpoint(&db, "c1", "m3", "b.rs", Some("later"))
+            .await
+            .unwrap();
+
+        let result = fork_conversation(&db, "c1", "m2", None).await.unwrap();
+        assert_eq!(result.messages_copied, 2);
+        assert_eq!(result.attachments_copied, 1);
+        assert_eq!(result.checkpoints_copied, 1);
+
+        // The fork's checkpoint is its own row but shares the parent's content
+        let checkpoints = file_checkpoints::list_checkpoints(&db, &result.conversation_id)
+            .await
+            .unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        assert_ne!(checkpoints[0].id, kept.id);
+        assert_eq!(checkpoints[0].content_hash, kept.content_hash);
+        assert_eq!(
+            file_checkpoints::read_checkpoint(&db, &checkpoints[0].id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("old")
+        );
+
+        let rows = db
+            .query(
+                "SELECT title FROM conversations WHERE id = ?",
+                vec![json!(result.conversation_id)],
+            )
+            .await
+            .unwrap()
+            .rows;
+        assert_eq!(rows[0]["title"], "Auth fix (fork)");
+
+        let forked = db
+            .query(
+                "SELECT content FROM messages WHERE conversation_id = ? ORDER BY position_index",
+                vec![json!(result.conversation_id)],
+            )
+            .await
+            .unwrap()
+            .rows;
+        assert_eq!(forked.len(), 2);
+        assert_eq!(forked[1]["content"], "message 1");
+
+        // Original is untouched
+        let original = db
+            .query(
+                "SELECT id FROM messages WHERE conversation_id = 'c1'",
+                vec![],
+            )
+            .await
+            .unwrap()
+            .rows;
+        assert_eq!(original.len(), 3);
+
+        let forks = list_forks(&db, "c1").await.unwrap();
+        assert_eq!(forks.len(), 1);
+        assert_eq!(forks[0].forked_at_message_id, "m2");
+    }
+
+    #[tokio::test]
+    async fn test_fork_unknown_message_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = setup_db(&temp_dir).await;
+        db.execute(
+            "INSERT INTO conversations (id, title, created_at, updated_at) VALUES ('c1', 't', 1, 1)",
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        let err = fork_conversation(&db, "c1", "nope", None)
+            .await
+            .unwrap_err();
+        assert!(err.contains("not found"));
+    }
+}
@@ -0,0 +1,613 @@
+// Automatic session titles and topic tags
+//
+// Titles and tags are generated either by a provider call (any OpenAI-compatible
+// chat completions endpoint supplied by the frontend) or, when no provider is given
+// or the call fails, by a local keyword heuristic. Results are cached by a hash of the
+// transcript so re-tagging an unchanged session never repeats the provider call.
+
+use crate::database::Database;
+use crate::provider_client::{self, ChatMessage, ProviderConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+
+const MAX_TITLE_CHARS: usize = 60;
+const MAX_TAGS: usize = 5;
+/// Only the start of a session is used for tagging; it carries the intent
+const MAX_TRANSCRIPT_CHARS: usize = 6_000;
+const PROVIDER_TIMEOUT_SECS: u64 = 30;
+
+const SCHEMA: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS conversation_tags (
+        conversation_id TEXT NOT NULL,
+        tag TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        PRIMARY KEY (conversation_id, tag),
+        FOREIGN KEY (conversation_id) REFERENCES conversations (id) ON DELETE CASCADE
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_conversation_tags_tag ON conversation_tags (tag)",
+    "CREATE TABLE IF NOT EXISTS session_title_cache (
+        content_hash TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        tags TEXT NOT NULL,
+        source TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    )",
+];
+
+/// Topic taxonomy used by the local heuristic: tag -> keywords
+const TOPIC_KEYWORDS: &[(&str, &[&str])] = &[
+    (
+        "auth",
+        &[
+            "auth", "login", "oauth", "token", "session", "password", "jwt",
+        ],
+    ),
+    (
+        "testing",
+        &[
+            "test", "tests", "vitest", "jest", "pytest", "assert", "mock",
+        ],
+    ),
+    (
+        "database",
+        &[
+            "database",
+            "sql",
+            "sqlite",
+            "query",
+            "migration",
+            "schema",
+            "table",
+        ],
+    ),
+    (
+        "ui",
+        &[
+            "css",
+            "component",
+            "button",
+            "layout",
+            "style",
+            "react",
+            "tailwind",
+        ],
+    ),
+    (
+        "performance",
+        &[
+            "performance",
+            "slow",
+            "latency",
+            "optimize",
+            "memory",
+            "cache",
+        ],
+    ),
+    (
+        "bug",
+        &[
+            "bug",
+            "error",
+            "crash",
+            "fix",
+            "broken",
+            "exception",
+            "panic",
+        ],
+    ),
+    (
+        "refactor",
+        &["refactor", "cleanup", "rename", "restructure", "simplify"],
+    ),
+    (
+        "build",
+        &[
+            "build",
+            "compile",
+            "cargo",
+            "webpack",
+            "vite",
+            "dependency",
+            "ci",
+        ],
+    ),
+    (
+        "git",
+        &["git", "commit", "branch", "merge", "rebase", "worktree"],
+    ),
+    (
+        "docs",
+        &["docs", "readme", "documentation", "comment", "changelog"],
+    ),
+    (
+        "api",
+        &["api", "endpoint", "request", "http", "rest", "graphql"],
+    ),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTags {
+    pub conversation_id: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub source: String, // "provider", "heuristic" or "cache"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaggedSession {
+    pub conversation_id: String,
+    pub title: String,
+    pub project_id: String,
+    pub updated_at: i64,
+    pub tags: Vec<String>,
+}
+
+async fn ensure_schema(db: &Database) -> Result<(), String> {
+    for sql in SCHEMA {
+        db.execute(sql, vec![]).await?;
+    }
+    Ok(())
+}
+
+fn transcript_hash(transcript: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(transcript.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Truncate to at most `max` characters on a char boundary
+fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((idx, _)) => &text[..idx],
+        None => text,
+    }
+}
+
+/// Derive a title from the first user message: its first sentence, capped in length
+pub fn heuristic_title(first_user_message: &str) -> String {
+    let line = first_user_message
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    let sentence = line
+        .split_terminator(['.', '?', '!'])
+        .next()
+        .unwrap_or(line)
+        .trim();
+
+    if sentence.is_empty() {
+        return "New chat".to_string();
+    }
+    if sentence.chars().count() <= MAX_TITLE_CHARS {
+        return sentence.to_string();
+    }
+
+    let cut = truncate_chars(sentence, MAX_TITLE_CHARS);
+    let cut = cut.rsplit_once(' ').map(|(head, _)| head).unwrap_or(cut);
+    format!("{}…", cut.trim_end())
+}
+
+/// Pick topic tags by counting taxonomy keyword hits, most frequent first
+pub fn heuristic_tags(transcript: &str) -> Vec<String> {
+    let words: Vec<String> = transcript
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    let mut scored: Vec<(usize, &str)> = TOPIC_KEYWORDS
+        .iter()
+        .map(|(tag, keywords)| {
+            let hits = words
+                .iter()
+                .filter(|w| keywords.contains(&w.as_str()))
+                .count();
+            (hits, *tag)
+        })
+        .filter(|(hits, _)| *hits > 0)
+        .collect();
+
+    // Stable, deterministic order: by hits desc, then by tag name
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+    scored
+        .into_iter()
+        .take(MAX_TAGS)
+        .map(|(_, tag)| tag.to_string())
+        .collect()
+}
+
+/// Normalize tags from any source: lowercase, kebab-case, deduplicated, capped
+fn normalize_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    let mut result = Vec::new();
+    for tag in tags {
+        let normalized = tag
+            .trim()
+            .trim_start_matches('#')
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-");
+        if !normalized.is_empty() && seen.insert(normalized.clone()) {
+            result.push(normalized);
+        }
+        if result.len() >= MAX_TAGS {
+            break;
+        }
+    }
+    result
+}
+
+/// Parse the provider's JSON answer, tolerating surrounding prose or code fences
+fn parse_provider_answer(answer: &str) -> Option<(String, Vec<String>)> {
+    let start = answer.find('{')?;
+    let end = answer.rfind('}')?;
+    let value: Value = serde_json::from_str(&answer[start..=end]).ok()?;
+    let title = value.get("title")?.as_str()?.trim().to_string();
+    if title.is_empty() {
+        return None;
+    }
+    let tags = value
+        .get("tags")
+        .and_then(|t| t.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|t| t.as_str().map(String::from))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    Some((
+        truncate_chars(&title, MAX_TITLE_CHARS).to_string(),
+        normalize_tags(tags),
+    ))
+}
+
+async fn provider_tags(
+    provider: &ProviderConfig,
+    transcript: &str,
+) -> Result<(String, Vec<String>), String> {
+    let messages = [
+        ChatMessage::system(
+            "Summarize the coding session. Reply with JSON only: {\"title\": \"<= 8 words\", \"tags\": [\"1-5 short lowercase topic tags\"]}",
+        ),
+        ChatMessage::user(transcript),
+    ];
+    let answer = provider_client::chat_completion(
+        provider,
+        &messages,
+        Duration::from_secs(PROVIDER_TIMEOUT_SECS),
+    )
+    .await?;
+    parse_provider_answer(&answer).ok_or_else(|| "Provider answer is not valid JSON".to_string())
+}
+
+/// Build a compact transcript from the first messages of a conversation
+async fn load_transcript(db: &Database, conversation_id: &str) -> Result<(String, String), String> {
+    let rows = db
+        .query(
+            "SELECT role, content FROM messages WHERE conversation_id = ? ORDER BY position_index ASC, timestamp ASC LIMIT 20",
+            vec![Value::from(conversation_id)],
+        )
+        .await?
+        .rows;
+
+    let mut transcript = String::new();
+    let mut first_user = String::new();
+    for row in &rows {
+        let role = row.get("role").and_then(|v| v.as_str()).unwrap_or("");
+        let content = row.get("content").and_then(|v| v.as_str()).unwrap_or("");
+        if role == "user" && first_user.is_empty() {
+            first_user = content.to_string();
+        }
+        transcript.push_str(&format!("{}: {}\n", role, content));
+        if transcript.len() > MAX_TRANSCRIPT_CHARS {
+            break;
+        }
+    }
+
+    Ok((
+        truncate_chars(&transcript, MAX_TRANSCRIPT_CHARS).to_string(),
+        first_user,
+    ))
+}
+
+async fn store_tags(
+    db: &Database,
+    conversation_id: &str,
+    title: &str,
+    tags: &[String],
+    update_title: bool,
+) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut statements = vec![(
+        "DELETE FROM conversation_tags WHERE conversation_id = ?".to_string(),
+        vec![Value::from(conversation_id)],
+    )];
+    for tag in tags {
+        statements.push((
+            "INSERT OR IGNORE INTO conversation_tags (conversation_id, tag, created_at) VALUES (?, ?, ?)".to_string(),
+            vec![Value::from(conversation_id), Value::from(tag.as_str()), Value::from(now)],
+        ));
+    }
+    if update_title {
+        statements.push((
+            "UPDATE conversations SET title = ? WHERE id = ?".to_string(),
+            vec![Value::from(title), Value::from(conversation_id)],
+        ));
+    }
+    db.batch(statements).await?;
+    Ok(())
+}
+
+/// Generate (or fetch from cache) a title and tags for a session and store them
+pub async fn tag_session(
+    db: &Database,
+    conversation_id: &str,
+    provider: Option<&ProviderConfig>,
+    update_title: bool,
+) -> Result<SessionTags, String> {
+    ensure_schema(db).await?;
+
+    let (transcript, first_user) = load_transcript(db, conversation_id).await?;
+    if transcript.is_empty() {
+        return Err(format!("Conversation has no messages: {}", conversation_id));
+    }
+    let hash = transcript_hash(&transcript);
+
+    let cached = db
+        .query(
+            "SELECT title, tags FROM session_title_cache WHERE content_hash = ?",
+            vec![Value::from(hash.as_str())],
+        )
+        .await?
+        .rows;
+
+    let (title, tags, source) = if let Some(row) = cached.first() {
+        let title = row.get("title").and_then(|v| v.as_str()).unwrap_or("");
+        let tags: Vec<String> = row
+            .get("tags")
+            .and_then(|v| v.as_str())
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default();
+        (title.to_string(), tags, "cache".to_string())
+    } else {
+        let provider_result = match provider {
+            Some(p) => match provider_tags(p, &transcript).await {
+                Ok(result) => Some(result),
+                Err(e) => {
+                    log::warn!(
+                        "Session tagging provider call failed, using heuristic: {}",
+                        e
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+        let (title, tags, source) = match provider_result {
+            Some((title, tags)) => (title, tags, "provider"),
+            None => (
+                heuristic_title(&first_user),
+                heuristic_tags(&transcript),
+                "heuristic",
+            ),
+        };
+
+        db.execute(
+            "INSERT OR REPLACE INTO session_title_cache (content_hash, title, tags, source, created_at) VALUES (?, ?, ?, ?, ?)",
+            vec![
+                Value::from(hash.as_str()),
+                Value::from(title.as_str()),
+                Value::from(serde_json::to_string(&tags).unwrap_or_else(|_| "[]".to_string())),
+                Value::from(source),
+                Value::from(chrono::Utc::now().timestamp_millis()),
+            ],
+        )
+        .await?;
+        (title, tags, source.to_string())
+    };
+
+    store_tags(db, conversation_id, &title, &tags, update_title).await?;
+
+    Ok(SessionTags {
+        conversation_id: conversation_id.to_string(),
+        title,
+        tags,
+        source,
+    })
+}
+
+/// List sessions carrying a tag, most recently updated first
+pub async fn sessions_by_tag(
+    db: &Database,
+    tag: &str,
+    project_id: Option<&str>,
+) -> Result<Vec<TaggedSession>, String> {
+    ensure_schema(db).await?;
+
+    let mut sql = String::from(
+        "SELECT c.id AS id, c.title AS title, c.project_id AS project_id, c.updated_at AS updated_at,
+                (SELECT group_concat(t2.tag, ',') FROM conversation_tags t2 WHERE t2.conversation_id = c.id) AS tags
+         FROM conversations c
+         JOIN conversation_tags t ON t.conversation_id = c.id
+         WHERE t.tag = ?",
+    );
+    let mut params = vec![Value::from(tag)];
+    if let Some(project_id) = project_id {
+        sql.push_str(" AND c.project_id = ?");
+        params.push(Value::from(project_id));
+    }
+    sql.push_str(" ORDER BY c.updated_at DESC");
+
+    let rows = db.query(&sql, params).await?.rows;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let mut tags: Vec<String> = row
+                .get("tags")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .split(',')
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect();
+            tags.sort();
+            TaggedSession {
+                conversation_id: row
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                title: row
+                    .get("title")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                project_id: row
+                    .get("project_id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                updated_at: row.get("updated_at").and_then(|v| v.as_i64()).unwrap_or(0),
+                tags,
+            }
+        })
+        .collect())
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn session_generate_tags(
+    db: State<'_, Arc<Database>>,
+    conversation_id: String,
+    provider: Option<ProviderConfig>,
+    update_title: Option<bool>,
+) -> Result<SessionTags, String> {
+    tag_session(
+        &db,
+        &conversation_id,
+        provider.as_ref(),
+        update_title.unwrap_or(true),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn session_list_by_tag(
+    db: State<'_, Arc<Database>>,
+    tag: String,
+    project_id: Option<String>,
+) -> Result<Vec<TaggedSession>, String> {
+    sessions_by_tag(&db, &tag, project_id.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn session_list_tags(db: State<'_, Arc<Database>>) -> Result<Vec<String>, String> {
+    ensure_schema(&db).await?;
+    let rows = db
+        .query(
+            "SELECT DISTINCT tag FROM conversation_tags ORDER BY tag ASC",
+            vec![],
+        )
+        .await?
+        .rows;
+    Ok(rows
+        .iter()
+        .filter_map(|r| r.get("tag").and_then(|v| v.as_str()).map(String::from))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_heuristic_title() {
+        assert_eq!(
+            heuristic_title("Fix the login redirect. It loops forever."),
+            "Fix the login redirect"
+        );
+        assert_eq!(heuristic_title("\n\n  "), "New chat");
+
+        let long = "Please refactor the entire configuration loading pipeline so that it supports layered overrides";
+        let title = heuristic_title(long);
+        assert!(title.ends_with('…'));
+        assert!(title.chars().count() <= MAX_TITLE_CHARS + 1);
+    }
+
+    #[test]
+    fn test_heuristic_tags() {
+        let tags = heuristic_tags(
+            "user: login fails with an invalid token error\nassistant: the jwt token expired; I added a test",
+        );
+        assert_eq!(tags[0], "auth");
+        assert!(tags.contains(&"bug".to_string()));
+        assert!(tags.contains(&"testing".to_string()));
+        assert!(heuristic_tags("hello there").is_empty());
+    }
+
+    #[test]
+    fn test_parse_provider_answer() {
+        let answer = "```json\n{\"title\": \"Fix OAuth loop\", \"tags\": [\"Auth\", \"#bug\", \"auth\", \"Web UI\"]}\n```";
+        let (title, tags) = parse_provider_answer(answer).unwrap();
+        assert_eq!(title, "Fix OAuth loop");
+        assert_eq!(tags, vec!["auth", "bug", "web-ui"]);
+
+        assert!(parse_provider_answer("no json here").is_none());
+        assert!(parse_provider_answer("{\"title\": \"\"}").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tag_session_stores_and_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(temp_dir.path().join("t.db").to_string_lossy().to_string());
+        db.connect().await.unwrap();
+        db.execute(
+            "CREATE TABLE conversations (id TEXT PRIMARY KEY, title TEXT NOT NULL, project_id TEXT NOT NULL DEFAULT 'default', created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        db.execute(
+            "CREATE TABLE messages (id TEXT PRIMARY KEY, conversation_id TEXT NOT NULL, role TEXT NOT NULL, content TEXT NOT NULL, timestamp INTEGER NOT NULL, position_index INTEGER DEFAULT 0)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        db.execute(
+            "INSERT INTO conversations (id, title, created_at, updated_at) VALUES ('c1', 'New chat', 1, 1)",
+            vec![],
+        )
+        .await
+        .unwrap();
+        db.execute(
+            "INSERT INTO messages (id, conversation_id, role, content, timestamp) VALUES ('m1', 'c1', 'user', 'The sqlite migration crashes on startup', 1)",
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        let result = tag_session(&db, "c1", None, true).await.unwrap();
+        assert_eq!(result.source, "heuristic");
+        assert_eq!(result.title, "The sqlite migration crashes on startup");
+        assert!(result.tags.contains(&"database".to_string()));
+
+        // Second run hits the cache
+        let again = tag_session(&db, "c1", None, true).await.unwrap();
+        assert_eq!(again.source, "cache");
+        assert_eq!(again.tags, result.tags);
+
+        let sessions = sessions_by_tag(&db, "database", None).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].title, "The sqlite migration crashes on startup");
+        assert!(sessions_by_tag(&db, "ui", None).await.unwrap().is_empty());
+    }
+}
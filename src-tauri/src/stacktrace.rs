@@ -0,0 +1,501 @@
+// Stack trace to source mapping
+//
+// Parses pasted stack traces (Node, Python, Rust panics/backtraces, Java), maps every
+// frame onto a workspace file and line, follows JavaScript source maps back to the
+// original sources, and attaches the enclosing symbol so each frame becomes a
+// navigable location.
+
+use crate::code_navigation::CodeNavigationService;
+use crate::next_edit::enclosing_symbol;
+use crate::walker::{WalkerConfig, WorkspaceWalker};
+use base64::Engine;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParsedFrame {
+    pub format: String, // "node", "python", "rust" or "java"
+    pub function: Option<String>,
+    pub file: String,
+    pub line: u32,
+    pub column: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedFrame {
+    #[serde(flatten)]
+    pub frame: ParsedFrame,
+    pub resolved_path: Option<String>,
+    pub resolved_line: Option<u32>,
+    pub resolved_column: Option<u32>,
+    pub source_mapped: bool,
+    pub symbol: Option<String>,
+    pub symbol_start_line: Option<u32>,
+    pub symbol_end_line: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceMap {
+    #[serde(default)]
+    source_root: Option<String>,
+    sources: Vec<String>,
+    mappings: String,
+}
+
+fn java_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*at\s+([\w$.<>]+)\(([^:()]+\.\w+):(\d+)\)").unwrap())
+}
+
+fn node_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*at\s+(?:(.+?)\s+\()?(.+?):(\d+):(\d+)\)?\s*$").unwrap())
+}
+
+fn python_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"^\s*File "(.+)", line (\d+)(?:, in (.+))?"#).unwrap())
+}
+
+fn rust_panic_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"panicked at (?:'.*', )?([^\s:]+):(\d+):(\d+)").unwrap())
+}
+
+fn rust_backtrace_fn_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*\d+:\s+(\S+)\s*$").unwrap())
+}
+
+/// Parse all recognizable frames in `text`, in order
+pub fn parse_stacktrace(text: &str) -> Vec<ParsedFrame> {
+    let mut frames = Vec::new();
+    // Rust backtraces put the function on the line before its location
+    let mut pending_rust_fn: Option<String> = None;
+
+    for line in text.lines() {
+        if let Some(caps) = rust_backtrace_fn_regex().captures(line) {
+            pending_rust_fn = Some(caps[1].to_string());
+            continue;
+        }
+        if let Some(caps) = java_regex().captures(line) {
+            frames.push(ParsedFrame {
+                format: "java".to_string(),
+                function: Some(caps[1].to_string()),
+                file: caps[2].to_string(),
+                line: caps[3].parse().unwrap_or(0),
+                column: None,
+            });
+        } else if let Some(caps) = node_regex().captures(line) {
+            let file = caps[2].to_string();
+            if file.starts_with("node:") || file == "<anonymous>" {
+                continue;
+            }
+            let function = caps.get(1).map(|m| m.as_str().to_string());
+            let (format, function) = match (function, pending_rust_fn.take()) {
+                (None, Some(rust_fn)) => ("rust", Some(rust_fn)),
+                (function, _) => ("node", function),
+            };
+            frames.push(ParsedFrame {
+                format: format.to_string(),
+                function,
+                file,
+                line: caps[3].parse().unwrap_or(0),
+                column: caps[4].parse().ok(),
+            });
+        } else if let Some(caps) = python_regex().captures(line) {
+            frames.push(ParsedFrame {
+                format: "python".to_string(),
+                function: caps.get(3).map(|m| m.as_str().trim().to_string()),
+                file: caps[1].to_string(),
+                line: caps[2].parse().unwrap_or(0),
+                column: None,
+            });
+        } else if let Some(caps) = rust_panic_regex().captures(line) {
+            frames.push(ParsedFrame {
+                format: "rust".to_string(),
+                function: None,
+                file: caps[1].to_string(),
+                line: caps[2].parse().unwrap_or(0),
+                column: caps[3].parse().ok(),
+            });
+        }
+    }
+    frames
+}
+
+/// Decode one base64 VLQ source map segment
+pub fn decode_vlq(segment: &str) -> Option<Vec<i64>> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut values = Vec::new();
+    let mut value: i64 = 0;
+    let mut shift = 0;
+    for byte in segment.bytes() {
+        let digit = ALPHABET.iter().position(|&c| c == byte)? as i64;
+        value += (digit & 31) << shift;
+        if digit & 32 != 0 {
+            shift += 5;
+            continue;
+        }
+        values.push(if value & 1 == 1 {
+            -(value >> 1)
+        } else {
+            value >> 1
+        });
+        value = 0;
+        shift = 0;
+    }
+    (shift == 0).then_some(values)
+}
+
+/// Map a 0-based generated position to (source index, original line, original column)
+pub fn lookup_mapping(mappings: &str, line: usize, column: usize) -> Option<(usize, u32, u32)> {
+    // Source, line and column deltas carry across lines; the generated column resets
+    let (mut source, mut orig_line, mut orig_col) = (0i64, 0i64, 0i64);
+    for (idx, line_mappings) in mappings.split(';').enumerate() {
+        let mut gen_col = 0i64;
+        let mut best = None;
+        for segment in line_mappings.split(',').filter(|s| !s.is_empty()) {
+            let values = decode_vlq(segment)?;
+            gen_col += values[0];
+            if values.len() < 4 {
+                continue;
+            }
+            source += values[1];
+            orig_line += values[2];
+            orig_col += values[3];
+            if idx == line && (gen_col <= column as i64 || best.is_none()) {
+                best = Some((source as usize, orig_line as u32, orig_col as u32));
+            }
+        }
+        if idx == line {
+            return best;
+        }
+    }
+    None
+}
+
+fn load_source_map(js_path: &Path) -> Option<(SourceMap, PathBuf)> {
+    let content = fs::read_to_string(js_path).ok()?;
+    let reference = content
+        .lines()
+        .rev()
+        .find_map(|l| l.trim().strip_prefix("//# sourceMappingURL="))
+        .map(|s| s.trim().to_string());
+
+    let (raw, map_dir) = match reference {
+        Some(url) if url.starts_with("data:") => {
+            let encoded = url.split(',').nth(1)?;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .ok()?;
+            (
+                String::from_utf8(bytes).ok()?,
+                js_path.parent()?.to_path_buf(),
+            )
+        }
+        Some(url) => {
+            let map_path = js_path.parent()?.join(url);
+            (
+                fs::read_to_string(&map_path).ok()?,
+                map_path.parent()?.to_path_buf(),
+            )
+        }
+        None => {
+            let map_path = PathBuf::from(format!("{}.map", js_path.display()));
+            (
+                fs::read_to_string(&map_path).ok()?,
+                js_path.parent()?.to_path_buf(),
+            )
+        }
+    };
+    let map = serde_json::from_str(&raw).ok()?;
+    Some((map, map_dir))
+}
+
+/// Workspace file lookup by file name, built once per resolution
+struct FileIndex {
+    by_name: HashMap<String, Vec<PathBuf>>,
+}
+
+impl FileIndex {
+    fn build(root: &str) -> Self {
+        let mut by_name: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for entry in WorkspaceWalker::new(root, WalkerConfig::for_list_files())
+            .build()
+            .flatten()
+        {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            if let Some(name) = path.file_name() {
+                by_name
+                    .entry(name.to_string_lossy().to_string())
+                    .or_default()
+                    .push(path.to_path_buf());
+            }
+        }
+        Self { by_name }
+    }
+
+    /// Candidate with the longest matching path suffix
+    fn best_match(&self, file: &str) -> Option<PathBuf> {
+        let wanted: Vec<&str> = file.split(['/', '\\']).filter(|s| !s.is_empty()).collect();
+        let candidates = self.by_name.get(*wanted.last()?)?;
+        candidates
+            .iter()
+            .max_by_key(|candidate| {
+                let parts: Vec<String> = candidate
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect();
+                parts
+                    .iter()
+                    .rev()
+                    .zip(wanted.iter().rev())
+                    .take_while(|(a, b)| a == *b)
+                    .count()
+            })
+            .cloned()
+    }
+}
+
+fn clean_file_reference(file: &str) -> String {
+    let mut file = file.trim();
+    for prefix in ["file://", "webpack:///", "webpack://"] {
+        if let Some(rest) = file.strip_prefix(prefix) {
+            file = rest;
+        }
+    }
+    file.trim_start_matches("./").to_string()
+}
+
+fn resolve_file(root: &Path, frame: &ParsedFrame, index: &FileIndex) -> Option<PathBuf> {
+    let file = clean_file_reference(&frame.file);
+    let path = Path::new(&file);
+    if path.is_absolute() && path.is_file() {
+        return Some(path.to_path_buf());
+    }
+    if root.join(path).is_file() {
+        return Some(root.join(path));
+    }
+    // Java frames only carry the file name; the package gives the directories
+    if frame.format == "java" {
+        if let Some(function) = &frame.function {
+            let mut package: Vec<&str> = function.split('.').collect();
+            package.truncate(package.len().saturating_sub(2));
+            if !package.is_empty() {
+                return index.best_match(&format!("{}/{}", package.join("/"), file));
+            }
+        }
+    }
+    index.best_match(&file)
+}
+
+fn resolve_frame(root: &Path, frame: ParsedFrame, index: &FileIndex) -> ResolvedFrame {
+    let mut resolved = ResolvedFrame {
+        resolved_path: None,
+        resolved_line: None,
+        resolved_column: None,
+        source_mapped: false,
+        symbol: None,
+        symbol_start_line: None,
+        symbol_end_line: None,
+        frame,
+    };
+    let Some(mut path) = resolve_file(root, &resolved.frame, index) else {
+        return resolved;
+    };
+    let mut line = resolved.frame.line;
+    let mut column = resolved.frame.column;
+
+    let is_js = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("js" | "mjs" | "cjs")
+    );
+    if is_js {
+        if let Some((map, map_dir)) = load_source_map(&path) {
+            let generated_col = column.unwrap_or(1).saturating_sub(1) as usize;
+            if let Some((source_idx, orig_line, orig_col)) = lookup_mapping(
+                &map.mappings,
+                line.saturating_sub(1) as usize,
+                generated_col,
+            ) {
+                if let Some(source) = map.sources.get(source_idx) {
+                    let source = match &map.source_root {
+                        Some(root) if !root.is_empty() => format!("{}/{}", root, source),
+                        _ => source.clone(),
+                    };
+                    let cleaned = clean_file_reference(&source);
+                    let candidate = map_dir.join(&cleaned);
+                    let original = if candidate.is_file() {
+                        Some(candidate)
+                    } else {
+                        index.best_match(&cleaned)
+                    };
+                    if let Some(original) = original {
+                        path = original;
+                        line = orig_line + 1;
+                        column = Some(orig_col + 1);
+                        resolved.source_mapped = true;
+                    }
+                }
+            }
+        }
+    }
+
+    let path_str = path.to_string_lossy().to_string();
+    if let (Some(lang_id), Ok(content)) = (
+        CodeNavigationService::get_lang_id_from_path(&path_str),
+        fs::read_to_string(&path),
+    ) {
+        if let Some((name, start, end)) = enclosing_symbol(&content, &lang_id, line) {
+            resolved.symbol = Some(name);
+            resolved.symbol_start_line = Some(start);
+            resolved.symbol_end_line = Some(end);
+        }
+    }
+    resolved.resolved_path = Some(path_str);
+    resolved.resolved_line = Some(line);
+    resolved.resolved_column = column;
+    resolved
+}
+
+#[tauri::command]
+pub async fn resolve_stacktrace(
+    root_path: String,
+    text: String,
+) -> Result<Vec<ResolvedFrame>, String> {
+    let frames = parse_stacktrace(&text);
+    if frames.is_empty() {
+        return Ok(Vec::new());
+    }
+    let root = PathBuf::from(&root_path);
+    let resolved = tokio::task::spawn_blocking(move || {
+        let index = FileIndex::build(&root_path);
+        frames
+            .into_iter()
+            .map(|frame| resolve_frame(&root, frame, &index))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Stack trace resolution failed: {}", e))?;
+
+    log::info!(
+        "Resolved {} of {} stack frames",
+        resolved
+            .iter()
+            .filter(|f| f.resolved_path.is_some())
+            .count(),
+        resolved.len()
+    );
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_node_and_python() {
+        let trace = "Error: boom\n    at run (/app/dist/app.js:2:7)\n    at /app/index.js:10:3\n    at node:internal/main:5:1\n\
+                     Traceback (most recent call last):\n  File \"/app/main.py\", line 12, in handler\n    raise ValueError()\n";
+        let frames = parse_stacktrace(trace);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].function.as_deref(), Some("run"));
+        assert_eq!((frames[0].line, frames[0].column), (2, Some(7)));
+        assert_eq!(frames[1].function, None);
+        assert_eq!(frames[2].format, "python");
+        assert_eq!(frames[2].function.as_deref(), Some("handler"));
+        assert_eq!(frames[2].line, 12);
+    }
+
+    #[test]
+    fn test_parse_rust_and_java() {
+        let trace = "thread 'main' panicked at src/main.rs:10:5:\nboom\nstack backtrace:\n   3: app::parse\n             at ./src/parse.rs:42:9\n\
+                     Exception in thread \"main\" java.lang.IllegalStateException\n\tat com.acme.Widget.render(Widget.java:27)\n";
+        let frames = parse_stacktrace(trace);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].format, "rust");
+        assert_eq!(frames[0].file, "src/main.rs");
+        assert_eq!(frames[1].format, "rust");
+        assert_eq!(frames[1].function.as_deref(), Some("app::parse"));
+        assert_eq!(frames[1].line, 42);
+        assert_eq!(frames[2].format, "java");
+        assert_eq!(frames[2].file, "Widget.java");
+        assert_eq!(frames[2].line, 27);
+    }
+
+    #[test]
+    fn test_decode_vlq() {
+        assert_eq!(decode_vlq("AAAA"), Some(vec![0, 0, 0, 0]));
+        assert_eq!(decode_vlq("CADI"), Some(vec![1, 0, -1, 4]));
+        assert_eq!(decode_vlq("gB"), Some(vec![16]));
+        assert_eq!(decode_vlq("g"), None);
+    }
+
+    #[test]
+    fn test_lookup_mapping() {
+        let mappings = "AAAA;AACA,IAAI";
+        assert_eq!(lookup_mapping(mappings, 0, 0), Some((0, 0, 0)));
+        assert_eq!(lookup_mapping(mappings, 1, 2), Some((0, 1, 0)));
+        assert_eq!(lookup_mapping(mappings, 1, 6), Some((0, 1, 4)));
+        assert_eq!(lookup_mapping(mappings, 5, 0), None);
+    }
+
+    #[test]
+    fn test_resolve_frame_through_source_map() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("dist")).unwrap();
+        fs::write(
+            root.join("src/app.ts"),
+            "export function run() {\n  throw new Error('x');\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("dist/app.js"), "compiled\ncompiled\n").unwrap();
+        fs::write(
+            root.join("dist/app.js.map"),
+            r#"{"version":3,"sources":["../src/app.ts"],"mappings":"AAAA;AACA,IAAI"}"#,
+        )
+        .unwrap();
+
+        let trace = format!("    at run ({}:2:7)", root.join("dist/app.js").display());
+        let frame = parse_stacktrace(&trace).remove(0);
+        let index = FileIndex::build(&root.to_string_lossy());
+        let resolved = resolve_frame(root, frame, &index);
+
+        assert!(resolved.source_mapped);
+        assert!(resolved.resolved_path.unwrap().ends_with("app.ts"));
+        assert_eq!(resolved.resolved_line, Some(2));
+        assert_eq!(resolved.resolved_column, Some(5));
+        assert_eq!(resolved.symbol.as_deref(), Some("run"));
+    }
+
+    #[test]
+    fn test_resolve_java_frame_by_package() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let dir = root.join("src/main/java/com/acme");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Widget.java"), "class Widget {}\n").unwrap();
+        fs::create_dir_all(root.join("other")).unwrap();
+        fs::write(root.join("other/Widget.java"), "class Widget {}\n").unwrap();
+
+        let frame = parse_stacktrace("\tat com.acme.Widget.render(Widget.java:1)").remove(0);
+        let index = FileIndex::build(&root.to_string_lossy());
+        let resolved = resolve_frame(root, frame, &index);
+        assert_eq!(
+            resolved.resolved_path.map(PathBuf::from),
+            Some(dir.join("Widget.java"))
+        );
+    }
+}
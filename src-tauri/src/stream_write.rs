@@ -0,0 +1,346 @@
+// Streamed writes for large generated files
+//
+// When the model generates a large file, the frontend forwards chunks as they arrive
+// instead of holding the whole text until the tool call completes. Chunks are appended
+// to a temp file next to the target, with a progress event per chunk, and the file is
+// only moved into place once the stream finishes and the content parses. Streams live in
+// the backend, so a reloaded webview can ask where a stream stopped and resend from
+// there; chunks carry a sequence number, which makes resends idempotent.
+
+use crate::code_navigation::CodeNavigationService;
+use crate::path_policy;
+use crate::syntax_check::{self, SyntaxCheckResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+
+struct StreamWrite {
+    path: PathBuf,
+    temp_path: PathBuf,
+    file: File,
+    bytes_written: u64,
+    next_seq: u64,
+    expected_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamWriteProgress {
+    pub stream_id: String,
+    pub path: String,
+    pub bytes_written: u64,
+    /// Sequence number the stream expects next; resend from here after a reload
+    pub next_seq: u64,
+    pub expected_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamWriteResult {
+    pub path: String,
+    pub bytes_written: u64,
+    /// False when the content failed validation and was left in the temp file
+    pub written: bool,
+    pub syntax: SyntaxCheckResult,
+}
+
+/// Open streams by id
+#[derive(Default)]
+pub struct StreamWrites {
+    streams: HashMap<String, StreamWrite>,
+}
+
+/// Tauri state for the open streams
+#[derive(Default)]
+pub struct StreamWriteState(pub Mutex<StreamWrites>);
+
+/// Temp file beside the target, so finishing is a rename on the same file system
+fn temp_path_for(path: &Path, stream_id: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.partial", name, stream_id))
+}
+
+fn progress(stream_id: &str, stream: &StreamWrite) -> StreamWriteProgress {
+    StreamWriteProgress {
+        stream_id: stream_id.to_string(),
+        path: stream.path.to_string_lossy().to_string(),
+        bytes_written: stream.bytes_written,
+        next_seq: stream.next_seq,
+        expected_bytes: stream.expected_bytes,
+    }
+}
+
+impl StreamWrites {
+    pub fn begin(
+        &mut self,
+        stream_id: &str,
+        path: &Path,
+        expected_bytes: Option<u64>,
+    ) -> Result<StreamWriteProgress, String> {
+        if self.streams.contains_key(stream_id) {
+            return Err(format!("Stream {} is already open", stream_id));
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let temp_path = temp_path_for(path, stream_id);
+        let file = File::create(&temp_path)
+            .map_err(|e| format!("Failed to create {}: {}", temp_path.display(), e))?;
+        let stream = StreamWrite {
+            path: path.to_path_buf(),
+            temp_path,
+            file,
+            bytes_written: 0,
+            next_seq: 0,
+            expected_bytes,
+        };
+        let started = progress(stream_id, &stream);
+        self.streams.insert(stream_id.to_string(), stream);
+        Ok(started)
+    }
+
+    /// Append chunk `seq`; a chunk that was already written is ignored, a gap is an error
+    pub fn append(
+        &mut self,
+        stream_id: &str,
+        seq: u64,
+        chunk: &str,
+    ) -> Result<StreamWriteProgress, String> {
+        let stream = self
+            .streams
+            .get_mut(stream_id)
+            .ok_or_else(|| format!("No open stream {}", stream_id))?;
+        if seq > stream.next_seq {
+            return Err(format!(
+                "Stream {} expected chunk {}, got {}",
+                stream_id, stream.next_seq, seq
+            ));
+        }
+        if seq == stream.next_seq {
+            stream
+                .file
+                .write_all(chunk.as_bytes())
+                .map_err(|e| format!("Failed to write {}: {}", stream.temp_path.display(), e))?;
+            stream.bytes_written += chunk.len() as u64;
+            stream.next_seq += 1;
+        }
+        Ok(progress(stream_id, stream))
+    }
+
+    pub fn status(&self, stream_id: &str) -> Option<StreamWriteProgress> {
+        self.streams
+            .get(stream_id)
+            .map(|stream| progress(stream_id, stream))
+    }
+
+    /// Validate the streamed content and move it into place. Content that fails to parse
+    /// stays in the temp file with the stream open, unless `allow_invalid` is set.
+    pub fn finish(
+        &mut self,
+        stream_id: &str,
+        allow_invalid: bool,
+    ) -> Result<StreamWriteResult, String> {
+        let stream = self
+            .streams
+            .get_mut(stream_id)
+            .ok_or_else(|| format!("No open stream {}", stream_id))?;
+        stream
+            .file
+            .flush()
+            .map_err(|e| format!("Failed to flush {}: {}", stream.temp_path.display(), e))?;
+        let content = fs::read_to_string(&stream.temp_path)
+            .map_err(|e| format!("Failed to read {}: {}", stream.temp_path.display(), e))?;
+
+        let path = stream.path.to_string_lossy().to_string();
+        let syntax = match CodeNavigationService::get_lang_id_from_path(&path) {
+            Some(lang_id) => syntax_check::check_syntax(&content, &lang_id)?,
+            None => SyntaxCheckResult {
+                supported: false,
+                valid: true,
+                issues: Vec::new(),
+            },
+        };
+        let bytes_written = stream.bytes_written;
+        if !syntax.valid && !allow_invalid {
+            log::warn!(
+                "Streamed write to {} has {} syntax issue(s); keeping it in {}",
+                path,
+                syntax.issues.len(),
+                stream.temp_path.display()
+            );
+            return Ok(StreamWriteResult {
+                path,
+                bytes_written,
+                written: false,
+                syntax,
+            });
+        }
+
+        if let Some(stream) = self.streams.remove(stream_id) {
+            drop(stream.file);
+            fs::rename(&stream.temp_path, &stream.path)
+                .map_err(|e| format!("Failed to move {} into place: {}", path, e))?;
+        }
+        log::info!("Streamed {} bytes to {}", bytes_written, path);
+        Ok(StreamWriteResult {
+            path,
+            bytes_written,
+            written: true,
+            syntax,
+        })
+    }
+
+    /// Drop the stream and its temp file; the target is left untouched
+    pub fn abort(&mut self, stream_id: &str) -> bool {
+        let Some(stream) = self.streams.remove(stream_id) else {
+            return false;
+        };
+        drop(stream.file);
+        if let Err(e) = fs::remove_file(&stream.temp_path) {
+            log::warn!("Failed to remove {}: {}", stream.temp_path.display(), e);
+        }
+        true
+    }
+}
+
+fn emit_progress(app: &AppHandle, progress: &StreamWriteProgress) {
+    if let Err(e) = app.emit("stream-write-progress", progress) {
+        log::error!("Failed to emit stream-write-progress event: {}", e);
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn stream_write_begin(
+    app: AppHandle,
+    state: State<'_, StreamWriteState>,
+    stream_id: String,
+    path: String,
+    expected_bytes: Option<u64>,
+    root_path: Option<String>,
+) -> Result<StreamWriteProgress, String> {
+    path_policy::check_optional(&path, root_path.as_deref())?;
+    let mut streams = state.0.lock().map_err(|e| e.to_string())?;
+    let started = streams.begin(&stream_id, Path::new(&path), expected_bytes)?;
+    emit_progress(&app, &started);
+    Ok(started)
+}
+
+#[tauri::command]
+pub fn stream_write_chunk(
+    app: AppHandle,
+    state: State<'_, StreamWriteState>,
+    stream_id: String,
+    seq: u64,
+    chunk: String,
+) -> Result<StreamWriteProgress, String> {
+    let mut streams = state.0.lock().map_err(|e| e.to_string())?;
+    let progress = streams.append(&stream_id, seq, &chunk)?;
+    emit_progress(&app, &progress);
+    Ok(progress)
+}
+
+/// Where an open stream stopped, or None when it was finished or never started
+#[tauri::command]
+pub fn stream_write_status(
+    state: State<'_, StreamWriteState>,
+    stream_id: String,
+) -> Result<Option<StreamWriteProgress>, String> {
+    let streams = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(streams.status(&stream_id))
+}
+
+#[tauri::command]
+pub fn stream_write_finish(
+    state: State<'_, StreamWriteState>,
+    stream_id: String,
+    allow_invalid: Option<bool>,
+) -> Result<StreamWriteResult, String> {
+    let mut streams = state.0.lock().map_err(|e| e.to_string())?;
+    streams.finish(&stream_id, allow_invalid.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn stream_write_abort(
+    state: State<'_, StreamWriteState>,
+    stream_id: String,
+) -> Result<bool, String> {
+    let mut streams = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(streams.abort(&stream_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stream_chunks_into_place() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("src").join("lib.rs");
+        let mut streams = StreamWrites::default();
+
+        streams.begin("s1", &path, Some(30)).unwrap();
+        streams.append("s1", 0, "pub fn add(a: i32, ").unwrap();
+        // A resent chunk is not written twice
+        streams.append("s1", 0, "pub fn add(a: i32, ").unwrap();
+        let progress = streams
+            .append("s1", 1, "b: i32) -> i32 { a + b }\n")
+            .unwrap();
+        assert_eq!(progress.next_seq, 2);
+        assert_eq!(progress.bytes_written, 44);
+        assert!(streams.append("s1", 5, "x").is_err());
+        assert!(!path.exists());
+
+        let result = streams.finish("s1", false).unwrap();
+        assert!(result.written);
+        assert!(result.syntax.valid);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "pub fn add(a: i32, b: i32) -> i32 { a + b }\n"
+        );
+        assert!(!temp_path_for(&path, "s1").exists());
+        assert!(streams.status("s1").is_none());
+    }
+
+    #[test]
+    fn test_invalid_content_is_held_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("main.rs");
+        fs::write(&path, "fn main() {}\n").unwrap();
+        let mut streams = StreamWrites::default();
+
+        streams.begin("s2", &path, None).unwrap();
+        streams.append("s2", 0, "fn main() {\n").unwrap();
+        let result = streams.finish("s2", false).unwrap();
+        assert!(!result.written);
+        assert!(!result.syntax.valid);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fn main() {}\n");
+        // The stream stays open so the rest can still arrive
+        streams.append("s2", 1, "}\n").unwrap();
+        assert!(streams.finish("s2", false).unwrap().written);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fn main() {\n}\n");
+    }
+
+    #[test]
+    fn test_abort_removes_temp_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        let mut streams = StreamWrites::default();
+
+        streams.begin("s3", &path, None).unwrap();
+        assert!(streams.begin("s3", &path, None).is_err());
+        streams.append("s3", 0, "draft").unwrap();
+        assert!(streams.abort("s3"));
+        assert!(!streams.abort("s3"));
+        assert!(!path.exists());
+        assert!(!temp_path_for(&path, "s3").exists());
+    }
+}
@@ -0,0 +1,440 @@
+// String literal index
+//
+// Records every string literal in indexed files together with the definition that
+// contains it, so an error message copied from a log ("User not found") can be traced
+// straight to the code that produces it. Literals with format placeholders ("User {}
+// not found", "%s failed", `${id} missing`) also match the rendered message.
+
+use crate::code_navigation::{get_language, CodeNavigationService};
+use crate::inline_edit::DEFINITION_KINDS;
+use crate::position_encoding::{FileContents, Position, PositionEncoding};
+use crate::walker::{WalkerConfig, WorkspaceWalker};
+use crate::workspace_state::{Scoped, WorkspaceState};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{OnceLock, RwLock};
+use std::time::Instant;
+use tauri::State;
+use tree_sitter::{Node, Parser};
+
+const STRING_KINDS: &[&str] = &[
+    "string_literal",
+    "raw_string_literal",
+    "interpreted_string_literal",
+    "string",
+    "template_string",
+];
+const MIN_LITERAL_CHARS: usize = 2;
+const MAX_LITERAL_CHARS: usize = 500;
+const DEFAULT_RESULT_LIMIT: usize = 20;
+/// Larger files are usually generated or minified
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone)]
+struct IndexedLiteral {
+    text: String,
+    lowercase: String,
+    line: u32,
+    column: u32,
+    symbol: Option<String>,
+    /// Matches rendered messages when the literal has format placeholders
+    template: Option<Regex>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StringMatch {
+    pub text: String,
+    pub file_path: String,
+    pub line: u32,
+    pub column: u32,
+    pub symbol: Option<String>,
+    pub match_kind: String, // "exact", "contains", "template" or "regex"
+}
+
+#[derive(Default)]
+pub struct StringLiteralIndex {
+    files: HashMap<String, Vec<IndexedLiteral>>,
+}
+
+/// Tauri state for the string literal index
+#[derive(Default)]
+pub struct StringIndexState(pub RwLock<StringLiteralIndex>);
+
+fn placeholder_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\{[A-Za-z0-9_:.?#]*\}|%[-+0#]*\d*(?:\.\d+)?[sdifvqxXeEgGp]|\$\{[^}]*\}")
+            .unwrap()
+    })
+}
+
+/// Regex matching rendered messages of a literal with placeholders
+pub fn template_pattern(literal: &str) -> Option<Regex> {
+    let placeholders = placeholder_regex();
+    // Require some fixed text so "{}" or "%s" alone don't match everything
+    if !placeholders.is_match(literal) || placeholders.replace_all(literal, "").trim().len() < 3 {
+        return None;
+    }
+    let mut pattern = String::from("^");
+    let mut last = 0;
+    for m in placeholders.find_iter(literal) {
+        pattern.push_str(&regex::escape(&literal[last..m.start()]));
+        pattern.push_str(".+?");
+        last = m.end();
+    }
+    pattern.push_str(&regex::escape(&literal[last..]));
+    pattern.push('$');
+    RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+/// Literal contents without prefixes (r, b, f, @) and quotes
+pub fn strip_quotes(raw: &str) -> &str {
+    let Some(start) = raw.find(['"', '\'', '`']) else {
+        return raw;
+    };
+    let quote = raw.as_bytes()[start];
+    let run = raw[start..]
+        .bytes()
+        .take(3)
+        .take_while(|&b| b == quote)
+        .count();
+    // A run of two is an empty string, not an opening triple quote
+    let run = if run == 3 { 3 } else { 1 };
+    let body = &raw[start + run..];
+    let body = body.trim_end_matches('#');
+    let end = body.len().saturating_sub(run);
+    body.get(..end).unwrap_or("")
+}
+
+fn definition_name(node: Node, source: &[u8]) -> Option<String> {
+    let mut current = node.parent();
+    while let Some(candidate) = current {
+        if DEFINITION_KINDS.contains(&candidate.kind()) {
+            // Arrow functions take the name of their declarator
+            let mut named = Some(candidate);
+            while let Some(n) = named {
+                if let Some(name) = n.child_by_field_name("name") {
+                    return name.utf8_text(source).ok().map(|s| s.to_string());
+                }
+                named = n.parent();
+            }
+            return None;
+        }
+        current = candidate.parent();
+    }
+    None
+}
+
+fn collect_literals(node: Node, source: &[u8], out: &mut Vec<IndexedLiteral>) {
+    if STRING_KINDS.contains(&node.kind()) {
+        if let Ok(raw) = node.utf8_text(source) {
+            let text = strip_quotes(raw);
+            let length = text.chars().count();
+            if (MIN_LITERAL_CHARS..=MAX_LITERAL_CHARS).contains(&length) {
+                out.push(IndexedLiteral {
+                    text: text.to_string(),
+                    lowercase: text.to_lowercase(),
+                    line: node.start_position().row as u32 + 1,
+                    column: node.start_position().column as u32 + 1,
+                    symbol: definition_name(node, source),
+                    template: template_pattern(text),
+                });
+            }
+        }
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_literals(child, source, out);
+    }
+}
+
+impl StringLiteralIndex {
+    pub fn index_file(&mut self, file_path: &str, content: &str, lang_id: &str) {
+        let Some(language) = get_language(lang_id) else {
+            return;
+        };
+        let mut parser = Parser::new();
+        if parser.set_language(&language).is_err() {
+            return;
+        }
+        let Some(tree) = parser.parse(content, None) else {
+            return;
+        };
+        let mut literals = Vec::new();
+        collect_literals(tree.root_node(), content.as_bytes(), &mut literals);
+        self.files.insert(file_path.to_string(), literals);
+    }
+
+    pub fn clear_file(&mut self, file_path: &str) {
+        self.files.remove(file_path);
+    }
+
+    pub fn literal_count(&self) -> usize {
+        self.files.values().map(|l| l.len()).sum()
+    }
+
+    /// Find literals producing `query`. With `regex`, the query is a pattern
+    /// matched against literal contents instead.
+    pub fn find(&self, query: &str, regex: bool, limit: usize) -> Result<Vec<StringMatch>, String> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let pattern = if regex {
+            Some(
+                RegexBuilder::new(query)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| format!("Invalid regex: {}", e))?,
+            )
+        } else {
+            None
+        };
+        let lowercase = query.to_lowercase();
+
+        let mut matches: Vec<(u8, StringMatch)> = Vec::new();
+        for (file_path, literals) in &self.files {
+            for literal in literals {
+                let ranked = match &pattern {
+                    Some(pattern) => pattern.is_match(&literal.text).then_some((0, "regex")),
+                    None if literal.lowercase == lowercase => Some((0, "exact")),
+                    None if literal.template.as_ref().is_some_and(|t| t.is_match(query)) => {
+                        Some((1, "template"))
+                    }
+                    None if literal.lowercase.contains(&lowercase)
+                        || (literal.lowercase.len() >= 8
+                            && lowercase.contains(&literal.lowercase)) =>
+                    {
+                        Some((2, "contains"))
+                    }
+                    None => None,
+                };
+                if let Some((rank, kind)) = ranked {
+                    matches.push((
+                        rank,
+                        StringMatch {
+                            text: literal.text.clone(),
+                            file_path: file_path.clone(),
+                            line: literal.line,
+                            column: literal.column,
+                            symbol: literal.symbol.clone(),
+                            match_kind: kind.to_string(),
+                        },
+                    ));
+                }
+            }
+        }
+
+        matches.sort_by(|(ra, a), (rb, b)| {
+            ra.cmp(rb)
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then_with(|| a.line.cmp(&b.line))
+        });
+        Ok(matches.into_iter().take(limit).map(|(_, m)| m).collect())
+    }
+}
+
+/// The workspace's string index when a workspace handle is given, otherwise the shared one
+fn resolve_index<'a>(
+    state: &'a StringIndexState,
+    workspaces: &WorkspaceState,
+    workspace_id: Option<&str>,
+) -> Result<Scoped<'a, StringLiteralIndex>, String> {
+    match workspace_id {
+        Some(id) => Ok(Scoped::Workspace(workspaces.string_index(id)?)),
+        None => Ok(Scoped::Shared(&state.0)),
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn string_index_file(
+    state: State<'_, StringIndexState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    file_path: String,
+    content: String,
+    lang_id: String,
+) -> Result<(), String> {
+    let scoped = resolve_index(&state, &workspaces, workspace_id.as_deref())?;
+    let mut index = scoped
+        .write()
+        .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+    index.index_file(&file_path, &content, &lang_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn string_index_clear_file(
+    state: State<'_, StringIndexState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    file_path: String,
+) -> Result<(), String> {
+    let scoped = resolve_index(&state, &workspaces, workspace_id.as_deref())?;
+    let mut index = scoped
+        .write()
+        .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
+    index.clear_file(&file_path);
+    Ok(())
+}
+
+/// Index every supported file under `root_path`; returns the number of literals
+#[tauri::command]
+pub async fn string_index_build(
+    state: State<'_, StringIndexState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    root_path: String,
+) -> Result<usize, String> {
+    let scoped = resolve_index(&state, &workspaces, workspace_id.as_deref())?;
+    let start = Instant::now();
+    let built = tokio::task::spawn_blocking(move || {
+        let mut index = StringLiteralIndex::default();
+        for entry in WorkspaceWalker::new(&root_path, WalkerConfig::for_list_files())
+            .build()
+            .flatten()
+        {
+            let path = entry.path();
+            if !path.is_file() || path.metadata().map(|m| m.len()).unwrap_or(0) > MAX_FILE_BYTES {
+                continue;
+            }
+            let path_str = path.to_string_lossy().to_string();
+            let Some(lang_id) = CodeNavigationService::get_lang_id_from_path(&path_str) else {
+                continue;
+            };
+            if let Ok(content) = fs::read_to_string(path) {
+                index.index_file(&path_str, &content, &lang_id);
+            }
+        }
+        index
+    })
+    .await
+    .map_err(|e| format!("String index build failed: {}", e))?;
+
+    let count = built.literal_count();
+    log::info!(
+        "Indexed {} string literals in {} files in {:?}",
+        count,
+        built.files.len(),
+        start.elapsed()
+    );
+    *scoped
+        .write()
+        .map_err(|e| format!("Failed to acquire write lock: {}", e))? = built;
+    Ok(count)
+}
+
+#[tauri::command]
+pub async fn find_string_origin(
+    state: State<'_, StringIndexState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    query: String,
+    regex: Option<bool>,
+    limit: Option<usize>,
+    encoding: Option<PositionEncoding>,
+) -> Result<Vec<StringMatch>, String> {
+    let scoped = resolve_index(&state, &workspaces, workspace_id.as_deref())?;
+    let mut matches = {
+        let index = scoped
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        index.find(
+            &query,
+            regex.unwrap_or(false),
+            limit.unwrap_or(DEFAULT_RESULT_LIMIT),
+        )?
+    };
+    if encoding == Some(PositionEncoding::Utf16) {
+        let mut files = FileContents::default();
+        for m in &mut matches {
+            let position = Position {
+                line: m.line,
+                column: m.column,
+            };
+            m.column = files.utf16_position(&m.file_path, position).column;
+        }
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_with(file_path: &str, content: &str, lang_id: &str) -> StringLiteralIndex {
+        let mut index = StringLiteralIndex::default();
+        index.index_file(file_path, content, lang_id);
+        index
+    }
+
+    #[test]
+    fn test_strip_quotes() {
+        assert_eq!(strip_quotes("\"hello\""), "hello");
+        assert_eq!(strip_quotes("r#\"raw \"quoted\"\"#"), "raw \"quoted\"");
+        assert_eq!(strip_quotes("f'{x} items'"), "{x} items");
+        assert_eq!(strip_quotes("\"\"\"doc\"\"\""), "doc");
+        assert_eq!(strip_quotes("\"\""), "");
+        assert_eq!(strip_quotes("`id ${id}`"), "id ${id}");
+    }
+
+    #[test]
+    fn test_template_pattern() {
+        let pattern = template_pattern("User {} not found").unwrap();
+        assert!(pattern.is_match("User 42 not found"));
+        assert!(!pattern.is_match("User not found"));
+        assert!(template_pattern("%s failed: %d")
+            .unwrap()
+            .is_match("sync failed: 3"));
+        assert!(template_pattern("plain message").is_none());
+        assert!(template_pattern("{}").is_none());
+    }
+
+    #[test]
+    fn test_find_exact_with_symbol() {
+        let content = "fn load_user(id: u32) -> Result<User, String> {\n    Err(\"User not found\".to_string())\n}\n";
+        let index = index_with("/src/users.rs", content, "rust");
+        let matches = index.find("user not found", false, 10).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].match_kind, "exact");
+        assert_eq!(matches[0].line, 2);
+        assert_eq!(matches[0].symbol.as_deref(), Some("load_user"));
+    }
+
+    #[test]
+    fn test_find_template_and_contains() {
+        let content = "const loadUser = (id) => {\n  throw new Error(`User ${id} not found`);\n};\nfunction other() { log('cache miss for users'); }\n";
+        let index = index_with("/src/users.ts", content, "typescript");
+
+        let matches = index.find("User 42 not found", false, 10).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].match_kind, "template");
+        assert_eq!(matches[0].symbol.as_deref(), Some("loadUser"));
+
+        let matches = index.find("cache miss", false, 10).unwrap();
+        assert_eq!(matches[0].match_kind, "contains");
+        assert_eq!(matches[0].symbol.as_deref(), Some("other"));
+    }
+
+    #[test]
+    fn test_find_regex_and_clear() {
+        let mut index = index_with(
+            "/app/main.py",
+            "def run():\n    raise ValueError('bad input: %s' % x)\n",
+            "python",
+        );
+        assert_eq!(index.find("^bad input", true, 10).unwrap().len(), 1);
+        assert!(index.find("(unclosed", true, 10).is_err());
+
+        index.clear_file("/app/main.py");
+        assert!(index.find("bad input", false, 10).unwrap().is_empty());
+    }
+}
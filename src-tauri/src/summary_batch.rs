@@ -0,0 +1,238 @@
+// Batch code summarization with a fast path for tiny files
+//
+// Files under TINY_FILE_LINES lines are not worth compressing: their summary is rarely
+// much shorter than the file, so the compaction step keeps the original anyway. Batch
+// requests run a cheap size estimate first and hand such files back untouched without
+// parsing them. Counters record the work done on each tier so the savings can be
+// checked against real sessions.
+
+use crate::code_navigation::{summarize_source, CodeSummary, SummaryOptions};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Files with fewer lines than this skip parsing
+pub const TINY_FILE_LINES: usize = 50;
+/// Short files with very long lines (minified or generated code) still get parsed
+const TINY_FILE_MAX_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryTier {
+    /// Returned as-is without parsing
+    Passthrough,
+    /// Parsed and summarized
+    Full,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentEstimate {
+    pub lines: usize,
+    pub bytes: usize,
+    pub tier: SummaryTier,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SummaryRequest {
+    pub content: String,
+    pub lang_id: String,
+    pub file_path: String,
+    #[serde(default)]
+    pub options: Option<SummaryOptions>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchSummaryItem {
+    pub file_path: String,
+    pub tier: SummaryTier,
+    pub result: CodeSummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryMetricsSnapshot {
+    pub fast_path_files: u64,
+    pub fast_path_lines: u64,
+    pub full_files: u64,
+    pub full_lines: u64,
+    pub full_micros: u64,
+    /// Parse time the fast path avoided, extrapolated from the full tier's per-line cost
+    pub estimated_saved_micros: u64,
+}
+
+struct SummaryMetrics {
+    fast_path_files: AtomicU64,
+    fast_path_lines: AtomicU64,
+    full_files: AtomicU64,
+    full_lines: AtomicU64,
+    full_micros: AtomicU64,
+}
+
+static METRICS: SummaryMetrics = SummaryMetrics {
+    fast_path_files: AtomicU64::new(0),
+    fast_path_lines: AtomicU64::new(0),
+    full_files: AtomicU64::new(0),
+    full_lines: AtomicU64::new(0),
+    full_micros: AtomicU64::new(0),
+};
+
+impl SummaryMetrics {
+    fn snapshot(&self) -> SummaryMetricsSnapshot {
+        let fast_path_lines = self.fast_path_lines.load(Ordering::Relaxed);
+        let full_lines = self.full_lines.load(Ordering::Relaxed);
+        let full_micros = self.full_micros.load(Ordering::Relaxed);
+        let estimated_saved_micros = if full_lines == 0 {
+            0
+        } else {
+            (fast_path_lines as f64 * full_micros as f64 / full_lines as f64) as u64
+        };
+        SummaryMetricsSnapshot {
+            fast_path_files: self.fast_path_files.load(Ordering::Relaxed),
+            fast_path_lines,
+            full_files: self.full_files.load(Ordering::Relaxed),
+            full_lines,
+            full_micros,
+            estimated_saved_micros,
+        }
+    }
+}
+
+/// Cheap pre-parse estimate deciding whether a file is worth summarizing
+pub fn estimate(content: &str) -> ContentEstimate {
+    let lines = content.lines().count();
+    let bytes = content.len();
+    let tier = if lines < TINY_FILE_LINES && bytes <= TINY_FILE_MAX_BYTES {
+        SummaryTier::Passthrough
+    } else {
+        SummaryTier::Full
+    };
+    ContentEstimate { lines, bytes, tier }
+}
+
+pub fn summarize_request(request: SummaryRequest) -> Result<BatchSummaryItem, String> {
+    let estimate = estimate(&request.content);
+    let file_path = request.file_path.clone();
+
+    if estimate.tier == SummaryTier::Passthrough {
+        METRICS.fast_path_files.fetch_add(1, Ordering::Relaxed);
+        METRICS
+            .fast_path_lines
+            .fetch_add(estimate.lines as u64, Ordering::Relaxed);
+        return Ok(BatchSummaryItem {
+            file_path,
+            tier: SummaryTier::Passthrough,
+            result: CodeSummary::unchanged(
+                request.content,
+                estimate.lines,
+                request.lang_id,
+                "tiny file",
+            ),
+        });
+    }
+
+    let start = Instant::now();
+    let options = request
+        .options
+        .unwrap_or_default()
+        .with_configured_policies(&request.lang_id);
+    let result = summarize_source(
+        request.content,
+        request.lang_id,
+        request.file_path,
+        &options,
+    )?;
+    METRICS.full_files.fetch_add(1, Ordering::Relaxed);
+    METRICS
+        .full_lines
+        .fetch_add(estimate.lines as u64, Ordering::Relaxed);
+    METRICS
+        .full_micros
+        .fetch_add(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+
+    Ok(BatchSummaryItem {
+        file_path,
+        tier: SummaryTier::Full,
+        result,
+    })
+}
+
+/// Summarize many files in parallel; tiny files are passed through unparsed
+#[tauri::command]
+pub async fn summarize_code_batch(
+    requests: Vec<SummaryRequest>,
+) -> Result<Vec<BatchSummaryItem>, String> {
+    let count = requests.len();
+    let start = Instant::now();
+    let items = tokio::task::spawn_blocking(move || {
+        requests
+            .into_par_iter()
+            .map(summarize_request)
+            .collect::<Result<Vec<_>, String>>()
+    })
+    .await
+    .map_err(|e| format!("Batch summarization failed: {}", e))??;
+
+    let skipped = items
+        .iter()
+        .filter(|item| item.tier == SummaryTier::Passthrough)
+        .count();
+    log::info!(
+        "Summarized {} files ({} via fast path) in {:.2}ms",
+        count,
+        skipped,
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+    Ok(items)
+}
+
+#[tauri::command]
+pub fn get_summary_metrics() -> SummaryMetricsSnapshot {
+    METRICS.snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(content: String) -> SummaryRequest {
+        SummaryRequest {
+            content,
+            lang_id: "rust".to_string(),
+            file_path: "lib.rs".to_string(),
+            options: None,
+        }
+    }
+
+    #[test]
+    fn test_estimate_tiers() {
+        assert_eq!(estimate("fn a() {}\n").tier, SummaryTier::Passthrough);
+        let long = "fn a() {}\n".repeat(TINY_FILE_LINES);
+        assert_eq!(estimate(&long).tier, SummaryTier::Full);
+        // A single minified line is short but not cheap
+        let minified = "x".repeat(TINY_FILE_MAX_BYTES + 1);
+        assert_eq!(estimate(&minified).tier, SummaryTier::Full);
+    }
+
+    #[test]
+    fn test_tiny_file_is_passed_through() {
+        let content = "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n".to_string();
+        let before = METRICS.snapshot().fast_path_files;
+        let item = summarize_request(request(content.clone())).unwrap();
+        assert_eq!(item.tier, SummaryTier::Passthrough);
+        assert!(!item.result.success);
+        assert_eq!(item.result.summary, content);
+        assert!(METRICS.snapshot().fast_path_files > before);
+    }
+
+    #[test]
+    fn test_large_file_is_summarized() {
+        let content = (0..60)
+            .map(|i| format!("pub fn f{}() -> u32 {{\n    {}\n}}\n", i, i))
+            .collect::<String>();
+        let item = summarize_request(request(content)).unwrap();
+        assert_eq!(item.tier, SummaryTier::Full);
+        assert!(item.result.success);
+        assert!(item.result.summary.contains("pub fn f59() -> u32 { ... }"));
+        assert!(METRICS.snapshot().full_files > 0);
+    }
+}
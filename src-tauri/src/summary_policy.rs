@@ -0,0 +1,159 @@
+// Per-kind summarization policies
+//
+// Teams disagree on what a summary should keep: one wants Rust `const` items in full,
+// another wants Java fields gone entirely. Policies are read from
+// ~/.talkcody/summary-policies.json (global) and <project>/.talkcody/summary-policies.json
+// (per-project overrides), keyed by language id and then by capture kind:
+//
+//   { "*": { "field": "first_line" }, "rust": { "const": "keep_full" }, "java": { "field": "drop" } }
+//
+// `*` applies to every language; a language's own entries win over it, and project
+// entries win over global ones. Kinds without a policy keep the built-in behavior.
+
+use crate::capture_kinds::CaptureKind;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const POLICY_FILE: &str = "summary-policies.json";
+const ALL_LANGUAGES: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KindPolicy {
+    /// The definition's full text
+    KeepFull,
+    /// Signature with the body elided
+    SignatureOnly,
+    /// Only the definition's first line
+    FirstLine,
+    /// Left out of the summary
+    Drop,
+}
+
+/// lang id (or `*`) -> kind -> policy
+pub type SummaryPolicyConfig = BTreeMap<String, HashMap<CaptureKind, KindPolicy>>;
+
+/// Get the global policy file (~/.talkcody/summary-policies.json)
+fn get_global_policy_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody").join(POLICY_FILE))
+}
+
+/// Get the project policy file (<root>/.talkcody/summary-policies.json)
+fn get_project_policy_path(root_path: &str) -> PathBuf {
+    Path::new(root_path).join(".talkcody").join(POLICY_FILE)
+}
+
+fn read_config(path: &Path) -> SummaryPolicyConfig {
+    let Ok(raw) = fs::read_to_string(path) else {
+        return SummaryPolicyConfig::new();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|e| {
+        log::warn!(
+            "Ignoring invalid summary policies in {}: {}",
+            path.display(),
+            e
+        );
+        SummaryPolicyConfig::new()
+    })
+}
+
+/// Load policies, with project entries overriding global ones per language and kind
+pub fn load_policies(global_path: Option<&Path>, root_path: Option<&str>) -> SummaryPolicyConfig {
+    let mut config = global_path.map(read_config).unwrap_or_default();
+    if let Some(root) = root_path {
+        for (lang_id, kinds) in read_config(&get_project_policy_path(root)) {
+            config.entry(lang_id).or_default().extend(kinds);
+        }
+    }
+    config
+}
+
+/// Effective policies for one language
+pub fn policies_for(
+    config: &SummaryPolicyConfig,
+    lang_id: &str,
+) -> HashMap<CaptureKind, KindPolicy> {
+    let mut policies = config.get(ALL_LANGUAGES).cloned().unwrap_or_default();
+    if let Some(own) = config.get(lang_id) {
+        policies.extend(own.iter().map(|(kind, policy)| (*kind, *policy)));
+    }
+    policies
+}
+
+/// Load the configured policies for `lang_id` from the global and project files
+pub fn configured_policies(
+    root_path: Option<&str>,
+    lang_id: &str,
+) -> HashMap<CaptureKind, KindPolicy> {
+    let global_path = get_global_policy_path().ok();
+    policies_for(&load_policies(global_path.as_deref(), root_path), lang_id)
+}
+
+#[tauri::command]
+pub fn get_summary_policies(root_path: Option<String>) -> Result<SummaryPolicyConfig, String> {
+    let global_path = get_global_policy_path()?;
+    Ok(load_policies(Some(&global_path), root_path.as_deref()))
+}
+
+/// Write policies to the project file, or the global one when no project is given
+#[tauri::command]
+pub fn set_summary_policies(
+    root_path: Option<String>,
+    config: SummaryPolicyConfig,
+) -> Result<(), String> {
+    let path = match root_path {
+        Some(root) => get_project_policy_path(&root),
+        None => get_global_policy_path()?,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_project_overrides_global() {
+        let temp_dir = TempDir::new().unwrap();
+        let global = temp_dir.path().join("global.json");
+        fs::write(
+            &global,
+            r#"{"*": {"field": "first_line"}, "rust": {"const": "first_line", "static": "drop"}}"#,
+        )
+        .unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir_all(root.join(".talkcody")).unwrap();
+        fs::write(
+            root.join(".talkcody").join(POLICY_FILE),
+            r#"{"rust": {"const": "keep_full"}, "java": {"field": "drop"}}"#,
+        )
+        .unwrap();
+
+        let config = load_policies(Some(&global), Some(&root.to_string_lossy()));
+        let rust = policies_for(&config, "rust");
+        assert_eq!(rust.get(&CaptureKind::Const), Some(&KindPolicy::KeepFull));
+        assert_eq!(rust.get(&CaptureKind::Static), Some(&KindPolicy::Drop));
+        assert_eq!(rust.get(&CaptureKind::Field), Some(&KindPolicy::FirstLine));
+
+        let java = policies_for(&config, "java");
+        assert_eq!(java.get(&CaptureKind::Field), Some(&KindPolicy::Drop));
+        assert!(!policies_for(&config, "go").contains_key(&CaptureKind::Const));
+    }
+
+    #[test]
+    fn test_invalid_file_is_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let global = temp_dir.path().join("global.json");
+        fs::write(&global, r#"{"rust": {"const": "shout"}}"#).unwrap();
+        assert!(load_policies(Some(&global), None).is_empty());
+    }
+}
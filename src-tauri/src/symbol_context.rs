@@ -0,0 +1,368 @@
+// Cross-file context for a single symbol
+//
+// A focused edit needs the symbol's own body plus the shape of everything it touches:
+// the types it takes and returns and the functions it calls. `get_symbol_context`
+// returns the full definition of the target and, breadth-first, summarized definitions
+// of the names it references, resolved through the definition index. Dependencies are
+// followed through their summaries (signatures and type shapes) rather than their
+// bodies, so the walk follows the type graph instead of fanning out into every
+// callee's callees. A character budget bounds the result; references that do not fit
+// are listed by name.
+
+use crate::code_navigation::{
+    get_language, resolve_nav, summarize_definition, CodeNavState, CodeNavigationService,
+    SymbolInfo,
+};
+use crate::doc_generation::find_named;
+use crate::workspace_state::WorkspaceState;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use tauri::State;
+use tree_sitter::Parser;
+
+pub const DEFAULT_CONTEXT_BUDGET: usize = 12_000;
+/// References of references are followed this many levels deep
+const MAX_CONTEXT_DEPTH: usize = 3;
+const IDENTIFIER_KINDS: &[&str] = &["identifier", "type_identifier"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextDependency {
+    pub name: String,
+    pub path: String,
+    /// 1-based first line of the definition
+    pub line: u32,
+    /// 1 for names the target references directly, 2 for names those reference, ...
+    pub depth: usize,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolContext {
+    pub path: String,
+    pub symbol: String,
+    /// 1-based line range of the target definition
+    pub start_line: u32,
+    pub end_line: u32,
+    pub body: String,
+    pub dependencies: Vec<ContextDependency>,
+    /// Referenced definitions left out to stay within the budget
+    pub omitted: Vec<String>,
+    /// Characters taken by the body and the dependency summaries
+    pub used_chars: usize,
+}
+
+pub(crate) struct Definition {
+    pub text: String,
+    pub node_kind: String,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+/// Full text of the definition of `name` in `content`
+pub(crate) fn locate(content: &str, lang_id: &str, name: &str) -> Option<Definition> {
+    let language = get_language(lang_id)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content, None)?;
+    let named = find_named(tree.root_node(), content.as_bytes(), name)?;
+    let node_kind = named.kind().to_string();
+
+    // Arrow functions and exported forms: take the whole statement so the name is kept
+    let mut node = named;
+    while let Some(parent) = node.parent() {
+        if matches!(
+            parent.kind(),
+            "export_statement" | "lexical_declaration" | "variable_declarator"
+        ) {
+            node = parent;
+        } else {
+            break;
+        }
+    }
+
+    Some(Definition {
+        text: node.utf8_text(content.as_bytes()).ok()?.to_string(),
+        node_kind,
+        start_line: node.start_position().row as u32 + 1,
+        end_line: node.end_position().row as u32 + 1,
+    })
+}
+
+/// Identifiers in `text`, in order of first appearance
+fn referenced_names(text: &str, lang_id: &str) -> Vec<String> {
+    let Some(language) = get_language(lang_id) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(text, None) else {
+        return Vec::new();
+    };
+
+    let mut seen = HashSet::new();
+    let mut names = Vec::new();
+    let mut cursor = tree.walk();
+    'walk: loop {
+        let node = cursor.node();
+        if IDENTIFIER_KINDS.contains(&node.kind()) {
+            if let Ok(name) = node.utf8_text(text.as_bytes()) {
+                if seen.insert(name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        if cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
+        }
+    }
+    names
+}
+
+/// Pick one definition for a name, preferring the referencing file
+pub(crate) fn pick_definition(
+    mut candidates: Vec<SymbolInfo>,
+    from_path: &str,
+) -> Option<SymbolInfo> {
+    candidates.sort_by(|a, b| {
+        (a.file_path != from_path, &a.file_path, a.start_line).cmp(&(
+            b.file_path != from_path,
+            &b.file_path,
+            b.start_line,
+        ))
+    });
+    candidates.into_iter().next()
+}
+
+pub fn build_symbol_context(
+    service: &CodeNavigationService,
+    path: &str,
+    symbol: &str,
+    budget: usize,
+) -> Result<SymbolContext, String> {
+    let lang_id = CodeNavigationService::get_lang_id_from_path(path)
+        .ok_or_else(|| format!("Unsupported file type: {}", path))?;
+    let lang_family = CodeNavigationService::get_lang_family(&lang_id);
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let target = locate(&content, &lang_id, symbol)
+        .ok_or_else(|| format!("Definition of {} not found in {}", symbol, path))?;
+
+    let mut files: HashMap<String, Option<String>> = HashMap::new();
+    files.insert(path.to_string(), Some(content));
+
+    let mut used_chars = target.text.len();
+    let mut dependencies = Vec::new();
+    let mut omitted = Vec::new();
+    let mut visited: HashSet<String> = HashSet::from([symbol.to_string()]);
+    let mut queue: VecDeque<(String, usize, String)> = referenced_names(&target.text, &lang_id)
+        .into_iter()
+        .map(|name| (name, 1, path.to_string()))
+        .collect();
+
+    while let Some((name, depth, from_path)) = queue.pop_front() {
+        if !visited.insert(name.clone()) {
+            continue;
+        }
+        // Locals, parameters and library names have no indexed definition
+        let Some(info) = pick_definition(service.find_definition(&name, lang_family), &from_path)
+        else {
+            continue;
+        };
+        let dep_lang = CodeNavigationService::get_lang_id_from_path(&info.file_path)
+            .unwrap_or_else(|| lang_id.clone());
+        let content = files
+            .entry(info.file_path.clone())
+            .or_insert_with(|| fs::read_to_string(&info.file_path).ok());
+        let Some(content) = content.as_deref() else {
+            continue;
+        };
+
+        let (summary, line) = match locate(content, &dep_lang, &name) {
+            Some(def) => (
+                summarize_definition(&def.text, &def.node_kind, &dep_lang),
+                def.start_line,
+            ),
+            // Kinds the locator does not know (constants, Go types): the defining line
+            None => (
+                content
+                    .lines()
+                    .nth(info.start_line.saturating_sub(1) as usize)
+                    .unwrap_or("")
+                    .trim()
+                    .to_string(),
+                info.start_line,
+            ),
+        };
+
+        if used_chars + summary.len() > budget {
+            omitted.push(name);
+            continue;
+        }
+        used_chars += summary.len();
+        if depth < MAX_CONTEXT_DEPTH {
+            for next in referenced_names(&summary, &dep_lang) {
+                queue.push_back((next, depth + 1, info.file_path.clone()));
+            }
+        }
+        dependencies.push(ContextDependency {
+            name,
+            path: info.file_path,
+            line,
+            depth,
+            summary,
+        });
+    }
+
+    Ok(SymbolContext {
+        path: path.to_string(),
+        symbol: symbol.to_string(),
+        start_line: target.start_line,
+        end_line: target.end_line,
+        body: target.text,
+        dependencies,
+        omitted,
+        used_chars,
+    })
+}
+
+/// The full definition of `symbol` in `path` plus summarized definitions of what it
+/// references, within `budget` characters
+#[tauri::command]
+pub async fn get_symbol_context(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    path: String,
+    symbol: String,
+    budget: Option<usize>,
+) -> Result<SymbolContext, String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let service = nav
+        .read()
+        .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+    build_symbol_context(
+        &service,
+        &path,
+        &symbol,
+        budget.unwrap_or(DEFAULT_CONTEXT_BUDGET),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const HANDLER: &str = r#"use crate::types::*;
+
+pub fn handle(req: Request) -> Response {
+    let config = load_config();
+    respond(req, &config)
+}
+"#;
+
+    const TYPES: &str = r#"pub struct Request {
+    pub body: Body,
+}
+
+pub struct Body {
+    pub text: String,
+}
+
+pub struct Response {
+    pub status: u16,
+}
+
+pub struct Config {
+    pub verbose: bool,
+}
+
+pub fn load_config() -> Config {
+    Config { verbose: false }
+}
+
+fn respond(req: Request, config: &Config) -> Response {
+    let _ = (req, config);
+    Response { status: 200 }
+}
+"#;
+
+    fn setup() -> (TempDir, CodeNavigationService, String) {
+        let temp_dir = TempDir::new().unwrap();
+        let mut service = CodeNavigationService::new();
+        for (name, content) in [("handler.rs", HANDLER), ("types.rs", TYPES)] {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            service.index_file(&path.to_string_lossy(), content, "rust");
+        }
+        let handler = temp_dir
+            .path()
+            .join("handler.rs")
+            .to_string_lossy()
+            .to_string();
+        (temp_dir, service, handler)
+    }
+
+    #[test]
+    fn test_context_follows_references_transitively() {
+        let (_temp_dir, service, handler) = setup();
+        let context =
+            build_symbol_context(&service, &handler, "handle", DEFAULT_CONTEXT_BUDGET).unwrap();
+
+        assert_eq!(context.start_line, 3);
+        assert!(context.body.contains("respond(req, &config)"));
+        let names: Vec<(&str, usize)> = context
+            .dependencies
+            .iter()
+            .map(|d| (d.name.as_str(), d.depth))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Request", 1),
+                ("Response", 1),
+                ("load_config", 1),
+                ("respond", 1),
+                ("Body", 2),
+                ("Config", 2),
+            ]
+        );
+        let respond = &context.dependencies[3];
+        assert_eq!(
+            respond.summary,
+            "fn respond(req: Request, config: &Config) -> Response { ... }"
+        );
+        assert!(context.omitted.is_empty());
+    }
+
+    #[test]
+    fn test_context_respects_budget() {
+        let (_temp_dir, service, handler) = setup();
+        let full =
+            build_symbol_context(&service, &handler, "handle", DEFAULT_CONTEXT_BUDGET).unwrap();
+        let budget = full.body.len() + full.dependencies[0].summary.len();
+
+        let limited = build_symbol_context(&service, &handler, "handle", budget).unwrap();
+        assert_eq!(limited.dependencies.len(), 1);
+        assert_eq!(limited.dependencies[0].name, "Request");
+        assert_eq!(limited.used_chars, budget);
+        assert_eq!(
+            limited.omitted,
+            vec!["Response", "load_config", "respond", "Body"]
+        );
+    }
+
+    #[test]
+    fn test_missing_symbol() {
+        let (_temp_dir, service, handler) = setup();
+        assert!(build_symbol_context(&service, &handler, "nope", 1000).is_err());
+    }
+}
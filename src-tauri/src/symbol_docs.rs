@@ -0,0 +1,213 @@
+// Batch symbol documentation lookup
+//
+// The context assembler resolves a whole dependency closure at once: every type and
+// function a change touches. Asking for each one separately costs an IPC round trip per
+// name, so `get_symbol_docs` takes the full list and returns, for each name, its
+// signature (the same summary the context builder uses) and its doc comment. Python
+// definitions document themselves with a docstring in the body, which is read when no
+// comment sits above the definition. Names without an indexed definition are listed
+// as missing rather than failing the batch.
+
+use crate::code_navigation::{
+    extract_doc_comment, get_language, resolve_nav, summarize_definition, CodeNavState,
+    CodeNavigationService,
+};
+use crate::qualified_names;
+use crate::symbol_context::{locate, pick_definition};
+use crate::workspace_state::WorkspaceState;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use tauri::State;
+use tree_sitter::Parser;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolDoc {
+    /// The name as requested
+    pub name: String,
+    pub kind: String,
+    pub path: String,
+    /// 1-based first line of the definition
+    pub line: u32,
+    pub signature: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolDocs {
+    pub docs: Vec<SymbolDoc>,
+    /// Requested names with no indexed definition
+    pub missing: Vec<String>,
+}
+
+/// Docstring opening the body of the Python function or class in `text`
+fn python_docstring(text: &str) -> Option<String> {
+    let language = get_language("python")?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(text, None)?;
+    let mut definition = tree.root_node().named_child(0)?;
+    if definition.kind() == "decorated_definition" {
+        definition = definition.child_by_field_name("definition")?;
+    }
+    let first = definition.child_by_field_name("body")?.named_child(0)?;
+    let string = first
+        .named_child(0)
+        .filter(|n| first.kind() == "expression_statement" && n.kind() == "string")?;
+    Some(text[string.byte_range()].to_string())
+}
+
+pub fn build_symbol_docs(
+    service: &CodeNavigationService,
+    symbols: &[String],
+    from_path: &str,
+) -> Result<SymbolDocs, String> {
+    let lang_id = CodeNavigationService::get_lang_id_from_path(from_path)
+        .ok_or_else(|| format!("Unsupported file type: {}", from_path))?;
+    let lang_family = CodeNavigationService::get_lang_family(&lang_id);
+
+    let mut files: HashMap<String, Option<String>> = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut result = SymbolDocs::default();
+    for name in symbols {
+        if !seen.insert(name.as_str()) {
+            continue;
+        }
+        let Some(info) = pick_definition(service.find_definition(name, lang_family), from_path)
+        else {
+            result.missing.push(name.clone());
+            continue;
+        };
+        let def_lang = CodeNavigationService::get_lang_id_from_path(&info.file_path)
+            .unwrap_or_else(|| lang_id.clone());
+        let content = files
+            .entry(info.file_path.clone())
+            .or_insert_with(|| fs::read_to_string(&info.file_path).ok());
+        let Some(content) = content.as_deref() else {
+            result.missing.push(name.clone());
+            continue;
+        };
+        let lines: Vec<&str> = content.lines().collect();
+
+        // `Foo.bar` is located by its last segment
+        let bare = qualified_names::segments(name)
+            .pop()
+            .unwrap_or_else(|| name.clone());
+        let (signature, line, text) = match locate(content, &def_lang, &bare) {
+            Some(def) => (
+                summarize_definition(&def.text, &def.node_kind, &def_lang),
+                def.start_line,
+                Some(def.text),
+            ),
+            None => (
+                lines
+                    .get(info.start_line.saturating_sub(1) as usize)
+                    .unwrap_or(&"")
+                    .trim()
+                    .to_string(),
+                info.start_line,
+                None,
+            ),
+        };
+
+        let mut doc = extract_doc_comment(&lines, line.saturating_sub(1) as usize, &def_lang);
+        if doc.is_empty() && def_lang == "python" {
+            doc = text
+                .as_deref()
+                .and_then(python_docstring)
+                .unwrap_or_default();
+        }
+        result.docs.push(SymbolDoc {
+            name: name.clone(),
+            kind: info.kind,
+            path: info.file_path,
+            line,
+            signature,
+            doc: (!doc.is_empty()).then_some(doc),
+        });
+    }
+    Ok(result)
+}
+
+/// Signatures and doc comments of `symbols`, preferring definitions in `from_path` and
+/// searching its language family
+#[tauri::command]
+pub async fn get_symbol_docs(
+    state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    symbols: Vec<String>,
+    from_path: String,
+) -> Result<SymbolDocs, String> {
+    let nav = resolve_nav(&state, &workspaces, workspace_id.as_deref())?;
+    let service = nav
+        .read()
+        .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+    build_symbol_docs(&service, &symbols, &from_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rust_docs_in_one_batch() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut service = CodeNavigationService::new();
+        let types = r#"/// An HTTP request.
+/// Bodies are buffered.
+pub struct Request {
+    pub body: String,
+}
+
+pub fn respond(req: Request) -> u16 {
+    let _ = req;
+    200
+}
+"#;
+        let path = temp_dir.path().join("types.rs");
+        fs::write(&path, types).unwrap();
+        let path = path.to_string_lossy().to_string();
+        service.index_file(&path, types, "rust");
+
+        let symbols = ["Request", "respond", "Request", "Unknown"].map(String::from);
+        let result = build_symbol_docs(&service, &symbols, &path).unwrap();
+
+        assert_eq!(result.missing, vec!["Unknown".to_string()]);
+        assert_eq!(result.docs.len(), 2);
+        let request = &result.docs[0];
+        assert_eq!((request.kind.as_str(), request.line), ("struct", 3));
+        assert_eq!(
+            request.doc.as_deref(),
+            Some("/// An HTTP request.\n/// Bodies are buffered.")
+        );
+        assert!(request.signature.contains("pub body: String"));
+
+        let respond = &result.docs[1];
+        assert!(respond
+            .signature
+            .starts_with("pub fn respond(req: Request) -> u16"));
+        assert!(!respond.signature.contains("200"));
+        assert_eq!(respond.doc, None);
+    }
+
+    #[test]
+    fn test_python_docstring() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut service = CodeNavigationService::new();
+        let code = "class Cart:\n    \"\"\"Items in the basket.\"\"\"\n\n    def total(self):\n        return 0\n";
+        let path = temp_dir.path().join("cart.py");
+        fs::write(&path, code).unwrap();
+        let path = path.to_string_lossy().to_string();
+        service.index_file(&path, code, "python");
+
+        let result = build_symbol_docs(&service, &["Cart".to_string()], &path).unwrap();
+        assert_eq!(
+            result.docs[0].doc.as_deref(),
+            Some("\"\"\"Items in the basket.\"\"\"")
+        );
+        assert!(build_symbol_docs(&service, &[], "notes.txt").is_err());
+    }
+}
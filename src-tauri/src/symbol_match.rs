@@ -0,0 +1,180 @@
+// Symbol name matching modes
+//
+// Definition lookup is an exact key match, which fails for the ways people actually
+// type symbol names: `messagerewriter`, `message_rewriter` for a `MessageRewriter`
+// class, `MR` or `MesRew` for short, `resume` for `résumé`. Matching modes are ordered
+// from strict to loose and each accepts everything the stricter ones do:
+//
+// - `exact`: the name as written
+// - `case_insensitive`: case and diacritics folded
+// - `words`: the names split into the same words, so snake_case, camelCase and
+//   kebab-case spellings match each other
+// - `initials`: each part of the query is a prefix of successive words
+//   (`MR`, `MesRew`, `mrw` all find `MessageRewriter`)
+//
+// A match reports the strictest mode that accepted it so results can be ranked.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    Exact,
+    CaseInsensitive,
+    #[default]
+    Words,
+    Initials,
+}
+
+/// Lowercase `c` and strip its diacritics (Latin letters only)
+fn fold_char(c: char) -> char {
+    let lower = c.to_lowercase().next().unwrap_or(c);
+    match lower {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        other => other,
+    }
+}
+
+fn fold(text: &str) -> String {
+    text.chars().map(fold_char).collect()
+}
+
+/// Words of an identifier, folded: `HTTPServer_config` -> `http`, `server`, `config`
+pub fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_uppercase()
+            && prev.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    // The last capital of an acronym starts the next word: HTTP|Server
+                    || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(fold_char(c));
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Whether `query` can be split into prefixes of successive words, skipping words
+fn matches_initials(query: &[char], words: &[Vec<char>]) -> bool {
+    let Some((word, rest)) = words.split_first() else {
+        return query.is_empty();
+    };
+    if query.is_empty() {
+        return true;
+    }
+    let longest = word.iter().zip(query).take_while(|(a, b)| a == b).count();
+    (1..=longest)
+        .rev()
+        .any(|k| matches_initials(&query[k..], rest))
+        || matches_initials(query, rest)
+}
+
+/// The strictest mode up to `mode` under which `name` matches `query`
+pub fn match_mode(name: &str, query: &str, mode: MatchMode) -> Option<MatchMode> {
+    if name == query {
+        return Some(MatchMode::Exact);
+    }
+    if mode >= MatchMode::CaseInsensitive && fold(name) == fold(query) {
+        return Some(MatchMode::CaseInsensitive);
+    }
+    if mode < MatchMode::Words {
+        return None;
+    }
+    let name_words = words(name);
+    let query_words = words(query);
+    if !query_words.is_empty() && name_words == query_words {
+        return Some(MatchMode::Words);
+    }
+    if mode < MatchMode::Initials {
+        return None;
+    }
+    let query_chars: Vec<char> = query_words.concat().chars().collect();
+    let name_words: Vec<Vec<char>> = name_words.iter().map(|w| w.chars().collect()).collect();
+    // The first letter has to start the name so `r` does not find every `*Reader`
+    let starts = name_words
+        .first()
+        .zip(query_chars.first())
+        .is_some_and(|(word, q)| word.first() == Some(q));
+    (starts && matches_initials(&query_chars, &name_words)).then_some(MatchMode::Initials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        assert_eq!(words("MessageRewriter"), vec!["message", "rewriter"]);
+        assert_eq!(words("HTTPServer_config"), vec!["http", "server", "config"]);
+        assert_eq!(words("parse-v2Header"), vec!["parse", "v2", "header"]);
+        assert_eq!(words("Résumé"), vec!["resume"]);
+    }
+
+    #[test]
+    fn test_match_modes() {
+        let name = "MessageRewriter";
+        assert_eq!(
+            match_mode(name, name, MatchMode::Exact),
+            Some(MatchMode::Exact)
+        );
+        assert_eq!(match_mode(name, "messagerewriter", MatchMode::Exact), None);
+        assert_eq!(
+            match_mode(name, "messagerewriter", MatchMode::Initials),
+            Some(MatchMode::CaseInsensitive)
+        );
+        assert_eq!(
+            match_mode(name, "message_rewriter", MatchMode::Words),
+            Some(MatchMode::Words)
+        );
+        assert_eq!(match_mode(name, "MR", MatchMode::Words), None);
+        for query in ["MR", "mr", "MesRew", "mrewr"] {
+            assert_eq!(
+                match_mode(name, query, MatchMode::Initials),
+                Some(MatchMode::Initials),
+                "{}",
+                query
+            );
+        }
+        assert_eq!(match_mode(name, "RM", MatchMode::Initials), None);
+        assert_eq!(match_mode(name, "R", MatchMode::Initials), None);
+        assert_eq!(
+            match_mode("crème_brûlée", "CremeBrulee", MatchMode::Words),
+            Some(MatchMode::Words)
+        );
+    }
+}
@@ -0,0 +1,233 @@
+// Symbol retention priority for budget-limited summaries
+//
+// When a summary has room for fewer definitions than a file contains, the ones dropped
+// should be the ones a caller is least likely to need: private helpers first, then
+// unexported constants and other private items, then crate- or package-internal items.
+// Exported/public API goes last, and symbols named in the current task's context are
+// never dropped before anything else.
+
+use crate::capture_kinds::CaptureKind;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Private,
+    /// Visible within the crate or package only
+    Internal,
+    Public,
+}
+
+/// A definition competing for a place in the summary
+#[derive(Debug, Clone)]
+pub struct RetentionCandidate<'a> {
+    pub kind: &'a str,
+    pub name: &'a str,
+    pub text: &'a str,
+    /// Direct child of an `export` statement (JS/TS)
+    pub exported: bool,
+}
+
+fn is_callable(kind: &str) -> bool {
+    CaptureKind::from_capture_name(kind).is_some_and(CaptureKind::is_callable)
+}
+
+fn is_value(kind: &str) -> bool {
+    CaptureKind::from_capture_name(kind).is_some_and(CaptureKind::is_value)
+}
+
+/// Classify a definition's visibility from its language's conventions
+pub fn classify(lang_id: &str, candidate: &RetentionCandidate) -> Visibility {
+    let text = candidate.text.trim_start();
+    match lang_id {
+        "rust" => {
+            if text.starts_with("pub(crate)") || text.starts_with("pub(super)") {
+                Visibility::Internal
+            } else if text.starts_with("pub") {
+                Visibility::Public
+            } else if candidate.kind == "impl" {
+                // Trait and inherent impls carry no visibility of their own
+                Visibility::Internal
+            } else {
+                Visibility::Private
+            }
+        }
+        "go" => match candidate.name.chars().next() {
+            Some(c) if c.is_uppercase() => Visibility::Public,
+            Some(_) => Visibility::Private,
+            None => Visibility::Internal,
+        },
+        "python" => {
+            let name = candidate.name;
+            if name.starts_with("__") && name.ends_with("__") {
+                // Dunder methods are protocol, not private
+                Visibility::Public
+            } else if name.starts_with('_') {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            }
+        }
+        "typescript" | "javascript" | "tsx" | "jsx" => {
+            if candidate.exported {
+                Visibility::Public
+            } else if text.starts_with("private ") || text.starts_with('#') {
+                Visibility::Private
+            } else if candidate.kind == "method" {
+                // Members of a class are as visible as the class
+                Visibility::Internal
+            } else {
+                Visibility::Private
+            }
+        }
+        "java" => {
+            if text.starts_with("public ") {
+                Visibility::Public
+            } else if text.starts_with("private ") {
+                Visibility::Private
+            } else {
+                Visibility::Internal
+            }
+        }
+        "c" | "cpp" => {
+            if text.starts_with("static ") {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            }
+        }
+        _ => Visibility::Public,
+    }
+}
+
+/// Retention rank; lower ranks are dropped first
+pub fn retention_rank(kind: &str, visibility: Visibility, in_context: bool) -> u8 {
+    if in_context {
+        return 5;
+    }
+    match visibility {
+        Visibility::Private if is_callable(kind) => 0,
+        Visibility::Private if is_value(kind) => 1,
+        Visibility::Private => 2,
+        Visibility::Internal => 3,
+        Visibility::Public => 4,
+    }
+}
+
+fn in_context(candidate: &RetentionCandidate, context_symbols: &HashSet<&str>) -> bool {
+    if candidate.name.is_empty() {
+        let first_line = candidate.text.lines().next().unwrap_or("");
+        context_symbols
+            .iter()
+            .any(|symbol| first_line.contains(symbol))
+    } else {
+        context_symbols.contains(candidate.name)
+    }
+}
+
+/// Indices of the candidates to keep within `budget`, in their original order.
+/// Within a rank, later definitions are dropped before earlier ones.
+pub fn select_within_budget(
+    lang_id: &str,
+    candidates: &[RetentionCandidate],
+    budget: usize,
+    context_symbols: &[String],
+) -> Vec<usize> {
+    if candidates.len() <= budget {
+        return (0..candidates.len()).collect();
+    }
+    let context: HashSet<&str> = context_symbols.iter().map(String::as_str).collect();
+
+    let mut ranked: Vec<(u8, usize)> = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| {
+            let visibility = classify(lang_id, candidate);
+            let rank = retention_rank(candidate.kind, visibility, in_context(candidate, &context));
+            (rank, index)
+        })
+        .collect();
+    // Highest rank first, earliest first within a rank
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let mut keep: Vec<usize> = ranked.into_iter().take(budget).map(|(_, i)| i).collect();
+    keep.sort_unstable();
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate<'a>(kind: &'a str, name: &'a str, text: &'a str) -> RetentionCandidate<'a> {
+        RetentionCandidate {
+            kind,
+            name,
+            text,
+            exported: false,
+        }
+    }
+
+    #[test]
+    fn test_classify_per_language() {
+        let rust = |text| classify("rust", &candidate("function", "f", text));
+        assert_eq!(rust("pub fn f() {}"), Visibility::Public);
+        assert_eq!(rust("pub(crate) fn f() {}"), Visibility::Internal);
+        assert_eq!(rust("fn f() {}"), Visibility::Private);
+
+        assert_eq!(
+            classify("go", &candidate("function", "Serve", "func Serve() {}")),
+            Visibility::Public
+        );
+        assert_eq!(
+            classify("go", &candidate("function", "serve", "func serve() {}")),
+            Visibility::Private
+        );
+
+        let python = |name| classify("python", &candidate("function", name, "def x(): ..."));
+        assert_eq!(python("load"), Visibility::Public);
+        assert_eq!(python("_helper"), Visibility::Private);
+        assert_eq!(python("__init__"), Visibility::Public);
+
+        let mut exported = candidate("function", "run", "function run() {}");
+        assert_eq!(classify("typescript", &exported), Visibility::Private);
+        exported.exported = true;
+        assert_eq!(classify("typescript", &exported), Visibility::Public);
+        assert_eq!(
+            classify("typescript", &candidate("method", "x", "private x() {}")),
+            Visibility::Private
+        );
+
+        assert_eq!(
+            classify("java", &candidate("method", "m", "protected void m() {}")),
+            Visibility::Internal
+        );
+        assert_eq!(
+            classify("c", &candidate("function", "f", "static int f(void) {}")),
+            Visibility::Private
+        );
+    }
+
+    #[test]
+    fn test_drop_order() {
+        let candidates = vec![
+            candidate("function", "Public", "pub fn public() {}"),
+            candidate("const", "LIMIT", "const LIMIT: u32 = 1;"),
+            candidate("function", "helper", "fn helper() {}"),
+            candidate("function", "used_helper", "fn used_helper() {}"),
+            candidate("struct", "Inner", "struct Inner;"),
+            candidate("function", "internal", "pub(crate) fn internal() {}"),
+        ];
+        let context = vec!["used_helper".to_string()];
+        let keep = |budget| select_within_budget("rust", &candidates, budget, &context);
+
+        assert_eq!(keep(10), vec![0, 1, 2, 3, 4, 5]);
+        // The private helper goes first, then the private const, then the private struct
+        assert_eq!(keep(5), vec![0, 1, 3, 4, 5]);
+        assert_eq!(keep(4), vec![0, 3, 4, 5]);
+        assert_eq!(keep(3), vec![0, 3, 5]);
+        // Context symbols outlast even the public API
+        assert_eq!(keep(1), vec![3]);
+    }
+}
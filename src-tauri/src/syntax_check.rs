@@ -0,0 +1,148 @@
+// Tree-sitter based syntax validation
+// Reports ERROR and MISSING nodes so generated code can be rejected before it reaches disk.
+
+use crate::code_navigation::get_language;
+use crate::position_encoding::{LineIndex, Position, PositionEncoding};
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Parser};
+
+/// Stop collecting after this many issues; the first few are what matters
+const MAX_ISSUES: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyntaxIssue {
+    pub kind: String, // "error" or "missing"
+    pub message: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyntaxCheckResult {
+    pub supported: bool,
+    pub valid: bool,
+    pub issues: Vec<SyntaxIssue>,
+}
+
+/// Parse `content` as `lang_id` and collect syntax errors.
+/// Unsupported languages are reported as valid with `supported: false`.
+pub fn check_syntax(content: &str, lang_id: &str) -> Result<SyntaxCheckResult, String> {
+    let Some(language) = get_language(lang_id) else {
+        return Ok(SyntaxCheckResult {
+            supported: false,
+            valid: true,
+            issues: Vec::new(),
+        });
+    };
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| format!("Failed to set language for {}: {:?}", lang_id, e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| format!("Failed to parse {} content", lang_id))?;
+
+    let mut issues = Vec::new();
+    if tree.root_node().has_error() {
+        collect_issues(tree.root_node(), content.as_bytes(), &mut issues);
+    }
+
+    Ok(SyntaxCheckResult {
+        supported: true,
+        valid: issues.is_empty(),
+        issues,
+    })
+}
+
+fn collect_issues(node: Node, source: &[u8], issues: &mut Vec<SyntaxIssue>) {
+    if issues.len() >= MAX_ISSUES {
+        return;
+    }
+
+    let position = node.start_position();
+    if node.is_missing() {
+        issues.push(SyntaxIssue {
+            kind: "missing".to_string(),
+            message: format!("Missing `{}`", node.kind()),
+            line: position.row as u32 + 1,
+            column: position.column as u32 + 1,
+        });
+        return;
+    }
+    if node.is_error() {
+        let snippet: String = node
+            .utf8_text(source)
+            .unwrap_or("")
+            .chars()
+            .take(40)
+            .collect();
+        issues.push(SyntaxIssue {
+            kind: "error".to_string(),
+            message: format!("Unexpected `{}`", snippet.trim()),
+            line: position.row as u32 + 1,
+            column: position.column as u32 + 1,
+        });
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.has_error() || child.is_missing() {
+            collect_issues(child, source, issues);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn check_code_syntax(
+    content: String,
+    lang_id: String,
+    encoding: Option<PositionEncoding>,
+) -> Result<SyntaxCheckResult, String> {
+    let mut result = check_syntax(&content, &lang_id)?;
+    if encoding == Some(PositionEncoding::Utf16) {
+        let index = LineIndex::new(&content);
+        for issue in &mut result.issues {
+            issue.column = index
+                .to_utf16(Position {
+                    line: issue.line,
+                    column: issue.column,
+                })
+                .column;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_code() {
+        let result = check_syntax("fn main() { let x = 1; }", "rust").unwrap();
+        assert!(result.supported);
+        assert!(result.valid);
+    }
+
+    #[test]
+    fn test_invalid_code_reports_position() {
+        let result = check_syntax("def f(:\n    pass\n", "python").unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.issues[0].line, 1);
+    }
+
+    #[test]
+    fn test_missing_brace() {
+        let result = check_syntax("function f() {\n  return 1;\n", "typescript").unwrap();
+        assert!(!result.valid);
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        let result = check_syntax("anything", "cobol").unwrap();
+        assert!(!result.supported);
+        assert!(result.valid);
+    }
+}
@@ -0,0 +1,372 @@
+// Opt-in usage telemetry, aggregated locally
+//
+// Nothing is recorded until the user turns telemetry on. While it is on, the app counts
+// feature usage and buckets durations into fixed histograms; only those aggregates are
+// kept, in ~/.talkcody/telemetry.json. Metric names must be short identifiers, so no
+// prompt, path or other content can end up in a report. `get_telemetry_preview` returns
+// exactly the payload `upload_telemetry` would send, and `export_telemetry` writes it
+// to a file instead. Turning telemetry off discards everything collected so far.
+
+use crate::http_client;
+use crate::offline_mode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const UPLOAD_URL: &str = "https://api.talkcody.com/api/telemetry";
+const SCHEMA_VERSION: u32 = 1;
+const MAX_METRIC_NAME_LEN: usize = 64;
+/// Upper bounds (inclusive) of the duration buckets; one more bucket holds the rest
+pub const HISTOGRAM_BOUNDS_MS: &[u64] = &[10, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000];
+
+/// Serializes read-modify-write cycles of the store
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Histogram {
+    /// `counts[i]` is the number of samples at most `HISTOGRAM_BOUNDS_MS[i]`; the last
+    /// entry counts samples above every bound
+    pub counts: Vec<u64>,
+    pub total: u64,
+    pub sum_ms: u64,
+}
+
+impl Histogram {
+    fn record(&mut self, duration_ms: u64) {
+        if self.counts.len() != HISTOGRAM_BOUNDS_MS.len() + 1 {
+            self.counts = vec![0; HISTOGRAM_BOUNDS_MS.len() + 1];
+        }
+        let bucket = HISTOGRAM_BOUNDS_MS
+            .iter()
+            .position(|bound| duration_ms <= *bound)
+            .unwrap_or(HISTOGRAM_BOUNDS_MS.len());
+        self.counts[bucket] += 1;
+        self.total += 1;
+        self.sum_ms = self.sum_ms.saturating_add(duration_ms);
+    }
+
+    /// Remove the samples in `sent`, which were taken from this histogram earlier
+    fn subtract(&mut self, sent: &Histogram) {
+        for (count, sent_count) in self.counts.iter_mut().zip(&sent.counts) {
+            *count = count.saturating_sub(*sent_count);
+        }
+        self.total = self.total.saturating_sub(sent.total);
+        self.sum_ms = self.sum_ms.saturating_sub(sent.sum_ms);
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryStore {
+    pub enabled: bool,
+    /// Milliseconds since the Unix epoch when the current aggregation period began
+    pub period_start: i64,
+    pub features: BTreeMap<String, u64>,
+    pub histograms: BTreeMap<String, Histogram>,
+}
+
+/// The report as it would be uploaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryPayload {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub os: String,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub histogram_bounds_ms: Vec<u64>,
+    pub features: BTreeMap<String, u64>,
+    pub histograms: BTreeMap<String, Histogram>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryStatus {
+    pub enabled: bool,
+    pub period_start: i64,
+    pub feature_events: u64,
+    pub timing_samples: u64,
+}
+
+fn get_store_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody").join("telemetry.json"))
+}
+
+/// Metric names are identifiers like `summarize.code` or `lsp:start`, never free text
+pub fn validate_metric_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_METRIC_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "_.:-".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid metric name {:?}: use up to {} of a-z, 0-9, _ . : -",
+            name, MAX_METRIC_NAME_LEN
+        ))
+    }
+}
+
+impl TelemetryStore {
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Start a new, empty aggregation period
+    fn reset(&mut self, now: i64) {
+        self.period_start = now;
+        self.features.clear();
+        self.histograms.clear();
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool, now: i64) {
+        if enabled != self.enabled {
+            self.reset(now);
+        }
+        self.enabled = enabled;
+    }
+
+    /// Returns whether the event was counted (false while telemetry is off)
+    pub fn record_feature(&mut self, feature: &str) -> Result<bool, String> {
+        validate_metric_name(feature)?;
+        if !self.enabled {
+            return Ok(false);
+        }
+        *self.features.entry(feature.to_string()).or_insert(0) += 1;
+        Ok(true)
+    }
+
+    pub fn record_timing(&mut self, metric: &str, duration_ms: u64) -> Result<bool, String> {
+        validate_metric_name(metric)?;
+        if !self.enabled {
+            return Ok(false);
+        }
+        self.histograms
+            .entry(metric.to_string())
+            .or_default()
+            .record(duration_ms);
+        Ok(true)
+    }
+
+    pub fn payload(&self, app_version: &str, now: i64) -> TelemetryPayload {
+        TelemetryPayload {
+            schema_version: SCHEMA_VERSION,
+            app_version: app_version.to_string(),
+            os: std::env::consts::OS.to_string(),
+            period_start: self.period_start,
+            period_end: now,
+            histogram_bounds_ms: HISTOGRAM_BOUNDS_MS.to_vec(),
+            features: self.features.clone(),
+            histograms: self.histograms.clone(),
+        }
+    }
+
+    fn status(&self) -> TelemetryStatus {
+        TelemetryStatus {
+            enabled: self.enabled,
+            period_start: self.period_start,
+            feature_events: self.features.values().sum(),
+            timing_samples: self.histograms.values().map(|h| h.total).sum(),
+        }
+    }
+}
+
+/// Load the store, apply `change` and save it if it reports a modification
+fn update<T>(
+    change: impl FnOnce(&mut TelemetryStore) -> Result<(T, bool), String>,
+) -> Result<T, String> {
+    let path = get_store_path()?;
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = TelemetryStore::load(&path);
+    let (result, modified) = change(&mut store)?;
+    if modified {
+        store.save(&path)?;
+    }
+    Ok(result)
+}
+
+fn current_payload(app: &AppHandle) -> Result<Option<TelemetryPayload>, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let store = TelemetryStore::load(&get_store_path()?);
+    if !store.enabled {
+        return Ok(None);
+    }
+    let version = app.package_info().version.to_string();
+    Ok(Some(
+        store.payload(&version, chrono::Utc::now().timestamp_millis()),
+    ))
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_telemetry_status() -> Result<TelemetryStatus, String> {
+    Ok(TelemetryStore::load(&get_store_path()?).status())
+}
+
+/// Turn telemetry on or off. Either way the collected aggregates are discarded.
+#[tauri::command]
+pub fn set_telemetry_enabled(enabled: bool) -> Result<TelemetryStatus, String> {
+    let status = update(|store| {
+        store.set_enabled(enabled, chrono::Utc::now().timestamp_millis());
+        Ok((store.status(), true))
+    })?;
+    log::info!("Telemetry {}", if enabled { "enabled" } else { "disabled" });
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn record_feature_usage(feature: String) -> Result<bool, String> {
+    update(|store| {
+        store
+            .record_feature(&feature)
+            .map(|counted| (counted, counted))
+    })
+}
+
+#[tauri::command]
+pub fn record_timing_sample(metric: String, duration_ms: u64) -> Result<bool, String> {
+    update(|store| {
+        store
+            .record_timing(&metric, duration_ms)
+            .map(|counted| (counted, counted))
+    })
+}
+
+/// Exactly what `upload_telemetry` would send right now; None while telemetry is off
+#[tauri::command]
+pub fn get_telemetry_preview(app: AppHandle) -> Result<Option<TelemetryPayload>, String> {
+    current_payload(&app)
+}
+
+/// Write the current payload to `dest` as JSON
+#[tauri::command]
+pub fn export_telemetry(app: AppHandle, dest: String) -> Result<(), String> {
+    let payload = current_payload(&app)?.ok_or("Telemetry is disabled")?;
+    let json = serde_json::to_string_pretty(&payload).map_err(|e| e.to_string())?;
+    fs::write(&dest, json).map_err(|e| format!("Failed to write {}: {}", dest, e))
+}
+
+/// Send the current payload and start a new period. Events recorded while the
+/// upload is in flight are kept for the next one.
+#[tauri::command]
+pub async fn upload_telemetry(app: AppHandle) -> Result<TelemetryStatus, String> {
+    let payload = current_payload(&app)?.ok_or("Telemetry is disabled")?;
+    offline_mode::check_url(UPLOAD_URL, "telemetry upload")?;
+
+    let response = http_client::builder()?
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?
+        .post(UPLOAD_URL)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Telemetry upload failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Telemetry upload returned status {}",
+            response.status().as_u16()
+        ));
+    }
+
+    update(|store| {
+        // Subtract what was sent rather than clearing, so concurrent events survive
+        for (feature, count) in &payload.features {
+            if let Some(current) = store.features.get_mut(feature) {
+                *current = current.saturating_sub(*count);
+            }
+        }
+        store.features.retain(|_, count| *count > 0);
+        for (metric, sent) in &payload.histograms {
+            if let Some(current) = store.histograms.get_mut(metric) {
+                current.subtract(sent);
+            }
+        }
+        store.histograms.retain(|_, histogram| histogram.total > 0);
+        store.period_start = payload.period_end;
+        Ok((store.status(), true))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_nothing_is_recorded_until_enabled() {
+        let mut store = TelemetryStore::default();
+        assert!(!store.record_feature("summarize").unwrap());
+        assert!(!store.record_timing("summarize_ms", 20).unwrap());
+        assert!(store.features.is_empty() && store.histograms.is_empty());
+
+        store.set_enabled(true, 1_000);
+        assert!(store.record_feature("summarize").unwrap());
+        store.record_feature("summarize").unwrap();
+        assert_eq!(store.features["summarize"], 2);
+
+        // Turning it off discards the aggregates
+        store.set_enabled(false, 2_000);
+        assert!(store.features.is_empty());
+    }
+
+    #[test]
+    fn test_metric_names_carry_no_content() {
+        assert!(validate_metric_name("lsp:start").is_ok());
+        assert!(validate_metric_name("summary.rust_ms").is_ok());
+        assert!(validate_metric_name("").is_err());
+        assert!(validate_metric_name("/home/me/secret.txt").is_err());
+        assert!(validate_metric_name("Fix the login bug").is_err());
+        assert!(validate_metric_name(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let mut store = TelemetryStore::default();
+        store.set_enabled(true, 0);
+        for ms in [5, 10, 11, 20_000] {
+            store.record_timing("index_ms", ms).unwrap();
+        }
+        let histogram = &store.histograms["index_ms"];
+        assert_eq!(histogram.counts.len(), HISTOGRAM_BOUNDS_MS.len() + 1);
+        assert_eq!(histogram.counts[0], 2);
+        assert_eq!(histogram.counts[1], 1);
+        assert_eq!(*histogram.counts.last().unwrap(), 1);
+        assert_eq!(histogram.total, 4);
+        assert_eq!(histogram.sum_ms, 20_026);
+    }
+
+    #[test]
+    fn test_store_round_trip_and_payload() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("telemetry.json");
+        let mut store = TelemetryStore::default();
+        store.set_enabled(true, 100);
+        store.record_feature("fim").unwrap();
+        store.save(&path).unwrap();
+
+        let loaded = TelemetryStore::load(&path);
+        let payload = loaded.payload("1.2.3", 500);
+        assert_eq!(payload.period_start, 100);
+        assert_eq!(payload.period_end, 500);
+        assert_eq!(payload.features["fim"], 1);
+        assert_eq!(payload.app_version, "1.2.3");
+    }
+}
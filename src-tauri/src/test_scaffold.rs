@@ -0,0 +1,409 @@
+// Unit test scaffolding with framework detection
+//
+// Detects the project's test framework from its manifests, picks the conventional test
+// file location for the source file, assembles context (the symbol's definition, the
+// types it uses, and an existing test file to imitate) and asks the provider for tests.
+// The result is returned as a draft plus a unified diff; nothing is written to disk.
+
+use crate::code_navigation::{resolve_nav, CodeNavState, CodeNavigationService};
+use crate::doc_generation::find_definition_target;
+use crate::provider_client::{self, ChatMessage, ProviderConfig};
+use crate::text_diff;
+use crate::workspace_state::WorkspaceState;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::State;
+
+const PROVIDER_TIMEOUT_SECS: u64 = 90;
+const MAX_TYPE_DEFINITIONS: usize = 8;
+const MAX_EXAMPLE_CHARS: usize = 4_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestScaffold {
+    pub framework: String,
+    pub test_path: String,
+    pub is_new_file: bool,
+    /// Full content of the test file after the change
+    pub content: String,
+    pub diff: String,
+}
+
+fn type_name_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b[A-Z][A-Za-z0-9_]+\b").unwrap())
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_default()
+}
+
+/// Detect the test framework used for `lang_id` in the project at `root`
+pub fn detect_framework(root: &Path, lang_id: &str) -> String {
+    match lang_id {
+        "rust" => "cargo-test".to_string(),
+        "go" => "go-testing".to_string(),
+        "python" => {
+            let pyproject = read(&root.join("pyproject.toml"));
+            let requirements =
+                read(&root.join("requirements-dev.txt")) + &read(&root.join("requirements.txt"));
+            if root.join("pytest.ini").exists()
+                || root.join("conftest.py").exists()
+                || pyproject.contains("[tool.pytest")
+                || pyproject.contains("pytest")
+                || requirements.contains("pytest")
+            {
+                "pytest".to_string()
+            } else {
+                "unittest".to_string()
+            }
+        }
+        "java" => {
+            let build = read(&root.join("pom.xml"))
+                + &read(&root.join("build.gradle"))
+                + &read(&root.join("build.gradle.kts"));
+            if build.contains("junit-jupiter") || build.contains("junit5") {
+                "junit5".to_string()
+            } else {
+                "junit4".to_string()
+            }
+        }
+        "typescript" | "javascript" => {
+            let package = read(&root.join("package.json"));
+            for framework in ["vitest", "jest", "mocha"] {
+                if package.contains(&format!("\"{}\"", framework)) {
+                    return framework.to_string();
+                }
+            }
+            "vitest".to_string()
+        }
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Conventional location of the tests for `source`
+pub fn test_file_path(root: &Path, source: &Path, lang_id: &str) -> PathBuf {
+    let dir = source.parent().unwrap_or(root);
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = source
+        .extension()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match lang_id {
+        // Inline `#[cfg(test)] mod tests`
+        "rust" => source.to_path_buf(),
+        "go" => dir.join(format!("{}_test.go", stem)),
+        "python" => {
+            let tests_dir = root.join("tests");
+            if tests_dir.is_dir() {
+                tests_dir.join(format!("test_{}.py", stem))
+            } else {
+                dir.join(format!("test_{}.py", stem))
+            }
+        }
+        "java" => {
+            let path = source.to_string_lossy();
+            if path.contains("src/main/java") {
+                PathBuf::from(path.replace("src/main/java", "src/test/java"))
+                    .with_file_name(format!("{}Test.java", stem))
+            } else {
+                dir.join(format!("{}Test.java", stem))
+            }
+        }
+        _ => {
+            let tests_dir = dir.join("__tests__");
+            if tests_dir.is_dir() {
+                tests_dir.join(format!("{}.test.{}", stem, ext))
+            } else {
+                dir.join(format!("{}.test.{}", stem, ext))
+            }
+        }
+    }
+}
+
+/// Indent generated code one level for placement inside `mod tests`
+fn indent(code: &str) -> String {
+    code.lines()
+        .map(|l| {
+            if l.is_empty() {
+                "\n".to_string()
+            } else {
+                format!("    {}\n", l)
+            }
+        })
+        .collect()
+}
+
+/// Merge generated test code into the (possibly empty) test file
+pub fn merge_test_code(
+    existing: &str,
+    generated: &str,
+    lang_id: &str,
+    is_new_file: bool,
+) -> String {
+    let generated = generated.trim_matches('\n');
+    if lang_id == "rust" {
+        if existing.contains("#[cfg(test)]") {
+            // Tests module closes at the last brace of the file
+            if let Some(pos) = existing.rfind('}') {
+                let indented = indent(generated);
+                let head = existing[..pos].trim_end_matches([' ', '\n']);
+                return format!("{}\n\n{}{}", head, indented, &existing[pos..]);
+            }
+        }
+        let indented = indent(generated);
+        return format!(
+            "{}\n\n#[cfg(test)]\nmod tests {{\n    use super::*;\n\n{}}}\n",
+            existing.trim_end(),
+            indented
+        );
+    }
+
+    if is_new_file || existing.trim().is_empty() {
+        format!("{}\n", generated)
+    } else {
+        format!("{}\n\n{}\n", existing.trim_end(), generated)
+    }
+}
+
+/// An existing test file near `test_path` to use as a style example
+fn find_example_test(test_path: &Path, lang_id: &str) -> Option<String> {
+    if test_path.exists() {
+        return Some(read(test_path));
+    }
+    let dir = test_path.parent()?;
+    let pattern = match lang_id {
+        "go" => "_test.go",
+        "python" => "test_",
+        "java" => "Test.java",
+        _ => ".test.",
+    };
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy().contains(pattern))
+                .unwrap_or(false)
+        })
+        .map(|p| read(&p))
+}
+
+fn instructions(framework: &str, lang_id: &str, is_new_file: bool) -> String {
+    let shape = if lang_id == "rust" {
+        "Reply with test functions only (annotated with #[test]); they are placed inside the module's `mod tests` which already has `use super::*;`."
+    } else if is_new_file {
+        "Reply with the complete test file, including imports."
+    } else {
+        "Reply with the new test code only; it is appended to the existing test file, whose imports you can rely on."
+    };
+    format!(
+        "You write unit tests using {} for {} code. Cover normal behaviour, edge cases and \
+         error paths. Match the style of the example tests when one is given. {} \
+         No explanations, no markdown fences.",
+        framework, lang_id, shape
+    )
+}
+
+#[tauri::command]
+pub async fn scaffold_tests(
+    nav_state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    provider: ProviderConfig,
+    root_path: String,
+    path: String,
+    symbol: String,
+) -> Result<TestScaffold, String> {
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&path)
+        .ok_or_else(|| format!("Unsupported file type: {}", path))?;
+    let target = find_definition_target(&content, &lang_id, &symbol)
+        .ok_or_else(|| format!("Definition of {} not found in {}", symbol, path))?;
+
+    let root = Path::new(&root_path);
+    let framework = detect_framework(root, &lang_id);
+    let test_path = test_file_path(root, Path::new(&path), &lang_id);
+    let existing = read(&test_path);
+    let is_new_file = lang_id != "rust" && !test_path.exists();
+
+    // Definitions of the types the symbol mentions
+    let nav = resolve_nav(&nav_state, &workspaces, workspace_id.as_deref())?;
+    let type_definitions: Vec<String> = {
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        let lang_family = CodeNavigationService::get_lang_family(&lang_id);
+        let names: BTreeSet<&str> = type_name_regex()
+            .find_iter(&target.text)
+            .map(|m| m.as_str())
+            .filter(|name| *name != symbol)
+            .collect();
+        names
+            .into_iter()
+            .flat_map(|name| service.find_definition(name, lang_family))
+            .take(MAX_TYPE_DEFINITIONS)
+            .filter_map(|def| {
+                let file = fs::read_to_string(&def.file_path).ok()?;
+                find_definition_target(&file, &lang_id, &def.name).map(|t| t.text)
+            })
+            .collect()
+    };
+
+    let mut prompt = format!(
+        "Source file: {}\nTest file: {}\n\nDefinition under test:\n{}\n",
+        path,
+        test_path.display(),
+        target.text
+    );
+    if !type_definitions.is_empty() {
+        prompt.push_str(&format!(
+            "\nTypes it uses:\n{}\n",
+            type_definitions.join("\n\n")
+        ));
+    }
+    if let Some(example) = find_example_test(&test_path, &lang_id).filter(|e| !e.trim().is_empty())
+    {
+        let example: String = example.chars().take(MAX_EXAMPLE_CHARS).collect();
+        prompt.push_str(&format!(
+            "\nExample tests from this project:\n{}\n",
+            example
+        ));
+    }
+
+    let messages = [
+        ChatMessage::system(instructions(&framework, &lang_id, is_new_file)),
+        ChatMessage::user(prompt),
+    ];
+    let answer = provider_client::chat_completion(
+        &provider,
+        &messages,
+        Duration::from_secs(PROVIDER_TIMEOUT_SECS),
+    )
+    .await?;
+    let generated = provider_client::strip_code_fence(&answer);
+
+    let new_content = merge_test_code(&existing, &generated, &lang_id, is_new_file);
+    let test_path_str = test_path.to_string_lossy().to_string();
+    let diff = text_diff::unified_diff(
+        &existing,
+        &new_content,
+        if is_new_file {
+            "/dev/null"
+        } else {
+            &test_path_str
+        },
+        &test_path_str,
+        3,
+    );
+
+    Ok(TestScaffold {
+        framework,
+        test_path: test_path_str,
+        is_new_file,
+        content: new_content,
+        diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_framework() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        assert_eq!(detect_framework(root, "python"), "unittest");
+        fs::write(root.join("conftest.py"), "").unwrap();
+        assert_eq!(detect_framework(root, "python"), "pytest");
+
+        fs::write(
+            root.join("package.json"),
+            r#"{"devDependencies": {"jest": "^29.0.0"}}"#,
+        )
+        .unwrap();
+        assert_eq!(detect_framework(root, "typescript"), "jest");
+        assert_eq!(detect_framework(root, "rust"), "cargo-test");
+    }
+
+    #[test]
+    fn test_test_file_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+
+        assert_eq!(
+            test_file_path(root, &src.join("util.ts"), "typescript"),
+            src.join("util.test.ts")
+        );
+        assert_eq!(
+            test_file_path(root, &src.join("db.go"), "go"),
+            src.join("db_test.go")
+        );
+        assert_eq!(
+            test_file_path(root, &src.join("lib.rs"), "rust"),
+            src.join("lib.rs")
+        );
+
+        fs::create_dir_all(root.join("tests")).unwrap();
+        assert_eq!(
+            test_file_path(root, &src.join("parser.py"), "python"),
+            root.join("tests/test_parser.py")
+        );
+
+        let java = root.join("src/main/java/com/acme/Widget.java");
+        assert_eq!(
+            test_file_path(root, &java, "java"),
+            root.join("src/test/java/com/acme/WidgetTest.java")
+        );
+    }
+
+    #[test]
+    fn test_merge_rust_new_module() {
+        let merged = merge_test_code(
+            "fn a() {}\n",
+            "#[test]\nfn test_a() {\n    a();\n}",
+            "rust",
+            false,
+        );
+        assert_eq!(
+            merged,
+            "fn a() {}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn test_a() {\n        a();\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_rust_existing_module() {
+        let existing = "fn a() {}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n}\n";
+        let merged = merge_test_code(existing, "#[test]\nfn test_a() {}", "rust", false);
+        assert_eq!(
+            merged,
+            "fn a() {}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn test_a() {}\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_appends_to_existing_file() {
+        assert_eq!(
+            merge_test_code("import x\n", "def test_b():\n    pass", "python", false),
+            "import x\n\ndef test_b():\n    pass\n"
+        );
+        assert_eq!(
+            merge_test_code("", "test('a', () => {});", "typescript", true),
+            "test('a', () => {});\n"
+        );
+    }
+}
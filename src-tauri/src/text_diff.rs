@@ -0,0 +1,245 @@
+// Line-based text diffing shared by the scratchpad, merge and preview features.
+// Uses an LCS table over the region between the common prefix and suffix, falling
+// back to a whole-block replacement when that region is too large to tabulate.
+
+use serde::{Deserialize, Serialize};
+
+/// Largest LCS table (old_lines × new_lines) we are willing to allocate
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", content = "line", rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal(String),
+    Insert(String),
+    Delete(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Compute a line diff between two texts
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffOp> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    diff_slices(&old_lines, &new_lines)
+}
+
+/// Compute a diff between two sequences of lines
+pub fn diff_slices(old: &[&str], new: &[&str]) -> Vec<DiffOp> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<DiffOp> = old[..prefix]
+        .iter()
+        .map(|l| DiffOp::Equal(l.to_string()))
+        .collect();
+
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_LCS_CELLS {
+        ops.extend(old_mid.iter().map(|l| DiffOp::Delete(l.to_string())));
+        ops.extend(new_mid.iter().map(|l| DiffOp::Insert(l.to_string())));
+    } else {
+        ops.extend(lcs_diff(old_mid, new_mid));
+    }
+
+    ops.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|l| DiffOp::Equal(l.to_string())),
+    );
+    ops
+}
+
+fn lcs_diff(old: &[&str], new: &[&str]) -> Vec<DiffOp> {
+    let (n, m) = (old.len(), new.len());
+    // table[i][j] = LCS length of old[i..] and new[j..]
+    let mut table = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if old[i] == new[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push(DiffOp::Equal(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            ops.push(DiffOp::Delete(old[i].to_string()));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(new[j].to_string()));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|l| DiffOp::Delete(l.to_string())));
+    ops.extend(new[j..].iter().map(|l| DiffOp::Insert(l.to_string())));
+    ops
+}
+
+/// Count added and removed lines
+pub fn diff_stats(ops: &[DiffOp]) -> DiffStats {
+    DiffStats {
+        added: ops
+            .iter()
+            .filter(|o| matches!(o, DiffOp::Insert(_)))
+            .count(),
+        removed: ops
+            .iter()
+            .filter(|o| matches!(o, DiffOp::Delete(_)))
+            .count(),
+    }
+}
+
+/// Render a unified diff with `context` lines around each change.
+/// Returns an empty string when the texts are identical.
+pub fn unified_diff(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    context: usize,
+) -> String {
+    let ops = diff_lines(old, new);
+    if ops.iter().all(|o| matches!(o, DiffOp::Equal(_))) {
+        return String::new();
+    }
+
+    // Positions (index into ops) of every change
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, o)| !matches!(o, DiffOp::Equal(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    // Group changes into hunks whose context windows overlap
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &idx in &changes {
+        let start = idx.saturating_sub(context);
+        let end = (idx + context + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+    for (start, end) in hunks {
+        // Line numbers (1-based) of the hunk start in old and new
+        let (mut old_line, mut new_line) = (1, 1);
+        for op in &ops[..start] {
+            match op {
+                DiffOp::Equal(_) => {
+                    old_line += 1;
+                    new_line += 1;
+                }
+                DiffOp::Delete(_) => old_line += 1,
+                DiffOp::Insert(_) => new_line += 1,
+            }
+        }
+        let hunk = &ops[start..end];
+        let old_count = hunk
+            .iter()
+            .filter(|o| !matches!(o, DiffOp::Insert(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|o| !matches!(o, DiffOp::Delete(_)))
+            .count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            if old_count == 0 {
+                old_line - 1
+            } else {
+                old_line
+            },
+            old_count,
+            if new_count == 0 {
+                new_line - 1
+            } else {
+                new_line
+            },
+            new_count
+        ));
+        for op in hunk {
+            match op {
+                DiffOp::Equal(l) => out.push_str(&format!(" {}\n", l)),
+                DiffOp::Delete(l) => out.push_str(&format!("-{}\n", l)),
+                DiffOp::Insert(l) => out.push_str(&format!("+{}\n", l)),
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines_identical() {
+        let ops = diff_lines("a\nb\n", "a\nb\n");
+        assert!(ops.iter().all(|o| matches!(o, DiffOp::Equal(_))));
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "a", "b", 3), "");
+    }
+
+    #[test]
+    fn test_diff_lines_insert_and_delete() {
+        let ops = diff_lines("a\nb\nc", "a\nx\nc\nd");
+        assert_eq!(
+            ops,
+            vec![
+                DiffOp::Equal("a".to_string()),
+                DiffOp::Delete("b".to_string()),
+                DiffOp::Insert("x".to_string()),
+                DiffOp::Equal("c".to_string()),
+                DiffOp::Insert("d".to_string()),
+            ]
+        );
+        let stats = diff_stats(&ops);
+        assert_eq!(stats.added, 2);
+        assert_eq!(stats.removed, 1);
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old = (1..=20)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let new = old.replace("\n5\n", "\nfive\n").replace("\n18\n", "\n");
+        let diff = unified_diff(&old, &new, "a/f", "b/f", 1);
+        assert!(diff.starts_with("--- a/f\n+++ b/f\n"));
+        assert!(diff.contains("@@ -4,3 +4,3 @@\n 4\n-5\n+five\n 6\n"));
+        assert!(diff.contains("@@ -17,3 +17,2 @@\n 17\n-18\n 19\n"));
+    }
+
+    #[test]
+    fn test_unified_diff_from_empty() {
+        let diff = unified_diff("", "a\nb", "old", "new", 3);
+        assert!(diff.contains("@@ -0,0 +1,2 @@\n+a\n+b\n"));
+    }
+}
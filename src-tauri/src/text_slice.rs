@@ -0,0 +1,212 @@
+// Unicode-safe slicing for signature extraction
+//
+// The summarizer cuts definitions at delimiters: the body's `{`, Python's `:`, an arrow's
+// `=>`. A plain `find` stops at the first match even when it sits inside a string literal
+// or a parameter list (`fn f(s = "{é}")`, `def f(x: int):`), and offsets computed on one
+// string and reused on another can land inside a multibyte char and panic. These helpers
+// clamp offsets to a char boundary and only match delimiters in code at the top
+// bracket level.
+
+/// Largest char boundary in `text` at or before byte `offset`
+pub fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// `text[..end]`, with `end` moved back to a char boundary
+pub fn slice_to(text: &str, end: usize) -> &str {
+    &text[..floor_char_boundary(text, end)]
+}
+
+/// Characters that open a string literal in `lang_id`. Rust is left without `'` because
+/// lifetimes (`&'a str`) would open a literal that never closes.
+fn quote_chars(lang_id: &str) -> &'static [char] {
+    match lang_id {
+        "rust" => &['"'],
+        // Swift has no character literals
+        "swift" => &['"'],
+        // Primes are part of Haskell names (`foldl'`)
+        "haskell" => &['"'],
+        // Type variables (`'a list`) are not character literals
+        "ocaml" | "ocaml_interface" => &['"'],
+        // `'` is also Julia's adjoint operator (`A'`)
+        "julia" => &['"'],
+        // GraphQL strings are double-quoted; apostrophes only appear in prose
+        "graphql" => &['"'],
+        "typescript" | "javascript" | "tsx" | "jsx" | "go" => &['"', '\'', '`'],
+        _ => &['"', '\''],
+    }
+}
+
+fn line_comment(lang_id: &str) -> &'static str {
+    match lang_id {
+        "python" | "ruby" | "elixir" | "powershell" | "graphql" | "julia" => "#",
+        "haskell" => "--",
+        _ => "//",
+    }
+}
+
+/// Byte offset of the first `needle` in `text` that is outside string literals and line
+/// comments, and not nested in `(...)` or `[...]`
+pub fn find_in_code(text: &str, needle: &str, lang_id: &str) -> Option<usize> {
+    let quotes = quote_chars(lang_id);
+    let comment = line_comment(lang_id);
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut chars = text.char_indices();
+
+    while let Some((offset, ch)) = chars.next() {
+        if let Some(open) = quote {
+            if ch == '\\' {
+                chars.next();
+            } else if ch == open {
+                quote = None;
+            }
+            continue;
+        }
+        if depth == 0 && text[offset..].starts_with(needle) {
+            return Some(offset);
+        }
+        if text[offset..].starts_with(comment) {
+            // Skip to the end of the line
+            for (_, c) in chars.by_ref() {
+                if c == '\n' {
+                    break;
+                }
+            }
+            continue;
+        }
+        match ch {
+            c if quotes.contains(&c) => quote = Some(c),
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `{` minus `}` on a line of code, leaving out braces in string literals and after a
+/// line comment. For the block-structured files that are scanned line by line instead
+/// of parsed (GraphQL, protobuf, Gradle).
+pub fn brace_balance(line: &str, lang_id: &str) -> i32 {
+    let quotes = quote_chars(lang_id);
+    let comment = line_comment(lang_id);
+    let mut balance = 0;
+    let mut quote: Option<char> = None;
+    let mut chars = line.char_indices();
+
+    while let Some((offset, ch)) = chars.next() {
+        if let Some(open) = quote {
+            if ch == '\\' {
+                chars.next();
+            } else if ch == open {
+                quote = None;
+            }
+            continue;
+        }
+        if line[offset..].starts_with(comment) {
+            break;
+        }
+        match ch {
+            c if quotes.contains(&c) => quote = Some(c),
+            '{' => balance += 1,
+            '}' => balance -= 1,
+            _ => {}
+        }
+    }
+    balance
+}
+
+/// `text` up to the first `needle` found by `find_in_code`
+pub fn before_in_code<'a>(text: &'a str, needle: &str, lang_id: &str) -> Option<&'a str> {
+    find_in_code(text, needle, lang_id).map(|pos| slice_to(text, pos))
+}
+
+/// `text` up to and including the first `needle` found by `find_in_code`
+pub fn through_in_code<'a>(text: &'a str, needle: &str, lang_id: &str) -> Option<&'a str> {
+    find_in_code(text, needle, lang_id).map(|pos| slice_to(text, pos + needle.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boundaries_inside_multibyte_chars() {
+        // "名" is 3 bytes, "😀" is 4
+        let text = "a名😀b";
+        assert_eq!(slice_to(text, 2), "a");
+        assert_eq!(slice_to(text, 4), "a名");
+        assert_eq!(slice_to(text, 100), text);
+        assert_eq!(slice_to(text, 0), "");
+        assert_eq!(floor_char_boundary(text, 6), 4);
+    }
+
+    #[test]
+    fn test_find_in_code_skips_literals_and_brackets() {
+        let ts = "function fmt(s = \"{é}\", o = { a: 1 }): string {";
+        assert_eq!(
+            before_in_code(ts, "{", "typescript"),
+            Some("function fmt(s = \"{é}\", o = { a: 1 }): string ")
+        );
+        let template = "const f = (x = `${y}`) => x";
+        assert_eq!(
+            through_in_code(template, "=>", "typescript"),
+            Some("const f = (x = `${y}`) =>")
+        );
+        let python = "def greet(name: str = 'wörld:') -> Dict[str, int]:";
+        assert_eq!(through_in_code(python, ":", "python"), Some(python));
+        // Lifetimes are not quotes, escaped quotes do not close a literal
+        let rust = "fn parse<'a>(s: &'a str, sep: &str) -> &'a str {";
+        assert_eq!(
+            before_in_code(rust, "{", "rust"),
+            Some("fn parse<'a>(s: &'a str, sep: &str) -> &'a str ")
+        );
+        assert_eq!(find_in_code("f(\"\\\"{\") {", "{", "go"), Some(9));
+        assert_eq!(find_in_code("// {\nx", "{", "rust"), None);
+        assert_eq!(find_in_code("\"unterminated {", "{", "rust"), None);
+    }
+
+    #[test]
+    fn test_brace_balance_skips_literals_and_comments() {
+        assert_eq!(brace_balance("task copy(type: Copy) {", "gradle"), 1);
+        assert_eq!(brace_balance("into \"${buildDir}/assets\" }", "gradle"), -1);
+        assert_eq!(brace_balance("message Order { // {", "protobuf"), 1);
+        assert_eq!(
+            brace_balance("type User @key(fields: \"{ id }\") {", "graphql"),
+            1
+        );
+        assert_eq!(brace_balance("# don't {", "graphql"), 0);
+    }
+
+    #[test]
+    fn test_multilingual_identifiers() {
+        let cases = [
+            ("fn größe(ä: u8) -> u8 {", "rust", "fn größe(ä: u8) -> u8 "),
+            ("func 计算(值 int) int {", "go", "func 计算(值 int) int "),
+            (
+                "function привет(имя) {",
+                "javascript",
+                "function привет(имя) ",
+            ),
+            ("def 挨拶(名前):", "python", "def 挨拶(名前):"),
+            (
+                "public void données(String é) {",
+                "java",
+                "public void données(String é) ",
+            ),
+        ];
+        for (text, lang, expected) in cases {
+            let cut = if lang == "python" {
+                through_in_code(text, ":", lang)
+            } else {
+                before_in_code(text, "{", lang)
+            };
+            assert_eq!(cut, Some(expected), "{}", lang);
+        }
+    }
+}
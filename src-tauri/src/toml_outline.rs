@@ -0,0 +1,248 @@
+// TOML table summaries
+//
+// Manifests like Cargo.toml and pyproject.toml are read for their structure and their
+// dependency versions, not for feature lists or long descriptions. The summary keeps
+// every table header and key, scalars up to a length, and short arrays; long arrays
+// become `[... N items]` and inline tables keep only their `version` and elide the
+// rest, so `serde = { version = "1", features = [...] }` still shows its constraint.
+
+use crate::code_navigation::CodeSummary;
+use crate::text_slice::slice_to;
+use tree_sitter::{Node, Parser};
+
+/// Arrays with more items than this are collapsed
+const SHORT_ARRAY: usize = 3;
+/// Values longer than this many bytes are cut
+const MAX_VALUE_LEN: usize = 60;
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn named_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|n| n.kind() != "comment")
+        .collect()
+}
+
+fn is_key(kind: &str) -> bool {
+    matches!(kind, "bare_key" | "dotted_key" | "quoted_key")
+}
+
+fn truncate(value: &str) -> String {
+    let value = collapse(value);
+    if value.len() <= MAX_VALUE_LEN {
+        return value;
+    }
+    format!("{}…", slice_to(&value, MAX_VALUE_LEN))
+}
+
+/// Key and value of a `key = value` pair
+fn pair_parts(pair: Node) -> Option<(Node, Node)> {
+    let children = named_children(pair);
+    Some((*children.first()?, *children.last()?))
+}
+
+/// Keys that carry a version constraint: `version`, `rust-version`, `requires-python`
+fn is_version_key(key: &str) -> bool {
+    let key = key.trim_matches('"').to_lowercase();
+    key.ends_with("version") || key.starts_with("requires")
+}
+
+fn render_value(value: Node, source: &str) -> String {
+    match value.kind() {
+        "array" => {
+            let items = named_children(value);
+            let nested = items
+                .iter()
+                .any(|item| matches!(item.kind(), "array" | "inline_table"));
+            if items.len() > SHORT_ARRAY || nested {
+                format!("[... {} items]", items.len())
+            } else {
+                truncate(text(value, source))
+            }
+        }
+        "inline_table" => {
+            let pairs = named_children(value);
+            let mut kept: Vec<String> = pairs
+                .iter()
+                .filter_map(|pair| pair_parts(*pair))
+                .filter(|(key, _)| is_version_key(text(*key, source)))
+                .map(|(key, value)| {
+                    format!("{} = {}", text(key, source), render_value(value, source))
+                })
+                .collect();
+            if kept.len() < pairs.len() {
+                kept.push("...".to_string());
+            }
+            if kept.is_empty() {
+                "{}".to_string()
+            } else {
+                format!("{{ {} }}", kept.join(", "))
+            }
+        }
+        _ => truncate(text(value, source)),
+    }
+}
+
+/// Header of a `[table]` or `[[array.of.tables]]`
+fn table_header(table: Node, source: &str) -> String {
+    let key = named_children(table)
+        .into_iter()
+        .find(|n| is_key(n.kind()))
+        .map(|k| text(k, source).to_string())
+        .unwrap_or_default();
+    if table.kind() == "table_array_element" {
+        format!("[[{}]]", key)
+    } else {
+        format!("[{}]", key)
+    }
+}
+
+fn outline_pairs(node: Node, source: &str, lines: &mut Vec<String>) {
+    for pair in named_children(node)
+        .into_iter()
+        .filter(|n| n.kind() == "pair")
+    {
+        if let Some((key, value)) = pair_parts(pair) {
+            lines.push(format!(
+                "{} = {}",
+                text(key, source),
+                render_value(value, source)
+            ));
+        }
+    }
+}
+
+/// Table and key outline of a TOML document
+pub fn toml_outline(content: &str) -> Result<String, String> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_toml_ng::LANGUAGE.into())
+        .map_err(|e| format!("Failed to set language for toml: {:?}", e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or("Failed to parse toml content")?;
+
+    let root = tree.root_node();
+    let mut lines = Vec::new();
+    outline_pairs(root, content, &mut lines);
+    for table in named_children(root)
+        .into_iter()
+        .filter(|n| matches!(n.kind(), "table" | "table_array_element"))
+    {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(table_header(table, content));
+        outline_pairs(table, content, &mut lines);
+    }
+    Ok(lines.join("\n"))
+}
+
+pub fn summarize_toml(content: &str) -> Result<CodeSummary, String> {
+    let original_lines = content.lines().count();
+    let outline = toml_outline(content)?;
+    if outline.is_empty() {
+        return Ok(CodeSummary::unchanged(
+            content.to_string(),
+            original_lines,
+            "toml".to_string(),
+            "no keys",
+        ));
+    }
+    Ok(CodeSummary {
+        success: true,
+        summary: format!(
+            "[COMPRESSED: Original {} lines → tables and keys, long arrays and inline tables elided]\n\n{}",
+            original_lines, outline
+        ),
+        original_lines,
+        lang_id: "toml".to_string(),
+        truncated: None,
+        skipped_reason: None,
+        omitted_symbols: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cargo_manifest() {
+        let manifest = r#"# Workspace member
+[package]
+name = "talkcody"
+version = "0.1.0"
+rust-version = "1.77"
+description = "A long description that keeps going well past the point where anyone reads it"
+keywords = ["ai", "editor"]
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+tokio = { version = "1", features = ["rt-multi-thread", "macros", "fs", "process"] }
+local-crate = { path = "../local" }
+regex = "1.10"
+
+[[bin]]
+name = "cli"
+targets = ["x86_64", "aarch64", "riscv64", "wasm32"]
+"#;
+        assert_eq!(
+            toml_outline(manifest).unwrap(),
+            [
+                "[package]",
+                "name = \"talkcody\"",
+                "version = \"0.1.0\"",
+                "rust-version = \"1.77\"",
+                "description = \"A long description that keeps going well past the point whe…",
+                "keywords = [\"ai\", \"editor\"]",
+                "",
+                "[dependencies]",
+                "serde = { version = \"1.0\", ... }",
+                "tokio = { version = \"1\", ... }",
+                "local-crate = { ... }",
+                "regex = \"1.10\"",
+                "",
+                "[[bin]]",
+                "name = \"cli\"",
+                "targets = [... 4 items]",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_summarize_pyproject() {
+        let pyproject = r#"[project]
+name = "tool"
+requires-python = ">=3.10"
+dependencies = [
+    "httpx>=0.27",
+]
+
+[tool.ruff.lint]
+select = ["E", "F"]
+"#;
+        let result = summarize_toml(pyproject).unwrap();
+        assert!(result.success);
+        assert!(result
+            .summary
+            .starts_with("[COMPRESSED: Original 10 lines → tables and keys"));
+        assert!(result.summary.contains("requires-python = \">=3.10\""));
+        assert!(result
+            .summary
+            .contains("dependencies = [ \"httpx>=0.27\", ]"));
+        assert!(result
+            .summary
+            .ends_with("[tool.ruff.lint]\nselect = [\"E\", \"F\"]"));
+
+        assert!(!summarize_toml("# empty\n").unwrap().success);
+    }
+}
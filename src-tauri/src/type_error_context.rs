@@ -0,0 +1,329 @@
+// Type error explanation context
+//
+// Given a compiler diagnostic, gathers what a model needs to explain it: the function
+// enclosing the failing line, the definitions of the types named in the message (looked
+// up in the code navigation index) and the impl/implements sites of those types. The
+// pieces are also rendered into one compact prompt payload.
+
+use crate::code_navigation::{resolve_nav, CodeNavState, CodeNavigationService};
+use crate::doc_generation::find_definition_target;
+use crate::lint::LintDiagnostic;
+use crate::next_edit::enclosing_symbol;
+use crate::workspace_state::WorkspaceState;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::sync::OnceLock;
+use tauri::State;
+
+const MAX_TYPES: usize = 6;
+const MAX_IMPLS_PER_TYPE: usize = 5;
+/// Longer excerpts are cut to keep the payload compact
+const MAX_EXCERPT_LINES: usize = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeExcerpt {
+    pub name: String,
+    pub path: String,
+    pub start_line: u32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImplSite {
+    pub type_name: String,
+    pub path: String,
+    pub line: u32,
+    pub header: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeErrorContext {
+    pub file_path: String,
+    pub diagnostic: LintDiagnostic,
+    pub failing_line: String,
+    pub enclosing_function: Option<CodeExcerpt>,
+    pub type_definitions: Vec<CodeExcerpt>,
+    pub impls: Vec<ImplSite>,
+    pub prompt: String,
+}
+
+fn quoted_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"`([^`]+)`|'([^']+)'|"([^"]+)""#).unwrap())
+}
+
+fn type_name_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b[A-Z][A-Za-z0-9_]*\b").unwrap())
+}
+
+/// Type names mentioned in a diagnostic message, in order of appearance. Quoted spans
+/// (`Foo<Bar>`, 'Foo') are preferred; unquoted messages fall back to the whole text.
+pub fn extract_type_names(message: &str) -> Vec<String> {
+    let quoted: Vec<&str> = quoted_regex()
+        .captures_iter(message)
+        .filter_map(|c| c.get(1).or(c.get(2)).or(c.get(3)))
+        .map(|m| m.as_str())
+        .collect();
+    let sources = if quoted.is_empty() {
+        vec![message]
+    } else {
+        quoted
+    };
+
+    let mut seen = HashSet::new();
+    sources
+        .into_iter()
+        .flat_map(|s| type_name_regex().find_iter(s))
+        .map(|m| m.as_str().to_string())
+        .filter(|name| name != "Self" && seen.insert(name.clone()))
+        .collect()
+}
+
+/// Whether `line` declares an implementation involving `type_name`
+pub fn is_impl_header(line: &str, type_name: &str) -> bool {
+    let trimmed = line.trim_start();
+    let word = Regex::new(&format!(r"\b{}\b", regex::escape(type_name))).unwrap();
+    if !word.is_match(trimmed) {
+        return false;
+    }
+    trimmed.starts_with("impl ")
+        || trimmed.starts_with("impl<")
+        || trimmed.starts_with("unsafe impl")
+        || ((trimmed.contains(" implements ") || trimmed.contains(" extends "))
+            && trimmed.contains("class "))
+}
+
+fn truncate_lines(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= MAX_EXCERPT_LINES {
+        return text.to_string();
+    }
+    format!(
+        "{}\n    // ... {} more lines",
+        lines[..MAX_EXCERPT_LINES].join("\n"),
+        lines.len() - MAX_EXCERPT_LINES
+    )
+}
+
+/// Render the gathered context into a single prompt payload
+pub fn render_prompt(context: &TypeErrorContext) -> String {
+    let diagnostic = &context.diagnostic;
+    let mut prompt = format!(
+        "Explain this type error and how to fix it.\n\n{}:{}:{}: {}{}\n    {}\n",
+        context.file_path,
+        diagnostic.line,
+        diagnostic.column,
+        diagnostic
+            .code
+            .as_ref()
+            .map(|c| format!("[{}] ", c))
+            .unwrap_or_default(),
+        diagnostic.message,
+        context.failing_line.trim()
+    );
+    if let Some(function) = &context.enclosing_function {
+        prompt.push_str(&format!(
+            "\nEnclosing function {} ({}:{}):\n{}\n",
+            function.name, function.path, function.start_line, function.text
+        ));
+    }
+    for definition in &context.type_definitions {
+        prompt.push_str(&format!(
+            "\nType {} ({}:{}):\n{}\n",
+            definition.name, definition.path, definition.start_line, definition.text
+        ));
+    }
+    if !context.impls.is_empty() {
+        prompt.push_str("\nRelated impls:\n");
+        for site in &context.impls {
+            prompt.push_str(&format!(
+                "{}:{}: {}\n",
+                site.path,
+                site.line,
+                site.header.trim()
+            ));
+        }
+    }
+    prompt
+}
+
+#[tauri::command]
+pub async fn build_type_error_context(
+    nav_state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    root_path: String,
+    file_path: String,
+    diagnostic: LintDiagnostic,
+) -> Result<TypeErrorContext, String> {
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path, e))?;
+    let lang_id = CodeNavigationService::get_lang_id_from_path(&file_path).unwrap_or_default();
+    let lines: Vec<&str> = content.lines().collect();
+    let failing_line = lines
+        .get(diagnostic.line.saturating_sub(1) as usize)
+        .map(|l| l.to_string())
+        .unwrap_or_default();
+
+    let enclosing_function =
+        enclosing_symbol(&content, &lang_id, diagnostic.line).map(|(name, start, end)| {
+            let text = lines
+                .get(start.saturating_sub(1) as usize..(end as usize).min(lines.len()))
+                .map(|l| l.join("\n"))
+                .unwrap_or_default();
+            CodeExcerpt {
+                name,
+                path: file_path.clone(),
+                start_line: start,
+                text: truncate_lines(&text),
+            }
+        });
+
+    let nav = resolve_nav(&nav_state, &workspaces, workspace_id.as_deref())?;
+    let service = nav
+        .read()
+        .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+    let lang_family = CodeNavigationService::get_lang_family(&lang_id);
+
+    let mut type_definitions = Vec::new();
+    let mut impls = Vec::new();
+    for type_name in extract_type_names(&diagnostic.message)
+        .into_iter()
+        .take(MAX_TYPES)
+    {
+        for definition in service.find_definition(&type_name, lang_family) {
+            let Ok(source) = fs::read_to_string(&definition.file_path) else {
+                continue;
+            };
+            if let Some(target) = find_definition_target(&source, &lang_id, &type_name) {
+                type_definitions.push(CodeExcerpt {
+                    name: type_name.clone(),
+                    path: definition.file_path.clone(),
+                    start_line: target.start_row as u32 + 1,
+                    text: truncate_lines(&target.text),
+                });
+                break;
+            }
+        }
+
+        let mut found = 0;
+        for reference in service.find_references_hybrid(&type_name, lang_family, &root_path) {
+            if found >= MAX_IMPLS_PER_TYPE {
+                break;
+            }
+            let header = fs::read_to_string(&reference.file_path)
+                .ok()
+                .and_then(|s| {
+                    s.lines()
+                        .nth(reference.start_line.saturating_sub(1) as usize)
+                        .map(|l| l.to_string())
+                })
+                .unwrap_or_default();
+            if is_impl_header(&header, &type_name) {
+                impls.push(ImplSite {
+                    type_name: type_name.clone(),
+                    path: reference.file_path.clone(),
+                    line: reference.start_line,
+                    header,
+                });
+                found += 1;
+            }
+        }
+    }
+
+    let mut context = TypeErrorContext {
+        file_path,
+        diagnostic,
+        failing_line,
+        enclosing_function,
+        type_definitions,
+        impls,
+        prompt: String::new(),
+    };
+    context.prompt = render_prompt(&context);
+    Ok(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(message: &str) -> LintDiagnostic {
+        LintDiagnostic {
+            severity: "error".to_string(),
+            message: message.to_string(),
+            line: 3,
+            column: 9,
+            end_line: 3,
+            end_column: 12,
+            code: Some("E0308".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_extract_type_names_from_quotes() {
+        let names = extract_type_names(
+            "mismatched types: expected `Result<Config, Error>`, found `Option<Config>`",
+        );
+        assert_eq!(names, vec!["Result", "Config", "Error", "Option"]);
+
+        let names = extract_type_names(
+            "Argument of type 'UserDto' is not assignable to parameter of type 'User'.",
+        );
+        assert_eq!(names, vec!["UserDto", "User"]);
+    }
+
+    #[test]
+    fn test_extract_type_names_unquoted() {
+        assert_eq!(
+            extract_type_names("cannot convert Widget to Self"),
+            vec!["Widget"]
+        );
+    }
+
+    #[test]
+    fn test_is_impl_header() {
+        assert!(is_impl_header("impl Display for Config {", "Config"));
+        assert!(is_impl_header("impl<T> From<T> for Config {", "Config"));
+        assert!(is_impl_header(
+            "export class Store implements Repository {",
+            "Repository"
+        ));
+        assert!(!is_impl_header("let config = Config::new();", "Config"));
+        assert!(!is_impl_header(
+            "impl Display for ConfigBuilder {",
+            "Config"
+        ));
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let context = TypeErrorContext {
+            file_path: "/src/main.rs".to_string(),
+            diagnostic: diagnostic("expected `Config`"),
+            failing_line: "    let c: Config = load();".to_string(),
+            enclosing_function: Some(CodeExcerpt {
+                name: "main".to_string(),
+                path: "/src/main.rs".to_string(),
+                start_line: 1,
+                text: "fn main() {}".to_string(),
+            }),
+            type_definitions: vec![],
+            impls: vec![ImplSite {
+                type_name: "Config".to_string(),
+                path: "/src/config.rs".to_string(),
+                line: 12,
+                header: "impl Default for Config {".to_string(),
+            }],
+            prompt: String::new(),
+        };
+        let prompt = render_prompt(&context);
+        assert!(prompt.contains("/src/main.rs:3:9: [E0308] expected `Config`"));
+        assert!(prompt.contains("let c: Config = load();"));
+        assert!(prompt.contains("Enclosing function main"));
+        assert!(prompt.contains("/src/config.rs:12: impl Default for Config {"));
+    }
+}
@@ -0,0 +1,381 @@
+// Vue single-file components
+//
+// A `.vue` file holds a `<template>`, one or two `<script>` blocks and any number of
+// `<style>` blocks, none of which a single grammar parses. The summary keeps the SFC
+// shape: the template is reduced to an outline of its element structure, each script
+// block is summarized with the TypeScript/JavaScript grammar, and styles are dropped.
+
+use crate::code_navigation::{summarize_source, CodeSummary, SummaryOptions};
+
+/// Template elements nested deeper than this are elided
+pub const VUE_TEMPLATE_MAX_DEPTH: usize = 4;
+
+/// Elements that never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Attributes that shape the rendered structure and stay in the outline
+const STRUCTURAL_ATTRIBUTES: &[&str] = &["v-if", "v-else-if", "v-else", "v-for", "v-slot", "is"];
+
+#[derive(Debug, Clone, PartialEq)]
+struct Block<'a> {
+    tag: &'a str,
+    /// The opening tag line, attributes included
+    open: &'a str,
+    body: String,
+}
+
+/// Top-level blocks in source order. Block tags start a line; a nested `<template>`
+/// inside the template is indented and does not end it.
+fn split_blocks(content: &str) -> Vec<Block<'_>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let Some(tag) = ["template", "script", "style"]
+            .into_iter()
+            .find(|tag| opens_tag(line, tag))
+        else {
+            i += 1;
+            continue;
+        };
+        let close = format!("</{}>", tag);
+        // One-line blocks (`<style src="./app.css"></style>`)
+        if let Some(rest) = line.find('>').map(|gt| &line[gt + 1..]) {
+            if let Some(inner) = rest.trim_end().strip_suffix(close.as_str()) {
+                blocks.push(Block {
+                    tag,
+                    open: line,
+                    body: inner.to_string(),
+                });
+                i += 1;
+                continue;
+            }
+        }
+        let end = (i + 1..lines.len())
+            .find(|&k| lines[k].starts_with(close.as_str()))
+            .unwrap_or(lines.len());
+        blocks.push(Block {
+            tag,
+            open: line,
+            body: lines[i + 1..end].join("\n"),
+        });
+        i = end + 1;
+    }
+    blocks
+}
+
+fn opens_tag(line: &str, tag: &str) -> bool {
+    line.strip_prefix('<')
+        .and_then(|rest| rest.strip_prefix(tag))
+        .is_some_and(|rest| rest.starts_with(['>', ' ', '\t']) || rest.is_empty())
+}
+
+/// Value of `name="..."` in an opening tag
+fn attribute<'a>(open: &'a str, name: &str) -> Option<&'a str> {
+    let start = open.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let len = open[start..].find('"')?;
+    Some(&open[start..start + len])
+}
+
+/// One opening tag: its name, the structural attributes, and whether it closes itself
+fn parse_tag(tag: &str) -> (String, Vec<String>, bool) {
+    let self_closing = tag.ends_with('/');
+    let tag = tag.trim_end_matches('/');
+    let name_end = tag.find(|c: char| c.is_whitespace()).unwrap_or(tag.len());
+    let name = tag[..name_end].to_string();
+
+    // Split attributes on whitespace outside quotes
+    let mut attributes = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    for ch in tag[name_end..].chars() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(ch);
+                current.push(ch);
+            }
+            (None, c) if c.is_whitespace() => {
+                if !current.is_empty() {
+                    attributes.push(std::mem::take(&mut current));
+                }
+            }
+            (None, c) => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        attributes.push(current);
+    }
+    attributes.retain(|attr| {
+        let key = attr.split('=').next().unwrap_or(attr);
+        STRUCTURAL_ATTRIBUTES.contains(&key) || key.starts_with('#') || key.starts_with("v-slot:")
+    });
+    (name, attributes, self_closing)
+}
+
+/// Outline of the template's elements, one per line, indented by nesting depth
+fn template_outline(template: &str, max_depth: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    let mut elided_at: Option<usize> = None;
+    let mut rest = template;
+
+    while let Some(lt) = rest.find('<') {
+        rest = &rest[lt..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        // The tag's end, skipping `>` inside quoted attribute values
+        let mut quote: Option<char> = None;
+        let Some(gt) = rest
+            .char_indices()
+            .skip(1)
+            .find_map(|(i, c)| match (quote, c) {
+                (Some(q), c) if c == q => {
+                    quote = None;
+                    None
+                }
+                (Some(_), _) => None,
+                (None, '"' | '\'') => {
+                    quote = Some(c);
+                    None
+                }
+                (None, '>') => Some(i),
+                _ => None,
+            })
+        else {
+            break;
+        };
+        let tag = rest[1..gt].trim();
+        rest = &rest[gt + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            // Close up to the matching element; stray closers are ignored
+            if let Some(pos) = stack.iter().rposition(|open| open == name.trim()) {
+                stack.truncate(pos);
+            }
+            continue;
+        }
+        if !tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            continue;
+        }
+
+        let (name, attributes, self_closing) = parse_tag(tag);
+        let depth = stack.len();
+        if depth < max_depth {
+            let mut line = format!("{}<{}", "  ".repeat(depth + 1), name);
+            for attr in &attributes {
+                line.push(' ');
+                line.push_str(attr);
+            }
+            line.push_str(if self_closing { " />" } else { ">" });
+            lines.push(line);
+            elided_at = None;
+        } else if elided_at.is_none_or(|elided| depth < elided) {
+            lines.push(format!("{}...", "  ".repeat(depth + 1)));
+            elided_at = Some(depth);
+        }
+        if !self_closing && !VOID_ELEMENTS.contains(&name.to_ascii_lowercase().as_str()) {
+            stack.push(name);
+        }
+    }
+    lines.join("\n")
+}
+
+/// The summary body without the summarizer's `[COMPRESSED: ...]` header
+fn without_header(summary: &str) -> &str {
+    if summary.starts_with("[COMPRESSED:") {
+        summary
+            .split_once("]\n\n")
+            .map_or(summary, |(_, body)| body)
+    } else {
+        summary
+    }
+}
+
+/// Summarize a `.vue` file: template outline, summarized scripts, no styles
+pub fn summarize_vue(
+    content: &str,
+    file_path: &str,
+    options: &SummaryOptions,
+) -> Result<CodeSummary, String> {
+    let original_lines = content.lines().count();
+    let blocks = split_blocks(content);
+    if blocks.is_empty() {
+        return Ok(CodeSummary::unchanged(
+            content.to_string(),
+            original_lines,
+            "vue".to_string(),
+            "no template, script or style blocks",
+        ));
+    }
+
+    let mut sections = Vec::new();
+    let mut dropped_styles = 0;
+    for block in &blocks {
+        match block.tag {
+            "template" => {
+                let outline = template_outline(&block.body, VUE_TEMPLATE_MAX_DEPTH);
+                sections.push(format!("{}\n{}\n</template>", block.open, outline));
+            }
+            "script" => {
+                let lang_id = match attribute(block.open, "lang") {
+                    Some("ts" | "tsx") => "typescript",
+                    _ => "javascript",
+                };
+                let summary = summarize_source(
+                    block.body.clone(),
+                    lang_id.to_string(),
+                    file_path.to_string(),
+                    options,
+                )?;
+                sections.push(format!(
+                    "{}\n{}\n</script>",
+                    block.open,
+                    without_header(&summary.summary).trim_end()
+                ));
+            }
+            _ => dropped_styles += 1,
+        }
+    }
+
+    let mut summary = format!(
+        "[COMPRESSED: Original {} lines → Vue component, template outlined, scripts summarized",
+        original_lines
+    );
+    if dropped_styles > 0 {
+        summary.push_str(&format!(", {} style block(s) dropped", dropped_styles));
+    }
+    summary.push_str("]\n\n");
+    summary.push_str(&sections.join("\n\n"));
+
+    Ok(CodeSummary {
+        success: true,
+        summary,
+        original_lines,
+        lang_id: "vue".to_string(),
+        truncated: None,
+        skipped_reason: None,
+        omitted_symbols: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPONENT: &str = r#"<template>
+  <div class="todo-list">
+    <!-- <p>old header</p> -->
+    <TodoHeader :count="todos.length" @add="add" />
+    <ul v-if="todos.length">
+      <li v-for="todo in todos" :key="todo.id">
+        <label>
+          <input type="checkbox" v-model="todo.done">
+          <span>{{ todo.title }}</span>
+        </label>
+      </li>
+    </ul>
+    <p v-else>Nothing to do</p>
+    <template #footer>
+      <button @click="clear">Clear</button>
+    </template>
+  </div>
+</template>
+
+<script setup lang="ts">
+import { ref } from 'vue'
+import TodoHeader from './TodoHeader.vue'
+
+interface Todo {
+  id: number
+  title: string
+  done: boolean
+}
+
+const todos = ref<Todo[]>([])
+
+function add(title: string): void {
+  const id = todos.value.length + 1
+  todos.value.push({ id, title, done: false })
+  console.log('added', title)
+}
+
+function clear(): void {
+  todos.value = todos.value.filter((todo) => !todo.done)
+  console.log('cleared')
+}
+</script>
+
+<style scoped>
+.todo-list {
+  padding: 1rem;
+}
+</style>
+"#;
+
+    #[test]
+    fn test_split_blocks() {
+        let blocks = split_blocks(COMPONENT);
+        let tags: Vec<&str> = blocks.iter().map(|b| b.tag).collect();
+        assert_eq!(tags, vec!["template", "script", "style"]);
+        // The nested `<template #footer>` does not end the template
+        assert!(blocks[0].body.contains("<button @click=\"clear\">"));
+        assert_eq!(attribute(blocks[1].open, "lang"), Some("ts"));
+
+        let one_line = split_blocks("<style src=\"./app.css\"></style>\n");
+        assert_eq!(one_line[0].body, "");
+    }
+
+    #[test]
+    fn test_template_outline() {
+        let blocks = split_blocks(COMPONENT);
+        assert_eq!(
+            template_outline(&blocks[0].body, 4),
+            [
+                "  <div>",
+                "    <TodoHeader />",
+                "    <ul v-if=\"todos.length\">",
+                "      <li v-for=\"todo in todos\">",
+                "        <label>",
+                "          ...",
+                "    <p v-else>",
+                "    <template #footer>",
+                "      <button>",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_summarize_vue() {
+        let result = summarize_vue(COMPONENT, "TodoList.vue", &SummaryOptions::default()).unwrap();
+        assert!(result.success);
+        let summary = &result.summary;
+        assert!(summary.starts_with("[COMPRESSED: Original "), "{}", summary);
+        assert!(summary.contains("1 style block(s) dropped"), "{}", summary);
+        assert!(summary.contains("<template>\n  <div>\n"), "{}", summary);
+        assert!(
+            summary.contains("<script setup lang=\"ts\">\n"),
+            "{}",
+            summary
+        );
+        assert!(
+            summary.contains("function add(title: string): void { ... }"),
+            "{}",
+            summary
+        );
+        assert!(summary.trim_end().ends_with("</script>"), "{}", summary);
+        assert!(!summary.contains("padding"), "{}", summary);
+        assert!(!summary.contains("console.log"), "{}", summary);
+    }
+}
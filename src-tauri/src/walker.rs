@@ -10,6 +10,7 @@
 //! - **Shared Exclusion Logic**: Centralized directory exclusion handling
 
 use crate::constants::{should_exclude_dir, DEFAULT_MAX_DEPTH};
+use crate::path_policy;
 use ignore::{Walk, WalkBuilder, WalkParallel};
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
@@ -208,10 +209,16 @@ impl WorkspaceWalker {
         let config = self.config;
         let additional_excludes = config.additional_excludes.clone();
         let allow_github = config.allow_github_dir;
+        let workspace_root = config.workspace_root.clone();
 
         self.builder
             .filter_entry(move |entry| {
-                Self::should_include_entry(entry, allow_github, &additional_excludes)
+                Self::should_include_entry(
+                    entry,
+                    allow_github,
+                    &additional_excludes,
+                    workspace_root.as_deref(),
+                )
             })
             .build()
     }
@@ -221,10 +228,16 @@ impl WorkspaceWalker {
         let config = self.config;
         let additional_excludes = config.additional_excludes.clone();
         let allow_github = config.allow_github_dir;
+        let workspace_root = config.workspace_root.clone();
 
         self.builder
             .filter_entry(move |entry| {
-                Self::should_include_entry(entry, allow_github, &additional_excludes)
+                Self::should_include_entry(
+                    entry,
+                    allow_github,
+                    &additional_excludes,
+                    workspace_root.as_deref(),
+                )
             })
             .build_parallel()
     }
@@ -239,9 +252,20 @@ impl WorkspaceWalker {
         entry: &ignore::DirEntry,
         allow_github: bool,
         additional_excludes: &[String],
+        workspace_root: Option<&Path>,
     ) -> bool {
         let path = entry.path();
 
+        // Links (followed or not) must not lead out of the workspace or into a cycle
+        if let Some(root) = workspace_root {
+            if entry.path_is_symlink() {
+                if let Err(e) = path_policy::resolve_within(path, root) {
+                    log::debug!("Skipping {}: {}", path.display(), e);
+                    return false;
+                }
+            }
+        }
+
         // Only filter directories
         if !path.is_dir() {
             return true;
@@ -268,9 +292,10 @@ impl WorkspaceWalker {
 
 /// Validate that a path stays within the workspace root.
 ///
-/// This function canonicalizes the given path and checks if it starts with
-/// the canonical workspace root. This prevents symlink attacks where a
-/// symlink points to a path outside the workspace.
+/// This function resolves every symlink along the given path and checks that
+/// the result stays under the canonical workspace root. This prevents symlink
+/// attacks where a symlink points to a path outside the workspace, and rejects
+/// symlink cycles.
 ///
 /// # Arguments
 /// * `path` - The path to validate
@@ -279,19 +304,8 @@ impl WorkspaceWalker {
 /// # Returns
 /// `true` if the path is within the workspace, `false` otherwise
 pub fn validate_path_in_workspace(path: &Path, workspace_root: &Path) -> bool {
-    // Canonicalize both paths to resolve symlinks
-    let canonical_path = match path.canonicalize() {
-        Ok(p) => p,
-        Err(_) => return false, // If we can't canonicalize, reject the path
-    };
-
-    let canonical_root = match workspace_root.canonicalize() {
-        Ok(p) => p,
-        Err(_) => return false, // If we can't canonicalize root, reject
-    };
-
-    // Check if the canonical path starts with the canonical root
-    canonical_path.starts_with(&canonical_root)
+    // Paths that don't exist (including broken symlinks) are rejected
+    path.exists() && path_policy::resolve_within(path, workspace_root).is_ok()
 }
 
 #[cfg(test)]
@@ -480,6 +494,31 @@ mod tests {
         assert!(!validate_path_in_workspace(&external_path, temp_dir.path()));
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_followed_symlink_must_stay_in_workspace() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new().unwrap();
+        let external_dir = TempDir::new().unwrap();
+        fs::write(external_dir.path().join("outside.txt"), "").unwrap();
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "").unwrap();
+        symlink(external_dir.path(), temp_dir.path().join("escape")).unwrap();
+        symlink(temp_dir.path().join("src"), temp_dir.path().join("alias")).unwrap();
+
+        let root = temp_dir.path().to_str().unwrap();
+        let config = WalkerConfig::for_glob(root).with_follow_links(true);
+        let paths: Vec<String> = WorkspaceWalker::new(root, config)
+            .build()
+            .flatten()
+            .map(|e| e.path().to_string_lossy().to_string())
+            .collect();
+
+        assert!(!paths.iter().any(|p| p.contains("outside.txt")));
+        assert!(paths.iter().any(|p| p.ends_with("alias/main.rs")));
+    }
+
     #[test]
     #[cfg(unix)]
     fn test_symlink_not_followed() {
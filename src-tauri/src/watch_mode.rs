@@ -0,0 +1,337 @@
+// Watch mode: keep summaries, the symbol index and quick diagnostics current on save
+//
+// A dedicated watcher (independent of the UI file watcher) debounces save events for
+// supported source files, re-indexes and re-summarizes them, re-runs the syntax check,
+// and emits only what changed as `watch-mode-update` events.
+
+use crate::code_navigation::{self, resolve_nav, CodeNavState, CodeNavigationService};
+use crate::constants::should_exclude_dir;
+use crate::syntax_check::{self, SyntaxCheckResult};
+use crate::workspace_state::WorkspaceState;
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc, Mutex,
+};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+const CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Last analysis of a file, used to emit deltas only
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileAnalysis {
+    pub summary: String,
+    pub diagnostics: SyntaxCheckResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchDelta {
+    pub path: String,
+    pub removed: bool,
+    /// Present only when the summary changed
+    pub summary: Option<String>,
+    /// Present only when the diagnostics changed
+    pub diagnostics: Option<SyntaxCheckResult>,
+}
+
+struct WatchSession {
+    root: String,
+    _watcher: RecommendedWatcher,
+    stop_flag: Arc<AtomicBool>,
+    _thread_handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for WatchSession {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Default)]
+pub struct WatchModeState(Mutex<Option<WatchSession>>);
+
+fn lang_id_for(path: &Path) -> Option<String> {
+    CodeNavigationService::get_lang_id_from_path(&path.to_string_lossy())
+}
+
+fn is_watched(path: &Path) -> bool {
+    let excluded = path.components().any(|c| {
+        c.as_os_str()
+            .to_str()
+            .map(should_exclude_dir)
+            .unwrap_or(false)
+    });
+    !excluded && lang_id_for(path).is_some()
+}
+
+/// Summarize and syntax-check a file
+pub async fn analyze_file(path: &str, content: &str, lang_id: &str) -> FileAnalysis {
+    let summary = code_navigation::summarize_code_content(
+        content.to_string(),
+        lang_id.to_string(),
+        path.to_string(),
+        None,
+    )
+    .await
+    .map(|s| s.summary)
+    .unwrap_or_default();
+    let diagnostics =
+        syntax_check::check_syntax(content, lang_id).unwrap_or_else(|_| SyntaxCheckResult {
+            supported: false,
+            valid: true,
+            issues: Vec::new(),
+        });
+    FileAnalysis {
+        summary,
+        diagnostics,
+    }
+}
+
+/// Compare against the previous analysis; `None` when nothing the frontend cares about changed
+pub fn compute_delta(
+    path: &str,
+    previous: Option<&FileAnalysis>,
+    next: Option<&FileAnalysis>,
+) -> Option<WatchDelta> {
+    match (previous, next) {
+        (None, None) => None,
+        (Some(_), None) => Some(WatchDelta {
+            path: path.to_string(),
+            removed: true,
+            summary: None,
+            diagnostics: None,
+        }),
+        (previous, Some(next)) => {
+            let summary =
+                (previous.map(|p| &p.summary) != Some(&next.summary)).then(|| next.summary.clone());
+            let diagnostics = (previous.map(|p| &p.diagnostics) != Some(&next.diagnostics))
+                .then(|| next.diagnostics.clone());
+            if summary.is_none() && diagnostics.is_none() {
+                return None;
+            }
+            Some(WatchDelta {
+                path: path.to_string(),
+                removed: false,
+                summary,
+                diagnostics,
+            })
+        }
+    }
+}
+
+/// Re-index and re-analyze changed paths, returning deltas. Files are re-indexed in
+/// the index of `workspace_id`, or the shared one when no workspace is given.
+fn process_changes(
+    app: &AppHandle,
+    workspace_id: Option<&str>,
+    paths: &BTreeSet<PathBuf>,
+    cache: &mut HashMap<String, FileAnalysis>,
+) -> Vec<WatchDelta> {
+    let nav_state = app.state::<CodeNavState>();
+    let workspaces = app.state::<WorkspaceState>();
+    // The workspace may have been released since the session started
+    let nav = match resolve_nav(&nav_state, &workspaces, workspace_id) {
+        Ok(nav) => Some(nav),
+        Err(e) => {
+            log::warn!("Watch mode not re-indexing: {}", e);
+            None
+        }
+    };
+    let mut deltas = Vec::new();
+
+    for path in paths {
+        let key = path.to_string_lossy().to_string();
+        let Some(lang_id) = lang_id_for(path) else {
+            continue;
+        };
+
+        let next = match std::fs::read_to_string(path) {
+            Ok(content) => {
+                if let Some(mut service) = nav.as_ref().and_then(|nav| nav.write().ok()) {
+                    service.index_file(&key, &content, &lang_id);
+                }
+                Some(tauri::async_runtime::block_on(analyze_file(
+                    &key, &content, &lang_id,
+                )))
+            }
+            Err(_) => {
+                if let Some(mut service) = nav.as_ref().and_then(|nav| nav.write().ok()) {
+                    service.clear_file(&key);
+                }
+                None
+            }
+        };
+
+        if let Some(delta) = compute_delta(&key, cache.get(&key), next.as_ref()) {
+            deltas.push(delta);
+        }
+        match next {
+            Some(analysis) => {
+                cache.insert(key, analysis);
+            }
+            None => {
+                cache.remove(&key);
+            }
+        }
+    }
+    deltas
+}
+
+fn start_session(
+    app: AppHandle,
+    root_path: &str,
+    workspace_id: Option<String>,
+) -> Result<WatchSession, String> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = RecommendedWatcher::new(
+        move |result| {
+            if let Err(e) = sender.send(result) {
+                log::error!("Failed to send watch mode event: {}", e);
+            }
+        },
+        Config::default(),
+    )
+    .map_err(|e| format!("Failed to create watcher: {}", e))?;
+    watcher
+        .watch(Path::new(root_path), RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {}", root_path, e))?;
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let thread_stop_flag = Arc::clone(&stop_flag);
+
+    let thread_handle = thread::spawn(move || {
+        let mut cache: HashMap<String, FileAnalysis> = HashMap::new();
+        let mut pending: BTreeSet<PathBuf> = BTreeSet::new();
+        let mut last_event_time = Instant::now();
+
+        loop {
+            if thread_stop_flag.load(Ordering::Relaxed) {
+                log::info!("Watch mode thread stopping");
+                break;
+            }
+
+            match receiver.recv_timeout(CHECK_INTERVAL) {
+                Ok(Ok(event)) => match event.kind {
+                    notify::EventKind::Create(_)
+                    | notify::EventKind::Remove(_)
+                    | notify::EventKind::Modify(notify::event::ModifyKind::Name(_))
+                    | notify::EventKind::Modify(notify::event::ModifyKind::Data(_)) => {
+                        let before = pending.len();
+                        pending.extend(event.paths.into_iter().filter(|p| is_watched(p)));
+                        if pending.len() != before {
+                            last_event_time = Instant::now();
+                        }
+                    }
+                    _ => {}
+                },
+                Ok(Err(e)) => log::error!("Watch mode error: {}", e),
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    log::info!("Watch mode channel disconnected");
+                    break;
+                }
+            }
+
+            if !pending.is_empty() && last_event_time.elapsed() >= DEBOUNCE {
+                let deltas = process_changes(&app, workspace_id.as_deref(), &pending, &mut cache);
+                pending.clear();
+                if !deltas.is_empty() {
+                    log::debug!("Watch mode emitting {} deltas", deltas.len());
+                    if let Err(e) = app.emit("watch-mode-update", &deltas) {
+                        log::error!("Failed to emit watch-mode-update event: {}", e);
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(WatchSession {
+        root: root_path.to_string(),
+        _watcher: watcher,
+        stop_flag,
+        _thread_handle: Some(thread_handle),
+    })
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn watch_mode_start(
+    app: AppHandle,
+    state: State<'_, WatchModeState>,
+    workspace_id: Option<String>,
+    root_path: String,
+) -> Result<(), String> {
+    let mut session = state.0.lock().map_err(|e| e.to_string())?;
+    // Dropping the previous session stops its thread
+    *session = None;
+    *session = Some(start_session(app, &root_path, workspace_id)?);
+    log::info!("Watch mode started for {}", root_path);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn watch_mode_stop(state: State<'_, WatchModeState>) -> Result<(), String> {
+    let mut session = state.0.lock().map_err(|e| e.to_string())?;
+    if let Some(previous) = session.take() {
+        log::info!("Watch mode stopped for {}", previous.root);
+    }
+    Ok(())
+}
+
+/// Root currently in watch mode, if any
+#[tauri::command]
+pub fn watch_mode_status(state: State<'_, WatchModeState>) -> Result<Option<String>, String> {
+    let session = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(session.as_ref().map(|s| s.root.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_watched() {
+        assert!(is_watched(Path::new("/project/src/main.rs")));
+        assert!(!is_watched(Path::new("/project/node_modules/pkg/index.ts")));
+        assert!(!is_watched(Path::new("/project/README.md")));
+    }
+
+    #[tokio::test]
+    async fn test_delta_only_on_change() {
+        let first = analyze_file("a.rs", "fn a() {}\n", "rust").await;
+        let delta = compute_delta("a.rs", None, Some(&first)).unwrap();
+        assert!(delta.summary.is_some());
+        assert!(delta.diagnostics.is_some());
+
+        // Same content: nothing to report
+        let same = analyze_file("a.rs", "fn a() {}\n", "rust").await;
+        assert!(compute_delta("a.rs", Some(&first), Some(&same)).is_none());
+
+        // Broken syntax changes diagnostics
+        let broken = analyze_file("a.rs", "fn a() {\n", "rust").await;
+        let delta = compute_delta("a.rs", Some(&first), Some(&broken)).unwrap();
+        assert!(!delta.diagnostics.unwrap().valid);
+    }
+
+    #[test]
+    fn test_delta_for_removed_file() {
+        let analysis = FileAnalysis {
+            summary: "fn a()".to_string(),
+            diagnostics: SyntaxCheckResult {
+                supported: true,
+                valid: true,
+                issues: Vec::new(),
+            },
+        };
+        let delta = compute_delta("a.rs", Some(&analysis), None).unwrap();
+        assert!(delta.removed);
+        assert!(compute_delta("a.rs", None, None).is_none());
+    }
+}
@@ -0,0 +1,464 @@
+// Recording agent runs as replayable workflows
+//
+// While recording is on for a session, the frontend reports each tool call it executes
+// (`record_workflow_step`). Saving the recording turns the successful calls into a named
+// workflow in ~/.talkcody/workflows/<name>.json; values the user marks as parameters
+// (a file path, a branch name) are replaced with `{{name}}` placeholders throughout the
+// recorded inputs. `replay_workflow(name, vars)` fills the placeholders back in and
+// returns the concrete tool calls in order, which the frontend runs through its tool
+// registry like any other calls, so permissions and approvals still apply.
+//
+// A placeholder that is a whole string takes the variable's JSON value as-is, so numbers
+// and lists survive; inside a longer string the value is interpolated as text.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedStep {
+    pub tool: String,
+    pub input: Value,
+    pub succeeded: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowParameter {
+    pub name: String,
+    /// The value seen while recording, used when replay does not set the variable
+    pub default: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowStep {
+    pub tool: String,
+    pub input: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workflow {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub parameters: Vec<WorkflowParameter>,
+    pub steps: Vec<WorkflowStep>,
+    pub created_at: i64,
+    pub source_session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingStatus {
+    pub session_id: String,
+    pub recording: bool,
+    pub steps: usize,
+}
+
+fn recordings() -> &'static Mutex<HashMap<String, Vec<RecordedStep>>> {
+    static RECORDINGS: OnceLock<Mutex<HashMap<String, Vec<RecordedStep>>>> = OnceLock::new();
+    RECORDINGS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn get_workflows_dir() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
+    Ok(home.join(".talkcody").join("workflows"))
+}
+
+fn validate_workflow_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid workflow name {:?}: use lowercase letters, digits, - and _",
+            name
+        ))
+    }
+}
+
+fn validate_variable_name(name: &str) -> Result<(), String> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid variable name {:?}: use letters, digits and _",
+            name
+        ))
+    }
+}
+
+fn placeholder(name: &str) -> String {
+    format!("{{{{{}}}}}", name)
+}
+
+/// Replace every occurrence of the parameter values in the strings of `value`
+fn parameterize(value: &mut Value, parameters: &[WorkflowParameter]) {
+    match value {
+        Value::String(text) => {
+            for parameter in parameters {
+                *text = text.replace(&parameter.default, &placeholder(&parameter.name));
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| parameterize(v, parameters)),
+        Value::Object(map) => map.values_mut().for_each(|v| parameterize(v, parameters)),
+        _ => {}
+    }
+}
+
+/// Fill in the placeholders of `value`
+fn substitute(value: &mut Value, vars: &HashMap<String, Value>) {
+    match value {
+        Value::String(text) => {
+            let whole = vars
+                .iter()
+                .find(|(name, _)| *text == placeholder(name))
+                .map(|(_, v)| v.clone());
+            if let Some(replacement) = whole {
+                *value = replacement;
+                return;
+            }
+            for (name, replacement) in vars {
+                let as_text = match replacement {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                *text = text.replace(&placeholder(name), &as_text);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| substitute(v, vars)),
+        Value::Object(map) => map.values_mut().for_each(|v| substitute(v, vars)),
+        _ => {}
+    }
+}
+
+/// Build a workflow from recorded steps; failed calls are left out
+pub fn build_workflow(
+    name: &str,
+    description: &str,
+    steps: &[RecordedStep],
+    parameters: &BTreeMap<String, String>,
+    source_session_id: Option<String>,
+) -> Result<Workflow, String> {
+    validate_workflow_name(name)?;
+    let mut parameters: Vec<WorkflowParameter> = parameters
+        .iter()
+        .map(|(name, default)| {
+            validate_variable_name(name)?;
+            if default.is_empty() {
+                return Err(format!("Parameter {} has an empty value", name));
+            }
+            Ok(WorkflowParameter {
+                name: name.clone(),
+                default: default.clone(),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    // Longer values first, so a value containing another is replaced whole
+    parameters.sort_by_key(|p| std::cmp::Reverse(p.default.len()));
+
+    let steps: Vec<WorkflowStep> = steps
+        .iter()
+        .filter(|step| step.succeeded)
+        .map(|step| {
+            let mut input = step.input.clone();
+            parameterize(&mut input, &parameters);
+            WorkflowStep {
+                tool: step.tool.clone(),
+                input,
+            }
+        })
+        .collect();
+    if steps.is_empty() {
+        return Err("The recording has no successful tool calls".to_string());
+    }
+    parameters.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Workflow {
+        name: name.to_string(),
+        description: description.to_string(),
+        parameters,
+        steps,
+        created_at: chrono::Utc::now().timestamp_millis(),
+        source_session_id,
+    })
+}
+
+/// The workflow's steps with `vars` filled in; unset variables take their recorded value
+pub fn resolve_steps(
+    workflow: &Workflow,
+    vars: &HashMap<String, Value>,
+) -> Result<Vec<WorkflowStep>, String> {
+    if let Some(unknown) = vars
+        .keys()
+        .find(|name| !workflow.parameters.iter().any(|p| &p.name == *name))
+    {
+        return Err(format!(
+            "Workflow {} has no parameter {}",
+            workflow.name, unknown
+        ));
+    }
+    let mut resolved: HashMap<String, Value> = workflow
+        .parameters
+        .iter()
+        .map(|p| (p.name.clone(), Value::String(p.default.clone())))
+        .collect();
+    resolved.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    Ok(workflow
+        .steps
+        .iter()
+        .map(|step| {
+            let mut input = step.input.clone();
+            substitute(&mut input, &resolved);
+            WorkflowStep {
+                tool: step.tool.clone(),
+                input,
+            }
+        })
+        .collect())
+}
+
+fn save_to(dir: &Path, workflow: &Workflow) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let json = serde_json::to_string_pretty(workflow).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.json", workflow.name));
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn load_from(dir: &Path, name: &str) -> Result<Workflow, String> {
+    validate_workflow_name(name)?;
+    let path = dir.join(format!("{}.json", name));
+    let raw = fs::read_to_string(&path).map_err(|_| format!("No workflow named {}", name))?;
+    serde_json::from_str(&raw).map_err(|e| format!("Invalid workflow {}: {}", name, e))
+}
+
+fn list_in(dir: &Path) -> Vec<Workflow> {
+    let mut workflows: Vec<Workflow> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+                .filter_map(|e| fs::read_to_string(e.path()).ok())
+                .filter_map(|raw| serde_json::from_str(&raw).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    workflows.sort_by(|a, b| a.name.cmp(&b.name));
+    workflows
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn start_workflow_recording(session_id: String) -> Result<RecordingStatus, String> {
+    recordings()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(session_id.clone(), Vec::new());
+    log::info!("Recording workflow for session {}", session_id);
+    Ok(RecordingStatus {
+        session_id,
+        recording: true,
+        steps: 0,
+    })
+}
+
+/// Append a tool call to the session's recording; ignored when it is not recording
+#[tauri::command]
+pub fn record_workflow_step(
+    session_id: String,
+    tool: String,
+    input: Value,
+    succeeded: bool,
+) -> Result<RecordingStatus, String> {
+    let mut recordings = recordings().lock().map_err(|e| e.to_string())?;
+    let Some(steps) = recordings.get_mut(&session_id) else {
+        return Ok(RecordingStatus {
+            session_id,
+            recording: false,
+            steps: 0,
+        });
+    };
+    steps.push(RecordedStep {
+        tool,
+        input,
+        succeeded,
+    });
+    let count = steps.len();
+    Ok(RecordingStatus {
+        session_id,
+        recording: true,
+        steps: count,
+    })
+}
+
+/// Stop recording and return what was recorded, e.g. for the user to pick parameters
+#[tauri::command]
+pub fn stop_workflow_recording(session_id: String) -> Result<Vec<RecordedStep>, String> {
+    Ok(recordings()
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&session_id)
+        .unwrap_or_default())
+}
+
+/// Save recorded steps as a workflow. `parameters` maps variable names to the values
+/// they replace, e.g. `{"file": "src/main.rs"}`.
+#[tauri::command]
+pub fn save_workflow(
+    name: String,
+    description: Option<String>,
+    steps: Vec<RecordedStep>,
+    parameters: BTreeMap<String, String>,
+    session_id: Option<String>,
+) -> Result<Workflow, String> {
+    let workflow = build_workflow(
+        &name,
+        description.as_deref().unwrap_or(""),
+        &steps,
+        &parameters,
+        session_id,
+    )?;
+    save_to(&get_workflows_dir()?, &workflow)?;
+    log::info!(
+        "Saved workflow {} ({} steps, {} parameters)",
+        workflow.name,
+        workflow.steps.len(),
+        workflow.parameters.len()
+    );
+    Ok(workflow)
+}
+
+#[tauri::command]
+pub fn list_workflows() -> Result<Vec<Workflow>, String> {
+    Ok(list_in(&get_workflows_dir()?))
+}
+
+#[tauri::command]
+pub fn delete_workflow(name: String) -> Result<(), String> {
+    validate_workflow_name(&name)?;
+    let path = get_workflows_dir()?.join(format!("{}.json", name));
+    fs::remove_file(&path).map_err(|_| format!("No workflow named {}", name))
+}
+
+/// Resolve a workflow's tool calls for the frontend to execute in order
+#[tauri::command]
+pub fn replay_workflow(
+    name: String,
+    vars: Option<HashMap<String, Value>>,
+) -> Result<Vec<WorkflowStep>, String> {
+    let workflow = load_from(&get_workflows_dir()?, &name)?;
+    resolve_steps(&workflow, &vars.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn recorded() -> Vec<RecordedStep> {
+        vec![
+            RecordedStep {
+                tool: "read_file".to_string(),
+                input: json!({"path": "src/lib.rs"}),
+                succeeded: true,
+            },
+            RecordedStep {
+                tool: "edit_file".to_string(),
+                input: json!({"path": "src/lib.rs", "edits": [{"old": "v1", "new": "v2"}]}),
+                succeeded: false,
+            },
+            RecordedStep {
+                tool: "bash".to_string(),
+                input: json!({"command": "cargo test --lib src/lib.rs", "timeout": 60}),
+                succeeded: true,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_build_workflow_parameterizes_inputs() {
+        let parameters = BTreeMap::from([
+            ("file".to_string(), "src/lib.rs".to_string()),
+            ("dir".to_string(), "src".to_string()),
+        ]);
+        let workflow = build_workflow("test-file", "", &recorded(), &parameters, None).unwrap();
+
+        // The failed edit is dropped
+        assert_eq!(workflow.steps.len(), 2);
+        assert_eq!(workflow.steps[0].input, json!({"path": "{{file}}"}));
+        assert_eq!(
+            workflow.steps[1].input,
+            json!({"command": "cargo test --lib {{file}}", "timeout": 60})
+        );
+        assert_eq!(workflow.parameters[0].name, "dir");
+    }
+
+    #[test]
+    fn test_resolve_steps() {
+        let parameters = BTreeMap::from([("file".to_string(), "src/lib.rs".to_string())]);
+        let workflow = build_workflow("test-file", "", &recorded(), &parameters, None).unwrap();
+
+        let defaults = resolve_steps(&workflow, &HashMap::new()).unwrap();
+        assert_eq!(defaults[0].input, json!({"path": "src/lib.rs"}));
+
+        let vars = HashMap::from([("file".to_string(), json!("src/main.rs"))]);
+        let steps = resolve_steps(&workflow, &vars).unwrap();
+        assert_eq!(steps[0].input, json!({"path": "src/main.rs"}));
+        assert_eq!(
+            steps[1].input["command"],
+            json!("cargo test --lib src/main.rs")
+        );
+
+        // A whole-string placeholder takes the variable's JSON value
+        let vars = HashMap::from([("file".to_string(), json!(["a.rs", "b.rs"]))]);
+        let steps = resolve_steps(&workflow, &vars).unwrap();
+        assert_eq!(steps[0].input, json!({"path": ["a.rs", "b.rs"]}));
+
+        let unknown = HashMap::from([("branch".to_string(), json!("main"))]);
+        assert!(resolve_steps(&workflow, &unknown).is_err());
+    }
+
+    #[test]
+    fn test_build_workflow_validation() {
+        let none = BTreeMap::new();
+        assert!(build_workflow("Bad Name", "", &recorded(), &none, None).is_err());
+        let failed_only = vec![recorded().remove(1)];
+        assert!(build_workflow("x", "", &failed_only, &none, None).is_err());
+        let bad_var = BTreeMap::from([("a-b".to_string(), "x".to_string())]);
+        assert!(build_workflow("x", "", &recorded(), &bad_var, None).is_err());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let workflow =
+            build_workflow("check", "Run checks", &recorded(), &BTreeMap::new(), None).unwrap();
+        save_to(temp_dir.path(), &workflow).unwrap();
+
+        let loaded = load_from(temp_dir.path(), "check").unwrap();
+        assert_eq!(loaded.steps, workflow.steps);
+        assert_eq!(list_in(temp_dir.path()).len(), 1);
+        assert!(load_from(temp_dir.path(), "missing").is_err());
+    }
+
+    #[test]
+    fn test_recording_only_when_started() {
+        let session = "workflow-recorder-test".to_string();
+        let status =
+            record_workflow_step(session.clone(), "bash".to_string(), json!({}), true).unwrap();
+        assert!(!status.recording);
+
+        start_workflow_recording(session.clone()).unwrap();
+        let status =
+            record_workflow_step(session.clone(), "bash".to_string(), json!({}), true).unwrap();
+        assert_eq!(status.steps, 1);
+        assert_eq!(stop_workflow_recording(session).unwrap().len(), 1);
+    }
+}
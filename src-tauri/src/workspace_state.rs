@@ -0,0 +1,251 @@
+// Per-workspace backend state
+//
+// Each open project gets its own code navigation service (parsers and symbol index),
+// string literal index and settings, so two windows on two projects never index into
+// or read from each other's state. Windows open a workspace to get a handle, pass the
+// handle's id to workspace-aware commands, and release it when they close; the state
+// is dropped once no window holds it.
+
+use crate::code_navigation::{get_project_hash, CodeNavigationService};
+use crate::language_config;
+use crate::string_index::StringLiteralIndex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::State;
+
+pub struct Workspace {
+    pub root_path: String,
+    /// Labels of the windows holding this workspace
+    pub windows: BTreeSet<String>,
+    pub code_nav: Arc<RwLock<CodeNavigationService>>,
+    pub string_index: Arc<RwLock<StringLiteralIndex>>,
+    pub settings: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceHandle {
+    pub id: String,
+    pub root_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceInfo {
+    pub id: String,
+    pub root_path: String,
+    pub windows: Vec<String>,
+    pub indexed_files: usize,
+}
+
+/// State for a command: a workspace's own copy or the shared app-wide one
+pub enum Scoped<'a, T> {
+    Shared(&'a RwLock<T>),
+    Workspace(Arc<RwLock<T>>),
+}
+
+impl<T> Deref for Scoped<'_, T> {
+    type Target = RwLock<T>;
+
+    fn deref(&self) -> &RwLock<T> {
+        match self {
+            Scoped::Shared(shared) => shared,
+            Scoped::Workspace(own) => own,
+        }
+    }
+}
+
+/// Tauri state: workspace id -> workspace
+#[derive(Default)]
+pub struct WorkspaceState(pub Mutex<HashMap<String, Workspace>>);
+
+impl WorkspaceState {
+    /// Open (or join) the workspace for `root_path` from `window_label`
+    pub fn open(&self, window_label: &str, root_path: &str) -> Result<WorkspaceHandle, String> {
+        let id = get_project_hash(root_path);
+        let mut workspaces = self.0.lock().map_err(|e| e.to_string())?;
+        let workspace = workspaces.entry(id.clone()).or_insert_with(|| {
+            log::info!("Created workspace {} for {}", id, root_path);
+            let mut code_nav = CodeNavigationService::new();
+            code_nav.language_config = language_config::load_for_workspace(root_path);
+            Workspace {
+                root_path: root_path.to_string(),
+                windows: BTreeSet::new(),
+                code_nav: Arc::new(RwLock::new(code_nav)),
+                string_index: Arc::new(RwLock::new(StringLiteralIndex::default())),
+                settings: HashMap::new(),
+            }
+        });
+        workspace.windows.insert(window_label.to_string());
+        Ok(WorkspaceHandle {
+            id,
+            root_path: workspace.root_path.clone(),
+        })
+    }
+
+    /// Detach `window_label` from every workspace, dropping workspaces no window holds.
+    /// Returns the ids of the dropped workspaces.
+    pub fn release_window(&self, window_label: &str) -> Result<Vec<String>, String> {
+        let mut workspaces = self.0.lock().map_err(|e| e.to_string())?;
+        let mut dropped = Vec::new();
+        workspaces.retain(|id, workspace| {
+            workspace.windows.remove(window_label);
+            let keep = !workspace.windows.is_empty();
+            if !keep {
+                dropped.push(id.clone());
+            }
+            keep
+        });
+        for id in &dropped {
+            log::info!("Dropped workspace {} (no windows left)", id);
+        }
+        Ok(dropped)
+    }
+
+    fn with_workspace<T>(
+        &self,
+        id: &str,
+        f: impl FnOnce(&mut Workspace) -> T,
+    ) -> Result<T, String> {
+        let mut workspaces = self.0.lock().map_err(|e| e.to_string())?;
+        let workspace = workspaces
+            .get_mut(id)
+            .ok_or_else(|| format!("Unknown workspace: {}", id))?;
+        Ok(f(workspace))
+    }
+
+    pub fn code_nav(&self, id: &str) -> Result<Arc<RwLock<CodeNavigationService>>, String> {
+        self.with_workspace(id, |w| w.code_nav.clone())
+    }
+
+    pub fn string_index(&self, id: &str) -> Result<Arc<RwLock<StringLiteralIndex>>, String> {
+        self.with_workspace(id, |w| w.string_index.clone())
+    }
+
+    pub fn list(&self) -> Result<Vec<WorkspaceInfo>, String> {
+        let workspaces = self.0.lock().map_err(|e| e.to_string())?;
+        let mut infos: Vec<WorkspaceInfo> = workspaces
+            .iter()
+            .map(|(id, workspace)| WorkspaceInfo {
+                id: id.clone(),
+                root_path: workspace.root_path.clone(),
+                windows: workspace.windows.iter().cloned().collect(),
+                indexed_files: workspace
+                    .code_nav
+                    .read()
+                    .map(|service| service.indexed_file_count())
+                    .unwrap_or(0),
+            })
+            .collect();
+        infos.sort_by(|a, b| a.root_path.cmp(&b.root_path));
+        Ok(infos)
+    }
+}
+
+#[tauri::command]
+pub fn workspace_open(
+    state: State<'_, WorkspaceState>,
+    window_label: String,
+    root_path: String,
+) -> Result<WorkspaceHandle, String> {
+    state.open(&window_label, &root_path)
+}
+
+#[tauri::command]
+pub fn workspace_release(
+    state: State<'_, WorkspaceState>,
+    window_label: String,
+) -> Result<Vec<String>, String> {
+    state.release_window(&window_label)
+}
+
+#[tauri::command]
+pub fn workspace_list(state: State<'_, WorkspaceState>) -> Result<Vec<WorkspaceInfo>, String> {
+    state.list()
+}
+
+#[tauri::command]
+pub fn workspace_get_setting(
+    state: State<'_, WorkspaceState>,
+    workspace_id: String,
+    key: String,
+) -> Result<Option<serde_json::Value>, String> {
+    state.with_workspace(&workspace_id, |w| w.settings.get(&key).cloned())
+}
+
+#[tauri::command]
+pub fn workspace_set_setting(
+    state: State<'_, WorkspaceState>,
+    workspace_id: String,
+    key: String,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    state.with_workspace(&workspace_id, |w| {
+        w.settings.insert(key, value);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspaces_are_isolated() {
+        let state = WorkspaceState::default();
+        let a = state.open("main", "/projects/a").unwrap();
+        let b = state.open("window-2", "/projects/b").unwrap();
+        assert_ne!(a.id, b.id);
+
+        state.code_nav(&a.id).unwrap().write().unwrap().index_file(
+            "/projects/a/lib.rs",
+            "fn only_in_a() {}",
+            "rust",
+        );
+
+        let in_a = state.code_nav(&a.id).unwrap();
+        let in_b = state.code_nav(&b.id).unwrap();
+        assert_eq!(
+            in_a.read()
+                .unwrap()
+                .find_definition("only_in_a", "rust")
+                .len(),
+            1
+        );
+        assert!(in_b
+            .read()
+            .unwrap()
+            .find_definition("only_in_a", "rust")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_release_drops_unused_workspaces() {
+        let state = WorkspaceState::default();
+        let shared = state.open("main", "/projects/a").unwrap();
+        assert_eq!(state.open("window-2", "/projects/a").unwrap().id, shared.id);
+        state.open("window-2", "/projects/b").unwrap();
+
+        assert!(state.release_window("main").unwrap().is_empty());
+        let dropped = state.release_window("window-2").unwrap();
+        assert_eq!(dropped.len(), 2);
+        assert!(state.code_nav(&shared.id).is_err());
+        assert!(state.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_settings_are_per_workspace() {
+        let state = WorkspaceState::default();
+        let a = state.open("main", "/projects/a").unwrap();
+        let b = state.open("window-2", "/projects/b").unwrap();
+        state
+            .with_workspace(&a.id, |w| {
+                w.settings
+                    .insert("tabSize".to_string(), serde_json::json!(2));
+            })
+            .unwrap();
+        assert!(state
+            .with_workspace(&b.id, |w| w.settings.get("tabSize").cloned())
+            .unwrap()
+            .is_none());
+    }
+}
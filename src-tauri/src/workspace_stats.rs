@@ -0,0 +1,329 @@
+// Workspace statistics
+//
+// Aggregates the numbers a project dashboard and the agent's orientation step need:
+// per-language file/line/byte counts, the largest files, symbol counts from the code
+// navigation index, the most referenced symbols (by identifier occurrence) and how
+// fresh the index is relative to the files on disk.
+
+use crate::code_navigation::{
+    code_nav_get_index_metadata, resolve_nav, CodeNavState, CodeNavigationService,
+};
+use crate::walker::{WalkerConfig, WorkspaceWalker};
+use crate::workspace_state::WorkspaceState;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, State};
+
+const LARGEST_FILES: usize = 10;
+const TOP_SYMBOLS: usize = 20;
+/// Only files up to this size are read for line counts and references
+const MAX_TEXT_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LanguageStats {
+    pub files: usize,
+    pub lines: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSize {
+    pub path: String,
+    pub bytes: u64,
+    pub lines: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolReferenceCount {
+    pub name: String,
+    pub kind: String,
+    pub file_path: String,
+    pub references: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexFreshness {
+    pub indexed_files: usize,
+    /// Source files in a supported language with no indexed definitions
+    pub unindexed_files: usize,
+    /// Unix seconds of the last persisted index
+    pub last_saved: Option<i64>,
+    /// Indexed files modified after the last save
+    pub stale_files: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceStats {
+    pub total_files: usize,
+    pub total_lines: usize,
+    pub total_bytes: u64,
+    pub languages: BTreeMap<String, LanguageStats>,
+    pub largest_files: Vec<FileSize>,
+    pub symbol_count: usize,
+    pub symbols_by_kind: BTreeMap<String, usize>,
+    pub most_referenced: Vec<SymbolReferenceCount>,
+    pub index: IndexFreshness,
+}
+
+fn identifier_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b[A-Za-z_][A-Za-z0-9_]*\b").unwrap())
+}
+
+/// Language name for a file extension, None for files that are not source/markup
+pub fn language_for_extension(extension: &str) -> Option<&'static str> {
+    let language = match extension.to_lowercase().as_str() {
+        "rs" => "Rust",
+        "ts" | "tsx" => "TypeScript",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "py" => "Python",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "c" | "h" => "C",
+        "cpp" | "cc" | "cxx" | "hpp" | "hxx" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "scala" => "Scala",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "html" | "htm" => "HTML",
+        "css" | "scss" | "sass" | "less" => "CSS",
+        "json" => "JSON",
+        "yml" | "yaml" => "YAML",
+        "toml" => "TOML",
+        "md" | "mdx" => "Markdown",
+        "sql" => "SQL",
+        "sh" | "bash" | "zsh" => "Shell",
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// Occurrences of each name in `names` across `contents`
+pub fn count_identifiers<'a>(
+    names: &HashSet<&str>,
+    contents: impl Iterator<Item = &'a str>,
+) -> HashMap<String, usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for content in contents {
+        for m in identifier_regex().find_iter(content) {
+            if names.contains(m.as_str()) {
+                *counts.entry(m.as_str().to_string()).or_default() += 1;
+            }
+        }
+    }
+    counts
+}
+
+struct ScannedFile {
+    path: String,
+    language: &'static str,
+    bytes: u64,
+    modified: Option<i64>,
+    content: Option<String>,
+}
+
+fn scan_files(root_path: &str) -> Vec<ScannedFile> {
+    WorkspaceWalker::new(root_path, WalkerConfig::for_list_files())
+        .build()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let metadata = path.metadata().ok().filter(|m| m.is_file())?;
+            let ext = path.extension()?.to_str()?;
+            let language = language_for_extension(ext)?;
+            let content = (metadata.len() <= MAX_TEXT_FILE_BYTES)
+                .then(|| fs::read_to_string(path).ok())
+                .flatten();
+            Some(ScannedFile {
+                path: path.to_string_lossy().to_string(),
+                language,
+                bytes: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64),
+                content,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_workspace_stats(
+    app_handle: AppHandle,
+    nav_state: State<'_, CodeNavState>,
+    workspaces: State<'_, WorkspaceState>,
+    workspace_id: Option<String>,
+    root_path: String,
+) -> Result<WorkspaceStats, String> {
+    let scan_root = root_path.clone();
+    let files = tokio::task::spawn_blocking(move || scan_files(&scan_root))
+        .await
+        .map_err(|e| format!("Workspace scan failed: {}", e))?;
+
+    let definitions = {
+        let nav = resolve_nav(&nav_state, &workspaces, workspace_id.as_deref())?;
+        let service = nav
+            .read()
+            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+        service.definitions_under(&root_path)
+    };
+
+    let mut languages: BTreeMap<String, LanguageStats> = BTreeMap::new();
+    let mut sizes = Vec::new();
+    for file in &files {
+        let lines = file
+            .content
+            .as_ref()
+            .map(|c| c.lines().count())
+            .unwrap_or(0);
+        let stats = languages.entry(file.language.to_string()).or_default();
+        stats.files += 1;
+        stats.lines += lines;
+        stats.bytes += file.bytes;
+        sizes.push(FileSize {
+            path: file.path.clone(),
+            bytes: file.bytes,
+            lines,
+        });
+    }
+    sizes.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    sizes.truncate(LARGEST_FILES);
+
+    let mut symbols_by_kind: BTreeMap<String, usize> = BTreeMap::new();
+    for definition in &definitions {
+        *symbols_by_kind.entry(definition.kind.clone()).or_default() += 1;
+    }
+
+    // Occurrences minus the definitions themselves approximate reference counts
+    let names: HashSet<&str> = definitions
+        .iter()
+        .map(|d| d.name.as_str())
+        .filter(|name| name.len() > 2)
+        .collect();
+    let occurrences = count_identifiers(&names, files.iter().filter_map(|f| f.content.as_deref()));
+    let mut definition_counts: HashMap<&str, usize> = HashMap::new();
+    for definition in &definitions {
+        *definition_counts
+            .entry(definition.name.as_str())
+            .or_default() += 1;
+    }
+    let mut seen = HashSet::new();
+    let mut most_referenced: Vec<SymbolReferenceCount> = definitions
+        .iter()
+        .filter(|d| seen.insert(d.name.as_str()))
+        .filter_map(|d| {
+            let total = *occurrences.get(&d.name)?;
+            let references = total.saturating_sub(definition_counts[d.name.as_str()]);
+            (references > 0).then(|| SymbolReferenceCount {
+                name: d.name.clone(),
+                kind: d.kind.clone(),
+                file_path: d.file_path.clone(),
+                references,
+            })
+        })
+        .collect();
+    most_referenced.sort_by(|a, b| {
+        b.references
+            .cmp(&a.references)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    most_referenced.truncate(TOP_SYMBOLS);
+
+    let indexed: HashSet<&str> = definitions.iter().map(|d| d.file_path.as_str()).collect();
+    let supported = files
+        .iter()
+        .filter(|f| CodeNavigationService::get_lang_id_from_path(&f.path).is_some())
+        .count();
+    let last_saved = code_nav_get_index_metadata(app_handle, root_path.clone())
+        .await
+        .ok()
+        .flatten()
+        .map(|m| m.last_updated);
+    let stale_files = last_saved
+        .map(|saved| {
+            files
+                .iter()
+                .filter(|f| indexed.contains(f.path.as_str()))
+                .filter(|f| f.modified.is_some_and(|m| m > saved))
+                .count()
+        })
+        .unwrap_or(0);
+
+    let stats = WorkspaceStats {
+        total_files: files.len(),
+        total_lines: languages.values().map(|l| l.lines).sum(),
+        total_bytes: files.iter().map(|f| f.bytes).sum(),
+        languages,
+        largest_files: sizes,
+        symbol_count: definitions.len(),
+        symbols_by_kind,
+        most_referenced,
+        index: IndexFreshness {
+            indexed_files: indexed.len(),
+            unindexed_files: supported.saturating_sub(indexed.len()),
+            last_saved,
+            stale_files,
+        },
+    };
+    log::info!(
+        "Workspace stats for {}: {} files, {} symbols",
+        root_path,
+        stats.total_files,
+        stats.symbol_count
+    );
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_language_for_extension() {
+        assert_eq!(language_for_extension("RS"), Some("Rust"));
+        assert_eq!(language_for_extension("tsx"), Some("TypeScript"));
+        assert_eq!(language_for_extension("png"), None);
+    }
+
+    #[test]
+    fn test_count_identifiers() {
+        let names: HashSet<&str> = ["parse", "Config"].into_iter().collect();
+        let contents = [
+            "fn parse(c: Config) {}\nlet parsed = parse(x);",
+            "use crate::Config; // Configuration",
+        ];
+        let counts = count_identifiers(&names, contents.into_iter());
+        assert_eq!(counts["parse"], 2);
+        assert_eq!(counts["Config"], 2);
+    }
+
+    #[test]
+    fn test_scan_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("main.rs"), "fn main() {}\n\nfn helper() {}\n").unwrap();
+        fs::write(root.join("logo.png"), [0u8; 8]).unwrap();
+        fs::write(root.join("README.md"), "# Demo\n").unwrap();
+
+        let mut files = scan_files(&root.to_string_lossy());
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[1].language, "Rust");
+        assert_eq!(
+            files[1].content.as_deref().map(|c| c.lines().count()),
+            Some(3)
+        );
+        assert!(files[1].modified.is_some());
+    }
+}
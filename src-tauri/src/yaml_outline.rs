@@ -0,0 +1,296 @@
+// YAML key hierarchy summaries
+//
+// Kubernetes manifests and CI pipelines run to hundreds of lines, but what the agent
+// usually needs is the shape: which top-level sections exist and what keys they hold.
+// The summary keeps the keys of the first two levels, truncates scalar values, and
+// collapses anything deeper or longer: nested mappings become `{... N keys}`, sequences
+// that are long or hold more than scalars become `[... N items]`, and block scalars
+// (`|`, `>`) keep only their indicator and line count. Documents of a multi-document
+// stream are separated by `---`.
+
+use crate::code_navigation::CodeSummary;
+use crate::text_slice::slice_to;
+use tree_sitter::{Node, Parser};
+
+/// Levels of keys kept before values are collapsed
+const MAX_DEPTH: usize = 2;
+/// Sequences with more items than this are always collapsed
+const SHORT_SEQUENCE: usize = 3;
+/// Scalars longer than this many bytes are cut
+const MAX_SCALAR_LEN: usize = 40;
+/// Items listed for a document whose root is a sequence
+const MAX_ROOT_ITEMS: usize = 10;
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn named_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|n| n.kind() != "comment")
+        .collect()
+}
+
+/// The value a node wraps, past `block_node`/`flow_node` wrappers, anchors and tags
+fn value_of(node: Node) -> Option<Node> {
+    match node.kind() {
+        "document" | "block_node" | "flow_node" | "block_sequence_item" => named_children(node)
+            .into_iter()
+            .find(|n| !matches!(n.kind(), "anchor" | "tag"))
+            .and_then(value_of),
+        _ => Some(node),
+    }
+}
+
+fn is_sequence(kind: &str) -> bool {
+    matches!(kind, "block_sequence" | "flow_sequence")
+}
+
+fn is_mapping(kind: &str) -> bool {
+    matches!(kind, "block_mapping" | "flow_mapping")
+}
+
+fn truncate(value: &str) -> String {
+    let value = collapse(value);
+    if value.len() <= MAX_SCALAR_LEN {
+        return value;
+    }
+    format!("{}…", slice_to(&value, MAX_SCALAR_LEN))
+}
+
+fn sequence_items(node: Node) -> Vec<Node> {
+    named_children(node)
+        .into_iter()
+        .filter(|item| matches!(item.kind(), "block_sequence_item" | "flow_node"))
+        .filter_map(value_of)
+        .collect()
+}
+
+/// Inline form of a value that is not expanded into keys
+fn render_value(value: Node, source: &str) -> String {
+    let kind = value.kind();
+    if is_mapping(kind) {
+        return format!("{{... {} keys}}", named_children(value).len());
+    }
+    if is_sequence(kind) {
+        let items = sequence_items(value);
+        let scalars = items
+            .iter()
+            .all(|item| !is_mapping(item.kind()) && !is_sequence(item.kind()));
+        if items.len() > SHORT_SEQUENCE || !scalars {
+            return format!("[... {} items]", items.len());
+        }
+        let items: Vec<String> = items.iter().map(|i| truncate(text(*i, source))).collect();
+        return format!("[{}]", items.join(", "));
+    }
+    if kind == "block_scalar" {
+        let body = text(value, source);
+        let indicator = body.lines().next().unwrap_or("").trim();
+        return format!(
+            "{} … ({} lines)",
+            indicator,
+            body.lines().count().saturating_sub(1)
+        );
+    }
+    truncate(text(value, source))
+}
+
+fn outline_mapping(mapping: Node, source: &str, depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    for pair in named_children(mapping) {
+        let key = pair
+            .child_by_field_name("key")
+            .map(|k| collapse(text(k, source)))
+            .unwrap_or_default();
+        let value = pair.child_by_field_name("value").and_then(value_of);
+        match value {
+            None => lines.push(format!("{}{}:", indent, key)),
+            Some(value) if value.kind() == "block_mapping" && depth + 1 < MAX_DEPTH => {
+                lines.push(format!("{}{}:", indent, key));
+                outline_mapping(value, source, depth + 1, lines);
+            }
+            Some(value) => lines.push(format!(
+                "{}{}: {}",
+                indent,
+                key,
+                render_value(value, source)
+            )),
+        }
+    }
+}
+
+/// Items of a root sequence (an Ansible playbook, a list of manifests) with their keys
+fn outline_root_sequence(sequence: Node, source: &str, lines: &mut Vec<String>) {
+    let items = sequence_items(sequence);
+    for item in items.iter().take(MAX_ROOT_ITEMS) {
+        if item.kind() == "block_mapping" {
+            let at = lines.len();
+            outline_mapping(*item, source, 1, lines);
+            if let Some(first) = lines.get_mut(at) {
+                first.replace_range(..2, "- ");
+            }
+        } else {
+            lines.push(format!("- {}", render_value(*item, source)));
+        }
+    }
+    if items.len() > MAX_ROOT_ITEMS {
+        lines.push(format!("# ... {} more items", items.len() - MAX_ROOT_ITEMS));
+    }
+}
+
+/// Key outline of a YAML stream
+pub fn yaml_outline(content: &str) -> Result<String, String> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_yaml::LANGUAGE.into())
+        .map_err(|e| format!("Failed to set language for yaml: {:?}", e))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or("Failed to parse yaml content")?;
+
+    let mut documents = Vec::new();
+    for document in named_children(tree.root_node()) {
+        if document.kind() != "document" {
+            continue;
+        }
+        let mut lines = Vec::new();
+        match value_of(document) {
+            Some(root) if root.kind() == "block_mapping" => {
+                outline_mapping(root, content, 0, &mut lines)
+            }
+            Some(root) if is_sequence(root.kind()) => {
+                outline_root_sequence(root, content, &mut lines)
+            }
+            Some(root) => lines.push(render_value(root, content)),
+            None => {}
+        }
+        if !lines.is_empty() {
+            documents.push(lines.join("\n"));
+        }
+    }
+    Ok(documents.join("\n---\n"))
+}
+
+pub fn summarize_yaml(content: &str) -> Result<CodeSummary, String> {
+    let original_lines = content.lines().count();
+    let outline = yaml_outline(content)?;
+    if outline.is_empty() {
+        return Ok(CodeSummary::unchanged(
+            content.to_string(),
+            original_lines,
+            "yaml".to_string(),
+            "no keys",
+        ));
+    }
+    Ok(CodeSummary {
+        success: true,
+        summary: format!(
+            "[COMPRESSED: Original {} lines → keys of the first {} levels, values truncated]\n\n{}",
+            original_lines, MAX_DEPTH, outline
+        ),
+        original_lines,
+        lang_id: "yaml".to_string(),
+        truncated: None,
+        skipped_reason: None,
+        omitted_symbols: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kubernetes_manifests() {
+        let yaml = r#"# Web deployment
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  labels: &labels
+    app: web
+    tier: frontend
+spec:
+  replicas: 3
+  template:
+    spec:
+      containers:
+        - name: web
+          image: registry.example.com/team/web-frontend:2024.06.01-build.1234
+  ports: [80, 443]
+  hosts: [a, b, c, d, e]
+  script: |
+    echo one
+    echo two
+  empty:
+---
+apiVersion: v1
+kind: Service
+"#;
+        assert_eq!(
+            yaml_outline(yaml).unwrap(),
+            [
+                "apiVersion: apps/v1",
+                "kind: Deployment",
+                "metadata:",
+                "  name: web",
+                "  labels: {... 2 keys}",
+                "spec:",
+                "  replicas: 3",
+                "  template: {... 1 keys}",
+                "  ports: [80, 443]",
+                "  hosts: [... 5 items]",
+                "  script: | … (2 lines)",
+                "  empty:",
+                "---",
+                "apiVersion: v1",
+                "kind: Service",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_summarize_pipeline_and_playbook() {
+        let pipeline = r#"name: CI
+on:
+  push:
+    branches: [main]
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - run: cargo test --workspace --all-features -- --include-ignored --test-threads=1
+"#;
+        let result = summarize_yaml(pipeline).unwrap();
+        assert!(result.success);
+        assert!(result
+            .summary
+            .starts_with("[COMPRESSED: Original 10 lines → keys of the first 2 levels"));
+        assert!(result
+            .summary
+            .ends_with("name: CI\non:\n  push: {... 1 keys}\njobs:\n  build: {... 2 keys}"));
+
+        let playbook = r#"- hosts: web
+  become: true
+  tasks:
+    - name: install nginx
+      apt: name=nginx
+- hosts: db
+  tasks: []
+"#;
+        assert_eq!(
+            yaml_outline(playbook).unwrap(),
+            "- hosts: web\n  become: true\n  tasks: [... 1 items]\n- hosts: db\n  tasks: []"
+        );
+
+        let empty = summarize_yaml("# only a comment\n").unwrap();
+        assert!(!empty.success);
+    }
+}
@@ -0,0 +1,22 @@
+package server
+
+import "net/http"
+
+// DefaultPort is used when no port is configured.
+const DefaultPort = 8080
+
+// Server wraps an HTTP mux.
+type Server struct {
+	mux  *http.ServeMux
+	port int
+}
+
+// New creates a server listening on port.
+func New(port int) *Server {
+	return &Server{mux: http.NewServeMux(), port: port}
+}
+
+// Handle registers a handler for pattern.
+func (s *Server) Handle(pattern string, h http.Handler) {
+	s.mux.Handle(pattern, h)
+}
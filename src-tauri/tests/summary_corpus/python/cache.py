@@ -0,0 +1,27 @@
+"""Simple TTL cache."""
+
+import time
+
+DEFAULT_TTL = 60
+
+
+class TTLCache:
+    """Cache entries expire after a fixed number of seconds."""
+
+    def __init__(self, ttl=DEFAULT_TTL):
+        self.ttl = ttl
+        self.entries = {}
+
+    def get(self, key):
+        value, expires = self.entries.get(key, (None, 0))
+        if expires < time.time():
+            return None
+        return value
+
+    def set(self, key, value):
+        self.entries[key] = (value, time.time() + self.ttl)
+
+
+# Shared module-level cache
+def default_cache():
+    return TTLCache()
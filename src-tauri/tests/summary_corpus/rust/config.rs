@@ -0,0 +1,41 @@
+//! Configuration loading
+
+use std::collections::HashMap;
+
+/// Maximum number of retries
+pub const MAX_RETRIES: u32 = 3;
+
+/// Application settings
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub name: String,
+    pub values: HashMap<String, String>,
+}
+
+pub enum Source {
+    File(String),
+    Env,
+}
+
+impl Settings {
+    /// Create empty settings
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.values.get(key)
+    }
+}
+
+/// Parse `key=value` lines
+pub fn parse(input: &str) -> HashMap<String, String> {
+    input
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
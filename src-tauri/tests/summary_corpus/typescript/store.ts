@@ -0,0 +1,46 @@
+import { EventEmitter } from 'events';
+
+/** Default page size for queries */
+export const PAGE_SIZE = 50;
+
+export type SortOrder = 'asc' | 'desc';
+
+export enum Status {
+  Active = 'active',
+  Archived = 'archived',
+}
+
+export interface Item {
+  id: string;
+  status: Status;
+  updatedAt: number;
+}
+
+/**
+ * In-memory item store with change notifications
+ */
+export class ItemStore extends EventEmitter {
+  private items = new Map<string, Item>();
+
+  constructor(private readonly name: string) {
+    super();
+  }
+
+  get size(): number {
+    return this.items.size;
+  }
+
+  upsert(item: Item): void {
+    this.items.set(item.id, item);
+    this.emit('change', item);
+  }
+
+  async load(ids: string[]): Promise<Item[]> {
+    return ids.flatMap((id) => this.items.get(id) ?? []);
+  }
+}
+
+export function sortItems(items: Item[], order: SortOrder = 'asc'): Item[] {
+  const sign = order === 'asc' ? 1 : -1;
+  return [...items].sort((a, b) => sign * (a.updatedAt - b.updatedAt));
+}
@@ -164,6 +164,14 @@ export interface CodeSummary {
   lang_id: string;
 }
 
+/**
+ * Per-call summarizer options (subset of the backend's SummaryOptions)
+ */
+export interface SummaryOptions {
+  /** Return files whose function bodies are less than this share of code lines as-is */
+  min_body_ratio?: number;
+}
+
 /**
  * Summarize code content using tree-sitter to extract only signatures and key definitions.
  * This is used for message compaction to reduce token usage while preserving semantic information.
@@ -177,14 +185,16 @@ export interface CodeSummary {
  * @param content - The code content to summarize
  * @param langId - Language identifier (e.g., 'typescript', 'python', 'rust')
  * @param filePath - File path for error messages
+ * @param options - Optional summarizer options
  * @returns CodeSummary with success=true if summarized, success=false if unsupported language
  */
 export async function summarizeCodeContent(
   content: string,
   langId: string,
-  filePath: string
+  filePath: string,
+  options?: SummaryOptions
 ): Promise<CodeSummary> {
-  return invoke('summarize_code_content', { content, langId, filePath });
+  return invoke('summarize_code_content', { content, langId, filePath, options });
 }
 
 // ============================================================================
@@ -178,7 +178,8 @@ describe('MessageRewriter', () => {
       expect(mockSummarizeCodeContent).toHaveBeenCalledWith(
         largeContent,
         'typescript',
-        '/src/large.ts'
+        '/src/large.ts',
+        { min_body_ratio: 0.1 }
       );
 
       // Verify the content was replaced
@@ -210,7 +211,8 @@ describe('MessageRewriter', () => {
       expect(mockSummarizeCodeContent).toHaveBeenCalledWith(
         largeContent,
         'python',
-        '/src/large.py'
+        '/src/large.py',
+        { min_body_ratio: 0.1 }
       );
 
       const toolResult = result[0] as ModelMessage & { role: 'tool' };
@@ -438,7 +440,8 @@ describe('MessageRewriter', () => {
       expect(mockSummarizeCodeContent).toHaveBeenCalledWith(
         largeContent,
         'typescript',
-        '/src/large.ts'
+        '/src/large.ts',
+        { min_body_ratio: 0.1 }
       );
 
       const assistantMsg = result[0] as ModelMessage & { role: 'assistant' };
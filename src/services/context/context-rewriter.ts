@@ -19,6 +19,9 @@ type AssistantContentPart = TextPart | ToolCallPart;
  */
 export class ContextRewriter {
   private readonly LINE_THRESHOLD = 100; // Only summarize files exceeding this line count
+  // Files that are mostly declarations (headers, interface-only modules) lose type
+  // details for little gain, so keep them as-is
+  private readonly MIN_BODY_RATIO = 0.1;
 
   /**
    * Rewrite messages to compress large code content using tree-sitter.
@@ -242,7 +245,9 @@ export class ContextRewriter {
     filePath: string
   ): Promise<CodeSummary> {
     try {
-      return await summarizeCodeContent(content, langId, filePath);
+      return await summarizeCodeContent(content, langId, filePath, {
+        min_body_ratio: this.MIN_BODY_RATIO,
+      });
     } catch (error) {
       logger.error('MessageRewriter: Failed to summarize content:', error);
       return {
@@ -1,6 +1,7 @@
 // Unified Turso Database Initialization
 // Replaces database-init.ts and agent-database-init.ts
 
+import { invoke } from '@tauri-apps/api/core';
 import { logger } from '@/lib/logger';
 import type { TursoClient } from './turso-client';
 import { TursoSchema } from './turso-schema';
@@ -54,6 +55,9 @@ export class TursoDatabaseInit {
       // Migration 6: Add context_usage column to conversations
       await TursoDatabaseInit.migrateConversationsContextUsage(db);
 
+      // Migration 7: Create the full-text index over messages
+      await TursoDatabaseInit.migrateMessagesFts();
+
       logger.info('✅ Database migrations check completed');
     } catch (error) {
       logger.error('❌ Database migration error:', error);
@@ -259,4 +263,16 @@ export class TursoDatabaseInit {
       logger.error('Error migrating conversations table context_usage:', error);
     }
   }
+
+  /**
+   * Create the messages FTS index and its triggers, backfilling existing messages.
+   * The schema lives in the backend (history_search.rs) so rebuilds use the same SQL.
+   */
+  private static async migrateMessagesFts(): Promise<void> {
+    try {
+      await invoke('migrate_history_index');
+    } catch (error) {
+      logger.error('Error creating messages full-text index:', error);
+    }
+  }
 }